@@ -0,0 +1,19 @@
+#![no_main]
+
+use codescope::graph::DependencyGraph;
+use codescope::parser::{PackageLock, ParseError};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    match PackageLock::parse(input) {
+        Ok(lock) => {
+            let mut graph = DependencyGraph::new();
+            graph.extend_from_lockfile(&lock);
+            graph.propagate_dependency_types();
+            graph.detect_cycles();
+            lock.required_paths();
+        }
+        Err(ParseError::Syntax(err)) => assert!(err.offset <= input.len()),
+        Err(_) => {}
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use codescope::parser::{extract_dependencies, parse_str, DependencySource, ParseError, VersionRange};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    match parse_str(input) {
+        Ok(package) => {
+            for dep in extract_dependencies(&package) {
+                DependencySource::parse(&dep.version);
+                VersionRange::parse(&dep.version);
+            }
+        }
+        Err(ParseError::Syntax(err)) => assert!(err.offset <= input.len()),
+        Err(_) => {}
+    }
+});
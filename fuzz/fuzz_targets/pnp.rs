@@ -0,0 +1,17 @@
+#![no_main]
+
+use std::path::Path;
+
+use codescope::parser::PnpManifest;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let root = Path::new("/project");
+    let parsed = [PnpManifest::parse_data(input, root), PnpManifest::parse_cjs(input, root)];
+    for manifest in parsed.into_iter().flatten() {
+        for package in &manifest.packages {
+            let _ = package.version();
+            let _ = manifest.location(package);
+        }
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use codescope::bundle::build::{from_esbuild_metafile, from_visualizer_data};
+use codescope::bundle::WebpackStats;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let parsed = [
+        WebpackStats::parse(input),
+        from_esbuild_metafile(input),
+        from_visualizer_data(input),
+    ];
+    for stats in parsed.into_iter().flatten() {
+        let analysis = stats.analyze();
+        analysis.format_summary(10);
+        analysis.format_asset_breakdown();
+    }
+});
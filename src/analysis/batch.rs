@@ -0,0 +1,265 @@
+//! Analysis of every project under a directory.
+//!
+//! Platform teams auditing dozens of repositories checked out side by side
+//! want one report rather than one run per repository. [`discover_projects`]
+//! finds each `package.json` under a directory, and a [`BatchReport`]
+//! combines a [`ProjectSummary`] per project with totals across them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::exports::{project_walk, AnalysisResult};
+use crate::graph::DependencyGraph;
+use crate::parser::{Dependency, DependencyType};
+
+/// Lists the directories under `root` (including `root` itself) that
+/// contain a `package.json`, sorted.
+///
+/// Directories are skipped the way [`source_files`](super::source_files)
+/// skips them: `node_modules`, build output, gitignored paths and the
+/// `ignore` globs are not searched.
+///
+/// # Errors
+///
+/// Returns [`InvalidIgnorePattern`](super::exports::AnalysisError::InvalidIgnorePattern)
+/// if a glob cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use codescope::analysis::batch::discover_projects;
+///
+/// let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+/// let projects = discover_projects(&root, &[]).unwrap();
+/// assert!(projects.contains(&root.join("cra-app")));
+/// assert!(projects.contains(&root.join("pnpm-monorepo/packages/ui")));
+/// ```
+pub fn discover_projects(root: &Path, ignore: &[String]) -> AnalysisResult<Vec<PathBuf>> {
+    let mut projects: Vec<PathBuf> = project_walk(root, ignore)?
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "package.json" && e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+        .collect();
+    projects.sort();
+    Ok(projects)
+}
+
+/// Figures for one project of a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectSummary {
+    /// Directory of the project, relative to the batch root (`.` for the
+    /// root itself)
+    pub path: String,
+    /// Name from package.json
+    pub name: String,
+    /// Version from package.json
+    pub version: String,
+    /// Number of direct dependencies
+    pub direct: usize,
+    /// Number of those that are development dependencies
+    pub dev: usize,
+    /// Names of the packages in the resolved graph, sorted
+    pub packages: Vec<String>,
+    /// Number of dependency cycles
+    pub cycles: usize,
+    /// Number of packages required at conflicting versions
+    pub conflicts: usize,
+}
+
+impl ProjectSummary {
+    /// Summarizes a project from its direct dependencies and its graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory of the project, relative to the batch root
+    /// * `name` - Name from package.json
+    /// * `version` - Version from package.json
+    /// * `deps` - The direct dependencies
+    /// * `graph` - The resolved dependency graph
+    pub fn new(
+        path: impl Into<String>,
+        name: impl Into<String>,
+        version: impl Into<String>,
+        deps: &[Dependency],
+        graph: &DependencyGraph,
+    ) -> Self {
+        let packages: BTreeSet<String> = graph
+            .get_all_nodes()
+            .into_iter()
+            .map(|node| node.package_name().to_string())
+            .collect();
+        Self {
+            path: path.into(),
+            name: name.into(),
+            version: version.into(),
+            direct: deps.len(),
+            dev: deps.iter().filter(|d| d.dep_type == DependencyType::Development).count(),
+            packages: packages.into_iter().collect(),
+            cycles: graph.detect_cycles().len(),
+            conflicts: graph.get_packages_with_conflicts().len(),
+        }
+    }
+
+    /// Returns the number of packages in the resolved graph.
+    pub fn package_count(&self) -> usize {
+        self.packages.len()
+    }
+}
+
+/// The projects of a batch and their totals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BatchReport {
+    /// One summary per project, in the order they were found
+    pub projects: Vec<ProjectSummary>,
+}
+
+impl BatchReport {
+    /// Creates a report of the given projects.
+    pub fn new(projects: Vec<ProjectSummary>) -> Self {
+        Self { projects }
+    }
+
+    /// Returns the number of distinct packages across all projects.
+    pub fn unique_packages(&self) -> usize {
+        self.projects
+            .iter()
+            .flat_map(|p| p.packages.iter())
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+
+    /// Returns the packages used by more than one project with the number
+    /// of projects using them, most shared first, then by name.
+    pub fn shared_packages(&self) -> Vec<(&str, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for package in self.projects.iter().flat_map(|p| p.packages.iter()) {
+            *counts.entry(package).or_default() += 1;
+        }
+        let mut shared: Vec<(&str, usize)> = counts.into_iter().filter(|(_, count)| *count > 1).collect();
+        shared.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        shared
+    }
+
+    /// Formats a section per project followed by the totals.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of shared packages to list
+    pub fn format_report(&self, limit: usize) -> String {
+        if self.projects.is_empty() {
+            return "📚 No projects found.\n".to_string();
+        }
+
+        let mut out = format!("📚 {} project(s)\n", self.projects.len());
+        for project in &self.projects {
+            out.push_str(&format!("\n{} {} ({})\n", project.name, project.version, project.path));
+            out.push_str(&format_counts(
+                project.direct,
+                project.dev,
+                &project.package_count().to_string(),
+                &project.cycles.to_string(),
+                &project.conflicts.to_string(),
+            ));
+        }
+
+        let with = |count: fn(&ProjectSummary) -> usize| {
+            let total: usize = self.projects.iter().map(count).sum();
+            let projects = self.projects.iter().filter(|p| count(p) > 0).count();
+            format!("{} in {} project(s)", total, projects)
+        };
+        out.push_str("\nTotals\n");
+        out.push_str(&format_counts(
+            self.projects.iter().map(|p| p.direct).sum(),
+            self.projects.iter().map(|p| p.dev).sum(),
+            &format!(
+                "{} ({} unique)",
+                self.projects.iter().map(ProjectSummary::package_count).sum::<usize>(),
+                self.unique_packages()
+            ),
+            &with(|p| p.cycles),
+            &with(|p| p.conflicts),
+        ));
+
+        let shared = self.shared_packages();
+        if !shared.is_empty() {
+            out.push_str(&format!("\nMost shared packages ({} in total):\n", shared.len()));
+            let width = shared.iter().take(limit).map(|(name, _)| name.len()).max().unwrap_or(0);
+            for (name, count) in shared.iter().take(limit) {
+                out.push_str(&format!("  {:width$}  {} projects\n", name, count, width = width));
+            }
+        }
+        out
+    }
+}
+
+/// Formats the figures shared by the project sections and the totals.
+fn format_counts(direct: usize, dev: usize, packages: &str, cycles: &str, conflicts: &str) -> String {
+    format!(
+        "  Direct dependencies  {} ({} dev)\n  Resolved packages    {}\n  Cycles               {}\n  Version conflicts    {}\n",
+        direct, dev, packages, cycles, conflicts
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::source::DependencySource;
+    use std::fs;
+
+    /// A project whose first two packages and `dev` are direct dependencies.
+    fn project(path: &str, packages: &[&str], dev: &[&str]) -> ProjectSummary {
+        let mut graph = DependencyGraph::new();
+        for name in packages {
+            graph.add_dependency(name, "1.0.0", crate::graph::DependencyType::Production);
+        }
+        let direct = packages.iter().take(2).map(|name| (name, DependencyType::Production));
+        let deps: Vec<Dependency> = direct
+            .chain(dev.iter().map(|name| (name, DependencyType::Development)))
+            .map(|(name, dep_type)| Dependency {
+                name: name.to_string(),
+                version: "^1.0.0".to_string(),
+                dep_type,
+                source: DependencySource::Registry,
+            })
+            .collect();
+        ProjectSummary::new(path, path.rsplit('/').next().unwrap(), "1.0.0", &deps, &graph)
+    }
+
+    #[test]
+    fn test_batch_totals() {
+        let report = BatchReport::new(vec![
+            project("web", &["react", "react-dom", "lodash"], &["jest"]),
+            project("services/api", &["express", "lodash"], &[]),
+            project("admin", &["react", "lodash"], &[]),
+        ]);
+        assert_eq!(report.unique_packages(), 4);
+        assert_eq!(report.shared_packages(), vec![("lodash", 3), ("react", 2)]);
+
+        let text = report.format_report(10);
+        assert!(text.starts_with("📚 3 project(s)\n\nweb 1.0.0 (web)\n  Direct dependencies  3 (1 dev)\n"));
+        assert!(text.contains("\napi 1.0.0 (services/api)\n"));
+        assert!(text.contains("\nTotals\n  Direct dependencies  7 (1 dev)\n  Resolved packages    7 (4 unique)\n"));
+        assert!(text.contains("  Cycles               0 in 0 project(s)\n"));
+        assert!(text.ends_with("Most shared packages (2 in total):\n  lodash  3 projects\n  react   2 projects\n"));
+    }
+
+    #[test]
+    fn test_discover_skips_dependencies_and_ignored_dirs() {
+        let root = std::env::temp_dir().join(format!("codescope-batch-test-{}", std::process::id()));
+        for dir in ["", "apps/web", "apps/web/node_modules/react", "legacy/old", "tools/gen"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("package.json"), "{}").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "legacy/\n").unwrap();
+
+        let projects = discover_projects(&root, &["tools/**".to_string()]).unwrap();
+        let relative: Vec<&Path> = projects.iter().map(|p| p.strip_prefix(&root).unwrap()).collect();
+        assert_eq!(relative, vec![Path::new(""), Path::new("apps/web")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
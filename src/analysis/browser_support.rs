@@ -0,0 +1,439 @@
+//! Dependencies that need newer browsers than the project supports.
+//!
+//! Bundlers usually skip `node_modules` when transpiling, so a dependency
+//! that publishes modern syntax either breaks older browsers or forces the
+//! build to transpile and polyfill it. This module compares the browsers a
+//! dependency needs, from its own `browserslist` field and the syntax of
+//! its entry file, with the project's browserslist targets.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use tree_sitter::{Node, Parser};
+
+use crate::graph::{DependencyGraph, DependencyType};
+use crate::parser::browserslist::{BrowserTargets, BrowserVersion};
+use crate::parser::installed::{PackageLocation, PackageStore};
+
+/// Entry files larger than this are not parsed.
+const MAX_ENTRY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Browsers with compatibility data, in the column order of a
+/// [`SupportRow`].
+pub(crate) const BROWSERS: [&str; 9] = ["chrome", "edge", "firefox", "safari", "ios_saf", "opera", "samsung", "ie", "node"];
+
+/// Syntax that older browsers cannot parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntaxFeature {
+    /// `() => {}`
+    ArrowFunctions,
+    /// `` `${x}` ``
+    TemplateLiterals,
+    /// `class A {}`
+    Classes,
+    /// `async function` and `await`
+    AsyncFunctions,
+    /// `{ ...rest }`
+    ObjectSpread,
+    /// `10n`
+    BigInt,
+    /// `a?.b`
+    OptionalChaining,
+    /// `a ?? b`
+    NullishCoalescing,
+    /// `this.#field`
+    PrivateFields,
+    /// `a ??= b`, `a ||= b`, `a &&= b`
+    LogicalAssignment,
+}
+
+/// First supporting version per browser, `None` if never supported.
+pub(crate) type SupportRow = [Option<BrowserVersion>; 9];
+
+pub(crate) const fn v(major: u32, minor: u32) -> Option<BrowserVersion> {
+    Some(BrowserVersion::new(major, minor))
+}
+
+/// Returns the first version of a browser with support, `None` for
+/// browsers without compatibility data and `Some(None)` for browsers that
+/// never added support.
+pub(crate) fn first_supported_in(row: &SupportRow, browser: &str) -> Option<Option<BrowserVersion>> {
+    let column = BROWSERS.iter().position(|b| *b == browser)?;
+    Some(row[column])
+}
+
+/// Returns true if every targeted browser supports a feature natively from
+/// its oldest targeted version. Browsers without compatibility data count
+/// as unsupported.
+pub(crate) fn supported_by_all(row: &SupportRow, targets: &BrowserTargets) -> bool {
+    !targets.is_empty()
+        && targets.minimums.iter().all(|(browser, minimum)| {
+            matches!(first_supported_in(row, browser), Some(Some(first)) if first <= *minimum)
+        })
+}
+
+impl SyntaxFeature {
+    /// Returns a short description of the feature.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SyntaxFeature::ArrowFunctions => "arrow functions",
+            SyntaxFeature::TemplateLiterals => "template literals",
+            SyntaxFeature::Classes => "classes",
+            SyntaxFeature::AsyncFunctions => "async functions",
+            SyntaxFeature::ObjectSpread => "object spread",
+            SyntaxFeature::BigInt => "BigInt literals",
+            SyntaxFeature::OptionalChaining => "optional chaining",
+            SyntaxFeature::NullishCoalescing => "nullish coalescing",
+            SyntaxFeature::PrivateFields => "private class fields",
+            SyntaxFeature::LogicalAssignment => "logical assignment",
+        }
+    }
+
+    /// The first version of each browser in [`BROWSERS`] that parses the
+    /// feature, from the MDN compatibility data.
+    fn support(&self) -> SupportRow {
+        match self {
+            SyntaxFeature::ArrowFunctions => [v(45, 0), v(12, 0), v(22, 0), v(10, 0), v(10, 0), v(32, 0), v(5, 0), None, v(4, 0)],
+            SyntaxFeature::TemplateLiterals => [v(41, 0), v(13, 0), v(34, 0), v(9, 0), v(9, 0), v(28, 0), v(4, 0), None, v(4, 0)],
+            SyntaxFeature::Classes => [v(49, 0), v(13, 0), v(45, 0), v(9, 0), v(9, 0), v(36, 0), v(5, 0), None, v(6, 0)],
+            SyntaxFeature::AsyncFunctions => [v(55, 0), v(15, 0), v(52, 0), v(11, 0), v(11, 0), v(42, 0), v(6, 2), None, v(7, 6)],
+            SyntaxFeature::ObjectSpread => [v(60, 0), v(79, 0), v(55, 0), v(11, 1), v(11, 3), v(47, 0), v(8, 2), None, v(8, 3)],
+            SyntaxFeature::BigInt => [v(67, 0), v(79, 0), v(68, 0), v(14, 0), v(14, 0), v(54, 0), v(9, 0), None, v(10, 4)],
+            SyntaxFeature::OptionalChaining => [v(80, 0), v(80, 0), v(74, 0), v(13, 1), v(13, 4), v(67, 0), v(13, 0), None, v(14, 0)],
+            SyntaxFeature::NullishCoalescing => [v(80, 0), v(80, 0), v(72, 0), v(13, 1), v(13, 4), v(67, 0), v(13, 0), None, v(14, 0)],
+            SyntaxFeature::PrivateFields => [v(74, 0), v(79, 0), v(90, 0), v(14, 1), v(14, 5), v(62, 0), v(11, 0), None, v(12, 0)],
+            SyntaxFeature::LogicalAssignment => [v(85, 0), v(85, 0), v(79, 0), v(14, 0), v(14, 0), v(71, 0), v(14, 0), None, v(15, 0)],
+        }
+    }
+
+    /// Returns the first version of a browser that parses the feature.
+    ///
+    /// # Returns
+    ///
+    /// `None` for browsers without compatibility data, `Some(None)` for
+    /// browsers that never added the feature.
+    pub fn first_supported(&self, browser: &str) -> Option<Option<BrowserVersion>> {
+        first_supported_in(&self.support(), browser)
+    }
+}
+
+/// Finds the modern syntax used in a JavaScript file.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::browser_support::{detect_syntax, SyntaxFeature};
+///
+/// let features = detect_syntax("export const get = (o) => o?.value ?? null;");
+/// assert!(features.contains(&SyntaxFeature::OptionalChaining));
+/// assert!(features.contains(&SyntaxFeature::NullishCoalescing));
+/// ```
+pub fn detect_syntax(source: &str) -> BTreeSet<SyntaxFeature> {
+    let mut features = BTreeSet::new();
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_javascript::LANGUAGE.into()).is_err() {
+        return features;
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return features;
+    };
+
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if let Some(feature) = feature_of(&node, source) {
+            features.insert(feature);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    features
+}
+
+fn feature_of(node: &Node, source: &str) -> Option<SyntaxFeature> {
+    let operator = || {
+        node.child_by_field_name("operator")
+            .and_then(|op| op.utf8_text(source.as_bytes()).ok())
+    };
+    match node.kind() {
+        "arrow_function" => Some(SyntaxFeature::ArrowFunctions),
+        "template_string" => Some(SyntaxFeature::TemplateLiterals),
+        "class" | "class_declaration" => Some(SyntaxFeature::Classes),
+        "async" | "await_expression" => Some(SyntaxFeature::AsyncFunctions),
+        "spread_element" if node.parent().is_some_and(|p| p.kind() == "object") => Some(SyntaxFeature::ObjectSpread),
+        "number" if node.utf8_text(source.as_bytes()).is_ok_and(|text| text.ends_with('n')) => Some(SyntaxFeature::BigInt),
+        "optional_chain" => Some(SyntaxFeature::OptionalChaining),
+        "binary_expression" if operator() == Some("??") => Some(SyntaxFeature::NullishCoalescing),
+        "private_property_identifier" => Some(SyntaxFeature::PrivateFields),
+        "augmented_assignment_expression" if matches!(operator(), Some("??=" | "||=" | "&&=")) => {
+            Some(SyntaxFeature::LogicalAssignment)
+        }
+        _ => None,
+    }
+}
+
+/// A browser the project supports that a dependency does not.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TargetConflict {
+    /// Canonical browser name
+    pub browser: String,
+    /// Oldest version the project supports
+    pub declared: BrowserVersion,
+    /// Oldest version the dependency works in, `None` if none does
+    pub required: Option<BrowserVersion>,
+    /// What raises the requirement: a syntax feature or `browserslist`
+    pub cause: String,
+}
+
+impl TargetConflict {
+    /// Describes the requirement, e.g. `safari 13.1 (optional chaining)`.
+    pub fn requirement(&self) -> String {
+        match self.required {
+            Some(version) => format!("{} {} ({})", self.browser, version, self.cause),
+            None => format!("no {} ({})", self.browser, self.cause),
+        }
+    }
+}
+
+/// A dependency that needs newer browsers than the project targets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BrowserIssue {
+    /// Package name
+    pub name: String,
+    /// Version from the graph
+    pub version: String,
+    /// The entry file that was scanned, if any
+    pub entry: Option<String>,
+    /// One conflict per browser, with the highest requirement
+    pub conflicts: Vec<TargetConflict>,
+}
+
+/// The dependencies that exceed the project's browser targets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrowserSupportReport {
+    /// The project's resolved targets
+    pub targets: BrowserTargets,
+    /// Offending packages, sorted by name
+    pub packages: Vec<BrowserIssue>,
+    /// Number of installed packages inspected
+    pub inspected: usize,
+}
+
+impl BrowserSupportReport {
+    /// Checks the runtime dependencies of the graph against the targets.
+    ///
+    /// Development dependencies are skipped since they do not ship to
+    /// browsers.
+    pub fn from_graph(graph: &DependencyGraph, targets: &BrowserTargets, store: &PackageStore) -> Self {
+        let mut report = Self {
+            targets: targets.clone(),
+            ..Self::default()
+        };
+        for node in graph.get_all_nodes() {
+            if node.dep_type == DependencyType::Development {
+                continue;
+            }
+            let name = node.package_name();
+            let Some(location) = store
+                .locate(name, Some(&node.version))
+                .or_else(|| store.locate(name, None))
+            else {
+                continue;
+            };
+            report.inspected += 1;
+
+            let (entry, conflicts) = check_package(&location, targets);
+            if !conflicts.is_empty() {
+                report.packages.push(BrowserIssue {
+                    name: node.name.clone(),
+                    version: node.version.clone(),
+                    entry,
+                    conflicts,
+                });
+            }
+        }
+        report.packages.sort_by(|a, b| a.name.cmp(&b.name));
+        report
+    }
+
+    /// Formats the offending packages as a listing.
+    pub fn format_report(&self) -> String {
+        let mut out = String::new();
+        if self.targets.is_empty() {
+            out.push_str("⚠️  No browser versions could be resolved from the browserslist config.\n");
+        } else {
+            out.push_str(&format!("🌐 Targets: {}\n", self.targets.describe()));
+        }
+        if !self.targets.unresolved.is_empty() {
+            out.push_str(&format!(
+                "⚠️  Ignored queries that need usage data: {}\n",
+                self.targets.unresolved.join(", ")
+            ));
+        }
+        out.push('\n');
+
+        if self.packages.is_empty() {
+            out.push_str(&format!(
+                "✅ All {} inspected package(s) fit the declared browser support.\n",
+                self.inspected
+            ));
+            return out;
+        }
+
+        out.push_str(&format!(
+            "🐢 {} package(s) need newer browsers than declared:\n",
+            self.packages.len()
+        ));
+        for package in &self.packages {
+            let requirements: Vec<String> = package.conflicts.iter().map(TargetConflict::requirement).collect();
+            out.push_str(&format!("  {}@{}: {}\n", package.name, package.version, requirements.join(", ")));
+        }
+        out
+    }
+}
+
+/// Compares one installed package with the targets.
+fn check_package(location: &PackageLocation, targets: &BrowserTargets) -> (Option<String>, Vec<TargetConflict>) {
+    let manifest: serde_json::Value = location
+        .read_to_string("package.json")
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    // Highest requirement per browser; `None` outranks every version
+    let mut worst: BTreeMap<String, (Option<BrowserVersion>, String)> = BTreeMap::new();
+    let mut raise = |browser: &str, required: Option<BrowserVersion>, cause: &str| {
+        let Some(declared) = targets.minimum(browser) else {
+            return;
+        };
+        if required.is_some_and(|required| required <= declared) {
+            return;
+        }
+        let higher = match worst.get(browser) {
+            None => true,
+            Some((None, _)) => false,
+            Some((Some(current), _)) => required.is_none_or(|required| required > *current),
+        };
+        if higher {
+            worst.insert(browser.to_string(), (required, cause.to_string()));
+        }
+    };
+
+    if let Some(published) = BrowserTargets::from_manifest(&manifest) {
+        for (browser, version) in &published.minimums {
+            raise(browser, Some(*version), "browserslist");
+        }
+    }
+
+    let entry = entry_file(&manifest).and_then(|entry| {
+        let source = read_entry(location, &entry)?;
+        Some((entry, source))
+    });
+    if let Some((_, source)) = &entry {
+        for feature in detect_syntax(source) {
+            for browser in targets.minimums.keys() {
+                if let Some(required) = feature.first_supported(browser) {
+                    raise(browser, required, feature.label());
+                }
+            }
+        }
+    }
+
+    let conflicts = worst
+        .into_iter()
+        .map(|(browser, (required, cause))| TargetConflict {
+            declared: targets.minimum(&browser).unwrap_or_default(),
+            browser,
+            required,
+            cause,
+        })
+        .collect();
+    (entry.map(|(entry, _)| entry), conflicts)
+}
+
+/// Returns the file a browser bundle would load: `module`, then a string
+/// `browser` field, then `main`.
+fn entry_file(manifest: &serde_json::Value) -> Option<String> {
+    ["module", "browser", "main"]
+        .iter()
+        .find_map(|field| manifest.get(*field).and_then(|v| v.as_str()))
+        .or(Some("index.js"))
+        .map(|entry| entry.trim_start_matches("./").to_string())
+}
+
+fn read_entry(location: &PackageLocation, entry: &str) -> Option<String> {
+    let candidates = [entry.to_string(), format!("{}.js", entry), format!("{}/index.js", entry)];
+    candidates
+        .iter()
+        .find_map(|candidate| location.read_to_string(candidate).ok())
+        .filter(|source| source.len() <= MAX_ENTRY_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::install;
+    use std::fs;
+
+    #[test]
+    fn test_detect_syntax() {
+        let source = r#"
+            var legacy = function () { return "a?.b ?? c"; }; // x?.y ?? z
+            class Cache { #items = new Map(); async load(key) { this.#items ??= await fetch(`/${key}`); } }
+            const copy = { ...legacy };
+            const big = 10n;
+        "#;
+        let features: Vec<SyntaxFeature> = detect_syntax(source).into_iter().collect();
+        assert_eq!(
+            features,
+            vec![
+                SyntaxFeature::TemplateLiterals,
+                SyntaxFeature::Classes,
+                SyntaxFeature::AsyncFunctions,
+                SyntaxFeature::ObjectSpread,
+                SyntaxFeature::BigInt,
+                SyntaxFeature::PrivateFields,
+                SyntaxFeature::LogicalAssignment,
+            ],
+            "operators inside strings and comments do not count"
+        );
+        assert!(detect_syntax("var a = [1, 2].map(function (x) { return x; });").is_empty());
+    }
+
+    #[test]
+    fn test_browser_support_report() {
+        let root = std::env::temp_dir().join(format!("codescope-browsers-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        install(&root, "modern", r#"{"module": "./dist/index.mjs"}"#, &[("dist/index.mjs", "export const f = (o) => o?.x;")]);
+        install(&root, "legacy", r#"{"main": "lib"}"#, &[("lib/index.js", "module.exports = function () {};")]);
+        install(&root, "picky", r#"{"browserslist": ["chrome >= 90"]}"#, &[("index.js", "module.exports = 1;")]);
+        install(&root, "tooling", "{}", &[("index.js", "export const f = () => 1;")]);
+
+        let mut graph = DependencyGraph::new();
+        for name in ["modern", "legacy", "picky"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_dependency("tooling", "1.0.0", DependencyType::Development);
+
+        let targets = BrowserTargets::parse(&["chrome >= 70", "safari >= 12", "ie 11", "last 2 versions"]);
+        let store = PackageStore::NodeModules(root.join("node_modules"));
+        let report = BrowserSupportReport::from_graph(&graph, &targets, &store);
+
+        assert_eq!(report.inspected, 3, "development dependencies are skipped");
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["modern", "picky"]);
+        assert_eq!(report.packages[0].entry.as_deref(), Some("dist/index.mjs"));
+        let requirements: Vec<String> = report.packages[0].conflicts.iter().map(TargetConflict::requirement).collect();
+        assert_eq!(
+            requirements,
+            vec!["chrome 80 (optional chaining)", "no ie (arrow functions)", "safari 13.1 (optional chaining)"]
+        );
+
+        let text = report.format_report();
+        assert!(text.starts_with("🌐 Targets: chrome >= 70, ie >= 11, safari >= 12\n"));
+        assert!(text.contains("⚠️  Ignored queries that need usage data: last 2 versions\n"));
+        assert!(text.contains("  picky@1.0.0: chrome 90 (browserslist)\n"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,189 @@
+//! How deep the dependency tree goes.
+//!
+//! Counts the packages at each depth, with direct dependencies at depth 1,
+//! and finds the longest chain of packages from a direct dependency down to
+//! the deepest one. Deep chains make installs slower and upgrades fragile,
+//! since a change at the bottom has to be picked up by every package above.
+
+use serde::Serialize;
+
+use crate::graph::{DependencyGraph, DependencyNode};
+
+/// Width of the widest histogram bar, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// The number of packages at one depth.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepthLevel {
+    /// Depth, 1 for direct dependencies
+    pub depth: usize,
+    /// Number of packages first reached at this depth
+    pub count: usize,
+}
+
+/// The distribution of dependency depths and the longest chain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DepthReport {
+    /// Packages per depth, shallowest first, including empty levels
+    pub histogram: Vec<DepthLevel>,
+    /// Mean depth over all packages
+    pub average: f64,
+    /// Packages from a direct dependency down to the deepest package
+    pub longest_chain: Vec<String>,
+}
+
+impl DepthReport {
+    /// Computes the depth report of a dependency graph.
+    ///
+    /// Each package counts at the depth of its shortest path from the
+    /// project, the same depth `--max-depth` checks. Among equally deep
+    /// packages the chain ends at the first by name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::DepthReport;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 0);
+    /// graph.add_dependency_with_depth("loose-envify", "1.4.0", DependencyType::Production, 1);
+    /// graph.add_edge("react", "loose-envify");
+    ///
+    /// let report = DepthReport::from_graph(&graph);
+    /// assert_eq!(report.max_depth(), 2);
+    /// assert_eq!(report.longest_chain, vec!["react", "loose-envify"]);
+    /// ```
+    pub fn from_graph(graph: &DependencyGraph) -> Self {
+        let nodes = graph.get_all_nodes();
+        let Some(deepest) = nodes
+            .iter()
+            .max_by(|a, b| a.depth.cmp(&b.depth).then_with(|| b.name.cmp(&a.name)))
+        else {
+            return Self::default();
+        };
+
+        let mut counts = vec![0; deepest.depth + 1];
+        for node in &nodes {
+            counts[node.depth] += 1;
+        }
+        let total: usize = nodes.iter().map(|node| node.depth + 1).sum();
+
+        Self {
+            histogram: counts
+                .into_iter()
+                .enumerate()
+                .map(|(depth, count)| DepthLevel { depth: depth + 1, count })
+                .collect(),
+            average: total as f64 / nodes.len() as f64,
+            longest_chain: chain_to(graph, deepest),
+        }
+    }
+
+    /// Returns the depth of the deepest package, 0 for an empty graph.
+    pub fn max_depth(&self) -> usize {
+        self.histogram.len()
+    }
+
+    /// Returns the number of packages.
+    pub fn package_count(&self) -> usize {
+        self.histogram.iter().map(|level| level.count).sum()
+    }
+
+    /// Formats the histogram and the longest chain.
+    pub fn format_report(&self) -> String {
+        if self.histogram.is_empty() {
+            return "📏 No dependencies.\n".to_string();
+        }
+
+        let mut out = format!(
+            "📏 Dependency depth ({} packages, max depth {}, average {:.1})\n\n",
+            self.package_count(),
+            self.max_depth(),
+            self.average
+        );
+        let largest = self.histogram.iter().map(|level| level.count).max().unwrap_or(0).max(1);
+        let width = largest.to_string().len().max("Packages".len());
+        out.push_str(&format!("  Depth  {:>width$}\n", "Packages", width = width));
+        for level in &self.histogram {
+            let bar = (level.count * BAR_WIDTH).div_ceil(largest);
+            out.push_str(&format!(
+                "  {:>5}  {:>width$}  {}\n",
+                level.depth,
+                level.count,
+                "█".repeat(bar),
+                width = width
+            ));
+        }
+
+        out.push_str(&format!("\nLongest chain ({} levels):\n", self.longest_chain.len()));
+        out.push_str(&format!("  {}\n", self.longest_chain.join(" -> ")));
+        out
+    }
+}
+
+/// Walks from `node` back to a direct dependency, always stepping to a
+/// dependent one level shallower, and returns the chain root first.
+pub fn chain_to(graph: &DependencyGraph, node: &DependencyNode) -> Vec<String> {
+    let mut chain = vec![node.name.clone()];
+    let mut current = node;
+    while current.depth > 0 {
+        let parent = graph
+            .get_dependents(&current.name)
+            .into_iter()
+            .filter(|p| p.depth + 1 == current.depth)
+            .min_by(|a, b| a.name.cmp(&b.name));
+        match parent {
+            Some(parent) => {
+                chain.push(parent.name.clone());
+                current = parent;
+            }
+            None => break,
+        }
+    }
+    chain.reverse();
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    #[test]
+    fn test_depth_report() {
+        let mut graph = DependencyGraph::new();
+        for (name, depth) in [("react", 0), ("lodash", 0), ("jest", 0), ("loose-envify", 1), ("chalk", 1), ("js-tokens", 2)] {
+            graph.add_dependency_with_depth(name, "1.0.0", DependencyType::Production, depth);
+        }
+        graph.add_edge("react", "loose-envify");
+        graph.add_edge("jest", "chalk");
+        graph.add_edge("loose-envify", "js-tokens");
+        graph.add_edge("chalk", "js-tokens");
+
+        let report = DepthReport::from_graph(&graph);
+        let counts: Vec<usize> = report.histogram.iter().map(|level| level.count).collect();
+        assert_eq!(counts, vec![3, 2, 1]);
+        assert_eq!(report.package_count(), 6);
+        assert!((report.average - 10.0 / 6.0).abs() < 1e-9);
+        assert_eq!(
+            report.longest_chain,
+            vec!["jest", "chalk", "js-tokens"],
+            "ties between parents go to the first by name"
+        );
+
+        let text = report.format_report();
+        assert!(text.contains("(6 packages, max depth 3, average 1.7)"));
+        assert!(text.contains(&format!("      1         3  {}\n", "█".repeat(30))));
+        assert!(text.contains(&format!("      3         1  {}\n", "█".repeat(10))));
+        assert!(text.contains("Longest chain (3 levels):\n  jest -> chalk -> js-tokens\n"));
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let report = DepthReport::from_graph(&DependencyGraph::new());
+        assert_eq!(report.max_depth(), 0);
+        assert!(report.longest_chain.is_empty());
+        assert_eq!(report.format_report(), "📏 No dependencies.\n");
+    }
+}
@@ -0,0 +1,139 @@
+//! Package descriptions and keywords.
+//!
+//! Names are not always memorable; "that date library" is easier to find
+//! by what a package says about itself. Descriptions and keywords come
+//! from the installed `package.json` files, or from the registry for
+//! packages that are not installed.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::graph::DependencyGraph;
+use crate::parser::{PackageLocation, PackageStore};
+
+/// What a package says about itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageDescription {
+    /// The `description` field
+    pub description: Option<String>,
+    /// The `keywords` field
+    pub keywords: Vec<String>,
+}
+
+impl PackageDescription {
+    /// Creates a description from manifest or registry fields.
+    ///
+    /// `keywords` is usually a list of strings; a single string is split
+    /// on commas and whitespace, and anything else is ignored.
+    pub fn new(description: Option<&str>, keywords: Option<&Value>) -> Self {
+        let keywords = match keywords {
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            Some(Value::String(s)) => s
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|k| !k.is_empty())
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            description: description.map(str::trim).filter(|d| !d.is_empty()).map(str::to_string),
+            keywords,
+        }
+    }
+
+    /// Reads the description of an installed package.
+    ///
+    /// Returns `None` if the manifest cannot be read or says nothing about
+    /// the package.
+    pub fn read(location: &PackageLocation) -> Option<Self> {
+        let manifest: Value = serde_json::from_str(&location.read_to_string("package.json").ok()?).ok()?;
+        Some(Self::new(manifest.get("description").and_then(Value::as_str), manifest.get("keywords")))
+            .filter(|description| !description.is_empty())
+    }
+
+    /// Returns true if neither a description nor keywords are known.
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.keywords.is_empty()
+    }
+
+    /// Returns true if every word of `query` appears in the description or
+    /// the keywords, ignoring case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::descriptions::PackageDescription;
+    /// use serde_json::json;
+    ///
+    /// let dayjs = PackageDescription::new(
+    ///     Some("2KB immutable date time library alternative to Moment.js"),
+    ///     Some(&json!(["dayjs", "date", "time"])),
+    /// );
+    /// assert!(dayjs.matches("date library"));
+    /// assert!(dayjs.matches("MOMENT"));
+    /// assert!(!dayjs.matches("http"));
+    /// ```
+    pub fn matches(&self, query: &str) -> bool {
+        let description = self.description.as_deref().unwrap_or_default().to_lowercase();
+        let keywords: Vec<String> = self.keywords.iter().map(|k| k.to_lowercase()).collect();
+        let mut words = query.split_whitespace().map(str::to_lowercase).peekable();
+        words.peek().is_some()
+            && words.all(|word| description.contains(&word) || keywords.iter().any(|k| k.contains(&word)))
+    }
+}
+
+/// Reads the description of every installed package in the graph, keyed by
+/// name.
+pub fn describe_packages(graph: &DependencyGraph, store: &PackageStore) -> BTreeMap<String, PackageDescription> {
+    graph
+        .get_all_nodes()
+        .into_iter()
+        .filter_map(|node| {
+            let name = node.package_name();
+            let location = store
+                .locate(name, Some(&node.version))
+                .or_else(|| store.locate(name, None))?;
+            Some((node.name.clone(), PackageDescription::read(&location)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keyword_forms() {
+        assert_eq!(
+            PackageDescription::new(None, Some(&json!("date, time  parse"))).keywords,
+            vec!["date", "time", "parse"]
+        );
+        assert_eq!(PackageDescription::new(Some("  "), Some(&json!({"a": 1}))), PackageDescription::default());
+        assert!(!PackageDescription::new(Some("Date utilities"), None).matches("  "));
+    }
+
+    #[test]
+    fn test_read_installed_descriptions() {
+        let dir = std::env::temp_dir().join(format!("codescope-descriptions-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (name, manifest) in [
+            ("date-fns", r#"{"name": "date-fns", "description": "Modern JavaScript date utility library", "keywords": ["date", "time"]}"#),
+            ("left-pad", r#"{"name": "left-pad"}"#),
+        ] {
+            std::fs::create_dir_all(dir.join("node_modules").join(name)).unwrap();
+            std::fs::write(dir.join("node_modules").join(name).join("package.json"), manifest).unwrap();
+        }
+
+        let mut graph = DependencyGraph::new();
+        for name in ["date-fns", "left-pad", "missing"] {
+            graph.add_dependency(name, "1.0.0", crate::graph::DependencyType::Production);
+        }
+        let descriptions = describe_packages(&graph, &PackageStore::detect(&dir).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(descriptions.keys().collect::<Vec<_>>(), vec!["date-fns"]);
+        assert!(descriptions["date-fns"].matches("utility date"));
+    }
+}
@@ -0,0 +1,449 @@
+//! Comparison of two dependency graphs.
+//!
+//! Used to compare a project between two git revisions (or a revision and
+//! the working tree): which packages were added, removed or changed
+//! version, and which cycles and version conflicts appeared or went away.
+//!
+//! [`compare_graphs`] lines up two graphs package by package instead, for
+//! the side-by-side comparison of two projects or snapshots.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::bundle::webpack::format_size;
+use crate::graph::DependencyGraph;
+
+/// How a package differs between the two graphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Only in the new graph
+    Added,
+    /// Only in the old graph
+    Removed,
+    /// In both graphs with different versions
+    Changed,
+}
+
+/// A package that differs between the two graphs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageChange {
+    /// Package name
+    pub name: String,
+    /// Version in the old graph, if present
+    pub before: Option<String>,
+    /// Version in the new graph, if present
+    pub after: Option<String>,
+}
+
+impl PackageChange {
+    /// Returns whether the package was added, removed or changed.
+    pub fn kind(&self) -> ChangeKind {
+        match (&self.before, &self.after) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        }
+    }
+}
+
+/// Differences between two dependency graphs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyDiff {
+    /// Added, removed and changed packages, sorted by name
+    pub packages: Vec<PackageChange>,
+    /// Cycle paths only found in the new graph
+    pub cycles_added: Vec<String>,
+    /// Cycle paths only found in the old graph
+    pub cycles_resolved: Vec<String>,
+    /// Packages with version conflicts only in the new graph
+    pub conflicts_added: Vec<String>,
+    /// Packages with version conflicts only in the old graph
+    pub conflicts_resolved: Vec<String>,
+}
+
+impl DependencyDiff {
+    /// Returns true if the graphs are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+            && self.cycles_added.is_empty()
+            && self.cycles_resolved.is_empty()
+            && self.conflicts_added.is_empty()
+            && self.conflicts_resolved.is_empty()
+    }
+
+    /// Returns the packages of one kind of change.
+    pub fn packages_of_kind(&self, kind: ChangeKind) -> Vec<&PackageChange> {
+        self.packages.iter().filter(|p| p.kind() == kind).collect()
+    }
+
+    /// Formats the differences as a human-readable report.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - Label of the old graph, e.g. `main (1a2b3c4)`
+    /// * `after` - Label of the new graph
+    pub fn format_report(&self, before: &str, after: &str) -> String {
+        let mut out = format!("Comparing {} -> {}\n", before, after);
+        if self.is_empty() {
+            out.push_str("\nNo dependency changes.\n");
+            return out;
+        }
+
+        let sections = [
+            (ChangeKind::Added, "Added", "+"),
+            (ChangeKind::Removed, "Removed", "-"),
+            (ChangeKind::Changed, "Changed", "~"),
+        ];
+        for (kind, title, marker) in sections {
+            let packages = self.packages_of_kind(kind);
+            if packages.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n{} ({}):\n", title, packages.len()));
+            for package in packages {
+                let version = match (&package.before, &package.after) {
+                    (Some(before), Some(after)) => format!("{} -> {}", before, after),
+                    (Some(version), None) | (None, Some(version)) => version.clone(),
+                    (None, None) => String::new(),
+                };
+                out.push_str(&format!("  {} {} {}\n", marker, package.name, version));
+            }
+        }
+
+        let lists = [
+            ("New cycles", "❌", &self.cycles_added),
+            ("Resolved cycles", "✅", &self.cycles_resolved),
+            ("New version conflicts", "❌", &self.conflicts_added),
+            ("Resolved version conflicts", "✅", &self.conflicts_resolved),
+        ];
+        for (title, marker, items) in lists {
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n{} ({}):\n", title, items.len()));
+            for item in items {
+                out.push_str(&format!("  {} {}\n", marker, item));
+            }
+        }
+        out
+    }
+}
+
+/// A package on one side of a side-by-side comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComparedSide {
+    /// Version on this side
+    pub version: String,
+    /// Bundle size in bytes on this side, if known
+    pub bundle_size: Option<u64>,
+}
+
+/// How the two sides of a compared package differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difference {
+    /// Only the left project has the package
+    OnlyLeft,
+    /// Only the right project has the package
+    OnlyRight,
+    /// Both have it, at different versions
+    Version,
+    /// Both have it at the same version, with different bundle sizes
+    Size,
+    /// Both have it at the same version and size
+    Same,
+}
+
+/// A package lined up across the two projects of a comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComparedPackage {
+    /// Package name
+    pub name: String,
+    /// The package in the left project, if present
+    pub left: Option<ComparedSide>,
+    /// The package in the right project, if present
+    pub right: Option<ComparedSide>,
+}
+
+impl ComparedPackage {
+    /// Returns how the two sides differ.
+    pub fn difference(&self) -> Difference {
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) if left.version != right.version => Difference::Version,
+            (Some(left), Some(right)) if left.bundle_size != right.bundle_size => Difference::Size,
+            (Some(_), Some(_)) => Difference::Same,
+            (Some(_), None) => Difference::OnlyLeft,
+            (None, _) => Difference::OnlyRight,
+        }
+    }
+
+    /// Returns the right bundle size minus the left one, if both are known.
+    pub fn size_delta(&self) -> Option<i64> {
+        let left = self.left.as_ref()?.bundle_size?;
+        let right = self.right.as_ref()?.bundle_size?;
+        Some(right as i64 - left as i64)
+    }
+}
+
+/// Formats a signed size difference, e.g. `+1.50 KB` or `-512 B`.
+pub fn format_size_delta(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{}{}", sign, format_size(delta.unsigned_abs()))
+}
+
+/// Lines up the packages of two graphs by name, sorted by name.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::diff::{compare_graphs, Difference};
+/// use codescope::graph::{DependencyGraph, DependencyType};
+///
+/// let mut left = DependencyGraph::new();
+/// left.add_dependency("react", "18.2.0", DependencyType::Production);
+/// left.add_dependency("moment", "2.29.4", DependencyType::Production);
+/// let mut right = DependencyGraph::new();
+/// right.add_dependency("react", "18.3.1", DependencyType::Production);
+///
+/// let rows = compare_graphs(&left, &right);
+/// assert_eq!(rows[0].difference(), Difference::OnlyLeft);
+/// assert_eq!(rows[1].difference(), Difference::Version);
+/// ```
+pub fn compare_graphs(left: &DependencyGraph, right: &DependencyGraph) -> Vec<ComparedPackage> {
+    let sides = |graph: &DependencyGraph| -> BTreeMap<String, ComparedSide> {
+        graph
+            .get_all_nodes()
+            .into_iter()
+            .map(|node| {
+                let side = ComparedSide {
+                    version: node.version.clone(),
+                    bundle_size: node.bundle_size,
+                };
+                (node.name.clone(), side)
+            })
+            .collect()
+    };
+    let (mut left, mut right) = (sides(left), sides(right));
+
+    let names: BTreeSet<String> = left.keys().chain(right.keys()).cloned().collect();
+    names
+        .into_iter()
+        .map(|name| ComparedPackage {
+            left: left.remove(&name),
+            right: right.remove(&name),
+            name,
+        })
+        .collect()
+}
+
+/// Formats the packages that differ between two compared projects.
+///
+/// # Arguments
+///
+/// * `rows` - The compared packages, from [`compare_graphs`]
+/// * `left` - Label of the left project
+/// * `right` - Label of the right project
+pub fn format_comparison(rows: &[ComparedPackage], left: &str, right: &str) -> String {
+    let mut out = format!("Comparing {} with {}\n", left, right);
+    let differing: Vec<&ComparedPackage> = rows.iter().filter(|r| r.difference() != Difference::Same).collect();
+    if differing.is_empty() {
+        out.push_str(&format!("\nThe {} packages are the same.\n", rows.len()));
+        return out;
+    }
+
+    let sections = [
+        (Difference::OnlyLeft, format!("Only in {}", left)),
+        (Difference::OnlyRight, format!("Only in {}", right)),
+        (Difference::Version, "Different versions".to_string()),
+        (Difference::Size, "Different sizes".to_string()),
+    ];
+    for (difference, title) in sections {
+        let packages: Vec<&&ComparedPackage> = differing.iter().filter(|r| r.difference() == difference).collect();
+        if packages.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n{} ({}):\n", title, packages.len()));
+        for package in packages {
+            let version = |side: &Option<ComparedSide>| side.as_ref().map(|s| s.version.clone()).unwrap_or_default();
+            let detail = match difference {
+                Difference::OnlyLeft => version(&package.left),
+                Difference::OnlyRight => version(&package.right),
+                Difference::Version => format!("{} | {}", version(&package.left), version(&package.right)),
+                Difference::Size | Difference::Same => {
+                    package.size_delta().map(format_size_delta).unwrap_or_default()
+                }
+            };
+            out.push_str(&format!("  {} {}\n", package.name, detail));
+        }
+    }
+    out
+}
+
+/// Compares two dependency graphs.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::{diff_graphs, ChangeKind};
+/// use codescope::graph::{DependencyGraph, DependencyType};
+///
+/// let mut before = DependencyGraph::new();
+/// before.add_dependency("react", "18.2.0", DependencyType::Production);
+/// before.add_dependency("moment", "2.29.4", DependencyType::Production);
+///
+/// let mut after = DependencyGraph::new();
+/// after.add_dependency("react", "18.3.1", DependencyType::Production);
+/// after.add_dependency("dayjs", "1.11.10", DependencyType::Production);
+///
+/// let diff = diff_graphs(&before, &after);
+/// let kinds: Vec<_> = diff.packages.iter().map(|p| (p.name.as_str(), p.kind())).collect();
+/// assert_eq!(kinds, vec![
+///     ("dayjs", ChangeKind::Added),
+///     ("moment", ChangeKind::Removed),
+///     ("react", ChangeKind::Changed),
+/// ]);
+/// ```
+pub fn diff_graphs(before: &DependencyGraph, after: &DependencyGraph) -> DependencyDiff {
+    let versions = |graph: &DependencyGraph| -> BTreeMap<String, String> {
+        graph
+            .get_all_nodes()
+            .into_iter()
+            .map(|node| (node.name.clone(), node.version.clone()))
+            .collect()
+    };
+    let (old, new) = (versions(before), versions(after));
+
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let packages = names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| PackageChange {
+            name: name.clone(),
+            before: old.get(name).cloned(),
+            after: new.get(name).cloned(),
+        })
+        .collect();
+
+    let cycles = |graph: &DependencyGraph| -> BTreeSet<String> {
+        graph.get_cycle_details().iter().map(|c| c.cycle_path()).collect()
+    };
+    let conflicts = |graph: &DependencyGraph| -> BTreeSet<String> {
+        graph.get_packages_with_conflicts().into_iter().collect()
+    };
+    let (old_cycles, new_cycles) = (cycles(before), cycles(after));
+    let (old_conflicts, new_conflicts) = (conflicts(before), conflicts(after));
+
+    DependencyDiff {
+        packages,
+        cycles_added: new_cycles.difference(&old_cycles).cloned().collect(),
+        cycles_resolved: old_cycles.difference(&new_cycles).cloned().collect(),
+        conflicts_added: new_conflicts.difference(&old_conflicts).cloned().collect(),
+        conflicts_resolved: old_conflicts.difference(&new_conflicts).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    #[test]
+    fn test_diff_findings() {
+        let mut before = DependencyGraph::new();
+        before.add_dependency("a", "1.0.0", DependencyType::Production);
+        before.add_dependency("b", "1.0.0", DependencyType::Production);
+        before.add_edge("a", "b");
+        before.add_edge("b", "a");
+
+        let mut after = before.clone();
+        after.add_dependency("c", "1.0.0", DependencyType::Production);
+        after.add_dependency("d", "1.0.0", DependencyType::Production);
+        after.add_edge("c", "d");
+        after.add_edge("d", "c");
+        after.track_version_requirement("c", "^1.0.0", "a");
+        after.track_version_requirement("c", "^2.0.0", "b");
+
+        let diff = diff_graphs(&before, &after);
+        assert_eq!(diff.packages_of_kind(ChangeKind::Added).len(), 2);
+        assert_eq!(diff.cycles_added, vec!["c -> d -> c"]);
+        assert!(diff.cycles_resolved.is_empty());
+        assert_eq!(diff.conflicts_added, vec!["c"]);
+
+        let reverse = diff_graphs(&after, &before);
+        assert_eq!(reverse.cycles_resolved, vec!["c -> d -> c"]);
+        assert_eq!(reverse.conflicts_resolved, vec!["c"]);
+        assert!(diff_graphs(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_compare_graphs() {
+        let mut left = DependencyGraph::new();
+        let mut right = DependencyGraph::new();
+        for (graph, react) in [(&mut left, "18.2.0"), (&mut right, "18.3.1")] {
+            graph.add_dependency("react", react, DependencyType::Production);
+            graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+            graph.add_dependency("scheduler", "0.23.0", DependencyType::Production);
+        }
+        left.add_dependency("moment", "2.29.4", DependencyType::Production);
+        right.add_dependency("dayjs", "1.11.10", DependencyType::Production);
+        let sizes = |lodash: u64| {
+            [("lodash".to_string(), (lodash, 1_usize)), ("scheduler".to_string(), (4096, 1))]
+                .into_iter()
+                .collect()
+        };
+        left.apply_bundle_sizes(&sizes(70_000));
+        right.apply_bundle_sizes(&sizes(71_536));
+
+        let rows = compare_graphs(&left, &right);
+        let differences: Vec<(&str, Difference)> = rows.iter().map(|r| (r.name.as_str(), r.difference())).collect();
+        assert_eq!(
+            differences,
+            vec![
+                ("dayjs", Difference::OnlyRight),
+                ("lodash", Difference::Size),
+                ("moment", Difference::OnlyLeft),
+                ("react", Difference::Version),
+                ("scheduler", Difference::Same),
+            ]
+        );
+        assert_eq!(rows[1].size_delta(), Some(1536));
+        assert_eq!(format_size_delta(-512), "-512 B");
+
+        assert_eq!(
+            format_comparison(&rows, "app-a", "app-b"),
+            "Comparing app-a with app-b\n\
+             \n\
+             Only in app-a (1):\n  moment 2.29.4\n\
+             \n\
+             Only in app-b (1):\n  dayjs 1.11.10\n\
+             \n\
+             Different versions (1):\n  react 18.2.0 | 18.3.1\n\
+             \n\
+             Different sizes (1):\n  lodash +1.50 KB\n"
+        );
+    }
+
+    #[test]
+    fn test_format_report() {
+        let mut before = DependencyGraph::new();
+        before.add_dependency("react", "18.2.0", DependencyType::Production);
+        before.add_dependency("moment", "2.29.4", DependencyType::Production);
+        let mut after = DependencyGraph::new();
+        after.add_dependency("react", "18.3.1", DependencyType::Production);
+
+        let report = diff_graphs(&before, &after).format_report("main", "HEAD");
+        assert_eq!(
+            report,
+            "Comparing main -> HEAD\n\
+             \n\
+             Removed (1):\n  - moment 2.29.4\n\
+             \n\
+             Changed (1):\n  ~ react 18.2.0 -> 18.3.1\n"
+        );
+        assert!(diff_graphs(&after, &after)
+            .format_report("a", "b")
+            .ends_with("No dependency changes.\n"));
+    }
+}
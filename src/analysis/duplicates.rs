@@ -0,0 +1,279 @@
+//! External packages used at different versions across a monorepo.
+//!
+//! When workspace packages ask for incompatible ranges of the same
+//! dependency, the package manager installs several copies and bundles that
+//! include more than one workspace may ship them all. For each such package
+//! this module suggests one version that every range accepts, which can be
+//! enforced with a `resolutions` (Yarn) or `overrides` (npm) entry in the
+//! root package.json.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use semver::Version;
+use serde::Serialize;
+
+use super::hoisting::{explain_hoisting, HoistingExplanation};
+use crate::parser::lockfile::{install_name, PackageLock};
+use crate::parser::range::{parse_version, VersionRange};
+use crate::parser::{extract_dependencies, Workspace};
+
+/// One workspace package's requirement on a duplicated dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateUsage {
+    /// Workspace package name (the root package for the monorepo root)
+    pub member: String,
+    /// Range declared in its package.json
+    pub range: String,
+    /// Version installed for it according to the lockfile, if known
+    pub resolved: Option<String>,
+}
+
+/// An external package required at different versions across workspaces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspaceDuplicate {
+    /// Package name
+    pub package: String,
+    /// Requirements, in workspace order
+    pub usages: Vec<DuplicateUsage>,
+    /// Version to align on, if any candidate version is known
+    pub suggested: Option<String>,
+    /// Whether `suggested` satisfies every declared range
+    pub satisfies_all: bool,
+    /// Why the lockfile installs several copies, if it does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hoisting: Option<HoistingExplanation>,
+}
+
+impl WorkspaceDuplicate {
+    /// Returns the distinct installed versions, or the distinct ranges when
+    /// nothing is resolved.
+    pub fn versions(&self) -> BTreeSet<&str> {
+        let resolved: BTreeSet<&str> = self.usages.iter().filter_map(|u| u.resolved.as_deref()).collect();
+        if resolved.is_empty() {
+            self.usages.iter().map(|u| u.range.as_str()).collect()
+        } else {
+            resolved
+        }
+    }
+
+    /// Returns the workspace packages whose range rejects the suggested
+    /// version; they must widen their range to align.
+    pub fn conflicting_members(&self) -> Vec<&str> {
+        let Some(suggested) = &self.suggested else {
+            return Vec::new();
+        };
+        self.usages
+            .iter()
+            .filter(|u| !VersionRange::parse(&u.range).is_some_and(|r| r.matches(suggested)))
+            .map(|u| u.member.as_str())
+            .collect()
+    }
+
+    /// Returns the `resolutions`/`overrides` entry pinning the suggested
+    /// version, e.g. `"lodash": "4.17.21"`.
+    pub fn resolution(&self) -> Option<String> {
+        self.suggested
+            .as_ref()
+            .map(|version| format!("\"{}\": \"{}\"", self.package, version))
+    }
+}
+
+/// Finds external packages the root and workspace packages depend on at
+/// different ranges or installed versions.
+///
+/// # Arguments
+///
+/// * `workspace` - The monorepo
+/// * `lock` - The root package-lock.json, used for installed versions and
+///   as the source of candidate versions
+///
+/// # Returns
+///
+/// Duplicates sorted by package name.
+pub fn find_workspace_duplicates(workspace: &Workspace, lock: Option<&PackageLock>) -> Vec<WorkspaceDuplicate> {
+    let root_name = workspace.package.name.clone().unwrap_or_else(|| "(root)".to_string());
+    let packages = std::iter::once((root_name, String::new(), &workspace.package))
+        .chain(workspace.members.iter().map(|m| (m.name.clone(), m.path.clone(), &m.package)));
+
+    let mut usages: BTreeMap<String, Vec<DuplicateUsage>> = BTreeMap::new();
+    for (member, path, package) in packages {
+        let mut deps = extract_dependencies(package);
+        deps.sort_by(|a, b| a.name.cmp(&b.name));
+        deps.dedup_by(|a, b| a.name == b.name);
+        for dep in deps {
+            if workspace.is_member(&dep.name) || dep.version.starts_with("workspace:") {
+                continue;
+            }
+            let resolved = lock
+                .and_then(|lock| lock.resolve(&path, &dep.name))
+                .and_then(|install_path| lock?.packages.get(install_path)?.version.clone());
+            usages.entry(dep.name).or_default().push(DuplicateUsage {
+                member: member.clone(),
+                range: dep.version,
+                resolved,
+            });
+        }
+    }
+
+    let mut hoisting = lock.map(explain_hoisting).unwrap_or_default();
+    usages
+        .into_iter()
+        .filter(|(_, usages)| usages.len() > 1)
+        .map(|(package, usages)| {
+            let (suggested, satisfies_all) = suggest_version(&package, &usages, lock);
+            WorkspaceDuplicate {
+                hoisting: hoisting.remove(&package),
+                package,
+                usages,
+                suggested,
+                satisfies_all,
+            }
+        })
+        .filter(|dup| dup.versions().len() > 1)
+        .collect()
+}
+
+/// Picks the highest known version accepted by the most ranges.
+fn suggest_version(package: &str, usages: &[DuplicateUsage], lock: Option<&PackageLock>) -> (Option<String>, bool) {
+    let ranges: Vec<Option<VersionRange>> = usages.iter().map(|u| VersionRange::parse(&u.range)).collect();
+
+    let mut candidates: BTreeSet<Version> = ranges.iter().flatten().flat_map(VersionRange::lower_bounds).collect();
+    candidates.extend(usages.iter().filter_map(|u| u.resolved.as_deref().and_then(parse_version)));
+    if let Some(lock) = lock {
+        candidates.extend(
+            lock.packages
+                .iter()
+                .filter(|(path, _)| install_name(path) == package)
+                .filter_map(|(_, entry)| entry.version.as_deref().and_then(parse_version)),
+        );
+    }
+
+    let accepted = |version: &Version| {
+        ranges
+            .iter()
+            .filter(|range| range.as_ref().is_some_and(|r| r.matches_version(version)))
+            .count()
+    };
+    // max_by_key keeps the last maximum, so ties go to the highest version
+    let best = candidates.iter().max_by_key(|version| accepted(version));
+    match best {
+        Some(version) => (Some(version.to_string()), accepted(version) == usages.len()),
+        None => (None, false),
+    }
+}
+
+/// Formats duplicates as a human-readable report.
+pub fn format_duplicates(duplicates: &[WorkspaceDuplicate]) -> String {
+    if duplicates.is_empty() {
+        return "✅ Every workspace package uses the same versions of its external dependencies.\n".to_string();
+    }
+
+    let mut out = format!(
+        "📦 {} package(s) used at different versions across workspaces\n",
+        duplicates.len()
+    );
+    for dup in duplicates {
+        out.push_str(&format!("\n{} ({} versions)\n", dup.package, dup.versions().len()));
+        let width = dup.usages.iter().map(|u| u.member.len()).max().unwrap_or(0);
+        for usage in &dup.usages {
+            let resolved = usage.resolved.as_deref().map(|v| format!(" -> {}", v)).unwrap_or_default();
+            out.push_str(&format!("  {:width$}  {}{}\n", usage.member, usage.range, resolved, width = width));
+        }
+        if let Some(hoisting) = &dup.hoisting {
+            out.push_str(&format!("  Installed {} times:\n", hoisting.copy_count()));
+            for line in hoisting.format_lines() {
+                out.push_str(&format!("  {}\n", line));
+            }
+        }
+
+        let (Some(version), Some(resolution)) = (&dup.suggested, dup.resolution()) else {
+            out.push_str("  No known version to align on.\n");
+            continue;
+        };
+        if dup.satisfies_all {
+            out.push_str(&format!("  Suggested: {} satisfies every range\n", version));
+        } else {
+            out.push_str(&format!(
+                "  Suggested: {} (widen the range in {})\n",
+                version,
+                dup.conflicting_members().join(", ")
+            ));
+        }
+        out.push_str(&format!(
+            "  Add to the root package.json \"resolutions\" (Yarn) or \"overrides\" (npm): {}\n",
+            resolution
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::workspace::tests::create_workspace;
+
+    #[test]
+    fn test_find_workspace_duplicates() {
+        let dir = create_workspace(
+            "duplicates",
+            r#"{"name": "root", "workspaces": ["packages/*"], "devDependencies": {"typescript": "^5.0.0"}}"#,
+            &[
+                (
+                    "packages/web",
+                    r#"{"name": "web", "dependencies": {"lodash": "^4.17.0", "react": "^18.0.0", "ui": "workspace:*"}}"#,
+                ),
+                (
+                    "packages/ui",
+                    r#"{"name": "ui", "dependencies": {"lodash": "^4.17.21", "react": "^18.0.0"}, "devDependencies": {"typescript": "~4.9.0"}}"#,
+                ),
+            ],
+        );
+        let lock = PackageLock::parse(
+            r#"{
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "root", "devDependencies": { "typescript": "^5.0.0" } },
+                    "packages/web": { "name": "web", "dependencies": { "lodash": "^4.17.0", "react": "^18.0.0" } },
+                    "packages/ui": {
+                        "name": "ui",
+                        "dependencies": { "lodash": "^4.17.21", "react": "^18.0.0" },
+                        "devDependencies": { "typescript": "~4.9.0" }
+                    },
+                    "node_modules/lodash": { "version": "4.17.20" },
+                    "packages/ui/node_modules/lodash": { "version": "4.17.21" },
+                    "node_modules/react": { "version": "18.2.0" },
+                    "node_modules/typescript": { "version": "5.3.3" },
+                    "packages/ui/node_modules/typescript": { "version": "4.9.5" }
+                }
+            }"#,
+        )
+        .unwrap();
+        let workspace = Workspace::load(&dir).unwrap().unwrap();
+
+        let duplicates = find_workspace_duplicates(&workspace, Some(&lock));
+        let names: Vec<&str> = duplicates.iter().map(|d| d.package.as_str()).collect();
+        assert_eq!(names, vec!["lodash", "typescript"], "react resolves to one version");
+
+        let lodash = &duplicates[0];
+        assert_eq!(lodash.suggested.as_deref(), Some("4.17.21"));
+        assert!(lodash.satisfies_all);
+        assert_eq!(lodash.resolution().unwrap(), r#""lodash": "4.17.21""#);
+
+        let typescript = &duplicates[1];
+        assert_eq!(typescript.suggested.as_deref(), Some("5.3.3"));
+        assert!(!typescript.satisfies_all);
+        assert_eq!(typescript.conflicting_members(), vec!["ui"]);
+
+        let report = format_duplicates(&duplicates);
+        assert!(report.contains("  root  ^5.0.0 -> 5.3.3\n"));
+        assert!(report.contains("Suggested: 5.3.3 (widen the range in ui)"));
+        assert!(report.contains(
+            "  Installed 2 times:\n    4.17.20 hoisted to node_modules/lodash\n      required by web (^4.17.0)\n"
+        ));
+        assert!(report.contains("      required by ui (^4.17.21); 4.17.20 is out of range\n"));
+        assert!(report.contains("      hoist blocked by root (^5.0.0); 4.9.5 is out of range\n"));
+        assert!(duplicates[1].hoisting.is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
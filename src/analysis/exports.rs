@@ -3,13 +3,14 @@
 //! This module parses source files to extract import statements and track
 //! which exports from each dependency are actually used.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
 use thiserror::Error;
 use tree_sitter::{Language, Parser, Tree};
-use walkdir::WalkDir;
 
 /// Errors that can occur during import analysis.
 #[derive(Error, Debug)]
@@ -25,6 +26,9 @@ pub enum AnalysisError {
 
     #[error("Tree-sitter language initialization failed")]
     LanguageInit,
+
+    #[error("Invalid ignore pattern: {0}")]
+    InvalidIgnorePattern(String),
 }
 
 /// Result type for analysis operations.
@@ -193,6 +197,15 @@ impl PackageUsage {
     }
 }
 
+/// A file importing a package, and where it does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSite {
+    /// Path of the importing file
+    pub file: String,
+    /// Lines of the file's imports of the package (1-indexed), in order
+    pub lines: Vec<usize>,
+}
+
 /// Collection of all imports found in a project.
 #[derive(Debug, Default)]
 pub struct ProjectImports {
@@ -200,6 +213,8 @@ pub struct ProjectImports {
     pub imports_by_file: HashMap<String, Vec<Import>>,
     /// Package usage statistics.
     pub package_usage: HashMap<String, PackageUsage>,
+    /// Source files that could not be read or parsed, with the error
+    pub unreadable_files: Vec<(String, String)>,
 }
 
 impl ProjectImports {
@@ -240,13 +255,53 @@ impl ProjectImports {
         self.imports_by_file.insert(file_path.to_string(), imports);
     }
 
+    /// Get the files importing each package, sorted by path, keyed by
+    /// package name.
+    pub fn import_sites(&self) -> BTreeMap<String, Vec<ImportSite>> {
+        let mut sites: BTreeMap<String, Vec<ImportSite>> = BTreeMap::new();
+        for (file, imports) in &self.imports_by_file {
+            let mut lines: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+            for import in imports {
+                if let Some(pkg_name) = import.package_name() {
+                    lines.entry(pkg_name).or_default().push(import.line);
+                }
+            }
+            for (pkg_name, mut lines) in lines {
+                lines.sort_unstable();
+                lines.dedup();
+                sites.entry(pkg_name.to_string()).or_default().push(ImportSite {
+                    file: file.clone(),
+                    lines,
+                });
+            }
+        }
+        for files in sites.values_mut() {
+            files.sort_by(|a, b| a.file.cmp(&b.file));
+        }
+        sites
+    }
+
     /// Get list of packages sorted by number of importing files (descending).
     pub fn packages_by_usage(&self) -> Vec<(&String, &PackageUsage)> {
         let mut packages: Vec<_> = self.package_usage.iter().collect();
-        packages.sort_by(|a, b| b.1.importing_files.len().cmp(&a.1.importing_files.len()));
+        packages.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.importing_files.len()));
         packages
     }
 
+    /// Get the percentage of its exports the project uses for every
+    /// imported package whose export count is known, or that is imported
+    /// as a namespace.
+    pub fn utilization(&self, export_counts: &HashMap<String, usize>) -> HashMap<String, f64> {
+        self.package_usage
+            .iter()
+            .filter_map(|(name, usage)| {
+                let total = export_counts.get(name).copied().unwrap_or(0);
+                let percentage = usage.utilization_percentage(total)?;
+                Some((name.clone(), percentage.min(100.0)))
+            })
+            .collect()
+    }
+
     /// Get packages that might be underutilized given export counts.
     pub fn underutilized_packages(
         &self,
@@ -289,15 +344,13 @@ impl SourceLanguage {
     }
 
     /// Get tree-sitter language for this source language.
-    #[allow(dead_code)]
     pub fn tree_sitter_language(&self) -> Language {
         match self {
             SourceLanguage::JavaScript | SourceLanguage::Jsx => {
                 tree_sitter_javascript::LANGUAGE.into()
             }
-            SourceLanguage::TypeScript | SourceLanguage::Tsx => {
-                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
-            }
+            SourceLanguage::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            SourceLanguage::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
         }
     }
 }
@@ -642,35 +695,28 @@ pub fn analyze_file(path: &Path) -> AnalysisResult<Vec<Import>> {
 
 /// Analyze all JavaScript/TypeScript files in a directory.
 pub fn analyze_project_imports(root: &Path) -> AnalysisResult<ProjectImports> {
+    analyze_project_imports_with(root, &[])
+}
+
+/// Analyze the JavaScript/TypeScript files in a directory, skipping those
+/// matched by any of the `ignore` globs as well as the files
+/// [`source_files`] always skips.
+#[tracing::instrument(name = "scan", skip_all, fields(root = %root.display()), err)]
+pub fn analyze_project_imports_with(root: &Path, ignore: &[String]) -> AnalysisResult<ProjectImports> {
     let mut analyzer = ImportAnalyzer::new()?;
     let mut project = ProjectImports::new();
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !is_ignored_dir(e))
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-
-        // Skip directories
-        if path.is_dir() {
-            continue;
-        }
-
-        // Check if it's a supported file type
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if SourceLanguage::from_extension(ext).is_none() {
-            continue;
-        }
-
-        match analyzer.analyze_file(path) {
+    for path in source_files(root, ignore)? {
+        match analyzer.analyze_file(&path) {
             Ok(imports) => {
                 let file_path = path.display().to_string();
                 project.add_file_imports(&file_path, imports);
             }
             Err(e) => {
-                // Log error but continue with other files
-                eprintln!("Warning: Failed to analyze {}: {}", path.display(), e);
+                // Continue with other files; the caller decides how to report it
+                project
+                    .unreadable_files
+                    .push((path.display().to_string(), e.to_string()));
             }
         }
     }
@@ -678,16 +724,89 @@ pub fn analyze_project_imports(root: &Path) -> AnalysisResult<ProjectImports> {
     Ok(project)
 }
 
+/// Lists the JavaScript/TypeScript source files of a project, sorted.
+///
+/// Dependencies, build output and VCS metadata are always skipped, and so
+/// is anything matched by the project's `.gitignore` files (whether or not
+/// it is a git checkout) or by one of the `ignore` globs. Globs use
+/// gitignore syntax and are matched against paths relative to `root`, so
+/// `**/fixtures/**` skips every `fixtures` directory.
+///
+/// # Errors
+///
+/// Returns [`AnalysisError::InvalidIgnorePattern`] if a glob cannot be
+/// parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use codescope::analysis::exports::source_files;
+///
+/// let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cra-app");
+/// let files = source_files(&root, &["src/report*".to_string()]).unwrap();
+/// assert!(files.iter().all(|f| f.starts_with(root.join("src"))));
+/// assert!(!files.iter().any(|f| f.ends_with("reportWebVitals.js")));
+/// ```
+pub fn source_files(root: &Path, ignore: &[String]) -> AnalysisResult<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = project_walk(root, ignore)?
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .map(|e| e.into_path())
+        // Yarn's PnP runtime is generated code, not project source
+        .filter(|path| !is_pnp_runtime(path))
+        .filter(|path| {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            SourceLanguage::from_extension(ext).is_some()
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Returns a walk over `root` that skips what [`source_files`] skips:
+/// dependencies, build output, VCS metadata, gitignored paths and the
+/// `ignore` globs.
+pub(crate) fn project_walk(root: &Path, ignore: &[String]) -> AnalysisResult<WalkBuilder> {
+    let mut overrides = OverrideBuilder::new(root);
+    for pattern in ignore {
+        // Override globs whitelist; a leading `!` turns them into ignores
+        overrides
+            .add(&format!("!{}", pattern))
+            .map_err(|e| AnalysisError::InvalidIgnorePattern(format!("{}: {}", pattern, e)))?;
+    }
+    let overrides = overrides
+        .build()
+        .map_err(|e| AnalysisError::InvalidIgnorePattern(e.to_string()))?;
+
+    let mut walk = WalkBuilder::new(root);
+    walk.hidden(false)
+        .require_git(false)
+        .git_global(false)
+        .overrides(overrides)
+        .filter_entry(|e| !is_ignored_dir(e));
+    Ok(walk)
+}
+
 /// Check if a directory should be ignored during traversal.
-fn is_ignored_dir(entry: &walkdir::DirEntry) -> bool {
-    if !entry.file_type().is_dir() {
+fn is_ignored_dir(entry: &ignore::DirEntry) -> bool {
+    if !entry.file_type().is_some_and(|t| t.is_dir()) {
         return false;
     }
 
     let name = entry.file_name().to_string_lossy();
     matches!(
         name.as_ref(),
-        "node_modules" | ".git" | "dist" | "build" | ".next" | "coverage" | ".turbo"
+        "node_modules" | ".git" | "dist" | "build" | ".next" | "coverage" | ".turbo" | ".yarn"
+    )
+}
+
+/// Check if a file is part of the Yarn Plug'n'Play runtime.
+fn is_pnp_runtime(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|n| n.to_str()),
+        Some(".pnp.cjs" | ".pnp.js" | ".pnp.loader.mjs")
     )
 }
 
@@ -939,6 +1058,29 @@ mod tests {
         assert_eq!(react_usage.importing_files.len(), 2);
     }
 
+    #[test]
+    fn test_import_sites() {
+        let mut project = ProjectImports::new();
+        let import = |source: &str, line: usize| Import {
+            source: source.to_string(),
+            specifiers: vec![ImportSpecifier::SideEffect],
+            kind: ImportKind::ES6,
+            line,
+        };
+        project.add_file_imports("src/b.js", vec![import("lodash/fp", 9), import("lodash", 2), import("./util", 3)]);
+        project.add_file_imports("src/a.js", vec![import("lodash", 1), import("react", 4)]);
+
+        let sites = project.import_sites();
+        assert_eq!(sites.keys().collect::<Vec<_>>(), vec!["lodash", "react"]);
+        assert_eq!(
+            sites["lodash"],
+            vec![
+                ImportSite { file: "src/a.js".to_string(), lines: vec![1] },
+                ImportSite { file: "src/b.js".to_string(), lines: vec![2, 9] },
+            ]
+        );
+    }
+
     #[test]
     fn test_utilization_percentage() {
         let mut usage = PackageUsage::default();
@@ -973,6 +1115,26 @@ mod tests {
         assert!(!usage.is_potentially_underutilized(10));
     }
 
+    #[test]
+    fn test_project_utilization() {
+        let mut project = ProjectImports::new();
+        let mut lodash = PackageUsage::default();
+        lodash.named_imports.insert("debounce".to_string());
+        project.package_usage.insert("lodash".to_string(), lodash);
+        project.package_usage.insert("dayjs".to_string(), PackageUsage::default());
+        let react = PackageUsage {
+            uses_namespace: true,
+            ..Default::default()
+        };
+        project.package_usage.insert("react".to_string(), react);
+
+        let counts = HashMap::from([("lodash".to_string(), 4)]);
+        let utilization = project.utilization(&counts);
+        assert_eq!(utilization.get("lodash"), Some(&25.0));
+        assert_eq!(utilization.get("react"), Some(&100.0), "namespaces need no count");
+        assert!(!utilization.contains_key("dayjs"));
+    }
+
     // ===== Multiple Import Statements =====
 
     #[test]
@@ -1005,4 +1167,81 @@ import './styles.css';
         assert_eq!(imports[0].source, "lodash");
         assert_eq!(imports[0].kind, ImportKind::DynamicImport);
     }
+
+    // ===== Project Traversal Tests =====
+
+    #[test]
+    fn test_project_walk_skips_pnp_files() {
+        let dir = std::env::temp_dir().join(format!("codescope-pnp-walk-{}", std::process::id()));
+        let unplugged = dir.join(".yarn/unplugged/esbuild-npm-0.19.0/node_modules/esbuild");
+        fs::create_dir_all(&unplugged).unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/index.js"), "import React from 'react';\n").unwrap();
+        fs::write(dir.join(".pnp.cjs"), "const fs = require('fs');\n").unwrap();
+        fs::write(unplugged.join("main.js"), "const esbuild = require('esbuild-linux-64');\n").unwrap();
+
+        let project = analyze_project_imports(&dir).unwrap();
+        let packages: Vec<_> = project.package_usage.keys().map(String::as_str).collect();
+        assert_eq!(packages, vec!["react"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_source_files_respect_gitignore_and_globs() {
+        let dir = std::env::temp_dir().join(format!("codescope-ignore-walk-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/generated")).unwrap();
+        fs::create_dir_all(dir.join("test/fixtures")).unwrap();
+        fs::create_dir_all(dir.join("node_modules/react")).unwrap();
+        fs::create_dir_all(dir.join(".storybook")).unwrap();
+        fs::write(dir.join(".gitignore"), "src/generated/\n*.min.js\n").unwrap();
+        for file in [
+            "src/index.ts",
+            "src/generated/api.ts",
+            "src/vendor.min.js",
+            "test/fixtures/app.js",
+            "test/index.test.js",
+            "node_modules/react/index.js",
+            ".storybook/main.js",
+        ] {
+            fs::write(dir.join(file), "import React from 'react';\n").unwrap();
+        }
+
+        let relative = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(&dir).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+        assert_eq!(
+            relative(source_files(&dir, &[]).unwrap()),
+            vec![".storybook/main.js", "src/index.ts", "test/fixtures/app.js", "test/index.test.js"]
+        );
+        assert_eq!(
+            relative(source_files(&dir, &["**/fixtures/**".to_string(), "*.test.js".to_string()]).unwrap()),
+            vec![".storybook/main.js", "src/index.ts"]
+        );
+        assert!(matches!(
+            source_files(&dir, &["src/[".to_string()]),
+            Err(AnalysisError::InvalidIgnorePattern(_))
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_project_walk_records_unreadable_files() {
+        let dir = std::env::temp_dir().join(format!("codescope-unreadable-walk-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/index.js"), "import React from 'react';\n").unwrap();
+        fs::write(dir.join("src/latin1.js"), b"// caf\xe9\nimport x from 'lodash';\n").unwrap();
+
+        let project = analyze_project_imports(&dir).unwrap();
+        assert!(project.package_usage.contains_key("react"));
+        assert!(!project.package_usage.contains_key("lodash"));
+        assert_eq!(project.unreadable_files.len(), 1);
+        assert!(project.unreadable_files[0].0.ends_with("latin1.js"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,368 @@
+//! What installing each package costs.
+//!
+//! Bundle sizes measure what ships to the browser; install time in CI is a
+//! separate budget driven by how many bytes and files land on disk and by
+//! packages that run scripts or compile native addons while installing.
+//! This module reads each installed package to report all of those.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::webpack::format_size;
+use crate::graph::DependencyGraph;
+use crate::parser::installed::{PackageLocation, PackageStore};
+
+/// Lifecycle scripts npm runs when installing a package.
+const INSTALL_SCRIPTS: [&str; 3] = ["preinstall", "install", "postinstall"];
+
+/// Commands that build or download a native addon.
+const NATIVE_BUILD_TOOLS: [&str; 4] = ["node-gyp", "prebuild-install", "node-pre-gyp", "cmake-js"];
+
+/// The fields of an installed package.json that affect installation.
+#[derive(Debug, Default, Deserialize)]
+struct InstallManifest {
+    #[serde(default)]
+    scripts: HashMap<String, String>,
+    #[serde(default)]
+    gypfile: bool,
+}
+
+/// The install cost of one package.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InstallFootprint {
+    /// Unpacked size in bytes
+    pub bytes: u64,
+    /// Number of files
+    pub files: usize,
+    /// Whether installing compiles or downloads a native addon
+    pub native: bool,
+    /// Install lifecycle scripts the package declares, in the order npm
+    /// runs them
+    pub install_scripts: Vec<String>,
+}
+
+impl InstallFootprint {
+    /// Measures an installed package.
+    ///
+    /// A package is native if it has a `binding.gyp` (which makes npm run
+    /// `node-gyp rebuild` even without an install script), sets `gypfile`,
+    /// or calls a native build tool from an install script.
+    pub fn read(location: &PackageLocation) -> io::Result<Self> {
+        let usage = location.disk_usage()?;
+        let manifest: InstallManifest = location
+            .read_to_string("package.json")
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let install_scripts: Vec<String> = INSTALL_SCRIPTS
+            .iter()
+            .filter(|name| manifest.scripts.contains_key(**name))
+            .map(|name| name.to_string())
+            .collect();
+        let builds_native = install_scripts
+            .iter()
+            .any(|name| NATIVE_BUILD_TOOLS.iter().any(|tool| manifest.scripts[name].contains(tool)));
+
+        Ok(Self {
+            bytes: usage.bytes,
+            files: usage.files,
+            native: manifest.gypfile || builds_native || location.read_to_string("binding.gyp").is_ok(),
+            install_scripts,
+        })
+    }
+
+    /// Returns true if installing the package runs code: a lifecycle
+    /// script or a native build.
+    pub fn runs_code(&self) -> bool {
+        self.native || !self.install_scripts.is_empty()
+    }
+}
+
+/// The install cost of a package in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageFootprint {
+    /// Package name
+    pub name: String,
+    /// Version from the graph
+    pub version: String,
+    /// What installing it costs
+    #[serde(flatten)]
+    pub footprint: InstallFootprint,
+}
+
+/// Order of the packages in a footprint report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FootprintSort {
+    /// Largest on disk first
+    #[default]
+    Size,
+    /// Most files first
+    Files,
+    /// Packages running install code first, then by size
+    Scripts,
+    /// Alphabetical
+    Name,
+}
+
+impl FootprintSort {
+    /// Cycle to the next sort order
+    pub fn cycle(&self) -> Self {
+        match self {
+            FootprintSort::Size => FootprintSort::Files,
+            FootprintSort::Files => FootprintSort::Scripts,
+            FootprintSort::Scripts => FootprintSort::Name,
+            FootprintSort::Name => FootprintSort::Size,
+        }
+    }
+
+    /// Get a short display name for the sort order
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FootprintSort::Size => "Size ↓",
+            FootprintSort::Files => "Files ↓",
+            FootprintSort::Scripts => "Scripts",
+            FootprintSort::Name => "A-Z",
+        }
+    }
+}
+
+impl fmt::Display for FootprintSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FootprintSort::Size => write!(f, "size"),
+            FootprintSort::Files => write!(f, "files"),
+            FootprintSort::Scripts => write!(f, "scripts"),
+            FootprintSort::Name => write!(f, "name"),
+        }
+    }
+}
+
+impl FromStr for FootprintSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "size" => Ok(FootprintSort::Size),
+            "files" => Ok(FootprintSort::Files),
+            "scripts" => Ok(FootprintSort::Scripts),
+            "name" => Ok(FootprintSort::Name),
+            other => Err(format!(
+                "unknown sort order '{}' (expected: size, files, scripts, name)",
+                other
+            )),
+        }
+    }
+}
+
+/// The install cost of every installed package of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FootprintReport {
+    /// Measured packages
+    pub packages: Vec<PackageFootprint>,
+    /// Packages of the graph that are not installed, sorted
+    pub missing: Vec<String>,
+}
+
+impl FootprintReport {
+    /// Measures every package of the graph found in the store.
+    ///
+    /// With `node_modules` installs only the top-level copy of a package is
+    /// measured. Packages are sorted by size.
+    pub fn from_graph(graph: &DependencyGraph, store: &PackageStore) -> Self {
+        let mut report = Self::default();
+        for node in graph.get_all_nodes() {
+            let name = node.package_name();
+            let measured = store
+                .locate(name, Some(&node.version))
+                .or_else(|| store.locate(name, None))
+                .and_then(|location| InstallFootprint::read(&location).ok());
+            match measured {
+                Some(footprint) => report.packages.push(PackageFootprint {
+                    name: node.name.clone(),
+                    version: node.version.clone(),
+                    footprint,
+                }),
+                None => report.missing.push(node.name.clone()),
+            }
+        }
+        report.missing.sort();
+        report.sort(FootprintSort::Size);
+        report
+    }
+
+    /// Reorders the packages.
+    pub fn sort(&mut self, order: FootprintSort) {
+        let by_size = |a: &PackageFootprint, b: &PackageFootprint| {
+            b.footprint.bytes.cmp(&a.footprint.bytes).then_with(|| a.name.cmp(&b.name))
+        };
+        match order {
+            FootprintSort::Size => self.packages.sort_by(by_size),
+            FootprintSort::Files => self.packages.sort_by(|a, b| {
+                b.footprint.files.cmp(&a.footprint.files).then_with(|| a.name.cmp(&b.name))
+            }),
+            FootprintSort::Scripts => self.packages.sort_by(|a, b| {
+                b.footprint.runs_code().cmp(&a.footprint.runs_code()).then_with(|| by_size(a, b))
+            }),
+            FootprintSort::Name => self.packages.sort_by(|a, b| a.name.cmp(&b.name)),
+        }
+    }
+
+    /// Returns the total unpacked size in bytes.
+    pub fn total_bytes(&self) -> u64 {
+        self.packages.iter().map(|p| p.footprint.bytes).sum()
+    }
+
+    /// Returns the total number of files.
+    pub fn total_files(&self) -> usize {
+        self.packages.iter().map(|p| p.footprint.files).sum()
+    }
+
+    /// Returns the packages that run code while installing.
+    pub fn packages_running_code(&self) -> Vec<&PackageFootprint> {
+        self.packages.iter().filter(|p| p.footprint.runs_code()).collect()
+    }
+
+    /// Returns the footprint of each package, keyed by name.
+    pub fn by_name(&self) -> BTreeMap<String, InstallFootprint> {
+        self.packages
+            .iter()
+            .map(|p| (p.name.clone(), p.footprint.clone()))
+            .collect()
+    }
+
+    /// Formats the report as a table of the first `top` packages in the
+    /// current order, followed by every package that runs install code.
+    pub fn format_report(&self, top: usize) -> String {
+        if self.packages.is_empty() {
+            return "💾 No installed packages found; run npm install first.\n".to_string();
+        }
+
+        let mut out = format!(
+            "💾 Install footprint: {} in {} files across {} packages\n\n",
+            format_size(self.total_bytes()),
+            self.total_files(),
+            self.packages.len()
+        );
+        out.push_str(&self.format_packages(top));
+        out
+    }
+
+    /// Formats the table of the first `top` packages, the packages running
+    /// install code and the number of missing packages.
+    pub(crate) fn format_packages(&self, top: usize) -> String {
+        let mut out = String::new();
+        let shown = &self.packages[..top.min(self.packages.len())];
+        let width = shown.iter().map(|p| p.name.len()).max().unwrap_or(0).max("Package".len());
+        out.push_str(&format!("  {:width$}  {:>10}  {:>7}  Install\n", "Package", "Size", "Files", width = width));
+        for package in shown {
+            out.push_str(&format!(
+                "  {:width$}  {:>10}  {:>7}  {}\n",
+                package.name,
+                format_size(package.footprint.bytes),
+                package.footprint.files,
+                install_label(&package.footprint),
+                width = width
+            ));
+        }
+        if shown.len() < self.packages.len() {
+            out.push_str(&format!("  ... and {} more\n", self.packages.len() - shown.len()));
+        }
+
+        let running = self.packages_running_code();
+        if running.is_empty() {
+            out.push_str("\n✅ No package runs install scripts or native builds.\n");
+        } else {
+            out.push_str(&format!("\n⚠️  {} package(s) run code while installing:\n", running.len()));
+            for package in running {
+                out.push_str(&format!("  {} ({})\n", package.name, install_label(&package.footprint)));
+            }
+        }
+        if !self.missing.is_empty() {
+            out.push_str(&format!("\n{} package(s) are not installed.\n", self.missing.len()));
+        }
+        out
+    }
+}
+
+/// Describes what a package runs while installing, e.g. `native, postinstall`.
+pub fn install_label(footprint: &InstallFootprint) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    if footprint.native {
+        parts.push("native");
+    }
+    parts.extend(footprint.install_scripts.iter().map(String::as_str));
+    if parts.is_empty() {
+        "-".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+    use crate::test_support::install;
+    use std::fs;
+
+    #[test]
+    fn test_footprint_report() {
+        let root = std::env::temp_dir().join(format!("codescope-footprint-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        install(&root, "lodash", "{}", &[("lodash.js", &"x".repeat(500))]);
+        install(
+            &root,
+            "esbuild",
+            r#"{"scripts": {"postinstall": "node install.js", "test": "jest"}}"#,
+            &[("install.js", "")],
+        );
+        install(&root, "bcrypt", r#"{"scripts": {"install": "node-pre-gyp install"}}"#, &[]);
+        install(&root, "fsevents", "{}", &[("binding.gyp", "{}")]);
+
+        let mut graph = DependencyGraph::new();
+        for name in ["lodash", "esbuild", "bcrypt", "fsevents", "missing"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        let store = PackageStore::NodeModules(root.join("node_modules"));
+        let mut report = FootprintReport::from_graph(&graph, &store);
+
+        let names = |report: &FootprintReport| report.packages.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&report), vec!["lodash", "esbuild", "bcrypt", "fsevents"]);
+        assert_eq!(report.missing, vec!["missing"]);
+        assert_eq!(report.total_files(), 7);
+
+        let footprints = report.by_name();
+        assert_eq!(footprints["esbuild"].install_scripts, vec!["postinstall"]);
+        assert!(!footprints["esbuild"].native);
+        assert!(footprints["bcrypt"].native, "native build tool in an install script");
+        assert!(footprints["fsevents"].native, "binding.gyp");
+        assert!(!footprints["lodash"].runs_code());
+
+        report.sort(FootprintSort::Scripts);
+        assert_eq!(names(&report), vec!["esbuild", "bcrypt", "fsevents", "lodash"]);
+
+        let text = report.format_report(2);
+        assert!(text.contains("across 4 packages"));
+        assert!(text.contains("  ... and 2 more\n"));
+        assert!(text.contains("3 package(s) run code while installing:\n  esbuild (postinstall)\n  bcrypt (native, install)\n  fsevents (native)\n"));
+        assert!(text.contains("1 package(s) are not installed."));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_sort_order_cycle() {
+        let mut order = FootprintSort::default();
+        for expected in ["files", "scripts", "name", "size"] {
+            order = order.cycle();
+            assert_eq!(order.to_string(), expected);
+            assert_eq!(expected.parse::<FootprintSort>().unwrap(), order);
+        }
+        assert!("bytes".parse::<FootprintSort>().is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Per-group aggregation of package counts and sizes.
+//!
+//! Groups are defined in the project configuration (see
+//! [`crate::config::GroupConfig`]) and tag packages by area, such as the UI
+//! framework or build tooling. A package may belong to several groups.
+
+use crate::config::{GroupConfig, ProjectConfig};
+use crate::graph::{DependencyGraph, DependencyNode};
+
+/// Aggregated figures for one package group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupSummary {
+    /// Group name from the configuration
+    pub name: String,
+    /// Names of the packages in the group, sorted
+    pub packages: Vec<String>,
+    /// Sum of the known bundle sizes in bytes
+    pub bundle_size: u64,
+    /// Number of packages with a known bundle size
+    pub sized_packages: usize,
+    /// Size budget in KB, if set
+    pub max_size_kb: Option<u64>,
+    /// Package count budget, if set
+    pub max_count: Option<usize>,
+}
+
+impl GroupSummary {
+    /// Returns the number of packages in the group.
+    pub fn count(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns true if any package in the group has a known bundle size.
+    pub fn has_sizes(&self) -> bool {
+        self.sized_packages > 0
+    }
+
+    /// Returns true if the group sets a size or count budget.
+    pub fn has_budget(&self) -> bool {
+        self.max_size_kb.is_some() || self.max_count.is_some()
+    }
+
+    /// Returns true if the known bundle size exceeds the size budget.
+    pub fn over_size_budget(&self) -> bool {
+        self.max_size_kb.is_some_and(|max| self.bundle_size > max * 1024)
+    }
+
+    /// Returns true if the group has more packages than its count budget.
+    pub fn over_count_budget(&self) -> bool {
+        self.max_count.is_some_and(|max| self.count() > max)
+    }
+
+    /// Returns true if any budget of the group is exceeded.
+    pub fn over_budget(&self) -> bool {
+        self.over_size_budget() || self.over_count_budget()
+    }
+}
+
+/// Aggregates the packages of the graph into the configured groups.
+///
+/// # Arguments
+///
+/// * `groups` - Group definitions keyed by name, as in the configuration
+/// * `graph` - The dependency graph, with bundle sizes applied if available
+///
+/// # Returns
+///
+/// One summary per group, in the order of `groups`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use codescope::analysis::summarize_groups;
+/// use codescope::config::GroupConfig;
+/// use codescope::graph::{DependencyGraph, DependencyType};
+///
+/// let mut graph = DependencyGraph::new();
+/// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+/// graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+///
+/// let mut groups = BTreeMap::new();
+/// groups.insert("ui".to_string(), GroupConfig {
+///     packages: vec!["react*".to_string()],
+///     max_count: Some(1),
+///     ..Default::default()
+/// });
+///
+/// let summaries = summarize_groups(&groups, &graph);
+/// assert_eq!(summaries[0].count(), 2);
+/// assert!(summaries[0].over_count_budget());
+/// ```
+pub fn summarize_groups<'a>(
+    groups: impl IntoIterator<Item = (&'a String, &'a GroupConfig)>,
+    graph: &DependencyGraph,
+) -> Vec<GroupSummary> {
+    summarize(groups, graph, |_| true)
+}
+
+/// Aggregates the packages of the graph into the groups of a project
+/// configuration, counting only the dependency types its preset budgets.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::summarize_budgeted_groups;
+/// use codescope::config::ProjectConfig;
+/// use codescope::graph::{DependencyGraph, DependencyType};
+///
+/// let mut graph = DependencyGraph::new();
+/// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+/// graph.add_dependency("react-test-renderer", "18.2.0", DependencyType::Development);
+///
+/// let config = ProjectConfig::parse(
+///     r#"{"preset": "vite-react", "groups": {"ui": {"packages": ["react*"]}}}"#,
+/// ).unwrap();
+/// assert_eq!(summarize_budgeted_groups(&config, &graph)[0].packages, vec!["react"]);
+/// ```
+pub fn summarize_budgeted_groups(config: &ProjectConfig, graph: &DependencyGraph) -> Vec<GroupSummary> {
+    summarize(&config.groups, graph, |node| config.counts_toward_budget(node.dep_type.into()))
+}
+
+fn summarize<'a>(
+    groups: impl IntoIterator<Item = (&'a String, &'a GroupConfig)>,
+    graph: &DependencyGraph,
+    counts: impl Fn(&DependencyNode) -> bool,
+) -> Vec<GroupSummary> {
+    let nodes: Vec<_> = graph.get_all_nodes().into_iter().filter(|node| counts(node)).collect();
+
+    groups
+        .into_iter()
+        .map(|(name, group)| {
+            let members: Vec<_> = nodes.iter().filter(|node| group.matches(&node.name)).collect();
+            let mut packages: Vec<String> = members.iter().map(|node| node.name.clone()).collect();
+            packages.sort();
+
+            GroupSummary {
+                name: name.clone(),
+                packages,
+                bundle_size: members.iter().filter_map(|node| node.bundle_size).sum(),
+                sized_packages: members.iter().filter(|node| node.bundle_size.is_some()).count(),
+                max_size_kb: group.max_size_kb,
+                max_count: group.max_count,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProjectConfig;
+    use crate::graph::DependencyType;
+    use std::collections::HashMap;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+        graph.add_dependency("webpack", "5.0.0", DependencyType::Development);
+        graph.add_dependency("@babel/core", "7.0.0", DependencyType::Development);
+
+        let mut sizes = HashMap::new();
+        sizes.insert("react".to_string(), (6_000_u64, 1_usize));
+        sizes.insert("react-dom".to_string(), (130_000_u64, 4_usize));
+        graph.apply_bundle_sizes(&sizes);
+        graph
+    }
+
+    #[test]
+    fn test_summarize_groups() {
+        let config = ProjectConfig::parse(
+            r#"{"groups": {
+                "ui-framework": { "packages": ["react", "react-dom"], "maxSizeKb": 100 },
+                "build-tooling": { "packages": ["webpack", "@babel/*"], "maxCount": 2 },
+                "polyfills": { "packages": ["core-js"] }
+            }}"#,
+        )
+        .unwrap();
+        let summaries = summarize_groups(&config.groups, &graph());
+        let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["build-tooling", "polyfills", "ui-framework"]);
+
+        let tooling = &summaries[0];
+        assert_eq!(tooling.packages, vec!["@babel/core", "webpack"]);
+        assert!(!tooling.has_sizes());
+        assert!(!tooling.over_budget());
+
+        assert_eq!(summaries[1].count(), 0);
+
+        let ui = &summaries[2];
+        assert_eq!(ui.bundle_size, 136_000);
+        assert_eq!(ui.sized_packages, 2);
+        assert!(ui.over_size_budget());
+        assert!(!ui.over_count_budget());
+    }
+}
@@ -0,0 +1,272 @@
+//! Why a package was hoisted or nested.
+//!
+//! npm installs one copy of each package at the top of `node_modules` and
+//! nests further copies under the packages that need a version the top
+//! copy does not satisfy. Which version wins the top slot is decided by
+//! the packages requiring it: while one of them pins a range the other
+//! versions fall outside of, those versions cannot be hoisted and stay
+//! duplicated. This module reads the copies of each package from the
+//! lockfile, who each copy was installed for, and those hoist blockers.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::parser::lockfile::{install_name, is_installed_path, PackageLock};
+use crate::parser::range::VersionRange;
+
+/// A package, or the project, requiring a copy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Requester {
+    /// Package name, or the project or workspace name for their folders
+    pub name: String,
+    /// Range it requires
+    pub range: String,
+}
+
+impl Requester {
+    /// Returns true if the requested range accepts `version`; unparsable
+    /// ranges accept nothing.
+    pub fn accepts(&self, version: &str) -> bool {
+        VersionRange::parse(&self.range).is_some_and(|range| range.matches(version))
+    }
+}
+
+/// One installed copy of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledCopy {
+    /// Install path in the lockfile, e.g. `node_modules/a/node_modules/b`
+    pub path: String,
+    /// Installed version, if the lockfile records it
+    pub version: Option<String>,
+    /// Packages this copy is loaded for, sorted by name
+    pub requesters: Vec<Requester>,
+}
+
+/// The copies of a package installed more than once, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HoistingExplanation {
+    /// Package name
+    pub package: String,
+    /// The copy at the top of `node_modules`, if any
+    pub hoisted: Option<InstalledCopy>,
+    /// The copies nested under other packages, sorted by path
+    pub nested: Vec<InstalledCopy>,
+}
+
+impl HoistingExplanation {
+    /// Returns the requesters of a nested copy whose range rejects the
+    /// hoisted version: the reason the copy could not use it.
+    pub fn rejecting_hoisted<'a>(&self, copy: &'a InstalledCopy) -> Vec<&'a Requester> {
+        let Some(version) = self.hoisted.as_ref().and_then(|hoisted| hoisted.version.as_deref()) else {
+            return Vec::new();
+        };
+        copy.requesters.iter().filter(|r| !r.accepts(version)).collect()
+    }
+
+    /// Returns the requesters of the hoisted copy whose range rejects the
+    /// version of a nested copy.
+    ///
+    /// They keep the hoisted version at the top: the nested version could
+    /// only take its place, and the duplicate go away, once they accept it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::hoisting::explain_hoisting;
+    /// use codescope::parser::lockfile::PackageLock;
+    ///
+    /// let lock = PackageLock::parse(r#"{
+    ///   "lockfileVersion": 3,
+    ///   "packages": {
+    ///     "": { "name": "app", "dependencies": { "lodash": "^4.17.0", "old-lib": "^1.0.0" } },
+    ///     "node_modules/lodash": { "version": "4.17.21" },
+    ///     "node_modules/old-lib": { "version": "1.0.0", "dependencies": { "lodash": "^3.0.0" } },
+    ///     "node_modules/old-lib/node_modules/lodash": { "version": "3.10.1" }
+    ///   }
+    /// }"#).unwrap();
+    ///
+    /// let lodash = &explain_hoisting(&lock)["lodash"];
+    /// let blockers = lodash.blockers(&lodash.nested[0]);
+    /// assert_eq!(blockers[0].name, "app");
+    /// ```
+    pub fn blockers<'a>(&'a self, copy: &InstalledCopy) -> Vec<&'a Requester> {
+        let (Some(hoisted), Some(version)) = (&self.hoisted, copy.version.as_deref()) else {
+            return Vec::new();
+        };
+        hoisted.requesters.iter().filter(|r| !r.accepts(version)).collect()
+    }
+
+    /// Returns the number of installed copies.
+    pub fn copy_count(&self) -> usize {
+        self.nested.len() + usize::from(self.hoisted.is_some())
+    }
+
+    /// Formats the copies, who they were installed for and the blockers,
+    /// indented by two spaces.
+    pub fn format_lines(&self) -> Vec<String> {
+        let version = |copy: &InstalledCopy| copy.version.clone().unwrap_or_else(|| "?".to_string());
+        let mut lines = Vec::new();
+        if let Some(hoisted) = &self.hoisted {
+            lines.push(format!("  {} hoisted to {}", version(hoisted), hoisted.path));
+            lines.push(format!("    required by {}", format_requesters(&hoisted.requesters)));
+        }
+        for copy in &self.nested {
+            lines.push(format!("  {} nested in {}", version(copy), copy.path));
+            let rejecting = self.rejecting_hoisted(copy);
+            match self.hoisted.as_ref().and_then(|hoisted| hoisted.version.as_deref()) {
+                Some(hoisted) if !rejecting.is_empty() => lines.push(format!(
+                    "    required by {}; {} is out of range",
+                    format_requesters(&copy.requesters),
+                    hoisted
+                )),
+                _ => lines.push(format!("    required by {}", format_requesters(&copy.requesters))),
+            }
+            let blockers = self.blockers(copy);
+            if !blockers.is_empty() {
+                let blockers: Vec<Requester> = blockers.into_iter().cloned().collect();
+                lines.push(format!(
+                    "    hoist blocked by {}; {} is out of range",
+                    format_requesters(&blockers),
+                    version(copy)
+                ));
+            }
+        }
+        lines
+    }
+}
+
+/// Explains every package the lockfile installs more than once.
+///
+/// Each copy lists the packages that load it, following Node's lookup
+/// through the enclosing `node_modules` folders, with the range they
+/// require. The project and workspace folders require their development
+/// dependencies too.
+///
+/// # Returns
+///
+/// The explanations keyed by package install name.
+pub fn explain_hoisting(lock: &PackageLock) -> BTreeMap<String, HoistingExplanation> {
+    let mut requesters: BTreeMap<&str, Vec<Requester>> = BTreeMap::new();
+    for (path, entry) in &lock.packages {
+        if entry.link {
+            continue;
+        }
+        let dev = (!is_installed_path(path)).then_some(&entry.dev_dependencies);
+        let ranges = entry
+            .dependencies
+            .iter()
+            .chain(&entry.optional_dependencies)
+            .chain(&entry.peer_dependencies)
+            .chain(dev.into_iter().flatten());
+        for (name, range) in ranges {
+            if let Some(copy) = lock.resolve(path, name) {
+                requesters.entry(copy).or_default().push(Requester {
+                    name: requester_name(lock, path),
+                    range: range.clone(),
+                });
+            }
+        }
+    }
+
+    let mut explanations: BTreeMap<String, HoistingExplanation> = BTreeMap::new();
+    for (path, entry) in lock.packages.iter().filter(|(path, _)| is_installed_path(path)) {
+        let mut copy_requesters = requesters.remove(path.as_str()).unwrap_or_default();
+        copy_requesters.sort_by(|a, b| (&a.name, &a.range).cmp(&(&b.name, &b.range)));
+        copy_requesters.dedup();
+        let copy = InstalledCopy {
+            path: path.clone(),
+            version: entry.version.clone(),
+            requesters: copy_requesters,
+        };
+
+        let package = install_name(path);
+        let explanation = explanations
+            .entry(package.to_string())
+            .or_insert_with(|| HoistingExplanation {
+                package: package.to_string(),
+                hoisted: None,
+                nested: Vec::new(),
+            });
+        if path.strip_prefix("node_modules/") == Some(package) {
+            explanation.hoisted = Some(copy);
+        } else {
+            explanation.nested.push(copy);
+        }
+    }
+
+    explanations.retain(|_, explanation| explanation.copy_count() > 1);
+    explanations
+}
+
+/// Names the package or folder at an install path.
+fn requester_name(lock: &PackageLock, path: &str) -> String {
+    if is_installed_path(path) {
+        return install_name(path).to_string();
+    }
+    match lock.packages.get(path).and_then(|entry| entry.name.clone()) {
+        Some(name) => name,
+        None if path.is_empty() => "(root)".to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Lists requesters as `name (range)`, comma-separated.
+fn format_requesters(requesters: &[Requester]) -> String {
+    if requesters.is_empty() {
+        return "nothing".to_string();
+    }
+    requesters
+        .iter()
+        .map(|r| format!("{} ({})", r.name, r.range))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_hoisting() {
+        let lock = PackageLock::parse(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app", "dependencies": { "lodash": "^4.17.0", "old-lib": "^1.0.0", "react": "^18.0.0" } },
+    "node_modules/lodash": { "version": "4.17.21" },
+    "node_modules/old-lib": { "version": "1.0.0", "dependencies": { "lodash": "^3.0.0" } },
+    "node_modules/old-lib/node_modules/lodash": { "version": "3.10.1" },
+    "node_modules/react": { "version": "18.2.0", "dependencies": { "lodash": "*" } }
+  }
+}"#,
+        )
+        .unwrap();
+
+        let explanations = explain_hoisting(&lock);
+        assert_eq!(explanations.keys().collect::<Vec<_>>(), vec!["lodash"], "single copies need no explanation");
+
+        let lodash = &explanations["lodash"];
+        assert_eq!(lodash.copy_count(), 2);
+        let hoisted = lodash.hoisted.as_ref().unwrap();
+        let names: Vec<&str> = hoisted.requesters.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "react"]);
+
+        let nested = &lodash.nested[0];
+        assert_eq!(nested.path, "node_modules/old-lib/node_modules/lodash");
+        assert_eq!(lodash.rejecting_hoisted(nested)[0].name, "old-lib");
+        let blockers: Vec<&str> = lodash.blockers(nested).iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(blockers, vec!["app"], "react accepts any version");
+
+        assert_eq!(
+            lodash.format_lines(),
+            vec![
+                "  4.17.21 hoisted to node_modules/lodash",
+                "    required by app (^4.17.0), react (*)",
+                "  3.10.1 nested in node_modules/old-lib/node_modules/lodash",
+                "    required by old-lib (^3.0.0); 4.17.21 is out of range",
+                "    hoist blocked by app (^4.17.0); 3.10.1 is out of range",
+            ]
+        );
+    }
+}
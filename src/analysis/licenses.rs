@@ -0,0 +1,369 @@
+//! License texts and third-party notices.
+//!
+//! Most licenses, MIT included, require redistributors to ship the
+//! copyright notice and license text along with the software. This module
+//! collects the declared license and the license files (`LICENSE`,
+//! `LICENCE`, `COPYING`, `NOTICE`) of every installed package, and renders
+//! them as a combined THIRD-PARTY-NOTICES file in text or HTML.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::graph::DependencyGraph;
+use crate::parser::{PackageLocation, PackageStore};
+
+/// File name prefixes that hold license texts, in upper case.
+const LICENSE_FILE_PREFIXES: [&str; 4] = ["LICENSE", "LICENCE", "COPYING", "NOTICE"];
+
+/// A license file shipped with a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseText {
+    /// File name relative to the package root
+    pub file: String,
+    /// File contents
+    pub text: String,
+}
+
+/// The license information of an installed package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+    /// Package name
+    pub name: String,
+    /// Installed version
+    pub version: String,
+    /// Declared license, usually an SPDX expression
+    pub license: Option<String>,
+    /// License files found in the package root
+    pub texts: Vec<LicenseText>,
+}
+
+impl PackageLicense {
+    /// Reads the license information of an installed package.
+    ///
+    /// `version` is used if the manifest does not name one. Returns `None`
+    /// if the manifest cannot be read.
+    pub fn read(name: &str, version: &str, location: &PackageLocation) -> Option<Self> {
+        let manifest: Value = serde_json::from_str(&location.read_to_string("package.json").ok()?).ok()?;
+        let texts = location
+            .root_files()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|file| is_license_file(file))
+            .filter_map(|file| {
+                let text = location.read_to_string(&file).ok()?;
+                Some(LicenseText { file, text })
+            })
+            .collect();
+        Some(Self {
+            name: name.to_string(),
+            version: manifest
+                .get("version")
+                .and_then(Value::as_str)
+                .unwrap_or(version)
+                .to_string(),
+            license: declared_license(&manifest),
+            texts,
+        })
+    }
+
+    /// Returns the declared license, or `UNKNOWN`.
+    pub fn license_label(&self) -> &str {
+        self.license.as_deref().unwrap_or("UNKNOWN")
+    }
+}
+
+/// Returns true if a file name marks a license file, ignoring case and
+/// extension.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::licenses::is_license_file;
+///
+/// assert!(is_license_file("LICENSE"));
+/// assert!(is_license_file("license.md"));
+/// assert!(is_license_file("LICENCE-MIT.txt"));
+/// assert!(!is_license_file("README.md"));
+/// ```
+pub fn is_license_file(file: &str) -> bool {
+    let upper = file.to_uppercase();
+    LICENSE_FILE_PREFIXES.iter().any(|prefix| upper.starts_with(prefix))
+}
+
+/// Reads the declared license of a manifest.
+///
+/// Besides the SPDX string form, this accepts the deprecated
+/// `{"type": "MIT"}` object and `"licenses"` list; several licenses in the
+/// list are joined with `OR`, as npm did.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::licenses::declared_license;
+/// use serde_json::json;
+///
+/// assert_eq!(declared_license(&json!({"license": "MIT"})).as_deref(), Some("MIT"));
+/// assert_eq!(declared_license(&json!({"license": {"type": "ISC"}})).as_deref(), Some("ISC"));
+/// assert_eq!(
+///     declared_license(&json!({"licenses": [{"type": "MIT"}, {"type": "Apache-2.0"}]})).as_deref(),
+///     Some("MIT OR Apache-2.0")
+/// );
+/// assert_eq!(declared_license(&json!({})), None);
+/// ```
+pub fn declared_license(manifest: &Value) -> Option<String> {
+    let license_type = |value: &Value| -> Option<String> {
+        let license = match value {
+            Value::String(s) => s.as_str(),
+            Value::Object(object) => object.get("type")?.as_str()?,
+            _ => return None,
+        };
+        Some(license.trim().to_string()).filter(|l| !l.is_empty())
+    };
+    if let Some(license) = manifest.get("license").and_then(license_type) {
+        return Some(license);
+    }
+    let licenses: Vec<String> = manifest
+        .get("licenses")?
+        .as_array()?
+        .iter()
+        .filter_map(license_type)
+        .collect();
+    match licenses.len() {
+        0 => None,
+        1 => licenses.into_iter().next(),
+        _ => Some(licenses.join(" OR ")),
+    }
+}
+
+/// The licenses of every installed package of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LicenseReport {
+    /// Installed packages, sorted by name
+    pub packages: Vec<PackageLicense>,
+    /// Packages of the graph that are not installed, sorted
+    pub missing: Vec<String>,
+}
+
+impl LicenseReport {
+    /// Reads the licenses of every package of the graph found in the store.
+    ///
+    /// With `node_modules` installs only the top-level copy of a package is
+    /// read.
+    pub fn from_graph(graph: &DependencyGraph, store: &PackageStore) -> Self {
+        let mut report = Self::default();
+        for node in graph.get_all_nodes() {
+            let name = node.package_name();
+            let read = store
+                .locate(name, Some(&node.version))
+                .or_else(|| store.locate(name, None))
+                .and_then(|location| PackageLicense::read(&node.name, &node.version, &location));
+            match read {
+                Some(license) => report.packages.push(license),
+                None => report.missing.push(node.name.clone()),
+            }
+        }
+        report.packages.sort_by(|a, b| a.name.cmp(&b.name));
+        report.missing.sort();
+        report
+    }
+
+    /// Returns the declared license of each package that declares one,
+    /// keyed by name.
+    pub fn by_name(&self) -> BTreeMap<String, String> {
+        self.packages
+            .iter()
+            .filter_map(|p| Some((p.name.clone(), p.license.clone()?)))
+            .collect()
+    }
+
+    /// Returns the packages that ship no license file.
+    pub fn without_text(&self) -> Vec<&PackageLicense> {
+        self.packages.iter().filter(|p| p.texts.is_empty()).collect()
+    }
+
+    /// Formats the report as a table of packages, their declared license
+    /// and their license files.
+    pub fn format_table(&self) -> String {
+        if self.packages.is_empty() {
+            return "📜 No installed packages found; run npm install first.\n".to_string();
+        }
+
+        let mut out = format!("📜 Licenses of {} packages\n\n", self.packages.len());
+        let width = self
+            .packages
+            .iter()
+            .map(|p| p.name.len() + p.version.len() + 1)
+            .max()
+            .unwrap_or(0)
+            .max("Package".len());
+        let license_width = self
+            .packages
+            .iter()
+            .map(|p| p.license_label().len())
+            .max()
+            .unwrap_or(0)
+            .max("License".len());
+        out.push_str(&format!(
+            "  {:width$}  {:license_width$}  Files\n",
+            "Package",
+            "License",
+            width = width,
+            license_width = license_width
+        ));
+        for package in &self.packages {
+            let files: Vec<&str> = package.texts.iter().map(|t| t.file.as_str()).collect();
+            out.push_str(&format!(
+                "  {:width$}  {:license_width$}  {}\n",
+                format!("{}@{}", package.name, package.version),
+                package.license_label(),
+                if files.is_empty() { "-".to_string() } else { files.join(", ") },
+                width = width,
+                license_width = license_width
+            ));
+        }
+
+        let without_text = self.without_text();
+        if !without_text.is_empty() {
+            out.push_str(&format!(
+                "\n⚠️  {} package(s) ship no license file; their notices only name the license.\n",
+                without_text.len()
+            ));
+        }
+        if !self.missing.is_empty() {
+            out.push_str(&format!("\n{} package(s) are not installed.\n", self.missing.len()));
+        }
+        out
+    }
+
+    /// Renders the combined notices as plain text.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Name of the project the notices are for
+    pub fn notices_text(&self, project: &str) -> String {
+        let rule = "=".repeat(80);
+        let mut out = format!(
+            "THIRD-PARTY SOFTWARE NOTICES\n\n{} includes the following {} third-party packages.\n",
+            project,
+            self.packages.len()
+        );
+        for package in &self.packages {
+            out.push_str(&format!(
+                "\n{}\n{} {}\nLicense: {}\n{}\n",
+                rule,
+                package.name,
+                package.version,
+                package.license_label(),
+                rule
+            ));
+            if package.texts.is_empty() {
+                out.push_str("\nNo license file is included in this package.\n");
+            }
+            for text in &package.texts {
+                out.push_str(&format!("\n--- {} ---\n\n{}\n", text.file, text.text.trim_end()));
+            }
+        }
+        out
+    }
+
+    /// Renders the combined notices as a standalone HTML page.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Name of the project the notices are for
+    pub fn notices_html(&self, project: &str) -> String {
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Third-party notices for {0}</title>\n</head>\n<body>\n<h1>Third-party software notices</h1>\n<p>{0} includes the following {1} third-party packages.</p>\n",
+            escape_html(project),
+            self.packages.len()
+        );
+        for package in &self.packages {
+            out.push_str(&format!(
+                "<section>\n<h2>{} {}</h2>\n<p>License: {}</p>\n",
+                escape_html(&package.name),
+                escape_html(&package.version),
+                escape_html(package.license_label())
+            ));
+            if package.texts.is_empty() {
+                out.push_str("<p>No license file is included in this package.</p>\n");
+            }
+            for text in &package.texts {
+                out.push_str(&format!(
+                    "<h3>{}</h3>\n<pre>{}</pre>\n",
+                    escape_html(&text.file),
+                    escape_html(text.text.trim_end())
+                ));
+            }
+            out.push_str("</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    use crate::test_support::install;
+
+    #[test]
+    fn test_collect_license_texts() {
+        let dir = std::env::temp_dir().join(format!("codescope-licenses-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        install(
+            &dir,
+            "lodash",
+            r#"{"name": "lodash", "version": "4.17.21", "license": "MIT"}"#,
+            &[
+                ("LICENSE", "Copyright OpenJS Foundation <https://openjsf.org/>\n"),
+                ("README.md", "# lodash"),
+            ],
+        );
+        install(&dir, "left-pad", r#"{"name": "left-pad", "version": "1.3.0"}"#, &[]);
+        std::fs::create_dir_all(dir.join("node_modules/lodash/docs")).unwrap();
+        std::fs::write(dir.join("node_modules/lodash/docs/LICENSE"), "nested").unwrap();
+
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("lodash", "^4.17.0", DependencyType::Production);
+        graph.add_dependency("left-pad", "^1.0.0", DependencyType::Production);
+        graph.add_dependency("missing", "1.0.0", DependencyType::Production);
+        let report = LicenseReport::from_graph(&graph, &PackageStore::detect(&dir).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.missing, vec!["missing"]);
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["left-pad", "lodash"]);
+        let lodash = &report.packages[1];
+        assert_eq!(lodash.version, "4.17.21");
+        assert_eq!(lodash.license.as_deref(), Some("MIT"));
+        assert_eq!(lodash.texts.len(), 1, "only root-level license files are collected");
+        assert_eq!(report.without_text().len(), 1);
+
+        let text = report.notices_text("my-app");
+        assert!(text.contains("my-app includes the following 2 third-party packages."));
+        assert!(text.contains("lodash 4.17.21\nLicense: MIT\n"));
+        assert!(text.contains("--- LICENSE ---\n\nCopyright OpenJS Foundation"));
+        assert!(text.contains("left-pad 1.3.0\nLicense: UNKNOWN\n"));
+
+        let html = report.notices_html("my-app");
+        assert!(html.contains("<pre>Copyright OpenJS Foundation &lt;https://openjsf.org/&gt;</pre>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
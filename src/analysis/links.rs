@@ -0,0 +1,254 @@
+//! Where to read more about a package.
+//!
+//! Deciding what to do about a dependency often starts outside codescope:
+//! its source, its npm page, its repository or what changed in its last
+//! release. The installed `package.json` files say where the repository and
+//! homepage are; changelogs are found next to the manifest and linked in the
+//! repository, or the repository's releases page stands in for them.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::graph::DependencyGraph;
+use crate::parser::{PackageLocation, PackageStore};
+
+/// File names of changelogs, compared ignoring case.
+const CHANGELOG_FILES: [&str; 6] = [
+    "CHANGELOG.md",
+    "CHANGELOG",
+    "CHANGES.md",
+    "HISTORY.md",
+    "History.md",
+    "RELEASES.md",
+];
+
+/// Returns the page of a package on npmjs.com.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::links::npm_page;
+///
+/// assert_eq!(npm_page("@babel/core"), "https://www.npmjs.com/package/@babel/core");
+/// ```
+pub fn npm_page(package: &str) -> String {
+    format!("https://www.npmjs.com/package/{}", package)
+}
+
+/// Places to read more about an installed package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageLinks {
+    /// The installed package directory, unless it is inside an archive
+    pub directory: Option<PathBuf>,
+    /// Web page of the source repository
+    pub repository: Option<String>,
+    /// The `homepage` field
+    pub homepage: Option<String>,
+    /// Web page of the changelog, or of the repository's releases
+    pub changelog: Option<String>,
+}
+
+impl PackageLinks {
+    /// Creates the links of a package from its manifest and the names of
+    /// the files in its root.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::links::PackageLinks;
+    /// use serde_json::json;
+    ///
+    /// let manifest = json!({
+    ///     "repository": { "type": "git", "url": "git+https://github.com/babel/babel.git", "directory": "packages/babel-core" }
+    /// });
+    /// let links = PackageLinks::new(&manifest, &["CHANGELOG.md".to_string()]);
+    /// assert_eq!(links.repository.as_deref(), Some("https://github.com/babel/babel"));
+    /// assert_eq!(
+    ///     links.changelog.as_deref(),
+    ///     Some("https://github.com/babel/babel/blob/HEAD/packages/babel-core/CHANGELOG.md")
+    /// );
+    /// ```
+    pub fn new(manifest: &Value, root_files: &[String]) -> Self {
+        let repository = manifest.get("repository").and_then(repository_url);
+        let homepage = manifest
+            .get("homepage")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+            .map(str::to_string);
+
+        let changelog_file = root_files
+            .iter()
+            .find(|file| CHANGELOG_FILES.iter().any(|name| name.eq_ignore_ascii_case(file)));
+        let changelog = repository.as_ref().and_then(|repository| {
+            let blob = if repository.starts_with("https://github.com/") {
+                "blob"
+            } else if repository.starts_with("https://gitlab.com/") {
+                "-/blob"
+            } else {
+                return None;
+            };
+            match changelog_file {
+                Some(file) => {
+                    let directory = manifest
+                        .get("repository")
+                        .and_then(|repository| repository.get("directory"))
+                        .and_then(Value::as_str)
+                        .map(|directory| directory.trim_matches('/'))
+                        .filter(|directory| !directory.is_empty())
+                        .map(|directory| format!("{}/", directory))
+                        .unwrap_or_default();
+                    Some(format!("{}/{}/HEAD/{}{}", repository, blob, directory, file))
+                }
+                None if blob == "blob" => Some(format!("{}/releases", repository)),
+                None => Some(format!("{}/-/releases", repository)),
+            }
+        });
+
+        Self {
+            directory: None,
+            repository,
+            homepage,
+            changelog,
+        }
+    }
+
+    /// Reads the links of an installed package.
+    ///
+    /// Returns `None` if the manifest cannot be read.
+    pub fn read(location: &PackageLocation) -> Option<Self> {
+        let manifest: Value = serde_json::from_str(&location.read_to_string("package.json").ok()?).ok()?;
+        let mut links = Self::new(&manifest, &location.root_files().unwrap_or_default());
+        if let PackageLocation::Directory(dir) = location {
+            links.directory = Some(dir.clone());
+        }
+        Some(links)
+    }
+}
+
+/// Returns the web page of the repository in a `repository` field.
+///
+/// Accepts the URL forms npm does: `git+https://`, `git://` and `git+ssh://`
+/// URLs, `github:`, `gitlab:` and `bitbucket:` shorthands and bare
+/// `user/repo` for GitHub, as a string or under `url`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::links::repository_url;
+/// use serde_json::json;
+///
+/// let github = Some("https://github.com/lodash/lodash".to_string());
+/// assert_eq!(repository_url(&json!("lodash/lodash")), github);
+/// assert_eq!(repository_url(&json!({ "url": "git+ssh://git@github.com/lodash/lodash.git" })), github);
+/// assert_eq!(repository_url(&json!("gitlab:acme/tools")), Some("https://gitlab.com/acme/tools".to_string()));
+/// assert_eq!(repository_url(&json!("file:../local")), None);
+/// ```
+pub fn repository_url(repository: &Value) -> Option<String> {
+    let url = match repository {
+        Value::String(url) => url.as_str(),
+        Value::Object(fields) => fields.get("url")?.as_str()?,
+        _ => return None,
+    }
+    .trim();
+
+    let url = if let Some((host, path)) = url.split_once(':').filter(|(host, path)| {
+        matches!(*host, "github" | "gitlab" | "bitbucket") && !path.starts_with("//")
+    }) {
+        let domain = if host == "bitbucket" { "bitbucket.org".to_string() } else { format!("{}.com", host) };
+        format!("https://{}/{}", domain, path)
+    } else if !url.contains(':') && url.split('/').count() == 2 && !url.starts_with('.') {
+        format!("https://github.com/{}", url)
+    } else {
+        let rest = url.strip_prefix("git+").unwrap_or(url);
+        let rest = ["https://", "http://", "git://", "ssh://", "git@"]
+            .iter()
+            .find_map(|scheme| rest.strip_prefix(scheme))?;
+        // `git@host:user/repo` uses a colon; user@ prefixes name ssh users
+        let rest = rest.split_once('@').map_or(rest, |(_, host)| host).replacen(':', "/", 1);
+        format!("https://{}", rest)
+    };
+    let url = url.split('#').next().unwrap_or_default().trim_end_matches('/');
+    Some(url.strip_suffix(".git").unwrap_or(url).to_string())
+}
+
+/// Reads the links of every installed package in the graph, keyed by name.
+pub fn link_packages(graph: &DependencyGraph, store: &PackageStore) -> BTreeMap<String, PackageLinks> {
+    graph
+        .get_all_nodes()
+        .into_iter()
+        .filter_map(|node| {
+            let name = node.package_name();
+            let location = store
+                .locate(name, Some(&node.version))
+                .or_else(|| store.locate(name, None))?;
+            Some((node.name.clone(), PackageLinks::read(&location)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repository_forms() {
+        let react = Some("https://github.com/facebook/react".to_string());
+        for form in [
+            json!("github:facebook/react"),
+            json!("git+https://github.com/facebook/react.git"),
+            json!("git://github.com/facebook/react.git"),
+            json!("git@github.com:facebook/react.git"),
+            json!({ "type": "git", "url": "https://github.com/facebook/react.git#main" }),
+        ] {
+            assert_eq!(repository_url(&form), react, "{}", form);
+        }
+        assert_eq!(
+            repository_url(&json!("bitbucket:acme/tools")),
+            Some("https://bitbucket.org/acme/tools".to_string())
+        );
+        assert_eq!(repository_url(&json!(["github:a/b"])), None);
+    }
+
+    #[test]
+    fn test_changelog_links() {
+        let manifest = |url: &str| json!({ "repository": url, "homepage": "https://example.com/docs" });
+        let github = PackageLinks::new(&manifest("github:a/b"), &[]);
+        assert_eq!(github.changelog.as_deref(), Some("https://github.com/a/b/releases"));
+        assert_eq!(github.homepage.as_deref(), Some("https://example.com/docs"));
+
+        let gitlab = PackageLinks::new(&manifest("gitlab:a/b"), &["history.md".to_string()]);
+        assert_eq!(gitlab.changelog.as_deref(), Some("https://gitlab.com/a/b/-/blob/HEAD/history.md"));
+
+        let elsewhere = PackageLinks::new(&manifest("https://git.example.com/a/b.git"), &[]);
+        assert_eq!(elsewhere.repository.as_deref(), Some("https://git.example.com/a/b"));
+        assert_eq!(elsewhere.changelog, None);
+    }
+
+    #[test]
+    fn test_link_installed_packages() {
+        let dir = std::env::temp_dir().join(format!("codescope-links-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let package = dir.join("node_modules").join("dayjs");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(package.join("package.json"), r#"{"name": "dayjs", "repository": "iamkun/dayjs"}"#).unwrap();
+        std::fs::write(package.join("CHANGELOG.md"), "# Changelog\n").unwrap();
+
+        let mut graph = DependencyGraph::new();
+        for name in ["dayjs", "missing"] {
+            graph.add_dependency(name, "1.0.0", crate::graph::DependencyType::Production);
+        }
+        let links = link_packages(&graph, &PackageStore::detect(&dir).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(links.keys().collect::<Vec<_>>(), vec!["dayjs"]);
+        assert!(links["dayjs"].directory.as_ref().is_some_and(|d| d.ends_with("node_modules/dayjs")));
+        assert_eq!(
+            links["dayjs"].changelog.as_deref(),
+            Some("https://github.com/iamkun/dayjs/blob/HEAD/CHANGELOG.md")
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Soft memory limit for very large inputs.
+//!
+//! Stats files of large monorepos run into gigabytes once parsed, and CI
+//! containers kill a process that outgrows its memory limit without a
+//! word. With a [`MemoryBudget`] the analysis checks its memory use before
+//! and after the big allocations (the module list, the dependency graph,
+//! the flattened tree) and, once it comes close to the limit, switches to
+//! [`Fidelity::Reduced`]: module-level detail is dropped while the package
+//! totals are kept, and a warning says so.
+//!
+//! The limit is soft. Memory already in use is not given back, so a single
+//! input larger than the limit can still exceed it.
+
+use std::fs;
+
+use super::warnings::AnalysisWarnings;
+use crate::bundle::webpack::format_size;
+
+/// Share of the limit, in percent, at which the analysis reduces fidelity.
+pub const SOFT_LIMIT_PERCENT: u64 = 80;
+
+/// Estimated bytes in memory per byte of a stats file while it is parsed.
+pub const STATS_EXPANSION: u64 = 6;
+
+/// Estimated bytes per row of the flattened dependency tree.
+pub const FLATTENED_NODE_BYTES: u64 = 512;
+
+/// Source recorded in the warning about reduced fidelity.
+const WARNING_SOURCE: &str = "memory";
+
+/// How much detail the analysis keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fidelity {
+    /// Everything, down to the individual modules
+    #[default]
+    Full,
+    /// Package totals only, without the modules they are made of
+    Reduced,
+}
+
+/// Tracks memory use against an optional soft limit.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBudget {
+    limit: Option<u64>,
+    fidelity: Fidelity,
+}
+
+impl MemoryBudget {
+    /// Creates a budget with the given limit in bytes, or an unlimited one.
+    pub fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            fidelity: Fidelity::Full,
+        }
+    }
+
+    /// Returns the limit in bytes, if there is one.
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Returns the current fidelity.
+    pub fn fidelity(&self) -> Fidelity {
+        self.fidelity
+    }
+
+    /// Returns true once the analysis switched to reduced fidelity.
+    pub fn is_reduced(&self) -> bool {
+        self.fidelity == Fidelity::Reduced
+    }
+
+    /// Checks the memory in use, plus an estimate of what `stage` is about
+    /// to allocate, against the limit.
+    ///
+    /// Where the memory in use cannot be measured, only the estimate is
+    /// compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - What the memory is for, used in the warning
+    /// * `upcoming` - Estimated bytes the stage is about to allocate
+    /// * `warnings` - Receives a warning when the fidelity is reduced
+    ///
+    /// # Returns
+    ///
+    /// The fidelity the stage should run at.
+    pub fn check(&mut self, stage: &str, upcoming: u64, warnings: &mut AnalysisWarnings) -> Fidelity {
+        if self.limit.is_none() {
+            return self.fidelity;
+        }
+        let usage = resident_memory().unwrap_or(0).saturating_add(upcoming);
+        self.check_usage(stage, usage, warnings)
+    }
+
+    /// Like [`check`](Self::check), with the memory use already known.
+    ///
+    /// Once reduced, the fidelity stays reduced and no further warning is
+    /// recorded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::{AnalysisWarnings, Fidelity, MemoryBudget};
+    ///
+    /// let mut budget = MemoryBudget::new(Some(1024 * 1024 * 1024));
+    /// let mut warnings = AnalysisWarnings::new();
+    /// assert_eq!(budget.check_usage("the module list", 512 * 1024 * 1024, &mut warnings), Fidelity::Full);
+    /// assert_eq!(budget.check_usage("the module list", 900 * 1024 * 1024, &mut warnings), Fidelity::Reduced);
+    /// assert_eq!(warnings.len(), 1);
+    /// ```
+    pub fn check_usage(&mut self, stage: &str, usage: u64, warnings: &mut AnalysisWarnings) -> Fidelity {
+        let Some(limit) = self.limit else {
+            return self.fidelity;
+        };
+        let threshold = limit / 100 * SOFT_LIMIT_PERCENT;
+        if self.fidelity == Fidelity::Full && usage >= threshold {
+            self.fidelity = Fidelity::Reduced;
+            warnings.push(
+                WARNING_SOURCE,
+                format!(
+                    "about {} in use for {}, over {}% of the {} limit; module-level detail is left out, package totals are kept",
+                    format_size(usage),
+                    stage,
+                    SOFT_LIMIT_PERCENT,
+                    format_size(limit)
+                ),
+            );
+        }
+        self.fidelity
+    }
+}
+
+/// Returns the resident memory of this process in bytes, where the
+/// platform reports it (Linux).
+pub fn resident_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// Reads the `VmRSS` line of `/proc/self/status`, which is in KiB.
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|line| line.strip_prefix("VmRSS:"))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_budget_reduces_once() {
+        let mut warnings = AnalysisWarnings::new();
+        let mut budget = MemoryBudget::new(Some(100 * MIB));
+        assert_eq!(budget.check_usage("the dependency graph", 79 * MIB, &mut warnings), Fidelity::Full);
+        assert!(warnings.is_empty());
+
+        assert_eq!(budget.check_usage("the module list", 80 * MIB, &mut warnings), Fidelity::Reduced);
+        assert_eq!(budget.check_usage("the flattened tree", 10 * MIB, &mut warnings), Fidelity::Reduced);
+        assert!(budget.is_reduced());
+        assert_eq!(warnings.len(), 1);
+        let warning = warnings.iter().next().unwrap();
+        assert_eq!(warning.source, "memory");
+        assert!(warning.message.starts_with("about 80.00 MB in use for the module list, over 80% of the 100.00 MB limit"));
+    }
+
+    #[test]
+    fn test_unlimited_budget_never_reduces() {
+        let mut warnings = AnalysisWarnings::new();
+        let mut budget = MemoryBudget::default();
+        assert_eq!(budget.check("the module list", u64::MAX, &mut warnings), Fidelity::Full);
+        assert_eq!(budget.limit(), None);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tcodescope\nVmPeak:\t  20000 kB\nVmRSS:\t   12345 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(12345 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tcodescope\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_resident_memory_on_linux() {
+        assert!(resident_memory().is_some_and(|bytes| bytes > 0));
+    }
+}
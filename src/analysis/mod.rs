@@ -10,6 +10,34 @@
 //! - Track which exports from each dependency are actually used
 //! - Calculate utilization percentage per dependency
 //! - Flag low-utilization dependencies
+//! - Find orphaned packages in `node_modules` that nothing requires
+//! - Aggregate package counts and sizes per configured group
+//! - Break sizes, savings and violations down by owning team
+//! - Preview which packages removing a direct dependency would uninstall
+//! - Compare the dependency graphs of two revisions, or two projects side
+//!   by side
+//! - Report the distribution of dependency depths and the longest chain
+//! - Measure the install footprint of each package: disk usage, files and
+//!   install scripts
+//! - Weigh the install cost of development-only tooling against its own
+//!   budget
+//! - Detect native modules and platform-specific packages
+//! - Aggregate package counts and sizes per npm scope
+//! - Flag dependencies that need newer browsers than the browserslist
+//!   targets
+//! - Find `@types/*` packages that are unpaired or made redundant by
+//!   bundled declarations
+//! - Find external packages used at different versions across workspaces
+//! - Explain which copy of a package npm hoisted and what keeps the others
+//!   nested
+//! - Order workspace packages by their internal dependencies
+//! - Read package descriptions and keywords for searching
+//! - Find the repository, homepage and changelog of installed packages
+//! - Find packages installed at pre-release or 0.x versions
+//! - Collect license texts and generate third-party notices
+//! - Collect warnings about optional inputs the analysis went without
+//! - Keep memory use under a soft limit by dropping module-level detail
+//! - Summarize every project under a directory in one report
 //!
 //! # Example
 //!
@@ -32,10 +60,59 @@
 //! }
 //! ```
 
+pub mod batch;
+pub mod browser_support;
+pub mod depth;
+pub mod descriptions;
+pub mod diff;
+pub mod duplicates;
 pub mod exports;
+pub mod footprint;
+pub mod groups;
+pub mod hoisting;
+pub mod licenses;
+pub mod links;
+pub mod memory;
+pub mod native;
+pub mod orphans;
+pub mod owners;
+pub mod prerelease;
+pub mod removal;
+pub mod scopes;
+pub mod tooling;
+pub mod types_audit;
+pub mod warnings;
+pub mod workspace_graph;
 
 // Re-export main types for convenience
+pub use batch::{discover_projects, BatchReport, ProjectSummary};
+pub use browser_support::{detect_syntax, BrowserIssue, BrowserSupportReport, SyntaxFeature, TargetConflict};
+pub use depth::{DepthLevel, DepthReport};
+pub use descriptions::{describe_packages, PackageDescription};
+pub use diff::{
+    compare_graphs, diff_graphs, format_comparison, ChangeKind, ComparedPackage, ComparedSide, DependencyDiff, Difference,
+    PackageChange,
+};
+pub use duplicates::{find_workspace_duplicates, format_duplicates, DuplicateUsage, WorkspaceDuplicate};
 pub use exports::{
-    analyze_file, analyze_project_imports, Import, ImportAnalyzer, ImportKind, ImportSpecifier,
-    PackageUsage, ProjectImports,
+    analyze_file, analyze_project_imports, analyze_project_imports_with, source_files, Import, ImportAnalyzer,
+    ImportKind, ImportSite, ImportSpecifier, PackageUsage, ProjectImports,
+};
+pub use footprint::{FootprintReport, FootprintSort, InstallFootprint, PackageFootprint};
+pub use groups::{summarize_budgeted_groups, summarize_groups, GroupSummary};
+pub use hoisting::{explain_hoisting, HoistingExplanation, InstalledCopy, Requester};
+pub use licenses::{LicenseReport, LicenseText, PackageLicense};
+pub use links::{link_packages, npm_page, PackageLinks};
+pub use memory::{Fidelity, MemoryBudget};
+pub use native::{NativePackage, NativeReport, PlatformSupport};
+pub use orphans::{find_orphaned_packages, OrphanReport, OrphanedPackage};
+pub use owners::{summarize_owners, OwnerSummary, UNOWNED};
+pub use prerelease::{Instability, PrereleaseReport, UnstablePackage};
+pub use removal::{simulate_removal, simulate_removals, RemovalImpact, RemovedPackage, RetainedPackage};
+pub use scopes::{format_scopes, summarize_scopes, ScopeSummary};
+pub use tooling::{BudgetCheck, ToolingReport};
+pub use types_audit::{TypesAudit, TypesFinding, TypesIssue};
+pub use warnings::{AnalysisWarning, AnalysisWarnings};
+pub use workspace_graph::{
+    format_workspace_graph, AffectedPackages, BuildOrder, InternalDependency, WorkspaceGraph, WorkspacePackage,
 };
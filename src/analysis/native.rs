@@ -0,0 +1,300 @@
+//! Native modules and platform-specific packages.
+//!
+//! Packages with native bindings are compiled or downloaded for the machine
+//! that installs them, and packages with `os`/`cpu` fields only install on
+//! some platforms. Both break the assumption that `node_modules` can be
+//! built on one machine and copied to another, such as a macOS laptop and a
+//! Linux Docker image, so this module finds them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::DependencyGraph;
+use crate::parser::installed::{PackageLocation, PackageStore};
+use crate::parser::PackageLock;
+
+/// Dependencies that load or build a native addon.
+const NATIVE_HELPERS: [&str; 6] = [
+    "node-gyp-build",
+    "node-addon-api",
+    "nan",
+    "prebuild-install",
+    "bindings",
+    "@mapbox/node-pre-gyp",
+];
+
+/// The fields of an installed package.json that describe its platforms.
+#[derive(Debug, Default, Deserialize)]
+struct PlatformManifest {
+    #[serde(default)]
+    gypfile: bool,
+    #[serde(default)]
+    napi: Option<serde_json::Value>,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    os: Vec<String>,
+    #[serde(default)]
+    cpu: Vec<String>,
+}
+
+/// What ties a package to the platform it is installed on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PlatformSupport {
+    /// Evidence of native bindings: `binding.gyp`, `gypfile`, `napi`,
+    /// native helper dependencies and compiled `.node` files
+    pub bindings: Vec<String>,
+    /// Operating systems the package is restricted to (`!` excludes one)
+    pub os: Vec<String>,
+    /// CPU architectures the package is restricted to (`!` excludes one)
+    pub cpu: Vec<String>,
+}
+
+impl PlatformSupport {
+    /// Inspects an installed package.
+    ///
+    /// `.node` files of packages nested in the package's own
+    /// `node_modules` are not counted.
+    pub fn read(location: &PackageLocation) -> io::Result<Self> {
+        let files = location.files()?;
+        let manifest: PlatformManifest = location
+            .read_to_string("package.json")
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let mut bindings = Vec::new();
+        if files.iter().any(|file| file == "binding.gyp") {
+            bindings.push("binding.gyp".to_string());
+        }
+        if manifest.gypfile {
+            bindings.push("gypfile".to_string());
+        }
+        if manifest.napi.is_some() {
+            bindings.push("napi".to_string());
+        }
+        bindings.extend(
+            NATIVE_HELPERS
+                .iter()
+                .filter(|helper| manifest.dependencies.contains_key(**helper))
+                .map(|helper| helper.to_string()),
+        );
+        bindings.extend(
+            files
+                .into_iter()
+                .filter(|file| file.ends_with(".node") && !file.contains("node_modules/")),
+        );
+
+        Ok(Self {
+            bindings,
+            os: manifest.os,
+            cpu: manifest.cpu,
+        })
+    }
+
+    /// Returns true if the package has native bindings.
+    pub fn is_native(&self) -> bool {
+        !self.bindings.is_empty()
+    }
+
+    /// Returns true if the package only installs on some platforms.
+    pub fn is_platform_specific(&self) -> bool {
+        !self.os.is_empty() || !self.cpu.is_empty()
+    }
+
+    /// Returns true if nothing ties the package to a platform.
+    pub fn is_portable(&self) -> bool {
+        !self.is_native() && !self.is_platform_specific()
+    }
+
+    /// Describes the platform restrictions, e.g. `os: darwin; cpu: arm64`.
+    pub fn platforms(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.os.is_empty() {
+            parts.push(format!("os: {}", self.os.join(", ")));
+        }
+        if !self.cpu.is_empty() {
+            parts.push(format!("cpu: {}", self.cpu.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// A package tied to the platform it is installed on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NativePackage {
+    /// Package name
+    pub name: String,
+    /// Version from the graph
+    pub version: String,
+    /// Native bindings and platform restrictions
+    #[serde(flatten)]
+    pub support: PlatformSupport,
+}
+
+/// The native and platform-specific packages of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NativeReport {
+    /// Packages with native bindings or platform restrictions, sorted by name
+    pub packages: Vec<NativePackage>,
+    /// Number of installed packages inspected for bindings
+    pub inspected: usize,
+}
+
+impl NativeReport {
+    /// Finds the native and platform-specific packages of the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The resolved dependency graph
+    /// * `store` - Installed packages, inspected for bindings and platform
+    ///   fields
+    /// * `lock` - The lockfile, whose `os`/`cpu` fields cover packages that
+    ///   are not installed, such as optional builds for other platforms
+    pub fn from_graph(graph: &DependencyGraph, store: Option<&PackageStore>, lock: Option<&PackageLock>) -> Self {
+        let mut report = Self::default();
+        for node in graph.get_all_nodes() {
+            let name = node.package_name();
+            let installed = store.and_then(|store| {
+                store
+                    .locate(name, Some(&node.version))
+                    .or_else(|| store.locate(name, None))
+            });
+            let mut support = match installed.map(|location| PlatformSupport::read(&location)) {
+                Some(Ok(support)) => {
+                    report.inspected += 1;
+                    support
+                }
+                _ => PlatformSupport::default(),
+            };
+            if !support.is_platform_specific() {
+                if let Some(entry) = lock.and_then(|lock| lock.packages.get(&format!("node_modules/{}", name))) {
+                    support.os = entry.os.clone();
+                    support.cpu = entry.cpu.clone();
+                }
+            }
+
+            if !support.is_portable() {
+                report.packages.push(NativePackage {
+                    name: node.name.clone(),
+                    version: node.version.clone(),
+                    support,
+                });
+            }
+        }
+        report.packages.sort_by(|a, b| a.name.cmp(&b.name));
+        report
+    }
+
+    /// Returns the platform support of each listed package, keyed by name.
+    pub fn by_name(&self) -> BTreeMap<String, PlatformSupport> {
+        self.packages
+            .iter()
+            .map(|p| (p.name.clone(), p.support.clone()))
+            .collect()
+    }
+
+    /// Formats the native and platform-specific packages as a listing.
+    pub fn format_report(&self) -> String {
+        let native: Vec<&NativePackage> = self.packages.iter().filter(|p| p.support.is_native()).collect();
+        let restricted: Vec<&NativePackage> =
+            self.packages.iter().filter(|p| p.support.is_platform_specific()).collect();
+
+        let mut out = String::new();
+        if self.inspected == 0 {
+            out.push_str("⚠️  No installed packages were inspected; run npm install to detect native bindings.\n\n");
+        }
+        if native.is_empty() {
+            out.push_str("✅ No native modules.\n");
+        } else {
+            out.push_str(&format!("🔧 {} native module(s):\n", native.len()));
+            for package in native {
+                out.push_str(&format!(
+                    "  {}@{}: {}\n",
+                    package.name,
+                    package.version,
+                    package.support.bindings.join(", ")
+                ));
+            }
+        }
+        if restricted.is_empty() {
+            out.push_str("✅ No platform-specific packages.\n");
+        } else {
+            out.push_str(&format!("\n🖥️  {} platform-specific package(s):\n", restricted.len()));
+            for package in restricted {
+                out.push_str(&format!(
+                    "  {}@{}: {}\n",
+                    package.name,
+                    package.version,
+                    package.support.platforms()
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+    use crate::test_support::install;
+    use std::fs;
+
+    #[test]
+    fn test_native_report() {
+        let root = std::env::temp_dir().join(format!("codescope-native-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        install(
+            &root,
+            "bcrypt",
+            r#"{"dependencies": {"node-addon-api": "^5.0.0"}}"#,
+            &[("binding.gyp", ""), ("lib/binding/napi-v3/bcrypt_lib.node", "")],
+        );
+        install(&root, "@swc/core-darwin-arm64", r#"{"os": ["darwin"], "cpu": ["arm64"]}"#, &[("swc.darwin-arm64.node", "")]);
+        install(&root, "lodash", "{}", &[("node_modules/nested/addon.node", "")]);
+
+        let mut graph = DependencyGraph::new();
+        for name in ["bcrypt", "@swc/core-darwin-arm64", "lodash", "fsevents"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        let lock = PackageLock::parse(
+            r#"{"lockfileVersion": 3, "packages": {
+                "node_modules/fsevents": {"version": "2.3.3", "optional": true, "os": ["darwin"]}
+            }}"#,
+        )
+        .unwrap();
+        let store = PackageStore::NodeModules(root.join("node_modules"));
+        let report = NativeReport::from_graph(&graph, Some(&store), Some(&lock));
+
+        assert_eq!(report.inspected, 3);
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["@swc/core-darwin-arm64", "bcrypt", "fsevents"], "nested .node files belong to other packages");
+
+        let support = report.by_name();
+        assert_eq!(
+            support["bcrypt"].bindings,
+            vec!["binding.gyp", "node-addon-api", "lib/binding/napi-v3/bcrypt_lib.node"]
+        );
+        assert_eq!(support["@swc/core-darwin-arm64"].platforms(), "os: darwin; cpu: arm64");
+        assert!(!support["fsevents"].is_native(), "not installed, known from the lockfile");
+        assert_eq!(support["fsevents"].os, vec!["darwin"]);
+
+        let text = report.format_report();
+        assert!(text.contains("🔧 2 native module(s):\n"));
+        assert!(text.contains("  fsevents@1.0.0: os: darwin\n"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_without_node_modules() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        let report = NativeReport::from_graph(&graph, None, None);
+        assert!(report.packages.is_empty());
+        assert!(report.format_report().starts_with("⚠️  No installed packages were inspected"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Detection of orphaned packages in `node_modules`.
+//!
+//! A package is orphaned when it is installed on disk but nothing in the
+//! resolved lockfile graph requires it — typically left behind after a
+//! dependency was removed or a branch switch changed the lockfile without
+//! a reinstall. Orphans do not affect the bundle but waste disk space and
+//! can shadow the packages Node would otherwise resolve.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::parser::installed::{DiskUsage, PackageLocation};
+use crate::parser::paths::long_path;
+use crate::parser::lockfile::{install_name, PackageLock};
+
+/// A package present on disk but not required by the lockfile graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanedPackage {
+    /// Install path relative to the project root (e.g. `node_modules/foo`)
+    pub path: String,
+    /// Package name from its `package.json`, or derived from the path
+    pub name: String,
+    /// Installed version, if readable
+    pub version: Option<String>,
+    /// Size on disk, including any nested `node_modules`
+    pub size: DiskUsage,
+}
+
+/// The orphaned packages of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrphanReport {
+    /// Orphans sorted by size, largest first
+    pub packages: Vec<OrphanedPackage>,
+}
+
+impl OrphanReport {
+    /// Returns true if no orphaned packages were found.
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Total bytes that removing every orphan would free.
+    pub fn total_bytes(&self) -> u64 {
+        self.packages.iter().map(|p| p.size.bytes).sum()
+    }
+
+    /// Returns a shell command that removes every orphan.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::orphans::{OrphanReport, OrphanedPackage};
+    /// use codescope::parser::DiskUsage;
+    ///
+    /// let report = OrphanReport {
+    ///     packages: vec![OrphanedPackage {
+    ///         path: "node_modules/left-pad".to_string(),
+    ///         name: "left-pad".to_string(),
+    ///         version: Some("1.3.0".to_string()),
+    ///         size: DiskUsage::default(),
+    ///     }],
+    /// };
+    /// assert_eq!(report.cleanup_command().as_deref(), Some("rm -rf node_modules/left-pad"));
+    /// ```
+    pub fn cleanup_command(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let paths: Vec<String> = self.packages.iter().map(|p| shell_quote(&p.path)).collect();
+        Some(format!("rm -rf {}", paths.join(" ")))
+    }
+}
+
+/// Finds packages installed under `project_dir/node_modules` that the
+/// lockfile graph does not require.
+///
+/// Only the outermost orphan of a subtree is reported: removing it also
+/// removes anything nested in its own `node_modules`.
+///
+/// # Arguments
+///
+/// * `project_dir` - Project root containing `node_modules`
+/// * `lock` - The project's parsed lockfile
+///
+/// # Returns
+///
+/// An `OrphanReport` with packages sorted by size, largest first.
+pub fn find_orphaned_packages(project_dir: &Path, lock: &PackageLock) -> io::Result<OrphanReport> {
+    let required = lock.required_paths();
+
+    // Start from the root and workspace folders
+    let mut stack = Vec::new();
+    for path in lock.packages.keys().filter(|path| !path.contains("node_modules/")) {
+        scan_node_modules(project_dir, path, &mut stack)?;
+    }
+
+    let mut packages = Vec::new();
+    while let Some(path) = stack.pop() {
+        let dir = long_path(&project_dir.join(&path));
+        let is_link = fs::symlink_metadata(&dir)?.file_type().is_symlink();
+        if required.contains(path.as_str()) {
+            // A linked workspace's node_modules is scanned under its own path
+            if !is_link {
+                scan_node_modules(project_dir, &path, &mut stack)?;
+            }
+            continue;
+        }
+
+        let location = PackageLocation::Directory(dir.clone());
+        let manifest = location
+            .read_to_string("package.json")
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        let field = |key: &str| {
+            manifest
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        // Symlinked packages free nothing but the link itself
+        let size = if is_link {
+            DiskUsage::default()
+        } else {
+            location.disk_usage()?
+        };
+
+        packages.push(OrphanedPackage {
+            name: field("name").unwrap_or_else(|| install_name(&path).to_string()),
+            version: field("version"),
+            path,
+            size,
+        });
+    }
+
+    packages.sort_by(|a, b| b.size.bytes.cmp(&a.size.bytes).then_with(|| a.path.cmp(&b.path)));
+    Ok(OrphanReport { packages })
+}
+
+/// Lists the packages directly inside `<parent>/node_modules`, as install
+/// paths relative to the project root.
+///
+/// Dot-entries (`.bin`, `.cache`, `.package-lock.json`) are skipped and
+/// scoped folders are expanded. Nested `node_modules` are not descended
+/// into; callers recurse for packages they keep.
+fn scan_node_modules(project_dir: &Path, parent: &str, out: &mut Vec<String>) -> io::Result<()> {
+    let prefix = if parent.is_empty() {
+        "node_modules".to_string()
+    } else {
+        format!("{}/node_modules", parent)
+    };
+    let dir = long_path(&project_dir.join(&prefix));
+    match fs::symlink_metadata(&dir) {
+        Ok(meta) if meta.is_dir() => {}
+        _ => return Ok(()),
+    }
+
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        if name.starts_with('@') {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            for scoped in fs::read_dir(entry.path())? {
+                let scoped = scoped?;
+                if scoped.path().is_dir() {
+                    out.push(format!("{}/{}/{}", prefix, name, scoped.file_name().to_string_lossy()));
+                }
+            }
+        } else if entry.path().is_dir() {
+            out.push(format!("{}/{}", prefix, name));
+        }
+    }
+    Ok(())
+}
+
+/// Quotes a path for POSIX shells when it contains special characters.
+fn shell_quote(path: &str) -> String {
+    if path
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '_' | '-' | '@' | '+'))
+    {
+        path.to_string()
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codescope-orphans-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn install(root: &Path, path: &str, name: &str, body: &str) {
+        let manifest = format!(r#"{{"name":"{}","version":"1.0.0"}}"#, name);
+        test_support::install(root, path, &manifest, &[("index.js", body)]);
+    }
+
+    const LOCK: &str = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "react": "^18.2.0", "@scope/kept": "^1.0.0" } },
+    "node_modules/react": { "version": "18.2.0" },
+    "node_modules/@scope/kept": { "version": "1.0.0" }
+  }
+}"#;
+
+    #[test]
+    fn test_finds_orphans() {
+        let dir = temp_dir("find");
+        install(&dir, "react", "react", "module.exports = {};\n");
+        install(&dir, "@scope/kept", "@scope/kept", "");
+        install(&dir, "@scope/stale", "@scope/stale", "x");
+        install(&dir, "left-pad", "left-pad", &"x".repeat(1000));
+        // Nested inside an orphan: removed along with it, not reported
+        install(&dir, "left-pad/node_modules/inner", "inner", "");
+        // Nested inside a required package but not in the lockfile
+        install(&dir, "react/node_modules/old", "old", "");
+        fs::create_dir_all(dir.join("node_modules/.bin")).unwrap();
+        fs::write(dir.join("node_modules/.package-lock.json"), "{}").unwrap();
+
+        let lock = PackageLock::parse(LOCK).unwrap();
+        let report = find_orphaned_packages(&dir, &lock).unwrap();
+        let paths: Vec<&str> = report.packages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "node_modules/left-pad",
+                "node_modules/@scope/stale",
+                "node_modules/react/node_modules/old"
+            ]
+        );
+
+        let left_pad = &report.packages[0];
+        assert_eq!(left_pad.name, "left-pad");
+        assert_eq!(left_pad.version.as_deref(), Some("1.0.0"));
+        assert_eq!(left_pad.size.files, 4);
+        assert!(report.total_bytes() > 1000);
+        assert_eq!(
+            report.cleanup_command().unwrap(),
+            "rm -rf node_modules/left-pad node_modules/@scope/stale node_modules/react/node_modules/old"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_no_node_modules() {
+        let dir = temp_dir("empty");
+        let lock = PackageLock::parse(LOCK).unwrap();
+        let report = find_orphaned_packages(&dir, &lock).unwrap();
+        assert!(report.is_empty());
+        assert_eq!(report.cleanup_command(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("node_modules/@a/b"), "node_modules/@a/b");
+        assert_eq!(shell_quote("node_modules/it's"), "'node_modules/it'\\''s'");
+    }
+}
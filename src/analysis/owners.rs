@@ -0,0 +1,182 @@
+//! Per-owner breakdown of sizes, savings and violations.
+//!
+//! Ownership comes from the CODEOWNERS-style `owners` lines of the project
+//! configuration (see [`crate::config::ProjectConfig::owners_of`]). A
+//! package with several owners counts towards each of them; packages no
+//! line matches are reported under [`UNOWNED`].
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::bundle::savings::SavingsReport;
+use crate::config::ProjectConfig;
+use crate::graph::DependencyGraph;
+
+/// Owner label for packages that no ownership line matches.
+pub const UNOWNED: &str = "(unowned)";
+
+/// Aggregated figures for the packages of one owner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnerSummary {
+    /// Team or person, as written in the configuration
+    pub owner: String,
+    /// Names of the owned packages, sorted
+    pub packages: Vec<String>,
+    /// Sum of the known bundle sizes in bytes
+    pub bundle_size: u64,
+    /// Estimated bundle savings in bytes, if a savings report was given
+    pub potential_savings: u64,
+    /// Owned packages that are part of a circular dependency
+    pub cycle_packages: usize,
+    /// Owned packages with conflicting version requirements
+    pub conflict_packages: usize,
+}
+
+impl OwnerSummary {
+    /// Returns the number of owned packages.
+    pub fn count(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns the number of owned packages with a cycle or conflict.
+    pub fn violations(&self) -> usize {
+        self.cycle_packages + self.conflict_packages
+    }
+}
+
+/// Breaks the packages of the graph down by owner.
+///
+/// # Arguments
+///
+/// * `config` - Project configuration with the ownership lines
+/// * `graph` - The dependency graph, with bundle sizes applied if available
+/// * `savings` - Savings report to attribute, if calculated
+///
+/// # Returns
+///
+/// One summary per owner, sorted by owner, with [`UNOWNED`] last. Empty
+/// if the configuration has no ownership lines.
+pub fn summarize_owners(
+    config: &ProjectConfig,
+    graph: &DependencyGraph,
+    savings: Option<&SavingsReport>,
+) -> Vec<OwnerSummary> {
+    if config.owners.is_empty() {
+        return Vec::new();
+    }
+
+    let cycle_nodes = graph.get_nodes_in_cycles();
+    let conflicts: HashSet<String> = graph.get_packages_with_conflicts();
+    let mut summaries: BTreeMap<String, OwnerSummary> = BTreeMap::new();
+    let mut unowned = OwnerSummary {
+        owner: UNOWNED.to_string(),
+        ..Default::default()
+    };
+
+    for node in graph.get_all_nodes() {
+        let owners = config.owners_of(&node.name);
+        let targets: Vec<&mut OwnerSummary> = if owners.is_empty() {
+            vec![&mut unowned]
+        } else {
+            for owner in owners {
+                summaries.entry(owner.clone()).or_insert_with(|| OwnerSummary {
+                    owner: owner.clone(),
+                    ..Default::default()
+                });
+            }
+            summaries
+                .values_mut()
+                .filter(|s| owners.contains(&s.owner))
+                .collect()
+        };
+
+        let saved = savings.map_or(0, |report| {
+            report
+                .package_savings
+                .iter()
+                .filter(|s| s.package_name == node.name)
+                .map(|s| s.potential_savings)
+                .sum()
+        });
+        for summary in targets {
+            summary.packages.push(node.name.clone());
+            summary.bundle_size += node.bundle_size.unwrap_or(0);
+            summary.potential_savings += saved;
+            summary.cycle_packages += usize::from(cycle_nodes.contains(&node.name));
+            summary.conflict_packages += usize::from(conflicts.contains(&node.name));
+        }
+    }
+
+    let mut result: Vec<OwnerSummary> = summaries.into_values().collect();
+    if !unowned.packages.is_empty() {
+        result.push(unowned);
+    }
+    for summary in &mut result {
+        summary.packages.sort();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::savings::{PackageSavings, SavingsCategory};
+    use crate::graph::DependencyType;
+    use std::collections::HashMap;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+        graph.add_dependency("moment", "2.29.4", DependencyType::Production);
+        graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+        graph.add_edge("react", "react-dom");
+        graph.add_edge("react-dom", "react");
+
+        let mut sizes = HashMap::new();
+        sizes.insert("react".to_string(), (6_000_u64, 1_usize));
+        sizes.insert("moment".to_string(), (290_000_u64, 100_usize));
+        graph.apply_bundle_sizes(&sizes);
+        graph
+    }
+
+    #[test]
+    fn test_summarize_owners() {
+        let config = ProjectConfig::parse(
+            r#"{"owners": ["react* @web", "moment @web @data"]}"#,
+        )
+        .unwrap();
+        let savings = SavingsReport {
+            package_savings: vec![PackageSavings {
+                package_name: "moment".to_string(),
+                current_size: 290_000,
+                potential_savings: 280_000,
+                category: SavingsCategory::HasAlternative,
+                utilization_percentage: None,
+                exports_used: 1,
+                total_exports: None,
+                suggestion: "Switch".to_string(),
+                alternative: Some("dayjs".to_string()),
+            }],
+            ..Default::default()
+        };
+
+        let owners = summarize_owners(&config, &graph(), Some(&savings));
+        let names: Vec<&str> = owners.iter().map(|o| o.owner.as_str()).collect();
+        assert_eq!(names, vec!["@data", "@web", UNOWNED]);
+
+        let web = &owners[1];
+        assert_eq!(web.packages, vec!["moment", "react", "react-dom"]);
+        assert_eq!(web.bundle_size, 296_000);
+        assert_eq!(web.potential_savings, 280_000);
+        assert_eq!(web.cycle_packages, 2);
+        assert_eq!(web.violations(), 2);
+
+        assert_eq!(owners[0].count(), 1);
+        assert_eq!(owners[2].packages, vec!["jest"]);
+    }
+
+    #[test]
+    fn test_no_owner_lines() {
+        assert!(summarize_owners(&ProjectConfig::default(), &graph(), None).is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! Pre-release and unstable versions.
+//!
+//! Versions tagged `-alpha`, `-beta` or `-rc` are not meant for
+//! production, and under semver a `0.x` version may break its API in any
+//! minor release. This module finds the packages installed at such
+//! versions, using the versions the lockfile resolved rather than the
+//! ranges in package.json.
+
+use std::collections::HashSet;
+
+use crate::graph::{DependencyGraph, DependencyType};
+use crate::parser::range::parse_version;
+
+/// Why a version is not considered stable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Instability {
+    /// A pre-release tag such as `-beta.2` or `-rc.1`
+    PreRelease,
+    /// Major version 0
+    ZeroMajor,
+}
+
+impl Instability {
+    /// Classifies a version.
+    ///
+    /// # Returns
+    ///
+    /// `None` for stable versions and for anything that is not a single
+    /// version.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::prerelease::Instability;
+    ///
+    /// assert_eq!(Instability::of("19.0.0-rc.1"), Some(Instability::PreRelease));
+    /// assert_eq!(Instability::of("0.27.2"), Some(Instability::ZeroMajor));
+    /// assert_eq!(Instability::of("4.17.21"), None);
+    /// ```
+    pub fn of(version: &str) -> Option<Self> {
+        let version = parse_version(version)?;
+        if !version.pre.is_empty() {
+            Some(Instability::PreRelease)
+        } else if version.major == 0 {
+            Some(Instability::ZeroMajor)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a short label for the kind of instability.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Instability::PreRelease => "pre-release",
+            Instability::ZeroMajor => "0.x",
+        }
+    }
+}
+
+/// A package installed at a pre-release or 0.x version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnstablePackage {
+    /// Package name
+    pub name: String,
+    /// Installed version
+    pub version: String,
+    /// Why the version is unstable
+    pub instability: Instability,
+    /// Dependency type, propagated from the direct dependencies
+    pub dep_type: DependencyType,
+    /// Whether the package is a direct dependency
+    pub direct: bool,
+}
+
+/// The packages of a graph installed at unstable versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrereleaseReport {
+    /// Unstable packages: pre-releases first, then direct dependencies
+    /// first, then by name
+    pub packages: Vec<UnstablePackage>,
+}
+
+impl PrereleaseReport {
+    /// Finds the unstable packages of a graph.
+    ///
+    /// Direct dependencies whose range the lockfile did not resolve are
+    /// only classified if they name a single version.
+    pub fn from_graph(graph: &DependencyGraph) -> Self {
+        let mut packages: Vec<UnstablePackage> = graph
+            .get_all_nodes()
+            .into_iter()
+            .filter_map(|node| {
+                let version = node.installed_version()?;
+                Some(UnstablePackage {
+                    name: node.name.clone(),
+                    version: version.to_string(),
+                    instability: Instability::of(version)?,
+                    dep_type: node.dep_type,
+                    direct: node.depth == 0,
+                })
+            })
+            .collect();
+        packages.sort_by(|a, b| {
+            a.instability
+                .cmp(&b.instability)
+                .then_with(|| b.direct.cmp(&a.direct))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Self { packages }
+    }
+
+    /// Returns the unstable production packages.
+    pub fn production(&self) -> impl Iterator<Item = &UnstablePackage> {
+        self.packages
+            .iter()
+            .filter(|package| package.dep_type == DependencyType::Production)
+    }
+
+    /// Returns the names of the packages with any instability, for marking
+    /// them in the tree.
+    pub fn names(&self) -> HashSet<String> {
+        self.packages.iter().map(|package| package.name.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unstable_packages() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "19.0.0-rc.1", DependencyType::Production);
+        graph.add_dependency("axios", "^0.27.2", DependencyType::Production);
+        graph.add_dependency("vitest", "2.0.0-beta.3", DependencyType::Development);
+        graph.add_dependency_with_depth("scheduler", "0.25.0-rc.1", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("nanoid", "0.4.1", DependencyType::Production, 1);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+
+        let report = PrereleaseReport::from_graph(&graph);
+        let found: Vec<(&str, Instability, bool)> = report
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.instability, p.direct))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("react", Instability::PreRelease, true),
+                ("vitest", Instability::PreRelease, true),
+                ("scheduler", Instability::PreRelease, false),
+                ("nanoid", Instability::ZeroMajor, false),
+            ],
+            "axios' range is unresolved without a lockfile"
+        );
+        assert_eq!(report.production().count(), 3);
+        assert!(report.names().contains("vitest"));
+    }
+}
@@ -0,0 +1,280 @@
+//! Simulation of removing a direct dependency.
+//!
+//! Uninstalling a package also removes every transitive dependency that
+//! nothing else needs. Packages shared with the rest of the graph stay
+//! installed. This module computes both sets from the resolved dependency
+//! graph so the effect of an uninstall can be previewed before running it.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::graph::DependencyGraph;
+use crate::parser::installed::{DiskUsage, PackageStore};
+
+/// A package that would be removed along with the dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedPackage {
+    /// Package name
+    pub name: String,
+    /// Version specification from the graph
+    pub version: String,
+    /// Bundle size in bytes, if known
+    pub bundle_size: Option<u64>,
+    /// Size on disk, if measured
+    pub disk_usage: Option<DiskUsage>,
+}
+
+/// A transitive dependency that stays installed because other packages
+/// still need it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainedPackage {
+    /// Package name
+    pub name: String,
+    /// Remaining packages that depend on it
+    pub needed_by: Vec<String>,
+}
+
+/// The effect of removing one direct dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovalImpact {
+    /// The direct dependency being removed
+    pub package: String,
+    /// Packages that disappear, the dependency itself first if it goes too
+    pub removed: Vec<RemovedPackage>,
+    /// Transitive dependencies kept by other packages, sorted by name
+    pub retained: Vec<RetainedPackage>,
+}
+
+impl RemovalImpact {
+    /// Total bundle bytes reclaimed by the packages with a known size.
+    pub fn reclaimed_bundle_bytes(&self) -> u64 {
+        self.removed.iter().filter_map(|p| p.bundle_size).sum()
+    }
+
+    /// Total disk bytes reclaimed by the packages that were measured.
+    pub fn reclaimed_disk_bytes(&self) -> u64 {
+        self.removed.iter().filter_map(|p| p.disk_usage).map(|u| u.bytes).sum()
+    }
+
+    /// Returns true if no removed package has a bundle size.
+    pub fn is_bundle_size_unknown(&self) -> bool {
+        self.removed.iter().all(|p| p.bundle_size.is_none())
+    }
+
+    /// Returns the command that performs the removal.
+    pub fn uninstall_command(&self) -> String {
+        format!("npm uninstall {}", self.package)
+    }
+}
+
+/// Simulates removing the direct dependency `name` from the graph.
+///
+/// A package is removed when it is reachable from `name` but from no other
+/// direct dependency. If another package still needs `name` itself, it is
+/// only dropped from `package.json` and listed as retained. Disk usage is
+/// left unmeasured; see [`simulate_removals`].
+///
+/// # Arguments
+///
+/// * `graph` - The resolved dependency graph
+/// * `name` - Name of a direct (depth 0) dependency
+///
+/// # Returns
+///
+/// `None` if `name` is not a direct dependency.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::simulate_removal;
+/// use codescope::graph::{DependencyGraph, DependencyType};
+///
+/// let mut graph = DependencyGraph::new();
+/// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+/// graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+/// graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+/// graph.add_edge("react-dom", "scheduler");
+///
+/// let impact = simulate_removal(&graph, "react-dom").unwrap();
+/// assert_eq!(impact.removed.len(), 2);
+/// assert!(impact.retained.is_empty());
+/// assert_eq!(impact.uninstall_command(), "npm uninstall react-dom");
+/// ```
+pub fn simulate_removal(graph: &DependencyGraph, name: &str) -> Option<RemovalImpact> {
+    if graph.get_node(name)?.depth != 0 {
+        return None;
+    }
+
+    let others: Vec<&str> = graph
+        .get_nodes_at_depth(0)
+        .into_iter()
+        .map(|node| node.name.as_str())
+        .filter(|other| *other != name)
+        .collect();
+    let remaining = reachable(graph, others);
+    let closure = reachable(graph, vec![name]);
+
+    let mut removed = Vec::new();
+    let mut retained = Vec::new();
+    for package in closure {
+        if !remaining.contains(package) {
+            let node = graph.get_node(package)?;
+            removed.push(RemovedPackage {
+                name: node.name.clone(),
+                version: node.version.clone(),
+                bundle_size: node.bundle_size,
+                disk_usage: None,
+            });
+        } else {
+            let mut needed_by: Vec<String> = graph
+                .get_dependents(package)
+                .into_iter()
+                .filter(|dependent| remaining.contains(dependent.name.as_str()))
+                .map(|dependent| dependent.name.clone())
+                .collect();
+            needed_by.sort();
+            needed_by.dedup();
+            retained.push(RetainedPackage {
+                name: package.to_string(),
+                needed_by,
+            });
+        }
+    }
+
+    removed.sort_by(|a, b| (a.name != name).cmp(&(b.name != name)).then_with(|| a.name.cmp(&b.name)));
+    retained.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Some(RemovalImpact {
+        package: name.to_string(),
+        removed,
+        retained,
+    })
+}
+
+/// Simulates removing each direct dependency of the graph.
+///
+/// With a `store`, the disk usage of every removed package installed in
+/// it is measured. Each package is measured once, however many direct
+/// dependencies share it.
+///
+/// # Returns
+///
+/// The impacts keyed by direct dependency name.
+pub fn simulate_removals(
+    graph: &DependencyGraph,
+    store: Option<&PackageStore>,
+) -> BTreeMap<String, RemovalImpact> {
+    let mut disk_usage: HashMap<String, Option<DiskUsage>> = HashMap::new();
+    let mut impacts = BTreeMap::new();
+
+    for node in graph.get_nodes_at_depth(0) {
+        let Some(mut impact) = simulate_removal(graph, &node.name) else {
+            continue;
+        };
+        if let Some(store) = store {
+            for package in &mut impact.removed {
+                package.disk_usage = *disk_usage.entry(package.name.clone()).or_insert_with(|| {
+                    store
+                        .locate(&package.name, None)
+                        .and_then(|location| location.disk_usage().ok())
+                });
+            }
+        }
+        impacts.insert(node.name.clone(), impact);
+    }
+
+    impacts
+}
+
+/// Returns the names reachable from `roots`, including the roots.
+fn reachable<'a>(graph: &'a DependencyGraph, roots: Vec<&'a str>) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    let mut queue: VecDeque<&str> = roots.into_iter().collect();
+
+    while let Some(name) = queue.pop_front() {
+        if !seen.insert(name) {
+            continue;
+        }
+        for dep in graph.get_dependencies(name) {
+            queue.push_back(dep.name.as_str());
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    /// react-dom -> scheduler, loose-envify; react -> loose-envify -> js-tokens
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+        graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("loose-envify", "1.4.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("js-tokens", "4.0.0", DependencyType::Production, 2);
+        graph.add_edge("react-dom", "scheduler");
+        graph.add_edge("react-dom", "loose-envify");
+        graph.add_edge("react", "loose-envify");
+        graph.add_edge("loose-envify", "js-tokens");
+        graph.get_node_mut("react-dom").unwrap().set_bundle_size(130_000, 10);
+        graph.get_node_mut("scheduler").unwrap().set_bundle_size(4_000, 2);
+        graph
+    }
+
+    fn names(impact: &RemovalImpact) -> Vec<&str> {
+        impact.removed.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_shared_dependencies_are_retained() {
+        let impact = simulate_removal(&graph(), "react-dom").unwrap();
+        assert_eq!(names(&impact), vec!["react-dom", "scheduler"]);
+        assert_eq!(impact.reclaimed_bundle_bytes(), 134_000);
+        assert_eq!(
+            impact.retained,
+            vec![
+                RetainedPackage {
+                    name: "js-tokens".to_string(),
+                    needed_by: vec!["loose-envify".to_string()],
+                },
+                RetainedPackage {
+                    name: "loose-envify".to_string(),
+                    needed_by: vec!["react".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_removes_whole_private_subtree() {
+        let mut graph = graph();
+        graph.add_edge("react-dom", "react");
+        let impact = simulate_removal(&graph, "react").unwrap();
+        // react stays installed because react-dom still needs it
+        assert!(impact.removed.is_empty());
+        let react = impact.retained.iter().find(|p| p.name == "react").unwrap();
+        assert_eq!(react.needed_by, vec!["react-dom"]);
+
+        let impact = simulate_removal(&graph, "react-dom").unwrap();
+        assert_eq!(names(&impact), vec!["react-dom", "scheduler"]);
+
+        let graph = self::graph();
+        let impact = simulate_removal(&graph, "react").unwrap();
+        assert_eq!(names(&impact), vec!["react"]);
+        assert!(impact.is_bundle_size_unknown());
+    }
+
+    #[test]
+    fn test_only_direct_dependencies() {
+        let graph = graph();
+        assert!(simulate_removal(&graph, "scheduler").is_none());
+        assert!(simulate_removal(&graph, "missing").is_none());
+
+        let impacts = simulate_removals(&graph, None);
+        assert_eq!(impacts.keys().collect::<Vec<_>>(), vec!["react", "react-dom"]);
+        assert_eq!(impacts["react"].reclaimed_disk_bytes(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Per-scope aggregation of package counts and sizes.
+//!
+//! Large vendors publish under one npm scope (`@babel/*`, `@aws-sdk/*`)
+//! and split their code into dozens of packages that each look small.
+//! Summing them per scope shows what a vendor costs as a whole.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::bundle::webpack::format_size;
+use crate::graph::DependencyGraph;
+
+/// Aggregated figures for one npm scope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScopeSummary {
+    /// The scope, including its `@`
+    pub scope: String,
+    /// Names of the packages in the scope, sorted
+    pub packages: Vec<String>,
+    /// Sum of the known bundle sizes in bytes
+    pub bundle_size: u64,
+    /// Number of packages with a known bundle size
+    pub sized_packages: usize,
+}
+
+impl ScopeSummary {
+    /// Returns the number of packages in the scope.
+    pub fn count(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns true if any package in the scope has a known bundle size.
+    pub fn has_sizes(&self) -> bool {
+        self.sized_packages > 0
+    }
+}
+
+/// Returns the scope of a package name, e.g. `@babel` for `@babel/core`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::scopes::scope_of;
+///
+/// assert_eq!(scope_of("@babel/core"), Some("@babel"));
+/// assert_eq!(scope_of("lodash"), None);
+/// ```
+pub fn scope_of(name: &str) -> Option<&str> {
+    let (scope, rest) = name.split_once('/')?;
+    (scope.starts_with('@') && scope.len() > 1 && !rest.is_empty()).then_some(scope)
+}
+
+/// Aggregates the scoped packages of the graph by scope.
+///
+/// Aliased packages count towards the scope of the installed package.
+/// Unscoped packages are left out.
+///
+/// # Returns
+///
+/// One summary per scope, largest known bundle size first, then by
+/// package count and name.
+pub fn summarize_scopes(graph: &DependencyGraph) -> Vec<ScopeSummary> {
+    let mut scopes: BTreeMap<&str, ScopeSummary> = BTreeMap::new();
+    for node in graph.get_all_nodes() {
+        let Some(scope) = scope_of(node.package_name()) else {
+            continue;
+        };
+        let summary = scopes.entry(scope).or_insert_with(|| ScopeSummary {
+            scope: scope.to_string(),
+            packages: Vec::new(),
+            bundle_size: 0,
+            sized_packages: 0,
+        });
+        summary.packages.push(node.name.clone());
+        if let Some(size) = node.bundle_size {
+            summary.bundle_size += size;
+            summary.sized_packages += 1;
+        }
+    }
+
+    let mut summaries: Vec<ScopeSummary> = scopes.into_values().collect();
+    for summary in &mut summaries {
+        summary.packages.sort();
+    }
+    summaries.sort_by(|a, b| {
+        b.bundle_size
+            .cmp(&a.bundle_size)
+            .then_with(|| b.count().cmp(&a.count()))
+            .then_with(|| a.scope.cmp(&b.scope))
+    });
+    summaries
+}
+
+/// Formats the scope summaries as a table.
+pub fn format_scopes(scopes: &[ScopeSummary]) -> String {
+    if scopes.is_empty() {
+        return "No scoped packages.\n".to_string();
+    }
+
+    let mut out = format!("🏢 {} npm scope(s)\n\n", scopes.len());
+    let width = scopes.iter().map(|s| s.scope.len()).max().unwrap_or(0).max("Scope".len());
+    out.push_str(&format!("  {:width$}  {:>8}  {:>10}\n", "Scope", "Packages", "Size", width = width));
+    for scope in scopes {
+        let size = if scope.has_sizes() {
+            format_size(scope.bundle_size)
+        } else {
+            "-".to_string()
+        };
+        out.push_str(&format!(
+            "  {:width$}  {:>8}  {:>10}\n",
+            scope.scope,
+            scope.count(),
+            size,
+            width = width
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_summarize_scopes() {
+        let mut graph = DependencyGraph::new();
+        for name in ["@babel/core", "@babel/parser", "@babel/types", "@types/node", "@aws-sdk/client-s3", "lodash"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        let mut sizes = HashMap::new();
+        sizes.insert("@babel/core".to_string(), (2048_u64, 1_usize));
+        sizes.insert("@babel/parser".to_string(), (1024_u64, 1_usize));
+        sizes.insert("@aws-sdk/client-s3".to_string(), (4096_u64, 1_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        let scopes = summarize_scopes(&graph);
+        let order: Vec<(&str, usize)> = scopes.iter().map(|s| (s.scope.as_str(), s.count())).collect();
+        assert_eq!(order, vec![("@aws-sdk", 1), ("@babel", 3), ("@types", 1)]);
+
+        let babel = &scopes[1];
+        assert_eq!(babel.bundle_size, 3072);
+        assert_eq!(babel.sized_packages, 2);
+        assert_eq!(babel.packages, vec!["@babel/core", "@babel/parser", "@babel/types"]);
+
+        let text = format_scopes(&scopes);
+        assert!(text.contains("  @babel           3     3.00 KB\n"));
+        assert!(text.contains("  @types           1           -\n"));
+    }
+
+    #[test]
+    fn test_scope_of() {
+        assert_eq!(scope_of("@aws-sdk/client-s3"), Some("@aws-sdk"));
+        assert_eq!(scope_of("@/alias"), None);
+        assert_eq!(scope_of("@scope/"), None);
+        assert_eq!(scope_of("lodash/fp"), None);
+    }
+}
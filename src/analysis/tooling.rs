@@ -0,0 +1,227 @@
+//! What the development tooling costs to install.
+//!
+//! devDependencies never reach the bundle, so the bundle reports leave them
+//! out, yet compilers, test runners and linters usually make up most of
+//! `node_modules` and dominate install time and CI cache size. This module
+//! narrows an install footprint to the packages only development needs and
+//! checks it against the project's [`ToolingBudget`].
+
+use crate::bundle::webpack::format_size;
+use crate::config::ToolingBudget;
+use crate::graph::{DependencyGraph, DependencyType};
+
+use super::footprint::FootprintReport;
+
+/// Bytes in the MB of [`ToolingBudget::max_install_mb`].
+const MB: u64 = 1024 * 1024;
+
+/// A threshold of the tooling budget and where the report stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetCheck {
+    /// What is measured, e.g. `Install size`
+    pub label: &'static str,
+    /// Measured value, formatted
+    pub actual: String,
+    /// Threshold, formatted
+    pub limit: String,
+    /// Whether the measured value exceeds the threshold
+    pub exceeded: bool,
+}
+
+/// The install cost of the development-only packages of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolingReport {
+    /// Footprint of the development-only packages
+    pub footprint: FootprintReport,
+    /// Unpacked size of every measured package, development or not
+    pub install_bytes: u64,
+}
+
+impl ToolingReport {
+    /// Keeps the packages of a footprint report that the graph marks as
+    /// development-only, in the report's order.
+    ///
+    /// Packages a production dependency also needs are left out: removing
+    /// the tooling would not uninstall them.
+    pub fn from_footprint(footprint: &FootprintReport, graph: &DependencyGraph) -> Self {
+        let is_tooling = |name: &str| {
+            graph
+                .get_node(name)
+                .is_some_and(|node| node.dep_type == DependencyType::Development)
+        };
+        Self {
+            footprint: FootprintReport {
+                packages: footprint
+                    .packages
+                    .iter()
+                    .filter(|p| is_tooling(&p.name))
+                    .cloned()
+                    .collect(),
+                missing: footprint.missing.iter().filter(|name| is_tooling(name)).cloned().collect(),
+            },
+            install_bytes: footprint.total_bytes(),
+        }
+    }
+
+    /// Returns the number of packages building a native addon.
+    pub fn native_count(&self) -> usize {
+        self.footprint.packages.iter().filter(|p| p.footprint.native).count()
+    }
+
+    /// Returns the number of packages declaring install scripts.
+    pub fn script_count(&self) -> usize {
+        self.footprint
+            .packages
+            .iter()
+            .filter(|p| !p.footprint.install_scripts.is_empty())
+            .count()
+    }
+
+    /// Checks every threshold the budget sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::tooling::ToolingReport;
+    /// use codescope::config::ProjectConfig;
+    ///
+    /// let config = ProjectConfig::parse(r#"{"tooling": {"maxNative": 0}}"#).unwrap();
+    /// let checks = ToolingReport::default().check(&config.tooling);
+    /// assert_eq!(checks.len(), 1);
+    /// assert!(!checks[0].exceeded);
+    /// ```
+    pub fn check(&self, budget: &ToolingBudget) -> Vec<BudgetCheck> {
+        let mut checks = Vec::new();
+        if let Some(max) = budget.max_install_mb {
+            let bytes = self.footprint.total_bytes();
+            checks.push(BudgetCheck {
+                label: "Install size",
+                actual: format_size(bytes),
+                limit: format!("{} MB", max),
+                exceeded: bytes > max * MB,
+            });
+        }
+        let counts = [
+            ("Files", self.footprint.total_files(), budget.max_files),
+            ("Native builds", self.native_count(), budget.max_native),
+            ("Install scripts", self.script_count(), budget.max_install_scripts),
+        ];
+        for (label, count, max) in counts {
+            if let Some(max) = max {
+                checks.push(BudgetCheck {
+                    label,
+                    actual: count.to_string(),
+                    limit: max.to_string(),
+                    exceeded: count > max,
+                });
+            }
+        }
+        checks
+    }
+
+    /// Returns true if the report exceeds a threshold of the budget.
+    pub fn exceeds(&self, budget: &ToolingBudget) -> bool {
+        self.check(budget).iter().any(|check| check.exceeded)
+    }
+
+    /// Formats the totals, the budget checks, and a table of the first
+    /// `top` packages followed by every package that runs install code.
+    pub fn format_report(&self, top: usize, budget: &ToolingBudget) -> String {
+        if self.footprint.packages.is_empty() {
+            return "🧰 No installed development-only packages found.\n".to_string();
+        }
+
+        let bytes = self.footprint.total_bytes();
+        let mut out = format!(
+            "🧰 Tooling weight: {} in {} files across {} development-only packages",
+            format_size(bytes),
+            self.footprint.total_files(),
+            self.footprint.packages.len()
+        );
+        if self.install_bytes > 0 {
+            out.push_str(&format!(
+                " ({:.0}% of node_modules)",
+                bytes as f64 * 100.0 / self.install_bytes as f64
+            ));
+        }
+        out.push_str(&format!(
+            "\n   {} native build(s), {} package(s) with install scripts\n\n",
+            self.native_count(),
+            self.script_count()
+        ));
+
+        let checks = self.check(budget);
+        if !checks.is_empty() {
+            for check in &checks {
+                let (icon, verb) = if check.exceeded { ("❌", "exceeds") } else { ("✅", "is within") };
+                out.push_str(&format!(
+                    "{} {} {} {} the budget of {}\n",
+                    icon, check.label, check.actual, verb, check.limit
+                ));
+            }
+            out.push('\n');
+        }
+
+        out.push_str(&self.footprint.format_packages(top));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::footprint::{InstallFootprint, PackageFootprint};
+
+    fn package(name: &str, bytes: u64, files: usize, native: bool, scripts: &[&str]) -> PackageFootprint {
+        PackageFootprint {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            footprint: InstallFootprint {
+                bytes,
+                files,
+                native,
+                install_scripts: scripts.iter().map(|s| s.to_string()).collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_tooling_report_keeps_dev_only_packages() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        for name in ["typescript", "esbuild", "fsevents", "jest"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Development);
+        }
+        let footprint = FootprintReport {
+            packages: vec![
+                package("typescript", 3 * MB, 200, false, &[]),
+                package("esbuild", 2 * MB, 10, false, &["postinstall"]),
+                package("react", MB, 40, false, &[]),
+                package("fsevents", 0, 5, true, &[]),
+            ],
+            missing: vec!["jest".to_string(), "react-dom".to_string()],
+        };
+
+        let report = ToolingReport::from_footprint(&footprint, &graph);
+        let names: Vec<&str> = report.footprint.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["typescript", "esbuild", "fsevents"]);
+        assert_eq!(report.footprint.missing, vec!["jest"]);
+        assert_eq!((report.native_count(), report.script_count()), (1, 1));
+
+        let budget = ToolingBudget {
+            max_install_mb: Some(4),
+            max_native: Some(0),
+            ..Default::default()
+        };
+        let checks = report.check(&budget);
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].exceeded, "5 MB of tooling");
+        assert!(checks[1].exceeded, "fsevents builds natively");
+        assert!(!report.exceeds(&ToolingBudget::default()));
+
+        let text = report.format_report(10, &budget);
+        assert!(text.contains("across 3 development-only packages (83% of node_modules)"));
+        assert!(text.contains("1 native build(s), 1 package(s) with install scripts"));
+        assert!(text.contains("❌ Native builds 1 exceeds the budget of 0\n"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Audit of `@types/*` packages.
+//!
+//! DefinitelyTyped packages only make sense next to the package they
+//! describe. They linger after that package is removed, and many packages
+//! have since started shipping their own declarations, which then take
+//! precedence and leave the `@types/*` package unused.
+
+use serde::Serialize;
+
+use crate::bundle::savings::{PackageSavings, SavingsCategory};
+use crate::parser::installed::{PackageLocation, PackageStore};
+use crate::parser::Dependency;
+
+/// Type packages for runtimes, browser APIs and specifications, which
+/// have no npm package of the same name.
+const AMBIENT_TYPES: [&str; 9] = [
+    "node", "bun", "web", "chrome", "estree", "json-schema", "unist", "hast", "mdast",
+];
+
+/// Why a `@types/*` package can be removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypesIssue {
+    /// The package it describes is not a dependency
+    Unpaired,
+    /// The package it describes ships its own declarations
+    Bundled,
+}
+
+/// A removable `@types/*` package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypesFinding {
+    /// The type package, e.g. `@types/react-redux`
+    pub types_package: String,
+    /// The package it describes, e.g. `react-redux`
+    pub target: String,
+    /// Why it can be removed
+    pub issue: TypesIssue,
+}
+
+impl TypesFinding {
+    /// Describes what to do about the finding.
+    pub fn suggestion(&self) -> String {
+        match self.issue {
+            TypesIssue::Unpaired => format!(
+                "{} is not a dependency; remove {}",
+                self.target, self.types_package
+            ),
+            TypesIssue::Bundled => format!(
+                "{} ships its own types; remove {}",
+                self.target, self.types_package
+            ),
+        }
+    }
+}
+
+/// The result of auditing a project's `@types/*` dependencies.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypesAudit {
+    /// Removable type packages, sorted by name
+    pub findings: Vec<TypesFinding>,
+    /// Number of `@types/*` dependencies checked
+    pub checked: usize,
+    /// Whether installed packages were inspected for bundled declarations
+    pub inspected: bool,
+}
+
+/// Returns the package a `@types/*` package describes.
+///
+/// Scoped packages are encoded with a double underscore.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::analysis::types_audit::types_target;
+///
+/// assert_eq!(types_target("@types/react"), Some("react".to_string()));
+/// assert_eq!(types_target("@types/babel__core"), Some("@babel/core".to_string()));
+/// assert_eq!(types_target("react"), None);
+/// ```
+pub fn types_target(name: &str) -> Option<String> {
+    let target = name.strip_prefix("@types/").filter(|t| !t.is_empty())?;
+    Some(match target.split_once("__") {
+        Some((scope, package)) => format!("@{}/{}", scope, package),
+        None => target.to_string(),
+    })
+}
+
+/// Returns true if an installed package declares or contains its own
+/// type declarations.
+pub fn ships_types(location: &PackageLocation) -> bool {
+    let manifest: serde_json::Value = match location
+        .read_to_string("package.json")
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+    {
+        Some(manifest) => manifest,
+        None => return false,
+    };
+    ["types", "typings"]
+        .iter()
+        .any(|field| manifest.get(*field).is_some_and(|v| v.is_string()))
+        || manifest.get("exports").is_some_and(has_types_condition)
+        || location.read_to_string("index.d.ts").is_ok()
+}
+
+/// Returns true if an `exports` map has a `types` condition anywhere.
+fn has_types_condition(exports: &serde_json::Value) -> bool {
+    match exports {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .any(|(key, value)| key == "types" || has_types_condition(value)),
+        serde_json::Value::Array(values) => values.iter().any(has_types_condition),
+        _ => false,
+    }
+}
+
+impl TypesAudit {
+    /// Audits the `@types/*` dependencies of a package.json.
+    ///
+    /// # Arguments
+    ///
+    /// * `deps` - The project's direct dependencies, of every type
+    /// * `store` - Installed packages, inspected for bundled declarations;
+    ///   without it only unpaired type packages are found
+    pub fn audit(deps: &[Dependency], store: Option<&PackageStore>) -> Self {
+        let mut audit = Self {
+            inspected: store.is_some(),
+            ..Self::default()
+        };
+        for dep in deps {
+            let Some(target) = types_target(dep.package_name()) else {
+                continue;
+            };
+            if AMBIENT_TYPES.contains(&target.as_str()) {
+                continue;
+            }
+            audit.checked += 1;
+
+            let issue = if !deps.iter().any(|d| d.package_name() == target) {
+                Some(TypesIssue::Unpaired)
+            } else {
+                store
+                    .and_then(|store| store.locate(&target, None))
+                    .filter(ships_types)
+                    .map(|_| TypesIssue::Bundled)
+            };
+            if let Some(issue) = issue {
+                audit.findings.push(TypesFinding {
+                    types_package: dep.name.clone(),
+                    target,
+                    issue,
+                });
+            }
+        }
+        audit.findings.sort_by(|a, b| a.types_package.cmp(&b.types_package));
+        audit
+    }
+
+    /// Returns true if no type package can be removed.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Converts the findings to savings report entries.
+    ///
+    /// Type packages have no runtime cost, so the entries carry no bundle
+    /// size.
+    pub fn to_savings(&self) -> Vec<PackageSavings> {
+        self.findings
+            .iter()
+            .map(|finding| PackageSavings {
+                package_name: finding.types_package.clone(),
+                current_size: 0,
+                potential_savings: 0,
+                category: SavingsCategory::RemovableTypes,
+                utilization_percentage: None,
+                exports_used: 0,
+                total_exports: None,
+                suggestion: finding.suggestion(),
+                alternative: None,
+            })
+            .collect()
+    }
+
+    /// Formats the findings as a listing.
+    pub fn format_report(&self) -> String {
+        let mut out = String::new();
+        if !self.inspected {
+            out.push_str("⚠️  Installed packages were not inspected; run npm install to find bundled types.\n\n");
+        }
+        if self.is_clean() {
+            out.push_str(&format!("✅ All {} @types package(s) are needed.\n", self.checked));
+            return out;
+        }
+
+        out.push_str(&format!(
+            "🧹 {} of {} @types package(s) can be removed:\n",
+            self.findings.len(),
+            self.checked
+        ));
+        for finding in &self.findings {
+            out.push_str(&format!("  {}: {}\n", finding.types_package, finding.suggestion()));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DependencyType;
+    use crate::test_support::install;
+    use std::fs;
+
+    #[test]
+    fn test_types_audit() {
+        let root = std::env::temp_dir().join(format!("codescope-types-audit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        install(&root, "react", "{}", &[]);
+        install(&root, "react-redux", r#"{"types": "es/index.d.ts"}"#, &[]);
+        install(&root, "@babel/core", r#"{"exports": {".": {"types": "./index.d.ts", "default": "./index.js"}}}"#, &[]);
+        install(&root, "lodash", "{}", &[("index.d.ts", "")]);
+
+        let deps = vec![
+            Dependency::new("react", "^18.0.0", DependencyType::Production),
+            Dependency::new("react-redux", "^9.0.0", DependencyType::Production),
+            Dependency::new("@babel/core", "^7.0.0", DependencyType::Development),
+            Dependency::new("lodash", "^4.17.21", DependencyType::Production),
+            Dependency::new("@types/react", "^18.0.0", DependencyType::Development),
+            Dependency::new("@types/react-redux", "^7.1.0", DependencyType::Development),
+            Dependency::new("@types/babel__core", "^7.0.0", DependencyType::Development),
+            Dependency::new("@types/lodash", "^4.0.0", DependencyType::Development),
+            Dependency::new("@types/express", "^4.0.0", DependencyType::Development),
+            Dependency::new("@types/node", "^20.0.0", DependencyType::Development),
+        ];
+        let store = PackageStore::NodeModules(root.join("node_modules"));
+        let audit = TypesAudit::audit(&deps, Some(&store));
+
+        assert_eq!(audit.checked, 5, "@types/node describes the runtime");
+        let findings: Vec<(&str, TypesIssue)> = audit
+            .findings
+            .iter()
+            .map(|f| (f.types_package.as_str(), f.issue))
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                ("@types/babel__core", TypesIssue::Bundled),
+                ("@types/express", TypesIssue::Unpaired),
+                ("@types/lodash", TypesIssue::Bundled),
+                ("@types/react-redux", TypesIssue::Bundled),
+            ]
+        );
+
+        let savings = audit.to_savings();
+        assert_eq!(savings[1].suggestion, "express is not a dependency; remove @types/express");
+        assert!(savings.iter().all(|s| s.category == SavingsCategory::RemovableTypes));
+
+        let text = audit.format_report();
+        assert!(text.starts_with("🧹 4 of 5 @types package(s) can be removed:\n"));
+        assert!(text.contains("  @types/react-redux: react-redux ships its own types; remove @types/react-redux\n"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_without_node_modules() {
+        let deps = vec![
+            Dependency::new("react", "^18.0.0", DependencyType::Production),
+            Dependency::new("@types/react", "^18.0.0", DependencyType::Development),
+        ];
+        let audit = TypesAudit::audit(&deps, None);
+        assert!(audit.is_clean());
+        assert!(audit.format_report().ends_with("✅ All 1 @types package(s) are needed.\n"));
+    }
+}
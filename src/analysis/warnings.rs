@@ -0,0 +1,176 @@
+//! Problems with optional inputs that did not stop the analysis.
+//!
+//! An unreadable source file, a stats file that cannot be parsed or a
+//! lockfile entry someone edited by hand leave the rest of the project
+//! analyzable. Instead of aborting, the analysis carries on without that
+//! input and records what was left out in [`AnalysisWarnings`], which the
+//! TUI shows in its notification area and the exports list in a warnings
+//! section.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::parser::ParseWarning;
+
+/// An input, or part of one, the analysis went without.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnalysisWarning {
+    /// File or input the problem is in (e.g. `package-lock.json`)
+    pub source: String,
+    /// Entry within the source, for parsers that skipped a single entry
+    pub location: Option<String>,
+    /// What went wrong and what was left out
+    pub message: String,
+}
+
+impl fmt::Display for AnalysisWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}: {}", self.source, location, self.message),
+            None => write!(f, "{}: {}", self.source, self.message),
+        }
+    }
+}
+
+/// The warnings collected over one analysis, in the order they occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalysisWarnings {
+    warnings: Vec<AnalysisWarning>,
+}
+
+impl AnalysisWarnings {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a problem with a whole input.
+    pub fn push(&mut self, source: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(AnalysisWarning {
+            source: source.into(),
+            location: None,
+            message: message.into(),
+        });
+    }
+
+    /// Records the entries a parser skipped in `source`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::AnalysisWarnings;
+    /// use codescope::parser::ParseWarning;
+    ///
+    /// let mut warnings = AnalysisWarnings::new();
+    /// warnings.extend_from_parse("stats.json", &[ParseWarning::new("modules[3]", "skipped, missing field `name`")]);
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(
+    ///     warnings.iter().next().unwrap().to_string(),
+    ///     "stats.json: modules[3]: skipped, missing field `name`"
+    /// );
+    /// ```
+    pub fn extend_from_parse(&mut self, source: &str, warnings: &[ParseWarning]) {
+        self.warnings.extend(warnings.iter().map(|warning| AnalysisWarning {
+            source: source.to_string(),
+            location: Some(warning.location.clone()),
+            message: warning.message.clone(),
+        }));
+    }
+
+    /// Appends every warning of `other`.
+    pub fn append(&mut self, other: AnalysisWarnings) {
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Appends every warning of `other`, collected for the project in
+    /// `dir`, with `dir` put in front of their sources.
+    pub fn append_in(&mut self, dir: &str, other: AnalysisWarnings) {
+        self.warnings.extend(other.warnings.into_iter().map(|mut warning| {
+            warning.source = format!("{}/{}", dir, warning.source);
+            warning
+        }));
+    }
+
+    /// Returns true if nothing was left out.
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Number of warnings.
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// Iterates over the warnings in the order they occurred.
+    pub fn iter(&self) -> std::slice::Iter<'_, AnalysisWarning> {
+        self.warnings.iter()
+    }
+
+    /// Returns the warnings as a slice.
+    pub fn as_slice(&self) -> &[AnalysisWarning] {
+        &self.warnings
+    }
+
+    /// Formats the warnings for the terminal, listing at most `limit` and
+    /// summarizing the rest. Empty when there are no warnings.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Number of warnings listed before the rest are counted
+    pub fn format_report(&self, limit: usize) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut out = format!(
+            "⚠️  {} {}; results may be incomplete:\n",
+            self.len(),
+            if self.len() == 1 { "warning" } else { "warnings" }
+        );
+        for warning in self.warnings.iter().take(limit) {
+            out.push_str(&format!("   {}\n", warning));
+        }
+        if self.len() > limit {
+            out.push_str(&format!("   ... and {} more\n", self.len() - limit));
+        }
+        out
+    }
+}
+
+impl<'a> IntoIterator for &'a AnalysisWarnings {
+    type Item = &'a AnalysisWarning;
+    type IntoIter = std::slice::Iter<'a, AnalysisWarning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_report() {
+        let mut warnings = AnalysisWarnings::new();
+        assert_eq!(warnings.format_report(5), "");
+
+        warnings.push("stats.json", "Failed to read webpack stats: not found; bundle sizes are unavailable");
+        warnings.extend_from_parse(
+            "package-lock.json",
+            &[
+                ParseWarning::new("packages[\"node_modules/a\"]", "skipped, invalid type"),
+                ParseWarning::new("packages[\"node_modules/b\"]", "skipped, invalid type"),
+            ],
+        );
+        assert_eq!(warnings.len(), 3);
+
+        let report = warnings.format_report(2);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "⚠️  3 warnings; results may be incomplete:");
+        assert!(lines[1].starts_with("   stats.json: Failed to read webpack stats"));
+        assert_eq!(lines[2], "   package-lock.json: packages[\"node_modules/a\"]: skipped, invalid type");
+        assert_eq!(lines[3], "   ... and 1 more");
+    }
+}
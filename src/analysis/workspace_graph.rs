@@ -0,0 +1,372 @@
+//! The dependency graph between the packages of a monorepo.
+//!
+//! Only workspace-internal dependencies are kept: which workspace package
+//! depends on which sibling. The graph is used to detect cycles between
+//! packages, to derive a build order in which every package comes after
+//! the siblings it depends on, and to find the packages affected by a set
+//! of changed files.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::graph::{CycleInfo, DependencyEdge, DependencyGraph, DependencyType};
+use crate::parser::Workspace;
+
+/// A dependency of a workspace package on a sibling.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InternalDependency {
+    /// Name of the sibling package
+    pub name: String,
+    /// Dependency list that declares it
+    #[serde(rename = "type")]
+    pub dep_type: String,
+}
+
+/// A workspace package and its internal dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspacePackage {
+    /// Package name
+    pub name: String,
+    /// Folder relative to the monorepo root
+    pub path: String,
+    /// Siblings this package depends on, sorted by name
+    pub dependencies: Vec<InternalDependency>,
+}
+
+/// Packages grouped into stages that can be built in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BuildOrder {
+    /// Each stage only depends on earlier stages, so the packages of one
+    /// stage can be built in parallel
+    pub stages: Vec<Vec<String>>,
+    /// Packages that cannot be ordered because they are in, or depend on,
+    /// a cycle
+    pub blocked: Vec<String>,
+}
+
+impl BuildOrder {
+    /// Returns true if every package could be ordered.
+    pub fn is_complete(&self) -> bool {
+        self.blocked.is_empty()
+    }
+}
+
+/// Root files whose changes affect every workspace package, since they
+/// decide what gets installed.
+const ROOT_FILES: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+];
+
+/// Workspace packages affected by a set of changed files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AffectedPackages {
+    /// Packages containing a changed file, sorted
+    pub changed: Vec<String>,
+    /// Packages depending on a changed package, directly or through
+    /// siblings, sorted
+    pub dependents: Vec<String>,
+    /// Changed root manifests or lockfiles, which affect every package
+    pub root_files: Vec<String>,
+}
+
+impl AffectedPackages {
+    /// Returns every affected package, sorted.
+    pub fn all(&self) -> Vec<&str> {
+        let mut all: Vec<&str> = self.changed.iter().chain(&self.dependents).map(String::as_str).collect();
+        all.sort();
+        all
+    }
+
+    /// Returns true if no package is affected.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.dependents.is_empty()
+    }
+}
+
+/// The graph of workspace-internal dependencies.
+#[derive(Debug, Clone)]
+pub struct WorkspaceGraph {
+    packages: Vec<WorkspacePackage>,
+    graph: DependencyGraph,
+}
+
+impl WorkspaceGraph {
+    /// Builds the internal graph of a monorepo.
+    ///
+    /// Dependencies of every type count, since a package's dev dependencies
+    /// on siblings must also be built first.
+    pub fn from_workspace(workspace: &Workspace) -> Self {
+        let mut graph = DependencyGraph::new();
+        for member in &workspace.members {
+            let version = member.package.version.as_deref().unwrap_or("0.0.0");
+            graph.add_dependency(&member.name, version, DependencyType::Production);
+        }
+
+        let mut packages = Vec::new();
+        for member in &workspace.members {
+            let mut dependencies: Vec<InternalDependency> = member
+                .dependencies()
+                .into_iter()
+                .filter(|dep| dep.name != member.name && workspace.is_member(&dep.name))
+                .map(|dep| {
+                    let dep_type = DependencyType::from(dep.dep_type);
+                    let edge = DependencyEdge::of_type(dep_type).with_requested(dep.version.as_str());
+                    graph.add_edge_with_metadata(&member.name, &dep.name, edge);
+                    InternalDependency {
+                        name: dep.name,
+                        dep_type: dep_type.to_string(),
+                    }
+                })
+                .collect();
+            dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+            dependencies.dedup_by(|a, b| a.name == b.name);
+
+            packages.push(WorkspacePackage {
+                name: member.name.clone(),
+                path: member.path.clone(),
+                dependencies,
+            });
+        }
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self { packages, graph }
+    }
+
+    /// Returns the workspace packages, sorted by name.
+    pub fn packages(&self) -> &[WorkspacePackage] {
+        &self.packages
+    }
+
+    /// Returns the number of internal dependencies.
+    pub fn edge_count(&self) -> usize {
+        self.packages.iter().map(|p| p.dependencies.len()).sum()
+    }
+
+    /// Returns the names of the packages that depend on `name`, sorted.
+    pub fn dependents_of(&self, name: &str) -> Vec<&str> {
+        self.packages
+            .iter()
+            .filter(|p| p.dependencies.iter().any(|d| d.name == name))
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    /// Finds the packages affected by changed files.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - Changed files, `/`-separated and relative to the monorepo
+    ///   root
+    ///
+    /// # Returns
+    ///
+    /// The packages containing a changed file and, transitively, every
+    /// package that depends on them. A file belongs to the deepest package
+    /// folder containing it; other files are ignored, except root manifests
+    /// and lockfiles, which mark every package as changed.
+    pub fn affected_by(&self, files: &[String]) -> AffectedPackages {
+        let root_files: Vec<String> = files
+            .iter()
+            .filter(|file| ROOT_FILES.contains(&file.as_str()))
+            .cloned()
+            .collect();
+
+        let changed: BTreeSet<&str> = if root_files.is_empty() {
+            files
+                .iter()
+                .filter_map(|file| {
+                    self.packages
+                        .iter()
+                        .filter(|p| file.strip_prefix(p.path.as_str()).is_some_and(|rest| rest.starts_with('/')))
+                        .max_by_key(|p| p.path.len())
+                })
+                .map(|p| p.name.as_str())
+                .collect()
+        } else {
+            self.packages.iter().map(|p| p.name.as_str()).collect()
+        };
+
+        let mut affected = changed.clone();
+        let mut queue: Vec<&str> = changed.iter().copied().collect();
+        while let Some(name) = queue.pop() {
+            for dependent in self.dependents_of(name) {
+                if affected.insert(dependent) {
+                    queue.push(dependent);
+                }
+            }
+        }
+
+        AffectedPackages {
+            changed: changed.iter().map(|name| name.to_string()).collect(),
+            dependents: affected.difference(&changed).map(|name| name.to_string()).collect(),
+            root_files,
+        }
+    }
+
+    /// Returns the cycles between workspace packages.
+    pub fn cycles(&self) -> Vec<CycleInfo> {
+        self.graph.get_cycle_details()
+    }
+
+    /// Orders the packages so that each comes after its internal
+    /// dependencies, in stages of packages that can build in parallel.
+    pub fn build_order(&self) -> BuildOrder {
+        let mut remaining: BTreeMap<&str, BTreeSet<&str>> = self
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.dependencies.iter().map(|d| d.name.as_str()).collect()))
+            .collect();
+
+        let mut order = BuildOrder::default();
+        loop {
+            let stage: Vec<&str> = remaining
+                .iter()
+                .filter(|(_, deps)| deps.is_empty())
+                .map(|(name, _)| *name)
+                .collect();
+            if stage.is_empty() {
+                break;
+            }
+            for name in &stage {
+                remaining.remove(name);
+            }
+            for deps in remaining.values_mut() {
+                for name in &stage {
+                    deps.remove(name);
+                }
+            }
+            order.stages.push(stage.into_iter().map(String::from).collect());
+        }
+        order.blocked = remaining.into_keys().map(String::from).collect();
+        order
+    }
+}
+
+/// Formats the internal graph, its cycles and the build order.
+pub fn format_workspace_graph(graph: &WorkspaceGraph) -> String {
+    let mut out = format!(
+        "🔗 Workspace graph ({} packages, {} internal dependencies)\n\n",
+        graph.packages().len(),
+        graph.edge_count()
+    );
+    for package in graph.packages() {
+        out.push_str(&format!("{} ({})\n", package.name, package.path));
+        for dep in &package.dependencies {
+            let suffix = if dep.dep_type == "production" {
+                String::new()
+            } else {
+                format!(" ({})", dep.dep_type)
+            };
+            out.push_str(&format!("  -> {}{}\n", dep.name, suffix));
+        }
+    }
+
+    let cycles = graph.cycles();
+    if !cycles.is_empty() {
+        out.push_str(&format!("\n❌ {} cycle(s) between workspace packages:\n", cycles.len()));
+        for cycle in &cycles {
+            out.push_str(&format!("  {}\n", cycle.cycle_path()));
+        }
+    }
+
+    let order = graph.build_order();
+    out.push_str("\nBuild order:\n");
+    for (i, stage) in order.stages.iter().enumerate() {
+        out.push_str(&format!("  {}. {}\n", i + 1, stage.join(", ")));
+    }
+    if !order.is_complete() {
+        out.push_str(&format!("  Blocked by cycles: {}\n", order.blocked.join(", ")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::workspace::tests::create_workspace;
+
+    fn load(name: &str, members: &[(&str, &str)]) -> WorkspaceGraph {
+        let dir = create_workspace(name, r#"{"workspaces": ["packages/*", "packages/*/testing"]}"#, members);
+        let workspace = Workspace::load(&dir).unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        WorkspaceGraph::from_workspace(&workspace)
+    }
+
+    #[test]
+    fn test_build_order() {
+        let graph = load(
+            "order",
+            &[
+                ("packages/utils", r#"{"name": "utils", "dependencies": {"lodash": "^4"}}"#),
+                ("packages/ui", r#"{"name": "ui", "dependencies": {"utils": "*"}}"#),
+                ("packages/api", r#"{"name": "api", "dependencies": {"utils": "*"}}"#),
+                ("packages/web", r#"{"name": "web", "dependencies": {"ui": "*"}, "devDependencies": {"api": "*"}}"#),
+            ],
+        );
+        assert_eq!(graph.edge_count(), 4, "lodash is external");
+        assert_eq!(graph.dependents_of("utils"), vec!["api", "ui"]);
+        assert!(graph.cycles().is_empty());
+
+        let order = graph.build_order();
+        assert_eq!(order.stages, vec![vec!["utils"], vec!["api", "ui"], vec!["web"]]);
+        assert!(order.is_complete());
+
+        let report = format_workspace_graph(&graph);
+        assert!(report.contains("web (packages/web)\n  -> api (dev)\n  -> ui\n"));
+        assert!(report.contains("  2. api, ui\n"));
+    }
+
+    #[test]
+    fn test_affected_by() {
+        let graph = load(
+            "affected",
+            &[
+                ("packages/utils", r#"{"name": "utils"}"#),
+                ("packages/utils/testing", r#"{"name": "utils-testing"}"#),
+                ("packages/ui", r#"{"name": "ui", "dependencies": {"utils": "*"}}"#),
+                ("packages/web", r#"{"name": "web", "devDependencies": {"ui": "*"}}"#),
+                ("packages/docs", r#"{"name": "docs"}"#),
+            ],
+        );
+        let files = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        let affected = graph.affected_by(&files(&["packages/utils/src/index.js", "README.md", "packages/uix.js"]));
+        assert_eq!(affected.changed, vec!["utils"]);
+        assert_eq!(affected.dependents, vec!["ui", "web"], "transitively, through dev dependencies too");
+        assert_eq!(affected.all(), vec!["ui", "utils", "web"]);
+
+        let nested = graph.affected_by(&files(&["packages/utils/testing/index.js"]));
+        assert_eq!(nested.changed, vec!["utils-testing"], "the deepest package owns the file");
+        assert!(nested.dependents.is_empty());
+
+        assert!(graph.affected_by(&files(&["docs/guide.md"])).is_empty());
+
+        let lockfile = graph.affected_by(&files(&["package-lock.json"]));
+        assert_eq!(lockfile.root_files, vec!["package-lock.json"]);
+        assert_eq!(lockfile.all().len(), 5);
+    }
+
+    #[test]
+    fn test_cycles_block_build_order() {
+        let graph = load(
+            "cycle",
+            &[
+                ("packages/a", r#"{"name": "a", "dependencies": {"b": "*"}}"#),
+                ("packages/b", r#"{"name": "b", "dependencies": {"a": "*"}}"#),
+                ("packages/c", r#"{"name": "c", "dependencies": {"a": "*"}}"#),
+                ("packages/d", r#"{"name": "d"}"#),
+            ],
+        );
+        assert_eq!(graph.cycles().len(), 1);
+
+        let order = graph.build_order();
+        assert_eq!(order.stages, vec![vec!["d"]]);
+        assert_eq!(order.blocked, vec!["a", "b", "c"]);
+        assert!(format_workspace_graph(&graph).contains("Blocked by cycles: a, b, c"));
+    }
+}
@@ -0,0 +1,510 @@
+//! Stats generation for projects without a stats file.
+//!
+//! Detects the project's bundler, runs it with the flags that make it
+//! write build statistics, and converts the result to webpack's stats
+//! format so the rest of the bundle analysis can read it.
+//!
+//! | Bundler | Command                                              | Output            |
+//! |---------|------------------------------------------------------|-------------------|
+//! | webpack | `webpack --profile --json=<file>`                    | webpack stats     |
+//! | Vite    | `vite-bundle-visualizer --template raw-data`         | visualizer data   |
+//! | esbuild | `esbuild <entry> --bundle --metafile=<file>`         | esbuild metafile  |
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::bundle::webpack::{ChunkId, ModuleReason, WebpackAsset, WebpackChunk, WebpackModule, WebpackStats};
+use crate::parser::paths::to_slash;
+use crate::parser::recovery::SyntaxError;
+
+/// Entry points esbuild is pointed at when package.json names none.
+const ENTRY_CANDIDATES: [&str; 6] = [
+    "src/index.ts",
+    "src/index.tsx",
+    "src/index.js",
+    "src/index.jsx",
+    "src/main.ts",
+    "src/main.js",
+];
+
+/// Errors that can occur while generating stats.
+#[derive(Error, Debug)]
+pub enum BuildStatsError {
+    /// No supported bundler was found in the project.
+    #[error("No webpack, Vite or esbuild setup found in {}", .0.display())]
+    NoBundler(PathBuf),
+
+    /// esbuild needs an entry point and none was found.
+    #[error("No entry point found for esbuild; set \"source\" or \"main\" in package.json")]
+    NoEntryPoint,
+
+    /// The bundler could not be run.
+    #[error("Failed to run {program}: {source}")]
+    Spawn { program: String, source: io::Error },
+
+    /// The bundler exited with an error.
+    #[error("{bundler} build failed:\n{output}")]
+    Failed { bundler: Bundler, output: String },
+
+    /// The bundler's output could not be read.
+    #[error("Failed to read the {bundler} stats: {source}")]
+    Stats { bundler: Bundler, source: io::Error },
+}
+
+/// Result type alias for stats generation.
+pub type BuildStatsResult<T> = Result<T, BuildStatsError>;
+
+/// A supported bundler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bundler {
+    /// webpack, through webpack-cli
+    Webpack,
+    /// Vite, through vite-bundle-visualizer
+    Vite,
+    /// esbuild
+    Esbuild,
+}
+
+impl fmt::Display for Bundler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bundler::Webpack => write!(f, "webpack"),
+            Bundler::Vite => write!(f, "vite"),
+            Bundler::Esbuild => write!(f, "esbuild"),
+        }
+    }
+}
+
+impl FromStr for Bundler {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "webpack" => Ok(Bundler::Webpack),
+            "vite" => Ok(Bundler::Vite),
+            "esbuild" => Ok(Bundler::Esbuild),
+            other => Err(format!(
+                "unknown bundler '{}' (expected: webpack, vite, esbuild)",
+                other
+            )),
+        }
+    }
+}
+
+impl Bundler {
+    /// Detects the bundler of a project.
+    ///
+    /// A bundler config file wins over dependencies; of the dependencies
+    /// webpack is checked first, since Vite and webpack setups often
+    /// pull in esbuild as well.
+    pub fn detect(project_dir: &Path) -> Option<Self> {
+        let has_config = |prefix: &str| {
+            ["js", "cjs", "mjs", "ts"]
+                .iter()
+                .any(|ext| project_dir.join(format!("{}.{}", prefix, ext)).is_file())
+        };
+        if has_config("webpack.config") {
+            return Some(Bundler::Webpack);
+        }
+        if has_config("vite.config") {
+            return Some(Bundler::Vite);
+        }
+
+        let manifest: serde_json::Value = fs::read_to_string(project_dir.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())?;
+        let depends_on = |name: &str| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|field| manifest.get(*field).and_then(|deps| deps.get(name)).is_some())
+        };
+        [Bundler::Webpack, Bundler::Vite, Bundler::Esbuild]
+            .into_iter()
+            .find(|bundler| depends_on(&bundler.to_string()))
+    }
+
+    /// Returns the command that builds a project and writes its stats.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The project to build
+    /// * `output` - Where the stats are written
+    pub fn command(&self, project_dir: &Path, output: &Path) -> BuildStatsResult<Vec<String>> {
+        let output = output.display().to_string();
+        let args: Vec<String> = match self {
+            Bundler::Webpack => vec![
+                "--no".to_string(),
+                "webpack".to_string(),
+                "--profile".to_string(),
+                format!("--json={}", output),
+            ],
+            Bundler::Vite => vec![
+                "--yes".to_string(),
+                "vite-bundle-visualizer".to_string(),
+                "--template".to_string(),
+                "raw-data".to_string(),
+                "--output".to_string(),
+                output,
+            ],
+            Bundler::Esbuild => {
+                let entry = esbuild_entry(project_dir).ok_or(BuildStatsError::NoEntryPoint)?;
+                let outdir = std::env::temp_dir().join(format!("codescope-esbuild-{}", std::process::id()));
+                vec![
+                    "--no".to_string(),
+                    "esbuild".to_string(),
+                    entry,
+                    "--bundle".to_string(),
+                    "--minify".to_string(),
+                    format!("--outdir={}", outdir.display()),
+                    format!("--metafile={}", output),
+                ]
+            }
+        };
+        Ok(std::iter::once("npx".to_string()).chain(args).collect())
+    }
+
+    /// Converts the stats this bundler wrote to webpack's format.
+    pub fn read_stats(&self, path: &Path) -> BuildStatsResult<WebpackStats> {
+        let stats_error = |source| BuildStatsError::Stats { bundler: *self, source };
+        let content = fs::read_to_string(path).map_err(stats_error)?;
+        match self {
+            Bundler::Webpack => WebpackStats::parse(&content),
+            Bundler::Vite => from_visualizer_data(&content),
+            Bundler::Esbuild => from_esbuild_metafile(&content),
+        }
+        .map_err(stats_error)
+    }
+}
+
+/// Finds the entry point esbuild should bundle.
+fn esbuild_entry(project_dir: &Path) -> Option<String> {
+    let manifest: Option<serde_json::Value> = fs::read_to_string(project_dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let declared = ["source", "module", "main"]
+        .iter()
+        .filter_map(|field| manifest.as_ref()?.get(*field)?.as_str().map(str::to_string));
+    declared
+        .chain(ENTRY_CANDIDATES.iter().map(|c| c.to_string()))
+        .find(|entry| project_dir.join(entry).is_file())
+}
+
+/// Builds a project with its bundler and reads the stats it wrote.
+///
+/// # Arguments
+///
+/// * `project_dir` - The project to build
+/// * `bundler` - The bundler to use, detected if `None`
+/// * `output` - Where the stats are written
+///
+/// # Returns
+///
+/// The bundler used and its stats in webpack's format.
+pub fn build_stats(
+    project_dir: &Path,
+    bundler: Option<Bundler>,
+    output: &Path,
+) -> BuildStatsResult<(Bundler, WebpackStats)> {
+    let bundler = bundler
+        .or_else(|| Bundler::detect(project_dir))
+        .ok_or_else(|| BuildStatsError::NoBundler(project_dir.to_path_buf()))?;
+    let command = bundler.command(project_dir, output)?;
+
+    let result = Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|source| BuildStatsError::Spawn {
+            program: command[0].clone(),
+            source,
+        })?;
+    if !result.status.success() {
+        let mut output = String::from_utf8_lossy(&result.stderr).trim().to_string();
+        if output.is_empty() {
+            output = String::from_utf8_lossy(&result.stdout).trim().to_string();
+        }
+        return Err(BuildStatsError::Failed { bundler, output });
+    }
+    Ok((bundler, bundler.read_stats(output)?))
+}
+
+fn invalid_data(json: &str, e: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, SyntaxError::from_json(json, &e))
+}
+
+/// An esbuild metafile.
+#[derive(Deserialize)]
+struct Metafile {
+    #[serde(default)]
+    inputs: BTreeMap<String, MetafileInput>,
+    #[serde(default)]
+    outputs: BTreeMap<String, MetafileOutput>,
+}
+
+#[derive(Deserialize)]
+struct MetafileInput {
+    #[serde(default)]
+    imports: Vec<MetafileImport>,
+}
+
+#[derive(Deserialize)]
+struct MetafileImport {
+    path: String,
+    kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetafileOutput {
+    #[serde(default)]
+    bytes: u64,
+    #[serde(default)]
+    inputs: BTreeMap<String, MetafileContribution>,
+    entry_point: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetafileContribution {
+    #[serde(default)]
+    bytes_in_output: u64,
+}
+
+/// Converts an esbuild metafile to webpack stats.
+///
+/// Each output file becomes an asset and a chunk, each input a module
+/// sized by the bytes it contributes to the outputs, with its importers
+/// as reasons.
+pub fn from_esbuild_metafile(json: &str) -> io::Result<WebpackStats> {
+    let metafile: Metafile = serde_json::from_str(json).map_err(|e| invalid_data(json, e))?;
+    let mut reasons: HashMap<&str, Vec<ModuleReason>> = HashMap::new();
+    for (importer, input) in &metafile.inputs {
+        for import in &input.imports {
+            reasons.entry(import.path.as_str()).or_default().push(ModuleReason {
+                module_name: Some(module_path(importer)),
+                reason_type: import.kind.clone(),
+                user_request: Some(import.path.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    let mut stats = WebpackStats::default();
+    let mut modules: BTreeMap<&str, WebpackModule> = BTreeMap::new();
+    for (file, output) in metafile.outputs.iter().filter(|(file, _)| !file.ends_with(".map")) {
+        let id = ChunkId::String(file.clone());
+        stats.assets.push(WebpackAsset {
+            name: file.clone(),
+            size: output.bytes,
+            chunks: vec![id.clone()],
+            ..Default::default()
+        });
+        stats.chunks.push(WebpackChunk {
+            id: Some(id.clone()),
+            names: output.entry_point.iter().cloned().collect(),
+            size: output.bytes,
+            files: vec![file.clone()],
+            entry: output.entry_point.is_some(),
+            initial: output.entry_point.is_some(),
+            ..Default::default()
+        });
+        for (input, contribution) in &output.inputs {
+            let module = modules.entry(input.as_str()).or_insert_with(|| WebpackModule {
+                name: Some(module_path(input)),
+                reasons: reasons.remove(input.as_str()).unwrap_or_default(),
+                ..Default::default()
+            });
+            module.size += contribution.bytes_in_output;
+            module.chunks.push(id.clone());
+        }
+    }
+    stats.modules = modules.into_values().collect();
+    Ok(stats)
+}
+
+/// Visualizer data, as written by rollup-plugin-visualizer's `raw-data`
+/// template.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VisualizerData {
+    #[serde(default)]
+    node_parts: HashMap<String, VisualizerPart>,
+    #[serde(default)]
+    node_metas: BTreeMap<String, VisualizerMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VisualizerPart {
+    #[serde(default)]
+    rendered_length: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VisualizerMeta {
+    id: String,
+    #[serde(default)]
+    module_parts: BTreeMap<String, String>,
+    #[serde(default)]
+    imported_by: Vec<VisualizerLink>,
+}
+
+#[derive(Deserialize)]
+struct VisualizerLink {
+    uid: String,
+}
+
+/// Converts rollup-plugin-visualizer data, as produced for Vite builds,
+/// to webpack stats.
+///
+/// Each bundle file becomes an asset and a chunk sized by the rendered
+/// modules in it; the data has no sizes for the files themselves.
+pub fn from_visualizer_data(json: &str) -> io::Result<WebpackStats> {
+    let data: VisualizerData = serde_json::from_str(json).map_err(|e| invalid_data(json, e))?;
+    let mut stats = WebpackStats::default();
+    let mut bundles: BTreeMap<&str, u64> = BTreeMap::new();
+
+    for meta in data.node_metas.values() {
+        let mut module = WebpackModule {
+            name: Some(module_path(&meta.id)),
+            reasons: meta
+                .imported_by
+                .iter()
+                .filter_map(|link| data.node_metas.get(&link.uid))
+                .map(|importer| ModuleReason {
+                    module_name: Some(module_path(&importer.id)),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (bundle, part) in &meta.module_parts {
+            let size = data.node_parts.get(part).map_or(0, |p| p.rendered_length);
+            module.size += size;
+            module.chunks.push(ChunkId::String(bundle.clone()));
+            *bundles.entry(bundle).or_default() += size;
+        }
+        if !module.chunks.is_empty() {
+            stats.modules.push(module);
+        }
+    }
+
+    for (bundle, size) in bundles {
+        let id = ChunkId::String(bundle.to_string());
+        stats.assets.push(WebpackAsset {
+            name: bundle.to_string(),
+            size,
+            chunks: vec![id.clone()],
+            ..Default::default()
+        });
+        stats.chunks.push(WebpackChunk {
+            id: Some(id),
+            size,
+            files: vec![bundle.to_string()],
+            ..Default::default()
+        });
+    }
+    Ok(stats)
+}
+
+/// Writes a module path the way webpack does, relative with a `./` prefix.
+fn module_path(path: &str) -> String {
+    let path = to_slash(path);
+    let path = path.trim_start_matches('/');
+    if path.starts_with("./") || path.starts_with("../") {
+        path.to_string()
+    } else {
+        format!("./{}", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_bundler() {
+        let root = std::env::temp_dir().join(format!("codescope-build-stats-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        assert_eq!(Bundler::detect(&root), None);
+        fs::write(root.join("package.json"), r#"{"devDependencies": {"esbuild": "^0.20.0", "vite": "^5.0.0"}}"#).unwrap();
+        assert_eq!(Bundler::detect(&root), Some(Bundler::Vite));
+        fs::write(root.join("webpack.config.js"), "").unwrap();
+        assert_eq!(Bundler::detect(&root), Some(Bundler::Webpack));
+
+        assert!(matches!(
+            Bundler::Esbuild.command(&root, Path::new("meta.json")),
+            Err(BuildStatsError::NoEntryPoint)
+        ));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/index.tsx"), "").unwrap();
+        let command = Bundler::Esbuild.command(&root, Path::new("meta.json")).unwrap();
+        assert_eq!(command[..4], ["npx", "--no", "esbuild", "src/index.tsx"]);
+        assert_eq!(command.last().unwrap(), "--metafile=meta.json");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_esbuild_metafile() {
+        let stats = from_esbuild_metafile(
+            r#"{
+                "inputs": {
+                    "src/index.js": { "bytes": 300, "imports": [{ "path": "node_modules/react/index.js", "kind": "import-statement" }] },
+                    "node_modules/react/index.js": { "bytes": 9000, "imports": [] }
+                },
+                "outputs": {
+                    "dist/index.js": { "bytes": 7000, "entryPoint": "src/index.js", "inputs": {
+                        "src/index.js": { "bytesInOutput": 200 },
+                        "node_modules/react/index.js": { "bytesInOutput": 6500 }
+                    } },
+                    "dist/index.js.map": { "bytes": 20000, "inputs": {} }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.assets.len(), 1, "source maps are skipped");
+        assert!(stats.chunks[0].entry);
+        let analysis = stats.analyze();
+        assert_eq!(analysis.package_sizes["react"].total_size, 6500);
+        let react = stats.modules.iter().find(|m| m.size == 6500).unwrap();
+        assert_eq!(react.reasons[0].module_name.as_deref(), Some("./src/index.js"));
+    }
+
+    #[test]
+    fn test_visualizer_data() {
+        let stats = from_visualizer_data(
+            r#"{
+                "version": 2,
+                "nodeParts": {
+                    "p1": { "renderedLength": 120, "gzipLength": 0, "brotliLength": 0, "metaUid": "m1" },
+                    "p2": { "renderedLength": 4000, "gzipLength": 0, "brotliLength": 0, "metaUid": "m2" }
+                },
+                "nodeMetas": {
+                    "m1": { "id": "/src/main.ts", "moduleParts": { "assets/index.js": "p1" }, "imported": [{ "uid": "m2" }], "importedBy": [] },
+                    "m2": { "id": "/node_modules/vue/dist/vue.runtime.esm-bundler.js", "moduleParts": { "assets/index.js": "p2" }, "imported": [], "importedBy": [{ "uid": "m1" }] }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.assets[0].name, "assets/index.js");
+        assert_eq!(stats.assets[0].size, 4120);
+        let analysis = stats.analyze();
+        assert_eq!(analysis.package_sizes["vue"].total_size, 4000);
+        let vue = stats.modules.iter().find(|m| m.size == 4000).unwrap();
+        assert_eq!(vue.reasons[0].module_name.as_deref(), Some("./src/main.ts"));
+    }
+}
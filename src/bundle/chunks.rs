@@ -0,0 +1,286 @@
+//! Chunk graph of a webpack build.
+//!
+//! Code splitting turns one bundle into entry chunks that load further
+//! chunks on demand. The stats record each chunk's parents, children and
+//! siblings; this module arranges them as a tree and works out which
+//! packages end up in each chunk.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::bundle::webpack::{extract_package_name, ChunkId, WebpackModule, WebpackStats};
+
+/// Package name used for modules outside node_modules.
+pub const PROJECT_CODE: &str = "(project)";
+
+/// A package's share of one chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkPackage {
+    /// Package name, or [`PROJECT_CODE`]
+    pub name: String,
+    /// Size in bytes of the package's modules in the chunk
+    pub size: u64,
+    /// Number of the package's modules in the chunk
+    pub module_count: usize,
+}
+
+/// One chunk of the build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkInfo {
+    /// Chunk ID
+    pub id: String,
+    /// Chunk names, empty for anonymous chunks
+    pub names: Vec<String>,
+    /// Chunk size in bytes
+    pub size: u64,
+    /// Whether the chunk contains the webpack runtime
+    pub entry: bool,
+    /// Whether the chunk loads with the page rather than on demand
+    pub initial: bool,
+    /// IDs of the chunks that load this one
+    pub parents: Vec<String>,
+    /// IDs of the chunks this one loads
+    pub children: Vec<String>,
+    /// IDs of the chunks loaded alongside this one
+    pub siblings: Vec<String>,
+    /// Packages in the chunk, largest first
+    pub packages: Vec<ChunkPackage>,
+}
+
+impl ChunkInfo {
+    /// Returns the chunk's name, or its ID for anonymous chunks.
+    pub fn label(&self) -> String {
+        match self.names.first() {
+            Some(name) => name.clone(),
+            None => format!("#{}", self.id),
+        }
+    }
+
+    /// Describes how the chunk loads: `entry`, `initial` or `async`.
+    pub fn kind(&self) -> &'static str {
+        if self.entry {
+            "entry"
+        } else if self.initial {
+            "initial"
+        } else {
+            "async"
+        }
+    }
+}
+
+/// A line of the chunk tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkRow {
+    /// Index into [`ChunkGraph::chunks`]
+    pub index: usize,
+    /// Nesting depth, 0 for root chunks
+    pub depth: usize,
+    /// Whether the chunk was already listed under another parent and is
+    /// not expanded again
+    pub repeated: bool,
+}
+
+/// The chunks of a build and their relationships.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkGraph {
+    /// Chunks in stats order
+    pub chunks: Vec<ChunkInfo>,
+}
+
+impl ChunkGraph {
+    /// Builds the chunk graph of webpack stats.
+    ///
+    /// Packages are attributed from each chunk's `modules` when the stats
+    /// include them, otherwise from the top-level modules' `chunks`.
+    /// Concatenated modules count through their inner modules.
+    pub fn from_stats(stats: &WebpackStats) -> Self {
+        let mut by_chunk: HashMap<String, HashMap<String, (u64, usize)>> = HashMap::new();
+        for chunk in &stats.chunks {
+            if let Some(id) = &chunk.id {
+                add_modules(&chunk.modules, by_chunk.entry(id.to_string()).or_default());
+            }
+        }
+        let chunks_with_modules: HashSet<String> =
+            by_chunk.iter().filter(|(_, p)| !p.is_empty()).map(|(id, _)| id.clone()).collect();
+        for module in &stats.modules {
+            for id in &module.chunks {
+                let id = id.to_string();
+                if !chunks_with_modules.contains(&id) {
+                    add_modules(std::slice::from_ref(module), by_chunk.entry(id).or_default());
+                }
+            }
+        }
+
+        let ids = |ids: &[ChunkId]| ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let chunks = stats
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let id = chunk.id.as_ref().map(ToString::to_string).unwrap_or_default();
+                let mut packages: Vec<ChunkPackage> = by_chunk
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, (size, module_count))| ChunkPackage { name, size, module_count })
+                    .collect();
+                packages.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+                ChunkInfo {
+                    id,
+                    names: chunk.names.clone(),
+                    size: chunk.size,
+                    entry: chunk.entry,
+                    initial: chunk.initial,
+                    parents: ids(&chunk.parents),
+                    children: ids(&chunk.children),
+                    siblings: ids(&chunk.siblings),
+                    packages,
+                }
+            })
+            .collect();
+        Self { chunks }
+    }
+
+    /// Returns true if the stats list no chunks.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Returns the chunk with an ID.
+    pub fn get(&self, id: &str) -> Option<&ChunkInfo> {
+        self.chunks.iter().find(|chunk| chunk.id == id)
+    }
+
+    /// Arranges the chunks as a tree, children under their parents and
+    /// largest first.
+    ///
+    /// Roots are the chunks without known parents. A chunk with several
+    /// parents is expanded under the first and marked as repeated under
+    /// the others; chunks only reachable through a cycle become roots.
+    pub fn rows(&self) -> Vec<ChunkRow> {
+        let index: HashMap<&str, usize> = self.chunks.iter().enumerate().map(|(i, c)| (c.id.as_str(), i)).collect();
+        let by_size = |mut indices: Vec<usize>| {
+            indices.sort_by(|a, b| {
+                self.chunks[*b]
+                    .size
+                    .cmp(&self.chunks[*a].size)
+                    .then_with(|| self.chunks[*a].id.cmp(&self.chunks[*b].id))
+            });
+            indices
+        };
+
+        let roots = by_size(
+            (0..self.chunks.len())
+                .filter(|i| !self.chunks[*i].parents.iter().any(|p| index.contains_key(p.as_str())))
+                .collect(),
+        );
+        let mut rows = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|i| (i, 0)).collect();
+        let mut remaining = 0..self.chunks.len();
+        while let Some((chunk, depth)) = stack
+            .pop()
+            .or_else(|| remaining.by_ref().find(|i| !visited.contains(i)).map(|i| (i, 0)))
+        {
+            let repeated = !visited.insert(chunk);
+            rows.push(ChunkRow { index: chunk, depth, repeated });
+            if !repeated {
+                let children = by_size(
+                    self.chunks[chunk]
+                        .children
+                        .iter()
+                        .filter_map(|id| index.get(id.as_str()).copied())
+                        .collect(),
+                );
+                stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+            }
+        }
+        rows
+    }
+}
+
+/// Adds the sizes of modules, or of the modules concatenated into them,
+/// to per-package totals.
+fn add_modules(modules: &[WebpackModule], packages: &mut HashMap<String, (u64, usize)>) {
+    for module in modules {
+        if !module.modules.is_empty() {
+            add_modules(&module.modules, packages);
+            continue;
+        }
+        let Some(path) = module.name.as_deref().or(module.identifier.as_deref()) else {
+            continue;
+        };
+        let name = extract_package_name(path).unwrap_or_else(|| PROJECT_CODE.to_string());
+        let entry = packages.entry(name).or_default();
+        entry.0 += module.size;
+        entry.1 += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_graph() {
+        let json = r#"{
+            "chunks": [
+                { "id": 0, "names": ["main"], "size": 50000, "entry": true, "initial": true, "children": [1, 2] },
+                { "id": 1, "size": 8000, "parents": [0, 2], "siblings": [2] },
+                { "id": 2, "names": ["admin"], "size": 20000, "parents": [0], "children": [1, 3] },
+                { "id": 3, "size": 1000, "parents": [4], "children": [4] },
+                { "id": 4, "size": 500, "parents": [3], "children": [3] }
+            ],
+            "modules": [
+                { "name": "./node_modules/react/index.js", "size": 30000, "chunks": [0] },
+                { "name": "./src/index.js + 2 modules", "size": 9000, "chunks": [0], "modules": [
+                    { "name": "./src/index.js", "size": 6000 },
+                    { "name": "./node_modules/clsx/dist/clsx.mjs", "size": 3000 }
+                ] },
+                { "name": "./node_modules/chart.js/dist/chart.js", "size": 18000, "chunks": [2] },
+                { "name": "./src/admin.js", "size": 2000, "chunks": [2] }
+            ],
+            "assets": []
+        }"#;
+        let graph = ChunkGraph::from_stats(&WebpackStats::parse(json).unwrap());
+
+        let main = graph.get("0").unwrap();
+        assert_eq!(main.label(), "main");
+        assert_eq!(main.kind(), "entry");
+        let packages: Vec<(&str, u64)> = main.packages.iter().map(|p| (p.name.as_str(), p.size)).collect();
+        assert_eq!(packages, vec![("react", 30000), (PROJECT_CODE, 6000), ("clsx", 3000)]);
+        assert_eq!(graph.get("1").unwrap().label(), "#1");
+        assert_eq!(graph.get("1").unwrap().kind(), "async");
+
+        let rows: Vec<(String, usize, bool)> = graph
+            .rows()
+            .iter()
+            .map(|row| (graph.chunks[row.index].id.clone(), row.depth, row.repeated))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("0".to_string(), 0, false),
+                ("2".to_string(), 1, false),
+                ("1".to_string(), 2, false),
+                ("3".to_string(), 2, false),
+                ("4".to_string(), 3, false),
+                ("3".to_string(), 4, true),
+                ("1".to_string(), 1, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_only_chunks_become_roots() {
+        let json = r#"{
+            "chunks": [
+                { "id": "a", "size": 10, "parents": ["b"], "children": ["b"] },
+                { "id": "b", "size": 20, "parents": ["a"], "children": ["a"] }
+            ],
+            "modules": [],
+            "assets": []
+        }"#;
+        let graph = ChunkGraph::from_stats(&WebpackStats::parse(json).unwrap());
+        let rows: Vec<(usize, usize, bool)> = graph.rows().iter().map(|r| (r.index, r.depth, r.repeated)).collect();
+        assert_eq!(rows, vec![(0, 0, false), (1, 1, false), (0, 2, true)]);
+    }
+}
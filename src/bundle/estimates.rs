@@ -0,0 +1,132 @@
+//! Size estimates for packages without bundler stats.
+//!
+//! The savings report needs a size for every runtime dependency, but only
+//! a stats file measures them. In between real stats and no data at all,
+//! [`SizeDataset::bundled`] provides the minified and gzipped sizes of
+//! about 140 common npm packages, compiled in from `package_sizes.csv`.
+//! The list is maintained by hand; editing it changes the estimates
+//! without touching the code. Packages it does not list are estimated at
+//! [`DEFAULT_ESTIMATE`].
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The dataset compiled into the binary.
+const BUNDLED_DATASET: &str = include_str!("package_sizes.csv");
+
+/// Estimated minified size of a package missing from the dataset.
+pub const DEFAULT_ESTIMATE: u64 = 25 * 1024;
+
+/// Sizes of one package in the dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeEstimate {
+    /// Minified size in bytes
+    pub minified: u64,
+    /// Minified and gzipped size in bytes
+    pub gzipped: u64,
+}
+
+/// Where an estimated size comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateSource {
+    /// Type declarations only, which cost nothing at runtime
+    TypesOnly,
+    /// The size dataset
+    Dataset,
+    /// Not in the dataset: [`DEFAULT_ESTIMATE`]
+    Default,
+}
+
+/// Package sizes keyed by package name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeDataset {
+    sizes: HashMap<String, SizeEstimate>,
+}
+
+impl SizeDataset {
+    /// Parses a dataset in the `name,minified,gzipped` format of
+    /// `package_sizes.csv`.
+    ///
+    /// Comments, blank lines, the header and lines that do not parse are
+    /// skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::bundle::estimates::SizeDataset;
+    ///
+    /// let dataset = SizeDataset::parse("# sizes\nname,minified,gzipped\nreact,7066,2765\nbroken,x,1\n");
+    /// assert_eq!(dataset.len(), 1);
+    /// assert_eq!(dataset.get("react").unwrap().gzipped, 2765);
+    /// ```
+    pub fn parse(content: &str) -> Self {
+        let sizes = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split(',').map(str::trim);
+                let name = fields.next()?;
+                let minified = fields.next()?.parse().ok()?;
+                let gzipped = fields.next()?.parse().ok()?;
+                Some((name.to_string(), SizeEstimate { minified, gzipped }))
+            })
+            .collect();
+        Self { sizes }
+    }
+
+    /// Returns the dataset compiled into the binary, parsed on first use.
+    pub fn bundled() -> &'static Self {
+        static DATASET: OnceLock<SizeDataset> = OnceLock::new();
+        DATASET.get_or_init(|| Self::parse(BUNDLED_DATASET))
+    }
+
+    /// Returns the sizes of a package, if the dataset lists it.
+    pub fn get(&self, name: &str) -> Option<SizeEstimate> {
+        self.sizes.get(name).copied()
+    }
+
+    /// Returns the number of packages in the dataset.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns true if the dataset lists no package.
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Estimates the minified size a package adds to the bundle.
+    ///
+    /// # Returns
+    ///
+    /// The size in bytes and where it comes from: 0 for `@types/*`
+    /// packages, the dataset's minified size, or [`DEFAULT_ESTIMATE`].
+    pub fn estimate(&self, name: &str) -> (u64, EstimateSource) {
+        if name.starts_with("@types/") {
+            return (0, EstimateSource::TypesOnly);
+        }
+        match self.get(name) {
+            Some(size) => (size.minified, EstimateSource::Dataset),
+            None => (DEFAULT_ESTIMATE, EstimateSource::Default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_dataset() {
+        let dataset = SizeDataset::bundled();
+        assert!(dataset.len() > 100);
+        assert!(dataset.get("name").is_none(), "the header is not a package");
+
+        let (size, source) = dataset.estimate("react-dom");
+        assert_eq!(source, EstimateSource::Dataset);
+        assert!(size > dataset.get("react-dom").unwrap().gzipped);
+        assert_eq!(dataset.estimate("@types/react"), (0, EstimateSource::TypesOnly));
+        assert_eq!(dataset.estimate("left-pad-but-larger"), (DEFAULT_ESTIMATE, EstimateSource::Default));
+    }
+}
@@ -26,19 +26,32 @@
 //! }
 //! ```
 
+pub mod build;
+pub mod chunks;
+pub mod estimates;
+pub mod polyfills;
+pub mod reasons;
 pub mod savings;
+pub mod tree_shaking;
 pub mod webpack;
 
 // Re-export main types for convenience
+pub use build::{build_stats, BuildStatsError, Bundler};
+pub use chunks::{ChunkGraph, ChunkInfo, ChunkPackage, ChunkRow};
+pub use polyfills::{PolyfillPackage, PolyfillReport, UnneededPolyfill};
+pub use reasons::{IncludedModule, Importer, InclusionReason, InclusionReport};
 pub use savings::{
     PackageSavings, SavingsCalculator, SavingsCategory, SavingsReport, SavingsSummary,
 };
+pub use tree_shaking::{TreeShakingReport, TreeShakingScore};
 pub use webpack::{
-    extract_package_name, format_size, BundleAnalysis, PackageBundleSize, WebpackAsset,
-    WebpackChunk, WebpackModule, WebpackStats,
+    AssetKind, AssetTotal, extract_package_name, extract_vendored_package, format_size, parse_size, BundleAnalysis,
+    PackageBundleSize, UsedExports, VendorKind, VendoredCopy, WebpackAsset, WebpackChunk, WebpackModule,
+    WebpackStats,
 };
 
 use crate::graph::DependencyGraph;
+use crate::parser::{self, PackageStore};
 use crate::ui::tree::TreeNode;
 use std::collections::HashMap;
 
@@ -109,6 +122,44 @@ pub fn apply_bundle_sizes_to_graph(graph: &mut DependencyGraph, analysis: &Bundl
     graph.apply_bundle_sizes(&sizes)
 }
 
+/// Reads `bundledDependencies` for every package in the bundle that has
+/// modules installed in its own private `node_modules`.
+///
+/// The result can be passed to `BundleAnalysis::apply_bundled_dependencies()`.
+/// Packages that cannot be located or whose manifest cannot be read are
+/// skipped.
+///
+/// # Arguments
+///
+/// * `analysis` - The bundle analysis to inspect
+/// * `store` - Where the project's packages are installed
+pub fn read_bundled_dependencies(
+    analysis: &BundleAnalysis,
+    store: &PackageStore,
+) -> HashMap<String, Vec<String>> {
+    let mut parents: Vec<String> = analysis
+        .package_sizes
+        .values()
+        .flat_map(|pkg| pkg.modules.iter())
+        .filter_map(|(path, _)| {
+            let path = parser::pnpm::normalize_store_path(path);
+            let nested = path.rfind("/node_modules/")?;
+            extract_package_name(&path[..nested + 1])
+        })
+        .collect();
+    parents.sort_unstable();
+    parents.dedup();
+
+    parents
+        .into_iter()
+        .filter_map(|parent| {
+            let manifest = store.locate(&parent, None)?.read_to_string("package.json").ok()?;
+            let bundled = parser::parse_str(&manifest).ok()?.bundled_dependency_names();
+            (!bundled.is_empty()).then_some((parent, bundled))
+        })
+        .collect()
+}
+
 /// Applies bundle size information from a BundleAnalysis to a TreeNode.
 ///
 /// This recursively updates the tree and all children with bundle size information.
@@ -387,19 +438,23 @@ mod tests {
 
     #[test]
     fn test_match_result_percentage() {
-        let mut result = MatchResult::default();
-        result.matched_count = 3;
-        result.unmatched_count = 1;
+        let result = MatchResult {
+            matched_count: 3,
+            unmatched_count: 1,
+            ..Default::default()
+        };
 
         assert!((result.match_percentage() - 75.0).abs() < 0.01);
     }
 
     #[test]
     fn test_match_result_complete() {
-        let mut result = MatchResult::default();
-        result.matched_count = 5;
-        result.unmatched_count = 0;
-        result.missing_packages = vec![];
+        let mut result = MatchResult {
+            matched_count: 5,
+            unmatched_count: 0,
+            missing_packages: vec![],
+            ..Default::default()
+        };
 
         assert!(result.is_complete());
 
@@ -458,4 +513,35 @@ mod tests {
         assert_eq!(transitive.get("b"), Some(&600));
         assert_eq!(transitive.get("c"), Some(&600));
     }
+
+    #[test]
+    fn test_read_bundled_dependencies() {
+        let dir = std::env::temp_dir().join(format!("codescope-bundled-test-{}", std::process::id()));
+        let cli_kit = dir.join("node_modules/cli-kit");
+        std::fs::create_dir_all(cli_kit.join("node_modules/chalk")).unwrap();
+        std::fs::write(
+            cli_kit.join("package.json"),
+            r#"{"name": "cli-kit", "bundledDependencies": ["chalk"]}"#,
+        )
+        .unwrap();
+        // A nested install without bundledDependencies is a regular dependency
+        std::fs::create_dir_all(dir.join("node_modules/other/node_modules/semver")).unwrap();
+        std::fs::write(dir.join("node_modules/other/package.json"), r#"{"name": "other"}"#).unwrap();
+
+        let json = r#"{
+            "modules": [
+                { "name": "./node_modules/cli-kit/node_modules/chalk/index.js", "size": 50 },
+                { "name": "./node_modules/other/node_modules/semver/index.js", "size": 70 },
+                { "name": "./node_modules/missing/node_modules/x/index.js", "size": 10 }
+            ]
+        }"#;
+        let analysis = WebpackStats::parse(json).unwrap().analyze();
+        let store = PackageStore::detect(&dir).unwrap();
+
+        let bundled = read_bundled_dependencies(&analysis, &store);
+        assert_eq!(bundled.len(), 1);
+        assert_eq!(bundled.get("cli-kit"), Some(&vec!["chalk".to_string()]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,341 @@
+//! Polyfills in the bundle.
+//!
+//! Finds core-js, regenerator-runtime and standalone polyfill packages in
+//! webpack stats, attributes their size, and compares the features they
+//! polyfill with the project's browserslist targets. A polyfill for a
+//! feature that every targeted browser ships natively is dead weight.
+
+use serde::Serialize;
+
+use crate::analysis::browser_support::{supported_by_all, v, SupportRow};
+use crate::bundle::savings::{PackageSavings, SavingsCategory};
+use crate::bundle::webpack::{format_size, BundleAnalysis, PackageBundleSize};
+use crate::parser::browserslist::BrowserTargets;
+
+/// Packages that ship the core-js standard library polyfills, whose
+/// modules are checked one by one.
+const CORE_JS_PACKAGES: [&str; 4] = ["core-js", "core-js-pure", "core-js-bundle", "@babel/polyfill"];
+
+/// A polyfill for one feature, with the first native version per browser.
+struct Polyfill {
+    /// Package name, or core-js module name without `.js`
+    name: &'static str,
+    /// The feature it polyfills
+    feature: &'static str,
+    /// Native support, in the browser order of [`SupportRow`]
+    support: SupportRow,
+}
+
+/// Standalone polyfill packages.
+const PACKAGES: [Polyfill; 10] = [
+    Polyfill { name: "regenerator-runtime", feature: "generators and async functions", support: [v(55, 0), v(15, 0), v(52, 0), v(11, 0), v(11, 0), v(42, 0), v(6, 2), None, v(7, 6)] },
+    Polyfill { name: "whatwg-fetch", feature: "fetch", support: [v(42, 0), v(14, 0), v(39, 0), v(10, 1), v(10, 3), v(29, 0), v(4, 0), None, v(18, 0)] },
+    Polyfill { name: "unfetch", feature: "fetch", support: [v(42, 0), v(14, 0), v(39, 0), v(10, 1), v(10, 3), v(29, 0), v(4, 0), None, v(18, 0)] },
+    Polyfill { name: "promise-polyfill", feature: "Promise", support: [v(33, 0), v(12, 0), v(29, 0), v(7, 1), v(8, 0), v(20, 0), v(2, 0), None, v(0, 12)] },
+    Polyfill { name: "es6-promise", feature: "Promise", support: [v(33, 0), v(12, 0), v(29, 0), v(7, 1), v(8, 0), v(20, 0), v(2, 0), None, v(0, 12)] },
+    Polyfill { name: "object-assign", feature: "Object.assign", support: [v(45, 0), v(12, 0), v(34, 0), v(9, 0), v(9, 0), v(32, 0), v(5, 0), None, v(4, 0)] },
+    Polyfill { name: "url-polyfill", feature: "URL and URLSearchParams", support: [v(49, 0), v(17, 0), v(44, 0), v(10, 1), v(10, 3), v(36, 0), v(5, 0), None, v(10, 0)] },
+    Polyfill { name: "abortcontroller-polyfill", feature: "AbortController", support: [v(66, 0), v(16, 0), v(57, 0), v(12, 1), v(12, 2), v(53, 0), v(9, 0), None, v(15, 0)] },
+    Polyfill { name: "intersection-observer", feature: "IntersectionObserver", support: [v(51, 0), v(15, 0), v(55, 0), v(12, 1), v(12, 2), v(38, 0), v(5, 0), None, None] },
+    Polyfill { name: "resize-observer-polyfill", feature: "ResizeObserver", support: [v(64, 0), v(79, 0), v(69, 0), v(13, 1), v(13, 4), v(51, 0), v(9, 0), None, None] },
+];
+
+/// Common core-js modules. Modules nested under these, such as
+/// `es.promise.finally`, polyfill later additions and are not matched.
+const CORE_JS_MODULES: [Polyfill; 12] = [
+    Polyfill { name: "es.symbol", feature: "Symbol", support: [v(38, 0), v(12, 0), v(36, 0), v(9, 0), v(9, 0), v(25, 0), v(3, 0), None, v(0, 12)] },
+    Polyfill { name: "es.promise", feature: "Promise", support: [v(33, 0), v(12, 0), v(29, 0), v(7, 1), v(8, 0), v(20, 0), v(2, 0), None, v(0, 12)] },
+    Polyfill { name: "es.map", feature: "Map", support: [v(38, 0), v(12, 0), v(13, 0), v(8, 0), v(8, 0), v(25, 0), v(3, 0), None, v(0, 12)] },
+    Polyfill { name: "es.set", feature: "Set", support: [v(38, 0), v(12, 0), v(13, 0), v(8, 0), v(8, 0), v(25, 0), v(3, 0), None, v(0, 12)] },
+    Polyfill { name: "es.array.from", feature: "Array.from", support: [v(45, 0), v(12, 0), v(32, 0), v(9, 0), v(9, 0), v(32, 0), v(5, 0), None, v(4, 0)] },
+    Polyfill { name: "es.array.includes", feature: "Array.prototype.includes", support: [v(47, 0), v(14, 0), v(43, 0), v(9, 0), v(9, 0), v(34, 0), v(5, 0), None, v(6, 0)] },
+    Polyfill { name: "es.array.flat", feature: "Array.prototype.flat", support: [v(69, 0), v(79, 0), v(62, 0), v(12, 0), v(12, 0), v(56, 0), v(10, 0), None, v(11, 0)] },
+    Polyfill { name: "es.object.assign", feature: "Object.assign", support: [v(45, 0), v(12, 0), v(34, 0), v(9, 0), v(9, 0), v(32, 0), v(5, 0), None, v(4, 0)] },
+    Polyfill { name: "es.object.entries", feature: "Object.entries", support: [v(54, 0), v(14, 0), v(47, 0), v(10, 1), v(10, 3), v(41, 0), v(6, 2), None, v(7, 0)] },
+    Polyfill { name: "es.string.includes", feature: "String.prototype.includes", support: [v(41, 0), v(12, 0), v(40, 0), v(9, 0), v(9, 0), v(28, 0), v(3, 4), None, v(4, 0)] },
+    Polyfill { name: "es.string.pad-start", feature: "String.prototype.padStart", support: [v(57, 0), v(15, 0), v(48, 0), v(10, 0), v(10, 0), v(44, 0), v(7, 0), None, v(8, 0)] },
+    Polyfill { name: "es.string.replace-all", feature: "String.prototype.replaceAll", support: [v(85, 0), v(85, 0), v(77, 0), v(13, 1), v(13, 4), v(71, 0), v(14, 0), None, v(15, 0)] },
+];
+
+/// A polyfill that every targeted browser makes unnecessary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnneededPolyfill {
+    /// Package name, or core-js module name
+    pub name: String,
+    /// The feature it polyfills
+    pub feature: String,
+    /// Bundle size in bytes
+    pub size: u64,
+}
+
+/// A polyfill package found in the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolyfillPackage {
+    /// Package name
+    pub name: String,
+    /// Bundle size in bytes
+    pub size: u64,
+    /// Number of bundled modules
+    pub module_count: usize,
+    /// The whole package or the core-js modules the targets do not need
+    pub unneeded: Vec<UnneededPolyfill>,
+}
+
+impl PolyfillPackage {
+    /// Returns the bytes that could be dropped for the targets.
+    pub fn unneeded_size(&self) -> u64 {
+        self.unneeded.iter().map(|p| p.size).sum()
+    }
+
+    /// Returns true for core-js style packages checked module by module.
+    pub fn is_core_js(&self) -> bool {
+        CORE_JS_PACKAGES.contains(&self.name.as_str())
+    }
+}
+
+/// The polyfills in a bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolyfillReport {
+    /// Polyfill packages, largest first
+    pub packages: Vec<PolyfillPackage>,
+    /// The targets compared with, `None` without a browserslist config
+    pub targets: Option<BrowserTargets>,
+}
+
+/// Returns the core-js module name of a module path, e.g. `es.promise`
+/// for `./node_modules/core-js/modules/es.promise.js`.
+fn core_js_module(path: &str) -> Option<&str> {
+    let (_, file) = path.rsplit_once("/modules/")?;
+    let file = file.split(' ').next()?;
+    file.strip_suffix(".js").or(Some(file)).filter(|name| !name.contains('/'))
+}
+
+impl PolyfillReport {
+    /// Finds the polyfills of a bundle analysis.
+    ///
+    /// # Arguments
+    ///
+    /// * `analysis` - Bundle sizes from webpack stats
+    /// * `targets` - The project's browserslist targets; without them no
+    ///   polyfill is considered unnecessary
+    pub fn from_bundle(analysis: &BundleAnalysis, targets: Option<&BrowserTargets>) -> Self {
+        let mut packages: Vec<PolyfillPackage> = analysis
+            .package_sizes
+            .values()
+            .filter_map(|package| {
+                let unneeded = if CORE_JS_PACKAGES.contains(&package.name.as_str()) {
+                    targets.map(|targets| unneeded_modules(package, targets))
+                } else {
+                    let polyfill = PACKAGES.iter().find(|p| p.name == package.name)?;
+                    targets.filter(|targets| supported_by_all(&polyfill.support, targets)).map(|_| {
+                        vec![UnneededPolyfill {
+                            name: package.name.clone(),
+                            feature: polyfill.feature.to_string(),
+                            size: package.total_size,
+                        }]
+                    })
+                };
+                Some(PolyfillPackage {
+                    name: package.name.clone(),
+                    size: package.total_size,
+                    module_count: package.module_count,
+                    unneeded: unneeded.unwrap_or_default(),
+                })
+            })
+            .collect();
+        packages.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            packages,
+            targets: targets.cloned(),
+        }
+    }
+
+    /// Returns the combined bundle size of the polyfills.
+    pub fn total_size(&self) -> u64 {
+        self.packages.iter().map(|p| p.size).sum()
+    }
+
+    /// Returns the bytes the targets make unnecessary.
+    pub fn unneeded_size(&self) -> u64 {
+        self.packages.iter().map(PolyfillPackage::unneeded_size).sum()
+    }
+
+    /// Converts the unnecessary polyfills to savings report entries.
+    pub fn to_savings(&self) -> Vec<PackageSavings> {
+        self.packages
+            .iter()
+            .filter(|package| package.unneeded_size() > 0)
+            .map(|package| {
+                let suggestion = if package.is_core_js() {
+                    let modules: Vec<&str> = package.unneeded.iter().map(|p| p.name.as_str()).collect();
+                    format!(
+                        "Exclude {} module(s) the browserslist targets support natively: {}",
+                        modules.len(),
+                        modules.join(", ")
+                    )
+                } else {
+                    format!(
+                        "{} is native in every targeted browser; remove {}",
+                        package.unneeded[0].feature, package.name
+                    )
+                };
+                PackageSavings {
+                    package_name: package.name.clone(),
+                    current_size: package.size,
+                    potential_savings: package.unneeded_size(),
+                    category: SavingsCategory::UnneededPolyfills,
+                    utilization_percentage: None,
+                    exports_used: 0,
+                    total_exports: None,
+                    suggestion,
+                    alternative: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Formats the polyfills and the unnecessary ones as a listing.
+    pub fn format_report(&self) -> String {
+        if self.packages.is_empty() {
+            return "✅ No polyfill packages in the bundle.\n".to_string();
+        }
+
+        let mut out = format!(
+            "🩹 {} polyfill package(s), {} in the bundle:\n",
+            self.packages.len(),
+            format_size(self.total_size())
+        );
+        for package in &self.packages {
+            out.push_str(&format!(
+                "  {}: {} ({} module(s))\n",
+                package.name,
+                format_size(package.size),
+                package.module_count
+            ));
+            if package.is_core_js() {
+                for polyfill in &package.unneeded {
+                    out.push_str(&format!(
+                        "    unneeded {} ({}): {}\n",
+                        polyfill.name,
+                        polyfill.feature,
+                        format_size(polyfill.size)
+                    ));
+                }
+            } else if let Some(polyfill) = package.unneeded.first() {
+                out.push_str(&format!("    unneeded: {} is native in every targeted browser\n", polyfill.feature));
+            }
+        }
+
+        out.push('\n');
+        match &self.targets {
+            None => out.push_str("⚠️  No browserslist configuration; cannot tell which polyfills are unnecessary.\n"),
+            Some(targets) if targets.is_empty() => {
+                out.push_str("⚠️  No browser versions could be resolved from the browserslist config.\n")
+            }
+            Some(targets) => out.push_str(&format!(
+                "Targets: {}\nUnnecessary for these targets: {}\n",
+                targets.describe(),
+                format_size(self.unneeded_size())
+            )),
+        }
+        out
+    }
+}
+
+/// Returns the core-js modules of a package that the targets support.
+fn unneeded_modules(package: &PackageBundleSize, targets: &BrowserTargets) -> Vec<UnneededPolyfill> {
+    let mut unneeded: Vec<UnneededPolyfill> = package
+        .modules
+        .iter()
+        .filter_map(|(path, size)| {
+            let module = core_js_module(path)?;
+            let polyfill = CORE_JS_MODULES.iter().find(|p| p.name == module)?;
+            supported_by_all(&polyfill.support, targets).then(|| UnneededPolyfill {
+                name: module.to_string(),
+                feature: polyfill.feature.to_string(),
+                size: *size,
+            })
+        })
+        .collect();
+    unneeded.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    unneeded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(packages: &[(&str, &[(&str, u64)])]) -> BundleAnalysis {
+        let mut analysis = BundleAnalysis::default();
+        for (name, modules) in packages {
+            let mut package = PackageBundleSize::new(*name);
+            for (path, size) in *modules {
+                package.add_module(format!("./node_modules/{}/{}", name, path), *size);
+            }
+            analysis.package_sizes.insert(name.to_string(), package);
+        }
+        analysis
+    }
+
+    #[test]
+    fn test_polyfill_report() {
+        let analysis = bundle(&[
+            (
+                "core-js",
+                &[
+                    ("modules/es.promise.js", 9000),
+                    ("modules/es.promise.finally.js", 1000),
+                    ("modules/es.string.replace-all.js", 2000),
+                    ("internals/export.js", 3000),
+                ],
+            ),
+            ("whatwg-fetch", &[("dist/fetch.umd.js", 4000)]),
+            ("intersection-observer", &[("intersection-observer.js", 5000)]),
+            ("react", &[("index.js", 6000)]),
+        ]);
+        let targets = BrowserTargets::parse(&["chrome >= 80", "safari >= 13", "firefox >= 78"]);
+        let report = PolyfillReport::from_bundle(&analysis, Some(&targets));
+
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["core-js", "intersection-observer", "whatwg-fetch"]);
+        assert_eq!(report.total_size(), 24000);
+
+        let core_js = &report.packages[0];
+        let modules: Vec<&str> = core_js.unneeded.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(modules, vec!["es.promise"], "safari 13 lacks replaceAll");
+        assert_eq!(core_js.unneeded_size(), 9000);
+        assert_eq!(report.unneeded_size(), 18000);
+
+        let savings = report.to_savings();
+        assert_eq!(savings.len(), 3);
+        assert_eq!(
+            savings[2].suggestion,
+            "fetch is native in every targeted browser; remove whatwg-fetch"
+        );
+
+        let text = report.format_report();
+        assert!(text.starts_with("🩹 3 polyfill package(s), 23.44 KB in the bundle:\n"));
+        assert!(text.contains("    unneeded es.promise (Promise): 8.79 KB\n"));
+        assert!(text.contains("Targets: chrome >= 80, firefox >= 78, safari >= 13\n"));
+    }
+
+    #[test]
+    fn test_without_targets() {
+        let analysis = bundle(&[("regenerator-runtime", &[("runtime.js", 6000)])]);
+        let report = PolyfillReport::from_bundle(&analysis, None);
+        assert_eq!(report.packages.len(), 1);
+        assert_eq!(report.unneeded_size(), 0);
+        assert!(report.to_savings().is_empty());
+        assert!(report.format_report().contains("No browserslist configuration"));
+
+        let ie = BrowserTargets::parse(&["chrome >= 80", "ie 11"]);
+        assert_eq!(PolyfillReport::from_bundle(&analysis, Some(&ie)).unneeded_size(), 0);
+    }
+
+    #[test]
+    fn test_core_js_module() {
+        assert_eq!(core_js_module("./node_modules/core-js/modules/es.array.includes.js"), Some("es.array.includes"));
+        assert_eq!(core_js_module("./node_modules/core-js/internals/export.js"), None);
+    }
+}
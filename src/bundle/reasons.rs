@@ -0,0 +1,314 @@
+//! Why a package is in the bundle.
+//!
+//! Webpack records for every module the reasons it was included: the
+//! modules that import it and the import requests they used. Following
+//! those reasons out of a package gives the source files and packages
+//! that pulled it in, the bundle-level counterpart of asking why a
+//! dependency is installed.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::bundle::webpack::{extract_package_name, format_size, ModuleReason, WebpackModule, WebpackStats};
+
+/// Returns the readable name of a module.
+fn module_name(module: &WebpackModule) -> Option<&str> {
+    module.name.as_deref().or(module.identifier.as_deref())
+}
+
+/// One reason a module was included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionReason {
+    /// The importing module, `None` for entry points
+    pub importer: Option<String>,
+    /// The kind of reference, e.g. `harmony import specifier`
+    pub kind: Option<String>,
+    /// The request as written in the importer, e.g. `lodash/debounce`
+    pub request: Option<String>,
+}
+
+impl InclusionReason {
+    fn from_reason(reason: &ModuleReason) -> Self {
+        Self {
+            importer: reason
+                .module_name
+                .clone()
+                .or_else(|| reason.module.clone())
+                .or_else(|| reason.module_identifier.clone()),
+            kind: reason.reason_type.clone(),
+            request: reason.user_request.clone(),
+        }
+    }
+
+    /// Returns true if the module is an entry point rather than imported.
+    pub fn is_entry(&self) -> bool {
+        self.importer.is_none() || self.kind.as_deref().is_some_and(|kind| kind.contains("entry"))
+    }
+
+    fn describe(&self) -> String {
+        let mut out = self.importer.clone().unwrap_or_else(|| "(entry point)".to_string());
+        if let Some(request) = &self.request {
+            out.push_str(&format!(" '{}'", request));
+        }
+        if let Some(kind) = &self.kind {
+            out.push_str(&format!(" ({})", kind));
+        }
+        out
+    }
+}
+
+/// A module of the package and the reasons it was included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludedModule {
+    /// Module name
+    pub name: String,
+    /// Module size in bytes
+    pub size: u64,
+    /// Reasons from the stats, in stats order
+    pub reasons: Vec<InclusionReason>,
+}
+
+/// A module outside the package that imports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Importer {
+    /// The importing module
+    pub module: String,
+    /// The package of the importing module, `None` for project code
+    pub package: Option<String>,
+    /// Requests the module uses to import the package
+    pub requests: Vec<String>,
+}
+
+/// Why a package is in the bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InclusionReport {
+    /// The package asked about
+    pub package: String,
+    /// The package's modules in the bundle, largest first
+    pub modules: Vec<IncludedModule>,
+    /// Modules outside the package that import it, project code first
+    pub importers: Vec<Importer>,
+    /// Shortest import chain from an entry point to the package, if the
+    /// reasons lead to one
+    pub entry_chain: Option<Vec<String>>,
+}
+
+impl InclusionReport {
+    /// Explains why a package is in the bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - Webpack stats, built with module reasons
+    /// * `package` - The package name, e.g. `lodash` or `@babel/runtime`
+    pub fn explain(stats: &WebpackStats, package: &str) -> Self {
+        let all = stats.all_modules();
+        let in_package = |name: &str| extract_package_name(name).as_deref() == Some(package);
+
+        let mut modules: Vec<IncludedModule> = all
+            .iter()
+            .filter(|module| module.modules.is_empty())
+            .filter_map(|module| {
+                let name = module_name(module)?;
+                in_package(name).then(|| IncludedModule {
+                    name: name.to_string(),
+                    size: module.size,
+                    reasons: module.reasons.iter().map(InclusionReason::from_reason).collect(),
+                })
+            })
+            .collect();
+        modules.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        let mut importers: BTreeMap<String, Importer> = BTreeMap::new();
+        for reason in modules.iter().flat_map(|m| &m.reasons) {
+            let Some(module) = reason.importer.as_ref().filter(|name| !in_package(name)) else {
+                continue;
+            };
+            let importer = importers.entry(module.clone()).or_insert_with(|| Importer {
+                module: module.clone(),
+                package: extract_package_name(module),
+                requests: Vec::new(),
+            });
+            if let Some(request) = &reason.request {
+                if !importer.requests.contains(request) {
+                    importer.requests.push(request.clone());
+                }
+            }
+        }
+        let mut importers: Vec<Importer> = importers.into_values().collect();
+        importers.sort_by(|a, b| a.package.is_some().cmp(&b.package.is_some()).then_with(|| a.module.cmp(&b.module)));
+
+        let entry_chain = entry_chain(&all, &modules);
+        Self {
+            package: package.to_string(),
+            modules,
+            importers,
+            entry_chain,
+        }
+    }
+
+    /// Returns true if the package has no modules in the bundle.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Returns the combined size of the package's modules.
+    pub fn total_size(&self) -> u64 {
+        self.modules.iter().map(|m| m.size).sum()
+    }
+
+    /// Formats the explanation.
+    pub fn format_report(&self) -> String {
+        if self.is_empty() {
+            return format!("{} is not in the bundle.\n", self.package);
+        }
+
+        let mut out = format!(
+            "🔍 Why {} is in the bundle ({} module(s), {})\n",
+            self.package,
+            self.modules.len(),
+            format_size(self.total_size())
+        );
+        if self.modules.iter().all(|m| m.reasons.is_empty()) {
+            out.push_str("\n⚠️  The stats include no module reasons; build with stats.reasons enabled.\n");
+            return out;
+        }
+
+        if !self.importers.is_empty() {
+            out.push_str("\nImported by:\n");
+            for importer in &self.importers {
+                let from = match &importer.package {
+                    Some(package) => format!("{} ({})", package, importer.module),
+                    None => importer.module.clone(),
+                };
+                if importer.requests.is_empty() {
+                    out.push_str(&format!("  {}\n", from));
+                } else {
+                    out.push_str(&format!("  {}: {}\n", from, importer.requests.join(", ")));
+                }
+            }
+        }
+
+        if let Some(chain) = &self.entry_chain {
+            out.push_str(&format!("\nFrom an entry point:\n  {}\n", chain.join(" → ")));
+        }
+
+        out.push_str("\nModules:\n");
+        for module in &self.modules {
+            out.push_str(&format!("  {} {}\n", module.name, format_size(module.size)));
+            for reason in &module.reasons {
+                out.push_str(&format!("    ← {}\n", reason.describe()));
+            }
+        }
+        out
+    }
+}
+
+/// Finds the shortest chain of imports from an entry point to one of the
+/// package's modules by walking reasons backwards.
+fn entry_chain(all: &[&WebpackModule], modules: &[IncludedModule]) -> Option<Vec<String>> {
+    let reasons: HashMap<&str, Vec<InclusionReason>> = all
+        .iter()
+        .filter_map(|module| {
+            let reasons = module.reasons.iter().map(InclusionReason::from_reason).collect();
+            Some((module_name(module)?, reasons))
+        })
+        .collect();
+
+    let mut previous: HashMap<String, String> = HashMap::new();
+    let mut seen: HashSet<String> = modules.iter().map(|m| m.name.clone()).collect();
+    let mut queue: VecDeque<String> = modules.iter().map(|m| m.name.clone()).collect();
+    while let Some(name) = queue.pop_front() {
+        let module_reasons = reasons.get(name.as_str()).map(Vec::as_slice).unwrap_or_default();
+        if module_reasons.iter().any(InclusionReason::is_entry) {
+            let mut chain = vec![name.clone()];
+            let mut current = name;
+            while let Some(next) = previous.get(&current) {
+                chain.push(next.clone());
+                current = next.clone();
+            }
+            return Some(chain);
+        }
+        for importer in module_reasons.iter().filter_map(|r| r.importer.as_ref()) {
+            if seen.insert(importer.clone()) {
+                previous.insert(importer.clone(), name.clone());
+                queue.push_back(importer.clone());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATS: &str = r#"{
+        "modules": [
+            { "name": "./src/index.js", "size": 100, "reasons": [
+                { "type": "entry", "userRequest": "./src/index.js" }
+            ] },
+            { "name": "./src/utils.js", "size": 200, "reasons": [
+                { "moduleName": "./src/index.js", "type": "harmony side effect evaluation", "userRequest": "./utils" }
+            ] },
+            { "name": "./node_modules/react-select/dist/index.js + 3 modules", "size": 900, "modules": [
+                { "name": "./node_modules/react-select/dist/Select.js", "size": 900, "reasons": [
+                    { "moduleName": "./src/index.js", "type": "harmony import specifier", "userRequest": "react-select" }
+                ] }
+            ] },
+            { "name": "./node_modules/lodash/debounce.js", "size": 3000, "reasons": [
+                { "moduleName": "./src/utils.js", "type": "harmony import specifier", "userRequest": "lodash/debounce" }
+            ] },
+            { "name": "./node_modules/lodash/isEqual.js", "size": 5000, "reasons": [
+                { "moduleName": "./node_modules/react-select/dist/Select.js", "type": "cjs require", "userRequest": "lodash/isEqual" },
+                { "moduleName": "./src/utils.js", "type": "harmony import specifier", "userRequest": "lodash/isEqual" }
+            ] },
+            { "name": "./node_modules/lodash/_baseIsEqual.js", "size": 1000, "reasons": [
+                { "moduleName": "./node_modules/lodash/isEqual.js", "type": "cjs require", "userRequest": "./_baseIsEqual" }
+            ] }
+        ]
+    }"#;
+
+    #[test]
+    fn test_explain_package() {
+        let stats = WebpackStats::parse(STATS).unwrap();
+        let report = InclusionReport::explain(&stats, "lodash");
+
+        assert_eq!(report.total_size(), 9000);
+        assert_eq!(report.modules[0].name, "./node_modules/lodash/isEqual.js");
+        let importers: Vec<(&str, Option<&str>, Vec<&str>)> = report
+            .importers
+            .iter()
+            .map(|i| (i.module.as_str(), i.package.as_deref(), i.requests.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            importers,
+            vec![
+                ("./src/utils.js", None, vec!["lodash/isEqual", "lodash/debounce"]),
+                ("./node_modules/react-select/dist/Select.js", Some("react-select"), vec!["lodash/isEqual"]),
+            ]
+        );
+        assert_eq!(
+            report.entry_chain,
+            Some(vec![
+                "./src/index.js".to_string(),
+                "./node_modules/react-select/dist/Select.js".to_string(),
+                "./node_modules/lodash/isEqual.js".to_string(),
+            ])
+        );
+
+        let text = report.format_report();
+        assert!(text.starts_with("🔍 Why lodash is in the bundle (3 module(s), "));
+        assert!(text.contains("  react-select (./node_modules/react-select/dist/Select.js): lodash/isEqual\n"));
+        assert!(text.contains("    ← ./node_modules/lodash/isEqual.js './_baseIsEqual' (cjs require)\n"));
+    }
+
+    #[test]
+    fn test_missing_package_and_reasons() {
+        let stats = WebpackStats::parse(STATS).unwrap();
+        assert_eq!(InclusionReport::explain(&stats, "moment").format_report(), "moment is not in the bundle.\n");
+
+        let stats = WebpackStats::parse(r#"{"modules": [{"name": "./node_modules/dayjs/dayjs.min.js", "size": 7000}]}"#).unwrap();
+        let report = InclusionReport::explain(&stats, "dayjs");
+        assert_eq!(report.entry_chain, None);
+        assert!(report.format_report().contains("no module reasons"));
+    }
+}
@@ -24,6 +24,7 @@
 use std::collections::HashMap;
 
 use crate::analysis::exports::{PackageUsage, ProjectImports};
+use crate::bundle::tree_shaking::TreeShakingReport;
 use crate::bundle::webpack::{format_size, BundleAnalysis, PackageBundleSize};
 
 /// Threshold for considering a package as "underutilized"
@@ -45,6 +46,10 @@ pub enum SavingsCategory {
     TreeShaking,
     /// Package has a lighter alternative available
     HasAlternative,
+    /// `@types/*` package that is no longer needed
+    RemovableTypes,
+    /// Polyfills for features the targeted browsers support natively
+    UnneededPolyfills,
 }
 
 impl SavingsCategory {
@@ -55,6 +60,8 @@ impl SavingsCategory {
             SavingsCategory::Underutilized => "Underutilized",
             SavingsCategory::TreeShaking => "Tree-shaking",
             SavingsCategory::HasAlternative => "Alternative available",
+            SavingsCategory::RemovableTypes => "Removable types",
+            SavingsCategory::UnneededPolyfills => "Unneeded polyfills",
         }
     }
 
@@ -65,6 +72,10 @@ impl SavingsCategory {
             SavingsCategory::Underutilized => "Package is used but most of its exports are unused",
             SavingsCategory::TreeShaking => "Package could have smaller footprint with better tree-shaking",
             SavingsCategory::HasAlternative => "A lighter alternative package exists",
+            SavingsCategory::RemovableTypes => {
+                "Type definitions for a package that ships its own types or is not a dependency"
+            }
+            SavingsCategory::UnneededPolyfills => "Polyfills for features every targeted browser supports natively",
         }
     }
 }
@@ -128,6 +139,10 @@ pub struct SavingsSummary {
     pub underutilized_count: usize,
     /// Number of packages with tree-shaking opportunities
     pub tree_shaking_count: usize,
+    /// Number of removable `@types/*` packages
+    pub removable_types_count: usize,
+    /// Number of packages with unneeded polyfills
+    pub unneeded_polyfill_count: usize,
 }
 
 impl SavingsSummary {
@@ -158,13 +173,15 @@ pub struct SavingsReport {
     pub package_savings: Vec<PackageSavings>,
     /// Summary statistics
     pub summary: SavingsSummary,
+    /// Tree-shaking scores from detailed webpack stats, if available
+    pub tree_shaking: TreeShakingReport,
 }
 
 impl SavingsReport {
     /// Get package savings sorted by potential savings (largest first)
     pub fn savings_by_size(&self) -> Vec<&PackageSavings> {
         let mut sorted: Vec<_> = self.package_savings.iter().collect();
-        sorted.sort_by(|a, b| b.potential_savings.cmp(&a.potential_savings));
+        sorted.sort_by_key(|s| std::cmp::Reverse(s.potential_savings));
         sorted
     }
 
@@ -176,6 +193,41 @@ impl SavingsReport {
             .collect()
     }
 
+    /// Adds a package's savings and updates the summary.
+    pub fn add(&mut self, saving: PackageSavings) {
+        match saving.category {
+            SavingsCategory::Unused => self.summary.unused_count += 1,
+            SavingsCategory::Underutilized => self.summary.underutilized_count += 1,
+            SavingsCategory::TreeShaking => self.summary.tree_shaking_count += 1,
+            SavingsCategory::RemovableTypes => self.summary.removable_types_count += 1,
+            SavingsCategory::UnneededPolyfills => self.summary.unneeded_polyfill_count += 1,
+            SavingsCategory::HasAlternative => {}
+        }
+
+        self.summary.total_potential_savings += saving.potential_savings;
+        self.summary.packages_with_savings += 1;
+        self.package_savings.push(saving);
+    }
+
+    /// Removes a package's savings, e.g. to replace an estimate with a
+    /// more specific finding, and updates the summary.
+    pub fn remove(&mut self, package_name: &str) -> Option<PackageSavings> {
+        let index = self.package_savings.iter().position(|s| s.package_name == package_name)?;
+        let saving = self.package_savings.remove(index);
+        match saving.category {
+            SavingsCategory::Unused => self.summary.unused_count -= 1,
+            SavingsCategory::Underutilized => self.summary.underutilized_count -= 1,
+            SavingsCategory::TreeShaking => self.summary.tree_shaking_count -= 1,
+            SavingsCategory::RemovableTypes => self.summary.removable_types_count -= 1,
+            SavingsCategory::UnneededPolyfills => self.summary.unneeded_polyfill_count -= 1,
+            SavingsCategory::HasAlternative => {}
+        }
+
+        self.summary.total_potential_savings -= saving.potential_savings;
+        self.summary.packages_with_savings -= 1;
+        Some(saving)
+    }
+
     /// Format the report as a text string suitable for CI output
     pub fn format_report(&self) -> String {
         let mut output = String::new();
@@ -213,9 +265,24 @@ impl SavingsReport {
                 self.summary.tree_shaking_count
             ));
         }
+        if self.summary.removable_types_count > 0 {
+            output.push_str(&format!(
+                "Removable type packages: {}\n",
+                self.summary.removable_types_count
+            ));
+        }
+        if self.summary.unneeded_polyfill_count > 0 {
+            output.push_str(&format!(
+                "Unneeded polyfills: {}\n",
+                self.summary.unneeded_polyfill_count
+            ));
+        }
 
         output.push('\n');
 
+        // Worst tree-shaking offenders, when the stats track export usage
+        output.push_str(&self.tree_shaking.format_offenders(5));
+
         // Individual packages
         if !self.package_savings.is_empty() {
             output.push_str("--- Package Details ---\n\n");
@@ -226,15 +293,18 @@ impl SavingsReport {
                     saving.package_name,
                     saving.category.label()
                 ));
-                output.push_str(&format!(
-                    "  Current size: {}\n",
-                    saving.format_current_size()
-                ));
-                output.push_str(&format!(
-                    "  Potential savings: {} ({:.1}%)\n",
-                    saving.format_potential_savings(),
-                    saving.savings_percentage()
-                ));
+                // Type packages never reach the bundle
+                if saving.category != SavingsCategory::RemovableTypes {
+                    output.push_str(&format!(
+                        "  Current size: {}\n",
+                        saving.format_current_size()
+                    ));
+                    output.push_str(&format!(
+                        "  Potential savings: {} ({:.1}%)\n",
+                        saving.format_potential_savings(),
+                        saving.savings_percentage()
+                    ));
+                }
                 if let Some(util) = saving.utilization_percentage {
                     output.push_str(&format!("  Utilization: {:.1}%\n", util));
                 }
@@ -321,17 +391,7 @@ impl SavingsCalculator {
                 project_imports.package_usage.get(package_name),
                 export_counts.get(package_name).copied(),
             ) {
-                // Update summary counts
-                match saving.category {
-                    SavingsCategory::Unused => report.summary.unused_count += 1,
-                    SavingsCategory::Underutilized => report.summary.underutilized_count += 1,
-                    SavingsCategory::TreeShaking => report.summary.tree_shaking_count += 1,
-                    SavingsCategory::HasAlternative => {}
-                }
-
-                report.summary.total_potential_savings += saving.potential_savings;
-                report.summary.packages_with_savings += 1;
-                report.package_savings.push(saving);
+                report.add(saving);
             }
         }
 
@@ -481,16 +541,7 @@ impl SavingsCalculator {
             let util = utilization.get(package_name).copied();
 
             if let Some(saving) = self.analyze_from_utilization(package_name, size, util) {
-                match saving.category {
-                    SavingsCategory::Unused => report.summary.unused_count += 1,
-                    SavingsCategory::Underutilized => report.summary.underutilized_count += 1,
-                    SavingsCategory::TreeShaking => report.summary.tree_shaking_count += 1,
-                    SavingsCategory::HasAlternative => {}
-                }
-
-                report.summary.total_potential_savings += saving.potential_savings;
-                report.summary.packages_with_savings += 1;
-                report.package_savings.push(saving);
+                report.add(saving);
             }
         }
 
@@ -644,8 +695,10 @@ mod tests {
         imports.package_usage.insert("lodash".to_string(), lodash_usage);
 
         // Moment is imported
-        let mut moment_usage = PackageUsage::default();
-        moment_usage.uses_default = true;
+        let mut moment_usage = PackageUsage {
+            uses_default: true,
+            ..Default::default()
+        };
         moment_usage.importing_files.insert("src/date.ts".to_string());
         imports.package_usage.insert("moment".to_string(), moment_usage);
 
@@ -669,6 +722,7 @@ mod tests {
         assert_eq!(SavingsCategory::Underutilized.label(), "Underutilized");
         assert_eq!(SavingsCategory::TreeShaking.label(), "Tree-shaking");
         assert_eq!(SavingsCategory::HasAlternative.label(), "Alternative available");
+        assert_eq!(SavingsCategory::RemovableTypes.label(), "Removable types");
     }
 
     #[test]
@@ -722,6 +776,8 @@ mod tests {
             unused_count: 1,
             underutilized_count: 1,
             tree_shaking_count: 0,
+            removable_types_count: 0,
+            unneeded_polyfill_count: 0,
         };
 
         assert!((summary.savings_percentage() - 25.0).abs() < 0.1);
@@ -916,6 +972,62 @@ mod tests {
         assert!(report.has_savings());
     }
 
+    #[test]
+    fn test_report_removable_types() {
+        let mut report = SavingsReport::default();
+        report.add(PackageSavings {
+            package_name: "@types/express".to_string(),
+            current_size: 0,
+            potential_savings: 0,
+            category: SavingsCategory::RemovableTypes,
+            utilization_percentage: None,
+            exports_used: 0,
+            total_exports: None,
+            suggestion: "express is not a dependency; remove @types/express".to_string(),
+            alternative: None,
+        });
+
+        assert_eq!(report.summary.removable_types_count, 1);
+        assert_eq!(report.summary.packages_with_savings, 1);
+        assert!(!report.has_savings());
+
+        let formatted = report.format_report();
+        assert!(formatted.contains("Removable type packages: 1\n"));
+        assert!(formatted.contains("@types/express [Removable types]\n  Suggestion:"));
+
+        let removed = report.remove("@types/express").unwrap();
+        assert_eq!(removed.category, SavingsCategory::RemovableTypes);
+        assert_eq!(report.summary.removable_types_count, 0);
+        assert_eq!(report.summary.packages_with_savings, 0);
+        assert!(report.remove("@types/express").is_none());
+    }
+
+    #[test]
+    fn test_report_lists_tree_shaking_offenders_first() {
+        use crate::bundle::tree_shaking::TreeShakingScore;
+
+        let score = TreeShakingScore {
+            package: "lodash-es".to_string(),
+            shipped: 100 * 1024,
+            needed: 20 * 1024,
+            coverage: 100.0,
+            source_utilization: None,
+        };
+        let mut report = SavingsReport {
+            tree_shaking: TreeShakingReport { scores: vec![score] },
+            ..Default::default()
+        };
+        for saving in report.tree_shaking.to_savings() {
+            report.add(saving);
+        }
+        let formatted = report.format_report();
+
+        assert_eq!(report.summary.tree_shaking_count, 1);
+        let offenders = formatted.find("--- Worst Tree-shaking ---").unwrap();
+        assert!(offenders < formatted.find("--- Package Details ---").unwrap());
+        assert!(formatted.contains("  Potential savings: 80.00 KB (80.0%)"));
+    }
+
     #[test]
     fn test_report_format() {
         let calc = SavingsCalculator::new();
@@ -981,8 +1093,11 @@ mod tests {
         analysis.package_sizes.insert("namespace-pkg".to_string(), pkg);
 
         let mut imports = ProjectImports::new();
-        let mut usage = PackageUsage::default();
-        usage.uses_namespace = true; // import * as pkg from 'namespace-pkg'
+        // import * as pkg from 'namespace-pkg'
+        let mut usage = PackageUsage {
+            uses_namespace: true,
+            ..Default::default()
+        };
         usage.importing_files.insert("src/app.ts".to_string());
         imports.package_usage.insert("namespace-pkg".to_string(), usage);
 
@@ -1008,8 +1123,11 @@ mod tests {
         analysis.package_sizes.insert("polyfill-pkg".to_string(), pkg);
 
         let mut imports = ProjectImports::new();
-        let mut usage = PackageUsage::default();
-        usage.has_side_effects = true; // import 'polyfill-pkg'
+        // import 'polyfill-pkg'
+        let mut usage = PackageUsage {
+            has_side_effects: true,
+            ..Default::default()
+        };
         usage.importing_files.insert("src/index.ts".to_string());
         imports.package_usage.insert("polyfill-pkg".to_string(), usage);
 
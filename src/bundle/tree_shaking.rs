@@ -0,0 +1,238 @@
+//! Tree-shaking effectiveness per package.
+//!
+//! With `usedExports` and `providedExports` in the stats, webpack says
+//! which exports of each module survive. Where it could not narrow the
+//! usage down (CommonJS, side effects), the project's own imports give an
+//! estimate instead. Comparing the bytes shipped with the bytes those
+//! exports plausibly need shows which packages tree-shake poorly.
+
+use crate::analysis::exports::ProjectImports;
+use crate::bundle::savings::{PackageSavings, SavingsCategory};
+use crate::bundle::webpack::{format_size, BundleAnalysis, PackageBundleSize};
+
+/// Scores below this percentage count as ineffective tree-shaking.
+const SCORE_THRESHOLD: f64 = 80.0;
+
+/// Wasted bytes below this are not worth a savings entry.
+const MIN_WASTE: u64 = 10 * 1024;
+
+/// How well a package tree-shakes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeShakingScore {
+    /// Package name
+    pub package: String,
+    /// Bytes of the package in the bundle
+    pub shipped: u64,
+    /// Estimated bytes the used exports need
+    pub needed: u64,
+    /// Share of the package webpack tracked export usage for, 0-100
+    pub coverage: f64,
+    /// Share of the package's exports the project imports, when known
+    pub source_utilization: Option<f64>,
+}
+
+impl TreeShakingScore {
+    /// Scores a package of the bundle.
+    ///
+    /// Bytes of modules without export usage from webpack are scaled by
+    /// the share of the package's exports the project imports, or counted
+    /// as needed if that is unknown too.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither webpack nor the project's imports say anything
+    /// about which exports are used.
+    pub fn from_package(package: &PackageBundleSize, imports: Option<&ProjectImports>) -> Option<Self> {
+        if package.total_size == 0 {
+            return None;
+        }
+        let source_utilization = imports
+            .and_then(|imports| imports.package_usage.get(&package.name))
+            .and_then(|usage| usage.utilization_percentage(package.provided_export_count))
+            .map(|util| util.min(100.0));
+        if package.export_tracked_size == 0 && source_utilization.is_none() {
+            return None;
+        }
+
+        let untracked = package.total_size.saturating_sub(package.export_tracked_size);
+        let untracked_needed = match source_utilization {
+            Some(util) => (untracked as f64 * util / 100.0).round() as u64,
+            None => untracked,
+        };
+        Some(Self {
+            package: package.name.clone(),
+            shipped: package.total_size,
+            needed: (package.used_export_size + untracked_needed).min(package.total_size),
+            coverage: package.export_tracked_size.min(package.total_size) as f64 / package.total_size as f64 * 100.0,
+            source_utilization,
+        })
+    }
+
+    /// Returns the effectiveness score: the share of shipped bytes the
+    /// used exports need, 0-100.
+    pub fn score(&self) -> f64 {
+        if self.shipped == 0 {
+            100.0
+        } else {
+            self.needed as f64 / self.shipped as f64 * 100.0
+        }
+    }
+
+    /// Returns the bytes shipped beyond what the used exports need.
+    pub fn wasted(&self) -> u64 {
+        self.shipped.saturating_sub(self.needed)
+    }
+}
+
+/// Tree-shaking scores of the packages in a bundle.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeShakingReport {
+    /// Scores, worst first
+    pub scores: Vec<TreeShakingScore>,
+}
+
+impl TreeShakingReport {
+    /// Scores every package of a bundle that export usage is known for.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - Bundle analysis of stats built with `usedExports` and
+    ///   `providedExports`
+    /// * `imports` - The project's imports, for modules webpack could not
+    ///   narrow down
+    pub fn from_bundle(bundle: &BundleAnalysis, imports: Option<&ProjectImports>) -> Self {
+        let mut scores: Vec<TreeShakingScore> = bundle
+            .package_sizes
+            .values()
+            .filter_map(|package| TreeShakingScore::from_package(package, imports))
+            .collect();
+        scores.sort_by(|a, b| {
+            a.score()
+                .total_cmp(&b.score())
+                .then_with(|| b.wasted().cmp(&a.wasted()))
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        Self { scores }
+    }
+
+    /// Returns true if no package could be scored.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Returns the packages that tree-shake poorly enough to report.
+    pub fn offenders(&self) -> impl Iterator<Item = &TreeShakingScore> {
+        self.scores
+            .iter()
+            .filter(|score| score.score() < SCORE_THRESHOLD && score.wasted() >= MIN_WASTE)
+    }
+
+    /// Converts the offenders to savings report entries.
+    pub fn to_savings(&self) -> Vec<PackageSavings> {
+        self.offenders()
+            .map(|score| PackageSavings {
+                package_name: score.package.clone(),
+                current_size: score.shipped,
+                potential_savings: score.wasted(),
+                category: SavingsCategory::TreeShaking,
+                utilization_percentage: Some(score.score()),
+                exports_used: 0,
+                total_exports: None,
+                suggestion: format!(
+                    "Only {:.0}% of the shipped code is used; import specific modules or an ES module build",
+                    score.score()
+                ),
+                alternative: None,
+            })
+            .collect()
+    }
+
+    /// Formats the worst offenders as a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of packages to list
+    pub fn format_offenders(&self, limit: usize) -> String {
+        let offenders: Vec<&TreeShakingScore> = self.offenders().take(limit).collect();
+        if offenders.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("--- Worst Tree-shaking ---\n\n");
+        out.push_str(&format!("  {:<30} {:>6} {:>12} {:>12}\n", "Package", "Score", "Shipped", "Needed"));
+        for score in offenders {
+            out.push_str(&format!(
+                "  {:<30} {:>5.0}% {:>12} {:>12}\n",
+                score.package,
+                score.score(),
+                format_size(score.shipped),
+                format_size(score.needed)
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::exports::PackageUsage;
+    use crate::bundle::WebpackStats;
+
+    const STATS: &str = r#"{
+        "modules": [
+            { "name": "./node_modules/lodash-es/lodash.js", "size": 4000,
+              "providedExports": ["chunk", "debounce", "map", "throttle"], "usedExports": ["debounce"] },
+            { "name": "./node_modules/lodash-es/debounce.js", "size": 16000,
+              "providedExports": ["default"], "usedExports": ["default"] },
+            { "name": "./node_modules/lodash-es/chunk.js", "size": 30000,
+              "providedExports": ["default"], "usedExports": false },
+            { "name": "./node_modules/moment/moment.js", "size": 60000,
+              "providedExports": ["default", "duration", "locale", "utc"], "usedExports": true },
+            { "name": "./node_modules/react/index.js", "size": 8000 }
+        ]
+    }"#;
+
+    #[test]
+    fn test_tree_shaking_scores() {
+        let bundle = WebpackStats::parse(STATS).unwrap().analyze();
+        let report = TreeShakingReport::from_bundle(&bundle, None);
+
+        let scores: Vec<(&str, u64, u64)> = report
+            .scores
+            .iter()
+            .map(|s| (s.package.as_str(), s.shipped, s.needed))
+            .collect();
+        assert_eq!(scores, vec![("lodash-es", 50000, 17000)], "webpack only tracked lodash-es");
+        assert_eq!(report.scores[0].coverage, 100.0);
+        assert_eq!(report.scores[0].wasted(), 33000);
+
+        let savings = report.to_savings();
+        assert_eq!(savings.len(), 1);
+        assert_eq!(savings[0].category, SavingsCategory::TreeShaking);
+        assert_eq!(savings[0].potential_savings, 33000);
+        assert!(report.format_offenders(5).contains("  lodash-es                         34%"));
+    }
+
+    #[test]
+    fn test_source_imports_fill_untracked_modules() {
+        let bundle = WebpackStats::parse(STATS).unwrap().analyze();
+        let mut imports = ProjectImports::new();
+        imports.package_usage.insert(
+            "moment".to_string(),
+            PackageUsage {
+                uses_default: true,
+                ..Default::default()
+            },
+        );
+        let report = TreeShakingReport::from_bundle(&bundle, Some(&imports));
+
+        let moment = &report.scores[0];
+        assert_eq!(moment.package, "moment");
+        assert_eq!(moment.source_utilization, Some(25.0));
+        assert_eq!(moment.coverage, 0.0);
+        assert_eq!(moment.needed, 15000);
+        assert!(report.scores.iter().all(|s| s.package != "react"), "nothing is known about react");
+    }
+}
@@ -2,13 +2,24 @@
 //!
 //! This module handles parsing of webpack-bundle-analyzer JSON output (stats.json)
 //! to extract module sizes, chunks, and asset information.
+//!
+//! Modules, assets and chunks that cannot be read are skipped and listed in
+//! [`WebpackStats::parse_warnings`], so one odd entry does not hide the
+//! sizes of the rest of the build.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::Path;
 
+use super::chunks::ChunkGraph;
+use crate::config::Locale;
+use crate::parser::paths::{normalize_separators, to_slash};
+use crate::parser::pnpm::{normalize_store_path, StoreEntry, VIRTUAL_STORE};
+use crate::parser::recovery::{recover_list, recover_map, ParseWarning, SyntaxError};
+
 /// Represents a webpack stats.json file output.
 ///
 /// This is the top-level structure produced by webpack when configured
@@ -55,6 +66,10 @@ pub struct WebpackStats {
     /// Build warnings
     #[serde(default)]
     pub warnings: Vec<WebpackWarning>,
+
+    /// Entries skipped while parsing because they could not be read
+    #[serde(skip)]
+    pub parse_warnings: Vec<ParseWarning>,
 }
 
 /// Represents a generated asset file from webpack build.
@@ -240,6 +255,70 @@ pub struct WebpackModule {
 
     /// Module depth in the dependency tree
     pub depth: Option<usize>,
+
+    /// Exports the module provides (with `stats.providedExports`)
+    pub provided_exports: Option<Vec<String>>,
+
+    /// Exports other modules use (with `stats.usedExports`)
+    pub used_exports: Option<UsedExports>,
+}
+
+/// Which exports of a module are used, as determined by webpack.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum UsedExports {
+    /// `true` if webpack could not narrow the usage down, `false` if no
+    /// export is used and the module is only kept for its side effects
+    All(bool),
+    /// The names of the used exports
+    Names(Vec<String>),
+}
+
+impl WebpackModule {
+    /// Rewrites the paths of the module, its reasons and its concatenated
+    /// modules with `/` separators.
+    fn normalize_separators(&mut self) {
+        for path in [&mut self.identifier, &mut self.name, &mut self.issuer, &mut self.issuer_name] {
+            path.iter_mut().for_each(normalize_separators);
+        }
+        for reason in &mut self.reasons {
+            for path in [&mut reason.module, &mut reason.module_identifier, &mut reason.module_name] {
+                path.iter_mut().for_each(normalize_separators);
+            }
+        }
+        for module in &mut self.modules {
+            module.normalize_separators();
+        }
+    }
+
+    /// Drops the module's source, reasons and issuer, here and in its
+    /// concatenated modules.
+    fn shed_detail(&mut self) {
+        self.source = None;
+        self.reasons = Vec::new();
+        self.issuer = None;
+        self.issuer_name = None;
+        for module in &mut self.modules {
+            module.shed_detail();
+        }
+    }
+
+    /// Estimates the bytes of the module that its used exports account
+    /// for, if webpack determined them.
+    ///
+    /// Bytes are assumed to spread evenly over the provided exports.
+    pub fn used_export_size(&self) -> Option<u64> {
+        match &self.used_exports {
+            Some(UsedExports::All(false)) => Some(0),
+            Some(UsedExports::Names(used)) if used.is_empty() => Some(0),
+            Some(UsedExports::Names(used)) => {
+                let provided = self.provided_exports.as_ref().filter(|p| !p.is_empty())?;
+                let used = used.len().min(provided.len());
+                Some((self.size as f64 * used as f64 / provided.len() as f64).round() as u64)
+            }
+            Some(UsedExports::All(true)) | None => None,
+        }
+    }
 }
 
 /// Reason why a module was included in the build.
@@ -395,6 +474,23 @@ pub struct PackageBundleSize {
 
     /// Individual module sizes: (module_path, size)
     pub modules: Vec<(String, u64)>,
+
+    /// Total size in bytes of the assets (CSS, images, fonts) its modules
+    /// emit, not included in `total_size`
+    pub asset_size: u64,
+
+    /// Emitted assets: (asset name, size)
+    pub assets: Vec<(String, u64)>,
+
+    /// Bytes of the modules whose used exports webpack determined
+    pub export_tracked_size: u64,
+
+    /// Estimated bytes of those modules that the used exports account for
+    pub used_export_size: u64,
+
+    /// Most exports provided by one of the package's modules, usually its
+    /// entry point
+    pub provided_export_count: usize,
 }
 
 impl PackageBundleSize {
@@ -405,7 +501,28 @@ impl PackageBundleSize {
             total_size: 0,
             module_count: 0,
             modules: Vec::new(),
+            asset_size: 0,
+            assets: Vec::new(),
+            export_tracked_size: 0,
+            used_export_size: 0,
+            provided_export_count: 0,
+        }
+    }
+
+    /// Record webpack's export usage of one of the package's modules.
+    pub fn add_export_usage(&mut self, module: &WebpackModule) {
+        if let Some(used) = module.used_export_size() {
+            self.export_tracked_size += module.size;
+            self.used_export_size += used;
         }
+        let provided = module.provided_exports.as_ref().map_or(0, Vec::len);
+        self.provided_export_count = self.provided_export_count.max(provided);
+    }
+
+    /// Add an emitted asset to this package.
+    pub fn add_asset(&mut self, asset_name: String, size: u64) {
+        self.asset_size += size;
+        self.assets.push((asset_name, size));
     }
 
     /// Add a module's size to this package.
@@ -425,6 +542,123 @@ impl PackageBundleSize {
     }
 }
 
+/// The type of an emitted asset, by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssetKind {
+    /// `.js`, `.mjs`, `.cjs`
+    JavaScript,
+    /// `.css`
+    Css,
+    /// `.png`, `.jpg`, `.svg`, `.webp`, ...
+    Image,
+    /// `.woff2`, `.woff`, `.ttf`, ...
+    Font,
+    /// Audio and video files
+    Media,
+    /// `.wasm`
+    WebAssembly,
+    /// `.map`
+    SourceMap,
+    /// Anything else, e.g. `.html` or `.json`
+    Other,
+}
+
+impl AssetKind {
+    /// Classifies an asset by the extension of its name, ignoring any
+    /// query string or fragment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use codescope::bundle::webpack::AssetKind;
+    ///
+    /// assert_eq!(AssetKind::from_name("main.3f2a1c.js"), AssetKind::JavaScript);
+    /// assert_eq!(AssetKind::from_name("fonts/icons.woff2?v=4.7.0"), AssetKind::Font);
+    /// assert_eq!(AssetKind::from_name("main.js.map"), AssetKind::SourceMap);
+    /// ```
+    pub fn from_name(name: &str) -> Self {
+        let path = name.split(['?', '#']).next().unwrap_or(name);
+        let extension = path
+            .rsplit_once('.')
+            .filter(|(_, ext)| !ext.contains('/'))
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "js" | "mjs" | "cjs" => AssetKind::JavaScript,
+            "css" => AssetKind::Css,
+            "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico" | "bmp" => AssetKind::Image,
+            "woff" | "woff2" | "ttf" | "otf" | "eot" => AssetKind::Font,
+            "mp4" | "webm" | "ogg" | "mp3" | "wav" => AssetKind::Media,
+            "wasm" => AssetKind::WebAssembly,
+            "map" => AssetKind::SourceMap,
+            _ => AssetKind::Other,
+        }
+    }
+
+    /// Returns a display label for the asset type.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AssetKind::JavaScript => "JavaScript",
+            AssetKind::Css => "CSS",
+            AssetKind::Image => "Images",
+            AssetKind::Font => "Fonts",
+            AssetKind::Media => "Media",
+            AssetKind::WebAssembly => "WebAssembly",
+            AssetKind::SourceMap => "Source maps",
+            AssetKind::Other => "Other",
+        }
+    }
+}
+
+/// Combined size of the emitted assets of one type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetTotal {
+    /// Asset type
+    pub kind: AssetKind,
+    /// Total size in bytes
+    pub size: u64,
+    /// Number of assets
+    pub count: usize,
+}
+
+/// How a package ships its own copy of another package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendorKind {
+    /// Listed in the parent's `bundledDependencies` and installed in its
+    /// private `node_modules`
+    Bundled,
+    /// Copied into the parent's own files (e.g. `dist/compiled/<pkg>/`)
+    Vendored,
+}
+
+impl VendorKind {
+    /// Returns a short label for the vendoring kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            VendorKind::Bundled => "bundled",
+            VendorKind::Vendored => "vendored",
+        }
+    }
+}
+
+/// A copy of one package shipped inside another.
+///
+/// The copy's size is counted towards `parent` in
+/// [`BundleAnalysis::package_sizes`], not towards `package`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VendoredCopy {
+    /// Package that ships the copy
+    pub parent: String,
+    /// Package that was copied
+    pub package: String,
+    /// How the copy is shipped
+    pub kind: VendorKind,
+    /// Total size in bytes of the copy's modules
+    pub size: u64,
+    /// Number of modules in the copy
+    pub module_count: usize,
+}
+
 /// Result of parsing and analyzing webpack stats.
 #[derive(Debug, Clone, Default)]
 pub struct BundleAnalysis {
@@ -445,20 +679,223 @@ pub struct BundleAnalysis {
 
     /// Number of modules
     pub module_count: usize,
+
+    /// Packages shipped inside other packages
+    pub vendored: Vec<VendoredCopy>,
+
+    /// Emitted assets per type, largest first
+    pub asset_totals: Vec<AssetTotal>,
+
+    /// Chunks and their parent/child relationships
+    pub chunk_graph: ChunkGraph,
 }
 
 impl BundleAnalysis {
     /// Get packages sorted by size (largest first).
     pub fn packages_by_size(&self) -> Vec<&PackageBundleSize> {
         let mut packages: Vec<_> = self.package_sizes.values().collect();
-        packages.sort_by(|a, b| b.total_size.cmp(&a.total_size));
+        packages.sort_by_key(|p| std::cmp::Reverse(p.total_size));
         packages
     }
 
+    /// Get packages that emit assets, sorted by asset size (largest first).
+    pub fn packages_by_asset_size(&self) -> Vec<&PackageBundleSize> {
+        let mut packages: Vec<_> = self.package_sizes.values().filter(|p| p.asset_size > 0).collect();
+        packages.sort_by(|a, b| b.asset_size.cmp(&a.asset_size).then_with(|| a.name.cmp(&b.name)));
+        packages
+    }
+
+    /// Returns the size users download: the emitted assets, or the
+    /// modules if the stats list no assets.
+    pub fn shipped_size(&self) -> u64 {
+        if self.total_asset_size > 0 {
+            self.total_asset_size
+        } else {
+            self.total_module_size
+        }
+    }
+
+    /// Drops the module lists of the packages and the unmapped modules,
+    /// keeping the totals, to save memory on very large builds.
+    ///
+    /// Reports that look at individual modules, such as the polyfill and
+    /// bundled dependency detection, find nothing afterwards.
+    pub fn shed_module_detail(&mut self) {
+        for package in self.package_sizes.values_mut() {
+            package.modules = Vec::new();
+        }
+        self.unmapped_modules = Vec::new();
+    }
+
+    /// Formats the bundle totals and its largest packages.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of packages to list
+    pub fn format_summary(&self, limit: usize) -> String {
+        let mut out = format!(
+            "📦 Bundle: {} in {} assets, {} modules in {} chunks\n",
+            format_size(self.total_asset_size),
+            self.asset_totals.iter().map(|t| t.count).sum::<usize>(),
+            self.module_count,
+            self.chunk_count
+        );
+        let packages = self.packages_by_size();
+        if packages.is_empty() {
+            return out;
+        }
+
+        out.push_str(&format!("\nLargest packages ({} in total):\n", packages.len()));
+        for package in packages.iter().take(limit) {
+            out.push_str(&format!(
+                "  {:<30} {:>12} {:>6.1}%\n",
+                package.name,
+                format_size(package.total_size),
+                package.percentage_of(self.total_module_size)
+            ));
+        }
+        out
+    }
+
+    /// Formats the bundle composition by asset type and the packages
+    /// that emit non-JavaScript assets.
+    pub fn format_asset_breakdown(&self) -> String {
+        if self.asset_totals.is_empty() {
+            return "No assets in the stats file; run webpack with --json to include them.\n".to_string();
+        }
+
+        let count: usize = self.asset_totals.iter().map(|t| t.count).sum();
+        let mut out = format!(
+            "📦 Bundle composition ({} assets, {})\n\n",
+            count,
+            format_size(self.total_asset_size)
+        );
+        out.push_str(&format!("  {:<12} {:>6} {:>12} {:>7}\n", "Type", "Assets", "Size", "Share"));
+        for total in &self.asset_totals {
+            let share = if self.total_asset_size == 0 {
+                0.0
+            } else {
+                total.size as f64 / self.total_asset_size as f64 * 100.0
+            };
+            out.push_str(&format!(
+                "  {:<12} {:>6} {:>12} {:>6.1}%\n",
+                total.kind.label(),
+                total.count,
+                format_size(total.size),
+                share
+            ));
+        }
+
+        let packages = self.packages_by_asset_size();
+        if !packages.is_empty() {
+            out.push_str("\nAssets emitted by packages:\n");
+            for package in packages {
+                out.push_str(&format!(
+                    "  {}: {} ({} asset(s))\n",
+                    package.name,
+                    format_size(package.asset_size),
+                    package.assets.len()
+                ));
+            }
+        }
+        out
+    }
+
     /// Get the size for a specific package.
     pub fn get_package_size(&self, name: &str) -> Option<u64> {
         self.package_sizes.get(name).map(|p| p.total_size)
     }
+
+    /// Returns the number of exports webpack reports each package
+    /// providing, for the packages where it reports any.
+    pub fn export_counts(&self) -> HashMap<String, usize> {
+        self.package_sizes
+            .values()
+            .filter(|p| p.provided_export_count > 0)
+            .map(|p| (p.name.clone(), p.provided_export_count))
+            .collect()
+    }
+
+    /// Re-attributes packages installed in a parent's private
+    /// `node_modules` to that parent when the parent lists them in its
+    /// `bundledDependencies`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundled` - Map of parent package names to their `bundledDependencies`
+    ///
+    /// # Returns
+    ///
+    /// The number of modules that were moved to a parent.
+    pub fn apply_bundled_dependencies(&mut self, bundled: &HashMap<String, Vec<String>>) -> usize {
+        let mut moved = 0;
+
+        for (parent, children) in bundled {
+            for child in children {
+                let marker = format!("node_modules/{}/node_modules/{}/", parent, child);
+                let Some(child_size) = self.package_sizes.get_mut(child) else {
+                    continue;
+                };
+
+                let (copied, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut child_size.modules)
+                    .into_iter()
+                    .partition(|(path, _)| path.contains(&marker));
+                child_size.total_size = kept.iter().map(|(_, size)| size).sum();
+                child_size.module_count = kept.len();
+                child_size.modules = kept;
+                if child_size.modules.is_empty() {
+                    self.package_sizes.remove(child);
+                }
+
+                for (path, size) in copied {
+                    self.package_sizes
+                        .entry(parent.clone())
+                        .or_insert_with(|| PackageBundleSize::new(parent.clone()))
+                        .add_module(path, size);
+                    self.record_vendored(parent, child, VendorKind::Bundled, size);
+                    moved += 1;
+                }
+            }
+        }
+
+        moved
+    }
+
+    /// Returns vendored copies of packages that are also top-level
+    /// dependencies, i.e. code that likely ships twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `top_level` - Names of the project's direct dependencies
+    pub fn duplicated_vendored<'a>(&'a self, top_level: &[&str]) -> Vec<&'a VendoredCopy> {
+        self.vendored
+            .iter()
+            .filter(|copy| top_level.contains(&copy.package.as_str()))
+            .collect()
+    }
+
+    /// Adds a module to the vendored copy of `package` inside `parent`.
+    fn record_vendored(&mut self, parent: &str, package: &str, kind: VendorKind, size: u64) {
+        let index = match self
+            .vendored
+            .iter()
+            .position(|c| c.parent == parent && c.package == package && c.kind == kind)
+        {
+            Some(index) => index,
+            None => {
+                self.vendored.push(VendoredCopy {
+                    parent: parent.to_string(),
+                    package: package.to_string(),
+                    kind,
+                    size: 0,
+                    module_count: 0,
+                });
+                self.vendored.len() - 1
+            }
+        };
+        self.vendored[index].size += size;
+        self.vendored[index].module_count += 1;
+    }
 }
 
 impl WebpackStats {
@@ -493,9 +930,98 @@ impl WebpackStats {
     ///
     /// # Returns
     ///
-    /// The parsed `WebpackStats` or an IO error.
+    /// The parsed `WebpackStats`, with unreadable entries skipped and
+    /// listed in `parse_warnings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidData` error wrapping a [`SyntaxError`] if the
+    /// input is not JSON, or if it is not a JSON object.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::bundle::webpack::WebpackStats;
+    ///
+    /// let stats = WebpackStats::parse(r#"{"modules": [
+    ///   { "name": "./node_modules/react/index.js", "size": 190 },
+    ///   { "name": "./node_modules/lodash/lodash.js", "size": "big" }
+    /// ]}"#).unwrap();
+    /// assert_eq!(stats.modules.len(), 1);
+    /// assert_eq!(stats.parse_warnings[0].location, "modules[1] (./node_modules/lodash/lodash.js)");
+    /// ```
     pub fn parse(json: &str) -> io::Result<Self> {
-        serde_json::from_str(json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, SyntaxError::from_json(json, &e)))?;
+        let Value::Object(mut fields) = value else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "stats must be a JSON object"));
+        };
+
+        let mut warnings = Vec::new();
+        let assets = recover_list(fields.remove("assets"), "assets", &mut warnings);
+        let chunks = recover_list(fields.remove("chunks"), "chunks", &mut warnings);
+        let modules = recover_list(fields.remove("modules"), "modules", &mut warnings);
+        let entrypoints = recover_map(fields.remove("entrypoints"), "entrypoints", &mut warnings);
+        let named_chunk_groups = recover_map(fields.remove("namedChunkGroups"), "namedChunkGroups", &mut warnings);
+        let errors = recover_list(fields.remove("errors"), "errors", &mut warnings);
+        let build_warnings = recover_list(fields.remove("warnings"), "warnings", &mut warnings);
+
+        // The remaining fields only describe the build
+        let mut stats: Self = serde_json::from_value(Value::Object(fields)).unwrap_or_else(|e| {
+            warnings.push(ParseWarning::new("stats", format!("build details skipped, {}", e)));
+            Self::default()
+        });
+        stats.assets = assets;
+        stats.chunks = chunks;
+        stats.modules = modules;
+        stats.entrypoints = entrypoints.into_iter().collect();
+        stats.named_chunk_groups = named_chunk_groups.into_iter().collect();
+        stats.errors = errors;
+        stats.warnings = build_warnings;
+        stats.parse_warnings = warnings;
+        stats.normalize_separators();
+        Ok(stats)
+    }
+
+    /// Rewrites the module paths of stats written on Windows with `/`
+    /// separators, so they match across platforms.
+    fn normalize_separators(&mut self) {
+        for module in &mut self.modules {
+            module.normalize_separators();
+        }
+        for chunk in &mut self.chunks {
+            for module in &mut chunk.modules {
+                module.normalize_separators();
+            }
+            for origin in &mut chunk.origins {
+                for path in [&mut origin.module, &mut origin.module_identifier, &mut origin.module_name] {
+                    path.iter_mut().for_each(normalize_separators);
+                }
+            }
+        }
+    }
+
+    /// Drops the module detail the package sizes do not need, to save
+    /// memory on very large builds.
+    ///
+    /// Module sources, reasons and issuers are removed, and so are the
+    /// per-chunk module lists when the top-level modules record their
+    /// chunks instead. [`analyze`](Self::analyze) gives the same package
+    /// sizes afterwards, but the stats can no longer explain why a module
+    /// was bundled.
+    pub fn shed_module_detail(&mut self) {
+        for module in &mut self.modules {
+            module.shed_detail();
+        }
+        let chunks_recorded = self.modules.iter().any(|module| !module.chunks.is_empty());
+        for chunk in &mut self.chunks {
+            if chunks_recorded {
+                chunk.modules = Vec::new();
+            }
+            for module in &mut chunk.modules {
+                module.shed_detail();
+            }
+        }
     }
 
     /// Analyze the stats and calculate per-package bundle sizes.
@@ -520,10 +1046,52 @@ impl WebpackStats {
 
         // Process all modules (including nested ones)
         self.process_modules(&self.modules, &mut analysis);
+        self.process_assets(&mut analysis);
+        analysis.chunk_graph = ChunkGraph::from_stats(self);
 
         analysis
     }
 
+    /// Totals the assets per type and attributes the assets that
+    /// node_modules modules emit (via their `assets` field) to their
+    /// packages. An asset emitted by several modules counts once.
+    fn process_assets(&self, analysis: &mut BundleAnalysis) {
+        let mut totals: BTreeMap<AssetKind, AssetTotal> = BTreeMap::new();
+        for asset in &self.assets {
+            let kind = AssetKind::from_name(&asset.name);
+            let total = totals.entry(kind).or_insert(AssetTotal { kind, size: 0, count: 0 });
+            total.size += asset.size;
+            total.count += 1;
+        }
+        analysis.asset_totals = totals.into_values().collect();
+        analysis.asset_totals.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.kind.cmp(&b.kind)));
+
+        let sizes: HashMap<&str, u64> = self.assets.iter().map(|a| (a.name.as_str(), a.size)).collect();
+        let mut attributed = HashSet::new();
+        for module in self.all_modules() {
+            let Some(package_name) = module
+                .name
+                .as_deref()
+                .or(module.identifier.as_deref())
+                .and_then(extract_package_name)
+            else {
+                continue;
+            };
+            for asset in &module.assets {
+                let Some(size) = sizes.get(asset.as_str()) else {
+                    continue;
+                };
+                if attributed.insert(asset.as_str()) {
+                    analysis
+                        .package_sizes
+                        .entry(package_name.clone())
+                        .or_insert_with(|| PackageBundleSize::new(package_name.clone()))
+                        .add_asset(asset.clone(), *size);
+                }
+            }
+        }
+    }
+
     /// Process modules recursively (handles concatenated modules).
     fn process_modules(&self, modules: &[WebpackModule], analysis: &mut BundleAnalysis) {
         for module in modules {
@@ -549,6 +1117,14 @@ impl WebpackStats {
                     .entry(package_name.clone())
                     .or_insert_with(|| PackageBundleSize::new(package_name));
                 package_size.add_module(module_path.clone(), module.size);
+                // Concatenated modules report usage through their inner modules
+                if module.modules.is_empty() {
+                    package_size.add_export_usage(module);
+                }
+
+                if let Some((parent, vendored)) = extract_vendored_package(&module_path) {
+                    analysis.record_vendored(&parent, &vendored, VendorKind::Vendored, module.size);
+                }
             } else {
                 // Module doesn't belong to node_modules
                 analysis.unmapped_modules.push((module_path, module.size));
@@ -590,6 +1166,8 @@ impl WebpackStats {
 /// - `./node_modules/@scope/package/index.js` -> `@scope/package`
 /// - `../node_modules/react/cjs/react.production.min.js` -> `react`
 /// - `/absolute/path/node_modules/chalk/index.js` -> `chalk`
+/// - `./node_modules/.pnpm/react@18.2.0/node_modules/react/index.js` -> `react`
+/// - `.\node_modules\react\index.js` (Windows) -> `react`
 ///
 /// # Arguments
 ///
@@ -609,6 +1187,11 @@ impl WebpackStats {
 /// assert_eq!(extract_package_name("./src/app.js"), None);
 /// ```
 pub fn extract_package_name(module_path: &str) -> Option<String> {
+    // See through pnpm's virtual store to the package's own folder
+    let module_path = to_slash(module_path);
+    let module_path = normalize_store_path(&module_path);
+    let module_path = module_path.as_ref();
+
     // Find the node_modules segment in the path
     let node_modules_marker = "node_modules/";
 
@@ -624,9 +1207,21 @@ pub fn extract_package_name(module_path: &str) -> Option<String> {
     }
 
     // Check if it's a scoped package (@org/package)
-    if segments[0].starts_with('@') {
+    if segments[0].is_empty() {
+        None
+    } else if segments[0] == VIRTUAL_STORE {
+        // A store folder without its inner node_modules, or store metadata
+        segments
+            .get(1)
+            .filter(|_| segments.len() > 2)
+            .and_then(|folder| StoreEntry::parse(folder))
+            .map(|entry| entry.name)
+    } else if segments[0].starts_with('.') {
+        // `.bin`, `.cache` and other tooling folders are not packages
+        None
+    } else if segments[0].starts_with('@') {
         // Scoped package: need @scope/package
-        if segments.len() >= 2 {
+        if segments.len() >= 2 && !segments[1].is_empty() {
             Some(format!("{}/{}", segments[0], segments[1]))
         } else {
             None
@@ -637,8 +1232,81 @@ pub fn extract_package_name(module_path: &str) -> Option<String> {
     }
 }
 
+/// Directory names packages copy third-party code into.
+const VENDOR_DIRS: &[&str] = &[
+    "compiled",
+    "vendor",
+    "vendors",
+    "vendored",
+    "_vendor",
+    "third_party",
+    "third-party",
+    "bundled",
+];
+
+/// Detect a package vendored inside another package's files.
+///
+/// Recognizes copies placed in a vendor directory of the owning package,
+/// such as `next/dist/compiled/react/` or `foo/lib/vendor/@scope/pkg/`.
+/// Only directories count: a loose `vendor/jquery.js` file is not treated
+/// as a package.
+///
+/// # Returns
+///
+/// `Some((parent, vendored))` if the module belongs to a vendored copy.
+///
+/// # Example
+///
+/// ```
+/// use codescope::bundle::webpack::extract_vendored_package;
+///
+/// assert_eq!(
+///     extract_vendored_package("./node_modules/next/dist/compiled/react/index.js"),
+///     Some(("next".to_string(), "react".to_string()))
+/// );
+/// assert_eq!(extract_vendored_package("./node_modules/react/index.js"), None);
+/// ```
+pub fn extract_vendored_package(module_path: &str) -> Option<(String, String)> {
+    let module_path = to_slash(module_path);
+    let module_path = normalize_store_path(&module_path);
+    let module_path = module_path.as_ref();
+    let parent = extract_package_name(module_path)?;
+    let marker = format!("node_modules/{}/", parent);
+    let pos = module_path.rfind(&marker)?;
+    let segments: Vec<&str> = module_path[pos + marker.len()..].split('/').collect();
+
+    let vendor_pos = segments.iter().position(|s| VENDOR_DIRS.contains(s))?;
+    let rest = &segments[vendor_pos + 1..];
+
+    // The vendored package must be a directory, so a file has to follow it
+    let (name, name_len) = match rest.first() {
+        Some(scope) if scope.starts_with('@') => (format!("{}/{}", scope, rest.get(1)?), 2),
+        Some(name) => (name.to_string(), 1),
+        None => return None,
+    };
+    if rest.len() <= name_len || !is_package_like(&name) {
+        return None;
+    }
+
+    Some((parent, name))
+}
+
+/// Check whether a path segment looks like an npm package name.
+fn is_package_like(name: &str) -> bool {
+    let bare = name.strip_prefix('@').unwrap_or(name);
+    !bare.is_empty()
+        && !bare.starts_with('.')
+        && !bare.starts_with('_')
+        && bare
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.' | '_' | '/'))
+}
+
 /// Format a byte size as a human-readable string.
 ///
+/// Numbers follow the locale from the project configuration, if one was
+/// installed; see [`Locale`].
+///
 /// # Example
 ///
 /// ```
@@ -648,25 +1316,78 @@ pub fn extract_package_name(module_path: &str) -> Option<String> {
 /// assert_eq!(format_size(1048576), "1.00 MB");
 /// ```
 pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
+    Locale::current().format_size(bytes)
+}
+
+/// Parse a human-friendly size such as `1.5MB`, `200KiB` or `512`.
+///
+/// Units are case-insensitive and may be separated from the number by a
+/// space. Like [`format_size`], `KB`, `MB` and `GB` are binary units, the
+/// same as `KiB`, `MiB` and `GiB`; a bare number is in bytes.
+///
+/// # Example
+///
+/// ```
+/// use codescope::bundle::webpack::parse_size;
+///
+/// assert_eq!(parse_size("1.5MB"), Ok(1572864));
+/// assert_eq!(parse_size("200 KiB"), Ok(204800));
+/// assert_eq!(parse_size("512"), Ok(512));
+/// assert!(parse_size("5 parsecs").is_err());
+/// ```
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    if number.is_empty() {
+        return Err(format!(
+            "invalid size '{}': expected a number with an optional unit, e.g. 1.5MB or 200KiB",
+            input
+        ));
     }
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{}': '{}' is not a number", input, number))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        other => {
+            return Err(format!(
+                "invalid size '{}': unknown unit '{}' (expected B, KB, MB, GB, KiB, MiB or GiB)",
+                input, other
+            ))
+        }
+    };
+    Ok((value * multiplier as f64).round() as u64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size("1.5 mb"), Ok(1572864));
+        assert_eq!(parse_size("2G"), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size(" 10kb "), Ok(10240));
+        assert_eq!(parse_size("100B"), Ok(100));
+        assert_eq!(
+            parse_size("MB"),
+            Err("invalid size 'MB': expected a number with an optional unit, e.g. 1.5MB or 200KiB".to_string())
+        );
+        assert_eq!(parse_size("1.2.3KB"), Err("invalid size '1.2.3KB': '1.2.3' is not a number".to_string()));
+        assert_eq!(
+            parse_size("-5KB"),
+            Err("invalid size '-5KB': expected a number with an optional unit, e.g. 1.5MB or 200KiB".to_string())
+        );
+        assert!(parse_size("3 TB").unwrap_err().contains("unknown unit 'tb'"));
+    }
+
     #[test]
     fn test_extract_package_name_regular() {
         assert_eq!(
@@ -708,6 +1429,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_package_name_pnpm_store() {
+        assert_eq!(
+            extract_package_name("../../node_modules/.pnpm/@babel+runtime@7.24.0/node_modules/@babel/runtime/helpers/extends.js"),
+            Some("@babel/runtime".to_string())
+        );
+        assert_eq!(
+            extract_package_name("./node_modules/.pnpm/next@14.1.0_react@18.2.0/node_modules/next/dist/compiled/react/index.js"),
+            Some("next".to_string())
+        );
+        assert_eq!(
+            extract_vendored_package("./node_modules/.pnpm/next@14.1.0_react@18.2.0/node_modules/next/dist/compiled/react/index.js"),
+            Some(("next".to_string(), "react".to_string()))
+        );
+        // A truncated path still names the store folder's package
+        assert_eq!(
+            extract_package_name("./node_modules/.pnpm/lodash@4.17.21/lodash.js"),
+            Some("lodash".to_string())
+        );
+        assert_eq!(extract_package_name("./node_modules/.pnpm/lock.yaml"), None);
+        assert_eq!(extract_package_name("./node_modules/.cache/babel-loader/1a2b.json"), None);
+    }
+
+    #[test]
+    fn test_windows_stats_paths() {
+        assert_eq!(
+            extract_package_name(r"C:\Users\dev\app\node_modules\@babel\runtime\helpers\extends.js"),
+            Some("@babel/runtime".to_string())
+        );
+
+        let stats = WebpackStats::parse(
+            r#"{"modules": [
+                {"name": ".\\node_modules\\react-dom\\index.js", "size": 1000,
+                 "reasons": [{"moduleName": ".\\src\\index.js"}]},
+                {"name": ".\\node_modules\\.pnpm\\lodash@4.17.21\\node_modules\\lodash\\lodash.js", "size": 500},
+                {"name": ".\\src\\index.js", "size": 100}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(stats.modules[0].name.as_deref(), Some("./node_modules/react-dom/index.js"));
+        assert_eq!(stats.modules[0].reasons[0].module_name.as_deref(), Some("./src/index.js"));
+
+        let analysis = stats.analyze();
+        assert_eq!(analysis.package_sizes["react-dom"].total_size, 1000);
+        assert_eq!(analysis.package_sizes["lodash"].total_size, 500);
+    }
+
+    #[test]
+    fn test_shed_module_detail_keeps_package_totals() {
+        let mut stats = WebpackStats::parse(
+            r#"{"chunks": [{"id": 0, "modules": [
+                {"name": "./node_modules/react/index.js", "size": 1000},
+                {"name": "./src/index.js + 2 modules", "size": 300, "modules": [
+                    {"name": "./node_modules/lodash/get.js", "size": 200}, {"name": "./src/index.js", "size": 100}
+                ]}
+            ]}],
+                "modules": [
+                {"name": "./node_modules/react/index.js", "size": 1000, "chunks": [0], "source": "module.exports = 1",
+                 "reasons": [{"moduleName": "./src/index.js"}], "issuer": "./src/index.js"},
+                {"name": "./src/index.js + 2 modules", "size": 300, "chunks": [0], "modules": [
+                    {"name": "./node_modules/lodash/get.js", "size": 200, "source": "..."},
+                    {"name": "./src/index.js", "size": 100}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+        let full = stats.analyze();
+
+        stats.shed_module_detail();
+        assert!(stats.chunks[0].modules.is_empty());
+        assert!(stats.all_modules().iter().all(|m| m.source.is_none() && m.reasons.is_empty()));
+
+        let mut reduced = stats.analyze();
+        assert_eq!(reduced.total_module_size, full.total_module_size);
+        assert_eq!(reduced.package_sizes["lodash"].total_size, 200);
+        let packages = |analysis: &BundleAnalysis| {
+            let chunk = &analysis.chunk_graph.chunks[0];
+            chunk.packages.iter().map(|p| (p.name.clone(), p.size)).collect::<Vec<_>>()
+        };
+        assert_eq!(packages(&reduced), packages(&full));
+
+        reduced.shed_module_detail();
+        assert!(reduced.package_sizes["react"].modules.is_empty());
+        assert_eq!(reduced.package_sizes["react"].module_count, 1);
+        assert!(reduced.unmapped_modules.is_empty());
+    }
+
     #[test]
     fn test_extract_package_name_absolute_path() {
         assert_eq!(
@@ -863,6 +1671,55 @@ mod tests {
         assert_eq!(sorted[2].name, "small");
     }
 
+    #[test]
+    fn test_asset_breakdown() {
+        let json = r#"{
+            "modules": [
+                { "name": "./node_modules/font-awesome/css/font-awesome.css", "size": 3000,
+                  "assets": ["fonts/fontawesome.woff2", "fonts/fontawesome.svg"] },
+                { "name": "./node_modules/leaflet/dist/images/marker.png", "size": 100,
+                  "assets": ["images/marker.png"] },
+                { "name": "./src/logo.svg", "size": 100, "assets": ["images/logo.svg"] },
+                { "name": "./node_modules/other/icons.css", "size": 50, "assets": ["fonts/fontawesome.woff2"] }
+            ],
+            "assets": [
+                { "name": "main.js", "size": 60000 },
+                { "name": "main.css", "size": 8000 },
+                { "name": "fonts/fontawesome.woff2", "size": 20000 },
+                { "name": "fonts/fontawesome.svg", "size": 10000 },
+                { "name": "images/marker.png", "size": 1000 },
+                { "name": "images/logo.svg", "size": 1000 }
+            ],
+            "chunks": []
+        }"#;
+
+        let analysis = WebpackStats::parse(json).unwrap().analyze();
+        let totals: Vec<(AssetKind, u64, usize)> =
+            analysis.asset_totals.iter().map(|t| (t.kind, t.size, t.count)).collect();
+        assert_eq!(
+            totals,
+            vec![
+                (AssetKind::JavaScript, 60000, 1),
+                (AssetKind::Font, 20000, 1),
+                (AssetKind::Image, 12000, 3),
+                (AssetKind::Css, 8000, 1),
+            ]
+        );
+
+        let font_awesome = &analysis.package_sizes["font-awesome"];
+        assert_eq!(font_awesome.asset_size, 30000);
+        assert_eq!(font_awesome.total_size, 3000, "assets are kept out of the module size");
+        assert_eq!(analysis.package_sizes["other"].asset_size, 0, "each asset counts once");
+
+        let packages: Vec<&str> = analysis.packages_by_asset_size().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(packages, vec!["font-awesome", "leaflet"]);
+
+        let text = analysis.format_asset_breakdown();
+        assert!(text.starts_with("📦 Bundle composition (6 assets, 97.66 KB)\n"));
+        assert!(text.contains("  Fonts             1     19.53 KB   20.0%\n"));
+        assert!(text.contains("  font-awesome: 29.30 KB (2 asset(s))\n"));
+    }
+
     #[test]
     fn test_all_modules_flattens_nested() {
         let json = r#"{
@@ -929,4 +1786,96 @@ mod tests {
             2000
         );
     }
+
+    // ===== Vendored Package Tests =====
+
+    #[test]
+    fn test_extract_vendored_package() {
+        assert_eq!(
+            extract_vendored_package("./node_modules/next/dist/compiled/react-dom/cjs/react-dom.js"),
+            Some(("next".to_string(), "react-dom".to_string()))
+        );
+        assert_eq!(
+            extract_vendored_package("./node_modules/@acme/sdk/lib/vendor/@babel/runtime/helpers/x.js"),
+            Some(("@acme/sdk".to_string(), "@babel/runtime".to_string()))
+        );
+        assert_eq!(
+            extract_vendored_package(
+                "./.yarn/cache/next-npm-14.0.0-abc.zip/node_modules/next/dist/compiled/react/index.js"
+            ),
+            Some(("next".to_string(), "react".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_extract_vendored_package_rejects_non_packages() {
+        // Loose files in a vendor directory
+        assert_eq!(extract_vendored_package("./node_modules/foo/vendor/jquery.js"), None);
+        // File named like a vendor directory
+        assert_eq!(extract_vendored_package("./node_modules/foo/dist/vendor.js"), None);
+        // Not package-like
+        assert_eq!(extract_vendored_package("./node_modules/foo/vendor/Utils/a.js"), None);
+        assert_eq!(extract_vendored_package("./node_modules/foo/vendor/_internal/a.js"), None);
+        // Project code
+        assert_eq!(extract_vendored_package("./src/vendor/lib/a.js"), None);
+    }
+
+    #[test]
+    fn test_vendored_copies_attributed_to_parent() {
+        let json = r#"{
+            "modules": [
+                { "name": "./node_modules/next/dist/client/index.js", "size": 1000 },
+                { "name": "./node_modules/next/dist/compiled/react/index.js", "size": 300 },
+                { "name": "./node_modules/next/dist/compiled/react/jsx-runtime.js", "size": 200 },
+                { "name": "./node_modules/react/index.js", "size": 400 }
+            ]
+        }"#;
+
+        let analysis = WebpackStats::parse(json).unwrap().analyze();
+
+        assert_eq!(analysis.get_package_size("next"), Some(1500));
+        assert_eq!(analysis.get_package_size("react"), Some(400));
+        assert_eq!(
+            analysis.vendored,
+            vec![VendoredCopy {
+                parent: "next".to_string(),
+                package: "react".to_string(),
+                kind: VendorKind::Vendored,
+                size: 500,
+                module_count: 2,
+            }]
+        );
+
+        let duplicates = analysis.duplicated_vendored(&["react", "lodash"]);
+        assert_eq!(duplicates.len(), 1);
+        assert!(analysis.duplicated_vendored(&["lodash"]).is_empty());
+    }
+
+    #[test]
+    fn test_apply_bundled_dependencies() {
+        let json = r#"{
+            "modules": [
+                { "name": "./node_modules/cli-kit/index.js", "size": 100 },
+                { "name": "./node_modules/cli-kit/node_modules/chalk/index.js", "size": 50 },
+                { "name": "./node_modules/chalk/index.js", "size": 60 },
+                { "name": "./node_modules/other/node_modules/semver/index.js", "size": 70 }
+            ]
+        }"#;
+        let mut analysis = WebpackStats::parse(json).unwrap().analyze();
+        assert_eq!(analysis.get_package_size("chalk"), Some(110));
+
+        let bundled = HashMap::from([
+            ("cli-kit".to_string(), vec!["chalk".to_string()]),
+            ("other".to_string(), vec!["semver".to_string()]),
+        ]);
+        assert_eq!(analysis.apply_bundled_dependencies(&bundled), 2);
+
+        assert_eq!(analysis.get_package_size("cli-kit"), Some(150));
+        assert_eq!(analysis.get_package_size("chalk"), Some(60));
+        assert_eq!(analysis.get_package_size("other"), Some(70));
+        // Packages only present as bundled copies disappear from the top level
+        assert_eq!(analysis.get_package_size("semver"), None);
+        assert_eq!(analysis.vendored.len(), 2);
+        assert!(analysis.vendored.iter().all(|c| c.kind == VendorKind::Bundled));
+    }
 }
@@ -0,0 +1,195 @@
+//! Locale-aware number formatting.
+//!
+//! Sizes and other numbers in reports are written with a `.` decimal
+//! separator and no digit grouping unless the project configures a
+//! `locale`, e.g. `"locale": "de-DE"` for `1.234,56 KB`. Only the number
+//! format follows the locale; messages stay in English, and machine
+//! readable exports (JSON, CSV) are never localized.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use super::{ConfigError, ConfigResult};
+
+/// No-break space, used for digit grouping where the locale groups with
+/// spaces so grouped numbers are not wrapped.
+const NBSP: char = '\u{a0}';
+
+/// The locale installed for the process.
+static CURRENT: OnceLock<Locale> = OnceLock::new();
+
+/// Separators of a locale's number format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// The tag the locale was parsed from, e.g. `de-DE`
+    pub tag: String,
+    /// Decimal separator
+    pub decimal: char,
+    /// Separator between groups of three digits, `None` for no grouping
+    pub grouping: Option<char>,
+}
+
+impl Default for Locale {
+    /// The unlocalized format: `.` decimals and no grouping.
+    fn default() -> Self {
+        Self {
+            tag: "C".to_string(),
+            decimal: '.',
+            grouping: None,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag)
+    }
+}
+
+impl Locale {
+    /// Parses a locale tag such as `de-DE`, `fr` or `pt_BR.UTF-8`.
+    ///
+    /// `C` and `POSIX` select the unlocalized format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::Locale;
+    ///
+    /// let locale = Locale::parse("de-DE").unwrap();
+    /// assert_eq!(locale.format_size(1_300_000), "1,24 MB");
+    /// assert_eq!(locale.format_number(1234.5, 2), "1.234,50");
+    /// assert!(Locale::parse("xx-XX").is_err());
+    /// ```
+    pub fn parse(tag: &str) -> ConfigResult<Self> {
+        let tag = tag.trim();
+        let base = tag.split('.').next().unwrap_or_default().replace('_', "-");
+        let mut subtags = base.split('-');
+        let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let region = subtags.next().map(str::to_ascii_uppercase);
+
+        if matches!(base.as_str(), "C" | "POSIX") {
+            return Ok(Self::default());
+        }
+        let (decimal, grouping) = match (language.as_str(), region.as_deref()) {
+            ("de" | "it", Some("CH" | "LI")) => ('.', '’'),
+            ("es", Some("MX" | "US")) => ('.', ','),
+            ("pt", Some("PT")) => (',', NBSP),
+            ("en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ms", _) => ('.', ','),
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "sl" | "hr", _) => (',', '.'),
+            ("fr" | "nb" | "no" | "sv" | "fi" | "cs" | "sk" | "pl" | "ru" | "uk" | "hu" | "bg" | "et" | "lt" | "lv", _) => {
+                (',', NBSP)
+            }
+            _ => return Err(ConfigError::Invalid(format!("unknown locale '{}'", tag))),
+        };
+        Ok(Self {
+            tag: tag.to_string(),
+            decimal,
+            grouping: Some(grouping),
+        })
+    }
+
+    /// Installs the locale for the process; later calls have no effect.
+    pub fn install(&self) {
+        let _ = CURRENT.set(self.clone());
+    }
+
+    /// Returns the installed locale, or the unlocalized format if none was
+    /// installed.
+    pub fn current() -> &'static Locale {
+        static DEFAULT: OnceLock<Locale> = OnceLock::new();
+        CURRENT.get().unwrap_or_else(|| DEFAULT.get_or_init(Locale::default))
+    }
+
+    /// Formats an integer with digit grouping.
+    pub fn format_integer(&self, value: u64) -> String {
+        self.group(&value.to_string())
+    }
+
+    /// Formats a number with a fixed number of decimals.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The number
+    /// * `decimals` - Digits after the decimal separator
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+        let mut out = String::new();
+        if value.is_sign_negative() && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        out.push_str(&self.group(integer));
+        if let Some(fraction) = fraction {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Formats a byte count in B, KB, MB or GB, with two decimals above
+    /// bytes.
+    pub fn format_size(&self, bytes: u64) -> String {
+        const KB: u64 = 1024;
+        const MB: u64 = KB * 1024;
+        const GB: u64 = MB * 1024;
+
+        if bytes >= GB {
+            format!("{} GB", self.format_number(bytes as f64 / GB as f64, 2))
+        } else if bytes >= MB {
+            format!("{} MB", self.format_number(bytes as f64 / MB as f64, 2))
+        } else if bytes >= KB {
+            format!("{} KB", self.format_number(bytes as f64 / KB as f64, 2))
+        } else {
+            format!("{} B", self.format_integer(bytes))
+        }
+    }
+
+    /// Inserts the grouping separator into a string of digits.
+    fn group(&self, digits: &str) -> String {
+        let Some(separator) = self.grouping else {
+            return digits.to_string();
+        };
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_separators() {
+        let plain = Locale::default();
+        assert_eq!(plain.format_number(1234.567, 2), "1234.57");
+        assert_eq!(plain.format_size(5 * 1024 * 1024 * 1024 * 1024), "5120.00 GB");
+
+        let en = Locale::parse("en_US.UTF-8").unwrap();
+        assert_eq!(en.format_size(5 * 1024 * 1024 * 1024 * 1024), "5,120.00 GB");
+        assert_eq!(en.format_integer(1_234_567), "1,234,567");
+        assert_eq!(en.format_number(-0.001, 2), "0.00");
+        assert_eq!(en.format_number(-1234.0, 0), "-1,234");
+
+        let fr = Locale::parse("fr-FR").unwrap();
+        assert_eq!(fr.format_number(1234.56, 2), "1\u{a0}234,56");
+        assert_eq!(Locale::parse("de-CH").unwrap().format_integer(10_000), "10’000");
+        assert_eq!(Locale::parse("pt-BR").unwrap().decimal, ',');
+        assert_eq!(Locale::parse("POSIX").unwrap(), Locale::default());
+    }
+
+    #[test]
+    fn test_unknown_locale() {
+        let err = Locale::parse("klingon").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid .codescoperc.json: unknown locale 'klingon'");
+    }
+}
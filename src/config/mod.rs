@@ -0,0 +1,907 @@
+//! Project configuration
+//!
+//! Settings that belong to a project rather than a single invocation are
+//! read from a `.codescoperc.json` file in the project root, or from a
+//! `.codescope.toml` with the same keys if there is no JSON file. Every
+//! field is optional, and a missing file is the same as an empty one.
+//!
+//! # Example
+//!
+//! ```json
+//! {
+//!   "preset": "vite-react",
+//!   "groups": {
+//!     "ui-framework": { "packages": ["react", "react-dom", "@mui/*"], "maxSizeKb": 300 },
+//!     "build-tooling": { "packages": ["webpack*", "@babel/*"], "maxCount": 40 }
+//!   },
+//!   "owners": [
+//!     "* @platform",
+//!     "group:ui-framework @web",
+//!     "@babel/* @platform @build"
+//!   ],
+//!   "checks": [
+//!     { "name": "no-copyleft", "command": ["node", "scripts/check-licenses.js"] }
+//!   ],
+//!   "pinning": { "production": "exact", "peer": "loose" },
+//!   "licenses": { "deny": ["GPL-3.0-only", "AGPL-3.0-only"] },
+//!   "tooling": { "maxInstallMb": 400, "maxNative": 0 },
+//!   "signing": { "keyFile": "ci/signing.pem", "publicKey": "4PI0oHplvwCQE365SkqwzGElZHknl1xOpEOIgEilfFU=" },
+//!   "notify": { "webhookUrl": "https://hooks.example.com/codescope" },
+//!   "locale": "de-DE",
+//!   "tui": { "sizeBars": false, "openBrowser": false },
+//!   "ignore": ["**/fixtures/**", "src/generated/**"]
+//! }
+//! ```
+//!
+//! `preset` applies the built-in settings of a common stack; see
+//! [`Preset`].
+//!
+//! `owners` follows CODEOWNERS: each line is a package pattern (or
+//! `group:<name>`) followed by one or more owners, and the last matching
+//! line wins.
+//!
+//! `checks` adds organization-specific policy checks that run as external
+//! commands; see [`crate::policy::CommandCheck`] for the protocol.
+//!
+//! `pinning` sets how tightly direct dependencies of each type must pin
+//! their versions; see [`PinningConfig`].
+//!
+//! `licenses` lists the licenses installed packages may or may not use;
+//! see [`LicensePolicy`].
+//!
+//! Projects opened in the TUI without either file get a first-run wizard
+//! that writes a `.codescope.toml`; see [`SetupPlan`].
+//!
+//! `codescope ratchet` rewrites the thresholds set in the file to the
+//! measured values; see [`Ratchet`].
+//!
+//! `tooling` sets thresholds for the install cost of development-only
+//! packages; see [`ToolingBudget`].
+//!
+//! `signing` sets the key `export --sign` signs reports with and the
+//! public key `verify-report` checks them against; see [`SigningConfig`].
+//!
+//! `notify` sets the webhook `analyze --notify` reports failed checks to;
+//! see [`NotifyConfig`].
+//!
+//! `locale` sets the number format of sizes in reports; see [`Locale`].
+//!
+//! `tui` adjusts the dependency tree view and whether it opens a browser;
+//! see [`TuiConfig`].
+//!
+//! `ignore` lists gitignore-style globs of source files the import scan
+//! skips, on top of `.gitignore`; see
+//! [`source_files`](crate::analysis::exports::source_files).
+
+pub mod locale;
+pub mod preset;
+pub mod ratchet;
+pub mod setup;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::parser::spdx::{LicenseExpression, LicenseTerm};
+use crate::parser::types::DependencyType;
+
+pub use locale::Locale;
+pub use preset::Preset;
+pub use ratchet::{Measurement, Ratchet, ThresholdChange};
+pub use setup::{PackageManager, SetupPlan};
+
+/// File name of the project configuration.
+pub const CONFIG_FILE: &str = ".codescoperc.json";
+
+/// File name of the project configuration in TOML.
+pub const TOML_CONFIG_FILE: &str = ".codescope.toml";
+
+/// The formats the project configuration can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// `.codescoperc.json`
+    Json,
+    /// `.codescope.toml`
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Every format, in the order the files are looked up.
+    pub const ALL: [ConfigFormat; 2] = [ConfigFormat::Json, ConfigFormat::Toml];
+
+    /// Returns the file name of the configuration in this format.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => CONFIG_FILE,
+            ConfigFormat::Toml => TOML_CONFIG_FILE,
+        }
+    }
+
+    /// Returns the format of the configuration file in `project_dir`, or
+    /// `None` if it has none.
+    pub fn detect(project_dir: &Path) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| project_dir.join(format.file_name()).is_file())
+    }
+}
+
+/// Errors that can occur while loading the project configuration.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// Failed to read the file from disk.
+    #[error("Failed to read {CONFIG_FILE}: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Failed to parse JSON content.
+    #[error("Failed to parse {CONFIG_FILE}: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Failed to parse TOML content.
+    #[error("Failed to parse {TOML_CONFIG_FILE}: {0}")]
+    TomlError(#[from] toml_edit::TomlError),
+
+    /// The configuration is well-formed JSON but not a valid configuration.
+    #[error("Invalid {CONFIG_FILE}: {0}")]
+    Invalid(String),
+}
+
+/// Result type alias for configuration operations.
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// A named group of packages, tagged by area of responsibility.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GroupConfig {
+    /// Package names or patterns, where `*` matches any run of characters
+    pub packages: Vec<String>,
+
+    /// Budget for the group's total bundle size, in KB
+    #[serde(default)]
+    pub max_size_kb: Option<u64>,
+
+    /// Budget for the number of packages in the group
+    #[serde(default)]
+    pub max_count: Option<usize>,
+}
+
+impl GroupConfig {
+    /// Returns true if `name` matches one of the group's patterns.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::GroupConfig;
+    ///
+    /// let group = GroupConfig {
+    ///     packages: vec!["react".to_string(), "@mui/*".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// assert!(group.matches("react"));
+    /// assert!(group.matches("@mui/material"));
+    /// assert!(!group.matches("react-dom"));
+    /// ```
+    pub fn matches(&self, name: &str) -> bool {
+        self.packages.iter().any(|pattern| glob_match(pattern, name))
+    }
+
+    /// Returns true if the group sets a size or count budget.
+    pub fn has_budget(&self) -> bool {
+        self.max_size_kb.is_some() || self.max_count.is_some()
+    }
+}
+
+/// One CODEOWNERS-style ownership line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerRule {
+    /// Package pattern, or `group:<name>` to match a configured group
+    pub pattern: String,
+    /// Owning teams or people, in the order written
+    pub owners: Vec<String>,
+}
+
+impl OwnerRule {
+    /// Parses a line such as `@babel/* @platform @build`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::OwnerRule;
+    ///
+    /// let rule = OwnerRule::parse("react* @web").unwrap();
+    /// assert_eq!(rule.pattern, "react*");
+    /// assert_eq!(rule.owners, vec!["@web"]);
+    /// assert!(OwnerRule::parse("react").is_err());
+    /// ```
+    pub fn parse(line: &str) -> ConfigResult<Self> {
+        let mut parts = line.split_whitespace();
+        let pattern = parts
+            .next()
+            .ok_or_else(|| ConfigError::Invalid("empty owners line".to_string()))?;
+        let owners: Vec<String> = parts.map(str::to_string).collect();
+        if owners.is_empty() {
+            return Err(ConfigError::Invalid(format!(
+                "owners line '{}' names no owner",
+                line.trim()
+            )));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            owners,
+        })
+    }
+
+    /// Returns the group name for `group:<name>` patterns.
+    pub fn group(&self) -> Option<&str> {
+        self.pattern.strip_prefix("group:")
+    }
+}
+
+/// A custom policy check implemented by an external command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CheckConfig {
+    /// Name shown next to the check's findings
+    pub name: String,
+
+    /// Program and arguments, run from the project directory
+    pub command: Vec<String>,
+
+    /// Exit code for findings that do not set their own
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+/// How tightly a version specifier pins a dependency, from loosest to
+/// strictest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pinning {
+    /// Any specifier, including `*`, `>=1.0.0` and dist-tags like `latest`
+    Loose,
+    /// A range that no new major version can satisfy, such as `^1.2.3`
+    Bounded,
+    /// A single version, such as `1.2.3`
+    Exact,
+}
+
+impl Pinning {
+    /// Returns the name used in the configuration file.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pinning::Loose => "loose",
+            Pinning::Bounded => "bounded",
+            Pinning::Exact => "exact",
+        }
+    }
+}
+
+/// The pinning each dependency type requires.
+///
+/// Unset types default to [`Pinning::Bounded`], except peer dependencies,
+/// which commonly accept open ranges such as `>=16.8` and default to
+/// [`Pinning::Loose`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PinningConfig {
+    /// Required pinning of `dependencies`
+    #[serde(default)]
+    pub production: Option<Pinning>,
+    /// Required pinning of `devDependencies`
+    #[serde(default)]
+    pub development: Option<Pinning>,
+    /// Required pinning of `peerDependencies`
+    #[serde(default)]
+    pub peer: Option<Pinning>,
+    /// Required pinning of `optionalDependencies`
+    #[serde(default)]
+    pub optional: Option<Pinning>,
+}
+
+impl PinningConfig {
+    /// Returns the pinning required for a dependency type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::{Pinning, ProjectConfig};
+    /// use codescope::parser::DependencyType;
+    ///
+    /// let config = ProjectConfig::parse(r#"{"pinning": {"production": "exact"}}"#).unwrap();
+    /// assert_eq!(config.pinning.required(DependencyType::Production), Pinning::Exact);
+    /// assert_eq!(config.pinning.required(DependencyType::Development), Pinning::Bounded);
+    /// assert_eq!(config.pinning.required(DependencyType::Peer), Pinning::Loose);
+    /// ```
+    pub fn required(&self, dep_type: DependencyType) -> Pinning {
+        match dep_type {
+            DependencyType::Production => self.production.unwrap_or(Pinning::Bounded),
+            DependencyType::Development => self.development.unwrap_or(Pinning::Bounded),
+            DependencyType::Peer => self.peer.unwrap_or(Pinning::Loose),
+            DependencyType::Optional => self.optional.unwrap_or(Pinning::Bounded),
+        }
+    }
+}
+
+/// The licenses installed packages may use.
+///
+/// Entries are SPDX license identifiers, optionally with an exception
+/// (`GPL-2.0-only WITH Classpath-exception-2.0`). An entry without an
+/// exception also matches the license with any exception. A license is
+/// acceptable if it is not denied and, when `allow` is not empty, allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LicensePolicy {
+    /// Licenses packages may use; empty allows every license not denied
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Licenses packages must not use
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Returns true if the policy restricts anything.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Returns true if a single license is acceptable.
+    pub fn accepts(&self, term: &LicenseTerm) -> bool {
+        let listed = |entries: &[String]| {
+            entries
+                .iter()
+                .filter_map(|entry| LicenseTerm::parse(entry))
+                .any(|entry| term.is_covered_by(&entry))
+        };
+        !listed(&self.deny) && (self.allow.is_empty() || listed(&self.allow))
+    }
+
+    /// Returns true if a package under `expression` can be used: some
+    /// choice among its `OR` alternatives consists of acceptable licenses
+    /// only.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::ProjectConfig;
+    /// use codescope::parser::LicenseExpression;
+    ///
+    /// let config = ProjectConfig::parse(r#"{"licenses": {"deny": ["GPL-3.0-only"]}}"#).unwrap();
+    /// let dual = LicenseExpression::parse("(GPL-3.0-only OR MIT)").unwrap();
+    /// let both = LicenseExpression::parse("GPL-3.0-only AND MIT").unwrap();
+    /// assert!(config.licenses.permits(&dual));
+    /// assert!(!config.licenses.permits(&both));
+    /// ```
+    pub fn permits(&self, expression: &LicenseExpression) -> bool {
+        expression.is_satisfied_by(|term| self.accepts(term))
+    }
+}
+
+/// Thresholds for the install cost of the packages only development needs.
+///
+/// devDependencies never reach the bundle, but they make up most of
+/// `node_modules`, so they get their own budget instead of the bundle's.
+/// Unset thresholds are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ToolingBudget {
+    /// Budget for the total unpacked size, in MB
+    #[serde(default)]
+    pub max_install_mb: Option<u64>,
+
+    /// Budget for the total number of files
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
+    /// Budget for the number of packages building native addons
+    #[serde(default)]
+    pub max_native: Option<usize>,
+
+    /// Budget for the number of packages running install scripts
+    #[serde(default)]
+    pub max_install_scripts: Option<usize>,
+}
+
+impl ToolingBudget {
+    /// Returns true if no threshold is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Keys for signing exported reports and verifying their signatures.
+///
+/// See [`crate::export::signing`] for the key formats.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SigningConfig {
+    /// PKCS#8 PEM file of the Ed25519 private key, relative to the project
+    /// directory; keep it out of version control
+    #[serde(default)]
+    pub key_file: Option<String>,
+
+    /// Ed25519 public key, PEM or the base64 of the raw key bytes
+    #[serde(default)]
+    pub public_key: Option<String>,
+}
+
+/// Where `--notify` sends the findings of failed checks.
+///
+/// See [`crate::policy::notify`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Webhook the findings are POSTed to; the `CODESCOPE_WEBHOOK_URL`
+    /// environment variable takes precedence
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+/// Display settings of the dependency tree view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TuiConfig {
+    /// Whether rows show a bar of the package's share of the bundle size
+    #[serde(default = "TuiConfig::enabled")]
+    pub size_bars: bool,
+    /// Whether a package's web pages open in the default browser; when
+    /// off, their URLs are shown instead, e.g. over SSH
+    #[serde(default = "TuiConfig::enabled")]
+    pub open_browser: bool,
+}
+
+impl TuiConfig {
+    fn enabled() -> bool {
+        true
+    }
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            size_bars: Self::enabled(),
+            open_browser: Self::enabled(),
+        }
+    }
+}
+
+/// The contents of `.codescoperc.json` or `.codescope.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfig {
+    /// Built-in settings for the project's stack
+    #[serde(default)]
+    pub preset: Option<Preset>,
+
+    /// Package groups keyed by group name
+    #[serde(default)]
+    pub groups: BTreeMap<String, GroupConfig>,
+
+    /// Ownership lines as written in the file
+    #[serde(default, rename = "owners")]
+    owner_lines: Vec<String>,
+
+    /// Parsed ownership rules, in file order
+    #[serde(skip)]
+    pub owners: Vec<OwnerRule>,
+
+    /// Custom checks, in the order they run
+    #[serde(default)]
+    pub checks: Vec<CheckConfig>,
+
+    /// Required version pinning of direct dependencies
+    #[serde(default)]
+    pub pinning: PinningConfig,
+
+    /// Licenses installed packages may use
+    #[serde(default)]
+    pub licenses: LicensePolicy,
+
+    /// Install cost thresholds for development-only packages
+    #[serde(default)]
+    pub tooling: ToolingBudget,
+
+    /// Keys for signing and verifying exported reports
+    #[serde(default)]
+    pub signing: SigningConfig,
+
+    /// Webhook notifications of failed checks
+    #[serde(default)]
+    pub notify: NotifyConfig,
+
+    /// Locale tag as written in the file
+    #[serde(default, rename = "locale")]
+    locale_tag: Option<String>,
+
+    /// Number format of sizes in reports, if the project sets one
+    #[serde(skip)]
+    pub locale: Option<Locale>,
+
+    /// Display settings of the dependency tree view
+    #[serde(default)]
+    pub tui: TuiConfig,
+
+    /// Globs of source files to leave out of the import scan, starting
+    /// with those of the preset
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Loads the configuration of a project, or the default configuration
+    /// if it has neither a `.codescoperc.json` nor a `.codescope.toml`.
+    pub fn load(project_dir: &Path) -> ConfigResult<Self> {
+        match ConfigFormat::detect(project_dir) {
+            Some(format) => Self::parse_as(&fs::read_to_string(project_dir.join(format.file_name()))?, format),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parses the contents of a `.codescoperc.json`.
+    pub fn parse(content: &str) -> ConfigResult<Self> {
+        Self::parse_as(content, ConfigFormat::Json)
+    }
+
+    /// Parses the contents of a configuration file in `format`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::{ConfigFormat, Preset, ProjectConfig};
+    ///
+    /// let config = ProjectConfig::parse_as("preset = \"nextjs\"\n\n[tooling]\nmaxNative = 0\n", ConfigFormat::Toml).unwrap();
+    /// assert_eq!(config.preset, Some(Preset::NextJs));
+    /// assert_eq!(config.tooling.max_native, Some(0));
+    /// ```
+    pub fn parse_as(content: &str, format: ConfigFormat) -> ConfigResult<Self> {
+        let mut config: ProjectConfig = match format {
+            ConfigFormat::Json => serde_json::from_str(content)?,
+            ConfigFormat::Toml => toml_edit::de::from_str(content).map_err(toml_edit::TomlError::from)?,
+        };
+        for (name, group) in &config.groups {
+            if group.packages.is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "group '{}' has no packages",
+                    name
+                )));
+            }
+        }
+
+        config.owners = config
+            .owner_lines
+            .iter()
+            .map(|line| OwnerRule::parse(line))
+            .collect::<ConfigResult<_>>()?;
+        for rule in &config.owners {
+            if let Some(group) = rule.group() {
+                if !config.groups.contains_key(group) {
+                    return Err(ConfigError::Invalid(format!(
+                        "owners line for unknown group '{}'",
+                        group
+                    )));
+                }
+            }
+        }
+
+        for entry in config.licenses.allow.iter().chain(&config.licenses.deny) {
+            if LicenseTerm::parse(entry).is_none() {
+                return Err(ConfigError::Invalid(format!(
+                    "'{}' in licenses is not a single SPDX license",
+                    entry
+                )));
+            }
+        }
+
+        config.locale = config.locale_tag.as_deref().map(Locale::parse).transpose()?;
+
+        if let Some(preset) = config.preset {
+            let own = std::mem::take(&mut config.ignore);
+            config.ignore = preset.ignore().iter().map(|glob| glob.to_string()).chain(own).collect();
+        }
+        for pattern in &config.ignore {
+            if let Err(e) = ignore::overrides::OverrideBuilder::new("").add(pattern) {
+                return Err(ConfigError::Invalid(format!("ignore pattern {}", e)));
+            }
+        }
+
+        for (i, check) in config.checks.iter().enumerate() {
+            if check.name.trim().is_empty() {
+                return Err(ConfigError::Invalid("check without a name".to_string()));
+            }
+            if check.command.is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "check '{}' has no command",
+                    check.name
+                )));
+            }
+            if config.checks[..i].iter().any(|other| other.name == check.name) {
+                return Err(ConfigError::Invalid(format!(
+                    "check '{}' is defined twice",
+                    check.name
+                )));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns true if packages of `dep_type` count toward group budgets:
+    /// those the preset selects, or every type without a preset.
+    pub fn counts_toward_budget(&self, dep_type: DependencyType) -> bool {
+        self.preset.is_none_or(|preset| preset.budget_types().contains(&dep_type))
+    }
+
+    /// Returns true if the preset treats `package` as used even when no
+    /// source file imports it.
+    pub fn is_always_used(&self, package: &str) -> bool {
+        self.preset.is_some_and(|preset| preset.uses(package))
+    }
+
+    /// Returns the owners of a package: those of the last matching
+    /// ownership line, or none if no line matches.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::ProjectConfig;
+    ///
+    /// let config = ProjectConfig::parse(r#"{"owners": ["* @platform", "react* @web"]}"#).unwrap();
+    /// assert_eq!(config.owners_of("react-dom"), ["@web"]);
+    /// assert_eq!(config.owners_of("lodash"), ["@platform"]);
+    /// ```
+    pub fn owners_of(&self, package: &str) -> &[String] {
+        self.owners
+            .iter()
+            .rev()
+            .find(|rule| match rule.group() {
+                Some(group) => self.groups.get(group).is_some_and(|g| g.matches(package)),
+                None => glob_match(&rule.pattern, package),
+            })
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+
+    /// Returns true if `package` is owned by `owner`.
+    pub fn is_owned_by(&self, package: &str, owner: &str) -> bool {
+        self.owners_of(package).iter().any(|o| o == owner)
+    }
+
+    /// Returns every owner named in the ownership lines, sorted.
+    pub fn all_owners(&self) -> Vec<&str> {
+        let mut owners: Vec<&str> = self
+            .owners
+            .iter()
+            .flat_map(|rule| rule.owners.iter().map(String::as_str))
+            .collect();
+        owners.sort();
+        owners.dedup();
+        owners
+    }
+}
+
+/// Matches `name` against a pattern in which `*` stands for any run of
+/// characters (including `/`).
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut remaining) = name.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or("");
+    for part in parts {
+        match remaining.find(part) {
+            Some(i) => remaining = &remaining[i + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= suffix.len() && remaining.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_groups() {
+        let config = ProjectConfig::parse(
+            r#"{
+                "groups": {
+                    "ui-framework": { "packages": ["react", "react-dom"], "maxSizeKb": 300 },
+                    "polyfills": { "packages": ["core-js", "regenerator-runtime"], "maxCount": 2 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.groups.len(), 2);
+        let ui = &config.groups["ui-framework"];
+        assert_eq!(ui.max_size_kb, Some(300));
+        assert_eq!(ui.max_count, None);
+        assert!(ui.has_budget());
+        assert_eq!(config.groups["polyfills"].max_count, Some(2));
+    }
+
+    #[test]
+    fn test_empty_and_invalid_configs() {
+        assert_eq!(ProjectConfig::parse("{}").unwrap(), ProjectConfig::default());
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"groups": {"empty": {"packages": []}}}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"groups": {"typo": {"packages": ["a"], "maxSize": 1}}}"#),
+            Err(ConfigError::JsonError(_))
+        ));
+    }
+
+    #[test]
+    fn test_owners_last_match_wins() {
+        let config = ProjectConfig::parse(
+            r#"{
+                "groups": { "polyfills": { "packages": ["core-js", "whatwg-*"] } },
+                "owners": [
+                    "* @platform",
+                    "group:polyfills @web-perf",
+                    "whatwg-fetch @web @web-perf"
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.owners_of("lodash"), ["@platform"]);
+        assert_eq!(config.owners_of("core-js"), ["@web-perf"]);
+        assert_eq!(config.owners_of("whatwg-fetch"), ["@web", "@web-perf"]);
+        assert!(config.is_owned_by("whatwg-fetch", "@web"));
+        assert!(!config.is_owned_by("core-js", "@web"));
+        assert_eq!(config.all_owners(), vec!["@platform", "@web", "@web-perf"]);
+        assert!(ProjectConfig::default().owners_of("react").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_owner_lines() {
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"owners": ["react"]}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"owners": ["group:missing @web"]}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_checks() {
+        let config = ProjectConfig::parse(
+            r#"{"checks": [
+                { "name": "no-copyleft", "command": ["node", "check.js"], "exitCode": 9 },
+                { "name": "lint", "command": ["./lint.sh"] }
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.checks.len(), 2);
+        assert_eq!(config.checks[0].command, vec!["node", "check.js"]);
+        assert_eq!(config.checks[0].exit_code, Some(9));
+        assert_eq!(config.checks[1].exit_code, None);
+
+        for invalid in [
+            r#"{"checks": [{ "name": "", "command": ["a"] }]}"#,
+            r#"{"checks": [{ "name": "a", "command": [] }]}"#,
+            r#"{"checks": [{ "name": "a", "command": ["a"] }, { "name": "a", "command": ["b"] }]}"#,
+        ] {
+            assert!(matches!(ProjectConfig::parse(invalid), Err(ConfigError::Invalid(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        let config = ProjectConfig::parse(r#"{"locale": "de-DE"}"#).unwrap();
+        assert_eq!(config.locale.unwrap().format_size(1536), "1,50 KB");
+        assert_eq!(ProjectConfig::parse("{}").unwrap().locale, None);
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"locale": "xx"}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_tui() {
+        assert!(ProjectConfig::parse("{}").unwrap().tui.size_bars);
+        assert!(ProjectConfig::parse(r#"{"tui": {}}"#).unwrap().tui.size_bars);
+        assert!(!ProjectConfig::parse(r#"{"tui": {"sizeBars": false}}"#).unwrap().tui.size_bars);
+        let tui = ProjectConfig::parse(r#"{"tui": {"openBrowser": false}}"#).unwrap().tui;
+        assert!(!tui.open_browser && tui.size_bars);
+        assert!(ProjectConfig::parse(r#"{"tui": {"bars": false}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_preset() {
+        let config = ProjectConfig::parse(r#"{"preset": "nextjs", "ignore": ["src/generated/**"]}"#).unwrap();
+        assert_eq!(config.preset, Some(Preset::NextJs));
+        assert_eq!(config.ignore.first().map(String::as_str), Some(".next/**"));
+        assert_eq!(config.ignore.last().map(String::as_str), Some("src/generated/**"));
+        assert!(config.is_always_used("react-dom"));
+        assert!(!config.counts_toward_budget(DependencyType::Development));
+
+        let plain = ProjectConfig::default();
+        assert!(plain.counts_toward_budget(DependencyType::Development));
+        assert!(!plain.is_always_used("react-dom"));
+        assert!(ProjectConfig::parse(r#"{"preset": "rails"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_ignore() {
+        let config = ProjectConfig::parse(r#"{"ignore": ["**/fixtures/**"]}"#).unwrap();
+        assert_eq!(config.ignore, vec!["**/fixtures/**"]);
+        assert!(ProjectConfig::parse("{}").unwrap().ignore.is_empty());
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"ignore": ["src/[a"]}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_license_policy() {
+        let config = ProjectConfig::parse(
+            r#"{"licenses": {"allow": ["MIT", "Apache-2.0", "GPL-2.0-only WITH Classpath-exception-2.0"]}}"#,
+        )
+        .unwrap();
+        let permits = |e: &str| config.licenses.permits(&LicenseExpression::parse(e).unwrap());
+        assert!(permits("MIT OR GPL-3.0-only"));
+        assert!(permits("GPL-2.0-only WITH Classpath-exception-2.0"));
+        assert!(!permits("GPL-2.0-only"));
+        assert!(!permits("MIT AND ISC"));
+
+        assert!(matches!(
+            ProjectConfig::parse(r#"{"licenses": {"deny": ["MIT OR ISC"]}}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let dir = std::env::temp_dir().join(format!("codescope-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(ProjectConfig::load(&dir).unwrap(), ProjectConfig::default());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_toml() {
+        let dir = std::env::temp_dir().join(format!("codescope-config-toml-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(TOML_CONFIG_FILE), "owners = [\"* @platform\"]\n\n[tui]\nsizeBars = false\n").unwrap();
+        assert_eq!(ConfigFormat::detect(&dir), Some(ConfigFormat::Toml));
+        let config = ProjectConfig::load(&dir).unwrap();
+        assert_eq!(config.owners_of("react"), ["@platform"]);
+        assert!(!config.tui.size_bars);
+
+        // The JSON file wins when there are both
+        fs::write(dir.join(CONFIG_FILE), "{}").unwrap();
+        assert_eq!(ConfigFormat::detect(&dir), Some(ConfigFormat::Json));
+        assert_eq!(ProjectConfig::load(&dir).unwrap(), ProjectConfig::default());
+
+        assert!(matches!(
+            ProjectConfig::parse_as("[tui]\nbars = false\n", ConfigFormat::Toml),
+            Err(ConfigError::TomlError(_))
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("react", "react"));
+        assert!(!glob_match("react", "react-dom"));
+        assert!(glob_match("@babel/*", "@babel/core"));
+        assert!(!glob_match("@babel/*", "babel-loader"));
+        assert!(glob_match("*-loader", "babel-loader"));
+        assert!(glob_match("eslint*", "eslint"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("@types/*react*", "@types/react-dom"));
+        assert!(!glob_match("a*a", "a"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Built-in settings for common stacks.
+//!
+//! Every framework leaves the same traces codescope would otherwise
+//! misread: build output next to the sources, stats files in
+//! framework-specific places, and runtime packages the framework loads
+//! without the project ever importing them. A `preset` in
+//! `.codescoperc.json` fills those in, e.g. `"preset": "nextjs"`; the
+//! project's own settings are applied on top.
+
+use std::fmt;
+
+use serde::Deserialize;
+
+use super::glob_match;
+use crate::parser::types::DependencyType;
+
+/// Dependency types that ship to the browser.
+const SHIPPED_TYPES: [DependencyType; 3] = [DependencyType::Production, DependencyType::Peer, DependencyType::Optional];
+
+/// A built-in configuration for a common stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Preset {
+    /// A Next.js application
+    #[serde(rename = "nextjs")]
+    NextJs,
+    /// A React application built with Vite
+    #[serde(rename = "vite-react")]
+    ViteReact,
+    /// A Node.js server or worker, which has no bundle
+    #[serde(rename = "node-service")]
+    NodeService,
+}
+
+impl Preset {
+    /// Every preset, in the order they are offered.
+    pub const ALL: [Preset; 3] = [Preset::NextJs, Preset::ViteReact, Preset::NodeService];
+
+    /// Returns the name used in the configuration file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::NextJs => "nextjs",
+            Preset::ViteReact => "vite-react",
+            Preset::NodeService => "node-service",
+        }
+    }
+
+    /// Returns the globs of generated files the import scan skips.
+    pub fn ignore(&self) -> &'static [&'static str] {
+        match self {
+            Preset::NextJs => &[".next/**", "out/**", "next-env.d.ts"],
+            Preset::ViteReact => &["dist/**", "coverage/**", "stats.html"],
+            Preset::NodeService => &["dist/**", "build/**", "coverage/**"],
+        }
+    }
+
+    /// Returns where the stack's bundle analyzers write their stats,
+    /// relative to the project, in the order they are tried.
+    pub fn stats_paths(&self) -> &'static [&'static str] {
+        match self {
+            Preset::NextJs => &[".next/analyze/client.json", ".next/stats.json"],
+            Preset::ViteReact => &["stats.json", "dist/stats.json"],
+            Preset::NodeService => &[],
+        }
+    }
+
+    /// Returns the dependency types that count toward group budgets.
+    ///
+    /// Browser apps count what ships; a service counts its runtime
+    /// dependencies, since it installs no peers of its own.
+    pub fn budget_types(&self) -> &'static [DependencyType] {
+        match self {
+            Preset::NextJs | Preset::ViteReact => &SHIPPED_TYPES,
+            Preset::NodeService => &[DependencyType::Production, DependencyType::Optional],
+        }
+    }
+
+    /// Returns patterns of packages in use even when no source file
+    /// imports them: framework runtimes, the JSX runtime, and packages
+    /// loaded by name from configuration or the command line.
+    pub fn always_used(&self) -> &'static [&'static str] {
+        match self {
+            Preset::NextJs => &["next", "react", "react-dom", "sharp", "@next/*", "styled-jsx"],
+            Preset::ViteReact => &["react", "react-dom", "vite", "@vitejs/*"],
+            Preset::NodeService => &["dotenv", "source-map-support", "tslib", "pino-pretty"],
+        }
+    }
+
+    /// Returns true if the preset treats `package` as always used.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::Preset;
+    ///
+    /// assert!(Preset::NextJs.uses("@next/font"));
+    /// assert!(!Preset::NodeService.uses("react"));
+    /// ```
+    pub fn uses(&self, package: &str) -> bool {
+        self.always_used().iter().any(|pattern| glob_match(pattern, package))
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
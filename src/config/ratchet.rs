@@ -0,0 +1,257 @@
+//! Tightening thresholds to the measured values.
+//!
+//! A budget set by hand is either too loose to catch a regression or too
+//! tight to pass. Ratcheting rewrites the thresholds of `.codescoperc.json`
+//! or `.codescope.toml` to what the project measures today, so CI enforces
+//! "never worse than today"; with [`Ratchet::down_only`] a threshold is
+//! only ever lowered. Only thresholds the file already sets are touched,
+//! and everything else in the file, including the order of its keys and
+//! the comments of a TOML file, is kept.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use super::{ConfigError, ConfigFormat, ConfigResult};
+
+/// A value measured for a threshold of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    /// Keys leading to the threshold, e.g. `["groups", "ui", "maxSizeKb"]`
+    pub path: Vec<String>,
+    /// Measured value, in the threshold's unit
+    pub value: u64,
+}
+
+impl Measurement {
+    /// Creates a measurement for the threshold at `path`.
+    pub fn new<S: AsRef<str>>(path: &[S], value: u64) -> Self {
+        Self {
+            path: path.iter().map(|key| key.as_ref().to_string()).collect(),
+            value,
+        }
+    }
+}
+
+/// A threshold the ratchet changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdChange {
+    /// Keys leading to the threshold
+    pub path: Vec<String>,
+    /// Threshold before
+    pub old: u64,
+    /// Threshold after
+    pub new: u64,
+}
+
+impl fmt::Display for ThresholdChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arrow = if self.new < self.old { "↓" } else { "↑" };
+        write!(f, "{}: {} {} {}", self.path.join("."), self.old, arrow, self.new)
+    }
+}
+
+/// Rewrites thresholds of a configuration file to measured values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ratchet {
+    /// Only lower thresholds, leaving those below the measured value
+    pub down_only: bool,
+}
+
+impl Ratchet {
+    /// Creates a ratchet that moves thresholds both ways.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only lowers thresholds.
+    pub fn down_only(mut self) -> Self {
+        self.down_only = true;
+        self
+    }
+
+    /// Applies measurements to the contents of a `.codescoperc.json`.
+    ///
+    /// Measurements of thresholds the file does not set are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The rewritten file and the thresholds that changed, in the order of
+    /// `measured`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::ratchet::{Measurement, Ratchet};
+    ///
+    /// let content = r#"{"groups": {"ui": {"packages": ["react*"], "maxSizeKb": 300, "maxCount": 4}}}"#;
+    /// let measured = [
+    ///     Measurement::new(&["groups", "ui", "maxSizeKb"], 245),
+    ///     Measurement::new(&["groups", "ui", "maxCount"], 5),
+    /// ];
+    ///
+    /// let (_, changes) = Ratchet::new().down_only().apply(content, &measured).unwrap();
+    /// assert_eq!(changes.len(), 1);
+    /// assert_eq!(changes[0].to_string(), "groups.ui.maxSizeKb: 300 ↓ 245");
+    /// ```
+    pub fn apply(&self, content: &str, measured: &[Measurement]) -> ConfigResult<(String, Vec<ThresholdChange>)> {
+        self.apply_as(content, ConfigFormat::Json, measured)
+    }
+
+    /// Applies measurements to the contents of a configuration file in
+    /// `format`, like [`apply`](Self::apply).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::ratchet::{Measurement, Ratchet};
+    /// use codescope::config::ConfigFormat;
+    ///
+    /// let content = "[tooling]\nmaxNative = 2 # bcrypt and sharp\n";
+    /// let measured = [Measurement::new(&["tooling", "maxNative"], 1)];
+    ///
+    /// let (rewritten, _) = Ratchet::new().apply_as(content, ConfigFormat::Toml, &measured).unwrap();
+    /// assert_eq!(rewritten, "[tooling]\nmaxNative = 1 # bcrypt and sharp\n");
+    /// ```
+    pub fn apply_as(
+        &self,
+        content: &str,
+        format: ConfigFormat,
+        measured: &[Measurement],
+    ) -> ConfigResult<(String, Vec<ThresholdChange>)> {
+        match format {
+            ConfigFormat::Json => self.apply_json(content, measured),
+            ConfigFormat::Toml => self.apply_toml(content, measured),
+        }
+    }
+
+    /// Returns the change of a threshold at `old` to the measured value, or
+    /// `None` if it stays.
+    fn change(&self, old: u64, measurement: &Measurement) -> Option<ThresholdChange> {
+        let new = measurement.value;
+        if new == old || (self.down_only && new > old) {
+            return None;
+        }
+        Some(ThresholdChange {
+            path: measurement.path.clone(),
+            old,
+            new,
+        })
+    }
+
+    fn apply_json(&self, content: &str, measured: &[Measurement]) -> ConfigResult<(String, Vec<ThresholdChange>)> {
+        let mut root: Value = serde_json::from_str(content)?;
+        let mut changes = Vec::new();
+        for measurement in measured {
+            let threshold = measurement
+                .path
+                .iter()
+                .try_fold(&mut root, |value, key| value.get_mut(key.as_str()));
+            let Some(threshold) = threshold else {
+                continue;
+            };
+            let Some(old) = threshold.as_u64() else {
+                return Err(not_a_whole_number(measurement));
+            };
+            if let Some(change) = self.change(old, measurement) {
+                *threshold = Value::from(change.new);
+                changes.push(change);
+            }
+        }
+
+        let mut rewritten = serde_json::to_string_pretty(&root)?;
+        rewritten.push('\n');
+        Ok((rewritten, changes))
+    }
+
+    /// Rewrites thresholds in place, so the formatting and comments of the
+    /// file stay as they are.
+    fn apply_toml(&self, content: &str, measured: &[Measurement]) -> ConfigResult<(String, Vec<ThresholdChange>)> {
+        let mut document: toml_edit::DocumentMut = content.parse()?;
+        let mut changes = Vec::new();
+        for measurement in measured {
+            let threshold = measurement
+                .path
+                .iter()
+                .try_fold(document.as_item_mut(), |item, key| item.get_mut(key.as_str()));
+            // Looking up a missing key in a table adds an empty item, which
+            // is not written out
+            let Some(threshold) = threshold.filter(|item| !item.is_none()) else {
+                continue;
+            };
+            let old = threshold.as_integer().and_then(|old| u64::try_from(old).ok());
+            let (Some(old), Some(value)) = (old, threshold.as_value_mut()) else {
+                return Err(not_a_whole_number(measurement));
+            };
+            if let Some(change) = self.change(old, measurement) {
+                let decor = value.decor().clone();
+                *value = toml_edit::Value::from(i64::try_from(change.new).unwrap_or(i64::MAX));
+                *value.decor_mut() = decor;
+                changes.push(change);
+            }
+        }
+        Ok((document.to_string(), changes))
+    }
+}
+
+/// The error for a threshold that is not set to a whole number.
+fn not_a_whole_number(measurement: &Measurement) -> ConfigError {
+    ConfigError::Invalid(format!("{} is not a whole number", measurement.path.join(".")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratchet_keeps_the_rest_of_the_file() {
+        let content = r#"{
+  "preset": "vite-react",
+  "tooling": { "maxNative": 2, "maxFiles": 50000 },
+  "groups": { "ui": { "packages": ["react*"], "maxCount": 4 } }
+}"#;
+        let measured = [
+            Measurement::new(&["tooling", "maxNative"], 1),
+            Measurement::new(&["tooling", "maxFiles"], 52000),
+            Measurement::new(&["tooling", "maxInstallMb"], 300),
+            Measurement::new(&["groups", "ui", "maxCount"], 4),
+        ];
+
+        let (rewritten, changes) = Ratchet::new().apply(content, &measured).unwrap();
+        let described: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(described, vec!["tooling.maxNative: 2 ↓ 1", "tooling.maxFiles: 50000 ↑ 52000"]);
+        assert!(!rewritten.contains("maxInstallMb"), "unset thresholds stay unset");
+        let keys: Vec<usize> = ["preset", "tooling", "groups"].iter().map(|key| rewritten.find(key).unwrap()).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "key order is kept:\n{}", rewritten);
+
+        let (_, changes) = Ratchet::new().down_only().apply(content, &measured).unwrap();
+        assert_eq!(changes.len(), 1);
+
+        let invalid = r#"{"tooling": {"maxNative": "two"}}"#;
+        assert!(Ratchet::new().apply(invalid, &measured).is_err());
+    }
+
+    #[test]
+    fn test_ratchet_toml_keeps_comments() {
+        let content = "preset = \"nextjs\"\n\n# Native addons need a build toolchain in CI\n[tooling]\nmaxNative = 2\nmaxFiles = 50000 # after the lodash split\n\n[groups.ui]\npackages = [\"react*\"]\nmaxCount = 4\n";
+        let measured = [
+            Measurement::new(&["tooling", "maxNative"], 1),
+            Measurement::new(&["tooling", "maxFiles"], 52000),
+            Measurement::new(&["tooling", "maxInstallMb"], 300),
+            Measurement::new(&["groups", "ui", "maxCount"], 3),
+        ];
+
+        let (rewritten, changes) = Ratchet::new().down_only().apply_as(content, ConfigFormat::Toml, &measured).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            rewritten,
+            content
+                .replace("maxNative = 2", "maxNative = 1")
+                .replace("maxCount = 4", "maxCount = 3")
+        );
+
+        let invalid = "[tooling]\nmaxNative = -1\n";
+        assert!(Ratchet::new().apply_as(invalid, ConfigFormat::Toml, &measured).is_err());
+        assert!(Ratchet::new().apply_as("[tooling", ConfigFormat::Toml, &measured).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! First-run setup of `.codescope.toml`.
+//!
+//! A project opened in the TUI without a configuration file gets a guided
+//! setup: codescope detects the package manager and the bundler, suggests
+//! the matching [`Preset`], and offers today's measurements as the
+//! thresholds to hold the project to. The choices end up in a
+//! [`SetupPlan`], which renders the file to write.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use super::{Measurement, Preset};
+
+/// Packages whose presence means the project ships to the browser.
+const BROWSER_FRAMEWORKS: [&str; 5] = ["react", "vue", "svelte", "preact", "@angular/core"];
+
+/// The package manager that installs a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    /// npm
+    Npm,
+    /// pnpm
+    Pnpm,
+    /// Yarn
+    Yarn,
+}
+
+impl PackageManager {
+    /// Returns the package manager that writes the given lockfile.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::PackageManager;
+    ///
+    /// assert_eq!(PackageManager::from_lockfile("pnpm-lock.yaml"), Some(PackageManager::Pnpm));
+    /// assert_eq!(PackageManager::from_lockfile("npm-shrinkwrap.json"), Some(PackageManager::Npm));
+    /// assert_eq!(PackageManager::from_lockfile("Cargo.lock"), None);
+    /// ```
+    pub fn from_lockfile(name: &str) -> Option<Self> {
+        match name {
+            "package-lock.json" | "npm-shrinkwrap.json" => Some(PackageManager::Npm),
+            "pnpm-lock.yaml" => Some(PackageManager::Pnpm),
+            "yarn.lock" => Some(PackageManager::Yarn),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageManager::Npm => write!(f, "npm"),
+            PackageManager::Pnpm => write!(f, "pnpm"),
+            PackageManager::Yarn => write!(f, "yarn"),
+        }
+    }
+}
+
+/// Suggests the preset of a project from the names of its dependencies.
+///
+/// Next.js and Vite with React are recognized by their packages; a project
+/// without a bundler or a browser framework is taken for a Node.js service.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::config::{setup::suggest_preset, Preset};
+///
+/// assert_eq!(suggest_preset(["react", "next"], false), Some(Preset::NextJs));
+/// assert_eq!(suggest_preset(["express", "pino"], false), Some(Preset::NodeService));
+/// assert_eq!(suggest_preset(["vue", "webpack"], true), None);
+/// ```
+pub fn suggest_preset<'a>(dependencies: impl IntoIterator<Item = &'a str>, has_bundler: bool) -> Option<Preset> {
+    let names: Vec<&str> = dependencies.into_iter().collect();
+    let has = |name: &str| names.contains(&name);
+    if has("next") {
+        Some(Preset::NextJs)
+    } else if has("vite") && has("react") {
+        Some(Preset::ViteReact)
+    } else if !has_bundler && !BROWSER_FRAMEWORKS.iter().any(|name| has(name)) {
+        Some(Preset::NodeService)
+    } else {
+        None
+    }
+}
+
+/// The settings chosen in the first-run setup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetupPlan {
+    /// Preset of the project's stack
+    pub preset: Option<Preset>,
+    /// Thresholds to set, at their measured values
+    pub thresholds: Vec<Measurement>,
+}
+
+impl SetupPlan {
+    /// Returns where generated stats are saved so the preset finds them,
+    /// or `None` if the preset does not look for stats.
+    pub fn stats_path(&self) -> Option<&'static str> {
+        self.preset.and_then(|preset| preset.stats_paths().first().copied())
+    }
+
+    /// Renders the `.codescope.toml` of the plan.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::config::{ConfigFormat, Measurement, Preset, ProjectConfig, SetupPlan};
+    ///
+    /// let plan = SetupPlan {
+    ///     preset: Some(Preset::ViteReact),
+    ///     thresholds: vec![Measurement::new(&["tooling", "maxInstallMb"], 412)],
+    /// };
+    /// let config = ProjectConfig::parse_as(&plan.to_config(), ConfigFormat::Toml).unwrap();
+    /// assert_eq!(config.preset, Some(Preset::ViteReact));
+    /// assert_eq!(config.tooling.max_install_mb, Some(412));
+    /// ```
+    pub fn to_config(&self) -> String {
+        let mut root = Map::new();
+        if let Some(preset) = self.preset {
+            root.insert("preset".to_string(), Value::from(preset.name()));
+        }
+        for threshold in &self.thresholds {
+            let Some((key, parents)) = threshold.path.split_last() else {
+                continue;
+            };
+            let mut object = &mut root;
+            for parent in parents {
+                object = match object
+                    .entry(parent.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                {
+                    Value::Object(child) => child,
+                    _ => unreachable!("only thresholds are inserted as values"),
+                };
+            }
+            object.insert(key.clone(), Value::from(threshold.value));
+        }
+        toml_edit::ser::to_string_pretty(&Value::Object(root)).expect("presets and thresholds always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigFormat, ProjectConfig};
+
+    #[test]
+    fn test_suggest_preset() {
+        assert_eq!(suggest_preset(["react", "react-dom", "vite"], true), Some(Preset::ViteReact));
+        assert_eq!(suggest_preset(["react", "vite"], false), Some(Preset::ViteReact));
+        assert_eq!(suggest_preset(["react", "react-dom"], false), None);
+        assert_eq!(suggest_preset(["express"], true), None);
+        assert_eq!(suggest_preset([], false), Some(Preset::NodeService));
+    }
+
+    #[test]
+    fn test_plan_to_config() {
+        let plan = SetupPlan {
+            preset: Some(Preset::NextJs),
+            thresholds: vec![
+                Measurement::new(&["tooling", "maxInstallMb"], 380),
+                Measurement::new(&["tooling", "maxNative"], 1),
+            ],
+        };
+        assert_eq!(
+            plan.to_config(),
+            "preset = \"nextjs\"\n\n[tooling]\nmaxInstallMb = 380\nmaxNative = 1\n"
+        );
+        assert_eq!(plan.stats_path(), Some(".next/analyze/client.json"));
+
+        let empty = SetupPlan::default();
+        assert_eq!(empty.to_config(), "");
+        assert_eq!(ProjectConfig::parse_as(&empty.to_config(), ConfigFormat::Toml).unwrap(), ProjectConfig::default());
+        assert_eq!(empty.stats_path(), None);
+    }
+}
@@ -0,0 +1,542 @@
+//! Cross-project aggregation of JSON exports.
+//!
+//! Each project exports its own analysis with `codescope export --format
+//! json`. [`AggregateReport`] reads those documents back and merges them
+//! into one report for the whole organization: the dependencies most
+//! projects share, the versions each of them is used at, how much is
+//! duplicated across projects and which projects break shared policies.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::json::SCHEMA_VERSION;
+use crate::bundle::webpack::format_size;
+use crate::config::LicensePolicy;
+use crate::parser::LicenseExpression;
+
+/// Errors that can occur while reading an exported report.
+#[derive(Error, Debug)]
+pub enum AggregateError {
+    /// The report could not be read.
+    #[error("Failed to read {0}: {1}")]
+    Io(String, #[source] std::io::Error),
+
+    /// The report is not a JSON export.
+    #[error("{0} is not a codescope JSON export: {1}")]
+    Parse(String, String),
+
+    /// The report was written with an incompatible schema version.
+    #[error("{path} uses schema version {version}, but only {supported}.x can be read")]
+    UnsupportedSchema {
+        /// The report
+        path: String,
+        /// Its schema version
+        version: String,
+        /// The major schema version this build reads
+        supported: String,
+    },
+}
+
+/// The parts of a JSON export the aggregation reads.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedProject {
+    /// Version of the export format
+    pub schema_version: String,
+    /// Name and version of the project
+    pub project: ExportedName,
+    /// Every package of the project
+    #[serde(default)]
+    pub packages: Vec<ExportedPackage>,
+    /// Dependency cycles
+    #[serde(default)]
+    pub cycles: Vec<ExportedCycle>,
+    /// Packages required at conflicting versions
+    #[serde(default)]
+    pub conflicts: Vec<ExportedConflict>,
+    /// Package groups and their budgets
+    #[serde(default)]
+    pub groups: Vec<ExportedGroup>,
+}
+
+/// Name and version of an exported project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedName {
+    /// Project name
+    pub name: String,
+    /// Project version
+    #[serde(default)]
+    pub version: String,
+}
+
+/// A package of an exported project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedPackage {
+    /// Name the package is installed under
+    pub name: String,
+    /// Version, or the requirement if it was not resolved
+    pub version: String,
+    /// Real package name, differing from `name` for aliases
+    #[serde(default)]
+    pub package: String,
+    /// Whether the project depends on it directly
+    #[serde(default)]
+    pub direct: bool,
+    /// Bundle size in bytes, if known
+    #[serde(default)]
+    pub bundle_size: Option<u64>,
+    /// SPDX license expression, if known
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+impl ExportedPackage {
+    /// Returns the real package name.
+    pub fn package_name(&self) -> &str {
+        if self.package.is_empty() {
+            &self.name
+        } else {
+            &self.package
+        }
+    }
+}
+
+/// A dependency cycle of an exported project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedCycle {
+    /// Packages in the cycle
+    pub packages: Vec<String>,
+}
+
+/// A version conflict of an exported project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedConflict {
+    /// Package required at conflicting versions
+    pub package: String,
+}
+
+/// A package group of an exported project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportedGroup {
+    /// Group name
+    pub name: String,
+    /// Whether the group exceeds its size or count budget
+    #[serde(default)]
+    pub over_budget: bool,
+}
+
+impl ExportedProject {
+    /// Reads a JSON export from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AggregateError`] if the file cannot be read, is not a
+    /// JSON export or uses another major schema version.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AggregateError> {
+        let path = path.as_ref().display().to_string();
+        let content = fs::read_to_string(&path).map_err(|e| AggregateError::Io(path.clone(), e))?;
+        Self::parse(&path, &content)
+    }
+
+    /// Parses a JSON export.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Where the export came from, used in errors
+    /// * `json` - The exported document
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::aggregate::ExportedProject;
+    ///
+    /// let json = r#"{"schema_version": "1.8", "project": {"name": "web", "version": "1.0.0"},
+    ///               "packages": [{"name": "react", "version": "18.2.0", "direct": true}]}"#;
+    /// let project = ExportedProject::parse("web.json", json).unwrap();
+    /// assert_eq!(project.packages[0].package_name(), "react");
+    /// assert!(ExportedProject::parse("old.json", r#"{"schema_version": "0.9", "project": {"name": "x"}}"#).is_err());
+    /// ```
+    pub fn parse(source: &str, json: &str) -> Result<Self, AggregateError> {
+        let project: Self =
+            serde_json::from_str(json).map_err(|e| AggregateError::Parse(source.to_string(), e.to_string()))?;
+        let major = |version: &str| version.split('.').next().unwrap_or_default().to_string();
+        let supported = major(SCHEMA_VERSION);
+        if major(&project.schema_version) != supported {
+            return Err(AggregateError::UnsupportedSchema {
+                path: source.to_string(),
+                version: project.schema_version,
+                supported,
+            });
+        }
+        Ok(project)
+    }
+
+    /// Returns the project's name and version for reports.
+    pub fn label(&self) -> String {
+        if self.project.version.is_empty() {
+            self.project.name.clone()
+        } else {
+            format!("{}@{}", self.project.name, self.project.version)
+        }
+    }
+}
+
+/// A dependency and the projects that use it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SharedDependency {
+    /// Package name
+    pub package: String,
+    /// Number of projects using it
+    pub projects: usize,
+    /// Number of those that depend on it directly
+    pub direct: usize,
+    /// Projects per version, by version
+    pub versions: BTreeMap<String, Vec<String>>,
+}
+
+impl SharedDependency {
+    /// Returns true if the projects use more than one version.
+    pub fn has_spread(&self) -> bool {
+        self.versions.len() > 1
+    }
+}
+
+/// A shared policy an exported project breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Violation {
+    /// The project has dependency cycles
+    Cycles {
+        /// Number of cycles
+        count: usize,
+    },
+    /// The project requires packages at conflicting versions
+    Conflicts {
+        /// The conflicting packages
+        packages: Vec<String>,
+    },
+    /// A package group exceeds its budget
+    OverBudget {
+        /// The group
+        group: String,
+    },
+    /// A package's license is not allowed by the shared license policy
+    License {
+        /// The package
+        package: String,
+        /// Its license expression
+        license: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Cycles { count } => write!(f, "{} dependency cycle(s)", count),
+            Violation::Conflicts { packages } => write!(f, "version conflicts in {}", packages.join(", ")),
+            Violation::OverBudget { group } => write!(f, "group '{}' is over budget", group),
+            Violation::License { package, license } => write!(f, "{} uses a disallowed license ({})", package, license),
+        }
+    }
+}
+
+/// The policies one project breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectViolations {
+    /// Project name and version
+    pub project: String,
+    /// What the project breaks
+    pub violations: Vec<Violation>,
+}
+
+/// Several exported projects merged into one report.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AggregateReport {
+    /// Names and versions of the projects, in the order given
+    pub projects: Vec<String>,
+    /// Every dependency, most widely used first, then by name
+    pub dependencies: Vec<SharedDependency>,
+    /// Installs of a package beyond the first project using it
+    pub duplicate_installs: usize,
+    /// Bundle bytes of those installs, where their sizes are known
+    pub duplicated_bundle_size: u64,
+    /// Projects that break a shared policy, in the order given
+    pub violations: Vec<ProjectViolations>,
+}
+
+impl AggregateReport {
+    /// Merges exported projects.
+    ///
+    /// Cycles, version conflicts and groups over budget count as policy
+    /// violations; licenses are checked against `licenses` unless it is
+    /// empty. Packages with a missing or unparsable license are not
+    /// flagged.
+    ///
+    /// # Arguments
+    ///
+    /// * `projects` - The exported projects
+    /// * `licenses` - The license policy every project must follow
+    pub fn from_projects(projects: &[ExportedProject], licenses: &LicensePolicy) -> Self {
+        let mut dependencies: BTreeMap<&str, SharedDependency> = BTreeMap::new();
+        let mut sizes: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+        let mut violations = Vec::new();
+
+        for project in projects {
+            let label = project.label();
+            // A package installed at several versions counts once per project
+            let mut used: BTreeMap<&str, (bool, Option<u64>)> = BTreeMap::new();
+            for package in &project.packages {
+                let name = package.package_name();
+                let (direct, size) = used.entry(name).or_insert((false, None));
+                *direct |= package.direct;
+                *size = (*size).max(package.bundle_size);
+
+                let shared = dependencies.entry(name).or_insert_with(|| SharedDependency {
+                    package: name.to_string(),
+                    projects: 0,
+                    direct: 0,
+                    versions: BTreeMap::new(),
+                });
+                let projects = shared.versions.entry(package.version.clone()).or_default();
+                if !projects.contains(&label) {
+                    projects.push(label.clone());
+                }
+            }
+            for (name, (direct, size)) in used {
+                let shared = dependencies.get_mut(name).expect("recorded above");
+                shared.projects += 1;
+                shared.direct += usize::from(direct);
+                if let Some(size) = size {
+                    sizes.entry(name).or_default().push(size);
+                }
+            }
+
+            let found = project_violations(project, licenses);
+            if !found.is_empty() {
+                violations.push(ProjectViolations {
+                    project: label,
+                    violations: found,
+                });
+            }
+        }
+
+        let mut dependencies: Vec<SharedDependency> = dependencies.into_values().collect();
+        dependencies.sort_by(|a, b| b.projects.cmp(&a.projects).then_with(|| a.package.cmp(&b.package)));
+        let duplicate_installs = dependencies.iter().map(|d| d.projects.saturating_sub(1)).sum();
+        // Every project past the one with the largest copy duplicates it
+        let duplicated_bundle_size = sizes
+            .values()
+            .map(|sizes| sizes.iter().sum::<u64>() - sizes.iter().max().copied().unwrap_or(0))
+            .sum();
+
+        Self {
+            projects: projects.iter().map(ExportedProject::label).collect(),
+            dependencies,
+            duplicate_installs,
+            duplicated_bundle_size,
+            violations,
+        }
+    }
+
+    /// Returns the dependencies used by more than one project.
+    pub fn shared(&self) -> impl Iterator<Item = &SharedDependency> {
+        self.dependencies.iter().filter(|d| d.projects > 1)
+    }
+
+    /// Returns the dependencies the projects use at more than one version.
+    pub fn version_spread(&self) -> impl Iterator<Item = &SharedDependency> {
+        self.dependencies.iter().filter(|d| d.has_spread())
+    }
+
+    /// Formats the report for the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of dependencies listed per section
+    pub fn format_report(&self, limit: usize) -> String {
+        let mut out = format!(
+            "🌐 {} project(s), {} distinct dependencies\n",
+            self.projects.len(),
+            self.dependencies.len()
+        );
+
+        let shared: Vec<&SharedDependency> = self.shared().collect();
+        if !shared.is_empty() {
+            out.push_str(&format!("\nMost common dependencies ({} shared):\n", shared.len()));
+            let width = shared.iter().take(limit).map(|d| d.package.len()).max().unwrap_or(0);
+            for dependency in shared.iter().take(limit) {
+                out.push_str(&format!(
+                    "  {:width$}  {} projects ({} direct)\n",
+                    dependency.package,
+                    dependency.projects,
+                    dependency.direct,
+                    width = width
+                ));
+            }
+        }
+
+        let spread: Vec<&SharedDependency> = self.version_spread().collect();
+        if !spread.is_empty() {
+            out.push_str(&format!("\nVersion spread ({} packages):\n", spread.len()));
+            for dependency in spread.iter().take(limit) {
+                out.push_str(&format!("  {}\n", dependency.package));
+                for (version, projects) in &dependency.versions {
+                    out.push_str(&format!("    {:<12} {}\n", version, projects.join(", ")));
+                }
+            }
+        }
+
+        out.push_str(&format!(
+            "\nDuplicated effort: {} installs of packages another project also installs",
+            self.duplicate_installs
+        ));
+        if self.duplicated_bundle_size > 0 {
+            out.push_str(&format!(", {} of bundle", format_size(self.duplicated_bundle_size)));
+        }
+        out.push('\n');
+
+        if self.violations.is_empty() {
+            out.push_str("\n✅ No project violates the shared policies.\n");
+        } else {
+            out.push_str(&format!("\n❌ {} project(s) violate shared policies:\n", self.violations.len()));
+            for project in &self.violations {
+                out.push_str(&format!("  {}\n", project.project));
+                for violation in &project.violations {
+                    out.push_str(&format!("    - {}\n", violation));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Returns the shared policies a project breaks.
+fn project_violations(project: &ExportedProject, licenses: &LicensePolicy) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if !project.cycles.is_empty() {
+        violations.push(Violation::Cycles {
+            count: project.cycles.len(),
+        });
+    }
+    if !project.conflicts.is_empty() {
+        violations.push(Violation::Conflicts {
+            packages: project.conflicts.iter().map(|c| c.package.clone()).collect(),
+        });
+    }
+    for group in project.groups.iter().filter(|g| g.over_budget) {
+        violations.push(Violation::OverBudget {
+            group: group.name.clone(),
+        });
+    }
+    if !licenses.is_empty() {
+        for package in &project.packages {
+            let Some(license) = &package.license else {
+                continue;
+            };
+            if LicenseExpression::parse(license).is_some_and(|expression| !licenses.permits(&expression)) {
+                violations.push(Violation::License {
+                    package: package.name.clone(),
+                    license: license.clone(),
+                });
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProjectConfig;
+    use crate::export::{ExportReport, JsonExporter};
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    fn exported(name: &str, packages: &[(&str, &str, Option<u64>)]) -> ExportedProject {
+        let mut graph = DependencyGraph::new();
+        for (package, version, _) in packages {
+            graph.add_dependency(package, version, DependencyType::Production);
+        }
+        let sizes = packages
+            .iter()
+            .filter_map(|(package, _, size)| size.map(|size| (package.to_string(), (size, 1_usize))))
+            .collect();
+        graph.apply_bundle_sizes(&sizes);
+        let json = JsonExporter::new().export(&ExportReport::from_graph(name, "1.0.0", &graph));
+        ExportedProject::parse(name, &json).unwrap()
+    }
+
+    #[test]
+    fn test_aggregate_exports() {
+        let projects = [
+            exported("web", &[("react", "18.2.0", Some(4000)), ("lodash", "4.17.21", Some(1000))]),
+            exported("admin", &[("react", "17.0.2", Some(3000)), ("lodash", "4.17.21", None)]),
+            exported("api", &[("express", "4.18.2", None), ("lodash", "4.17.21", Some(1000))]),
+        ];
+        let report = AggregateReport::from_projects(&projects, &LicensePolicy::default());
+
+        assert_eq!(report.projects, vec!["web@1.0.0", "admin@1.0.0", "api@1.0.0"]);
+        let common: Vec<(&str, usize)> = report.shared().map(|d| (d.package.as_str(), d.projects)).collect();
+        assert_eq!(common, vec![("lodash", 3), ("react", 2)]);
+
+        let spread: Vec<&SharedDependency> = report.version_spread().collect();
+        assert_eq!(spread.len(), 1);
+        assert_eq!(spread[0].versions["17.0.2"], vec!["admin@1.0.0"]);
+        assert_eq!(spread[0].versions["18.2.0"], vec!["web@1.0.0"]);
+
+        assert_eq!(report.duplicate_installs, 3);
+        assert_eq!(report.duplicated_bundle_size, 3000 + 1000);
+        assert!(report.violations.is_empty());
+
+        let text = report.format_report(10);
+        assert!(text.starts_with("🌐 3 project(s), 3 distinct dependencies\n"));
+        assert!(text.contains("  lodash  3 projects (3 direct)\n"));
+        assert!(text.contains("    17.0.2       admin@1.0.0\n"));
+        assert!(text.contains("✅ No project violates the shared policies."));
+    }
+
+    #[test]
+    fn test_shared_policy_violations() {
+        let json = r#"{"schema_version": "1.8", "project": {"name": "web", "version": "2.0.0"},
+            "packages": [
+                {"name": "react", "version": "18.2.0", "license": "MIT"},
+                {"name": "readline-sync", "version": "1.4.10", "license": "GPL-3.0-only"},
+                {"name": "dual", "version": "1.0.0", "license": "(GPL-3.0-only OR MIT)"},
+                {"name": "unknown", "version": "1.0.0"}
+            ],
+            "cycles": [{"packages": ["a", "b"], "path": "a -> b -> a"}],
+            "conflicts": [{"package": "b", "requirements": []}],
+            "groups": [{"name": "charts", "over_budget": true}, {"name": "ui", "over_budget": false}]}"#;
+        let project = ExportedProject::parse("web.json", json).unwrap();
+        let config = ProjectConfig::parse(r#"{"licenses": {"deny": ["GPL-3.0-only"]}}"#).unwrap();
+
+        let report = AggregateReport::from_projects(&[project], &config.licenses);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(
+            report.violations[0].violations,
+            vec![
+                Violation::Cycles { count: 1 },
+                Violation::Conflicts { packages: vec!["b".to_string()] },
+                Violation::OverBudget { group: "charts".to_string() },
+                Violation::License {
+                    package: "readline-sync".to_string(),
+                    license: "GPL-3.0-only".to_string()
+                },
+            ]
+        );
+        assert!(report
+            .format_report(10)
+            .contains("❌ 1 project(s) violate shared policies:\n  web@2.0.0\n    - 1 dependency cycle(s)\n"));
+    }
+
+    #[test]
+    fn test_rejects_other_major_versions() {
+        let err = ExportedProject::parse("old.json", r#"{"schema_version": "2.0", "project": {"name": "x"}}"#)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "old.json uses schema version 2.0, but only 1.x can be read");
+        assert!(matches!(ExportedProject::parse("x.json", "[]"), Err(AggregateError::Parse(..))));
+    }
+}
@@ -0,0 +1,442 @@
+//! CSV exporter.
+//!
+//! Writes one row per package. The set and order of columns is
+//! configurable so spreadsheets only receive the data they need.
+//! Quoting follows RFC 4180. The report metadata, if attached, precedes
+//! the header as `#` comment lines, which spreadsheet imports and most CSV
+//! readers can be told to skip.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::analysis::footprint::install_label;
+
+use super::{ExportReport, PackageEntry};
+
+/// A column that can be included in the CSV output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// Package name
+    Name,
+    /// Version specification
+    Version,
+    /// Dependency type (production, dev, peer, optional)
+    Type,
+    /// Bundle size in bytes
+    BundleSize,
+    /// Gzipped bundle size in bytes
+    GzipSize,
+    /// Number of bundled modules
+    ModuleCount,
+    /// Percentage of exports used by the project
+    Utilization,
+    /// License identifier
+    License,
+    /// Distance from the root package
+    Depth,
+    /// Whether the package is a direct dependency
+    Direct,
+    /// Number of packages depending on this one
+    Dependents,
+    /// Install source (registry, alias, git, file, tarball, workspace)
+    Source,
+    /// Owning teams, separated by spaces
+    Owners,
+    /// Unpacked size in node_modules, in bytes
+    InstallSize,
+    /// Number of files in node_modules
+    InstallFiles,
+    /// Native build and install scripts, separated by spaces
+    InstallScripts,
+    /// Evidence of native bindings, separated by spaces
+    Native,
+    /// Operating system and CPU restrictions
+    Platforms,
+}
+
+impl CsvColumn {
+    /// Every available column, in the order used by `all`.
+    pub const ALL: [CsvColumn; 18] = [
+        CsvColumn::Name,
+        CsvColumn::Version,
+        CsvColumn::Type,
+        CsvColumn::BundleSize,
+        CsvColumn::GzipSize,
+        CsvColumn::ModuleCount,
+        CsvColumn::Utilization,
+        CsvColumn::License,
+        CsvColumn::Depth,
+        CsvColumn::Direct,
+        CsvColumn::Dependents,
+        CsvColumn::Source,
+        CsvColumn::Owners,
+        CsvColumn::InstallSize,
+        CsvColumn::InstallFiles,
+        CsvColumn::InstallScripts,
+        CsvColumn::Native,
+        CsvColumn::Platforms,
+    ];
+
+    /// The columns written when no selection is given.
+    pub const DEFAULT: [CsvColumn; 3] = [CsvColumn::Name, CsvColumn::Version, CsvColumn::Type];
+
+    /// Returns the header name of the column.
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Name => "name",
+            CsvColumn::Version => "version",
+            CsvColumn::Type => "type",
+            CsvColumn::BundleSize => "bundle_size",
+            CsvColumn::GzipSize => "gzip_size",
+            CsvColumn::ModuleCount => "module_count",
+            CsvColumn::Utilization => "utilization",
+            CsvColumn::License => "license",
+            CsvColumn::Depth => "depth",
+            CsvColumn::Direct => "direct",
+            CsvColumn::Dependents => "dependents",
+            CsvColumn::Source => "source",
+            CsvColumn::Owners => "owners",
+            CsvColumn::InstallSize => "install_size",
+            CsvColumn::InstallFiles => "install_files",
+            CsvColumn::InstallScripts => "install_scripts",
+            CsvColumn::Native => "native",
+            CsvColumn::Platforms => "platforms",
+        }
+    }
+
+    /// Parses a comma-separated column selection such as `name,bundle_size`.
+    ///
+    /// The special value `all` selects every column.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::CsvColumn;
+    ///
+    /// let columns = CsvColumn::parse_list("name, bundle_size").unwrap();
+    /// assert_eq!(columns, vec![CsvColumn::Name, CsvColumn::BundleSize]);
+    /// ```
+    pub fn parse_list(list: &str) -> Result<Vec<CsvColumn>, String> {
+        let mut columns = Vec::new();
+        for item in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            if item.eq_ignore_ascii_case("all") {
+                columns.extend_from_slice(&Self::ALL);
+            } else {
+                columns.push(item.parse()?);
+            }
+        }
+        if columns.is_empty() {
+            return Err("no columns selected".to_string());
+        }
+        Ok(columns)
+    }
+
+    /// Returns the cell value of this column for a package.
+    pub(crate) fn value(&self, pkg: &PackageEntry) -> String {
+        match self {
+            CsvColumn::Name => pkg.name.clone(),
+            CsvColumn::Version => pkg.version.clone(),
+            CsvColumn::Type => pkg.dep_type.to_string(),
+            CsvColumn::BundleSize => optional(pkg.bundle_size),
+            CsvColumn::GzipSize => optional(pkg.gzip_size),
+            CsvColumn::ModuleCount => optional(pkg.module_count),
+            CsvColumn::Utilization => pkg
+                .utilization
+                .map(|pct| format!("{:.1}", pct))
+                .unwrap_or_default(),
+            CsvColumn::Native => pkg
+                .platform
+                .as_ref()
+                .map(|p| p.bindings.join(" "))
+                .unwrap_or_default(),
+            CsvColumn::Platforms => pkg.platform.as_ref().map(|p| p.platforms()).unwrap_or_default(),
+            CsvColumn::License => pkg.license.clone().unwrap_or_default(),
+            CsvColumn::Depth => pkg.depth.to_string(),
+            CsvColumn::Direct => pkg.is_direct().to_string(),
+            CsvColumn::Dependents => pkg.dependents.to_string(),
+            CsvColumn::Source => pkg.source.label().to_string(),
+            CsvColumn::Owners => pkg.owners.join(" "),
+            CsvColumn::InstallSize => optional(pkg.install.as_ref().map(|i| i.bytes)),
+            CsvColumn::InstallFiles => optional(pkg.install.as_ref().map(|i| i.files)),
+            CsvColumn::InstallScripts => pkg
+                .install
+                .as_ref()
+                .map(|i| install_label(i).replace(", ", " "))
+                .filter(|label| label != "-")
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for CsvColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL
+            .iter()
+            .find(|c| c.header() == normalized)
+            .copied()
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|c| c.header()).collect();
+                format!("unknown column '{}' (expected: {}, all)", s, known.join(", "))
+            })
+    }
+}
+
+/// Renders an `ExportReport` as CSV.
+#[derive(Debug, Clone)]
+pub struct CsvExporter {
+    /// Columns to write, in order
+    columns: Vec<CsvColumn>,
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvExporter {
+    /// Creates an exporter writing the default columns.
+    pub fn new() -> Self {
+        Self {
+            columns: CsvColumn::DEFAULT.to_vec(),
+        }
+    }
+
+    /// Sets the columns to write.
+    pub fn with_columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Renders the report as CSV with a header row.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{CsvExporter, ExportReport};
+    ///
+    /// let csv = CsvExporter::new().export(&ExportReport::default());
+    /// assert_eq!(csv, "name,version,type\n");
+    /// ```
+    pub fn export(&self, report: &ExportReport) -> String {
+        let mut out = metadata_comments(report);
+
+        let header: Vec<&str> = self.columns.iter().map(|c| c.header()).collect();
+        out.push_str(&header.join(","));
+        out.push('\n');
+
+        for pkg in &report.packages {
+            let row: Vec<String> = self
+                .columns
+                .iter()
+                .map(|c| escape_field(&c.value(pkg)))
+                .collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Renders the version requirement matrix instead of the packages: one
+    /// row per requirement, with the package, its requester, the requested
+    /// range and the version installed for the requester.
+    ///
+    /// The column selection does not apply.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{CsvExporter, ExportReport};
+    ///
+    /// let csv = CsvExporter::new().export_requirements(&ExportReport::default());
+    /// assert_eq!(csv, "package,required_by,range,resolved\n");
+    /// ```
+    pub fn export_requirements(&self, report: &ExportReport) -> String {
+        let mut out = metadata_comments(report);
+        out.push_str("package,required_by,range,resolved\n");
+        for requirement in &report.requirements {
+            let row = [
+                &requirement.package,
+                &requirement.required_by,
+                &requirement.range,
+                requirement.resolved.as_deref().unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| escape_field(field)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Returns the report metadata as `# label: value` lines, or nothing if
+/// none is attached.
+fn metadata_comments(report: &ExportReport) -> String {
+    let Some(metadata) = &report.metadata else {
+        return String::new();
+    };
+    metadata
+        .fields()
+        .iter()
+        .map(|(label, value)| format!("# {}: {}\n", label, value.replace(['\n', '\r'], " ")))
+        .collect()
+}
+
+/// Formats an optional number, leaving the cell empty when unknown.
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quotes a field if it contains a delimiter, quote or line break.
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::footprint::InstallFootprint;
+    use crate::analysis::native::PlatformSupport;
+    use crate::export::ReportMetadata;
+    use crate::graph::{DependencyEdge, DependencyGraph, DependencyType, GraphRoot};
+    use std::collections::HashMap;
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+        graph.add_dependency("typescript", "^5.0.0", DependencyType::Development);
+        graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+        graph.add_edge("react", "scheduler");
+
+        let mut sizes = HashMap::new();
+        sizes.insert("react".to_string(), (46080_u64, 3_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        let mut report = ExportReport::from_graph("app", "1.0.0", &graph);
+        report.packages[0].gzip_size = Some(14000);
+        report.packages[0].license = Some("MIT".to_string());
+        report.packages[0].utilization = Some(12.345);
+        report.packages[0].owners = vec!["@web".to_string(), "@platform".to_string()];
+        report.packages[0].install = Some(InstallFootprint {
+            bytes: 320000,
+            files: 41,
+            native: true,
+            install_scripts: vec!["postinstall".to_string()],
+        });
+        report.packages[0].platform = Some(PlatformSupport {
+            bindings: vec!["binding.gyp".to_string()],
+            os: vec!["darwin".to_string()],
+            cpu: Vec::new(),
+        });
+        report
+    }
+
+    #[test]
+    fn test_default_columns() {
+        let csv = CsvExporter::new().export(&create_test_report());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "name,version,type");
+        assert_eq!(lines[1], "react,^18.2.0,production");
+        assert_eq!(lines[3], "typescript,^5.0.0,dev");
+    }
+
+    #[test]
+    fn test_all_columns() {
+        let csv = CsvExporter::new()
+            .with_columns(CsvColumn::ALL.to_vec())
+            .export(&create_test_report());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "name,version,type,bundle_size,gzip_size,module_count,utilization,license,depth,direct,dependents,source,owners,install_size,install_files,install_scripts,native,platforms"
+        );
+        assert_eq!(
+            lines[1],
+            "react,^18.2.0,production,46080,14000,3,12.3,MIT,0,true,0,registry,@web @platform,320000,41,native postinstall,binding.gyp,os: darwin"
+        );
+        assert_eq!(lines[2], "scheduler,0.23.0,production,,,,,,1,false,1,registry,,,,,,");
+    }
+
+    #[test]
+    fn test_requirement_matrix() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+        graph.add_dependency_with_depth("loose-envify", "1.4.0", DependencyType::Production, 1);
+        graph.add_edge_with_metadata(
+            "react",
+            "loose-envify",
+            DependencyEdge::new().with_requested(">=1.1.0 <2").with_resolved("1.4.0"),
+        );
+        graph.get_node_mut("react").unwrap().resolved_version = Some("18.2.0".to_string());
+        graph.set_root(GraphRoot::new(["react"]).with_name("app"));
+
+        let csv = CsvExporter::new().export_requirements(&ExportReport::from_graph("app", "1.0.0", &graph));
+        assert_eq!(
+            csv,
+            "package,required_by,range,resolved\nloose-envify,react,>=1.1.0 <2,1.4.0\nreact,app,^18.2.0,18.2.0\n"
+        );
+    }
+
+    #[test]
+    fn test_metadata_comments() {
+        let metadata = ReportMetadata::new("apps/web").with_stats(Some("dist/stats.json".to_string()));
+        let csv = CsvExporter::new().export(&create_test_report().with_metadata(metadata));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], format!("# codescope: {}", env!("CARGO_PKG_VERSION")));
+        assert!(lines.contains(&"# path: apps/web"));
+        assert!(lines.contains(&"# stats: dist/stats.json"));
+        assert_eq!(lines.iter().find(|line| !line.starts_with('#')), Some(&"name,version,type"));
+    }
+
+    #[test]
+    fn test_column_order_is_respected() {
+        let columns = CsvColumn::parse_list("dependents,name").unwrap();
+        let csv = CsvExporter::new()
+            .with_columns(columns)
+            .export(&create_test_report());
+        assert!(csv.starts_with("dependents,name\n0,react\n1,scheduler\n"));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            CsvColumn::parse_list("name,Bundle-Size, gzip_size").unwrap(),
+            vec![CsvColumn::Name, CsvColumn::BundleSize, CsvColumn::GzipSize]
+        );
+        assert_eq!(CsvColumn::parse_list("all").unwrap().len(), CsvColumn::ALL.len());
+        assert!(CsvColumn::parse_list("name,bogus").is_err());
+        assert!(CsvColumn::parse_list(" , ").is_err());
+    }
+
+    #[test]
+    fn test_escape_field() {
+        assert_eq!(escape_field("plain"), "plain");
+        assert_eq!(escape_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field(">=1.0.0 <2.0.0"), ">=1.0.0 <2.0.0");
+    }
+
+    #[test]
+    fn test_export_escapes_values() {
+        let mut report = create_test_report();
+        report.packages[0].license = Some("(MIT OR Apache-2.0), see LICENSE".to_string());
+        let csv = CsvExporter::new()
+            .with_columns(vec![CsvColumn::Name, CsvColumn::License])
+            .export(&report);
+        assert!(csv.contains("react,\"(MIT OR Apache-2.0), see LICENSE\"\n"));
+    }
+}
@@ -0,0 +1,214 @@
+//! D3 graph exporter.
+//!
+//! Emits the node-link JSON that `d3-force` and most graph libraries
+//! consume directly:
+//!
+//! ```json
+//! {
+//!   "nodes": [{ "id": "my-app", "type": "root", ... }, { "id": "react", "size": 6400, ... }],
+//!   "links": [{ "source": "my-app", "target": "react", "requested": "^18.2.0" }]
+//! }
+//! ```
+//!
+//! The report metadata, if attached, is written under `metadata` with
+//! the fields of the JSON export's.
+//!
+//! Links refer to nodes by `id`, so use `forceLink().id(d => d.id)`. The
+//! project itself is the root node, linked to every direct dependency, so
+//! the layout is connected.
+
+use serde::Serialize;
+
+use super::{ExportReport, PackageEntry, ReportMetadata};
+
+#[derive(Debug, Serialize)]
+struct D3Graph<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<&'a ReportMetadata>,
+    nodes: Vec<D3Node<'a>>,
+    links: Vec<D3Link<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct D3Node<'a> {
+    id: &'a str,
+    version: &'a str,
+    #[serde(rename = "type")]
+    node_type: String,
+    /// Distance from the project: 0 for the root, 1 for direct dependencies
+    depth: usize,
+    size: Option<u64>,
+    dependents: usize,
+    in_cycle: bool,
+    has_conflict: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct D3Link<'a> {
+    source: &'a str,
+    target: &'a str,
+    /// Version range the source requested, where known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requested: Option<&'a str>,
+    /// Version installed for the source, where known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<&'a str>,
+}
+
+impl<'a> From<&'a PackageEntry> for D3Node<'a> {
+    fn from(pkg: &'a PackageEntry) -> Self {
+        Self {
+            id: &pkg.name,
+            version: &pkg.version,
+            node_type: pkg.dep_type.to_string(),
+            depth: pkg.depth + 1,
+            size: pkg.bundle_size,
+            dependents: pkg.dependents,
+            in_cycle: pkg.in_cycle,
+            has_conflict: pkg.has_conflict,
+        }
+    }
+}
+
+/// Renders an `ExportReport` as D3 node-link JSON.
+#[derive(Debug, Clone, Default)]
+pub struct D3Exporter {
+    /// Whether to pretty-print the output
+    pretty: bool,
+}
+
+impl D3Exporter {
+    /// Creates an exporter producing pretty-printed JSON.
+    pub fn new() -> Self {
+        Self { pretty: true }
+    }
+
+    /// Sets whether the output is pretty-printed.
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Renders the report as a node-link graph.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{D3Exporter, ExportReport};
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    ///
+    /// let json = D3Exporter::new().export(&ExportReport::from_graph("my-app", "1.0.0", &graph));
+    /// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(value["nodes"][1]["id"], "react");
+    /// assert_eq!(value["links"][0]["source"], "my-app");
+    /// ```
+    pub fn export(&self, report: &ExportReport) -> String {
+        let total_size = report.total_bundle_size();
+        let mut nodes = vec![D3Node {
+            id: &report.project_name,
+            version: &report.project_version,
+            node_type: "root".to_string(),
+            depth: 0,
+            size: (total_size > 0).then_some(total_size),
+            dependents: 0,
+            in_cycle: false,
+            has_conflict: false,
+        }];
+        nodes.extend(report.packages.iter().map(D3Node::from));
+
+        let mut links: Vec<D3Link> = report
+            .packages
+            .iter()
+            .filter(|pkg| pkg.is_direct())
+            .map(|pkg| D3Link {
+                source: &report.project_name,
+                target: &pkg.name,
+                requested: Some(&pkg.version),
+                resolved: None,
+            })
+            .collect();
+        // Dependencies outside the report (e.g. after narrowing it to an
+        // owner) have no node to link to.
+        links.extend(report.packages.iter().flat_map(|pkg| {
+            pkg.dependencies
+                .iter()
+                .filter(|dep| report.packages.iter().any(|p| &p.name == *dep))
+                .map(move |dep| {
+                    let requested = pkg.requested.get(dep);
+                    D3Link {
+                        source: &pkg.name,
+                        target: dep,
+                        requested: requested.and_then(|r| r.range.as_deref()),
+                        resolved: requested.and_then(|r| r.resolved.as_deref()),
+                    }
+                })
+        }));
+
+        let graph = D3Graph {
+            metadata: report.metadata.as_ref(),
+            nodes,
+            links,
+        };
+        let result = if self.pretty {
+            serde_json::to_string_pretty(&graph)
+        } else {
+            serde_json::to_string(&graph)
+        };
+        result.expect("D3 graph is always serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyEdge, DependencyGraph, DependencyType};
+    use serde_json::Value;
+
+    #[test]
+    fn test_nodes_and_links() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+        graph.add_dependency_with_depth("loose-envify", "1.4.0", DependencyType::Production, 1);
+        let edge = DependencyEdge::new().with_requested("^1.1.0").with_resolved("1.4.0");
+        graph.add_edge_with_metadata("react", "loose-envify", edge);
+
+        let mut report = ExportReport::from_graph("app", "1.0.0", &graph);
+        report.packages[2].bundle_size = Some(6400);
+        let json = D3Exporter::new().with_pretty(false).export(&report);
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        let nodes: Vec<(&str, &str, u64)> = value["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| (n["id"].as_str().unwrap(), n["type"].as_str().unwrap(), n["depth"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("app", "root", 0),
+                ("jest", "dev", 1),
+                ("loose-envify", "production", 2),
+                ("react", "production", 1),
+            ]
+        );
+        assert_eq!(value["nodes"][0]["size"], 6400);
+        assert_eq!(value["nodes"][3]["size"], 6400);
+
+        let links: Vec<(&str, &str)> = value["links"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|l| (l["source"].as_str().unwrap(), l["target"].as_str().unwrap()))
+            .collect();
+        assert_eq!(links, vec![("app", "jest"), ("app", "react"), ("react", "loose-envify")]);
+        assert_eq!(value["links"][1]["requested"], "18.2.0");
+        assert_eq!(value["links"][2]["requested"], "^1.1.0");
+        assert_eq!(value["links"][2]["resolved"], "1.4.0");
+        assert!(value["links"][0].get("resolved").is_none());
+    }
+}
@@ -0,0 +1,203 @@
+//! Crash-safe writing of report files.
+//!
+//! Reports are consumed by other jobs, which cannot tell a truncated file
+//! from a complete one. [`write_atomic`] therefore writes to a temporary
+//! file next to the target, flushes it to disk and only then renames it
+//! over the target, so readers see either the previous file or the whole
+//! new one. A run that fails midway leaves no partial report behind.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Output argument that selects stdout instead of a file
+pub const STDOUT: &str = "-";
+
+/// Returns the file an `--output` argument names, or `None` for stdout:
+/// no argument, or `-`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::export::file::output_file;
+///
+/// assert_eq!(output_file(Some("deps.md")), Some("deps.md"));
+/// assert_eq!(output_file(Some("-")), None);
+/// assert_eq!(output_file(None), None);
+/// ```
+pub fn output_file(output: Option<&str>) -> Option<&str> {
+    output.filter(|output| *output != STDOUT)
+}
+
+/// Fails with [`io::ErrorKind::AlreadyExists`] if `path` exists, so
+/// several files can be checked before any of them is written.
+pub fn check_overwrite(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        return Err(already_exists());
+    }
+    Ok(())
+}
+
+fn already_exists() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "the file already exists; pass --force to overwrite it",
+    )
+}
+
+/// Writes `content` to `path` through a temporary file, replacing an
+/// existing file only if `overwrite` is set.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::export::file::write_atomic;
+///
+/// let path = std::env::temp_dir().join(format!("codescope-doc-{}.md", std::process::id()));
+/// write_atomic(&path, b"# Report\n", false).unwrap();
+/// assert!(write_atomic(&path, b"# Other\n", false).is_err());
+/// write_atomic(&path, b"# Other\n", true).unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Other\n");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn write_atomic(path: &Path, content: &[u8], overwrite: bool) -> io::Result<()> {
+    if !overwrite {
+        check_overwrite(path)?;
+    }
+    let temp = TempFile::create(path)?;
+    {
+        let mut file = &temp.file;
+        file.write_all(content)?;
+        file.sync_all()?;
+    }
+    if overwrite {
+        temp.persist(path)
+    } else {
+        temp.persist_new(path)
+    }
+}
+
+/// A temporary file removed on drop unless persisted.
+struct TempFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl TempFile {
+    /// Creates a new file in the directory of `target`, where renaming it
+    /// over `target` cannot cross file systems.
+    fn create(target: &Path) -> io::Result<Self> {
+        let dir = match target.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let name = target
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a file", target.display())))?
+            .to_string_lossy();
+        let mut attempt = 0;
+        loop {
+            let path = dir.join(format!(".{}.{}-{}.tmp", name, std::process::id(), attempt));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok(Self { path, file }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Renames the file to `target`.
+    fn persist(self, target: &Path) -> io::Result<()> {
+        fs::rename(&self.path, target)
+        // On failure, drop removes the temporary file
+    }
+
+    /// Moves the file to `target` unless `target` exists, even if it was
+    /// created after [`check_overwrite`]: a hard link is never made over an
+    /// existing file.
+    fn persist_new(self, target: &Path) -> io::Result<()> {
+        match fs::hard_link(&self.path, target) {
+            // Drop removes the temporary name
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(already_exists()),
+            // File systems without hard links fall back to the earlier check
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+                check_overwrite(target)?;
+                self.persist(target)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // Fails harmlessly once the file was renamed
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codescope-file-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = temp_dir("write");
+        let path = dir.join("report.json");
+        write_atomic(&path, b"{}", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+
+        let err = write_atomic(&path, b"[]", false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}", "refused writes keep the file");
+
+        write_atomic(&path, b"[]", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+        assert_eq!(entries(&dir), vec!["report.json"], "no temporary files are left");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_write_leaves_nothing() {
+        let dir = temp_dir("failed");
+        // Renaming a file over a directory fails after the content is written
+        let path = dir.join("report.md");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep"), "").unwrap();
+        assert!(write_atomic(&path, b"# Report", true).is_err());
+        assert_eq!(entries(&dir), vec!["report.md"]);
+        assert!(write_atomic(&dir.join("missing/report.md"), b"# Report", false).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_persist_new_never_replaces() {
+        let dir = temp_dir("race");
+        let path = dir.join("report.json");
+        let temp = TempFile::create(&path).unwrap();
+        // Created after the overwrite check, before the move
+        fs::write(&path, "{}").unwrap();
+        let err = temp.persist_new(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+        assert_eq!(entries(&dir), vec!["report.json"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
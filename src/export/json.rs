@@ -0,0 +1,832 @@
+//! JSON exporter.
+//!
+//! Every document is stamped with a `schema_version` and described by a
+//! JSON Schema available from [`schema`] (and `codescope export --schema`).
+//!
+//! # Compatibility
+//!
+//! The format evolves in a backward-compatible way within a major schema
+//! version: fields are only ever added, never renamed, removed or retyped,
+//! and new fields are optional in the schema. Consumers should ignore
+//! fields they do not recognize. Breaking changes bump the major version.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::analysis::footprint::InstallFootprint;
+use crate::analysis::native::PlatformSupport;
+use crate::analysis::warnings::AnalysisWarning;
+use crate::git::Commit;
+
+use std::collections::BTreeMap;
+
+use super::{ExportReport, PackageEntry, ReportMetadata, RequestedVersion, RequirementEntry};
+
+/// Version of the JSON export format (`major.minor`).
+///
+/// The minor version increases when fields are added; the major version
+/// increases on breaking changes.
+pub const SCHEMA_VERSION: &str = "1.13";
+
+/// Canonical URL identifying the schema document.
+const SCHEMA_ID: &str = "https://github.com/zach-fau/codescope/schemas/export-v1.json";
+
+/// Top-level JSON export document.
+#[derive(Debug, Serialize)]
+struct JsonDocument<'a> {
+    schema_version: &'static str,
+    metadata: Option<&'a ReportMetadata>,
+    project: JsonProject<'a>,
+    summary: JsonSummary,
+    packages: Vec<JsonPackage<'a>>,
+    cycles: Vec<JsonCycle<'a>>,
+    conflicts: Vec<JsonConflict<'a>>,
+    requirements: &'a [RequirementEntry],
+    groups: Vec<JsonGroup<'a>>,
+    owners: Vec<JsonOwner<'a>>,
+    warnings: &'a [AnalysisWarning],
+}
+
+#[derive(Debug, Serialize)]
+struct JsonProject<'a> {
+    name: &'a str,
+    version: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSummary {
+    total_packages: usize,
+    total_bundle_size: u64,
+    cycle_count: usize,
+    conflict_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPackage<'a> {
+    name: &'a str,
+    version: &'a str,
+    package: &'a str,
+    source: &'static str,
+    #[serde(rename = "type")]
+    dep_type: String,
+    depth: usize,
+    direct: bool,
+    bundle_size: Option<u64>,
+    gzip_size: Option<u64>,
+    module_count: Option<usize>,
+    utilization: Option<f64>,
+    license: Option<&'a str>,
+    dependents: usize,
+    requested: &'a BTreeMap<String, RequestedVersion>,
+    in_cycle: bool,
+    has_conflict: bool,
+    owners: &'a [String],
+    introduced: Option<&'a Commit>,
+    install: Option<&'a InstallFootprint>,
+    platform: Option<&'a PlatformSupport>,
+    note: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonCycle<'a> {
+    packages: &'a [String],
+    path: String,
+    suggested_break: Option<JsonCycleBreak<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonCycleBreak<'a> {
+    from: &'a str,
+    to: &'a str,
+    reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonConflict<'a> {
+    package: &'a str,
+    requirements: Vec<JsonRequirement<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRequirement<'a> {
+    version: &'a str,
+    required_by: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonGroup<'a> {
+    name: &'a str,
+    packages: &'a [String],
+    count: usize,
+    bundle_size: Option<u64>,
+    max_size_kb: Option<u64>,
+    max_count: Option<usize>,
+    over_budget: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonOwner<'a> {
+    owner: &'a str,
+    packages: &'a [String],
+    count: usize,
+    bundle_size: u64,
+    potential_savings: u64,
+    cycle_packages: usize,
+    conflict_packages: usize,
+}
+
+impl<'a> From<&'a PackageEntry> for JsonPackage<'a> {
+    fn from(pkg: &'a PackageEntry) -> Self {
+        Self {
+            name: &pkg.name,
+            version: &pkg.version,
+            package: &pkg.package,
+            source: pkg.source.label(),
+            dep_type: pkg.dep_type.to_string(),
+            depth: pkg.depth,
+            direct: pkg.is_direct(),
+            bundle_size: pkg.bundle_size,
+            gzip_size: pkg.gzip_size,
+            module_count: pkg.module_count,
+            utilization: pkg.utilization,
+            license: pkg.license.as_deref(),
+            dependents: pkg.dependents,
+            requested: &pkg.requested,
+            in_cycle: pkg.in_cycle,
+            has_conflict: pkg.has_conflict,
+            owners: &pkg.owners,
+            introduced: pkg.introduced.as_ref(),
+            install: pkg.install.as_ref(),
+            platform: pkg.platform.as_ref(),
+            note: pkg.note.as_deref(),
+        }
+    }
+}
+
+/// Renders an `ExportReport` as a versioned JSON document.
+#[derive(Debug, Clone, Default)]
+pub struct JsonExporter {
+    /// Whether to pretty-print the output
+    pretty: bool,
+}
+
+impl JsonExporter {
+    /// Creates an exporter producing pretty-printed JSON.
+    pub fn new() -> Self {
+        Self { pretty: true }
+    }
+
+    /// Sets whether the output is pretty-printed.
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Renders the report as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{ExportReport, JsonExporter, SCHEMA_VERSION};
+    ///
+    /// let json = JsonExporter::new().export(&ExportReport::default());
+    /// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    /// ```
+    pub fn export(&self, report: &ExportReport) -> String {
+        let document = JsonDocument {
+            schema_version: SCHEMA_VERSION,
+            metadata: report.metadata.as_ref(),
+            project: JsonProject {
+                name: &report.project_name,
+                version: &report.project_version,
+            },
+            summary: JsonSummary {
+                total_packages: report.packages.len(),
+                total_bundle_size: report.total_bundle_size(),
+                cycle_count: report.cycles.len(),
+                conflict_count: report.conflicts.len(),
+            },
+            packages: report.packages.iter().map(JsonPackage::from).collect(),
+            cycles: report
+                .cycles
+                .iter()
+                .map(|c| JsonCycle {
+                    packages: &c.nodes,
+                    path: c.cycle_path(),
+                    suggested_break: c.suggested_break.as_ref().map(|fix| JsonCycleBreak {
+                        from: &fix.from,
+                        to: &fix.to,
+                        reason: fix.reason.label(),
+                    }),
+                })
+                .collect(),
+            conflicts: report
+                .conflicts
+                .iter()
+                .map(|c| JsonConflict {
+                    package: &c.package_name,
+                    requirements: c
+                        .requirements
+                        .iter()
+                        .map(|r| JsonRequirement {
+                            version: &r.version,
+                            required_by: &r.required_by,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            requirements: &report.requirements,
+            groups: report
+                .groups
+                .iter()
+                .map(|g| JsonGroup {
+                    name: &g.name,
+                    packages: &g.packages,
+                    count: g.count(),
+                    bundle_size: g.has_sizes().then_some(g.bundle_size),
+                    max_size_kb: g.max_size_kb,
+                    max_count: g.max_count,
+                    over_budget: g.over_budget(),
+                })
+                .collect(),
+            owners: report
+                .owners
+                .iter()
+                .map(|o| JsonOwner {
+                    owner: &o.owner,
+                    packages: &o.packages,
+                    count: o.count(),
+                    bundle_size: o.bundle_size,
+                    potential_savings: o.potential_savings,
+                    cycle_packages: o.cycle_packages,
+                    conflict_packages: o.conflict_packages,
+                })
+                .collect(),
+            warnings: &report.warnings,
+        };
+
+        let mut out = if self.pretty {
+            serde_json::to_string_pretty(&document)
+        } else {
+            serde_json::to_string(&document)
+        }
+        .expect("export document is always serializable");
+        out.push('\n');
+        out
+    }
+}
+
+/// Returns the JSON Schema (draft 2020-12) describing the export format.
+pub fn schema() -> Value {
+    let nullable_int = json!({ "type": ["integer", "null"], "minimum": 0 });
+    let introduced = json!({
+        "description": "Commit that added a direct dependency to package.json, or null if unknown (since 1.5)",
+        "type": ["object", "null"],
+        "properties": {
+            "commit": { "type": "string" },
+            "author": { "type": "string" },
+            "date": { "type": "string", "format": "date" },
+            "summary": { "type": "string" }
+        }
+    });
+    let install = json!({
+        "description": "Install cost from node_modules, or null if not measured (since 1.6)",
+        "type": ["object", "null"],
+        "properties": {
+            "bytes": { "type": "integer", "minimum": 0, "description": "Unpacked size in bytes" },
+            "files": { "type": "integer", "minimum": 0 },
+            "native": { "type": "boolean", "description": "Builds or downloads a native addon" },
+            "install_scripts": {
+                "type": "array",
+                "items": { "enum": ["preinstall", "install", "postinstall"] }
+            }
+        }
+    });
+    let string_list = json!({ "type": "array", "items": { "type": "string" } });
+    let platform = json!({
+        "description": "Native bindings and os/cpu restrictions, or null if not detected (since 1.7)",
+        "type": ["object", "null"],
+        "properties": {
+            "bindings": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "binding.gyp, gypfile, napi, native helper dependencies and .node files"
+            },
+            "os": string_list,
+            "cpu": string_list
+        }
+    });
+
+    let packages = json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "required": ["name", "version", "type", "depth", "direct", "dependents", "in_cycle", "has_conflict"],
+            "properties": {
+                "name": { "type": "string" },
+                "version": { "type": "string" },
+                "package": {
+                    "type": "string",
+                    "description": "Installed package name; differs from name for npm: aliases (since 1.1)"
+                },
+                "source": {
+                    "enum": ["registry", "alias", "git", "file", "tarball", "workspace"],
+                    "description": "Where the package is installed from (since 1.1)"
+                },
+                "type": { "enum": ["production", "dev", "peer", "optional"] },
+                "depth": { "type": "integer", "minimum": 0 },
+                "direct": { "type": "boolean" },
+                "bundle_size": nullable_int,
+                "gzip_size": nullable_int,
+                "module_count": nullable_int,
+                "utilization": { "type": ["number", "null"], "minimum": 0, "maximum": 100 },
+                "license": { "type": ["string", "null"] },
+                "dependents": { "type": "integer", "minimum": 0 },
+                "requested": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "range": { "type": ["string", "null"] },
+                            "resolved": { "type": ["string", "null"] }
+                        }
+                    },
+                    "description": "Version range requested and version installed per dependency, where the lockfile records them (since 1.9)"
+                },
+                "in_cycle": { "type": "boolean" },
+                "has_conflict": { "type": "boolean" },
+                "owners": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Owning teams from .codescoperc.json (since 1.4)"
+                },
+                "introduced": introduced,
+                "install": install,
+                "platform": platform,
+                "note": {
+                    "type": ["string", "null"],
+                    "description": "The team's note on the package from .codescope/notes.json, or null if none (since 1.12)"
+                }
+            }
+        }
+    });
+    let requirements = json!({
+        "description": "Every version requirement, conflicting or not, sorted by package then requester (since 1.10)",
+        "type": "array",
+        "items": {
+            "type": "object",
+            "required": ["package", "required_by", "range"],
+            "properties": {
+                "package": { "type": "string" },
+                "required_by": {
+                    "type": "string",
+                    "description": "Requiring package, or the project for direct dependencies"
+                },
+                "range": { "type": "string" },
+                "resolved": {
+                    "type": ["string", "null"],
+                    "description": "Version installed for the requester, or null if unknown"
+                }
+            }
+        }
+    });
+    let metadata = json!({
+        "description": "How and from what the report was produced, or null if not recorded (since 1.11)",
+        "type": ["object", "null"],
+        "properties": {
+            "tool_version": { "type": "string", "description": "codescope version that produced the report" },
+            "generated_at": { "type": "string", "format": "date-time" },
+            "path": { "type": "string", "description": "Analyzed project directory" },
+            "commit": { "type": ["string", "null"], "description": "Git commit the inputs were read from" },
+            "lockfile": { "type": ["string", "null"], "description": "File name of the project's lockfile" },
+            "stats": { "type": ["string", "null"], "description": "Stats file the bundle sizes were read from" },
+            "focus": {
+                "type": ["string", "null"],
+                "description": "Part of the project the report was narrowed to, or null for all of it (since 1.13)"
+            }
+        }
+    });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "CodeScope dependency report",
+        "description": "Dependency analysis exported by `codescope export --format json`.",
+        "type": "object",
+        "required": ["schema_version", "project", "summary", "packages", "cycles", "conflicts"],
+        "properties": {
+            "schema_version": {
+                "type": "string",
+                "description": "Format version (major.minor). Minor bumps only add fields.",
+                "pattern": "^1\\.[0-9]+$"
+            },
+            "metadata": metadata,
+            "project": {
+                "type": "object",
+                "required": ["name", "version"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "version": { "type": "string" }
+                }
+            },
+            "summary": {
+                "type": "object",
+                "required": ["total_packages", "total_bundle_size", "cycle_count", "conflict_count"],
+                "properties": {
+                    "total_packages": { "type": "integer", "minimum": 0 },
+                    "total_bundle_size": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Sum of known bundle sizes in bytes"
+                    },
+                    "cycle_count": { "type": "integer", "minimum": 0 },
+                    "conflict_count": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "packages": packages,
+            "cycles": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["packages", "path"],
+                    "properties": {
+                        "packages": { "type": "array", "items": { "type": "string" } },
+                        "path": { "type": "string" },
+                        "suggested_break": {
+                            "description": "Edge whose removal breaks the cycle, or null if no single edge does (since 1.2)",
+                            "type": ["object", "null"],
+                            "required": ["from", "to", "reason"],
+                            "properties": {
+                                "from": { "type": "string" },
+                                "to": { "type": "string" },
+                                "reason": { "enum": ["non_runtime", "back_edge", "lightest"] }
+                            }
+                        }
+                    }
+                }
+            },
+            "conflicts": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["package", "requirements"],
+                    "properties": {
+                        "package": { "type": "string" },
+                        "requirements": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "required": ["version", "required_by"],
+                                "properties": {
+                                    "version": { "type": "string" },
+                                    "required_by": { "type": "string" }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "requirements": requirements,
+            "groups": {
+                "description": "Package groups from .codescoperc.json (since 1.3)",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["name", "packages", "count", "over_budget"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "packages": { "type": "array", "items": { "type": "string" } },
+                        "count": { "type": "integer", "minimum": 0 },
+                        "bundle_size": {
+                            "type": ["integer", "null"],
+                            "minimum": 0,
+                            "description": "Sum of known bundle sizes in bytes, or null if none is known"
+                        },
+                        "max_size_kb": nullable_int,
+                        "max_count": nullable_int,
+                        "over_budget": { "type": "boolean" }
+                    }
+                }
+            },
+            "owners": {
+                "description": "Per-owner breakdown from the owners lines of .codescoperc.json (since 1.4)",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["owner", "packages", "count", "bundle_size", "potential_savings"],
+                    "properties": {
+                        "owner": {
+                            "type": "string",
+                            "description": "Team or person, or \"(unowned)\" for packages no line matches"
+                        },
+                        "packages": { "type": "array", "items": { "type": "string" } },
+                        "count": { "type": "integer", "minimum": 0 },
+                        "bundle_size": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Sum of known bundle sizes in bytes"
+                        },
+                        "potential_savings": { "type": "integer", "minimum": 0 },
+                        "cycle_packages": { "type": "integer", "minimum": 0 },
+                        "conflict_packages": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            },
+            "warnings": {
+                "description": "Inputs the analysis went without, so the report may be incomplete (since 1.8)",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["source", "message"],
+                    "properties": {
+                        "source": {
+                            "type": "string",
+                            "description": "File or input the problem is in"
+                        },
+                        "location": {
+                            "type": ["string", "null"],
+                            "description": "Entry within the source that was skipped, if only one was"
+                        },
+                        "message": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+    use std::collections::HashMap;
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "^2.0.0", DependencyType::Development);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        graph.track_version_requirement("b", "^1.0.0", "a");
+        graph.track_version_requirement("b", "^2.0.0", "app");
+
+        let mut sizes = HashMap::new();
+        sizes.insert("a".to_string(), (1024_u64, 2_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        ExportReport::from_graph("app", "1.0.0", &graph)
+    }
+
+    /// Checks that every `required` key of an object schema is present,
+    /// recursing into nested objects and arrays.
+    fn assert_matches_schema(value: &Value, schema: &Value, path: &str) {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required {
+                let key = key.as_str().unwrap();
+                assert!(value.get(key).is_some(), "missing required field {}.{}", path, key);
+            }
+        }
+        if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+            for (key, sub_schema) in props {
+                if let Some(sub_value) = value.get(key) {
+                    assert_matches_schema(sub_value, sub_schema, &format!("{}.{}", path, key));
+                }
+            }
+        }
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for item in array {
+                assert_matches_schema(item, items, &format!("{}[]", path));
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_is_stamped_with_schema_version() {
+        let json = JsonExporter::new().export(&create_test_report());
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], "1.13");
+    }
+
+    #[test]
+    fn test_export_content() {
+        let json = JsonExporter::new().export(&create_test_report());
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["project"]["name"], "app");
+        assert_eq!(value["summary"]["total_packages"], 2);
+        assert_eq!(value["summary"]["total_bundle_size"], 1024);
+        assert_eq!(value["summary"]["cycle_count"], 1);
+        assert_eq!(value["packages"][0]["name"], "a");
+        assert_eq!(value["packages"][0]["type"], "production");
+        assert_eq!(value["packages"][0]["bundle_size"], 1024);
+        assert_eq!(value["packages"][1]["bundle_size"], Value::Null);
+        assert_eq!(value["packages"][1]["dependents"], 1);
+        assert_eq!(value["conflicts"][0]["package"], "b");
+        assert_eq!(value["conflicts"][0]["requirements"][1]["required_by"], "app");
+        assert_eq!(value["requirements"][0]["package"], "b");
+        assert_eq!(value["requirements"][0]["required_by"], "a");
+        assert_eq!(value["requirements"][0]["range"], "^1.0.0");
+        assert_eq!(value["requirements"][0]["resolved"], Value::Null);
+    }
+
+    #[test]
+    fn test_export_matches_schema() {
+        let json = JsonExporter::new().export(&create_test_report());
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+    }
+
+    #[test]
+    fn test_export_groups() {
+        use crate::analysis::groups::GroupSummary;
+
+        let report = create_test_report().with_groups(vec![GroupSummary {
+            name: "core".to_string(),
+            packages: vec!["a".to_string(), "b".to_string()],
+            bundle_size: 1024,
+            sized_packages: 1,
+            max_size_kb: None,
+            max_count: Some(1),
+        }]);
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        let group = &value["groups"][0];
+        assert_eq!(group["count"], 2);
+        assert_eq!(group["bundle_size"], 1024);
+        assert_eq!(group["max_size_kb"], Value::Null);
+        assert_eq!(group["over_budget"], true);
+    }
+
+    #[test]
+    fn test_export_warnings() {
+        use crate::analysis::warnings::AnalysisWarnings;
+        use crate::parser::ParseWarning;
+
+        let mut warnings = AnalysisWarnings::new();
+        warnings.push("stats.json", "Failed to read webpack stats; bundle sizes are unavailable");
+        warnings.extend_from_parse("package-lock.json", &[ParseWarning::new("packages[\"node_modules/a\"]", "skipped")]);
+        let report = create_test_report().with_warnings(&warnings);
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        assert_eq!(value["warnings"][0]["source"], "stats.json");
+        assert_eq!(value["warnings"][0]["location"], Value::Null);
+        assert_eq!(value["warnings"][1]["location"], "packages[\"node_modules/a\"]");
+
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&create_test_report())).unwrap();
+        assert_eq!(value["warnings"], json!([]));
+    }
+
+    #[test]
+    fn test_export_owners() {
+        use crate::analysis::owners::OwnerSummary;
+
+        let report = create_test_report().with_owners(vec![OwnerSummary {
+            owner: "@web".to_string(),
+            packages: vec!["a".to_string()],
+            bundle_size: 2048,
+            potential_savings: 1024,
+            cycle_packages: 1,
+            conflict_packages: 0,
+        }]);
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        let owner = &value["owners"][0];
+        assert_eq!(owner["owner"], "@web");
+        assert_eq!(owner["count"], 1);
+        assert_eq!(owner["potential_savings"], 1024);
+        assert_eq!(value["packages"][0]["owners"], json!([]));
+    }
+
+    #[test]
+    fn test_export_introduced() {
+        let mut report = create_test_report();
+        report.packages[0].introduced = Some(Commit {
+            commit: "1a2b3c4d5e".to_string(),
+            author: "Ada".to_string(),
+            date: "2024-01-02".to_string(),
+            summary: "Add a".to_string(),
+        });
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        let introduced = &value["packages"][0]["introduced"];
+        assert_eq!(introduced["author"], "Ada");
+        assert_eq!(introduced["date"], "2024-01-02");
+        assert_eq!(value["packages"][1]["introduced"], Value::Null);
+    }
+
+    #[test]
+    fn test_export_note() {
+        let mut report = create_test_report();
+        report.packages[0].note = Some("owner: infra".to_string());
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        assert_eq!(value["packages"][0]["note"], "owner: infra");
+        assert_eq!(value["packages"][1]["note"], Value::Null);
+    }
+
+    #[test]
+    fn test_export_install_footprint() {
+        let mut report = create_test_report();
+        report.packages[0].install = Some(InstallFootprint {
+            bytes: 4096,
+            files: 12,
+            native: true,
+            install_scripts: vec!["install".to_string()],
+        });
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        let install = &value["packages"][0]["install"];
+        assert_eq!(install["files"], 12);
+        assert_eq!(install["native"], true);
+        assert_eq!(install["install_scripts"], json!(["install"]));
+        assert_eq!(value["packages"][1]["install"], Value::Null);
+    }
+
+    #[test]
+    fn test_export_platform_support() {
+        let mut report = create_test_report();
+        report.packages[0].platform = Some(PlatformSupport {
+            bindings: vec!["napi".to_string()],
+            os: vec!["linux".to_string()],
+            cpu: vec!["x64".to_string(), "arm64".to_string()],
+        });
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+
+        let platform = &value["packages"][0]["platform"];
+        assert_eq!(platform["bindings"], json!(["napi"]));
+        assert_eq!(platform["cpu"], json!(["x64", "arm64"]));
+        assert_eq!(value["packages"][1]["platform"], Value::Null);
+    }
+
+    #[test]
+    fn test_export_metadata() {
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&create_test_report())).unwrap();
+        assert_eq!(value["metadata"], Value::Null);
+
+        let metadata = ReportMetadata::new("apps/web")
+            .with_commit(Some("1a2b3c4d".to_string()))
+            .with_lockfile(Some("package-lock.json".to_string()))
+            .with_focus(Some("react and its dependencies".to_string()));
+        let report = create_test_report().with_metadata(metadata);
+        let value: Value = serde_json::from_str(&JsonExporter::new().export(&report)).unwrap();
+        assert_matches_schema(&value, &schema(), "$");
+        assert_eq!(value["metadata"]["tool_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(value["metadata"]["path"], "apps/web");
+        assert_eq!(value["metadata"]["commit"], "1a2b3c4d");
+        assert_eq!(value["metadata"]["stats"], Value::Null);
+        assert_eq!(value["metadata"]["focus"], "react and its dependencies");
+    }
+
+    #[test]
+    fn test_schema_covers_every_package_field() {
+        // Every emitted field must be documented so dashboards can rely on it.
+        let json = JsonExporter::new().export(&create_test_report());
+        let value: Value = serde_json::from_str(&json).unwrap();
+        let schema = schema();
+        let documented = schema["properties"]["packages"]["items"]["properties"]
+            .as_object()
+            .unwrap();
+
+        for key in value["packages"][0].as_object().unwrap().keys() {
+            assert!(documented.contains_key(key), "undocumented package field {}", key);
+        }
+    }
+
+    #[test]
+    fn test_v1_required_fields_are_stable() {
+        // Removing or renaming any of these is a breaking change (schema 2.0).
+        let schema = schema();
+        let required: Vec<&str> = schema["properties"]["packages"]["items"]["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        for field in ["name", "version", "type", "depth", "direct", "dependents", "in_cycle", "has_conflict"] {
+            assert!(required.contains(&field), "{} dropped from schema", field);
+        }
+    }
+
+    #[test]
+    fn test_schema_version_matches_pattern() {
+        let schema = schema();
+        assert_eq!(schema["properties"]["schema_version"]["pattern"], "^1\\.[0-9]+$");
+        assert!(SCHEMA_VERSION.starts_with("1."));
+    }
+
+    #[test]
+    fn test_compact_output() {
+        let json = JsonExporter::new()
+            .with_pretty(false)
+            .export(&ExportReport::default());
+        assert_eq!(json.lines().count(), 1);
+    }
+}
@@ -0,0 +1,656 @@
+//! GitHub-flavored markdown exporter.
+//!
+//! Produces a report that can be pasted directly into a pull request
+//! comment: shields-style summary badges, a table of the largest packages,
+//! and the full dependency table folded inside a `<details>` block. Every
+//! package row carries an HTML anchor so other comments can link to it.
+
+use std::fmt::Write;
+
+use super::{ExportReport, PackageEntry};
+use crate::bundle::webpack::format_size;
+
+/// Number of rows in the largest-packages table by default
+const DEFAULT_TOP_N: usize = 10;
+
+/// Bundle size (bytes) above which the size badge turns yellow
+const SIZE_WARNING_THRESHOLD: u64 = 100 * 1024;
+
+/// Bundle size (bytes) above which the size badge turns red
+const SIZE_CRITICAL_THRESHOLD: u64 = 500 * 1024;
+
+/// Renders an `ExportReport` as GitHub-flavored markdown.
+#[derive(Debug, Clone)]
+pub struct MarkdownExporter {
+    /// Number of rows in the largest-packages table
+    top_n: usize,
+    /// Whether the full dependency table is wrapped in `<details>`
+    collapsible: bool,
+    /// Whether to emit shields.io badges
+    badges: bool,
+}
+
+impl Default for MarkdownExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownExporter {
+    /// Creates an exporter with badges, a collapsible table and a top-10 list.
+    pub fn new() -> Self {
+        Self {
+            top_n: DEFAULT_TOP_N,
+            collapsible: true,
+            badges: true,
+        }
+    }
+
+    /// Sets the number of rows in the largest-packages table.
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = top_n;
+        self
+    }
+
+    /// Sets whether the full dependency table is collapsible.
+    pub fn with_collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Sets whether summary badges are emitted.
+    pub fn with_badges(mut self, badges: bool) -> Self {
+        self.badges = badges;
+        self
+    }
+
+    /// Renders the report as a markdown document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{ExportReport, MarkdownExporter};
+    ///
+    /// let report = ExportReport::default();
+    /// let markdown = MarkdownExporter::new().export(&report);
+    /// assert!(markdown.contains("<details>"));
+    /// ```
+    pub fn export(&self, report: &ExportReport) -> String {
+        let mut out = String::new();
+
+        if report.project_version.is_empty() {
+            let _ = writeln!(out, "## Dependency Report: {}", report.project_name);
+        } else {
+            let _ = writeln!(
+                out,
+                "## Dependency Report: {}@{}",
+                report.project_name, report.project_version
+            );
+        }
+        out.push('\n');
+
+        if let Some(metadata) = &report.metadata {
+            let fields: Vec<String> = metadata
+                .fields()
+                .iter()
+                .map(|(label, value)| format!("{} `{}`", label, value.replace('`', "'")))
+                .collect();
+            let _ = writeln!(out, "<sub>{}</sub>", fields.join(" · "));
+            out.push('\n');
+        }
+
+        if self.badges {
+            out.push_str(&self.render_badges(report));
+            out.push_str("\n\n");
+        }
+
+        self.render_largest(&mut out, report);
+        render_groups(&mut out, report);
+        render_owners(&mut out, report);
+        self.render_dependency_table(&mut out, report);
+        render_notes(&mut out, report);
+        render_cycles(&mut out, report);
+        render_conflicts(&mut out, report);
+        render_warnings(&mut out, report);
+
+        out
+    }
+
+    /// Renders the summary badge line.
+    fn render_badges(&self, report: &ExportReport) -> String {
+        let total_size = report.total_bundle_size();
+        let size_color = if total_size >= SIZE_CRITICAL_THRESHOLD {
+            "red"
+        } else if total_size >= SIZE_WARNING_THRESHOLD {
+            "yellow"
+        } else {
+            "brightgreen"
+        };
+
+        let mut badges = vec![
+            badge("total size", &format_size(total_size), size_color),
+            badge("dependencies", &report.packages.len().to_string(), "blue"),
+        ];
+
+        if !report.cycles.is_empty() {
+            badges.push(badge("cycles", &report.cycles.len().to_string(), "red"));
+        }
+        if !report.conflicts.is_empty() {
+            badges.push(badge("conflicts", &report.conflicts.len().to_string(), "orange"));
+        }
+
+        badges.join(" ")
+    }
+
+    /// Renders the largest-packages table, if any package has a known size.
+    fn render_largest(&self, out: &mut String, report: &ExportReport) {
+        let largest = report.largest_packages(self.top_n);
+        if largest.is_empty() {
+            return;
+        }
+
+        let total = report.total_bundle_size();
+        let _ = writeln!(out, "### Top {} largest packages", largest.len());
+        out.push('\n');
+        out.push_str("| # | Package | Version | Size | % of total |\n");
+        out.push_str("|---:|---|---|---:|---:|\n");
+
+        for (i, pkg) in largest.iter().enumerate() {
+            let size = pkg.bundle_size.unwrap_or(0);
+            let percentage = if total > 0 {
+                (size as f64 / total as f64) * 100.0
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                out,
+                "| {} | [{}](#{}) | {} | {} | {:.1}% |",
+                i + 1,
+                escape_cell(&pkg.name),
+                anchor(&pkg.name),
+                escape_cell(&pkg.version),
+                format_size(size),
+                percentage
+            );
+        }
+        out.push('\n');
+    }
+
+    /// Renders the full dependency table with a per-package anchor.
+    fn render_dependency_table(&self, out: &mut String, report: &ExportReport) {
+        let title = format!("All dependencies ({})", report.packages.len());
+
+        if self.collapsible {
+            out.push_str("<details>\n");
+            let _ = writeln!(out, "<summary>{}</summary>", title);
+        } else {
+            let _ = writeln!(out, "### {}", title);
+        }
+        out.push('\n');
+
+        if report.packages.is_empty() {
+            out.push_str("_No dependencies found._\n");
+        } else {
+            out.push_str("| Package | Version | Type | Size | Modules | Flags |\n");
+            out.push_str("|---|---|---|---:|---:|---|\n");
+            for pkg in &report.packages {
+                let _ = writeln!(
+                    out,
+                    "| <a id=\"{}\"></a>{} | {} | {} | {} | {} | {} |",
+                    anchor(&pkg.name),
+                    escape_cell(&pkg.name),
+                    escape_cell(&pkg.version),
+                    pkg.dep_type,
+                    pkg.bundle_size.map(format_size).unwrap_or_else(|| "-".to_string()),
+                    pkg.module_count
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    flags(pkg)
+                );
+            }
+        }
+
+        out.push('\n');
+        if self.collapsible {
+            out.push_str("</details>\n\n");
+        }
+    }
+}
+
+/// Renders the package group table, if groups are configured.
+fn render_groups(out: &mut String, report: &ExportReport) {
+    if report.groups.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "### Groups ({})", report.groups.len());
+    out.push('\n');
+    out.push_str("| Group | Packages | Size | Budget |\n");
+    out.push_str("|---|---:|---:|---|\n");
+
+    for group in &report.groups {
+        let size = if group.has_sizes() {
+            format_size(group.bundle_size)
+        } else {
+            "-".to_string()
+        };
+        let mut budget = Vec::new();
+        if let Some(max) = group.max_size_kb {
+            budget.push(format!("{} KB", max));
+        }
+        if let Some(max) = group.max_count {
+            budget.push(format!("{} packages", max));
+        }
+        let budget = if budget.is_empty() {
+            "-".to_string()
+        } else if group.over_budget() {
+            format!("❌ {}", budget.join(", "))
+        } else {
+            format!("✅ {}", budget.join(", "))
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            escape_cell(&group.name),
+            group.count(),
+            size,
+            budget
+        );
+    }
+    out.push('\n');
+}
+
+/// Renders the per-owner table, if ownership is configured.
+fn render_owners(out: &mut String, report: &ExportReport) {
+    if report.owners.is_empty() {
+        return;
+    }
+
+    out.push_str("### By Owner\n\n");
+    out.push_str("| Owner | Packages | Size | Potential Savings | Violations |\n");
+    out.push_str("|---|---:|---:|---:|---:|\n");
+
+    for owner in &report.owners {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            escape_cell(&owner.owner),
+            owner.count(),
+            format_size(owner.bundle_size),
+            format_size(owner.potential_savings),
+            owner.violations()
+        );
+    }
+    out.push('\n');
+}
+
+/// Renders the team's notes on packages, if any were written.
+fn render_notes(out: &mut String, report: &ExportReport) {
+    let noted: Vec<(&PackageEntry, &str)> = report
+        .packages
+        .iter()
+        .filter_map(|pkg| Some((pkg, pkg.note.as_deref()?)))
+        .collect();
+    if noted.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "### Notes ({})", noted.len());
+    out.push('\n');
+    for (pkg, note) in noted {
+        let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+        let _ = writeln!(out, "- [{}](#{}): {}", pkg.name, anchor(&pkg.name), note);
+    }
+    out.push('\n');
+}
+
+/// Renders the circular dependency section, if any cycles were found.
+fn render_cycles(out: &mut String, report: &ExportReport) {
+    if report.cycles.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "### Circular dependencies ({})", report.cycles.len());
+    out.push('\n');
+    for cycle in &report.cycles {
+        match &cycle.suggested_break {
+            Some(fix) => {
+                let _ = writeln!(
+                    out,
+                    "- `{}` — break `{}` ({})",
+                    cycle.cycle_path(),
+                    fix.edge(),
+                    fix.reason.description()
+                );
+            }
+            None => {
+                let _ = writeln!(out, "- `{}`", cycle.cycle_path());
+            }
+        }
+    }
+    out.push('\n');
+}
+
+/// Renders the version conflict section, if any conflicts were found.
+fn render_conflicts(out: &mut String, report: &ExportReport) {
+    if report.conflicts.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "### Version conflicts ({})", report.conflicts.len());
+    out.push('\n');
+    for conflict in &report.conflicts {
+        let _ = writeln!(
+            out,
+            "- [{}](#{}): {}",
+            conflict.package_name,
+            anchor(&conflict.package_name),
+            conflict
+                .requirements
+                .iter()
+                .map(|r| format!("`{}` (by {})", r.version, r.required_by))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    out.push('\n');
+}
+
+/// Renders the inputs the analysis went without, if there were any.
+fn render_warnings(out: &mut String, report: &ExportReport) {
+    if report.warnings.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "### Warnings ({})", report.warnings.len());
+    out.push('\n');
+    out.push_str("The analysis continued without these inputs, so this report may be incomplete.\n\n");
+    for warning in &report.warnings {
+        match &warning.location {
+            Some(location) => {
+                let _ = writeln!(out, "- `{}` `{}`: {}", warning.source, location, warning.message);
+            }
+            None => {
+                let _ = writeln!(out, "- `{}`: {}", warning.source, warning.message);
+            }
+        }
+    }
+    out.push('\n');
+}
+
+/// Builds a shields.io static badge image.
+fn badge(label: &str, message: &str, color: &str) -> String {
+    format!(
+        "![{}: {}](https://img.shields.io/badge/{}-{}-{})",
+        label,
+        message,
+        shields_escape(label),
+        shields_escape(message),
+        color
+    )
+}
+
+/// Escapes text for use in a shields.io badge path segment.
+///
+/// Shields uses `-` as a separator and `_` as a space, so literal dashes
+/// and underscores are doubled; everything else is percent-encoded.
+fn shields_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '-' => escaped.push_str("--"),
+            '_' => escaped.push_str("__"),
+            ' ' => escaped.push_str("%20"),
+            c if c.is_ascii_alphanumeric() || c == '.' => escaped.push(c),
+            c => {
+                let mut buf = [0u8; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    let _ = write!(escaped, "%{:02X}", byte);
+                }
+            }
+        }
+    }
+    escaped
+}
+
+/// Returns the anchor id used for a package row.
+///
+/// For example: `@types/node` becomes `pkg-types-node`.
+pub fn anchor(package_name: &str) -> String {
+    let segments: Vec<String> = package_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_ascii_lowercase())
+        .collect();
+    format!("pkg-{}", segments.join("-"))
+}
+
+/// Escapes characters that would break a markdown table cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Returns the flag column text for a package row.
+fn flags(pkg: &PackageEntry) -> String {
+    let mut flags = Vec::new();
+    if pkg.in_cycle {
+        flags.push("cycle");
+    }
+    if pkg.has_conflict {
+        flags.push("conflict");
+    }
+    flags.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+    use std::collections::HashMap;
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "^4.17.21", DependencyType::Production);
+        graph.add_dependency("@types/node", "^20.0.0", DependencyType::Development);
+
+        let mut sizes = HashMap::new();
+        sizes.insert("react".to_string(), (45 * 1024_u64, 3_usize));
+        sizes.insert("lodash".to_string(), (70 * 1024_u64, 10_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        ExportReport::from_graph("my-app", "1.0.0", &graph)
+    }
+
+    #[test]
+    fn test_anchor() {
+        assert_eq!(anchor("react"), "pkg-react");
+        assert_eq!(anchor("@types/node"), "pkg-types-node");
+        assert_eq!(anchor("lodash.debounce"), "pkg-lodash-debounce");
+        assert_eq!(anchor("React-DOM"), "pkg-react-dom");
+    }
+
+    #[test]
+    fn test_shields_escape() {
+        assert_eq!(shields_escape("total size"), "total%20size");
+        assert_eq!(shields_escape("a-b_c"), "a--b__c");
+        assert_eq!(shields_escape("1.50 KB"), "1.50%20KB");
+        assert_eq!(shields_escape("a/b"), "a%2Fb");
+    }
+
+    #[test]
+    fn test_export_has_badges() {
+        let markdown = MarkdownExporter::new().export(&create_test_report());
+        assert!(markdown.contains("## Dependency Report: my-app@1.0.0"));
+        assert!(markdown.contains("https://img.shields.io/badge/total%20size-115.00%20KB-yellow"));
+        assert!(markdown.contains("https://img.shields.io/badge/dependencies-3-blue"));
+        assert!(!markdown.contains("badge/cycles"));
+    }
+
+    #[test]
+    fn test_export_without_badges() {
+        let markdown = MarkdownExporter::new()
+            .with_badges(false)
+            .export(&create_test_report());
+        assert!(!markdown.contains("img.shields.io"));
+    }
+
+    #[test]
+    fn test_export_top_packages_table() {
+        let markdown = MarkdownExporter::new().export(&create_test_report());
+        assert!(markdown.contains("### Top 2 largest packages"));
+        let lodash_row = markdown
+            .lines()
+            .find(|l| l.starts_with("| 1 |"))
+            .unwrap();
+        assert!(lodash_row.contains("[lodash](#pkg-lodash)"));
+        assert!(lodash_row.contains("60.9%"));
+    }
+
+    #[test]
+    fn test_export_top_n_limit() {
+        let markdown = MarkdownExporter::new()
+            .with_top_n(1)
+            .export(&create_test_report());
+        assert!(markdown.contains("### Top 1 largest packages"));
+        assert!(!markdown.contains("| 2 |"));
+    }
+
+    #[test]
+    fn test_export_collapsible_table_with_anchors() {
+        let markdown = MarkdownExporter::new().export(&create_test_report());
+        assert!(markdown.contains("<details>\n<summary>All dependencies (3)</summary>\n\n"));
+        assert!(markdown.contains("\n</details>"));
+        assert!(markdown.contains("<a id=\"pkg-types-node\"></a>@types/node"));
+        assert!(markdown.contains("<a id=\"pkg-react\"></a>react | ^18.2.0 | production | 45.00 KB | 3 |"));
+    }
+
+    #[test]
+    fn test_export_not_collapsible() {
+        let markdown = MarkdownExporter::new()
+            .with_collapsible(false)
+            .export(&create_test_report());
+        assert!(!markdown.contains("<details>"));
+        assert!(markdown.contains("### All dependencies (3)"));
+    }
+
+    #[test]
+    fn test_export_without_sizes_skips_top_table() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+        let report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+
+        let markdown = MarkdownExporter::new().export(&report);
+        assert!(!markdown.contains("largest packages"));
+        assert!(markdown.contains("| - | - |"));
+    }
+
+    #[test]
+    fn test_export_cycles_and_conflicts() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "1.0.0", DependencyType::Production);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        graph.track_version_requirement("b", "^1.0.0", "a");
+        graph.track_version_requirement("b", "^2.0.0", "app");
+
+        let report = ExportReport::from_graph("app", "1.0.0", &graph);
+        let markdown = MarkdownExporter::new().export(&report);
+
+        assert!(markdown.contains("badge/cycles-1-red"));
+        assert!(markdown.contains("badge/conflicts-1-orange"));
+        assert!(markdown.contains("### Circular dependencies (1)"));
+        assert!(markdown.contains("- `a -> b -> a` — break `a -> b` (smallest dependency in the cycle)"));
+        assert!(markdown.contains("### Version conflicts (1)"));
+        assert!(markdown.contains("- [b](#pkg-b): `^1.0.0` (by a), `^2.0.0` (by app)"));
+        assert!(markdown.contains("| cycle, conflict |"));
+    }
+
+    #[test]
+    fn test_export_groups() {
+        use crate::analysis::groups::GroupSummary;
+
+        let group = |name: &str, bundle_size: u64, max_size_kb: Option<u64>| GroupSummary {
+            name: name.to_string(),
+            packages: vec!["react".to_string()],
+            bundle_size,
+            sized_packages: usize::from(bundle_size > 0),
+            max_size_kb,
+            max_count: None,
+        };
+        let report = create_test_report().with_groups(vec![
+            group("build-tooling", 0, None),
+            group("ui-framework", 200 * 1024, Some(100)),
+        ]);
+        let markdown = MarkdownExporter::new().export(&report);
+
+        assert!(markdown.contains("### Groups (2)"));
+        assert!(markdown.contains("| build-tooling | 1 | - | - |"));
+        assert!(markdown.contains("| ui-framework | 1 | 200.00 KB | ❌ 100 KB |"));
+        assert!(!MarkdownExporter::new().export(&create_test_report()).contains("### Groups"));
+    }
+
+    #[test]
+    fn test_export_owners() {
+        use crate::analysis::owners::OwnerSummary;
+
+        let report = create_test_report().with_owners(vec![OwnerSummary {
+            owner: "@web".to_string(),
+            packages: vec!["react".to_string(), "react-dom".to_string()],
+            bundle_size: 200 * 1024,
+            potential_savings: 0,
+            cycle_packages: 2,
+            conflict_packages: 1,
+        }]);
+        let markdown = MarkdownExporter::new().export(&report);
+
+        assert!(markdown.contains("### By Owner"));
+        assert!(markdown.contains("| @web | 2 | 200.00 KB | 0 B | 3 |"));
+        assert!(!MarkdownExporter::new().export(&create_test_report()).contains("### By Owner"));
+    }
+
+    #[test]
+    fn test_export_notes() {
+        let mut report = create_test_report();
+        report.packages[0].note = Some("scheduled for removal in Q3,\nowner: infra".to_string());
+        let markdown = MarkdownExporter::new().export(&report);
+        assert!(markdown.contains("### Notes (1)"));
+        let name = &report.packages[0].name;
+        assert!(markdown.contains(&format!(
+            "- [{}](#{}): scheduled for removal in Q3, owner: infra\n",
+            name,
+            anchor(name)
+        )));
+        assert!(!MarkdownExporter::new().export(&create_test_report()).contains("### Notes"));
+    }
+
+    #[test]
+    fn test_export_warnings() {
+        use crate::analysis::warnings::AnalysisWarnings;
+
+        let mut warnings = AnalysisWarnings::new();
+        warnings.push("src/legacy.js", "skipped, stream did not contain valid UTF-8");
+        let markdown = MarkdownExporter::new().export(&create_test_report().with_warnings(&warnings));
+        assert!(markdown.contains("### Warnings (1)"));
+        assert!(markdown.contains("- `src/legacy.js`: skipped, stream did not contain valid UTF-8"));
+        assert!(!MarkdownExporter::new().export(&create_test_report()).contains("### Warnings"));
+    }
+
+    #[test]
+    fn test_export_metadata() {
+        use crate::export::ReportMetadata;
+
+        let metadata = ReportMetadata::new("apps/web").with_commit(Some("1a2b3c4".to_string()));
+        let markdown = MarkdownExporter::new().export(&create_test_report().with_metadata(metadata));
+        let line = markdown.lines().find(|line| line.starts_with("<sub>")).unwrap();
+        assert!(line.starts_with(&format!("<sub>codescope `{}`", env!("CARGO_PKG_VERSION"))));
+        assert!(line.contains(" · path `apps/web` · commit `1a2b3c4`</sub>"), "{}", line);
+        assert!(!MarkdownExporter::new().export(&create_test_report()).contains("<sub>"));
+    }
+
+    #[test]
+    fn test_escape_cell() {
+        assert_eq!(escape_cell("^1.0.0 || ^2.0.0"), "^1.0.0 \\|\\| ^2.0.0");
+    }
+}
@@ -0,0 +1,167 @@
+//! Provenance of an exported report.
+//!
+//! A report read months later, or next to one made by another release,
+//! needs to say how it was made: which codescope version produced it and
+//! when, from which directory and commit, and which lockfile and stats file
+//! the numbers come from. Every export format writes this
+//! [`ReportMetadata`] into its header.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Lockfiles recognized in a project root, in the order package managers
+/// prefer them.
+pub const LOCKFILES: &[&str] = &["npm-shrinkwrap.json", "package-lock.json", "pnpm-lock.yaml", "yarn.lock"];
+
+/// How and from what a report was produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportMetadata {
+    /// Version of codescope that produced the report
+    pub tool_version: String,
+    /// UTC time the report was produced, RFC 3339
+    pub generated_at: String,
+    /// Analyzed project directory, as given on the command line
+    pub path: String,
+    /// Git commit the inputs were read from, if the project is in a
+    /// repository
+    pub commit: Option<String>,
+    /// File name of the project's lockfile, if it has one
+    pub lockfile: Option<String>,
+    /// Stats file the bundle sizes were read from, if any
+    pub stats: Option<String>,
+    /// The part of the project the report was narrowed to, if not all of
+    /// it, e.g. `react and its dependencies`
+    pub focus: Option<String>,
+}
+
+impl ReportMetadata {
+    /// Creates the metadata of a report on `path` produced now by this
+    /// version of codescope.
+    pub fn new(path: impl Into<String>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: format_timestamp(now),
+            path: path.into(),
+            commit: None,
+            lockfile: None,
+            stats: None,
+            focus: None,
+        }
+    }
+
+    /// Sets the git commit the inputs were read from.
+    pub fn with_commit(mut self, commit: Option<String>) -> Self {
+        self.commit = commit;
+        self
+    }
+
+    /// Sets the lockfile name.
+    pub fn with_lockfile(mut self, lockfile: Option<String>) -> Self {
+        self.lockfile = lockfile;
+        self
+    }
+
+    /// Sets the stats file the bundle sizes were read from.
+    pub fn with_stats(mut self, stats: Option<String>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Sets the part of the project the report was narrowed to.
+    pub fn with_focus(mut self, focus: Option<String>) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    /// Returns the fields as label and value pairs for text formats,
+    /// leaving out unknown ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::metadata::ReportMetadata;
+    ///
+    /// let metadata = ReportMetadata::new("apps/web").with_lockfile(Some("yarn.lock".to_string()));
+    /// let fields = metadata.fields();
+    /// assert_eq!(fields[0].0, "codescope");
+    /// assert!(fields.contains(&("path", "apps/web".to_string())));
+    /// assert!(fields.contains(&("lockfile", "yarn.lock".to_string())));
+    /// assert!(!fields.iter().any(|(label, _)| *label == "commit"));
+    /// ```
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("codescope", self.tool_version.clone()),
+            ("generated", self.generated_at.clone()),
+            ("path", self.path.clone()),
+        ];
+        let optional = [("commit", &self.commit), ("lockfile", &self.lockfile), ("stats", &self.stats), ("focus", &self.focus)];
+        fields.extend(
+            optional
+                .into_iter()
+                .filter_map(|(label, value)| value.clone().map(|value| (label, value))),
+        );
+        fields
+    }
+}
+
+/// Returns the first of [`LOCKFILES`] that `exists` in the project root.
+pub fn detect_lockfile(mut exists: impl FnMut(&str) -> bool) -> Option<&'static str> {
+    LOCKFILES.iter().copied().find(|name| exists(name))
+}
+
+/// Returns the lockfile of a project directory in the working tree.
+pub fn lockfile_in(project_dir: &Path) -> Option<&'static str> {
+    detect_lockfile(|name| project_dir.join(name).is_file())
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let seconds = secs % 86_400;
+
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_153_845), "2026-10-16T12:30:45Z");
+    }
+
+    #[test]
+    fn test_detect_lockfile() {
+        assert_eq!(detect_lockfile(|name| name.ends_with(".json")), Some("npm-shrinkwrap.json"));
+        assert_eq!(detect_lockfile(|name| name == "pnpm-lock.yaml"), Some("pnpm-lock.yaml"));
+        assert_eq!(detect_lockfile(|_| false), None);
+    }
+}
@@ -0,0 +1,825 @@
+//! Report export module
+//!
+//! This module turns analysis results into shareable report formats
+//! that can be written to disk or posted to code review tools.
+//!
+//! # Supported Formats
+//!
+//! - **Markdown**: GitHub-flavored markdown suitable for PR comments
+//! - **CSV**: One row per package with a selectable set of columns
+//! - **JSON**: Versioned document described by a published JSON Schema
+//! - **Excel**: Multi-sheet xlsx workbook (requires the `xlsx` feature)
+//! - **D3**: `{nodes, links}` graph JSON for force-directed layouts
+//! - **Prometheus**: Gauges in the text exposition format, for a
+//!   Pushgateway
+//! - **SVG**: Image of the layered dependency graph, optionally rasterized
+//!   to PNG (requires the `png` feature)
+//! - **Template**: Any text format, rendered through a user-supplied
+//!   Handlebars template (requires the `templates` feature)
+//!
+//! Before a report leaves the organization, [`redact::Redactor`] can
+//! replace internal package names and paths with stable hashes.
+//!
+//! Report files are written atomically by [`file::write_atomic`], so a
+//! failed run never leaves a truncated report behind.
+//!
+//! Reports written to a file can be signed with an Ed25519 key so their
+//! consumers can check them with [`signing::VerifyingKey`].
+//!
+//! JSON exports of several projects can be merged again into one
+//! cross-project report with [`aggregate::AggregateReport`].
+//!
+//! Instead of exporting every package, [`query::Query`] picks and orders
+//! the packages answering one question, and [`select::select`] picks one
+//! value out of a JSON export.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use codescope::export::{ExportReport, MarkdownExporter};
+//!
+//! let report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+//! let markdown = MarkdownExporter::new().export(&report);
+//! std::fs::write("deps.md", markdown)?;
+//! ```
+
+pub mod aggregate;
+pub mod csv;
+pub mod d3;
+pub mod file;
+pub mod json;
+pub mod markdown;
+pub mod metadata;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod prometheus;
+pub mod query;
+pub mod redact;
+pub mod select;
+pub mod signing;
+pub mod svg;
+#[cfg(feature = "templates")]
+pub mod template;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+
+pub use csv::{CsvColumn, CsvExporter};
+pub use d3::D3Exporter;
+pub use json::{JsonExporter, SCHEMA_VERSION};
+pub use markdown::MarkdownExporter;
+pub use metadata::ReportMetadata;
+#[cfg(feature = "png")]
+pub use png::{render_png, PngError};
+pub use prometheus::PrometheusExporter;
+pub use svg::{ImageFormat, SvgExporter};
+#[cfg(feature = "templates")]
+pub use template::TemplateExporter;
+#[cfg(feature = "xlsx")]
+pub use xlsx::XlsxExporter;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::analysis::footprint::InstallFootprint;
+use crate::analysis::groups::GroupSummary;
+use crate::analysis::native::PlatformSupport;
+use crate::analysis::owners::OwnerSummary;
+use crate::analysis::warnings::{AnalysisWarning, AnalysisWarnings};
+use crate::bundle::estimates::SizeDataset;
+use crate::bundle::savings::SavingsReport;
+use crate::config::ProjectConfig;
+use crate::git::Commit;
+use crate::graph::{CycleInfo, DependencyGraph, DependencyType, VersionConflict};
+use crate::parser::DependencySource;
+
+/// Output formats supported by the exporter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// GitHub-flavored markdown
+    Markdown,
+    /// Comma-separated values
+    Csv,
+    /// Versioned JSON document
+    Json,
+    /// Excel workbook
+    Xlsx,
+    /// Node-link graph JSON for D3
+    D3,
+    /// Prometheus text exposition format
+    Prometheus,
+}
+
+impl ExportFormat {
+    /// Returns the conventional file extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::D3 => "json",
+            ExportFormat::Prometheus => "prom",
+        }
+    }
+
+    /// Returns true if the format produces binary output.
+    pub fn is_binary(&self) -> bool {
+        matches!(self, ExportFormat::Xlsx)
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Markdown => write!(f, "markdown"),
+            ExportFormat::Csv => write!(f, "csv"),
+            ExportFormat::Json => write!(f, "json"),
+            ExportFormat::Xlsx => write!(f, "xlsx"),
+            ExportFormat::D3 => write!(f, "d3"),
+            ExportFormat::Prometheus => write!(f, "prometheus"),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "xlsx" | "excel" => Ok(ExportFormat::Xlsx),
+            "d3" => Ok(ExportFormat::D3),
+            "prometheus" | "prom" => Ok(ExportFormat::Prometheus),
+            other => Err(format!(
+                "unknown export format '{}' (expected: markdown, csv, json, xlsx, d3, prometheus)",
+                other
+            )),
+        }
+    }
+}
+
+/// A single package row in an exported report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageEntry {
+    /// Package name
+    pub name: String,
+    /// Version specification
+    pub version: String,
+    /// Name of the installed package (differs from `name` for `npm:` aliases)
+    pub package: String,
+    /// Where the package is installed from
+    pub source: DependencySource,
+    /// Type of dependency relationship
+    pub dep_type: DependencyType,
+    /// Distance from the root package (0 = direct dependency)
+    pub depth: usize,
+    /// Bundle size in bytes, if known
+    pub bundle_size: Option<u64>,
+    /// Minified and gzipped size in bytes, from the size dataset bundled
+    /// with codescope, if it lists the package
+    pub gzip_size: Option<u64>,
+    /// Number of bundled modules, if known
+    pub module_count: Option<usize>,
+    /// Percentage of the package's exports used by the project, if known
+    pub utilization: Option<f64>,
+    /// SPDX license identifier, if known
+    pub license: Option<String>,
+    /// Number of packages in the graph that depend on this one
+    pub dependents: usize,
+    /// Names of the packages this one depends on, sorted
+    pub dependencies: Vec<String>,
+    /// What this package requested and got for each dependency, where the
+    /// graph records it
+    pub requested: BTreeMap<String, RequestedVersion>,
+    /// Whether the package is part of a circular dependency
+    pub in_cycle: bool,
+    /// Whether the package has conflicting version requirements
+    pub has_conflict: bool,
+    /// Owning teams from the project configuration
+    pub owners: Vec<String>,
+    /// Commit that added the package to package.json, for direct
+    /// dependencies when git history was read
+    pub introduced: Option<Commit>,
+    /// Disk usage and install scripts, when node_modules was measured
+    pub install: Option<InstallFootprint>,
+    /// Native bindings and platform restrictions, when detected
+    pub platform: Option<PlatformSupport>,
+    /// The team's note on the package, from the project state
+    pub note: Option<String>,
+}
+
+/// The version range a package requested for one of its dependencies, and
+/// the version installed for it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RequestedVersion {
+    /// Requested range, e.g. `^4.17.0`
+    pub range: Option<String>,
+    /// Installed version
+    pub resolved: Option<String>,
+}
+
+/// One requirement on a package: who requires it, at which range, and
+/// what was installed for them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RequirementEntry {
+    /// The required package
+    pub package: String,
+    /// The package, or project, requiring it
+    pub required_by: String,
+    /// Requested range
+    pub range: String,
+    /// Version installed for the requester, where the graph records it
+    pub resolved: Option<String>,
+}
+
+impl PackageEntry {
+    /// Returns true if the package is a direct dependency of the project.
+    pub fn is_direct(&self) -> bool {
+        self.depth == 0
+    }
+}
+
+/// Format-independent snapshot of an analysis, consumed by the exporters.
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// Name of the analyzed project
+    pub project_name: String,
+    /// Version of the analyzed project
+    pub project_version: String,
+    /// All packages, sorted by name
+    pub packages: Vec<PackageEntry>,
+    /// Detected circular dependencies
+    pub cycles: Vec<CycleInfo>,
+    /// Detected version conflicts
+    pub conflicts: Vec<VersionConflict>,
+    /// Every version requirement, conflicting or not, sorted by package
+    /// then requester
+    pub requirements: Vec<RequirementEntry>,
+    /// Bundle size savings opportunities, if calculated
+    pub savings: Option<SavingsReport>,
+    /// Per-group aggregates from the project configuration
+    pub groups: Vec<GroupSummary>,
+    /// Per-owner breakdown from the project configuration
+    pub owners: Vec<OwnerSummary>,
+    /// Inputs the analysis went without, so the report may be incomplete
+    pub warnings: Vec<AnalysisWarning>,
+    /// How and from what the report was produced, written into the header
+    /// of every format
+    pub metadata: Option<ReportMetadata>,
+}
+
+impl ExportReport {
+    /// Builds a report from a dependency graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - Name of the analyzed project
+    /// * `project_version` - Version of the analyzed project
+    /// * `graph` - The dependency graph, with bundle sizes applied if available
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::ExportReport;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    ///
+    /// let report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+    /// assert_eq!(report.packages.len(), 1);
+    /// ```
+    pub fn from_graph(project_name: &str, project_version: &str, graph: &DependencyGraph) -> Self {
+        let cycle_nodes = graph.get_nodes_in_cycles();
+        let conflict_packages = graph.get_packages_with_conflicts();
+
+        let mut packages: Vec<PackageEntry> = graph
+            .get_all_nodes()
+            .into_iter()
+            .map(|node| PackageEntry {
+                name: node.name.clone(),
+                version: node.version.clone(),
+                package: node.package_name().to_string(),
+                source: node.source.clone(),
+                dep_type: node.dep_type,
+                depth: node.depth,
+                bundle_size: node.bundle_size,
+                gzip_size: None,
+                module_count: node.module_count,
+                utilization: None,
+                license: None,
+                dependents: graph.get_dependents(&node.name).len(),
+                dependencies: {
+                    let mut dependencies: Vec<String> = graph
+                        .get_dependencies(&node.name)
+                        .into_iter()
+                        .map(|dep| dep.name.clone())
+                        .collect();
+                    dependencies.sort();
+                    dependencies
+                },
+                requested: graph
+                    .get_dependencies(&node.name)
+                    .into_iter()
+                    .filter_map(|dep| {
+                        let edge = graph.get_edge(&node.name, &dep.name)?;
+                        let requested = RequestedVersion {
+                            range: edge.requested.clone(),
+                            resolved: edge.resolved.clone(),
+                        };
+                        (requested != RequestedVersion::default()).then(|| (dep.name.clone(), requested))
+                    })
+                    .collect(),
+                in_cycle: cycle_nodes.contains(&node.name),
+                has_conflict: conflict_packages.contains(&node.name),
+                owners: Vec::new(),
+                introduced: None,
+                install: None,
+                platform: None,
+                note: None,
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let requirements = graph
+            .requirement_matrix()
+            .into_iter()
+            .flat_map(|(package, requirements)| {
+                requirements.into_iter().map(move |requirement| RequirementEntry {
+                    // The project is not a node: its ranges resolve to the
+                    // version the lockfile installed at the top level
+                    resolved: match graph.get_edge(&requirement.required_by, &package) {
+                        Some(edge) => edge.resolved.clone(),
+                        None if !graph.contains(&requirement.required_by) => {
+                            graph.get_node(&package).and_then(|node| node.resolved_version.clone())
+                        }
+                        None => None,
+                    },
+                    package: package.clone(),
+                    required_by: requirement.required_by,
+                    range: requirement.version,
+                })
+            })
+            .collect();
+
+        Self {
+            project_name: project_name.to_string(),
+            project_version: project_version.to_string(),
+            packages,
+            cycles: graph.get_cycle_details(),
+            conflicts: graph.detect_version_conflicts(),
+            requirements,
+            savings: None,
+            groups: Vec::new(),
+            owners: Vec::new(),
+            warnings: Vec::new(),
+            metadata: None,
+        }
+    }
+
+    /// Attaches a savings report.
+    pub fn with_savings(mut self, savings: SavingsReport) -> Self {
+        self.savings = Some(savings);
+        self
+    }
+
+    /// Attaches per-group aggregates.
+    pub fn with_groups(mut self, groups: Vec<GroupSummary>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Attaches the per-owner breakdown.
+    pub fn with_owners(mut self, owners: Vec<OwnerSummary>) -> Self {
+        self.owners = owners;
+        self
+    }
+
+    /// Attaches the warnings collected during the analysis.
+    pub fn with_warnings(mut self, warnings: &AnalysisWarnings) -> Self {
+        self.warnings = warnings.as_slice().to_vec();
+        self
+    }
+
+    /// Attaches the provenance of the report.
+    pub fn with_metadata(mut self, metadata: ReportMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Sets the commit that introduced each direct dependency.
+    ///
+    /// # Arguments
+    ///
+    /// * `origins` - Introducing commit per package name, as returned by
+    ///   [`blame_dependencies`](crate::git::blame_dependencies)
+    pub fn apply_blame(&mut self, origins: &BTreeMap<String, Commit>) {
+        for pkg in self.packages.iter_mut().filter(|pkg| pkg.is_direct()) {
+            pkg.introduced = origins.get(&pkg.name).cloned();
+        }
+    }
+
+    /// Sets the install footprint of every measured package.
+    ///
+    /// # Arguments
+    ///
+    /// * `footprints` - Footprint per package name, as returned by
+    ///   [`FootprintReport::by_name`](crate::analysis::FootprintReport::by_name)
+    pub fn apply_footprints(&mut self, footprints: &BTreeMap<String, InstallFootprint>) {
+        for pkg in &mut self.packages {
+            pkg.install = footprints.get(&pkg.name).cloned();
+        }
+    }
+
+    /// Sets the native bindings and platform restrictions of the packages
+    /// tied to a platform; other packages are marked portable.
+    ///
+    /// # Arguments
+    ///
+    /// * `support` - Platform support per package name, as returned by
+    ///   [`NativeReport::by_name`](crate::analysis::NativeReport::by_name)
+    pub fn apply_platform_support(&mut self, support: &BTreeMap<String, PlatformSupport>) {
+        for pkg in &mut self.packages {
+            pkg.platform = Some(support.get(&pkg.name).cloned().unwrap_or_default());
+        }
+    }
+
+    /// Sets the gzipped size of every package the size dataset lists.
+    ///
+    /// The dataset describes the published package as a whole, so the
+    /// size is an estimate even when the stats measured `bundle_size`.
+    ///
+    /// Returns the number of packages that were updated.
+    pub fn apply_gzip_sizes(&mut self, dataset: &SizeDataset) -> usize {
+        let mut updated = 0;
+        for pkg in &mut self.packages {
+            pkg.gzip_size = dataset.get(&pkg.package).map(|size| size.gzipped);
+            updated += usize::from(pkg.gzip_size.is_some());
+        }
+        updated
+    }
+
+    /// Sets the declared license of every installed package.
+    ///
+    /// # Arguments
+    ///
+    /// * `licenses` - License per package name, as returned by
+    ///   [`LicenseReport::by_name`](crate::analysis::LicenseReport::by_name)
+    pub fn apply_licenses(&mut self, licenses: &BTreeMap<String, String>) {
+        for pkg in &mut self.packages {
+            pkg.license = licenses.get(&pkg.name).cloned();
+        }
+    }
+
+    /// Sets the note on every package that has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `notes` - Note per package name, as kept in
+    ///   [`Notes`](crate::state::Notes)
+    pub fn apply_notes(&mut self, notes: &BTreeMap<String, String>) {
+        for pkg in &mut self.packages {
+            pkg.note = notes.get(&pkg.name).cloned();
+        }
+    }
+
+    /// Sets the owners of every package from the configuration.
+    pub fn apply_ownership(&mut self, config: &ProjectConfig) {
+        for pkg in &mut self.packages {
+            pkg.owners = config.owners_of(&pkg.name).to_vec();
+        }
+    }
+
+    /// Narrows the report to what `owner` is responsible for.
+    ///
+    /// Keeps the owner's packages, the cycles, conflicts and requirements
+    /// involving them, their savings and the owner's own summary. Call
+    /// [`apply_ownership`](Self::apply_ownership) first.
+    pub fn retain_owner(&mut self, owner: &str) {
+        let owns = |pkg: &PackageEntry| pkg.owners.iter().any(|o| o == owner);
+        let owned: HashSet<String> = self
+            .packages
+            .iter()
+            .filter(|pkg| owns(pkg))
+            .map(|pkg| pkg.name.clone())
+            .collect();
+
+        self.retain_packages(&owned);
+        self.owners.retain(|summary| summary.owner == owner);
+    }
+
+    /// Narrows the report to `root` and every package it depends on,
+    /// directly or transitively.
+    ///
+    /// Returns false, leaving the report unchanged, if `root` is not in it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::ExportReport;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// for name in ["react", "loose-envify", "lodash"] {
+    ///     graph.add_dependency(name, "1.0.0", DependencyType::Production);
+    /// }
+    /// graph.add_edge("react", "loose-envify");
+    ///
+    /// let mut report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+    /// assert!(report.retain_subtree("react"));
+    /// let names: Vec<&str> = report.packages.iter().map(|pkg| pkg.name.as_str()).collect();
+    /// assert_eq!(names, vec!["loose-envify", "react"]);
+    /// ```
+    pub fn retain_subtree(&mut self, root: &str) -> bool {
+        if !self.packages.iter().any(|pkg| pkg.name == root) {
+            return false;
+        }
+        let subtree = self.subtree([root]);
+        self.retain_packages(&subtree);
+        true
+    }
+
+    /// Returns the names of the `roots` and of every package they depend
+    /// on, directly or transitively; names not in the report are left out.
+    pub fn subtree<'a>(&self, roots: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+        let dependencies: HashMap<&str, &[String]> = self
+            .packages
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.dependencies.as_slice()))
+            .collect();
+
+        let mut subtree = HashSet::new();
+        let mut stack: Vec<&str> = roots.into_iter().filter(|name| dependencies.contains_key(name)).collect();
+        while let Some(name) = stack.pop() {
+            if subtree.insert(name.to_string()) {
+                stack.extend(dependencies.get(name).copied().unwrap_or_default().iter().map(String::as_str));
+            }
+        }
+        subtree
+    }
+
+    /// Narrows the report to the packages named in `keep`.
+    ///
+    /// Keeps the cycles, conflicts and requirements involving them, and
+    /// their savings. Group and owner summaries are left whole.
+    pub fn retain_packages(&mut self, keep: &HashSet<String>) {
+        self.packages.retain(|pkg| keep.contains(&pkg.name));
+        self.cycles
+            .retain(|cycle| cycle.nodes.iter().any(|name| keep.contains(name)));
+        self.conflicts
+            .retain(|conflict| keep.contains(&conflict.package_name));
+        self.requirements
+            .retain(|requirement| keep.contains(&requirement.package));
+        if let Some(savings) = &mut self.savings {
+            savings
+                .package_savings
+                .retain(|s| keep.contains(&s.package_name));
+        }
+    }
+
+    /// Sets the utilization percentage for packages present in `utilization`.
+    ///
+    /// Returns the number of packages that were updated.
+    pub fn apply_utilization(&mut self, utilization: &HashMap<String, f64>) -> usize {
+        let mut updated = 0;
+        for pkg in &mut self.packages {
+            if let Some(&pct) = utilization.get(&pkg.name) {
+                pkg.utilization = Some(pct);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Returns the sum of all known package bundle sizes in bytes.
+    pub fn total_bundle_size(&self) -> u64 {
+        self.packages.iter().filter_map(|p| p.bundle_size).sum()
+    }
+
+    /// Returns up to `limit` packages with known sizes, largest first.
+    pub fn largest_packages(&self, limit: usize) -> Vec<&PackageEntry> {
+        let mut sized: Vec<&PackageEntry> = self
+            .packages
+            .iter()
+            .filter(|p| p.bundle_size.is_some())
+            .collect();
+        sized.sort_by(|a, b| b.bundle_size.cmp(&a.bundle_size).then(a.name.cmp(&b.name)));
+        sized.truncate(limit);
+        sized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+
+        let mut sizes = HashMap::new();
+        sizes.insert("react".to_string(), (45_000_u64, 3_usize));
+        sizes.insert("lodash".to_string(), (70_000_u64, 10_usize));
+        graph.apply_bundle_sizes(&sizes);
+        graph
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("markdown".parse::<ExportFormat>(), Ok(ExportFormat::Markdown));
+        assert_eq!("MD".parse::<ExportFormat>(), Ok(ExportFormat::Markdown));
+        assert_eq!("csv".parse::<ExportFormat>(), Ok(ExportFormat::Csv));
+        assert_eq!("JSON".parse::<ExportFormat>(), Ok(ExportFormat::Json));
+        assert_eq!("xlsx".parse::<ExportFormat>(), Ok(ExportFormat::Xlsx));
+        assert_eq!("prometheus".parse::<ExportFormat>(), Ok(ExportFormat::Prometheus));
+        assert!(ExportFormat::Xlsx.is_binary());
+        assert!(!ExportFormat::Csv.is_binary());
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_from_graph_sorts_packages() {
+        let report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["jest", "lodash", "react"]);
+        assert_eq!(report.project_name, "app");
+    }
+
+    #[test]
+    fn test_from_graph_marks_cycles() {
+        let mut graph = create_test_graph();
+        graph.add_edge("react", "lodash");
+        graph.add_edge("lodash", "react");
+
+        let report = ExportReport::from_graph("app", "1.0.0", &graph);
+        assert_eq!(report.cycles.len(), 1);
+        assert!(report.packages.iter().find(|p| p.name == "react").unwrap().in_cycle);
+        assert!(!report.packages.iter().find(|p| p.name == "jest").unwrap().in_cycle);
+    }
+
+    #[test]
+    fn test_from_graph_dependents_and_direct() {
+        let mut graph = create_test_graph();
+        graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+        graph.add_edge("react", "scheduler");
+
+        let report = ExportReport::from_graph("app", "1.0.0", &graph);
+        let scheduler = report.packages.iter().find(|p| p.name == "scheduler").unwrap();
+        assert_eq!(scheduler.dependents, 1);
+        assert!(!scheduler.is_direct());
+        assert!(report.packages.iter().find(|p| p.name == "react").unwrap().is_direct());
+    }
+
+    #[test]
+    fn test_apply_utilization() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let mut utilization = HashMap::new();
+        utilization.insert("lodash".to_string(), 2.5);
+        utilization.insert("missing".to_string(), 50.0);
+
+        assert_eq!(report.apply_utilization(&utilization), 1);
+        let lodash = report.packages.iter().find(|p| p.name == "lodash").unwrap();
+        assert_eq!(lodash.utilization, Some(2.5));
+    }
+
+    #[test]
+    fn test_apply_gzip_sizes() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let dataset = SizeDataset::parse("name,minified,gzipped\nlodash,71000,25000\nleft-pad,500,300\n");
+
+        assert_eq!(report.apply_gzip_sizes(&dataset), 1);
+        let lodash = report.packages.iter().find(|p| p.name == "lodash").unwrap();
+        assert_eq!(lodash.gzip_size, Some(25000));
+        assert!(report.packages.iter().filter(|p| p.name != "lodash").all(|p| p.gzip_size.is_none()));
+    }
+
+    #[test]
+    fn test_apply_blame() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let commit = |summary: &str| Commit {
+            commit: "1a2b3c4d".to_string(),
+            author: "Ada".to_string(),
+            date: "2024-01-02".to_string(),
+            summary: summary.to_string(),
+        };
+        let mut origins = BTreeMap::new();
+        origins.insert("react".to_string(), commit("Add react"));
+        origins.insert("lodash".to_string(), commit("Not direct"));
+        report.packages.iter_mut().find(|p| p.name == "lodash").unwrap().depth = 1;
+
+        report.apply_blame(&origins);
+        let introduced = |name: &str| report.packages.iter().find(|p| p.name == name).unwrap().introduced.clone();
+        assert_eq!(introduced("react").unwrap().summary, "Add react");
+        assert_eq!(introduced("lodash"), None);
+        assert_eq!(introduced("jest"), None);
+    }
+
+    #[test]
+    fn test_apply_notes() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let mut notes = BTreeMap::new();
+        notes.insert("lodash".to_string(), "replace with native methods".to_string());
+        notes.insert("missing".to_string(), "not installed".to_string());
+
+        report.apply_notes(&notes);
+        let note = |name: &str| report.packages.iter().find(|p| p.name == name).unwrap().note.clone();
+        assert_eq!(note("lodash").as_deref(), Some("replace with native methods"));
+        assert_eq!(note("react"), None);
+    }
+
+    #[test]
+    fn test_apply_footprints() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let mut footprints = BTreeMap::new();
+        footprints.insert(
+            "react".to_string(),
+            InstallFootprint {
+                bytes: 300_000,
+                files: 40,
+                native: false,
+                install_scripts: vec!["postinstall".to_string()],
+            },
+        );
+
+        report.apply_footprints(&footprints);
+        let install = |name: &str| report.packages.iter().find(|p| p.name == name).unwrap().install.clone();
+        assert_eq!(install("react").unwrap().files, 40);
+        assert_eq!(install("lodash"), None);
+    }
+
+    #[test]
+    fn test_apply_platform_support() {
+        let mut report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let mut support = BTreeMap::new();
+        support.insert(
+            "react".to_string(),
+            PlatformSupport {
+                bindings: vec!["binding.gyp".to_string()],
+                ..Default::default()
+            },
+        );
+
+        report.apply_platform_support(&support);
+        let platform = |name: &str| report.packages.iter().find(|p| p.name == name).unwrap().platform.clone();
+        assert!(platform("react").unwrap().is_native());
+        assert!(platform("lodash").unwrap().is_portable(), "detection ran, nothing found");
+    }
+
+    #[test]
+    fn test_retain_owner() {
+        let mut graph = create_test_graph();
+        graph.add_edge("react", "lodash");
+        graph.add_edge("lodash", "react");
+        let config = ProjectConfig::parse(r#"{"owners": ["* @platform", "react @web"]}"#).unwrap();
+
+        let mut report = ExportReport::from_graph("app", "1.0.0", &graph);
+        report.apply_ownership(&config);
+        let react = report.packages.iter().find(|p| p.name == "react").unwrap();
+        assert_eq!(react.owners, vec!["@web"]);
+
+        report.retain_owner("@web");
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["react"]);
+        assert_eq!(report.cycles.len(), 1, "cycle involves an owned package");
+    }
+
+    #[test]
+    fn test_retain_subtree() {
+        let mut graph = create_test_graph();
+        graph.add_edge("react", "lodash");
+        graph.add_edge("lodash", "react");
+        graph.add_edge("jest", "lodash");
+
+        let mut report = ExportReport::from_graph("app", "1.0.0", &graph);
+        assert!(!report.retain_subtree("vue"));
+        assert_eq!(report.packages.len(), 3);
+
+        assert!(report.retain_subtree("react"));
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["lodash", "react"]);
+        assert_eq!(report.cycles.len(), 1);
+        assert!(report.requirements.iter().all(|r| r.package != "jest"));
+    }
+
+    #[test]
+    fn test_total_bundle_size() {
+        let report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        assert_eq!(report.total_bundle_size(), 115_000);
+    }
+
+    #[test]
+    fn test_largest_packages() {
+        let report = ExportReport::from_graph("app", "1.0.0", &create_test_graph());
+        let largest = report.largest_packages(10);
+        assert_eq!(largest.len(), 2);
+        assert_eq!(largest[0].name, "lodash");
+        assert_eq!(largest[1].name, "react");
+
+        assert_eq!(report.largest_packages(1).len(), 1);
+    }
+}
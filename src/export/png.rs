@@ -0,0 +1,61 @@
+//! PNG rasterization of SVG images (requires the `png` feature).
+//!
+//! Labels are drawn with the fonts installed on the system; where none
+//! match the SVG's font families, the image is rendered without text.
+
+use resvg::{tiny_skia, usvg};
+use thiserror::Error;
+
+/// Errors that can occur while rasterizing an image.
+#[derive(Error, Debug)]
+pub enum PngError {
+    /// The SVG could not be parsed.
+    #[error("Invalid SVG: {0}")]
+    Svg(#[from] usvg::Error),
+
+    /// The image has no area or is too large to allocate.
+    #[error("Cannot allocate a {0}x{1} image")]
+    Size(u32, u32),
+
+    /// The pixels could not be encoded.
+    #[error("Failed to encode PNG: {0}")]
+    Encode(String),
+}
+
+/// Rasterizes an SVG document to PNG bytes.
+///
+/// # Arguments
+///
+/// * `svg` - The SVG document
+/// * `scale` - Pixels per SVG unit, e.g. 2.0 for high-DPI screens
+///
+/// # Errors
+///
+/// Returns an error if the SVG cannot be parsed or the image is empty.
+pub fn render_png(svg: &str, scale: f32) -> Result<Vec<u8>, PngError> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options)?;
+
+    let size = tree.size().to_int_size().scale_by(scale).ok_or(PngError::Size(0, 0))?;
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).ok_or(PngError::Size(size.width(), size.height()))?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|e| PngError::Encode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_png() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20" fill="#4e79a7"/></svg>"##;
+        let png = render_png(svg, 2.0).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // IHDR width and height, big-endian
+        assert_eq!(&png[16..24], &[0, 0, 0, 80, 0, 0, 0, 40]);
+
+        assert!(matches!(render_png("<svg", 1.0), Err(PngError::Svg(_))));
+    }
+}
@@ -0,0 +1,193 @@
+//! Prometheus exporter.
+//!
+//! Writes gauges of the report in the Prometheus text exposition format,
+//! so a scheduled job can push them to a Pushgateway and the dependency
+//! health of a project can be graphed over time:
+//!
+//! ```text
+//! # HELP codescope_total_bundle_bytes Sum of the known bundle sizes of the packages.
+//! # TYPE codescope_total_bundle_bytes gauge
+//! codescope_total_bundle_bytes{project="my-app"} 210000
+//! # HELP codescope_deps_total Number of packages by dependency type.
+//! # TYPE codescope_deps_total gauge
+//! codescope_deps_total{project="my-app",type="production"} 2
+//! ```
+//!
+//! Every sample carries a `project` label, so the metrics of several
+//! projects can share one Pushgateway group. Every dependency type has a
+//! `codescope_deps_total` sample, zero when unused, so series do not
+//! disappear when the last package of a type is removed.
+
+use std::fmt::Write;
+
+use crate::graph::DependencyType;
+
+use super::ExportReport;
+
+/// Dependency types, in the order their samples are written
+const DEPENDENCY_TYPES: [DependencyType; 4] = [
+    DependencyType::Production,
+    DependencyType::Development,
+    DependencyType::Peer,
+    DependencyType::Optional,
+];
+
+/// Renders an `ExportReport` as Prometheus metrics.
+#[derive(Debug, Clone, Default)]
+pub struct PrometheusExporter;
+
+impl PrometheusExporter {
+    /// Creates an exporter.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Renders the report's metrics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{ExportReport, PrometheusExporter};
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    ///
+    /// let metrics = PrometheusExporter::new().export(&ExportReport::from_graph("my-app", "1.0.0", &graph));
+    /// assert!(metrics.contains("codescope_deps_total{project=\"my-app\",type=\"production\"} 1\n"));
+    /// assert!(metrics.contains("codescope_cycles_total{project=\"my-app\"} 0\n"));
+    /// ```
+    pub fn export(&self, report: &ExportReport) -> String {
+        let project = format!("project=\"{}\"", escape(&report.project_name));
+        let mut out = String::new();
+        let mut gauge = |name: &str, help: &str, samples: Vec<(String, u64)>| {
+            let _ = writeln!(out, "# HELP codescope_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE codescope_{} gauge", name);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "codescope_{}{{{}{}}} {}", name, project, labels, value);
+            }
+        };
+
+        gauge(
+            "total_bundle_bytes",
+            "Sum of the known bundle sizes of the packages.",
+            vec![(String::new(), report.total_bundle_size())],
+        );
+        gauge(
+            "deps_total",
+            "Number of packages by dependency type.",
+            DEPENDENCY_TYPES
+                .iter()
+                .map(|dep_type| {
+                    let count = report.packages.iter().filter(|pkg| pkg.dep_type == *dep_type).count();
+                    (format!(",type=\"{}\"", dep_type), count as u64)
+                })
+                .collect(),
+        );
+        gauge(
+            "direct_deps_total",
+            "Number of direct dependencies.",
+            vec![(String::new(), report.packages.iter().filter(|pkg| pkg.is_direct()).count() as u64)],
+        );
+        gauge(
+            "unsized_deps_total",
+            "Number of packages without a known bundle size.",
+            vec![(String::new(), report.packages.iter().filter(|pkg| pkg.bundle_size.is_none()).count() as u64)],
+        );
+        gauge(
+            "cycles_total",
+            "Number of circular dependencies.",
+            vec![(String::new(), report.cycles.len() as u64)],
+        );
+        gauge(
+            "conflicts_total",
+            "Number of packages required at conflicting versions.",
+            vec![(String::new(), report.conflicts.len() as u64)],
+        );
+        if let Some(savings) = &report.savings {
+            gauge(
+                "potential_savings_bytes",
+                "Bundle bytes the savings report expects removals and replacements to save.",
+                vec![(String::new(), savings.summary.total_potential_savings)],
+            );
+        }
+        if !report.groups.is_empty() {
+            gauge(
+                "group_deps_total",
+                "Number of packages by package group.",
+                report
+                    .groups
+                    .iter()
+                    .map(|group| (format!(",group=\"{}\"", escape(&group.name)), group.count() as u64))
+                    .collect(),
+            );
+            gauge(
+                "group_bundle_bytes",
+                "Sum of the known bundle sizes by package group.",
+                report
+                    .groups
+                    .iter()
+                    .filter(|group| group.has_sizes())
+                    .map(|group| (format!(",group=\"{}\"", escape(&group.name)), group.bundle_size))
+                    .collect(),
+            );
+            gauge(
+                "group_over_budget",
+                "1 if a package group exceeds its size or count budget, 0 otherwise.",
+                report
+                    .groups
+                    .iter()
+                    .map(|group| (format!(",group=\"{}\"", escape(&group.name)), group.over_budget() as u64))
+                    .collect(),
+            );
+        }
+        gauge(
+            "analysis_warnings_total",
+            "Number of inputs the analysis could not fully read.",
+            vec![(String::new(), report.warnings.len() as u64)],
+        );
+        out
+    }
+}
+
+/// Escapes a label value: backslashes, double quotes and newlines.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyGraph;
+
+    #[test]
+    fn test_export_metrics() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        graph.add_dependency("jest", "29.7.0", DependencyType::Development);
+        let mut report = ExportReport::from_graph("my \"app\"", "1.0.0", &graph);
+        report.packages[1].bundle_size = Some(70_000);
+        let metrics = PrometheusExporter::new().export(&report);
+
+        for line in [
+            "# TYPE codescope_total_bundle_bytes gauge",
+            "codescope_total_bundle_bytes{project=\"my \\\"app\\\"\"} 70000",
+            "codescope_deps_total{project=\"my \\\"app\\\"\",type=\"production\"} 2",
+            "codescope_deps_total{project=\"my \\\"app\\\"\",type=\"dev\"} 1",
+            "codescope_deps_total{project=\"my \\\"app\\\"\",type=\"optional\"} 0",
+            "codescope_direct_deps_total{project=\"my \\\"app\\\"\"} 3",
+            "codescope_unsized_deps_total{project=\"my \\\"app\\\"\"} 2",
+            "codescope_conflicts_total{project=\"my \\\"app\\\"\"} 0",
+        ] {
+            assert!(metrics.lines().any(|l| l == line), "missing {}\n{}", line, metrics);
+        }
+        assert!(!metrics.contains("codescope_group_"), "no groups, no group metrics");
+        assert!(!metrics.contains("potential_savings"));
+        // Every metric has exactly one HELP and one TYPE line
+        assert_eq!(
+            metrics.matches("# HELP").count(),
+            metrics.matches("# TYPE").count()
+        );
+    }
+}
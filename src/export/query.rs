@@ -0,0 +1,671 @@
+//! Ad-hoc queries over the packages of a report.
+//!
+//! `codescope query` answers one question about the dependencies without
+//! exporting every package first, e.g.
+//!
+//! ```text
+//! deps where size > 100KB and type = prod order by size desc limit 10
+//! ```
+//!
+//! A query starts with `deps` and may add, in this order:
+//!
+//! - `where` and conditions joined by `and` / `or`, negated with `not` and
+//!   grouped with parentheses. A condition compares a field with a value
+//!   using `=`, `!=`, `<`, `<=`, `>`, `>=`, or `~` for a case-insensitive
+//!   substring match.
+//! - `order by` a field, followed by `asc` (the default) or `desc`.
+//! - `limit` and a count.
+//!
+//! Fields are the CSV export's columns, with `size`, `gzip` and `modules`
+//! as short names for `bundle_size`, `gzip_size` and `module_count`. Sizes
+//! take a unit (`100KB`, `1.5MB`), `type` takes `prod` for `production`
+//! and `development` for `dev`, and text is quoted when it contains
+//! spaces or operators. Packages without a value for a compared field,
+//! such as a bundle size without stats, never match, and sort last.
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+use crate::bundle::webpack::{format_size, parse_size};
+
+use super::{CsvColumn, ExportReport, PackageEntry};
+
+/// How a field's values are written and compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    /// Bytes, written with a unit
+    Size,
+    /// Plain numbers
+    Number,
+    /// `true` or `false`
+    Flag,
+    /// Text, compared case-insensitively
+    Text,
+}
+
+fn kind(column: CsvColumn) -> FieldKind {
+    match column {
+        CsvColumn::BundleSize | CsvColumn::GzipSize | CsvColumn::InstallSize => FieldKind::Size,
+        CsvColumn::ModuleCount
+        | CsvColumn::Utilization
+        | CsvColumn::Depth
+        | CsvColumn::Dependents
+        | CsvColumn::InstallFiles => FieldKind::Number,
+        CsvColumn::Direct => FieldKind::Flag,
+        _ => FieldKind::Text,
+    }
+}
+
+/// A comparison operator of a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `~`, a case-insensitive substring match
+    Contains,
+}
+
+impl Operator {
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Equal => "=",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Contains => "~",
+        }
+    }
+
+    /// Returns whether `ordering`, of a field against a value, satisfies
+    /// the operator.
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Equal => ordering == Ordering::Equal,
+            Operator::NotEqual => ordering != Ordering::Equal,
+            Operator::Less => ordering == Ordering::Less,
+            Operator::LessOrEqual => ordering != Ordering::Greater,
+            Operator::Greater => ordering == Ordering::Greater,
+            Operator::GreaterOrEqual => ordering != Ordering::Less,
+            Operator::Contains => false,
+        }
+    }
+}
+
+/// A value a field is compared with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    /// A size in bytes or a plain number
+    Number(f64),
+    /// A flag
+    Flag(bool),
+    /// Text, lowercased
+    Text(String),
+}
+
+/// The `where` clause of a query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// A field compared with a value
+    Compare {
+        /// The compared field
+        column: CsvColumn,
+        /// The comparison
+        operator: Operator,
+        /// The value compared with
+        value: Literal,
+    },
+    /// Both conditions hold
+    And(Box<Condition>, Box<Condition>),
+    /// Either condition holds
+    Or(Box<Condition>, Box<Condition>),
+    /// The condition does not hold
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Returns true if the package satisfies the condition.
+    pub fn matches(&self, pkg: &PackageEntry) -> bool {
+        match self {
+            Condition::Compare { column, operator, value } => compare(*column, *operator, value, pkg),
+            Condition::And(left, right) => left.matches(pkg) && right.matches(pkg),
+            Condition::Or(left, right) => left.matches(pkg) || right.matches(pkg),
+            Condition::Not(condition) => !condition.matches(pkg),
+        }
+    }
+
+    fn columns(&self, columns: &mut Vec<CsvColumn>) {
+        match self {
+            Condition::Compare { column, .. } => columns.push(*column),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.columns(columns);
+                right.columns(columns);
+            }
+            Condition::Not(condition) => condition.columns(columns),
+        }
+    }
+}
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// Packages to keep, or every package
+    pub filter: Option<Condition>,
+    /// Field to sort by and whether descending; the report's order, by
+    /// name, otherwise
+    pub order: Option<(CsvColumn, bool)>,
+    /// Most packages to return
+    pub limit: Option<usize>,
+}
+
+impl Query {
+    /// Parses a query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::query::Query;
+    /// use codescope::export::CsvColumn;
+    ///
+    /// let query = Query::parse("deps where size > 100KB and type = prod order by size desc limit 10").unwrap();
+    /// assert_eq!(query.order, Some((CsvColumn::BundleSize, true)));
+    /// assert_eq!(query.limit, Some(10));
+    /// assert!(Query::parse("deps where size > lots").is_err());
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        if !parser.keyword("deps") {
+            return Err("a query starts with 'deps'".to_string());
+        }
+        let filter = if parser.keyword("where") {
+            Some(parser.or()?)
+        } else {
+            None
+        };
+        let order = if parser.keyword("order") {
+            if !parser.keyword("by") {
+                return Err("expected 'by' after 'order'".to_string());
+            }
+            let column = parser.field()?;
+            let descending = if parser.keyword("desc") {
+                true
+            } else {
+                parser.keyword("asc");
+                false
+            };
+            Some((column, descending))
+        } else {
+            None
+        };
+        let limit = if parser.keyword("limit") {
+            let count = parser.word("a count after 'limit'")?;
+            Some(count.parse().map_err(|_| format!("invalid limit '{}'", count))?)
+        } else {
+            None
+        };
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("unexpected '{}'", token));
+        }
+        Ok(Self { filter, order, limit })
+    }
+
+    /// Returns the report's packages matching the query, in its order.
+    pub fn run<'a>(&self, report: &'a ExportReport) -> Vec<&'a PackageEntry> {
+        let mut packages: Vec<&PackageEntry> = report
+            .packages
+            .iter()
+            .filter(|pkg| self.filter.as_ref().is_none_or(|filter| filter.matches(pkg)))
+            .collect();
+        if let Some((column, descending)) = self.order {
+            packages.sort_by(|a, b| order(column, descending, a, b));
+        }
+        if let Some(limit) = self.limit {
+            packages.truncate(limit);
+        }
+        packages
+    }
+
+    /// Returns the columns to show: name, version and type, then the
+    /// fields the query compares or sorts by.
+    pub fn columns(&self) -> Vec<CsvColumn> {
+        let mut referenced = Vec::new();
+        if let Some(filter) = &self.filter {
+            filter.columns(&mut referenced);
+        }
+        referenced.extend(self.order.map(|(column, _)| column));
+        let mut columns = CsvColumn::DEFAULT.to_vec();
+        for column in referenced {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+}
+
+/// Formats the packages a query returned as an aligned table of `columns`.
+pub fn format_table(packages: &[&PackageEntry], columns: &[CsvColumn], total: usize) -> String {
+    if packages.is_empty() {
+        return format!("No packages match the query (of {}).\n", total);
+    }
+    let rows: Vec<Vec<String>> = packages
+        .iter()
+        .map(|pkg| columns.iter().map(|column| cell(*column, pkg)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(column.header().len())
+        })
+        .collect();
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        format!("  {}\n", padded.join("  ").trim_end())
+    };
+
+    let mut out = line(columns.iter().map(|column| column.header().to_string()).collect());
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out.push_str(&format!("\n{} of {} packages\n", packages.len(), total));
+    out
+}
+
+/// Returns the packages a query returned as a JSON array of objects with
+/// one key per column; missing values are `null`.
+pub fn to_json(packages: &[&PackageEntry], columns: &[CsvColumn]) -> Value {
+    Value::Array(
+        packages
+            .iter()
+            .map(|pkg| {
+                let mut object = Map::new();
+                for column in columns {
+                    let value = match (kind(*column), number(*column, pkg)) {
+                        // Utilization is the only field with a fraction
+                        (FieldKind::Size | FieldKind::Number, Some(n)) if *column == CsvColumn::Utilization => {
+                            Value::from(n)
+                        }
+                        (FieldKind::Size | FieldKind::Number, Some(n)) => Value::from(n as u64),
+                        (FieldKind::Size | FieldKind::Number, None) => Value::Null,
+                        (FieldKind::Flag, _) => Value::Bool(pkg.is_direct()),
+                        (FieldKind::Text, _) => Value::String(column.value(pkg)),
+                    };
+                    object.insert(column.header().to_string(), value);
+                }
+                Value::Object(object)
+            })
+            .collect(),
+    )
+}
+
+/// Returns a table cell: sizes with a unit, and `-` for missing values.
+fn cell(column: CsvColumn, pkg: &PackageEntry) -> String {
+    match kind(column) {
+        FieldKind::Size => number(column, pkg).map_or("-".to_string(), |bytes| format_size(bytes as u64)),
+        _ => {
+            let value = column.value(pkg);
+            if value.is_empty() {
+                "-".to_string()
+            } else {
+                value
+            }
+        }
+    }
+}
+
+/// Returns the numeric value of a size or number field, if known.
+fn number(column: CsvColumn, pkg: &PackageEntry) -> Option<f64> {
+    match column {
+        CsvColumn::BundleSize => pkg.bundle_size.map(|n| n as f64),
+        CsvColumn::GzipSize => pkg.gzip_size.map(|n| n as f64),
+        CsvColumn::InstallSize => pkg.install.as_ref().map(|i| i.bytes as f64),
+        CsvColumn::ModuleCount => pkg.module_count.map(|n| n as f64),
+        CsvColumn::Utilization => pkg.utilization,
+        CsvColumn::Depth => Some(pkg.depth as f64),
+        CsvColumn::Dependents => Some(pkg.dependents as f64),
+        CsvColumn::InstallFiles => pkg.install.as_ref().map(|i| i.files as f64),
+        _ => None,
+    }
+}
+
+fn compare(column: CsvColumn, operator: Operator, value: &Literal, pkg: &PackageEntry) -> bool {
+    match value {
+        Literal::Number(expected) => number(column, pkg)
+            .and_then(|actual| actual.partial_cmp(expected))
+            .is_some_and(|ordering| operator.accepts(ordering)),
+        Literal::Flag(expected) => operator.accepts(pkg.is_direct().cmp(expected)),
+        Literal::Text(expected) => {
+            let actual = column.value(pkg).to_lowercase();
+            match operator {
+                Operator::Contains => actual.contains(expected.as_str()),
+                _ => operator.accepts(actual.as_str().cmp(expected.as_str())),
+            }
+        }
+    }
+}
+
+/// Orders two packages by a field; missing values sort last either way.
+fn order(column: CsvColumn, descending: bool, a: &PackageEntry, b: &PackageEntry) -> Ordering {
+    let ordering = match kind(column) {
+        FieldKind::Size | FieldKind::Number => match (number(column, a), number(column, b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        _ => column.value(a)
+            .to_lowercase()
+            .cmp(&column.value(b).to_lowercase()),
+    };
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// A token of a query.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A keyword, field name or unquoted value
+    Word(String),
+    /// A quoted value
+    Quoted(String),
+    /// A comparison operator
+    Operator(Operator),
+    /// `(`
+    Open,
+    /// `)`
+    Close,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::Operator(operator) => write!(f, "{}", operator.symbol()),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|&next| next != c).collect();
+                tokens.push(Token::Quoted(text));
+            }
+            '=' | '~' => {
+                chars.next();
+                tokens.push(Token::Operator(if c == '=' { Operator::Equal } else { Operator::Contains }));
+            }
+            '!' | '<' | '>' => {
+                chars.next();
+                let or_equal = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Operator(match (c, or_equal) {
+                    ('!', true) => Operator::NotEqual,
+                    ('!', false) => return Err("expected '=' after '!'".to_string()),
+                    ('<', false) => Operator::Less,
+                    ('<', true) => Operator::LessOrEqual,
+                    ('>', false) => Operator::Greater,
+                    _ => Operator::GreaterOrEqual,
+                }));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(next) = chars.next_if(|&next| !next.is_whitespace() && !"()\"'=~!<>".contains(next)) {
+                    word.push(next);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    /// Consumes the next token if it is the keyword `keyword`.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes the next token as a word or quoted value.
+    fn word(&mut self, expected: &str) -> Result<String, String> {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word) | Token::Quoted(word)) => {
+                self.position += 1;
+                Ok(word.clone())
+            }
+            Some(token) => Err(format!("expected {}, found '{}'", expected, token)),
+            None => Err(format!("expected {} at the end of the query", expected)),
+        }
+    }
+
+    fn field(&mut self) -> Result<CsvColumn, String> {
+        let name = self.word("a field")?;
+        match name.to_ascii_lowercase().as_str() {
+            "size" => Ok(CsvColumn::BundleSize),
+            "gzip" => Ok(CsvColumn::GzipSize),
+            "modules" => Ok(CsvColumn::ModuleCount),
+            _ => name.parse(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+        while self.keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.factor()?;
+        while self.keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.factor()?));
+        }
+        Ok(condition)
+    }
+
+    fn factor(&mut self) -> Result<Condition, String> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.factor()?)));
+        }
+        if self.tokens.get(self.position) == Some(&Token::Open) {
+            self.position += 1;
+            let condition = self.or()?;
+            if self.tokens.get(self.position) != Some(&Token::Close) {
+                return Err("expected ')'".to_string());
+            }
+            self.position += 1;
+            return Ok(condition);
+        }
+
+        let column = self.field()?;
+        let operator = match self.tokens.get(self.position) {
+            Some(Token::Operator(operator)) => *operator,
+            _ => return Err(format!("expected an operator after '{}'", column)),
+        };
+        self.position += 1;
+        let text = self.word(&format!("a value for '{}'", column))?;
+        let kind = kind(column);
+        let valid = match kind {
+            FieldKind::Size | FieldKind::Number => operator != Operator::Contains,
+            FieldKind::Flag => matches!(operator, Operator::Equal | Operator::NotEqual),
+            FieldKind::Text => matches!(operator, Operator::Equal | Operator::NotEqual | Operator::Contains),
+        };
+        if !valid {
+            return Err(format!("'{}' cannot be compared with '{}'", column, operator.symbol()));
+        }
+        let value = match kind {
+            FieldKind::Size => Literal::Number(parse_size(&text)? as f64),
+            FieldKind::Number => Literal::Number(
+                text.parse()
+                    .map_err(|_| format!("invalid value '{}' for '{}': expected a number", text, column))?,
+            ),
+            FieldKind::Flag => match text.to_ascii_lowercase().as_str() {
+                "true" | "yes" => Literal::Flag(true),
+                "false" | "no" => Literal::Flag(false),
+                _ => return Err(format!("invalid value '{}' for '{}': expected true or false", text, column)),
+            },
+            FieldKind::Text if column == CsvColumn::Type => Literal::Text(
+                match text.to_ascii_lowercase().as_str() {
+                    "prod" => "production",
+                    "development" => "dev",
+                    "opt" => "optional",
+                    other => other,
+                }
+                .to_string(),
+            ),
+            FieldKind::Text => Literal::Text(text.to_lowercase()),
+        };
+        Ok(Condition::Compare { column, operator, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        graph.add_dependency("jest", "29.7.0", DependencyType::Development);
+        graph.add_dependency("dayjs", "1.11.10", DependencyType::Production);
+        let mut report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+        for pkg in &mut report.packages {
+            pkg.bundle_size = match pkg.name.as_str() {
+                "react" => Some(140_000),
+                "lodash" => Some(530_000),
+                "jest" => Some(90_000),
+                _ => None,
+            };
+        }
+        report
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let report = create_test_report();
+        Query::parse(query)
+            .unwrap()
+            .run(&report)
+            .iter()
+            .map(|pkg| pkg.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_order_and_limit() {
+        assert_eq!(names("deps"), vec!["dayjs", "jest", "lodash", "react"]);
+        assert_eq!(
+            names("deps where size > 100KB and type = prod order by size desc"),
+            vec!["lodash", "react"]
+        );
+        assert_eq!(names("DEPS ORDER BY size LIMIT 2"), vec!["jest", "react"]);
+        assert_eq!(names("deps order by size desc"), vec!["lodash", "react", "jest", "dayjs"], "unknown sizes sort last");
+        assert_eq!(names("deps where size < 1MB"), vec!["jest", "lodash", "react"], "unknown sizes never match");
+    }
+
+    #[test]
+    fn test_boolean_conditions() {
+        assert_eq!(names("deps where type = dev or name = 'dayjs'"), vec!["dayjs", "jest"]);
+        assert_eq!(names("deps where not (name ~ da or name ~ DASH)"), vec!["jest", "react"]);
+        assert_eq!(names("deps where type != production and direct = true"), vec!["jest"]);
+        assert_eq!(names("deps where type = prod and (size >= 140000 or depth > 0)"), vec!["lodash", "react"]);
+    }
+
+    #[test]
+    fn test_columns() {
+        let query = Query::parse("deps where size > 1KB and name ~ re order by dependents").unwrap();
+        assert_eq!(
+            query.columns(),
+            vec![CsvColumn::Name, CsvColumn::Version, CsvColumn::Type, CsvColumn::BundleSize, CsvColumn::Dependents]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (query, error) in [
+            ("packages", "a query starts with 'deps'"),
+            ("deps where", "expected a field at the end of the query"),
+            ("deps where size", "expected an operator after 'bundle_size'"),
+            ("deps where name > b", "'name' cannot be compared with '>'"),
+            ("deps where depth = deep", "invalid value 'deep' for 'depth': expected a number"),
+            ("deps where (depth = 0", "expected ')'"),
+            ("deps order size", "expected 'by' after 'order'"),
+            ("deps limit 10 extra", "unexpected 'extra'"),
+            ("deps where name ! x", "expected '=' after '!'"),
+        ] {
+            assert_eq!(Query::parse(query).unwrap_err(), error, "{}", query);
+        }
+        assert!(Query::parse("deps where colour = red").unwrap_err().starts_with("unknown column 'colour'"));
+    }
+
+    #[test]
+    fn test_output() {
+        let report = create_test_report();
+        let query = Query::parse("deps where size > 100KB order by size desc").unwrap();
+        let packages = query.run(&report);
+        let table = format_table(&packages, &query.columns(), report.packages.len());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "  name    version  type        bundle_size");
+        assert!(lines[1].starts_with("  lodash  4.17.21  production  "));
+        assert_eq!(lines.last(), Some(&"2 of 4 packages"));
+        assert_eq!(format_table(&[], &query.columns(), 4), "No packages match the query (of 4).\n");
+
+        let json = to_json(&packages, &query.columns());
+        assert_eq!(json[0]["name"], "lodash");
+        assert_eq!(json[0]["bundle_size"], 530_000);
+        assert_eq!(json[1]["type"], "production");
+    }
+}
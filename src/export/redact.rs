@@ -0,0 +1,325 @@
+//! Anonymizing reports before they are shared.
+//!
+//! A report sent to a vendor or attached to a bug report should not reveal
+//! the names of proprietary packages. [`Redactor`] replaces the project
+//! name, scoped packages, packages installed from outside the registry,
+//! file paths and git URLs with stable hashes, and keeps sizes, versions
+//! and the shape of the graph. The same name always hashes the same way,
+//! so redacted reports of one project can still be compared, and packages
+//! of one scope share a redacted scope.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::parser::DependencySource;
+
+use super::ExportReport;
+
+/// Replaces internal names in an [`ExportReport`] with stable hashes.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Scopes, such as `@types`, whose packages are left readable
+    public_scopes: Vec<String>,
+}
+
+impl Redactor {
+    /// Creates a redactor that hides every scoped package.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leaves the packages of these scopes readable, e.g. `@babel` or
+    /// `@types`; a leading `@` is optional.
+    pub fn with_public_scopes(mut self, scopes: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.public_scopes = scopes
+            .into_iter()
+            .map(|scope| format!("@{}", scope.as_ref().trim().trim_start_matches('@')))
+            .collect();
+        self
+    }
+
+    /// Returns the redacted form of a package name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::redact::Redactor;
+    ///
+    /// let redactor = Redactor::new().with_public_scopes(["types"]);
+    /// let hidden = redactor.package_name("@acme/billing");
+    /// assert!(hidden.starts_with('@') && !hidden.contains("acme"));
+    /// assert_eq!(hidden, redactor.package_name("@acme/billing"));
+    /// assert_eq!(redactor.package_name("@types/node"), "@types/node");
+    /// ```
+    pub fn package_name(&self, name: &str) -> String {
+        match name.split_once('/') {
+            Some((scope, rest)) if scope.starts_with('@') => {
+                if self.public_scopes.iter().any(|public| public == scope) {
+                    name.to_string()
+                } else {
+                    format!("@{}/{}", hash("scope", scope), hash("pkg", rest))
+                }
+            }
+            _ => hash("pkg", name),
+        }
+    }
+
+    /// Returns the redacted form of a file path: the directory is hashed
+    /// and the file name kept, since it only says what kind of input the
+    /// file is (`package-lock.json`, `stats.json`).
+    pub fn path(&self, path: &str) -> String {
+        let file_name = Path::new(path).file_name().map(|name| name.to_string_lossy());
+        match file_name {
+            Some(name) if name != path => format!("{}/{}", hash("dir", path), name),
+            _ => path.to_string(),
+        }
+    }
+
+    /// Returns the redacted form of a version specifier: registry ranges
+    /// and workspace ranges are kept, aliases of hidden packages are
+    /// redacted, and paths and URLs are hashed.
+    pub fn specifier(&self, spec: &str, names: &BTreeMap<String, String>) -> String {
+        match DependencySource::parse(spec) {
+            DependencySource::Registry | DependencySource::Workspace { .. } => spec.to_string(),
+            DependencySource::Alias { package, range } => {
+                let package = names.get(&package).cloned().unwrap_or(package);
+                format!("npm:{}@{}", package, range)
+            }
+            DependencySource::File { path } => format!("file:{}", self.path(&path)),
+            DependencySource::Git { .. } | DependencySource::Tarball { .. } => hash("url", spec),
+        }
+    }
+
+    /// Redacts a report in place.
+    ///
+    /// Hidden are the project name, packages of non-public scopes and
+    /// packages not installed from the registry, wherever they appear,
+    /// along with local paths, git and tarball URLs, and commit authors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::ExportReport;
+    /// use codescope::export::redact::Redactor;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("@acme/ui", "1.0.0", DependencyType::Production);
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    /// graph.add_edge("@acme/ui", "react");
+    ///
+    /// let mut report = ExportReport::from_graph("acme-web", "1.0.0", &graph);
+    /// Redactor::new().redact(&mut report);
+    /// assert!(!report.project_name.contains("acme"));
+    /// assert!(!report.packages[0].name.contains("acme"));
+    /// assert_eq!(report.packages[0].dependencies, vec!["react"]);
+    /// ```
+    pub fn redact(&self, report: &mut ExportReport) {
+        let mut names: BTreeMap<String, String> = report
+            .packages
+            .iter()
+            .filter(|pkg| self.is_internal(&pkg.name, &pkg.source) || self.is_internal(&pkg.package, &pkg.source))
+            .flat_map(|pkg| [pkg.name.clone(), pkg.package.clone()])
+            .map(|name| {
+                let redacted = self.package_name(&name);
+                (name, redacted)
+            })
+            .collect();
+        names.insert(report.project_name.clone(), hash("project", &report.project_name));
+        let name = |name: &mut String| {
+            if let Some(redacted) = names.get(name.as_str()) {
+                *name = redacted.clone();
+            }
+        };
+        let text = |text: &mut String| *text = redact_text(text, &names);
+
+        for pkg in &mut report.packages {
+            pkg.version = self.specifier(&pkg.version, &names);
+            pkg.source = self.source(&pkg.source, &names);
+            name(&mut pkg.name);
+            name(&mut pkg.package);
+            pkg.dependencies.iter_mut().for_each(name);
+            pkg.dependencies.sort();
+            pkg.requested = std::mem::take(&mut pkg.requested)
+                .into_iter()
+                .map(|(mut dependency, mut requested)| {
+                    name(&mut dependency);
+                    requested.range = requested.range.map(|range| self.specifier(&range, &names));
+                    requested.resolved = requested.resolved.map(|resolved| self.specifier(&resolved, &names));
+                    (dependency, requested)
+                })
+                .collect();
+            if let Some(commit) = &mut pkg.introduced {
+                commit.author = hash("author", &commit.author);
+                text(&mut commit.summary);
+            }
+            if let Some(note) = &mut pkg.note {
+                text(note);
+            }
+        }
+        report.packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for cycle in &mut report.cycles {
+            cycle.nodes.iter_mut().for_each(name);
+            if let Some(suggested) = &mut cycle.suggested_break {
+                name(&mut suggested.from);
+                name(&mut suggested.to);
+            }
+        }
+        for conflict in &mut report.conflicts {
+            name(&mut conflict.package_name);
+            for requirement in &mut conflict.requirements {
+                name(&mut requirement.required_by);
+                requirement.version = self.specifier(&requirement.version, &names);
+            }
+        }
+        for requirement in &mut report.requirements {
+            name(&mut requirement.package);
+            name(&mut requirement.required_by);
+            requirement.range = self.specifier(&requirement.range, &names);
+            requirement.resolved = requirement.resolved.take().map(|resolved| self.specifier(&resolved, &names));
+        }
+        report
+            .requirements
+            .sort_by(|a, b| a.package.cmp(&b.package).then_with(|| a.required_by.cmp(&b.required_by)));
+        if let Some(savings) = &mut report.savings {
+            for saving in &mut savings.package_savings {
+                name(&mut saving.package_name);
+                text(&mut saving.suggestion);
+                if let Some(alternative) = &mut saving.alternative {
+                    text(alternative);
+                }
+            }
+            for score in &mut savings.tree_shaking.scores {
+                name(&mut score.package);
+            }
+        }
+        for group in &mut report.groups {
+            group.packages.iter_mut().for_each(name);
+            group.packages.sort();
+        }
+        for owner in &mut report.owners {
+            owner.packages.iter_mut().for_each(name);
+            owner.packages.sort();
+        }
+        for warning in &mut report.warnings {
+            let path = self.path(&warning.source);
+            warning.message = warning.message.replace(&warning.source, &path);
+            text(&mut warning.message);
+            if let Some(location) = &mut warning.location {
+                text(location);
+            }
+            warning.source = path;
+        }
+        if let Some(metadata) = &mut report.metadata {
+            metadata.path = hash("dir", &metadata.path);
+            metadata.stats = metadata.stats.take().map(|stats| self.path(&stats));
+            if let Some(focus) = &mut metadata.focus {
+                text(focus);
+            }
+        }
+        name(&mut report.project_name);
+    }
+
+    /// Returns true if a package's name must not leave the organization:
+    /// it belongs to a non-public scope or is not installed from the
+    /// registry.
+    fn is_internal(&self, name: &str, source: &DependencySource) -> bool {
+        let scoped = name.starts_with('@') && self.package_name(name) != name;
+        let unpublished = !matches!(source, DependencySource::Registry | DependencySource::Alias { .. });
+        scoped || unpublished
+    }
+
+    fn source(&self, source: &DependencySource, names: &BTreeMap<String, String>) -> DependencySource {
+        match source {
+            DependencySource::Alias { package, range } => DependencySource::Alias {
+                package: names.get(package).cloned().unwrap_or_else(|| package.clone()),
+                range: range.clone(),
+            },
+            DependencySource::Git { url, .. } => DependencySource::Git {
+                url: hash("url", url),
+                committish: None,
+            },
+            DependencySource::File { path } => DependencySource::File { path: self.path(path) },
+            DependencySource::Tarball { url } => DependencySource::Tarball { url: hash("url", url) },
+            other => other.clone(),
+        }
+    }
+}
+
+/// Replaces every redacted name in free text, longest name first so a name
+/// that contains another is replaced whole.
+fn redact_text(text: &str, names: &BTreeMap<String, String>) -> String {
+    let mut by_length: Vec<(&String, &String)> = names.iter().filter(|(name, _)| !name.is_empty()).collect();
+    by_length.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(b.0)));
+    by_length
+        .into_iter()
+        .fold(text.to_string(), |text, (name, redacted)| text.replace(name.as_str(), redacted))
+}
+
+/// Returns `<kind>-<hash>`, where the hash is the 64-bit FNV-1a hash of
+/// `value`, which is stable across runs, platforms and releases.
+fn hash(kind: &str, value: &str) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = value
+        .bytes()
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+    format!("{}-{:016x}", kind, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::warnings::AnalysisWarnings;
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    #[test]
+    fn test_redact_report() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("@acme/ui", "1.0.0", DependencyType::Production);
+        graph.add_dependency("@acme/ui-kit", "2.0.0", DependencyType::Production);
+        graph.add_dependency("@types/react", "18.2.0", DependencyType::Development);
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_edge("@acme/ui", "react");
+        graph.add_edge("@acme/ui", "@acme/ui-kit");
+        graph.add_edge("@acme/ui-kit", "@acme/ui");
+        graph.get_node_mut("@acme/ui").unwrap().set_bundle_size(4096, 2);
+
+        let mut warnings = AnalysisWarnings::new();
+        warnings.push("/home/dev/acme-web/stats.json", "@acme/ui-kit has no size in /home/dev/acme-web/stats.json");
+        let mut report = ExportReport::from_graph("acme-web", "1.0.0", &graph).with_warnings(&warnings);
+        Redactor::new().with_public_scopes(["types"]).redact(&mut report);
+
+        let ui = Redactor::new().package_name("@acme/ui");
+        let kit = Redactor::new().package_name("@acme/ui-kit");
+        assert_eq!(ui.split('/').next(), kit.split('/').next(), "one scope, one redacted scope");
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert!(names.contains(&"react") && names.contains(&"@types/react"));
+        assert!(names.contains(&ui.as_str()) && names.contains(&kit.as_str()));
+
+        let redacted_ui = report.packages.iter().find(|p| p.name == ui).unwrap();
+        assert_eq!(redacted_ui.bundle_size, Some(4096), "sizes are kept");
+        assert_eq!(redacted_ui.dependencies.len(), 2, "structure is kept");
+        assert_eq!(report.cycles.len(), 1);
+        assert!(report.cycles[0].nodes.iter().all(|node| !node.contains("acme")));
+
+        let everything = format!("{:?}", report);
+        assert!(!everything.contains("acme"), "{}", everything);
+        assert!(report.warnings[0].source.ends_with("/stats.json"));
+        assert_eq!(report.warnings[0].message, format!("{} has no size in {}", kit, report.warnings[0].source));
+    }
+
+    #[test]
+    fn test_redact_specifiers() {
+        let redactor = Redactor::new();
+        let names = BTreeMap::from([("@acme/ui".to_string(), redactor.package_name("@acme/ui"))]);
+        assert_eq!(redactor.specifier("^1.2.0", &names), "^1.2.0");
+        assert_eq!(redactor.specifier("workspace:*", &names), "workspace:*");
+        assert!(!redactor.specifier("npm:@acme/ui@^1.0.0", &names).contains("acme"));
+        assert!(redactor.specifier("file:../acme/lib.tgz", &names).ends_with("/lib.tgz"));
+        assert!(redactor.specifier("github:acme/ui#main", &names).starts_with("url-"));
+        assert_eq!(hash("pkg", "a"), hash("pkg", "a"));
+        assert_ne!(hash("pkg", "a"), hash("pkg", "b"));
+    }
+}
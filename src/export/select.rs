@@ -0,0 +1,265 @@
+//! Extracting one part of a JSON export.
+//!
+//! `codescope export --select` prints only the value at a path instead of
+//! the whole document, so a CI script can read a number without jq. Paths
+//! are written either jq-style or as a JSON pointer (RFC 6901):
+//!
+//! ```text
+//! .summary.total_bundle_size     /summary/total_bundle_size
+//! .packages[0].name              /packages/0/name
+//! .packages[-1].name             (the last package)
+//! .packages[].name               (the name of every package)
+//! ."key with spaces"
+//! ```
+//!
+//! As in jq, a missing key selects `null`; indexing into a value that is
+//! not an object or array is an error.
+
+use serde_json::Value;
+
+/// One step of a jq-style path.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// `.key`
+    Key(String),
+    /// `[n]`, counted from the end when negative
+    Index(i64),
+    /// `[]`, every element
+    Each,
+}
+
+/// Returns the value at `path` in `document`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::export::select::select;
+/// use serde_json::json;
+///
+/// let document = json!({"summary": {"total_bundle_size": 1024}, "packages": [{"name": "react"}]});
+/// assert_eq!(select(&document, ".summary.total_bundle_size").unwrap(), json!(1024));
+/// assert_eq!(select(&document, "/packages/0/name").unwrap(), json!("react"));
+/// assert_eq!(select(&document, ".packages[].name").unwrap(), json!(["react"]));
+/// assert!(select(&document, ".summary.total_bundle_size.kb").is_err());
+/// ```
+pub fn select(document: &Value, path: &str) -> Result<Value, String> {
+    if path.is_empty() || path.starts_with('/') {
+        return document
+            .pointer(path)
+            .cloned()
+            .ok_or_else(|| format!("nothing at '{}'", path));
+    }
+    let steps = parse(path)?;
+    walk(document, &steps, &mut String::new())
+}
+
+/// Formats a selected value for printing: strings without quotes, so
+/// shells can use them directly, and everything else as JSON.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::export::select::format_selected;
+/// use serde_json::json;
+///
+/// assert_eq!(format_selected(&json!("react")), "react\n");
+/// assert_eq!(format_selected(&json!(1024)), "1024\n");
+/// ```
+pub fn format_selected(value: &Value) -> String {
+    let mut out = match value {
+        Value::String(text) => text.clone(),
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::to_string_pretty(value).expect("selected values are valid JSON")
+        }
+        other => other.to_string(),
+    };
+    out.push('\n');
+    out
+}
+
+fn parse(path: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    let mut chars = path.chars().peekable();
+    if chars.peek() != Some(&'.') {
+        return Err(format!("'{}' is neither a jq-style path (.a.b) nor a JSON pointer (/a/b)", path));
+    }
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => match chars.peek() {
+                // A lone `.` selects the whole document
+                None if steps.is_empty() => {}
+                Some('[') => {}
+                Some('"') => {
+                    chars.next();
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(c) => key.push(c),
+                            None => return Err(format!("unclosed quote in '{}'", path)),
+                        }
+                    }
+                    steps.push(Step::Key(key));
+                }
+                _ => {
+                    let mut key = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    if key.is_empty() {
+                        return Err(format!("expected a key after '.' in '{}'", path));
+                    }
+                    steps.push(Step::Key(key));
+                }
+            },
+            '[' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err(format!("unclosed '[' in '{}'", path)),
+                    }
+                }
+                let inner = inner.trim();
+                if inner.is_empty() {
+                    steps.push(Step::Each);
+                } else if let Some(key) = inner.strip_prefix('"').and_then(|key| key.strip_suffix('"')) {
+                    steps.push(Step::Key(key.to_string()));
+                } else {
+                    let index = inner
+                        .parse()
+                        .map_err(|_| format!("invalid index '[{}]' in '{}'", inner, path))?;
+                    steps.push(Step::Index(index));
+                }
+            }
+            other => return Err(format!("unexpected '{}' in '{}'", other, path)),
+        }
+    }
+    Ok(steps)
+}
+
+/// Follows `steps` from `value`; `at` is the path walked so far, for
+/// error messages.
+fn walk(value: &Value, steps: &[Step], at: &mut String) -> Result<Value, String> {
+    let Some((step, rest)) = steps.split_first() else {
+        return Ok(value.clone());
+    };
+    let location = if at.is_empty() { ".".to_string() } else { at.clone() };
+    match (step, value) {
+        (Step::Key(key), Value::Object(map)) => {
+            at.push_str(&format!(".{}", key));
+            match map.get(key) {
+                Some(child) => walk(child, rest, at),
+                None => walk(&Value::Null, rest, at),
+            }
+        }
+        (Step::Key(_) | Step::Index(_), Value::Null) => walk(&Value::Null, rest, at),
+        (Step::Index(index), Value::Array(items)) => {
+            at.push_str(&format!("[{}]", index));
+            let position = if *index < 0 {
+                items.len().checked_sub(index.unsigned_abs() as usize)
+            } else {
+                Some(*index as usize)
+            };
+            match position.and_then(|position| items.get(position)) {
+                Some(child) => walk(child, rest, at),
+                None => walk(&Value::Null, rest, at),
+            }
+        }
+        (Step::Each, Value::Array(items)) => {
+            at.push_str("[]");
+            items
+                .iter()
+                .map(|item| walk(item, rest, &mut at.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+        (Step::Each, Value::Object(map)) => {
+            at.push_str("[]");
+            map.values()
+                .map(|item| walk(item, rest, &mut at.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+        (step, value) => {
+            let wanted = match step {
+                Step::Key(key) => format!("key '{}'", key),
+                Step::Index(index) => format!("index [{}]", index),
+                Step::Each => "elements".to_string(),
+            };
+            Err(format!("{} is {}, which has no {}", location, describe(value), wanted))
+        }
+    }
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "summary": {"total_bundle_size": 670000, "total_packages": 3},
+            "packages": [
+                {"name": "react", "bundle_size": 140000},
+                {"name": "lodash", "bundle_size": 530000},
+                {"name": "jest", "bundle_size": null}
+            ],
+            "odd keys": {"a.b": true}
+        })
+    }
+
+    #[test]
+    fn test_jq_paths() {
+        let doc = document();
+        assert_eq!(select(&doc, ".").unwrap(), doc);
+        assert_eq!(select(&doc, ".summary.total_packages").unwrap(), json!(3));
+        assert_eq!(select(&doc, ".packages[1].name").unwrap(), json!("lodash"));
+        assert_eq!(select(&doc, ".packages[-1].name").unwrap(), json!("jest"));
+        assert_eq!(select(&doc, ".packages[].name").unwrap(), json!(["react", "lodash", "jest"]));
+        assert_eq!(select(&doc, ".\"odd keys\"[\"a.b\"]").unwrap(), json!(true));
+        assert_eq!(select(&doc, ".summary.missing").unwrap(), Value::Null, "missing keys select null");
+        assert_eq!(select(&doc, ".packages[9].name").unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_json_pointers() {
+        let doc = document();
+        assert_eq!(select(&doc, "").unwrap(), doc);
+        assert_eq!(select(&doc, "/summary/total_bundle_size").unwrap(), json!(670000));
+        assert_eq!(select(&doc, "/packages/0/name").unwrap(), json!("react"));
+        assert_eq!(select(&doc, "/summary/missing").unwrap_err(), "nothing at '/summary/missing'");
+    }
+
+    #[test]
+    fn test_errors() {
+        let doc = document();
+        for (path, error) in [
+            ("summary", "'summary' is neither a jq-style path (.a.b) nor a JSON pointer (/a/b)"),
+            (".packages.name", ".packages is an array, which has no key 'name'"),
+            (".summary[0]", ".summary is an object, which has no index [0]"),
+            (".summary.total_packages[]", ".summary.total_packages is a number, which has no elements"),
+            (".packages[x]", "invalid index '[x]' in '.packages[x]'"),
+            (".packages[0", "unclosed '[' in '.packages[0'"),
+            (".summary..total", "expected a key after '.' in '.summary..total'"),
+        ] {
+            assert_eq!(select(&doc, path).unwrap_err(), error, "{}", path);
+        }
+    }
+}
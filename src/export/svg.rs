@@ -0,0 +1,350 @@
+//! SVG image of the dependency graph.
+//!
+//! Draws a [`LayeredLayout`] without Graphviz: packages are boxes colored
+//! by dependency type, taller the larger their bundle size, with curved
+//! links running down from dependents to their dependencies. Edges that
+//! close a cycle are drawn dashed. With the `png` feature the image can be
+//! rasterized with [`render_png`](super::png::render_png).
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::bundle::webpack::format_size;
+use crate::graph::layout::{LayeredLayout, Vertex};
+use crate::graph::DependencyType;
+
+/// Margin around the drawing, in pixels.
+const MARGIN: f64 = 20.0;
+/// Horizontal space between boxes of a layer.
+const H_GAP: f64 = 24.0;
+/// Vertical space between layers.
+const V_GAP: f64 = 56.0;
+/// Height of a box without a known size.
+const MIN_HEIGHT: f64 = 28.0;
+/// Extra height of the box with the largest bundle size.
+const SIZE_HEIGHT: f64 = 36.0;
+/// Approximate width of a label character at the font size used.
+const CHAR_WIDTH: f64 = 7.2;
+/// Height of the legend below the drawing.
+const LEGEND_HEIGHT: f64 = 28.0;
+
+/// Image formats the graph can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Scalable vector graphics
+    Svg,
+    /// Rasterized SVG (requires the `png` feature)
+    Png,
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageFormat::Svg => write!(f, "svg"),
+            ImageFormat::Png => write!(f, "png"),
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "svg" => Ok(ImageFormat::Svg),
+            "png" => Ok(ImageFormat::Png),
+            other => Err(format!("unknown image format '{}' (expected: svg, png)", other)),
+        }
+    }
+}
+
+/// Fill color of each kind of node.
+const COLORS: [(&str, &str); 5] = [
+    ("project", "#555555"),
+    ("production", "#4e79a7"),
+    ("development", "#f28e2b"),
+    ("peer", "#59a14f"),
+    ("optional", "#b07aa1"),
+];
+
+fn color(dep_type: Option<DependencyType>) -> &'static str {
+    let index = match dep_type {
+        None => 0,
+        Some(DependencyType::Production) => 1,
+        Some(DependencyType::Development) => 2,
+        Some(DependencyType::Peer) => 3,
+        Some(DependencyType::Optional) => 4,
+    };
+    COLORS[index].1
+}
+
+/// A vertex placed on the canvas.
+#[derive(Debug, Clone, Copy)]
+struct Placed {
+    center_x: f64,
+    top: f64,
+    bottom: f64,
+    width: f64,
+}
+
+/// Renders a graph layout as an SVG image.
+#[derive(Debug, Clone)]
+pub struct SvgExporter {
+    /// Whether to draw the color legend
+    legend: bool,
+}
+
+impl Default for SvgExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgExporter {
+    /// Creates an exporter that draws a legend.
+    pub fn new() -> Self {
+        Self { legend: true }
+    }
+
+    /// Sets whether the color legend is drawn.
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Renders the layout as a standalone SVG document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::SvgExporter;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    /// use codescope::graph::layout::LayeredLayout;
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    ///
+    /// let svg = SvgExporter::new().export(&LayeredLayout::project(&graph, "my-app", None));
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(">react</text>"));
+    /// ```
+    pub fn export(&self, layout: &LayeredLayout) -> String {
+        let largest = layout.nodes.iter().filter_map(|n| n.bundle_size).max().unwrap_or(0);
+        let size_of = |vertex: Vertex| -> (f64, f64) {
+            match layout.node(vertex) {
+                None => (0.0, 0.0),
+                Some(node) => {
+                    let width = node.name.chars().count() as f64 * CHAR_WIDTH + 24.0;
+                    let scale = match (node.bundle_size, largest) {
+                        (Some(size), largest) if largest > 0 => (size as f64 / largest as f64).sqrt(),
+                        _ => 0.0,
+                    };
+                    (width, MIN_HEIGHT + SIZE_HEIGHT * scale)
+                }
+            }
+        };
+
+        // Place the layers top to bottom, each centered on the widest.
+        let layer_widths: Vec<f64> = layout
+            .layers
+            .iter()
+            .map(|layer| {
+                layer.iter().map(|&v| size_of(v).0).sum::<f64>() + H_GAP * layer.len().saturating_sub(1) as f64
+            })
+            .collect();
+        let width = layer_widths.iter().copied().fold(0.0, f64::max);
+        let mut placed: Vec<Vec<Placed>> = Vec::new();
+        let mut y = MARGIN;
+        for (layer, layer_width) in layout.layers.iter().zip(&layer_widths) {
+            let band = layer.iter().map(|&v| size_of(v).1).fold(0.0, f64::max);
+            let mut x = MARGIN + (width - layer_width) / 2.0;
+            let row = layer
+                .iter()
+                .map(|&v| {
+                    let (w, h) = size_of(v);
+                    let center_y = y + band / 2.0;
+                    let vertex = Placed {
+                        center_x: x + w / 2.0,
+                        top: center_y - h / 2.0,
+                        bottom: center_y + h / 2.0,
+                        width: w,
+                    };
+                    x += w + H_GAP;
+                    vertex
+                })
+                .collect();
+            placed.push(row);
+            y += band + V_GAP;
+        }
+        let graph_bottom = y - V_GAP + MARGIN;
+        // Cycle edges bulge out to the right of the boxes they connect.
+        let bulge_room = if layout.back_edges.is_empty() { 0.0 } else { V_GAP };
+        let total_width = width + MARGIN * 2.0 + bulge_room;
+        let total_height = graph_bottom + if self.legend { LEGEND_HEIGHT } else { 0.0 };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="Helvetica, Arial, sans-serif" font-size="12">"##,
+            w = total_width,
+            h = total_height
+        );
+        svg.push_str(concat!(
+            "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto-start-reverse\">",
+            "<path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#888888\"/></marker></defs>\n",
+        ));
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n");
+
+        svg.push_str("<g fill=\"none\" stroke=\"#888888\" stroke-width=\"1.2\">\n");
+        for link in &layout.links {
+            let upper = placed[link.layer][link.from];
+            let lower = placed[link.layer + 1][link.to];
+            let arrow = matches!(layout.layers[link.layer + 1][link.to], Vertex::Node(_));
+            let middle = (upper.bottom + lower.top) / 2.0;
+            let _ = writeln!(
+                svg,
+                r#"<path d="M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}"{}/>"#,
+                upper.center_x,
+                upper.bottom,
+                upper.center_x,
+                middle,
+                lower.center_x,
+                middle,
+                lower.center_x,
+                lower.top,
+                if arrow { r#" marker-end="url(#arrow)""# } else { "" }
+            );
+        }
+        let position = |node: usize| {
+            layout.layers.iter().enumerate().find_map(|(l, layer)| {
+                layer.iter().position(|&v| v == Vertex::Node(node)).map(|pos| placed[l][pos])
+            })
+        };
+        for &(from, to) in &layout.back_edges {
+            if let (Some(source), Some(target)) = (position(from), position(to)) {
+                let (x1, x2) = (source.center_x + source.width / 2.0, target.center_x + target.width / 2.0);
+                let bulge = x1.max(x2) + V_GAP;
+                let _ = writeln!(
+                    svg,
+                    r##"<path d="M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}" stroke="#e15759" stroke-dasharray="4 3" marker-end="url(#arrow)"/>"##,
+                    x1,
+                    (source.top + source.bottom) / 2.0,
+                    bulge,
+                    (source.top + source.bottom) / 2.0,
+                    bulge,
+                    (target.top + target.bottom) / 2.0,
+                    x2,
+                    (target.top + target.bottom) / 2.0
+                );
+            }
+        }
+        svg.push_str("</g>\n");
+
+        for (l, layer) in layout.layers.iter().enumerate() {
+            for (pos, &vertex) in layer.iter().enumerate() {
+                let Some(node) = layout.node(vertex) else {
+                    continue;
+                };
+                let at = placed[l][pos];
+                let mut title = node.name.clone();
+                if !node.version.is_empty() {
+                    title.push_str(&format!(" {}", node.version));
+                }
+                if let Some(size) = node.bundle_size {
+                    title.push_str(&format!(" ({})", format_size(size)));
+                }
+                let _ = writeln!(
+                    svg,
+                    concat!(
+                        r#"<g><title>{}</title><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="6" fill="{}"/>"#,
+                        r##"<text x="{:.1}" y="{:.1}" fill="#ffffff" text-anchor="middle" dominant-baseline="central">{}</text></g>"##
+                    ),
+                    escape_xml(&title),
+                    at.center_x - at.width / 2.0,
+                    at.top,
+                    at.width,
+                    at.bottom - at.top,
+                    color(node.dep_type),
+                    at.center_x,
+                    (at.top + at.bottom) / 2.0,
+                    escape_xml(&node.name)
+                );
+            }
+        }
+
+        if self.legend {
+            let mut x = MARGIN;
+            let y = graph_bottom;
+            for (label, fill) in COLORS {
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{:.1}" y="{:.1}" width="12" height="12" rx="2" fill="{}"/><text x="{:.1}" y="{:.1}" fill="#333333" dominant-baseline="central">{}</text>"##,
+                    x,
+                    y,
+                    fill,
+                    x + 16.0,
+                    y + 6.0,
+                    label
+                );
+                x += 16.0 + label.len() as f64 * CHAR_WIDTH + 16.0;
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyGraph;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_svg_nodes_links_and_sizes() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+        graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+        graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 1);
+        graph.add_edge("react-dom", "react");
+        graph.add_edge("react", "react-dom");
+        let mut sizes = HashMap::new();
+        sizes.insert("react-dom".to_string(), (130_000_u64, 1_usize));
+        sizes.insert("react".to_string(), (6_500_u64, 1_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        let svg = SvgExporter::new().export(&LayeredLayout::project(&graph, "<app>", None));
+        assert!(svg.contains(">&lt;app&gt;</text>"));
+        assert_eq!(svg.matches("<rect x=").count(), 4 + COLORS.len(), "three packages, the root and the legend");
+        assert_eq!(svg.matches("marker-end").count(), 4, "three links and one cycle edge");
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+        assert!(svg.contains("#f28e2b"), "development dependencies are orange");
+
+        // The largest package gets the tallest box.
+        let height = |name: &str| -> f64 {
+            let title = svg.find(&format!("<title>{} ", name)).unwrap();
+            let rect = &svg[title..];
+            let start = rect.find("height=\"").unwrap() + 8;
+            rect[start..start + rect[start..].find('"').unwrap()].parse().unwrap()
+        };
+        assert!(height("react-dom") > height("react"));
+        assert!(height("react") > height("jest"));
+
+        let without_legend = SvgExporter::new().with_legend(false).export(&LayeredLayout::project(&graph, "app", None));
+        assert!(!without_legend.contains(">optional</text>"));
+    }
+}
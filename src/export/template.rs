@@ -0,0 +1,200 @@
+//! Template exporter.
+//!
+//! Renders a report through a user-supplied [Handlebars] template, so a
+//! team can match its internal report format without a new built-in
+//! exporter. The template sees the document of the JSON export (`project`,
+//! `summary`, `packages`, `cycles`, `conflicts`, ...) with two additions:
+//!
+//! - `savings`: `summary` and `packages` of the savings report, or `null`
+//!   when savings were not calculated
+//! - `findings`: the policy findings, each with `check`, `message`,
+//!   `details`, `packages` and `owners`
+//!
+//! The `size` helper formats a byte count, e.g. `{{size bundle_size}}`.
+//! Output is not HTML-escaped unless enabled with
+//! [`TemplateExporter::with_html_escape`].
+//!
+//! ```text
+//! # {{project.name}}
+//! {{#each packages}}
+//! - {{name}}@{{version}} ({{size bundle_size}})
+//! {{/each}}
+//! ```
+//!
+//! Only available with the `templates` cargo feature.
+//!
+//! [Handlebars]: https://handlebarsjs.com/guide/
+
+use handlebars::{handlebars_helper, no_escape, Handlebars};
+use serde_json::{json, Value};
+
+use crate::bundle::webpack::format_size;
+use crate::policy::Finding;
+
+use super::{ExportReport, JsonExporter};
+
+/// Name the template is registered under
+const TEMPLATE_NAME: &str = "report";
+
+handlebars_helper!(size: |bytes: Json| bytes.as_u64().map(format_size).unwrap_or_default());
+
+/// Renders an `ExportReport` through a Handlebars template.
+pub struct TemplateExporter {
+    registry: Handlebars<'static>,
+    /// Policy findings to pass to the template
+    findings: Vec<Finding>,
+}
+
+impl TemplateExporter {
+    /// Creates an exporter from the source of a template, failing if it
+    /// does not parse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::export::{ExportReport, TemplateExporter};
+    ///
+    /// let exporter = TemplateExporter::new("{{project.name}}: {{summary.total_packages}} packages").unwrap();
+    /// let report = ExportReport { project_name: "my-app".to_string(), ..Default::default() };
+    /// assert_eq!(exporter.export(&report).unwrap(), "my-app: 0 packages");
+    /// assert!(TemplateExporter::new("{{#each packages}}").is_err());
+    /// ```
+    pub fn new(source: &str) -> Result<Self, String> {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(no_escape);
+        registry.register_helper("size", Box::new(size));
+        registry
+            .register_template_string(TEMPLATE_NAME, source)
+            .map_err(|e| format!("invalid template: {}", e))?;
+        Ok(Self {
+            registry,
+            findings: Vec::new(),
+        })
+    }
+
+    /// Sets whether values are HTML-escaped, for HTML templates.
+    pub fn with_html_escape(mut self, escape: bool) -> Self {
+        if escape {
+            self.registry.unregister_escape_fn();
+        } else {
+            self.registry.register_escape_fn(no_escape);
+        }
+        self
+    }
+
+    /// Sets the policy findings passed to the template.
+    pub fn with_findings(mut self, findings: Vec<Finding>) -> Self {
+        self.findings = findings;
+        self
+    }
+
+    /// Renders the report.
+    pub fn export(&self, report: &ExportReport) -> Result<String, String> {
+        self.registry
+            .render(TEMPLATE_NAME, &self.context(report))
+            .map_err(|e| format!("failed to render template: {}", e))
+    }
+
+    /// Returns the data the template is rendered with.
+    pub fn context(&self, report: &ExportReport) -> Value {
+        let mut context: Value = serde_json::from_str(&JsonExporter::new().with_pretty(false).export(report))
+            .expect("the JSON export is valid JSON");
+        context["savings"] = report.savings.as_ref().map_or(Value::Null, |savings| {
+            json!({
+                "summary": {
+                    "total_potential_savings": savings.summary.total_potential_savings,
+                    "total_bundle_size": savings.summary.total_bundle_size,
+                    "packages_with_savings": savings.summary.packages_with_savings,
+                    "savings_percentage": savings.summary.savings_percentage(),
+                },
+                "packages": savings
+                    .savings_by_size()
+                    .into_iter()
+                    .map(|s| json!({
+                        "name": s.package_name,
+                        "current_size": s.current_size,
+                        "potential_savings": s.potential_savings,
+                        "category": s.category.label(),
+                        "utilization": s.utilization_percentage,
+                        "suggestion": s.suggestion,
+                        "alternative": s.alternative,
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        });
+        context["findings"] = serde_json::to_value(&self.findings).expect("findings serialize to JSON");
+        context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::savings::{PackageSavings, SavingsCategory, SavingsReport};
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        let mut report = ExportReport::from_graph("my-app", "1.0.0", &graph);
+        for pkg in &mut report.packages {
+            if pkg.name == "lodash" {
+                pkg.bundle_size = Some(2048);
+            }
+        }
+        report
+    }
+
+    #[test]
+    fn test_render_packages() {
+        let template = "# {{project.name}} {{project.version}}\n{{#each packages}}- {{name}} {{size bundle_size}}\n{{/each}}";
+        let out = TemplateExporter::new(template).unwrap().export(&create_test_report()).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "# my-app 1.0.0");
+        assert!(lines[1].starts_with("- lodash 2"), "{}", lines[1]);
+        assert_eq!(lines[2], "- react ", "unknown sizes render empty");
+    }
+
+    #[test]
+    fn test_savings_and_findings() {
+        let mut savings = SavingsReport::default();
+        savings.add(PackageSavings {
+            package_name: "lodash".to_string(),
+            current_size: 2048,
+            potential_savings: 1024,
+            category: SavingsCategory::Underutilized,
+            utilization_percentage: Some(10.0),
+            exports_used: 1,
+            total_exports: Some(10),
+            suggestion: "Import lodash/debounce".to_string(),
+            alternative: None,
+        });
+        let report = create_test_report().with_savings(savings);
+        let findings = vec![Finding::new("cycles", "1 circular dependency", 1)];
+        let template = "{{#if savings}}{{#each savings.packages}}{{name}}: {{potential_savings}}{{/each}}{{/if}}\
+                        {{#each findings}} [{{check}}] {{message}}{{/each}}";
+        let out = TemplateExporter::new(template)
+            .unwrap()
+            .with_findings(findings)
+            .export(&report)
+            .unwrap();
+        assert_eq!(out, "lodash: 1024 [cycles] 1 circular dependency");
+
+        let out = TemplateExporter::new("{{#if savings}}yes{{else}}no{{/if}}")
+            .unwrap()
+            .export(&create_test_report())
+            .unwrap();
+        assert_eq!(out, "no");
+    }
+
+    #[test]
+    fn test_html_escape() {
+        let mut report = create_test_report();
+        report.project_name = "<app>".to_string();
+        let exporter = TemplateExporter::new("{{project.name}}").unwrap();
+        assert_eq!(exporter.export(&report).unwrap(), "<app>");
+        let exporter = exporter.with_html_escape(true);
+        assert_eq!(exporter.export(&report).unwrap(), "&lt;app&gt;");
+    }
+}
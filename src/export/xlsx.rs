@@ -0,0 +1,367 @@
+//! Excel (xlsx) exporter.
+//!
+//! Produces a workbook with one sheet per section of the report:
+//! dependencies, bundle sizes, cycles, conflicts, savings, groups and owners,
+//! followed by an "About" sheet with the report metadata, if attached.
+//! Each sheet has a bold, frozen header row and an autofilter over its data.
+//!
+//! Only available with the `xlsx` cargo feature.
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use super::ExportReport;
+
+/// Default column width in characters
+const COLUMN_WIDTH: f64 = 18.0;
+
+/// A single cell value.
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl Cell {
+    fn text(value: impl Into<String>) -> Self {
+        Cell::Text(value.into())
+    }
+
+    fn number<T: Into<f64>>(value: Option<T>) -> Self {
+        value.map(|v| Cell::Number(v.into())).unwrap_or(Cell::Empty)
+    }
+}
+
+/// Format-independent description of one worksheet.
+#[derive(Debug, Clone)]
+struct Sheet {
+    name: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<Cell>>,
+}
+
+/// Renders an `ExportReport` as an xlsx workbook.
+#[derive(Debug, Clone, Default)]
+pub struct XlsxExporter;
+
+impl XlsxExporter {
+    /// Creates a new xlsx exporter.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Renders the report to the bytes of an xlsx file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the workbook cannot be assembled.
+    pub fn export(&self, report: &ExportReport) -> Result<Vec<u8>, XlsxError> {
+        let mut workbook = Workbook::new();
+        let header_format = Format::new().set_bold();
+
+        for sheet in build_sheets(report) {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(sheet.name)?;
+
+            for (col, header) in sheet.headers.iter().enumerate() {
+                worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+                worksheet.set_column_width(col as u16, COLUMN_WIDTH)?;
+            }
+
+            for (i, row) in sheet.rows.iter().enumerate() {
+                let row_idx = (i + 1) as u32;
+                for (col, cell) in row.iter().enumerate() {
+                    match cell {
+                        Cell::Text(text) => {
+                            worksheet.write_string(row_idx, col as u16, text)?;
+                        }
+                        Cell::Number(n) => {
+                            worksheet.write_number(row_idx, col as u16, *n)?;
+                        }
+                        Cell::Empty => {}
+                    }
+                }
+            }
+
+            worksheet.set_freeze_panes(1, 0)?;
+            let last_col = sheet.headers.len().saturating_sub(1) as u16;
+            worksheet.autofilter(0, 0, sheet.rows.len() as u32, last_col)?;
+        }
+
+        workbook.save_to_buffer()
+    }
+}
+
+/// Builds the contents of every sheet in the workbook.
+fn build_sheets(report: &ExportReport) -> Vec<Sheet> {
+    let dependencies = Sheet {
+        name: "Dependencies",
+        headers: &["Package", "Version", "Type", "Depth", "Direct", "Dependents", "License", "In cycle", "Conflict"],
+        rows: report
+            .packages
+            .iter()
+            .map(|p| {
+                vec![
+                    Cell::text(&p.name),
+                    Cell::text(&p.version),
+                    Cell::text(p.dep_type.to_string()),
+                    Cell::Number(p.depth as f64),
+                    Cell::text(yes_no(p.is_direct())),
+                    Cell::Number(p.dependents as f64),
+                    p.license.as_deref().map(Cell::text).unwrap_or(Cell::Empty),
+                    Cell::text(yes_no(p.in_cycle)),
+                    Cell::text(yes_no(p.has_conflict)),
+                ]
+            })
+            .collect(),
+    };
+
+    let bundle_sizes = Sheet {
+        name: "Bundle Sizes",
+        headers: &["Package", "Bundle size (bytes)", "Gzip size (bytes)", "Modules", "Utilization (%)"],
+        rows: report
+            .largest_packages(usize::MAX)
+            .into_iter()
+            .map(|p| {
+                vec![
+                    Cell::text(&p.name),
+                    Cell::number(p.bundle_size.map(|s| s as f64)),
+                    Cell::number(p.gzip_size.map(|s| s as f64)),
+                    Cell::number(p.module_count.map(|c| c as f64)),
+                    Cell::number(p.utilization),
+                ]
+            })
+            .collect(),
+    };
+
+    let cycles = Sheet {
+        name: "Cycles",
+        headers: &["#", "Packages", "Path", "Suggested break"],
+        rows: report
+            .cycles
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                vec![
+                    Cell::Number((i + 1) as f64),
+                    Cell::Number(c.len() as f64),
+                    Cell::text(c.cycle_path()),
+                    c.suggested_break
+                        .as_ref()
+                        .map_or(Cell::Empty, |fix| Cell::text(fix.to_string())),
+                ]
+            })
+            .collect(),
+    };
+
+    let conflicts = Sheet {
+        name: "Conflicts",
+        headers: &["Package", "Version", "Required by"],
+        rows: report
+            .conflicts
+            .iter()
+            .flat_map(|c| {
+                c.requirements.iter().map(move |r| {
+                    vec![
+                        Cell::text(&c.package_name),
+                        Cell::text(&r.version),
+                        Cell::text(&r.required_by),
+                    ]
+                })
+            })
+            .collect(),
+    };
+
+    let savings = Sheet {
+        name: "Savings",
+        headers: &["Package", "Category", "Current size (bytes)", "Potential savings (bytes)", "Utilization (%)", "Suggestion", "Alternative"],
+        rows: report
+            .savings
+            .as_ref()
+            .map(|s| {
+                s.savings_by_size()
+                    .into_iter()
+                    .map(|p| {
+                        vec![
+                            Cell::text(&p.package_name),
+                            Cell::text(p.category.label()),
+                            Cell::Number(p.current_size as f64),
+                            Cell::Number(p.potential_savings as f64),
+                            Cell::number(p.utilization_percentage),
+                            Cell::text(&p.suggestion),
+                            p.alternative.as_deref().map(Cell::text).unwrap_or(Cell::Empty),
+                        ]
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let groups = Sheet {
+        name: "Groups",
+        headers: &["Group", "Packages", "Bundle size (bytes)", "Size budget (KB)", "Count budget", "Over budget", "Members"],
+        rows: report
+            .groups
+            .iter()
+            .map(|g| {
+                vec![
+                    Cell::text(&g.name),
+                    Cell::Number(g.count() as f64),
+                    Cell::number(g.has_sizes().then_some(g.bundle_size as f64)),
+                    Cell::number(g.max_size_kb.map(|kb| kb as f64)),
+                    Cell::number(g.max_count.map(|c| c as f64)),
+                    Cell::text(yes_no(g.over_budget())),
+                    Cell::text(g.packages.join(", ")),
+                ]
+            })
+            .collect(),
+    };
+
+    let owners = Sheet {
+        name: "Owners",
+        headers: &["Owner", "Packages", "Bundle size (bytes)", "Potential savings (bytes)", "In cycle", "Conflicts", "Members"],
+        rows: report
+            .owners
+            .iter()
+            .map(|o| {
+                vec![
+                    Cell::text(&o.owner),
+                    Cell::Number(o.count() as f64),
+                    Cell::Number(o.bundle_size as f64),
+                    Cell::Number(o.potential_savings as f64),
+                    Cell::Number(o.cycle_packages as f64),
+                    Cell::Number(o.conflict_packages as f64),
+                    Cell::text(o.packages.join(", ")),
+                ]
+            })
+            .collect(),
+    };
+
+    let mut sheets = vec![dependencies, bundle_sizes, cycles, conflicts, savings, groups, owners];
+    if let Some(metadata) = &report.metadata {
+        sheets.push(Sheet {
+            name: "About",
+            headers: &["Field", "Value"],
+            rows: metadata
+                .fields()
+                .into_iter()
+                .map(|(label, value)| vec![Cell::text(label), Cell::text(value)])
+                .collect(),
+        });
+    }
+    sheets
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::savings::{PackageSavings, SavingsCategory, SavingsReport};
+    use crate::graph::{DependencyGraph, DependencyType};
+    use std::collections::HashMap;
+
+    fn create_test_report() -> ExportReport {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "2.0.0", DependencyType::Development);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        graph.track_version_requirement("b", "^1.0.0", "a");
+        graph.track_version_requirement("b", "^2.0.0", "app");
+
+        let mut sizes = HashMap::new();
+        sizes.insert("a".to_string(), (2048_u64, 4_usize));
+        graph.apply_bundle_sizes(&sizes);
+
+        ExportReport::from_graph("app", "1.0.0", &graph)
+    }
+
+    fn sheet<'a>(sheets: &'a [Sheet], name: &str) -> &'a Sheet {
+        sheets.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_sheet_names() {
+        let sheets = build_sheets(&create_test_report());
+        let names: Vec<&str> = sheets.iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["Dependencies", "Bundle Sizes", "Cycles", "Conflicts", "Savings", "Groups", "Owners"]);
+
+        let report = create_test_report().with_metadata(crate::export::ReportMetadata::new("."));
+        let sheets = build_sheets(&report);
+        assert_eq!(sheets.last().unwrap().name, "About");
+        assert_eq!(sheets.last().unwrap().rows[0][0], Cell::text("codescope"));
+    }
+
+    #[test]
+    fn test_rows_match_header_width() {
+        let sheets = build_sheets(&create_test_report());
+        for sheet in &sheets {
+            for row in &sheet.rows {
+                assert_eq!(row.len(), sheet.headers.len(), "sheet {}", sheet.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sheet_contents() {
+        let sheets = build_sheets(&create_test_report());
+
+        assert_eq!(sheet(&sheets, "Dependencies").rows.len(), 2);
+
+        let sizes = sheet(&sheets, "Bundle Sizes");
+        assert_eq!(sizes.rows.len(), 1);
+        assert_eq!(sizes.rows[0][1], Cell::Number(2048.0));
+        assert_eq!(sizes.rows[0][2], Cell::Empty);
+
+        assert_eq!(sheet(&sheets, "Cycles").rows.len(), 1);
+        assert_eq!(sheet(&sheets, "Conflicts").rows.len(), 2);
+        assert!(sheet(&sheets, "Savings").rows.is_empty());
+        assert!(sheet(&sheets, "Groups").rows.is_empty());
+        assert!(sheet(&sheets, "Owners").rows.is_empty());
+    }
+
+    #[test]
+    fn test_savings_sheet() {
+        let mut report = create_test_report();
+        report.savings = Some(SavingsReport {
+            package_savings: vec![PackageSavings {
+                package_name: "moment".to_string(),
+                current_size: 1000,
+                potential_savings: 800,
+                category: SavingsCategory::HasAlternative,
+                utilization_percentage: None,
+                exports_used: 1,
+                total_exports: None,
+                suggestion: "Switch".to_string(),
+                alternative: Some("dayjs".to_string()),
+            }],
+            ..Default::default()
+        });
+
+        let sheets = build_sheets(&report);
+        let savings = sheet(&sheets, "Savings");
+        assert_eq!(savings.rows.len(), 1);
+        assert_eq!(savings.rows[0][1], Cell::text("Alternative available"));
+        assert_eq!(savings.rows[0][6], Cell::text("dayjs"));
+    }
+
+    #[test]
+    fn test_export_produces_xlsx() {
+        let bytes = XlsxExporter::new().export(&create_test_report()).unwrap();
+        // xlsx files are zip archives
+        assert!(bytes.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn test_export_empty_report() {
+        let bytes = XlsxExporter::new().export(&ExportReport::default()).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Which commit introduced each direct dependency.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::parser::{extract_dependencies, parse_str};
+
+use super::{Commit, GitResult, Repository};
+
+/// Finds the commit that added each direct dependency of the project's
+/// package.json, following the history reachable from `rev`.
+///
+/// A dependency that was removed and added again is attributed to the
+/// commit that added it back. Revisions whose package.json cannot be parsed
+/// are skipped.
+///
+/// # Returns
+///
+/// The introducing commit per dependency name, for every dependency in
+/// package.json at `rev`.
+pub fn blame_dependencies(repository: &Repository, rev: &str) -> GitResult<BTreeMap<String, Commit>> {
+    let mut origins: BTreeMap<String, Commit> = BTreeMap::new();
+
+    for commit in repository.history(rev, "package.json")? {
+        let Some(pkg) = repository
+            .read_file(&commit.commit, "package.json")?
+            .and_then(|content| parse_str(&content).ok())
+        else {
+            continue;
+        };
+
+        let names: BTreeSet<String> = extract_dependencies(&pkg).into_iter().map(|d| d.name).collect();
+        origins.retain(|name, _| names.contains(name));
+        for name in names {
+            origins.entry(name).or_insert_with(|| commit.clone());
+        }
+    }
+
+    Ok(origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{git, tests::create_repo};
+    use std::fs;
+
+    #[test]
+    fn test_blame_dependencies() {
+        let root = create_repo("blame");
+        let app = root.join("app");
+        let commit = |message: &str, manifest: &str| {
+            fs::write(app.join("package.json"), manifest).unwrap();
+            for args in [&["add", "."][..], &["commit", "-q", "-m", message]] {
+                assert!(git(&root, args).unwrap().status.success());
+            }
+        };
+        commit("add react", r#"{"dependencies": {"react": "^18.0.0"}}"#);
+        commit(
+            "add lodash",
+            r#"{"dependencies": {"react": "^18.2.0", "lodash": "^4.0.0"}, "devDependencies": {"jest": "^29.0.0"}}"#,
+        );
+        commit("drop lodash", r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"jest": "^29.0.0"}}"#);
+        commit("broken", "{");
+        commit("restore lodash", r#"{"dependencies": {"react": "^18.2.0", "lodash": "^4.0.0"}, "devDependencies": {"jest": "^29.0.0"}}"#);
+
+        let repository = Repository::discover(&app).unwrap();
+        let origins = blame_dependencies(&repository, "HEAD").unwrap();
+        let summaries: Vec<(&str, &str)> = origins
+            .iter()
+            .map(|(name, commit)| (name.as_str(), commit.summary.as_str()))
+            .collect();
+        assert_eq!(
+            summaries,
+            vec![("jest", "add lodash"), ("lodash", "restore lodash"), ("react", "add react")]
+        );
+
+        // History stops at the requested revision
+        let at_v1 = blame_dependencies(&repository, "v1").unwrap();
+        assert!(at_v1.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
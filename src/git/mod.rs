@@ -0,0 +1,372 @@
+//! Reading project files from git revisions
+//!
+//! `--rev` analyzes a project as it was at a commit, branch or tag without
+//! checking it out: manifests are read straight from the git object
+//! database, so the working tree may be dirty or on another branch. This
+//! module drives the `git` command-line tool, which every CI image that
+//! has a clone already provides.
+//!
+//! The same history powers [`blame_dependencies`], which finds the commit
+//! that introduced each direct dependency.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::path::Path;
+//! use codescope::git::RevisionFiles;
+//!
+//! let main = RevisionFiles::open(Path::new("."), "main")?;
+//! let manifest = main.read("package.json")?.expect("package.json at main");
+//! println!("{} at {}", manifest.len(), main.short_commit());
+//! ```
+
+mod blame;
+
+pub use blame::blame_dependencies;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde::Serialize;
+use thiserror::Error;
+
+/// Errors that can occur while reading from git.
+#[derive(Error, Debug)]
+pub enum GitError {
+    /// The `git` executable could not be run.
+    #[error("Failed to run git: {0}")]
+    Spawn(#[from] io::Error),
+
+    /// The directory is not inside a git work tree.
+    #[error("Not a git repository: {}", .0.display())]
+    NotARepository(PathBuf),
+
+    /// The revision does not name a commit.
+    #[error("Unknown revision: {0}")]
+    UnknownRevision(String),
+
+    /// A git command failed for another reason.
+    #[error("git {command} failed: {stderr}")]
+    Command { command: String, stderr: String },
+}
+
+/// Result type alias for git operations.
+pub type GitResult<T> = Result<T, GitError>;
+
+/// A commit as shown in history and blame views.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Commit {
+    /// Full commit hash
+    pub commit: String,
+    /// Author name
+    pub author: String,
+    /// Author date, `YYYY-MM-DD`
+    pub date: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+impl Commit {
+    /// Returns the abbreviated commit hash.
+    pub fn short_commit(&self) -> &str {
+        &self.commit[..self.commit.len().min(7)]
+    }
+}
+
+/// A git repository containing the analyzed project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    /// Top-level directory of the work tree
+    root: PathBuf,
+    /// Path of the project directory relative to `root`, `/`-separated,
+    /// empty or ending in `/`
+    prefix: String,
+}
+
+impl Repository {
+    /// Finds the repository that contains `project_dir`.
+    pub fn discover(project_dir: &Path) -> GitResult<Self> {
+        let output = git(project_dir, &["rev-parse", "--show-toplevel", "--show-prefix"])?;
+        if !output.status.success() {
+            return Err(GitError::NotARepository(project_dir.to_path_buf()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        let root = lines.next().unwrap_or_default();
+        let prefix = lines.next().unwrap_or_default();
+        Ok(Self {
+            root: PathBuf::from(root),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    /// Returns the top-level directory of the work tree.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolves a commit, branch, tag or other revision to a commit hash.
+    pub fn resolve(&self, rev: &str) -> GitResult<String> {
+        let spec = format!("{}^{{commit}}", rev);
+        let output = git(&self.root, &["rev-parse", "--verify", "--quiet", &spec])?;
+        if !output.status.success() {
+            return Err(GitError::UnknownRevision(rev.to_string()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Reads a file of the project directory at `commit`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the file does not exist at that commit.
+    pub fn read_file(&self, commit: &str, name: &str) -> GitResult<Option<String>> {
+        let object = format!("{}:{}{}", commit, self.prefix, name);
+        if !git(&self.root, &["cat-file", "-e", &object])?.status.success() {
+            return Ok(None);
+        }
+
+        let output = git(&self.root, &["cat-file", "blob", &object])?;
+        if !output.status.success() {
+            return Err(GitError::Command {
+                command: format!("cat-file blob {}", object),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+    }
+
+    /// Lists the files of the project directory changed since `base`,
+    /// relative to the project directory.
+    ///
+    /// With a `head` revision this compares `head` with its merge base with
+    /// `base`, like `git diff base...head`. Without one it compares the
+    /// working tree, including untracked files, with that merge base.
+    pub fn changed_files(&self, base: &str, head: Option<&str>) -> GitResult<Vec<String>> {
+        let head_commit = self.resolve(head.unwrap_or("HEAD"))?;
+        let base_commit = self.resolve(base)?;
+        let merge_base = self.run(&["merge-base", &base_commit, &head_commit])?;
+        let merge_base = merge_base.trim();
+
+        let mut files: Vec<String> = match head {
+            Some(_) => self.run(&["diff", "--name-only", "--no-renames", merge_base, &head_commit])?,
+            None => {
+                let mut out = self.run(&["diff", "--name-only", "--no-renames", merge_base])?;
+                out.push_str(&self.run(&["ls-files", "--others", "--exclude-standard"])?);
+                out
+            }
+        }
+        .lines()
+        .filter_map(|path| path.strip_prefix(self.prefix.as_str()))
+        .map(String::from)
+        .collect();
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Runs a git command at the top level and returns its stdout.
+    fn run(&self, args: &[&str]) -> GitResult<String> {
+        let output = git(&self.root, args)?;
+        if !output.status.success() {
+            return Err(GitError::Command {
+                command: args.join(" "),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Lists the commits reachable from `rev` that changed a file of the
+    /// project directory, oldest first.
+    pub fn history(&self, rev: &str, name: &str) -> GitResult<Vec<Commit>> {
+        let path = format!("{}{}", self.prefix, name);
+        let args = [
+            "log",
+            "--reverse",
+            "--date=short",
+            "--format=%H%x1f%an%x1f%ad%x1f%s",
+            rev,
+            "--",
+            &path,
+        ];
+        let output = git(&self.root, &args)?;
+        if !output.status.success() {
+            return Err(GitError::Command {
+                command: format!("log {} -- {}", rev, path),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\x1f');
+                Some(Commit {
+                    commit: fields.next()?.to_string(),
+                    author: fields.next()?.to_string(),
+                    date: fields.next()?.to_string(),
+                    summary: fields.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+/// The files of a project directory at one revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionFiles {
+    repository: Repository,
+    rev: String,
+    commit: String,
+}
+
+impl RevisionFiles {
+    /// Opens the project in `project_dir` at revision `rev`.
+    pub fn open(project_dir: &Path, rev: &str) -> GitResult<Self> {
+        let repository = Repository::discover(project_dir)?;
+        let commit = repository.resolve(rev)?;
+        Ok(Self {
+            repository,
+            rev: rev.to_string(),
+            commit,
+        })
+    }
+
+    /// Returns the repository the files are read from.
+    pub fn repository(&self) -> &Repository {
+        &self.repository
+    }
+
+    /// Returns the revision as given.
+    pub fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// Returns the full hash of the resolved commit.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// Returns the abbreviated hash of the resolved commit.
+    pub fn short_commit(&self) -> &str {
+        &self.commit[..self.commit.len().min(7)]
+    }
+
+    /// Reads a file of the project directory, such as `package.json`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the file does not exist at this revision.
+    pub fn read(&self, name: &str) -> GitResult<Option<String>> {
+        self.repository.read_file(&self.commit, name)
+    }
+}
+
+/// Runs git in `dir` and captures its output.
+fn git(dir: &Path, args: &[&str]) -> GitResult<Output> {
+    Ok(Command::new("git").arg("-C").arg(dir).args(args).output()?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Creates a repository with the project in `app/` and two commits.
+    pub(super) fn create_repo(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("codescope-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("app")).unwrap();
+
+        let run = |args: &[&str]| {
+            let output = git(&root, args).unwrap();
+            assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        };
+        run(&["init", "-q", "-b", "main"]);
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["config", "user.name", "Test"]);
+        fs::write(root.join("app/package.json"), r#"{"name": "v1"}"#).unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "v1"]);
+        run(&["tag", "v1"]);
+        fs::write(root.join("app/package.json"), r#"{"name": "v2"}"#).unwrap();
+        fs::write(root.join("app/package-lock.json"), "{}").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "v2"]);
+
+        // Uncommitted changes are ignored
+        fs::write(root.join("app/package.json"), r#"{"name": "dirty"}"#).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_read_files_at_revision() {
+        let root = create_repo("read");
+        let app = root.join("app");
+
+        let v1 = RevisionFiles::open(&app, "v1").unwrap();
+        assert_eq!(v1.read("package.json").unwrap().as_deref(), Some(r#"{"name": "v1"}"#));
+        assert_eq!(v1.read("package-lock.json").unwrap(), None);
+        assert_eq!(v1.short_commit().len(), 7);
+
+        let head = RevisionFiles::open(&app, "HEAD").unwrap();
+        assert_eq!(head.read("package.json").unwrap().as_deref(), Some(r#"{"name": "v2"}"#));
+        assert_ne!(head.commit(), v1.commit());
+        assert_eq!(head.rev(), "HEAD");
+
+        let history = head.repository().history("HEAD", "package.json").unwrap();
+        let summaries: Vec<&str> = history.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["v1", "v2"]);
+        assert_eq!(history[0].commit, v1.commit());
+        assert_eq!(history[0].author, "Test");
+        assert_eq!(history[0].date.len(), 10);
+        assert_eq!(head.repository().history("v1", "package-lock.json").unwrap(), vec![]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let root = create_repo("changed");
+        let repository = Repository::discover(&root.join("app")).unwrap();
+        fs::write(root.join("outside.txt"), "not in the project").unwrap();
+        fs::write(root.join("app/new.js"), "").unwrap();
+
+        assert_eq!(
+            repository.changed_files("v1", Some("HEAD")).unwrap(),
+            vec!["package-lock.json", "package.json"]
+        );
+        assert_eq!(
+            repository.changed_files("HEAD", None).unwrap(),
+            vec!["new.js", "package.json"],
+            "working tree changes and untracked files"
+        );
+        assert!(matches!(
+            repository.changed_files("no-such-branch", None),
+            Err(GitError::UnknownRevision(_))
+        ));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_errors() {
+        let root = create_repo("errors");
+        assert!(matches!(
+            RevisionFiles::open(&root, "no-such-branch"),
+            Err(GitError::UnknownRevision(_))
+        ));
+        let _ = fs::remove_dir_all(&root);
+
+        let outside = std::env::temp_dir().join(format!("codescope-git-none-{}", std::process::id()));
+        fs::create_dir_all(&outside).unwrap();
+        // The temp directory itself is not expected to be inside a repository
+        if Repository::discover(&outside).is_err() {
+            assert!(matches!(Repository::discover(&outside), Err(GitError::NotARepository(_))));
+        }
+        let _ = fs::remove_dir_all(&outside);
+    }
+}
@@ -0,0 +1,268 @@
+//! Condensation of a dependency graph.
+//!
+//! Packages that depend on each other in a cycle form a strongly connected
+//! component. Collapsing each component into a single node leaves an
+//! acyclic graph, the condensation, whose structure stays readable however
+//! tangled the cycles are. [`Condensation::to_graph`] turns it back into a
+//! [`DependencyGraph`], so it can be laid out, drawn and exported like any
+//! other.
+
+use std::collections::{BTreeSet, HashMap};
+
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
+
+use super::{DependencyGraph, DependencyNode, DependencyType};
+
+/// A strongly connected component of the dependency graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Name of the node standing for the component: the package name for
+    /// a single package, `{name +N}` for a cycle
+    pub label: String,
+    /// Package names, starting with the shallowest (ties broken by name)
+    pub members: Vec<String>,
+}
+
+impl Component {
+    /// Returns true if the component collapses a cycle of several packages.
+    pub fn is_cycle(&self) -> bool {
+        self.members.len() > 1
+    }
+}
+
+/// The acyclic graph of the strongly connected components of a dependency
+/// graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condensation {
+    /// Components, in the order of their first member in the graph
+    pub components: Vec<Component>,
+    /// Edges between components, as indices into `components`; edges
+    /// inside a component are left out
+    pub edges: BTreeSet<(usize, usize)>,
+    /// Index of the component of each package
+    component_of: HashMap<String, usize>,
+}
+
+impl Condensation {
+    /// Collapses each strongly connected component of `graph`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::condensation::Condensation;
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// for name in ["app", "a", "b", "c"] {
+    ///     graph.add_dependency(name, "1.0.0", DependencyType::Production);
+    /// }
+    /// graph.add_edge("app", "a");
+    /// graph.add_edge("a", "b");
+    /// graph.add_edge("b", "a");
+    /// graph.add_edge("b", "c");
+    ///
+    /// let condensed = Condensation::of(&graph);
+    /// assert_eq!(condensed.components.len(), 3);
+    /// let cycle = condensed.component_of("b").unwrap();
+    /// assert_eq!(cycle.members, vec!["a", "b"]);
+    /// assert!(!condensed.to_graph(&graph).has_cycles());
+    /// ```
+    pub fn of(graph: &DependencyGraph) -> Self {
+        let nodes = graph.get_all_nodes();
+        let index: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, node)| (node.name.as_str(), i)).collect();
+
+        let mut shape: DiGraph<usize, ()> = DiGraph::with_capacity(nodes.len(), graph.edge_count());
+        let ids: Vec<_> = (0..nodes.len()).map(|i| shape.add_node(i)).collect();
+        for (i, node) in nodes.iter().enumerate() {
+            for dependency in graph.get_dependencies(&node.name) {
+                shape.add_edge(ids[i], ids[index[dependency.name.as_str()]], ());
+            }
+        }
+
+        // Tarjan returns components in reverse topological order; keep the
+        // graph's own order instead, which follows discovery from the root
+        let mut sccs: Vec<Vec<usize>> = tarjan_scc(&shape)
+            .into_iter()
+            .map(|scc| {
+                let mut members: Vec<usize> = scc.into_iter().map(|id| shape[id]).collect();
+                members.sort_by(|&a, &b| {
+                    (nodes[a].depth, &nodes[a].name).cmp(&(nodes[b].depth, &nodes[b].name))
+                });
+                members
+            })
+            .collect();
+        sccs.sort_by_key(|members| members.iter().min().copied());
+
+        let mut owner = vec![0; nodes.len()];
+        let components = sccs
+            .iter()
+            .enumerate()
+            .map(|(c, members)| {
+                for &member in members {
+                    owner[member] = c;
+                }
+                let members: Vec<String> = members.iter().map(|&m| nodes[m].name.clone()).collect();
+                Component {
+                    label: label(&members),
+                    members,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let edges = shape
+            .raw_edges()
+            .iter()
+            .map(|edge| (owner[shape[edge.source()]], owner[shape[edge.target()]]))
+            .filter(|(from, to)| from != to)
+            .collect();
+        let component_of = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.name.clone(), owner[i]))
+            .collect();
+
+        Self {
+            components,
+            edges,
+            component_of,
+        }
+    }
+
+    /// Returns the component a package belongs to.
+    pub fn component_of(&self, package: &str) -> Option<&Component> {
+        self.component_of.get(package).map(|&c| &self.components[c])
+    }
+
+    /// Returns the components that collapse a cycle.
+    pub fn cycles(&self) -> impl Iterator<Item = &Component> {
+        self.components.iter().filter(|component| component.is_cycle())
+    }
+
+    /// Returns the components a component depends on.
+    pub fn dependencies(&self, component: &Component) -> Vec<&Component> {
+        self.neighbours(component, |(from, to)| (from, to))
+    }
+
+    /// Returns the components depending on a component.
+    pub fn dependents(&self, component: &Component) -> Vec<&Component> {
+        self.neighbours(component, |(from, to)| (to, from))
+    }
+
+    fn neighbours(&self, component: &Component, orient: fn((usize, usize)) -> (usize, usize)) -> Vec<&Component> {
+        let Some(&index) = component.members.first().and_then(|m| self.component_of.get(m)) else {
+            return Vec::new();
+        };
+        self.edges
+            .iter()
+            .map(|&edge| orient(edge))
+            .filter(|&(from, _)| from == index)
+            .map(|(_, to)| &self.components[to])
+            .collect()
+    }
+
+    /// Builds the condensed graph, with one node per component.
+    ///
+    /// A single package keeps its version, type, depth and bundle size. A
+    /// cycle becomes a node named after the component's label, at the depth
+    /// of its shallowest member, with the number of members as its version,
+    /// the summed bundle size of its members, and the type most tied to the
+    /// runtime among them: the cycle ships if any member does.
+    pub fn to_graph(&self, graph: &DependencyGraph) -> DependencyGraph {
+        let mut condensed = DependencyGraph::with_capacity(self.components.len(), self.edges.len());
+        for component in &self.components {
+            let members: Vec<_> = component.members.iter().filter_map(|m| graph.get_node(m)).collect();
+            let Some(first) = members.first() else {
+                continue;
+            };
+            let (version, dep_type) = if component.is_cycle() {
+                (format!("{} packages", members.len()), strongest_type(&members))
+            } else {
+                (first.version.clone(), first.dep_type)
+            };
+            condensed.add_dependency_with_depth(&component.label, &version, dep_type, first.depth);
+
+            let sizes: Vec<(u64, usize)> = members
+                .iter()
+                .filter_map(|m| m.bundle_size.map(|size| (size, m.module_count.unwrap_or(0))))
+                .collect();
+            if !sizes.is_empty() {
+                let (size, modules) = sizes.iter().fold((0, 0), |(s, n), (size, modules)| (s + size, n + modules));
+                if let Some(node) = condensed.get_node_mut(&component.label) {
+                    node.set_bundle_size(size, modules);
+                }
+            }
+        }
+        for &(from, to) in &self.edges {
+            condensed.add_edge(&self.components[from].label, &self.components[to].label);
+        }
+        condensed
+    }
+}
+
+/// Names a component: the package itself, or its first member and the
+/// number of others in braces, which no package name contains.
+fn label(members: &[String]) -> String {
+    if members.len() == 1 {
+        return members[0].clone();
+    }
+    format!("{{{} +{}}}", members[0], members.len() - 1)
+}
+
+/// Returns the type most tied to the runtime among the members of a cycle.
+fn strongest_type(members: &[&DependencyNode]) -> DependencyType {
+    [DependencyType::Production, DependencyType::Peer, DependencyType::Optional]
+        .into_iter()
+        .find(|dep_type| members.iter().any(|m| m.dep_type == *dep_type))
+        .unwrap_or(DependencyType::Development)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// app -> a -> b -> c -> a, c -> d -> e -> d, app -> f
+    fn tangled_graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        for (name, depth) in [("app", 0), ("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5), ("f", 1)] {
+            graph.add_dependency_with_depth(name, "1.0.0", DependencyType::Production, depth);
+        }
+        for (from, to) in [("app", "a"), ("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e"), ("e", "d"), ("app", "f")] {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_condensation_collapses_each_cycle() {
+        let graph = tangled_graph();
+        let condensed = Condensation::of(&graph);
+
+        let labels: Vec<&str> = condensed.components.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["app", "{a +2}", "{d +1}", "f"]);
+        assert_eq!(condensed.cycles().count(), 2);
+        assert_eq!(condensed.component_of("c").unwrap().members, vec!["a", "b", "c"]);
+
+        let cycle = condensed.component_of("a").unwrap();
+        let names = |components: Vec<&Component>| components.into_iter().map(|c| c.label.clone()).collect::<Vec<_>>();
+        assert_eq!(names(condensed.dependencies(cycle)), vec!["{d +1}"]);
+        assert_eq!(names(condensed.dependents(cycle)), vec!["app"]);
+    }
+
+    #[test]
+    fn test_condensed_graph_is_acyclic() {
+        let mut graph = tangled_graph();
+        graph.get_node_mut("d").unwrap().set_bundle_size(1000, 3);
+        graph.get_node_mut("e").unwrap().set_bundle_size(500, 2);
+
+        let condensed = Condensation::of(&graph).to_graph(&graph);
+        assert!(graph.has_cycles());
+        assert!(!condensed.has_cycles());
+        assert_eq!((condensed.node_count(), condensed.edge_count()), (4, 3));
+
+        let node = condensed.get_node("{d +1}").unwrap();
+        assert_eq!((node.version.as_str(), node.depth), ("2 packages", 4));
+        assert_eq!((node.bundle_size, node.module_count), (Some(1500), Some(5)));
+        assert_eq!(condensed.get_node("f").unwrap().version, "1.0.0");
+    }
+}
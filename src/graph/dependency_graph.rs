@@ -4,10 +4,14 @@
 //! with support for different dependency types, cycle detection, and traversal.
 
 use petgraph::algo::is_cyclic_directed;
-use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::graph::{DiGraph, EdgeReference, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::parser::lockfile::{install_name, is_installed_path, LockedPackage, PackageLock};
+use crate::parser::{DependencySource, DependencyType as ParsedDependencyType, Scope};
+use crate::parser::range::parse_version;
 
 /// Represents the type of dependency relationship.
 ///
@@ -26,6 +30,51 @@ pub enum DependencyType {
     Optional,
 }
 
+impl DependencyType {
+    /// Ranks how strongly a type ties a package to the runtime, used to pick
+    /// the effective type of a package reached along several paths.
+    fn runtime_rank(self) -> u8 {
+        match self {
+            Self::Production => 3,
+            Self::Peer => 2,
+            Self::Optional => 1,
+            Self::Development => 0,
+        }
+    }
+
+    /// Returns the weaker of two types: a dev dependency of a production
+    /// package stays dev, a production dependency of a dev package is dev.
+    fn weaker(self, other: Self) -> Self {
+        if self.runtime_rank() <= other.runtime_rank() {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+impl From<DependencyType> for ParsedDependencyType {
+    fn from(dep_type: DependencyType) -> Self {
+        match dep_type {
+            DependencyType::Production => ParsedDependencyType::Production,
+            DependencyType::Development => ParsedDependencyType::Development,
+            DependencyType::Peer => ParsedDependencyType::Peer,
+            DependencyType::Optional => ParsedDependencyType::Optional,
+        }
+    }
+}
+
+impl From<ParsedDependencyType> for DependencyType {
+    fn from(dep_type: ParsedDependencyType) -> Self {
+        match dep_type {
+            ParsedDependencyType::Production => DependencyType::Production,
+            ParsedDependencyType::Development => DependencyType::Development,
+            ParsedDependencyType::Peer => DependencyType::Peer,
+            ParsedDependencyType::Optional => DependencyType::Optional,
+        }
+    }
+}
+
 impl std::fmt::Display for DependencyType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -54,6 +103,10 @@ pub struct DependencyNode {
     pub bundle_size: Option<u64>,
     /// Number of modules from this package included in the bundle
     pub module_count: Option<usize>,
+    /// Where the package is installed from, parsed from `version`
+    pub source: DependencySource,
+    /// Version the lockfile resolved a direct dependency's range to
+    pub resolved_version: Option<String>,
 }
 
 impl DependencyNode {
@@ -79,13 +132,16 @@ impl DependencyNode {
         version: impl Into<String>,
         dep_type: DependencyType,
     ) -> Self {
+        let version = version.into();
         Self {
             name: name.into(),
-            version: version.into(),
+            source: DependencySource::parse(&version),
+            version,
             dep_type,
             depth: 0,
             bundle_size: None,
             module_count: None,
+            resolved_version: None,
         }
     }
 
@@ -96,13 +152,16 @@ impl DependencyNode {
         dep_type: DependencyType,
         depth: usize,
     ) -> Self {
+        let version = version.into();
         Self {
             name: name.into(),
-            version: version.into(),
+            source: DependencySource::parse(&version),
+            version,
             dep_type,
             depth,
             bundle_size: None,
             module_count: None,
+            resolved_version: None,
         }
     }
 
@@ -114,13 +173,16 @@ impl DependencyNode {
         bundle_size: u64,
         module_count: usize,
     ) -> Self {
+        let version = version.into();
         Self {
             name: name.into(),
-            version: version.into(),
+            source: DependencySource::parse(&version),
+            version,
             dep_type,
             depth: 0,
             bundle_size: Some(bundle_size),
             module_count: Some(module_count),
+            resolved_version: None,
         }
     }
 
@@ -134,6 +196,42 @@ impl DependencyNode {
     pub fn has_bundle_size(&self) -> bool {
         self.bundle_size.is_some()
     }
+
+    /// Returns the installed version: the version the lockfile resolved,
+    /// or `version` itself if it is a single version.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyNode, DependencyType};
+    ///
+    /// assert_eq!(DependencyNode::new("react", "18.2.0", DependencyType::Production).installed_version(), Some("18.2.0"));
+    /// assert_eq!(DependencyNode::new("react", "^18.2.0", DependencyType::Production).installed_version(), None);
+    /// ```
+    pub fn installed_version(&self) -> Option<&str> {
+        self.resolved_version
+            .as_deref()
+            .or_else(|| parse_version(&self.version).map(|_| self.version.as_str()))
+    }
+
+    /// Returns the name of the package that is actually installed,
+    /// resolving `npm:` aliases.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyNode, DependencyType};
+    ///
+    /// let node = DependencyNode::new("lodash-es5", "npm:lodash@^3", DependencyType::Production);
+    /// assert_eq!(node.package_name(), "lodash");
+    /// assert_eq!(node.source.label(), "alias");
+    /// ```
+    pub fn package_name(&self) -> &str {
+        match &self.source {
+            DependencySource::Alias { package, .. } => package,
+            _ => &self.name,
+        }
+    }
 }
 
 /// Represents an edge in the dependency graph.
@@ -144,6 +242,13 @@ impl DependencyNode {
 pub struct DependencyEdge {
     /// Whether this dependency is optional
     pub is_optional: bool,
+    /// Which dependency list of the dependent package declares this edge
+    pub dep_type: DependencyType,
+    /// Version range the dependent package requests, where known
+    pub requested: Option<String>,
+    /// Version installed for the dependent package, where known; nested
+    /// copies make it differ from the version of the target node
+    pub resolved: Option<String>,
 }
 
 impl DependencyEdge {
@@ -154,7 +259,77 @@ impl DependencyEdge {
 
     /// Creates a new optional dependency edge.
     pub fn optional() -> Self {
-        Self { is_optional: true }
+        Self::of_type(DependencyType::Optional)
+    }
+
+    /// Creates an edge declared in the given dependency list.
+    pub fn of_type(dep_type: DependencyType) -> Self {
+        Self {
+            is_optional: dep_type == DependencyType::Optional,
+            dep_type,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the version range the dependent package requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyEdge, DependencyType};
+    ///
+    /// let edge = DependencyEdge::of_type(DependencyType::Production)
+    ///     .with_requested("^4.17.0")
+    ///     .with_resolved("4.17.21");
+    /// assert_eq!(edge.requested.as_deref(), Some("^4.17.0"));
+    /// assert_eq!(edge.resolved.as_deref(), Some("4.17.21"));
+    /// ```
+    pub fn with_requested(mut self, range: impl Into<String>) -> Self {
+        self.requested = Some(range.into());
+        self
+    }
+
+    /// Sets the version installed for the dependent package.
+    pub fn with_resolved(mut self, version: impl Into<String>) -> Self {
+        self.resolved = Some(version.into());
+        self
+    }
+
+    /// Returns the type this edge passes on to its target.
+    fn effective_type(&self) -> DependencyType {
+        if self.is_optional {
+            self.dep_type.weaker(DependencyType::Optional)
+        } else {
+            self.dep_type
+        }
+    }
+}
+
+/// The project a dependency graph was built for.
+///
+/// The project itself is not a node of the graph: its direct dependencies
+/// are the nodes at depth 0, and the root records which ones they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphRoot {
+    /// Name of the project, where known
+    pub name: Option<String>,
+    /// Names of the project's direct dependencies
+    pub dependencies: Vec<String>,
+}
+
+impl GraphRoot {
+    /// Creates a root with the given direct dependencies.
+    pub fn new<S: Into<String>>(dependencies: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            name: None,
+            dependencies: dependencies.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Sets the name of the project.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
     }
 }
 
@@ -192,6 +367,8 @@ pub struct DependencyGraph {
     node_indices: HashMap<String, NodeIndex>,
     /// Tracks version requirements for each package: package_name -> [(version, required_by)]
     version_requirements: HashMap<String, Vec<VersionRequirement>>,
+    /// The project the graph was built for, if set
+    root: Option<GraphRoot>,
 }
 
 impl Default for DependencyGraph {
@@ -216,6 +393,7 @@ impl DependencyGraph {
             graph: DiGraph::new(),
             node_indices: HashMap::new(),
             version_requirements: HashMap::new(),
+            root: None,
         }
     }
 
@@ -233,6 +411,7 @@ impl DependencyGraph {
             graph: DiGraph::with_capacity(nodes, edges),
             node_indices: HashMap::with_capacity(nodes),
             version_requirements: HashMap::with_capacity(nodes),
+            root: None,
         }
     }
 
@@ -376,6 +555,433 @@ impl DependencyGraph {
         true
     }
 
+    /// Removes a package and every edge to or from it.
+    ///
+    /// The version requirements on the package and those it recorded on
+    /// other packages are dropped, and so is its place among the root's
+    /// direct dependencies. Packages that only it depended on stay in the
+    /// graph; [`unreachable_nodes`](Self::unreachable_nodes) lists them.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Package name
+    ///
+    /// # Returns
+    ///
+    /// The removed node, or `None` if the package is not in the graph.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    /// graph.add_dependency("scheduler", "0.23.0", DependencyType::Production);
+    /// graph.add_dependency("loose-envify", "1.4.0", DependencyType::Production);
+    /// graph.add_edge("react", "loose-envify");
+    /// graph.add_edge("scheduler", "loose-envify");
+    ///
+    /// assert_eq!(graph.remove_dependency("react").unwrap().version, "18.2.0");
+    /// assert_eq!(graph.get_dependents("loose-envify")[0].name, "scheduler");
+    /// assert!(graph.remove_dependency("react").is_none());
+    /// ```
+    pub fn remove_dependency(&mut self, name: &str) -> Option<DependencyNode> {
+        let idx = self.node_indices.remove(name)?;
+        // petgraph moves the last node into the freed index, so the one
+        // entry pointing at the last index has to follow it
+        let last = NodeIndex::new(self.graph.node_count() - 1);
+        let node = self.graph.remove_node(idx)?;
+        if idx != last {
+            self.node_indices.insert(self.graph[idx].name.clone(), idx);
+        }
+
+        self.version_requirements.remove(name);
+        self.version_requirements.retain(|_, requirements| {
+            requirements.retain(|requirement| requirement.required_by != name);
+            !requirements.is_empty()
+        });
+        if let Some(root) = &mut self.root {
+            root.dependencies.retain(|dependency| dependency != name);
+        }
+        Some(node)
+    }
+
+    /// Removes the edge from one package to another, with the version
+    /// requirement `from` recorded on `to`. Both packages stay in the
+    /// graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The dependent package name
+    /// * `to` - The dependency package name
+    ///
+    /// # Returns
+    ///
+    /// The removed edge, or `None` if there is no such edge.
+    pub fn remove_edge(&mut self, from: &str, to: &str) -> Option<DependencyEdge> {
+        let (from_idx, to_idx) = (*self.node_indices.get(from)?, *self.node_indices.get(to)?);
+        let edge = self.graph.find_edge(from_idx, to_idx)?;
+        let removed = self.graph.remove_edge(edge)?;
+
+        if let Some(requirements) = self.version_requirements.get_mut(to) {
+            requirements.retain(|requirement| requirement.required_by != from);
+            if requirements.is_empty() {
+                self.version_requirements.remove(to);
+            }
+        }
+        Some(removed)
+    }
+
+    /// Adds the resolved install tree of an npm lockfile to the graph.
+    ///
+    /// Packages are walked breadth-first from the root's dependencies, so
+    /// each package gets its shallowest depth. Nodes are keyed by install
+    /// name, like direct dependencies; packages already in the graph keep
+    /// their version and type. Transitive packages are added as production
+    /// dependencies; call [`propagate_dependency_types`](Self::propagate_dependency_types)
+    /// afterwards to classify them.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock` - The parsed lockfile
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes added.
+    pub fn extend_from_lockfile(&mut self, lock: &PackageLock) -> usize {
+        let before = self.node_count();
+        let version = |path: &str| lock.packages[path].version.clone().unwrap_or_default();
+
+        let mut queue = VecDeque::new();
+        if let Some(root) = lock.packages.get("") {
+            for (name, _, dep_type) in locked_edges(root, true) {
+                if let Some(path) = lock.resolve("", name) {
+                    let idx = self.add_dependency_with_depth(install_name(path), &version(path), dep_type, 0);
+                    self.graph[idx].resolved_version = lock.packages[path].version.clone();
+                    queue.push_back((path, 0));
+                }
+            }
+        }
+
+        let mut visited = HashSet::new();
+        while let Some((path, depth)) = queue.pop_front() {
+            if !visited.insert(path) {
+                continue;
+            }
+            // Links (workspaces, `file:` deps) take their dependencies from the target folder
+            let entry = &lock.packages[path];
+            let dir = match (entry.link, entry.resolved.as_deref()) {
+                (true, Some(target)) if lock.packages.contains_key(target) => target,
+                (true, _) => continue,
+                _ => path,
+            };
+
+            let from = install_name(path);
+            for (name, range, dep_type) in locked_edges(&lock.packages[dir], !is_installed_path(dir)) {
+                let Some(child) = lock.resolve(dir, name) else {
+                    continue;
+                };
+                let to = install_name(child);
+                self.add_dependency_with_depth(to, &version(child), DependencyType::Production, depth + 1);
+
+                let (from_idx, to_idx) = (self.node_indices[from], self.node_indices[to]);
+                if self.graph.find_edge(from_idx, to_idx).is_none() {
+                    let mut edge = DependencyEdge::of_type(dep_type).with_requested(range);
+                    edge.resolved = lock.packages[child].version.clone();
+                    self.graph.add_edge(from_idx, to_idx, edge);
+                }
+                queue.push_back((child, depth + 1));
+            }
+        }
+
+        self.node_count() - before
+    }
+
+    /// Derives the effective type of transitive packages from the direct
+    /// dependencies that pull them in.
+    ///
+    /// Direct dependencies (depth 0) keep their declared type. Every other
+    /// package reached through edges gets the strongest type over all paths
+    /// to it, where each path is as weak as its weakest link: a package
+    /// required only through devDependencies is dev, one reachable from any
+    /// production dependency through required production edges is
+    /// production. Unreached packages are left unchanged.
+    ///
+    /// # Returns
+    ///
+    /// The number of packages whose type changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency_with_depth("jest", "29.0.0", DependencyType::Development, 0);
+    /// graph.add_dependency_with_depth("chalk", "4.1.2", DependencyType::Production, 1);
+    /// graph.add_edge("jest", "chalk");
+    ///
+    /// assert_eq!(graph.propagate_dependency_types(), 1);
+    /// assert_eq!(graph.get_node("chalk").unwrap().dep_type, DependencyType::Development);
+    /// ```
+    pub fn propagate_dependency_types(&mut self) -> usize {
+        let mut effective: HashMap<NodeIndex, DependencyType> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for idx in self.graph.node_indices() {
+            if self.graph[idx].depth == 0 {
+                effective.insert(idx, self.graph[idx].dep_type);
+                queue.push_back(idx);
+            }
+        }
+
+        // Types only ever get stronger, so each node is revisited a bounded number of times
+        while let Some(idx) = queue.pop_front() {
+            let parent = effective[&idx];
+            for edge in self.graph.edges_directed(idx, Direction::Outgoing) {
+                let child = edge.target();
+                if self.graph[child].depth == 0 {
+                    continue;
+                }
+                let candidate = parent.weaker(edge.weight().effective_type());
+                let improves = effective
+                    .get(&child)
+                    .is_none_or(|current| candidate.runtime_rank() > current.runtime_rank());
+                if improves {
+                    effective.insert(child, candidate);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        let mut changed = 0;
+        for (idx, dep_type) in effective {
+            let node = &mut self.graph[idx];
+            if node.dep_type != dep_type {
+                node.dep_type = dep_type;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Removes every package outside `scope`, along with its edges and
+    /// version requirements.
+    ///
+    /// Run this after [`propagate_dependency_types`](Self::propagate_dependency_types)
+    /// so transitive packages are judged by their effective type.
+    ///
+    /// # Returns
+    ///
+    /// The number of packages removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    /// use codescope::parser::Scope;
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    /// graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+    ///
+    /// assert_eq!(graph.retain_scope(Scope::Production), 1);
+    /// assert!(graph.get_node("jest").is_none());
+    /// assert!(graph.get_node("react").is_some());
+    /// ```
+    pub fn retain_scope(&mut self, scope: Scope) -> usize {
+        if scope == Scope::All {
+            return 0;
+        }
+
+        let before = self.node_count();
+        self.graph
+            .retain_nodes(|graph, idx| scope.includes(graph[idx].dep_type.into()));
+
+        // Removal shifts node indices, so rebuild the lookup table
+        let previous = std::mem::take(&mut self.node_indices);
+        self.node_indices = self
+            .graph
+            .node_indices()
+            .map(|idx| (self.graph[idx].name.clone(), idx))
+            .collect();
+        let kept = &self.node_indices;
+        let removed: HashSet<&String> = previous.keys().filter(|name| !kept.contains_key(*name)).collect();
+
+        // Requirements from removed packages go with them; requesters that
+        // were never nodes, like the project itself, stay. A package left
+        // with fewer than two distinct ranges no longer has a conflict to
+        // report.
+        self.version_requirements.retain(|name, requirements| {
+            if !kept.contains_key(name) {
+                return false;
+            }
+            let count = requirements.len();
+            requirements.retain(|requirement| !removed.contains(&requirement.required_by));
+            if requirements.len() == count {
+                return true;
+            }
+            let ranges: HashSet<&str> = requirements.iter().map(|r| r.version.as_str()).collect();
+            ranges.len() >= 2
+        });
+
+        before - self.node_count()
+    }
+
+    /// Recomputes the depth of every package as its shortest distance from
+    /// the project, walking the edges breadth-first from the direct
+    /// dependencies.
+    ///
+    /// Depths set while building the graph go stale once packages are
+    /// removed, and are all 0 for graphs built without a lockfile walk.
+    /// Names in `roots` that are not in the graph are ignored; packages
+    /// that cannot be reached from the roots keep their depth.
+    ///
+    /// # Arguments
+    ///
+    /// * `roots` - The project's direct dependencies, at depth 0
+    ///
+    /// # Returns
+    ///
+    /// The number of packages whose depth changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// for name in ["react", "react-dom", "scheduler"] {
+    ///     graph.add_dependency(name, "1.0.0", DependencyType::Production);
+    /// }
+    /// graph.add_edge("react", "react-dom");
+    /// graph.add_edge("react-dom", "scheduler");
+    ///
+    /// assert_eq!(graph.compute_depths(&["react"]), 2);
+    /// assert_eq!(graph.get_node("scheduler").unwrap().depth, 2);
+    /// ```
+    pub fn compute_depths<S: AsRef<str>>(&mut self, roots: &[S]) -> usize {
+        let mut changed = 0;
+        for (idx, depth) in self.distances_from(roots) {
+            if self.graph[idx].depth != depth {
+                self.graph[idx].depth = depth;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Returns the shortest distance of every package reachable from
+    /// `roots`, which are at distance 0.
+    fn distances_from<S: AsRef<str>>(&self, roots: &[S]) -> HashMap<NodeIndex, usize> {
+        let mut distances: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut queue: VecDeque<NodeIndex> = roots
+            .iter()
+            .filter_map(|root| self.node_indices.get(root.as_ref()).copied())
+            .collect();
+        for &idx in &queue {
+            distances.insert(idx, 0);
+        }
+
+        while let Some(idx) = queue.pop_front() {
+            let distance = distances[&idx] + 1;
+            for child in self.graph.neighbors_directed(idx, Direction::Outgoing) {
+                if let std::collections::hash_map::Entry::Vacant(entry) = distances.entry(child) {
+                    entry.insert(distance);
+                    queue.push_back(child);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Records the project the graph was built for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType, GraphRoot};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+    /// graph.add_dependency("left-pad", "1.3.0", DependencyType::Production);
+    /// graph.set_root(GraphRoot::new(["react"]).with_name("my-app"));
+    ///
+    /// assert_eq!(graph.root().unwrap().name.as_deref(), Some("my-app"));
+    /// let stale: Vec<&str> = graph.unreachable_nodes().iter().map(|n| n.name.as_str()).collect();
+    /// assert_eq!(stale, vec!["left-pad"]);
+    /// ```
+    pub fn set_root(&mut self, root: GraphRoot) {
+        self.root = Some(root);
+    }
+
+    /// Returns the project the graph was built for, if it was recorded.
+    pub fn root(&self) -> Option<&GraphRoot> {
+        self.root.as_ref()
+    }
+
+    /// Returns the packages that cannot be reached from the root's direct
+    /// dependencies, sorted by name.
+    ///
+    /// These are usually stale lockfile entries, or packages only kept
+    /// alive by dependencies that were removed. Without a root, nothing
+    /// is considered unreachable.
+    pub fn unreachable_nodes(&self) -> Vec<&DependencyNode> {
+        let Some(root) = &self.root else {
+            return Vec::new();
+        };
+        let reachable = self.distances_from(&root.dependencies);
+        let mut nodes: Vec<&DependencyNode> = self
+            .graph
+            .node_indices()
+            .filter(|idx| !reachable.contains_key(idx))
+            .map(|idx| &self.graph[idx])
+            .collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        nodes
+    }
+
+    /// Returns the underlying petgraph graph, to run algorithms this type
+    /// does not wrap.
+    ///
+    /// Node weights are the [`DependencyNode`]s and edge weights the
+    /// [`DependencyEdge`]s; edges point from the dependent package to its
+    /// dependency, and the project itself is not a node. Use
+    /// [`node_index`](Self::node_index) to find a package's index. Indices
+    /// stay valid while packages are only added: removing one with
+    /// [`remove_dependency`](Self::remove_dependency) or
+    /// [`retain_scope`](Self::retain_scope) moves other nodes to new
+    /// indices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    /// use petgraph::algo::dominators::simple_fast;
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// for name in ["next", "react", "react-dom", "scheduler"] {
+    ///     graph.add_dependency(name, "1.0.0", DependencyType::Production);
+    /// }
+    /// graph.add_edge("next", "react");
+    /// graph.add_edge("next", "react-dom");
+    /// graph.add_edge("react-dom", "scheduler");
+    ///
+    /// let root = graph.node_index("next").unwrap();
+    /// let dominators = simple_fast(graph.as_petgraph(), root);
+    /// let scheduler = graph.node_index("scheduler").unwrap();
+    /// let idom = dominators.immediate_dominator(scheduler).unwrap();
+    /// assert_eq!(graph.as_petgraph()[idom].name, "react-dom");
+    /// ```
+    pub fn as_petgraph(&self) -> &DiGraph<DependencyNode, DependencyEdge> {
+        &self.graph
+    }
+
+    /// Returns the index of a package in [`as_petgraph`](Self::as_petgraph).
+    pub fn node_index(&self, name: &str) -> Option<NodeIndex> {
+        self.node_indices.get(name).copied()
+    }
+
     /// Gets a reference to a dependency node by name.
     ///
     /// # Arguments
@@ -463,6 +1069,50 @@ impl DependencyGraph {
             .collect()
     }
 
+    /// Gets the edge from a package to one of its dependencies.
+    ///
+    /// # Returns
+    ///
+    /// The edge metadata, or `None` if there is no such edge.
+    pub fn get_edge(&self, from: &str, to: &str) -> Option<&DependencyEdge> {
+        let (&from, &to) = (self.node_indices.get(from)?, self.node_indices.get(to)?);
+        self.graph.find_edge(from, to).map(|edge| &self.graph[edge])
+    }
+
+    /// Gets the dependents of a package with the edge each one reaches it
+    /// through, telling what version range each dependent asked for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyEdge, DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// for name in ["webpack", "jest", "chalk"] {
+    ///     graph.add_dependency(name, "1.0.0", DependencyType::Production);
+    /// }
+    /// graph.add_edge_with_metadata("webpack", "chalk", DependencyEdge::new().with_requested("^4.1.0"));
+    /// graph.add_edge_with_metadata("jest", "chalk", DependencyEdge::new().with_requested("^5.0.0"));
+    ///
+    /// let mut ranges: Vec<(&str, Option<&str>)> = graph
+    ///     .get_requesters("chalk")
+    ///     .into_iter()
+    ///     .map(|(node, edge)| (node.name.as_str(), edge.requested.as_deref()))
+    ///     .collect();
+    /// ranges.sort();
+    /// assert_eq!(ranges, vec![("jest", Some("^5.0.0")), ("webpack", Some("^4.1.0"))]);
+    /// ```
+    pub fn get_requesters(&self, name: &str) -> Vec<(&DependencyNode, &DependencyEdge)> {
+        let Some(&idx) = self.node_indices.get(name) else {
+            return Vec::new();
+        };
+
+        self.graph
+            .edges_directed(idx, Direction::Incoming)
+            .map(|edge| (&self.graph[edge.source()], edge.weight()))
+            .collect()
+    }
+
     /// Gets all nodes in the graph.
     ///
     /// # Returns
@@ -505,14 +1155,43 @@ impl DependencyGraph {
     ///
     /// assert!(graph.has_cycles());
     /// ```
-    pub fn has_cycles(&self) -> bool {
-        is_cyclic_directed(&self.graph)
+    pub fn has_cycles(&self) -> bool {
+        is_cyclic_directed(&self.graph)
+    }
+
+    /// Detects and returns all cycles in the graph.
+    ///
+    /// Uses depth-first search to find strongly connected components
+    /// that form cycles.
+    ///
+    /// # Returns
+    ///
+    /// A vector of cycles, where each cycle is a vector of package names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("a", "1.0.0", DependencyType::Production);
+    /// graph.add_dependency("b", "1.0.0", DependencyType::Production);
+    /// graph.add_dependency("c", "1.0.0", DependencyType::Production);
+    /// graph.add_edge("a", "b");
+    /// graph.add_edge("b", "c");
+    /// graph.add_edge("c", "a"); // Creates cycle: a -> b -> c -> a
+    ///
+    /// let cycles = graph.detect_cycles();
+    /// assert!(!cycles.is_empty());
+    /// ```
+    pub fn detect_cycles(&self) -> Vec<Vec<String>> {
+        self.cycle_names(false)
     }
 
-    /// Detects and returns all cycles in the graph.
+    /// Detects cycles formed by production dependencies only.
     ///
-    /// Uses depth-first search to find strongly connected components
-    /// that form cycles.
+    /// Edges that are optional, or that lead to dev, peer or optional
+    /// packages, are ignored: cycles through them never exist at runtime.
     ///
     /// # Returns
     ///
@@ -524,44 +1203,53 @@ impl DependencyGraph {
     /// use codescope::graph::{DependencyGraph, DependencyType};
     ///
     /// let mut graph = DependencyGraph::new();
-    /// graph.add_dependency("a", "1.0.0", DependencyType::Production);
-    /// graph.add_dependency("b", "1.0.0", DependencyType::Production);
-    /// graph.add_dependency("c", "1.0.0", DependencyType::Production);
-    /// graph.add_edge("a", "b");
-    /// graph.add_edge("b", "c");
-    /// graph.add_edge("c", "a"); // Creates cycle: a -> b -> c -> a
+    /// graph.add_dependency("app", "1.0.0", DependencyType::Production);
+    /// graph.add_dependency("test-kit", "1.0.0", DependencyType::Development);
+    /// graph.add_edge("app", "test-kit");
+    /// graph.add_edge("test-kit", "app"); // Only closes through a dev dependency
     ///
-    /// let cycles = graph.detect_cycles();
-    /// assert!(!cycles.is_empty());
+    /// assert_eq!(graph.detect_cycles().len(), 1);
+    /// assert!(graph.detect_production_cycles().is_empty());
     /// ```
-    pub fn detect_cycles(&self) -> Vec<Vec<String>> {
+    pub fn detect_production_cycles(&self) -> Vec<Vec<String>> {
+        self.cycle_names(true)
+    }
+
+    fn cycle_names(&self, production_only: bool) -> Vec<Vec<String>> {
+        self.cycle_components(production_only)
+            .into_iter()
+            .map(|scc| scc.iter().map(|&idx| self.graph[idx].name.clone()).collect())
+            .collect()
+    }
+
+    /// Returns the strongly connected components that form cycles.
+    ///
+    /// A component is a cycle if it has more than one node, or if it is a
+    /// single node with a self-loop.
+    fn cycle_components(&self, production_only: bool) -> Vec<Vec<NodeIndex>> {
         use petgraph::algo::tarjan_scc;
+        use petgraph::visit::EdgeFiltered;
 
-        let sccs = tarjan_scc(&self.graph);
-        let mut cycles = Vec::new();
-
-        for scc in sccs {
-            // A strongly connected component is a cycle if it has more than one node,
-            // or if it's a single node with a self-loop
-            if scc.len() > 1 {
-                let cycle: Vec<String> = scc
-                    .iter()
-                    .filter_map(|&idx| self.graph.node_weight(idx))
-                    .map(|node| node.name.clone())
-                    .collect();
-                cycles.push(cycle);
-            } else if scc.len() == 1 {
-                // Check for self-loop
-                let idx = scc[0];
-                if self.graph.contains_edge(idx, idx) {
-                    if let Some(node) = self.graph.node_weight(idx) {
-                        cycles.push(vec![node.name.clone()]);
-                    }
-                }
-            }
-        }
+        let allowed = |edge: EdgeReference<'_, DependencyEdge>| !production_only || self.is_runtime_edge(edge);
+        let filtered = EdgeFiltered::from_fn(&self.graph, allowed);
+
+        tarjan_scc(&filtered)
+            .into_iter()
+            .filter(|scc| {
+                scc.len() > 1
+                    || self
+                        .graph
+                        .edges_connecting(scc[0], scc[0])
+                        .any(allowed)
+            })
+            .collect()
+    }
 
-        cycles
+    /// Returns true if an edge is followed at runtime: it is a required
+    /// production edge leading to a production dependency.
+    fn is_runtime_edge(&self, edge: EdgeReference<'_, DependencyEdge>) -> bool {
+        edge.weight().effective_type() == DependencyType::Production
+            && self.graph[edge.target()].dep_type == DependencyType::Production
     }
 
     /// Returns a set of package names that are part of any cycle.
@@ -610,23 +1298,152 @@ impl DependencyGraph {
     /// Returns detailed cycle information including the cycle path.
     ///
     /// For each cycle detected, returns the list of package names in the order
-    /// they form the cycle (note: the last element connects back to the first).
+    /// they form the cycle (note: the last element connects back to the first),
+    /// starting from the package closest to the root. Each cycle also carries
+    /// a suggested edge whose removal breaks it, when a single edge suffices.
     ///
     /// # Returns
     ///
     /// A vector of `CycleInfo` structs containing cycle details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency_with_depth("a", "1.0.0", DependencyType::Production, 0);
+    /// graph.add_dependency_with_depth("b", "1.0.0", DependencyType::Production, 1);
+    /// graph.add_dependency_with_depth("c", "1.0.0", DependencyType::Production, 2);
+    /// graph.add_edge("b", "c");
+    /// graph.add_edge("c", "a");
+    /// graph.add_edge("a", "b");
+    ///
+    /// let cycle = &graph.get_cycle_details()[0];
+    /// assert_eq!(cycle.cycle_path(), "a -> b -> c -> a");
+    ///
+    /// let fix = cycle.suggested_break.as_ref().unwrap();
+    /// assert_eq!((fix.from.as_str(), fix.to.as_str()), ("c", "a"));
+    /// ```
     pub fn get_cycle_details(&self) -> Vec<CycleInfo> {
-        self.detect_cycles()
+        self.cycle_details(false)
+    }
+
+    /// Returns detailed information about production-only cycles.
+    ///
+    /// See [`detect_production_cycles`](Self::detect_production_cycles) for
+    /// which edges are considered; suggested breaks are chosen among
+    /// production edges only.
+    pub fn get_production_cycle_details(&self) -> Vec<CycleInfo> {
+        self.cycle_details(true)
+    }
+
+    fn cycle_details(&self, production_only: bool) -> Vec<CycleInfo> {
+        self.cycle_components(production_only)
             .into_iter()
-            .map(|nodes| CycleInfo { nodes })
+            .map(|scc| CycleInfo {
+                nodes: self
+                    .order_cycle(&scc, production_only)
+                    .into_iter()
+                    .map(|idx| self.graph[idx].name.clone())
+                    .collect(),
+                suggested_break: self.suggest_cycle_break(&scc, production_only),
+            })
             .collect()
     }
 
+    /// Orders the members of a strongly connected component for display.
+    ///
+    /// Starts at the shallowest package (ties broken by name) and follows
+    /// edges inside the component, so simple cycles come out in edge order.
+    /// Members not reached by that walk are appended by name.
+    fn order_cycle(&self, scc: &[NodeIndex], production_only: bool) -> Vec<NodeIndex> {
+        let members: HashSet<NodeIndex> = scc.iter().copied().collect();
+        let key = |idx: &NodeIndex| (self.graph[*idx].depth, self.graph[*idx].name.clone());
+
+        let mut remaining: Vec<NodeIndex> = scc.to_vec();
+        remaining.sort_by_key(key);
+
+        let mut ordered = Vec::with_capacity(scc.len());
+        let mut visited = HashSet::new();
+        let mut current = remaining.first().copied();
+        while let Some(idx) = current {
+            visited.insert(idx);
+            ordered.push(idx);
+            current = self
+                .graph
+                .edges_directed(idx, Direction::Outgoing)
+                .filter(|edge| !production_only || self.is_runtime_edge(*edge))
+                .map(|edge| edge.target())
+                .filter(|next| members.contains(next) && !visited.contains(next))
+                .min_by_key(key);
+        }
+
+        ordered.extend(remaining.into_iter().filter(|idx| !visited.contains(idx)));
+        ordered
+    }
+
+    /// Picks the edge to remove to break a strongly connected component.
+    ///
+    /// Only edges whose removal alone leaves the component acyclic qualify.
+    /// Among those, non-runtime edges (optional edges, or edges to dev,
+    /// peer or optional packages) win, then edges pointing back toward the
+    /// root, then the edge to the smallest package by bundle size; each
+    /// criterion breaks ties in the previous one.
+    fn suggest_cycle_break(&self, scc: &[NodeIndex], production_only: bool) -> Option<CycleBreak> {
+        use petgraph::visit::EdgeFiltered;
+
+        let members: HashSet<NodeIndex> = scc.iter().copied().collect();
+        let internal: Vec<_> = scc
+            .iter()
+            .flat_map(|&idx| self.graph.edges_directed(idx, Direction::Outgoing))
+            .filter(|edge| members.contains(&edge.target()))
+            .filter(|edge| !production_only || self.is_runtime_edge(*edge))
+            .collect();
+
+        internal
+            .iter()
+            .filter(|candidate| {
+                let remaining = EdgeFiltered::from_fn(&self.graph, |edge| {
+                    edge.id() != candidate.id()
+                        && members.contains(&edge.source())
+                        && members.contains(&edge.target())
+                        && (!production_only || self.is_runtime_edge(edge))
+                });
+                !is_cyclic_directed(&remaining)
+            })
+            .map(|edge| {
+                let from = &self.graph[edge.source()];
+                let to = &self.graph[edge.target()];
+                let runtime = self.is_runtime_edge(*edge);
+                let back_edge = to.depth < from.depth;
+                let weight = (runtime, !back_edge, to.bundle_size.unwrap_or(u64::MAX));
+                (weight, from.name.as_str(), to.name.as_str())
+            })
+            .min()
+            .map(|((runtime, forward, _), from, to)| CycleBreak {
+                from: from.to_string(),
+                to: to.to_string(),
+                reason: if !runtime {
+                    BreakReason::NonRuntime
+                } else if !forward {
+                    BreakReason::BackEdge
+                } else {
+                    BreakReason::Lightest
+                },
+            })
+    }
+
     /// Tracks a version requirement for a package.
     ///
     /// Records that `required_by` package requires `package_name` at `version`.
     /// This information is used to detect version conflicts.
     ///
+    /// `npm:` aliases are recorded against the aliased package with the
+    /// alias range, so `"foo": "npm:bar@^2"` and `"bar": "^2"` are the same
+    /// requirement on `bar`. Git, file and other non-registry specifiers are
+    /// kept verbatim.
+    ///
     /// # Arguments
     ///
     /// * `package_name` - The dependency package name
@@ -649,6 +1466,10 @@ impl DependencyGraph {
         version: &str,
         required_by: &str,
     ) {
+        let source = DependencySource::parse(version);
+        let (package_name, version) = source
+            .registry_target(package_name, version)
+            .unwrap_or((package_name, version));
         let requirements = self
             .version_requirements
             .entry(package_name.to_string())
@@ -656,10 +1477,69 @@ impl DependencyGraph {
         requirements.push(VersionRequirement::new(version, required_by));
     }
 
+    /// Returns every version requirement on every package, conflicting or
+    /// not, keyed by package name.
+    ///
+    /// Combines the requirements recorded with
+    /// [`track_version_requirement`](Self::track_version_requirement), the
+    /// ranges requested along edges, and the ranges the root declares for
+    /// its direct dependencies (required by the root's name, or
+    /// `(project)` if it has none). Each package's requirements are sorted
+    /// by requester, then range, without duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyEdge, DependencyGraph, DependencyType, GraphRoot};
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+    /// graph.add_dependency("loose-envify", "1.4.0", DependencyType::Production);
+    /// graph.add_edge_with_metadata("react", "loose-envify", DependencyEdge::new().with_requested("^1.1.0"));
+    /// graph.set_root(GraphRoot::new(["react"]).with_name("my-app"));
+    ///
+    /// let matrix = graph.requirement_matrix();
+    /// assert_eq!(matrix["react"][0].required_by, "my-app");
+    /// assert_eq!(matrix["loose-envify"][0].version, "^1.1.0");
+    /// ```
+    pub fn requirement_matrix(&self) -> BTreeMap<String, Vec<VersionRequirement>> {
+        let mut matrix: BTreeMap<String, Vec<VersionRequirement>> = BTreeMap::new();
+        for (package, requirements) in &self.version_requirements {
+            matrix.entry(package.clone()).or_default().extend(requirements.iter().cloned());
+        }
+        for edge in self.graph.edge_references() {
+            if let Some(range) = &edge.weight().requested {
+                let (from, to) = (&self.graph[edge.source()], &self.graph[edge.target()]);
+                matrix
+                    .entry(to.name.clone())
+                    .or_default()
+                    .push(VersionRequirement::new(range.as_str(), from.name.as_str()));
+            }
+        }
+        if let Some(root) = &self.root {
+            let project = root.name.as_deref().unwrap_or("(project)");
+            for node in root.dependencies.iter().filter_map(|name| self.get_node(name)) {
+                matrix
+                    .entry(node.name.clone())
+                    .or_default()
+                    .push(VersionRequirement::new(node.version.as_str(), project));
+            }
+        }
+
+        for requirements in matrix.values_mut() {
+            requirements.sort_by(|a, b| (&a.required_by, &a.version).cmp(&(&b.required_by, &b.version)));
+            requirements.dedup();
+        }
+        matrix
+    }
+
     /// Detects version conflicts in the dependency graph.
     ///
     /// A conflict exists when the same package is required at different
-    /// versions by different dependents.
+    /// versions by different dependents. Requirements are gathered like
+    /// [`requirement_matrix`](Self::requirement_matrix) does, so the
+    /// ranges requested along lockfile edges and the root manifest's own
+    /// ranges count as well as tracked ones.
     ///
     /// # Returns
     ///
@@ -681,7 +1561,7 @@ impl DependencyGraph {
     pub fn detect_version_conflicts(&self) -> Vec<VersionConflict> {
         let mut conflicts = Vec::new();
 
-        for (package_name, requirements) in &self.version_requirements {
+        for (package_name, requirements) in self.requirement_matrix() {
             if requirements.len() <= 1 {
                 continue;
             }
@@ -691,8 +1571,8 @@ impl DependencyGraph {
 
             if versions.len() > 1 {
                 conflicts.push(VersionConflict {
-                    package_name: package_name.clone(),
-                    requirements: requirements.clone(),
+                    package_name,
+                    requirements,
                 });
             }
         }
@@ -823,6 +1703,8 @@ pub struct Dependency {
 pub struct CycleInfo {
     /// The package names in the cycle (the last connects back to the first)
     pub nodes: Vec<String>,
+    /// The edge whose removal breaks the cycle, if a single edge suffices
+    pub suggested_break: Option<CycleBreak>,
 }
 
 impl CycleInfo {
@@ -852,6 +1734,92 @@ impl CycleInfo {
     }
 }
 
+/// Why an edge was suggested for breaking a cycle.
+///
+/// Variants are ordered by preference: earlier reasons make for cheaper fixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BreakReason {
+    /// The edge is not needed at runtime (optional, dev or peer dependency)
+    NonRuntime,
+    /// The edge points from a deeper package back toward the root
+    BackEdge,
+    /// The edge leads to the smallest package in the cycle
+    Lightest,
+}
+
+impl BreakReason {
+    /// Returns a stable identifier for machine-readable output.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NonRuntime => "non_runtime",
+            Self::BackEdge => "back_edge",
+            Self::Lightest => "lightest",
+        }
+    }
+
+    /// Returns a short human-readable explanation.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NonRuntime => "not a runtime dependency",
+            Self::BackEdge => "points back toward the root",
+            Self::Lightest => "smallest dependency in the cycle",
+        }
+    }
+}
+
+/// An edge suggested for removal to break a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleBreak {
+    /// The dependent package
+    pub from: String,
+    /// The dependency to drop from `from`
+    pub to: String,
+    /// Why this edge was chosen
+    pub reason: BreakReason,
+}
+
+impl CycleBreak {
+    /// Returns the edge as `from -> to`.
+    pub fn edge(&self) -> String {
+        format!("{} -> {}", self.from, self.to)
+    }
+}
+
+impl std::fmt::Display for CycleBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remove {} ({})", self.edge(), self.reason.description())
+    }
+}
+
+/// Lists the dependencies declared by a lockfile entry, sorted by name.
+///
+/// `include_dev` should only be set for the root and workspace folders.
+fn locked_edges(entry: &LockedPackage, include_dev: bool) -> Vec<(&str, &str, DependencyType)> {
+    let lists = [
+        (&entry.dependencies, DependencyType::Production),
+        (&entry.peer_dependencies, DependencyType::Peer),
+        (&entry.optional_dependencies, DependencyType::Optional),
+    ];
+    let dev = include_dev.then_some((&entry.dev_dependencies, DependencyType::Development));
+
+    let mut edges: Vec<(&str, &str, DependencyType)> = Vec::new();
+    for (deps, dep_type) in lists.into_iter().chain(dev) {
+        for (name, range) in deps {
+            // A package listed twice keeps its strongest declaration
+            match edges.iter_mut().find(|(n, _, _)| n == name) {
+                Some((_, existing_range, existing)) if dep_type.runtime_rank() > existing.runtime_rank() => {
+                    *existing_range = range;
+                    *existing = dep_type;
+                }
+                Some(_) => {}
+                None => edges.push((name, range, dep_type)),
+            }
+        }
+    }
+    edges.sort_by_key(|(name, _, _)| *name);
+    edges
+}
+
 /// Represents a version requirement from a specific package.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VersionRequirement {
@@ -1346,18 +2314,142 @@ mod tests {
     fn test_cycle_info_cycle_path() {
         let cycle = CycleInfo {
             nodes: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            suggested_break: None,
         };
         assert_eq!(cycle.cycle_path(), "a -> b -> c -> a");
     }
 
     #[test]
     fn test_cycle_info_empty() {
-        let cycle = CycleInfo { nodes: vec![] };
+        let cycle = CycleInfo {
+            nodes: vec![],
+            suggested_break: None,
+        };
         assert!(cycle.is_empty());
         assert_eq!(cycle.len(), 0);
         assert_eq!(cycle.cycle_path(), "");
     }
 
+    #[test]
+    fn test_suggested_break_prefers_non_runtime_edge() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "1.0.0", DependencyType::Production);
+        graph.add_dependency("test-utils", "1.0.0", DependencyType::Development);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "test-utils");
+        graph.add_edge("test-utils", "a");
+
+        let fix = graph.get_cycle_details()[0].suggested_break.clone().unwrap();
+        assert_eq!(fix.edge(), "b -> test-utils");
+        assert_eq!(fix.reason, BreakReason::NonRuntime);
+        assert_eq!(fix.to_string(), "remove b -> test-utils (not a runtime dependency)");
+    }
+
+    #[test]
+    fn test_suggested_break_uses_lightest_edge() {
+        let mut graph = DependencyGraph::new();
+        for (name, size) in [("a", 5000), ("b", 100), ("c", 900)] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+            graph.get_node_mut(name).unwrap().set_bundle_size(size, 1);
+        }
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "a");
+
+        let fix = graph.get_cycle_details()[0].suggested_break.clone().unwrap();
+        assert_eq!(fix.edge(), "a -> b");
+        assert_eq!(fix.reason, BreakReason::Lightest);
+    }
+
+    #[test]
+    fn test_suggested_break_must_break_whole_component() {
+        // a <-> b and b <-> c share b; no single edge breaks both cycles
+        let mut graph = DependencyGraph::new();
+        for name in ["a", "b", "c"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "b");
+        assert!(graph.get_cycle_details()[0].suggested_break.is_none());
+
+        // With c -> a instead, a -> b is shared by both loops
+        let mut graph = DependencyGraph::new();
+        for name in ["a", "b", "c"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "a");
+        let fix = graph.get_cycle_details()[0].suggested_break.clone().unwrap();
+        assert_eq!(fix.edge(), "a -> b");
+    }
+
+    #[test]
+    fn test_cycle_details_start_at_shallowest_node() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("x", "1.0.0", DependencyType::Production, 3);
+        graph.add_dependency_with_depth("y", "1.0.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("z", "1.0.0", DependencyType::Production, 2);
+        graph.add_edge("x", "y");
+        graph.add_edge("y", "z");
+        graph.add_edge("z", "x");
+
+        let cycle = &graph.get_cycle_details()[0];
+        assert_eq!(cycle.cycle_path(), "y -> z -> x -> y");
+        let fix = cycle.suggested_break.as_ref().unwrap();
+        assert_eq!((fix.edge().as_str(), fix.reason), ("x -> y", BreakReason::BackEdge));
+    }
+
+    #[test]
+    fn test_production_cycles_ignore_dev_and_optional_edges() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "1.0.0", DependencyType::Production);
+        graph.add_dependency("c", "1.0.0", DependencyType::Production);
+        graph.add_dependency("lint", "1.0.0", DependencyType::Development);
+
+        // Runtime cycle: a -> b -> a
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+        // Closes only through an optional edge: b -> c -?-> b
+        graph.add_edge("b", "c");
+        graph.add_optional_edge("c", "b");
+        // Closes only through a dev dependency
+        graph.add_edge("c", "lint");
+        graph.add_edge("lint", "c");
+
+        assert_eq!(graph.detect_cycles().len(), 1, "all four nodes form one component");
+
+        let cycles = graph.get_production_cycle_details();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].cycle_path(), "a -> b -> a");
+        assert_eq!(cycles[0].suggested_break.as_ref().unwrap().reason, BreakReason::Lightest);
+    }
+
+    #[test]
+    fn test_production_self_loop() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_optional_edge("a", "a");
+        assert_eq!(graph.detect_cycles().len(), 1);
+        assert!(graph.detect_production_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_self_loop_break() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_edge("a", "a");
+
+        let cycles = graph.get_cycle_details();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].suggested_break.as_ref().unwrap().edge(), "a -> a");
+    }
+
     #[test]
     fn test_multiple_cycles() {
         let mut graph = DependencyGraph::new();
@@ -1384,6 +2476,244 @@ mod tests {
         assert!(cycle_nodes.contains("e"));
     }
 
+    // Dependency type propagation tests
+    #[test]
+    fn test_propagate_takes_strongest_path() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("app-lib", "1.0.0", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("jest", "29.0.0", DependencyType::Development, 0);
+        graph.add_dependency_with_depth("chalk", "4.1.2", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("ansi-styles", "4.3.0", DependencyType::Production, 2);
+        graph.add_dependency_with_depth("fsevents", "2.3.3", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("orphan", "1.0.0", DependencyType::Production, 1);
+
+        graph.add_edge("jest", "chalk");
+        graph.add_edge("chalk", "ansi-styles");
+        graph.add_edge("jest", "ansi-styles");
+        graph.add_optional_edge("app-lib", "fsevents");
+
+        assert_eq!(graph.propagate_dependency_types(), 3);
+        let dep_type = |name: &str| graph.get_node(name).unwrap().dep_type;
+        assert_eq!(dep_type("chalk"), DependencyType::Development);
+        assert_eq!(dep_type("ansi-styles"), DependencyType::Development);
+        assert_eq!(dep_type("fsevents"), DependencyType::Optional);
+        assert_eq!(dep_type("orphan"), DependencyType::Production, "unreached nodes are untouched");
+
+        // A production path wins over a dev path
+        graph.add_edge("app-lib", "ansi-styles");
+        assert_eq!(graph.propagate_dependency_types(), 1);
+        assert_eq!(graph.get_node("ansi-styles").unwrap().dep_type, DependencyType::Production);
+    }
+
+    #[test]
+    fn test_propagate_keeps_direct_types() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("testing-library", "14.0.0", DependencyType::Development, 0);
+        graph.add_edge("testing-library", "react");
+
+        assert_eq!(graph.propagate_dependency_types(), 0);
+        assert_eq!(graph.get_node("react").unwrap().dep_type, DependencyType::Production);
+    }
+
+    #[test]
+    fn test_extend_from_lockfile() {
+        let lock = PackageLock::parse(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "dependencies": { "react": "^18.2.0", "ui": "*" },
+      "devDependencies": { "jest": "^29.0.0" }
+    },
+    "node_modules/react": { "version": "18.2.0", "dependencies": { "loose-envify": "^1.1.0" } },
+    "node_modules/loose-envify": { "version": "1.4.0", "dependencies": { "js-tokens": "^4.0.0" } },
+    "node_modules/js-tokens": { "version": "4.0.0" },
+    "node_modules/jest": {
+      "version": "29.7.0", "dev": true,
+      "dependencies": { "chalk": "^4.0.0", "js-tokens": "^4.0.0" },
+      "optionalDependencies": { "fsevents": "^2.3.2" }
+    },
+    "node_modules/chalk": { "version": "4.1.2", "dev": true },
+    "node_modules/fsevents": { "version": "2.3.3", "dev": true, "optional": true },
+    "node_modules/ui": { "resolved": "packages/ui", "link": true },
+    "packages/ui": { "version": "1.0.0", "devDependencies": { "storybook": "^7.0.0" } },
+    "node_modules/storybook": { "version": "7.6.0", "dev": true }
+  }
+}"#,
+        )
+        .unwrap();
+
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "^18.2.0", DependencyType::Production);
+        assert_eq!(graph.extend_from_lockfile(&lock), 7);
+        graph.propagate_dependency_types();
+
+        let node = |name: &str| graph.get_node(name).unwrap();
+        assert_eq!(node("react").version, "^18.2.0", "existing nodes are kept");
+        assert_eq!(node("react").installed_version(), Some("18.2.0"));
+        assert_eq!(node("ui").installed_version(), None, "links have no version");
+        assert_eq!(node("jest").dep_type, DependencyType::Development);
+        assert_eq!(node("jest").depth, 0);
+        assert_eq!(node("loose-envify").depth, 1);
+        assert_eq!(node("js-tokens").depth, 1, "shallowest path via jest");
+        assert_eq!(node("js-tokens").dep_type, DependencyType::Production);
+        assert_eq!(node("chalk").dep_type, DependencyType::Development);
+        assert_eq!(node("fsevents").dep_type, DependencyType::Development);
+        assert_eq!(node("storybook").dep_type, DependencyType::Development);
+        assert_eq!(graph.get_dependencies("ui").len(), 1);
+        assert_eq!(graph.get_dependents("js-tokens").len(), 2);
+    }
+
+    #[test]
+    fn test_lockfile_edges_record_requested_versions() {
+        let lock = PackageLock::parse(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "chalk": "^5.3.0" }, "devDependencies": { "jest": "^29.0.0" } },
+    "node_modules/chalk": { "version": "5.3.0" },
+    "node_modules/jest": { "version": "29.7.0", "dev": true, "dependencies": { "chalk": "^4.0.0" } },
+    "node_modules/jest/node_modules/chalk": { "version": "4.1.2", "dev": true }
+  }
+}"#,
+        )
+        .unwrap();
+
+        let mut graph = DependencyGraph::new();
+        graph.extend_from_lockfile(&lock);
+
+        let edge = graph.get_edge("jest", "chalk").unwrap();
+        assert_eq!(edge.requested.as_deref(), Some("^4.0.0"));
+        assert_eq!(edge.resolved.as_deref(), Some("4.1.2"), "the nested copy, not the hoisted one");
+        assert_eq!(graph.get_node("chalk").unwrap().version, "5.3.0");
+        assert!(graph.get_edge("chalk", "jest").is_none());
+
+        let requesters = graph.get_requesters("chalk");
+        assert_eq!(requesters.len(), 1);
+        assert_eq!(requesters[0].0.name, "jest");
+    }
+
+    #[test]
+    fn test_retain_scope() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("jest", "29.0.0", DependencyType::Development, 0);
+        graph.add_dependency_with_depth("chalk", "4.1.2", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+        graph.add_edge("jest", "chalk");
+        graph.add_edge("react", "scheduler");
+        graph.track_version_requirement("chalk", "^4.0.0", "jest");
+        graph.propagate_dependency_types();
+
+        let mut prod = graph.clone();
+        assert_eq!(prod.retain_scope(Scope::Production), 2);
+        assert!(prod.get_node("chalk").is_none());
+        assert_eq!(prod.get_dependencies("react")[0].name, "scheduler");
+        assert_eq!(prod.edge_count(), 1);
+
+        let mut dev = graph.clone();
+        assert_eq!(dev.retain_scope(Scope::Development), 2);
+        assert_eq!(dev.get_dependencies("jest")[0].name, "chalk");
+        assert_eq!(dev.version_requirements.len(), 1);
+
+        assert_eq!(graph.retain_scope(Scope::All), 0);
+    }
+
+    #[test]
+    fn test_retain_scope_filters_requesters() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("jest", "29.0.0", DependencyType::Development);
+        graph.add_dependency("chalk", "4.1.2", DependencyType::Production);
+        graph.add_dependency("semver", "7.5.0", DependencyType::Production);
+        graph.track_version_requirement("chalk", "^4.0.0", "react");
+        graph.track_version_requirement("chalk", "^2.0.0", "jest");
+        graph.track_version_requirement("semver", "^7.0.0", "my-app");
+        graph.track_version_requirement("semver", "^6.0.0", "react");
+        graph.track_version_requirement("semver", "^5.0.0", "jest");
+        assert_eq!(graph.detect_version_conflicts().len(), 2);
+
+        assert_eq!(graph.retain_scope(Scope::Production), 1);
+        assert!(!graph.version_requirements.contains_key("chalk"));
+        let requesters: Vec<&str> = graph.version_requirements["semver"]
+            .iter()
+            .map(|r| r.required_by.as_str())
+            .collect();
+        assert_eq!(requesters, vec!["my-app", "react"]);
+        assert_eq!(graph.detect_version_conflicts().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_dependency_keeps_lookups_consistent() {
+        let mut graph = DependencyGraph::new();
+        for name in ["react", "react-dom", "scheduler", "loose-envify"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_edge("react", "loose-envify");
+        graph.add_edge("react-dom", "scheduler");
+        graph.add_edge("scheduler", "loose-envify");
+        graph.track_version_requirement("scheduler", "^0.23.0", "react-dom");
+        graph.track_version_requirement("loose-envify", "^1.1.0", "react");
+        graph.track_version_requirement("loose-envify", "^1.4.0", "scheduler");
+        graph.set_root(GraphRoot::new(["react", "react-dom"]));
+
+        // Removing the first node moves loose-envify, the last, into its index
+        assert!(graph.remove_dependency("react").is_some());
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.get_node("loose-envify").unwrap().name, "loose-envify");
+        assert_eq!(graph.get_dependencies("scheduler")[0].name, "loose-envify");
+        assert_eq!(graph.version_requirements["loose-envify"].len(), 1);
+        assert_eq!(graph.root().unwrap().dependencies, vec!["react-dom"]);
+
+        assert!(graph.remove_edge("react-dom", "scheduler").is_some());
+        assert!(graph.remove_edge("react-dom", "scheduler").is_none());
+        assert!(!graph.version_requirements.contains_key("scheduler"));
+        let names: Vec<&str> = graph.unreachable_nodes().iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["loose-envify", "scheduler"]);
+
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        assert!(graph.add_edge("react", "scheduler"));
+    }
+
+    #[test]
+    fn test_unreachable_nodes() {
+        let mut graph = DependencyGraph::new();
+        for name in ["react", "scheduler", "loose-envify", "left-pad"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_edge("react", "loose-envify");
+        graph.add_edge("scheduler", "loose-envify");
+        assert!(graph.unreachable_nodes().is_empty(), "no root, nothing to reach from");
+
+        graph.set_root(GraphRoot::new(["react", "not-installed"]));
+        let names: Vec<&str> = graph.unreachable_nodes().iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["left-pad", "scheduler"]);
+        assert_eq!(graph.root().unwrap().name, None);
+    }
+
+    #[test]
+    fn test_compute_depths_after_retain_scope() {
+        // lodash is reached at depth 1 through jest, at depth 2 through react
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("jest", "29.0.0", DependencyType::Development, 0);
+        graph.add_dependency_with_depth("scheduler", "0.23.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("lodash", "4.17.21", DependencyType::Production, 1);
+        graph.add_dependency("orphan", "1.0.0", DependencyType::Production);
+        graph.get_node_mut("orphan").unwrap().depth = 7;
+        graph.add_edge("react", "scheduler");
+        graph.add_edge("scheduler", "lodash");
+        graph.add_edge("jest", "lodash");
+
+        assert_eq!(graph.compute_depths(&["react", "jest", "missing"]), 0);
+
+        assert_eq!(graph.retain_scope(Scope::Production), 1);
+        assert_eq!(graph.compute_depths(&["react", "jest"]), 1);
+        assert_eq!(graph.get_node("lodash").unwrap().depth, 2);
+        assert_eq!(graph.get_node("orphan").unwrap().depth, 7, "unreachable packages keep their depth");
+    }
+
     // Version conflict tests
     #[test]
     fn test_track_version_requirement() {
@@ -1420,6 +2750,51 @@ mod tests {
         assert!(conflicts.is_empty());
     }
 
+    #[test]
+    fn test_alias_requirements_resolve_to_real_package() {
+        let mut graph = DependencyGraph::new();
+        graph.track_version_requirement("lodash", "^4.17.0", "my-app");
+        graph.track_version_requirement("lodash-v4", "npm:lodash@^4.17.0", "other-pkg");
+        assert!(graph.detect_version_conflicts().is_empty());
+
+        graph.track_version_requirement("lodash-v3", "npm:lodash@^3.10.0", "legacy-pkg");
+        let conflicts = graph.detect_version_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].package_name, "lodash");
+        assert!(conflicts[0]
+            .requirements
+            .iter()
+            .any(|r| r.version == "^3.10.0" && r.required_by == "legacy-pkg"));
+    }
+
+    #[test]
+    fn test_non_registry_requirements_kept_verbatim() {
+        let mut graph = DependencyGraph::new();
+        graph.track_version_requirement("ui", "file:../ui", "my-app");
+        graph.track_version_requirement("ui", "^1.0.0", "other-pkg");
+
+        let conflicts = graph.detect_version_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].requirements.iter().any(|r| r.version == "file:../ui"));
+    }
+
+    #[test]
+    fn test_conflicts_from_edges_and_root() {
+        let mut graph = DependencyGraph::new();
+        for (name, version) in [("a", "^1.0.0"), ("b", "^1.0.0"), ("c", "^1.2.0")] {
+            graph.add_dependency(name, version, DependencyType::Production);
+        }
+        graph.add_edge_with_metadata("a", "c", DependencyEdge::new().with_requested("^1.0.0"));
+        graph.set_root(GraphRoot::new(["a", "b", "c"]).with_name("my-app"));
+        assert_eq!(graph.get_packages_with_conflicts(), HashSet::from(["c".to_string()]));
+
+        graph.add_edge_with_metadata("b", "a", DependencyEdge::new().with_requested("^1.0.0"));
+        let conflicts = graph.detect_version_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        let requesters: Vec<&str> = conflicts[0].requirements.iter().map(|r| r.required_by.as_str()).collect();
+        assert_eq!(requesters, vec!["a", "my-app"]);
+    }
+
     #[test]
     fn test_get_packages_with_conflicts() {
         let mut graph = DependencyGraph::new();
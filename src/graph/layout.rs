@@ -0,0 +1,416 @@
+//! Layered layout of a dependency graph for drawing.
+//!
+//! Packages are assigned to layers so that every dependency sits below the
+//! packages that depend on it (longest-path layering). Dependencies that
+//! skip layers are routed through placeholder vertices, one per skipped
+//! layer, and the packages of each layer are ordered to reduce crossings
+//! (barycenter heuristic). Renderers only need to turn positions into
+//! coordinates.
+//!
+//! Edges that close a cycle cannot point downwards; they are left out of
+//! the layout and listed in [`LayeredLayout::back_edges`].
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{DependencyGraph, DependencyType};
+
+/// Number of down and up ordering sweeps.
+const ORDERING_SWEEPS: usize = 4;
+
+/// A package shown in the layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutNode {
+    /// Package name, or the project name for the root
+    pub name: String,
+    /// Version specification
+    pub version: String,
+    /// Dependency type; `None` for the project root
+    pub dep_type: Option<DependencyType>,
+    /// Bundle size in bytes, if known
+    pub bundle_size: Option<u64>,
+}
+
+/// An entry of a layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vertex {
+    /// A package, indexing [`LayeredLayout::nodes`]
+    Node(usize),
+    /// A placeholder carrying an edge through the layer
+    Dummy,
+}
+
+/// A connection between vertices of consecutive layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Link {
+    /// Layer of the upper vertex
+    pub layer: usize,
+    /// Position of the upper vertex in its layer
+    pub from: usize,
+    /// Position of the lower vertex in the next layer
+    pub to: usize,
+}
+
+/// A dependency graph arranged in ordered layers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayeredLayout {
+    /// Packages; the first is the root
+    pub nodes: Vec<LayoutNode>,
+    /// Vertices of each layer, from the root downwards, in drawing order
+    pub layers: Vec<Vec<Vertex>>,
+    /// Connections between consecutive layers
+    pub links: Vec<Link>,
+    /// Edges left out because they close a cycle, as `(from, to)` node
+    /// indices
+    pub back_edges: Vec<(usize, usize)>,
+}
+
+impl LayeredLayout {
+    /// Lays out a whole project: the root is the project, linked to every
+    /// direct dependency.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The dependency graph
+    /// * `project` - Name of the project, shown as the root
+    /// * `max_depth` - Number of dependency levels to include below the
+    ///   root, or `None` for all
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::graph::{DependencyGraph, DependencyType};
+    /// use codescope::graph::layout::LayeredLayout;
+    ///
+    /// let mut graph = DependencyGraph::new();
+    /// graph.add_dependency("react-dom", "18.2.0", DependencyType::Production);
+    /// graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 1);
+    /// graph.add_edge("react-dom", "react");
+    ///
+    /// let layout = LayeredLayout::project(&graph, "my-app", None);
+    /// assert_eq!(layout.layers.len(), 3);
+    /// assert_eq!(LayeredLayout::project(&graph, "my-app", Some(1)).nodes.len(), 2);
+    /// ```
+    pub fn project(graph: &DependencyGraph, project: &str, max_depth: Option<usize>) -> Self {
+        let root = LayoutNode {
+            name: project.to_string(),
+            version: String::new(),
+            dep_type: None,
+            bundle_size: None,
+        };
+        let direct = graph
+            .get_all_nodes()
+            .into_iter()
+            .filter(|node| node.depth == 0)
+            .map(|node| node.name.clone())
+            .collect();
+        Self::build(graph, root, direct, max_depth)
+    }
+
+    /// Lays out the dependencies of one package, with the package as the
+    /// root.
+    ///
+    /// Returns `None` if the package is not in the graph.
+    pub fn focused(graph: &DependencyGraph, package: &str, max_depth: Option<usize>) -> Option<Self> {
+        let node = graph.get_node(package)?;
+        let root = LayoutNode {
+            name: node.name.clone(),
+            version: node.version.clone(),
+            dep_type: Some(node.dep_type),
+            bundle_size: node.bundle_size,
+        };
+        let children = graph.get_dependencies(package).into_iter().map(|dep| dep.name.clone()).collect();
+        Some(Self::build(graph, root, children, max_depth))
+    }
+
+    /// Returns the label of a vertex's package, or `None` for placeholders.
+    pub fn node(&self, vertex: Vertex) -> Option<&LayoutNode> {
+        match vertex {
+            Vertex::Node(index) => self.nodes.get(index),
+            Vertex::Dummy => None,
+        }
+    }
+
+    fn build(graph: &DependencyGraph, root: LayoutNode, mut root_children: Vec<String>, max_depth: Option<usize>) -> Self {
+        // Collect the packages within reach, breadth first so that the
+        // initial order within a layer follows discovery.
+        let mut nodes = vec![root];
+        let mut children: Vec<Vec<usize>> = vec![Vec::new()];
+        // The project root is not a package, even if a dependency shares
+        // its name.
+        let mut index: HashMap<String, usize> = HashMap::new();
+        if nodes[0].dep_type.is_some() {
+            index.insert(nodes[0].name.clone(), 0);
+        }
+        let mut queue = VecDeque::from([(0_usize, 0_usize)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            let mut names = if current == 0 {
+                std::mem::take(&mut root_children)
+            } else {
+                graph
+                    .get_dependencies(&nodes[current].name)
+                    .into_iter()
+                    .map(|dep| dep.name.clone())
+                    .collect()
+            };
+            names.sort();
+            names.dedup();
+            for name in names {
+                let child = match index.get(&name) {
+                    Some(&child) => child,
+                    None if max_depth.is_some_and(|max| distance + 1 > max) => continue,
+                    None => {
+                        let Some(dep) = graph.get_node(&name) else {
+                            continue;
+                        };
+                        nodes.push(LayoutNode {
+                            name: dep.name.clone(),
+                            version: dep.version.clone(),
+                            dep_type: Some(dep.dep_type),
+                            bundle_size: dep.bundle_size,
+                        });
+                        children.push(Vec::new());
+                        index.insert(name, nodes.len() - 1);
+                        queue.push_back((nodes.len() - 1, distance + 1));
+                        nodes.len() - 1
+                    }
+                };
+                children[current].push(child);
+            }
+        }
+
+        let (forward, back_edges) = split_back_edges(&children);
+        let layer_of = longest_path_layers(&forward);
+        let layer_count = layer_of.iter().max().map_or(0, |max| max + 1);
+
+        // Vertices in discovery order, with placeholders for edges that
+        // skip layers.
+        let mut vertices: Vec<(Vertex, usize)> = (0..nodes.len()).map(|n| (Vertex::Node(n), layer_of[n])).collect();
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for (from, targets) in forward.iter().enumerate() {
+            for &to in targets {
+                let mut upper = from;
+                for layer in layer_of[from] + 1..layer_of[to] {
+                    vertices.push((Vertex::Dummy, layer));
+                    edges.push((upper, vertices.len() - 1));
+                    upper = vertices.len() - 1;
+                }
+                edges.push((upper, to));
+            }
+        }
+
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+        for (id, &(_, layer)) in vertices.iter().enumerate() {
+            layers[layer].push(id);
+        }
+        order_layers(&mut layers, &edges, vertices.len());
+
+        let mut position = vec![0; vertices.len()];
+        for layer in &layers {
+            for (pos, &id) in layer.iter().enumerate() {
+                position[id] = pos;
+            }
+        }
+        let mut links: Vec<Link> = edges
+            .iter()
+            .map(|&(upper, lower)| Link {
+                layer: vertices[upper].1,
+                from: position[upper],
+                to: position[lower],
+            })
+            .collect();
+        links.sort_by_key(|link| (link.layer, link.from, link.to));
+
+        Self {
+            nodes,
+            layers: layers
+                .into_iter()
+                .map(|layer| layer.into_iter().map(|id| vertices[id].0).collect())
+                .collect(),
+            links,
+            back_edges,
+        }
+    }
+}
+
+/// Separates the edges that close a cycle, found by a depth-first search
+/// from the root, from the others.
+fn split_back_edges(children: &[Vec<usize>]) -> (Vec<Vec<usize>>, Vec<(usize, usize)>) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        Active,
+        Done,
+    }
+
+    fn visit(
+        node: usize,
+        children: &[Vec<usize>],
+        state: &mut [State],
+        forward: &mut [Vec<usize>],
+        back: &mut Vec<(usize, usize)>,
+    ) {
+        state[node] = State::Active;
+        for &child in &children[node] {
+            match state[child] {
+                State::Active => back.push((node, child)),
+                State::New => {
+                    forward[node].push(child);
+                    visit(child, children, state, forward, back);
+                }
+                State::Done => forward[node].push(child),
+            }
+        }
+        state[node] = State::Done;
+    }
+
+    let mut state = vec![State::New; children.len()];
+    let mut forward = vec![Vec::new(); children.len()];
+    let mut back = Vec::new();
+    if !children.is_empty() {
+        visit(0, children, &mut state, &mut forward, &mut back);
+    }
+    (forward, back)
+}
+
+/// Assigns each node the length of the longest path to it from the root.
+fn longest_path_layers(forward: &[Vec<usize>]) -> Vec<usize> {
+    fn post_order(node: usize, forward: &[Vec<usize>], seen: &mut [bool], order: &mut Vec<usize>) {
+        seen[node] = true;
+        for &child in &forward[node] {
+            if !seen[child] {
+                post_order(child, forward, seen, order);
+            }
+        }
+        order.push(node);
+    }
+
+    let mut order = Vec::new();
+    let mut seen = vec![false; forward.len()];
+    if !forward.is_empty() {
+        post_order(0, forward, &mut seen, &mut order);
+    }
+    let mut layer = vec![0; forward.len()];
+    for &node in order.iter().rev() {
+        for &child in &forward[node] {
+            layer[child] = layer[child].max(layer[node] + 1);
+        }
+    }
+    layer
+}
+
+/// Reorders each layer by the average position of its neighbours in the
+/// previous layer, sweeping down and up alternately.
+fn order_layers(layers: &mut [Vec<usize>], edges: &[(usize, usize)], vertex_count: usize) {
+    let mut upper: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    let mut lower: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    for &(from, to) in edges {
+        lower[from].push(to);
+        upper[to].push(from);
+    }
+    let mut position = vec![0.0_f64; vertex_count];
+    let record = |layers: &[Vec<usize>], position: &mut [f64]| {
+        for layer in layers {
+            for (pos, &id) in layer.iter().enumerate() {
+                position[id] = pos as f64;
+            }
+        }
+    };
+    record(layers, &mut position);
+
+    let reorder = |layer: &mut Vec<usize>, neighbours: &[Vec<usize>], position: &[f64]| {
+        let key = |id: usize| {
+            let adjacent = &neighbours[id];
+            if adjacent.is_empty() {
+                position[id]
+            } else {
+                adjacent.iter().map(|&n| position[n]).sum::<f64>() / adjacent.len() as f64
+            }
+        };
+        layer.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+    };
+    for sweep in 0..ORDERING_SWEEPS * 2 + 1 {
+        if sweep % 2 == 0 {
+            for l in 1..layers.len() {
+                reorder(&mut layers[l], &upper, &position);
+                record(&layers[l..=l], &mut position);
+            }
+        } else {
+            for l in (0..layers.len().saturating_sub(1)).rev() {
+                reorder(&mut layers[l], &lower, &position);
+                record(&layers[l..=l], &mut position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(layout: &LayeredLayout, layer: usize) -> Vec<&str> {
+        layout.layers[layer]
+            .iter()
+            .map(|&v| layout.node(v).map_or("·", |n| n.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_layers_and_placeholders() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "1.0.0", DependencyType::Production);
+        graph.add_dependency_with_depth("c", "1.0.0", DependencyType::Production, 1);
+        graph.add_edge("a", "c");
+        graph.add_edge("b", "a");
+
+        let layout = LayeredLayout::project(&graph, "app", None);
+        assert_eq!(names(&layout, 0), vec!["app"]);
+        assert_eq!(names(&layout, 1), vec!["b", "·"], "app -> a skips the layer of b");
+        assert_eq!(names(&layout, 2), vec!["a"]);
+        assert_eq!(names(&layout, 3), vec!["c"]);
+        assert_eq!(layout.links.len(), 5);
+        assert!(layout.links.iter().all(|link| link.layer < 3));
+        assert!(layout.back_edges.is_empty());
+    }
+
+    #[test]
+    fn test_cycles_and_focus() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency_with_depth("b", "1.0.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("c", "1.0.0", DependencyType::Production, 2);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "a");
+
+        let layout = LayeredLayout::project(&graph, "app", None);
+        assert_eq!(layout.layers.len(), 4);
+        let back: Vec<(&str, &str)> = layout
+            .back_edges
+            .iter()
+            .map(|&(from, to)| (layout.nodes[from].name.as_str(), layout.nodes[to].name.as_str()))
+            .collect();
+        assert_eq!(back, vec![("c", "a")]);
+
+        let focused = LayeredLayout::focused(&graph, "b", Some(1)).unwrap();
+        assert_eq!(focused.nodes.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(), vec!["b", "c"]);
+        assert!(LayeredLayout::focused(&graph, "missing", None).is_none());
+    }
+
+    #[test]
+    fn test_ordering_reduces_crossings() {
+        let mut graph = DependencyGraph::new();
+        for name in ["a", "b", "y"] {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+        }
+        graph.add_dependency_with_depth("x", "1.0.0", DependencyType::Production, 1);
+        graph.add_edge("a", "x");
+        graph.add_edge("b", "y");
+
+        // Discovery order puts y (a direct dependency) before x, which
+        // would cross a -> x with b -> y.
+        let layout = LayeredLayout::project(&graph, "app", None);
+        assert_eq!(names(&layout, 1), vec!["a", "b", "·"]);
+        assert_eq!(names(&layout, 2), vec!["x", "y"]);
+    }
+}
@@ -17,9 +17,12 @@
 //! assert_eq!(graph.edge_count(), 1);
 //! ```
 
+pub mod condensation;
 mod dependency_graph;
+pub mod layout;
 
 pub use dependency_graph::{
-    CycleInfo, Dependency, DependencyEdge, DependencyGraph, DependencyNode, DependencyType,
-    VersionConflict, VersionRequirement,
+    BreakReason, CycleBreak, CycleInfo, Dependency, DependencyEdge, DependencyGraph, DependencyNode, DependencyType,
+    GraphRoot, VersionConflict, VersionRequirement,
 };
+pub use petgraph::graph::NodeIndex;
@@ -5,6 +5,18 @@
 
 pub mod analysis;
 pub mod bundle;
+pub mod config;
+pub mod export;
+pub mod git;
 pub mod graph;
+pub mod net;
 pub mod parser;
+pub mod policy;
+pub mod state;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+#[cfg(test)]
+mod test_support;
 pub mod ui;
+#[cfg(feature = "watch-mode")]
+pub mod watch;
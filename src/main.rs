@@ -1,7 +1,8 @@
+use std::collections::{BTreeMap, HashMap};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -9,10 +10,72 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
-use codescope::bundle::savings::{SavingsCalculator, SavingsReport};
-use codescope::graph::{self, DependencyGraph};
-use codescope::parser::{self, extract_dependencies, parse_file, DependencyType};
-use codescope::ui::{run_app, App, TreeNode, format_size, SortMode};
+use codescope::analysis::{
+    compare_graphs, diff_graphs, format_comparison, find_orphaned_packages, find_workspace_duplicates, format_duplicates,
+    format_workspace_graph, WorkspaceGraph, simulate_removals, summarize_budgeted_groups, summarize_owners, DepthReport,
+    FootprintReport, FootprintSort, LicenseReport, NativeReport, OrphanReport, format_scopes, summarize_scopes,
+    TypesAudit, BrowserSupportReport, analyze_project_imports_with, describe_packages, link_packages, PackageDescription, PrereleaseReport,
+    AnalysisWarnings, Fidelity, MemoryBudget, discover_projects, BatchReport, ProjectSummary, explain_hoisting, ToolingReport,
+    GroupSummary,
+};
+use codescope::analysis::exports::ProjectImports;
+use codescope::analysis::memory::{FLATTENED_NODE_BYTES, STATS_EXPANSION};
+use codescope::config::setup::suggest_preset;
+use codescope::config::{
+    ConfigFormat, LicensePolicy, Measurement, PackageManager, ProjectConfig, Ratchet, SetupPlan, CONFIG_FILE,
+    TOML_CONFIG_FILE,
+};
+use codescope::bundle::estimates::SizeDataset;
+use codescope::bundle::savings::{SavingsCalculator, SavingsCategory, SavingsReport};
+use codescope::bundle::{
+    apply_bundle_sizes_to_graph, build_stats, parse_size, Bundler, InclusionReport, PolyfillReport, read_bundled_dependencies, BundleAnalysis,
+    TreeShakingReport, WebpackStats,
+};
+use codescope::export::{
+    self, CsvColumn, CsvExporter, D3Exporter, ExportFormat, ImageFormat, SvgExporter, ExportReport, JsonExporter, MarkdownExporter,
+    PrometheusExporter, ReportMetadata,
+};
+use codescope::export::aggregate::{AggregateReport, ExportedProject};
+use codescope::export::file;
+use codescope::export::metadata::detect_lockfile;
+use codescope::export::query::{self, Query};
+use codescope::export::redact::Redactor;
+use codescope::export::select;
+use codescope::export::signing::{signature_path, SigningError, SigningKey, VerifyingKey};
+#[cfg(feature = "png")]
+use codescope::export::render_png;
+#[cfg(feature = "templates")]
+use codescope::export::TemplateExporter;
+#[cfg(feature = "xlsx")]
+use codescope::export::XlsxExporter;
+use codescope::git::{blame_dependencies, Commit, Repository, RevisionFiles};
+use codescope::graph::{self, DependencyGraph, GraphRoot};
+use codescope::net::{DataSource, Fetched, HttpTransport, NetError, NpmrcConfig, RegistryClient};
+use codescope::parser::{
+    self, extract_dependencies, BrowserTargets, parse_str, DependencyType, PackageLock, PackageStore, Scope,
+    Workspace,
+};
+use codescope::parser::lockfile::PACKAGE_LOCK;
+use codescope::policy::notify::{self, NotifyFormat, WEBHOOK_URL_VAR};
+use codescope::policy::{
+    self, BundleLimits, CheckContext, CheckRegistry, DependencyLimits, PolicyReport, SizeThreshold,
+};
+use codescope::state::{Notes, Pins, STATE_DIR};
+use codescope::graph::condensation::Condensation;
+use codescope::graph::layout::LayeredLayout;
+use codescope::ui::graph_art::{draw_graph, Charset};
+use codescope::ui::{
+    run_app, run_compare, run_setup, App, CompareApp, SetupOutcome, SetupWizard, TreeNode, format_size, SortMode,
+};
+
+/// Number of packages listed by `analyze --footprint`.
+const FOOTPRINT_TOP: usize = 20;
+
+/// Number of shared packages listed by `analyze --recursive`.
+const SHARED_PACKAGES_TOP: usize = 10;
+
+/// Number of dependencies listed per section by `aggregate`.
+const AGGREGATE_TOP: usize = 20;
 
 #[derive(Parser)]
 #[command(name = "codescope")]
@@ -22,6 +85,68 @@ use codescope::ui::{run_app, App, TreeNode, format_size, SortMode};
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Never access the network; online features use cached data only
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Only analyze production dependencies (including peer and optional ones)
+    #[arg(long, global = true, conflicts_with = "dev")]
+    prod: bool,
+
+    /// Only analyze development dependencies
+    #[arg(long, global = true)]
+    dev: bool,
+
+    /// Only report packages and findings owned by this team (see the
+    /// `owners` lines of .codescoperc.json)
+    #[arg(long, global = true, value_name = "TEAM")]
+    owner: Option<String>,
+
+    /// Read package.json, package-lock.json and the configuration from a
+    /// git commit, branch or tag instead of the working tree (give twice
+    /// to `diff`)
+    #[arg(long, global = true, value_name = "REV")]
+    rev: Vec<String>,
+
+    /// Soft memory limit (e.g. 2GB); close to it, module-level detail is
+    /// dropped and only package totals are kept
+    #[arg(long, global = true, value_name = "SIZE", value_parser = parse_size)]
+    max_memory: Option<u64>,
+
+    /// Send spans of the analysis phases to this OpenTelemetry collector
+    /// (OTLP over HTTP, e.g. http://localhost:4318); defaults to
+    /// OTEL_EXPORTER_OTLP_ENDPOINT (needs the otel feature)
+    #[arg(long, global = true, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+}
+
+impl Cli {
+    /// Returns the dependency scope selected by --prod / --dev.
+    fn scope(&self) -> Scope {
+        if self.prod {
+            Scope::Production
+        } else if self.dev {
+            Scope::Development
+        } else {
+            Scope::All
+        }
+    }
+
+    /// Returns the single --rev of commands that analyze one revision,
+    /// exiting if several were given.
+    fn single_rev(&self) -> Option<&str> {
+        if self.rev.len() > 1 {
+            eprintln!("❌ --rev can only be given once here; use 'codescope diff' to compare revisions.");
+            std::process::exit(1);
+        }
+        self.rev.first().map(String::as_str)
+    }
+
+    /// Returns a memory budget with the --max-memory limit.
+    fn memory_budget(&self) -> MemoryBudget {
+        MemoryBudget::new(self.max_memory)
+    }
 }
 
 #[derive(Subcommand)]
@@ -40,14 +165,122 @@ enum Commands {
         #[arg(long)]
         no_tui: bool,
 
+        /// Analyze every project with a package.json under --path (skipping
+        /// node_modules and ignored directories) and print a section per
+        /// project with totals across them
+        #[arg(long, conflicts_with_all = ["watch", "stats"])]
+        recursive: bool,
+
+        /// Print the dependency tree as plain indented text for screen
+        /// readers, spelling out each package's level and position instead
+        /// of using symbols or color
+        #[arg(long)]
+        linear: bool,
+
         /// Check for circular dependencies (for CI usage, exits with code 1 if found)
         #[arg(long)]
         check_cycles: bool,
 
+        /// With --check-cycles, only fail on cycles between production dependencies
+        #[arg(long, requires = "check_cycles")]
+        prod_only: bool,
+
         /// Check for version conflicts (for CI usage, exits with code 1 if found)
         #[arg(long)]
         check_conflicts: bool,
 
+        /// Fail if the resolved graph has more than this many packages
+        /// (for CI usage, exits with code 3)
+        #[arg(long, value_name = "COUNT")]
+        max_deps: Option<usize>,
+
+        /// Fail if there are more than this many direct dependencies
+        /// (for CI usage, exits with code 4)
+        #[arg(long, value_name = "COUNT")]
+        max_direct_deps: Option<usize>,
+
+        /// Fail if the dependency tree is deeper than this, counting direct
+        /// dependencies as depth 1 (for CI usage, exits with code 5)
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Fail if the bundle is larger than this, e.g. 1.5MB (for CI
+        /// usage, exits with code 9)
+        #[arg(long, value_name = "SIZE", requires = "stats", value_parser = parse_size)]
+        max_size: Option<u64>,
+
+        /// Fail if the bundle grew more than this over --baseline-stats,
+        /// as a size (50KB) or a percentage (5%) (for CI usage, exits with
+        /// code 10)
+        #[arg(long, value_name = "SIZE|PERCENT", requires_all = ["stats", "baseline_stats"])]
+        max_increase: Option<SizeThreshold>,
+
+        /// Webpack stats.json of the build to compare with for --max-increase
+        #[arg(long, value_name = "FILE", requires = "max_increase")]
+        baseline_stats: Option<String>,
+
+        /// Fail if a package group in .codescoperc.json exceeds its size or
+        /// count budget (for CI usage, exits with code 6)
+        #[arg(long)]
+        check_budgets: bool,
+
+        /// Fail if a direct dependency's version is pinned less tightly than
+        /// the "pinning" setting in .codescoperc.json requires; by default
+        /// unbounded ranges such as * or >=1 (for CI usage, exits with
+        /// code 11)
+        #[arg(long)]
+        check_pinning: bool,
+
+        /// Fail if a production dependency is installed at a pre-release
+        /// (-alpha, -beta, -rc) or 0.x version; the 0.x findings are
+        /// reported by the zero-major check (for CI usage, exits with
+        /// code 12)
+        #[arg(long)]
+        check_prerelease: bool,
+
+        /// Fail if an installed package's license is not allowed by the
+        /// licenses policy in .codescoperc.json; dual-licensed packages
+        /// pass if any of their licenses is allowed (for CI usage, exits
+        /// with code 13)
+        #[arg(long)]
+        check_licenses: bool,
+
+        /// Run the custom checks defined in .codescoperc.json (for CI
+        /// usage, exits with code 7 on findings and 8 if a check fails)
+        #[arg(long)]
+        custom_checks: bool,
+
+        /// Report policy findings as warnings and exit with code 0, to try
+        /// out checks in CI before enforcing them
+        #[arg(long)]
+        no_fail: bool,
+
+        /// When the checks fail, POST their findings to the webhook in
+        /// CODESCOPE_WEBHOOK_URL or notify.webhookUrl of .codescoperc.json,
+        /// as a Slack message or plain JSON (slack, json)
+        #[arg(long, value_name = "FORMAT", conflicts_with_all = ["watch", "recursive"])]
+        notify: Option<NotifyFormat>,
+
+        /// Report the findings of this check as warnings instead of failing,
+        /// e.g. max-deps or a custom check's name (repeatable)
+        #[arg(long, value_name = "CHECK", value_delimiter = ',')]
+        warn_only: Vec<String>,
+
+        /// Re-run the analysis whenever package.json, package-lock.json,
+        /// the configuration or the stats file changes. The TUI badges the
+        /// packages whose size changed; with --no-tui, --json or checks,
+        /// new and resolved findings and size changes are printed
+        #[arg(long)]
+        watch: bool,
+
+        /// In --watch mode, print events as NDJSON instead of text
+        #[arg(long, requires = "watch")]
+        json: bool,
+
+        /// Webpack stats.json to take bundle sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
         /// Sort dependencies by bundle size (largest first) instead of alphabetically
         #[arg(long)]
         sort_by_size: bool,
@@ -57,184 +290,2371 @@ enum Commands {
         #[arg(long)]
         savings_report: bool,
 
-        /// Set a minimum savings threshold in KB for CI checks
-        /// Exit with code 1 if potential savings exceed this threshold
-        #[arg(long, value_name = "KB")]
+        /// Print how many packages sit at each dependency depth and the
+        /// longest chain from a direct dependency
+        #[arg(long)]
+        depth_report: bool,
+
+        /// Print the install footprint of each package in node_modules:
+        /// disk usage, file count, native builds and install scripts
+        #[arg(long)]
+        footprint: bool,
+
+        /// Order of the --footprint and --tooling reports (size, files,
+        /// scripts, name)
+        #[arg(long, value_name = "ORDER", default_value = "size")]
+        footprint_sort: FootprintSort,
+
+        /// Print the install cost of development-only packages (size,
+        /// files, native builds, install scripts) against the "tooling"
+        /// budget in .codescoperc.json
+        #[arg(long)]
+        tooling: bool,
+
+        /// Print the number and combined size of packages per npm scope
+        /// (@babel, @aws-sdk, ...)
+        #[arg(long)]
+        scopes: bool,
+
+        /// List packages with native bindings (.node files, binding.gyp,
+        /// napi) or os/cpu restrictions, which complicate Docker builds
+        /// and cross-platform deployments
+        #[arg(long)]
+        check_native: bool,
+
+        /// List @types packages whose package is not a dependency or now
+        /// ships its own types
+        #[arg(long)]
+        check_types: bool,
+
+        /// List dependencies whose browserslist or syntax (optional
+        /// chaining, private fields, ...) needs newer browsers than the
+        /// project's browserslist config
+        #[arg(long)]
+        check_browsers: bool,
+
+        /// List core-js, regenerator-runtime and other polyfills in the
+        /// bundle, and which of them the browserslist targets make
+        /// unnecessary
+        #[arg(long, requires = "stats")]
+        polyfills: bool,
+
+        /// Print the bundle composition by asset type (JavaScript, CSS,
+        /// images, fonts) and the packages that emit assets
+        #[arg(long, requires = "stats")]
+        assets: bool,
+
+        /// Explain why a package is in the bundle: the source files and
+        /// packages that import it, from the module reasons in the stats
+        #[arg(long, value_name = "PACKAGE", requires = "stats")]
+        why_bundled: Option<String>,
+
+        /// Set a maximum for potential savings in CI checks, e.g. 200KB
+        /// or 1.5MB (a bare number is in KB).
+        /// Exit with code 14 if potential savings exceed this threshold
+        #[arg(long, value_name = "SIZE", value_parser = parse_savings_threshold)]
         savings_threshold: Option<u64>,
+
+        /// Enable online features (fetches npm registry metadata for direct dependencies).
+        /// Combined with --offline, only cached metadata is used
+        #[arg(long)]
+        online: bool,
+
+        /// Read git history to show which commit added each direct
+        /// dependency (press b in the TUI)
+        #[arg(long)]
+        blame: bool,
+
+        /// Parse the project's sources to list the files importing each
+        /// package, with a preview of their import lines (press v in the
+        /// TUI)
+        #[arg(long)]
+        imports: bool,
+    },
+    /// Export a dependency report to a file or stdout
+    Export {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Report format (markdown, csv, json, xlsx, d3, prometheus)
+        #[arg(short, long, default_value = "markdown")]
+        format: ExportFormat,
+
+        /// Write the report to this file instead of stdout (- for stdout,
+        /// which also takes the xlsx format)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Replace the --output file (and its signature) if it exists
+        #[arg(long, requires = "output")]
+        force: bool,
+
+        /// Webpack stats.json to take bundle sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
+        /// Number of packages in the largest-packages table
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Comma-separated CSV columns, or "all"
+        /// (name, version, type, bundle_size, gzip_size, module_count,
+        /// utilization, license, depth, direct, dependents, source);
+        /// bundle sizes and utilization need --stats, licenses need
+        /// installed packages, and gzip sizes are estimates
+        #[arg(long, value_name = "COLUMNS")]
+        columns: Option<String>,
+
+        /// Write every version requirement (package, requester, range,
+        /// resolved version) instead of one row per package (csv format;
+        /// the json format always includes them)
+        #[arg(long, conflicts_with = "columns")]
+        requirements: bool,
+
+        /// Include the estimated bundle size savings report
+        #[arg(long)]
+        savings: bool,
+
+        /// Read git history to record which commit added each direct
+        /// dependency (json format)
+        #[arg(long)]
+        blame: bool,
+
+        /// Measure node_modules to record each package's disk usage, file
+        /// count and install scripts (json format and install_* columns)
+        #[arg(long)]
+        footprint: bool,
+
+        /// Detect native bindings and os/cpu restrictions (json format and
+        /// native/platforms columns)
+        #[arg(long)]
+        native: bool,
+
+        /// Export only this package and everything it depends on, for a
+        /// smaller report about one problem area
+        #[arg(long, value_name = "PACKAGE")]
+        focus: Option<String>,
+
+        /// Replace the project name, scoped and unpublished packages, file
+        /// paths and git URLs with stable hashes, keeping sizes and the
+        /// graph's structure, so the report can be shared outside the
+        /// organization
+        #[arg(long)]
+        redact: bool,
+
+        /// Scopes whose packages --redact leaves readable, e.g.
+        /// @babel,@types
+        #[arg(long, value_name = "SCOPES", value_delimiter = ',', requires = "redact")]
+        public_scopes: Vec<String>,
+
+        /// Sign the written report with the Ed25519 key in the config's
+        /// signing.keyFile, saving the signature as <FILE>.sig
+        #[arg(long, requires = "output")]
+        sign: bool,
+
+        /// Print the JSON Schema of the json format and exit
+        #[arg(long)]
+        schema: bool,
+
+        /// Write only the value at this path of the json or d3 format,
+        /// jq-style (.summary.total_bundle_size, .packages[].name) or as a
+        /// JSON pointer (/summary/total_bundle_size); strings are written
+        /// without quotes
+        #[arg(long, value_name = "PATH")]
+        select: Option<String>,
+
+        /// Render the report through this Handlebars template instead of a
+        /// built-in format, with the savings report and policy findings
+        /// (HTML-escaped for .html templates; needs the templates feature)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["format", "columns", "requirements", "select"])]
+        template: Option<String>,
+    },
+    /// Answer a question about the dependencies with a query, e.g.
+    /// "deps where size > 100KB and type = prod order by size desc limit 10"
+    Query {
+        /// The query: deps [where CONDITIONS] [order by FIELD [asc|desc]]
+        /// [limit N], over the fields of the csv format's columns
+        expr: String,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Webpack stats.json to take bundle sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
+        /// Print the matching packages as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Merge JSON exports of several projects into one cross-project
+    /// report: common dependencies, version spread, duplicated installs
+    /// and projects violating shared policies
+    Aggregate {
+        /// JSON exports written by `codescope export --format json`
+        #[arg(required = true, value_name = "FILE")]
+        reports: Vec<String>,
+
+        /// .codescoperc.json or .codescope.toml whose license policy every
+        /// project must follow
+        #[arg(long, value_name = "FILE")]
+        policy: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that a report was signed by `export --sign` and not modified
+    /// since
+    VerifyReport {
+        /// The exported report
+        #[arg(value_name = "REPORT")]
+        report: String,
+
+        /// Its detached signature (defaults to <REPORT>.sig)
+        #[arg(value_name = "SIGNATURE")]
+        signature: Option<String>,
+
+        /// Project whose config's signing.publicKey to verify with
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Public key to verify with, PEM or base64, instead of the config's
+        #[arg(long, value_name = "KEY", allow_hyphen_values = true)]
+        public_key: Option<String>,
+    },
+    /// Show two projects, or two snapshots of one, side by side:
+    /// packages present in only one, differing versions and sizes
+    Compare {
+        /// One project to compare at two revisions, or two projects
+        #[arg(default_value = ".", num_args = 1..=2, value_name = "PATH")]
+        paths: Vec<String>,
+
+        /// Webpack stats file for each side, in the order of the projects
+        #[arg(long, value_name = "FILE", num_args = 1..=2)]
+        stats: Vec<String>,
+
+        /// Print the differences instead of opening the side-by-side view
+        #[arg(long)]
+        no_tui: bool,
+    },
+    /// Compare dependencies between two git revisions, or between one
+    /// revision and the working tree
+    Diff {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the workspace packages of a monorepo changed since a git
+    /// revision, and the siblings that depend on them
+    Affected {
+        /// Monorepo root (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Revision to compare with, usually the target branch
+        /// (e.g. origin/main); changes are counted from its merge base
+        #[arg(long, value_name = "REV")]
+        base: String,
+
+        /// Revision to compare (defaults to the working tree, including
+        /// uncommitted and untracked files)
+        #[arg(long, value_name = "REV")]
+        head: Option<String>,
+
+        /// Print the changed files and packages as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Build the project with its bundler (webpack, Vite or esbuild),
+    /// save the build stats and analyze them
+    BuildStats {
+        /// Path to build (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Bundler to run (webpack, vite, esbuild) instead of detecting it
+        #[arg(long, value_name = "BUNDLER")]
+        bundler: Option<Bundler>,
+
+        /// Where to save the stats, in webpack's format, for later use
+        /// with analyze --stats (defaults to a temporary file)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Overwrite the output file if it exists
+        #[arg(long, requires = "output")]
+        force: bool,
+    },
+    /// List the licenses of the installed packages, or write their license
+    /// texts to a third-party notices file
+    Licenses {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Write the combined license texts to this file, as HTML if it
+        /// ends in .html or .htm and as plain text otherwise
+        #[arg(long, value_name = "FILE")]
+        notices: Option<String>,
+
+        /// Replace the --notices file if it exists
+        #[arg(long, requires = "notices")]
+        force: bool,
+    },
+    /// Tighten the budgets of .codescoperc.json or .codescope.toml to what
+    /// the project measures today: group sizes and counts, and the tooling
+    /// thresholds
+    Ratchet {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Webpack stats.json to measure group sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
+        /// Only lower thresholds; those already above the measured value
+        /// are kept
+        #[arg(long)]
+        down_only: bool,
+
+        /// Print the changes without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-analyze the project on a schedule and summarize what changed
+    /// since the last run in a desktop notification
+    Monitor {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Time between runs: a number with a unit of s, m, h or d
+        #[arg(long, default_value = "24h", value_name = "INTERVAL")]
+        interval: String,
+
+        /// Webpack stats.json to take bundle sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
+        /// Run once, compare with the last run and exit (for cron or
+        /// other schedulers)
+        #[arg(long)]
+        once: bool,
+    },
+    /// Draw the dependency graph as box art, dependents above their
+    /// dependencies (best for small or focused graphs)
+    Graph {
+        /// Path to analyze (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Draw with plain ASCII characters instead of Unicode box drawing
+        #[arg(long)]
+        ascii: bool,
+
+        /// Only draw this package and what it depends on
+        #[arg(long, value_name = "PACKAGE")]
+        focus: Option<String>,
+
+        /// Only draw this many dependency levels below the root
+        #[arg(long, value_name = "LEVELS")]
+        depth: Option<usize>,
+
+        /// Refuse to draw box art for graphs with more packages than this
+        #[arg(long, default_value_t = 40, value_name = "COUNT")]
+        max_nodes: usize,
+
+        /// Export an image instead of drawing box art: svg, or png when
+        /// built with the png feature. Boxes are colored by dependency
+        /// type and scaled by bundle size (with --stats)
+        #[arg(long, value_name = "FORMAT", conflicts_with = "ascii")]
+        export: Option<ImageFormat>,
+
+        /// Write the image to this file instead of stdout (- for stdout)
+        #[arg(short, long, value_name = "FILE", requires = "export")]
+        output: Option<String>,
+
+        /// Replace the --output file if it exists
+        #[arg(long, requires = "output")]
+        force: bool,
+
+        /// Webpack stats.json to take bundle sizes from
+        #[arg(long, value_name = "FILE")]
+        stats: Option<String>,
+
+        /// Collapse each dependency cycle into a single box, leaving an
+        /// acyclic graph; the packages of each cycle are listed below
+        #[arg(long)]
+        condense: bool,
+    },
+    /// Analyze the workspace packages of a monorepo
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommands,
     },
     /// Show version information
     Version,
 }
 
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// List external packages used at different versions across
+    /// workspace packages, with a version to align on
+    Duplicates {
+        /// Monorepo root (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Print the duplicates as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show which workspace packages depend on which siblings, with
+    /// cycles and a build order (exits with code 1 on cycles)
+    Graph {
+        /// Monorepo root (defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Print the graph, cycles and build order as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Only print the build order: one stage per line, packages of a
+        /// stage separated by spaces
+        #[arg(long, conflicts_with = "json")]
+        order: bool,
+    },
+}
+
 fn main() -> io::Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // Dropped in reverse order: the run's span ends before the exporter stops
+    let _telemetry = start_telemetry(cli.otlp_endpoint.as_deref(), cli.offline);
+    let _run = tracing::info_span!("codescope", command = matches.subcommand_name().unwrap_or("help")).entered();
 
     match &cli.command {
         Some(Commands::Analyze {
             path,
             with_bundle_size: _,
             no_tui,
+            recursive,
+            linear,
             check_cycles,
+            prod_only,
             check_conflicts,
+            max_deps,
+            max_direct_deps,
+            max_depth,
+            max_size,
+            max_increase,
+            baseline_stats,
+            check_budgets,
+            check_pinning,
+            check_prerelease,
+            check_licenses,
+            custom_checks,
+            no_fail,
+            notify,
+            warn_only,
+            watch,
+            json,
+            stats,
             sort_by_size,
             savings_report,
+            depth_report,
+            footprint,
+            footprint_sort,
+            tooling,
+            scopes,
+            check_native,
+            check_types,
+            check_browsers,
+            polyfills,
+            assets,
+            why_bundled,
             savings_threshold,
+            online,
+            blame,
+            imports,
         }) => {
-            let package_json_path = Path::new(path).join("package.json");
+            let checks = PolicyOptions {
+                cycles: *check_cycles,
+                production_only: *prod_only,
+                conflicts: *check_conflicts,
+                limits: DependencyLimits {
+                    max_deps: *max_deps,
+                    max_direct_deps: *max_direct_deps,
+                    max_depth: *max_depth,
+                },
+                bundle: BundleLimits {
+                    max_size: *max_size,
+                    max_increase: *max_increase,
+                    baseline_size: baseline_stats.as_ref().map(|stats_path| {
+                        match WebpackStats::from_file(stats_path) {
+                            Ok(stats) => stats.analyze().shipped_size(),
+                            Err(e) => {
+                                eprintln!("❌ Failed to read webpack stats {}: {}", stats_path, e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }),
+                },
+                budgets: *check_budgets,
+                pinning: *check_pinning,
+                prerelease: *check_prerelease,
+                licenses: *check_licenses,
+                custom: *custom_checks,
+            };
+
+            if *watch && !cli.rev.is_empty() {
+                eprintln!("❌ --watch cannot be combined with --rev.");
+                std::process::exit(1);
+            }
+            if notify.is_some() && !checks.any() {
+                eprintln!("❌ --notify reports failed checks; combine it with a check such as --check-cycles.");
+                std::process::exit(1);
+            }
+            // Without the watch-mode feature this exits with a hint to rebuild
+            if *watch && (*json || *no_tui || checks.any() || !cfg!(feature = "watch-mode")) {
+                run_watch(&cli, path, stats.as_deref(), checks, *json);
+            }
+
+            if *recursive {
+                if !cli.rev.is_empty() {
+                    eprintln!("❌ --recursive cannot be combined with --rev.");
+                    std::process::exit(1);
+                }
+                analyze_recursive(&cli, path);
+                return Ok(());
+            }
+
+            let project = Project::open(path, cli.single_rev());
+            let (pkg, mut deps) = load_package(&project, cli.scope());
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            let lock = collect_lockfile(&project, &mut warnings);
+
+            // Build dependency graph for cycle detection
+            let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            budget.check("the dependency graph", 0, &mut warnings);
+            let config = load_config(&project);
+            let stats = stats_file(stats.as_ref(), &project, &config);
+            let bundle = stats.as_ref().and_then(|stats_path| {
+                collect_stats_file(Path::new(path), stats_path, &deps, &mut graph, &mut budget, &mut warnings)
+            });
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+            let owner = selected_owner(cli.owner.as_deref(), &config);
+            let groups = summarize_budgeted_groups(&config, &graph);
+
+            // Handle --check-cycles, --check-conflicts and the dependency
+            // limits together (for CI usage)
+            if checks.any() {
+                // A gate must not pass on partial inputs
+                if stats.is_some() && bundle.is_none() {
+                    eprintln!("❌ Cannot run the checks without the webpack stats.");
+                    std::process::exit(1);
+                }
+                if checks.custom && config.checks.is_empty() {
+                    eprintln!("⚠️  No custom checks are defined in .codescoperc.json.");
+                }
+                let project_name = pkg.name.as_deref().unwrap_or("project");
+                let mut report = checks.run(&deps, &graph, bundle.as_ref(), &config, Path::new(path), project_name);
+                if let Some(owner) = owner {
+                    report.retain_owner(owner);
+                }
+                downgrade_findings(&mut report, warn_only, *no_fail);
+
+                print!("{}", report.format_passed());
+                eprint!("{}", report.format_warnings());
+                if !report.passed() {
+                    eprint!("{}", report.format_findings());
+                    if let Some(format) = notify {
+                        send_notification(&report, project_name, *format, &config, Path::new(path), cli.offline);
+                    }
+                    std::process::exit(report.exit_code());
+                }
+                return Ok(());
+            }
+
+            // The graph keeps every package so cycles through other teams'
+            // packages are still found; the listings only show the owner's.
+            if let Some(owner) = owner {
+                deps.retain(|dep| config.is_owned_by(&dep.name, owner));
+            }
+
+            if *depth_report {
+                print!("{}", DepthReport::from_graph(&graph).format_report());
+                return Ok(());
+            }
+
+            if *footprint {
+                let Some(mut report) = measure_footprint(&project, &graph) else {
+                    std::process::exit(1);
+                };
+                report.sort(*footprint_sort);
+                print!("{}", report.format_report(FOOTPRINT_TOP));
+                return Ok(());
+            }
+
+            if *tooling {
+                if cli.prod {
+                    eprintln!("❌ --tooling reports development-only packages, which --prod leaves out.");
+                    std::process::exit(1);
+                }
+                let Some(mut footprint) = measure_footprint(&project, &graph) else {
+                    std::process::exit(1);
+                };
+                footprint.sort(*footprint_sort);
+                let report = ToolingReport::from_footprint(&footprint, &graph);
+                print!("{}", report.format_report(FOOTPRINT_TOP, &config.tooling));
+                return Ok(());
+            }
+
+            if *scopes {
+                print!("{}", format_scopes(&summarize_scopes(&graph)));
+                return Ok(());
+            }
+
+            if *check_native {
+                print!("{}", detect_native(&project, &graph, lock.as_ref()).format_report());
+                return Ok(());
+            }
+
+            if *check_types {
+                print!("{}", audit_types(&project, &deps).format_report());
+                return Ok(());
+            }
+
+            if *check_browsers {
+                print!("{}", check_browser_support(&project, &graph).format_report());
+                return Ok(());
+            }
+
+            if *polyfills {
+                if let Some(bundle) = &bundle {
+                    print!("{}", find_polyfills(&project, bundle).format_report());
+                }
+                return Ok(());
+            }
+
+            if *assets {
+                if let Some(bundle) = &bundle {
+                    print!("{}", bundle.format_asset_breakdown());
+                }
+                return Ok(());
+            }
+
+            if let (Some(package), Some(stats_path)) = (why_bundled, stats.as_ref()) {
+                let stats = WebpackStats::from_file(stats_path).unwrap_or_else(|e| {
+                    eprintln!("❌ Failed to read webpack stats {}: {}", stats_path, e);
+                    std::process::exit(1);
+                });
+                print!("{}", InclusionReport::explain(&stats, package).format_report());
+                return Ok(());
+            }
+
+            // Handle --savings-report flag (for CI usage)
+            if *savings_report {
+                let mut source_warnings = AnalysisWarnings::new();
+                let imports = bundle
+                    .as_ref()
+                    .and_then(|_| scan_imports(&project, &config, &mut source_warnings, "source imports are not analyzed"));
+                let report = generate_savings_report(&project, &deps, bundle.as_ref(), imports.as_ref(), &config);
+                eprint!("{}", source_warnings.format_report(MAX_LISTED_WARNINGS));
+                print!("{}", report.format_report());
+
+                // Check threshold if specified
+                if let Some(threshold) = *savings_threshold {
+                    let mut checks = PolicyReport::new();
+                    policy::check_savings(&report, threshold, &mut checks);
+                    checks.assign_owners(&config);
+                    downgrade_findings(&mut checks, warn_only, *no_fail);
+
+                    println!();
+                    print!("{}", checks.format_passed());
+                    eprint!("{}", checks.format_warnings());
+                    if !checks.passed() {
+                        eprint!("{}", checks.format_findings());
+                        if let Some(format) = notify {
+                            let project_name = pkg.name.as_deref().unwrap_or("project");
+                            send_notification(&checks, project_name, *format, &config, Path::new(path), cli.offline);
+                        }
+                        std::process::exit(checks.exit_code());
+                    }
+                }
+                return Ok(());
+            }
+
+            let metadata = online.then(|| fetch_registry_metadata(Path::new(path), &deps, cli.offline));
+            if let Some(metadata) = &metadata {
+                report_deprecations(metadata);
+            }
+
+            let mut tree = analyzed_tree(&pkg, &deps, &graph);
+
+            if *linear {
+                print!("{}", tree.format_linear(calculate_tree_total_bundle_size(&tree)));
+                return Ok(());
+            }
+
+            if *no_tui {
+                // Print tree to stdout
+                let total_bundle_size = calculate_tree_total_bundle_size(&tree);
+                print_tree(&tree, 0, total_bundle_size);
+                return Ok(());
+            }
+
+            // Close to the memory limit, the tree opens with its
+            // categories collapsed so only they are flattened
+            let tree_estimate = (deps.len() as u64 + 1).saturating_mul(FLATTENED_NODE_BYTES);
+            if budget.check("the flattened tree", tree_estimate, &mut warnings) == Fidelity::Reduced {
+                for category in &mut tree.children {
+                    category.expanded = false;
+                }
+            }
+
+            // Compare node_modules against the lockfile before taking over the
+            // terminal; node_modules says nothing about another revision
+            let working_tree = project.revision.is_none();
+            let orphans = lock
+                .as_ref()
+                .filter(|_| working_tree)
+                .and_then(|lock| find_orphans(Path::new(path), lock));
+            let store = PackageStore::detect(Path::new(path)).ok().filter(|_| working_tree);
+            let removals = simulate_removals(&graph, store.as_ref());
+            let footprint = store
+                .as_ref()
+                .map(|store| FootprintReport::from_graph(&graph, store))
+                .filter(|report| !report.packages.is_empty());
+            let mut descriptions = store
+                .as_ref()
+                .map(|store| describe_packages(&graph, store))
+                .unwrap_or_default();
+            let links = store
+                .as_ref()
+                .map(|store| link_packages(&graph, store))
+                .unwrap_or_default();
+            for (name, fetched) in metadata.iter().flatten() {
+                if !descriptions.contains_key(name) {
+                    let description = PackageDescription::new(
+                        fetched.metadata.description.as_deref(),
+                        fetched.metadata.keywords.as_ref(),
+                    );
+                    descriptions.insert(name.clone(), description);
+                }
+            }
+
+            let import_sites = if !*imports {
+                None
+            } else if !working_tree {
+                warnings.push(CONFIG_FILE, "sources are only read from the working tree; imports are not shown");
+                None
+            } else {
+                analyze_project_imports_with(&project.dir, &config.ignore)
+                    .map_err(|e| warnings.push(CONFIG_FILE, format!("{}; imports are not shown", e)))
+                    .ok()
+                    .map(|imports| {
+                        for (file, error) in &imports.unreadable_files {
+                            warnings.push(file.as_str(), format!("skipped, {}", error));
+                        }
+                        imports.import_sites()
+                    })
+            };
+
+            // Setup terminal for TUI
+            enable_raw_mode()?;
+            let mut stdout = io::stdout();
+            execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+            let backend = CrosstermBackend::new(stdout);
+            let mut terminal = Terminal::new(backend)?;
+
+            // A project opened without a config first gets the setup wizard
+            if working_tree && ConfigFormat::detect(&project.dir).is_none() {
+                let tooling = footprint
+                    .as_ref()
+                    .map(|footprint| ToolingReport::from_footprint(footprint, &graph));
+                let bundler = Bundler::detect(&project.dir);
+                let mut wizard = SetupWizard::new(
+                    pkg.name.as_deref().unwrap_or("project"),
+                    suggest_preset(deps.iter().map(|dep| dep.name.as_str()), bundler.is_some()),
+                    ratchet_measurements(&[], tooling.as_ref()),
+                )
+                .with_detected(project.lockfile().and_then(PackageManager::from_lockfile), bundler, stats.clone());
+                match run_setup(&mut terminal, &mut wizard) {
+                    Ok(SetupOutcome::Confirmed) => {
+                        restore_terminal(&mut terminal)?;
+                        finish_setup(&project, &wizard);
+                        rerun();
+                    }
+                    Ok(SetupOutcome::Dismissed) => {
+                        let config_path = project.dir.join(TOML_CONFIG_FILE);
+                        if let Err(e) = std::fs::write(&config_path, SetupPlan::default().to_config()) {
+                            warnings.push(TOML_CONFIG_FILE, format!("failed to write {}: {}", config_path.display(), e));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        restore_terminal(&mut terminal)?;
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            // Create app and run with appropriate sort mode
+            let initial_sort_mode = if *sort_by_size {
+                SortMode::SizeDescending
+            } else {
+                SortMode::Alphabetical
+            };
+            let mut app = App::with_sort_mode(tree, initial_sort_mode);
+            if let Some(report) = orphans {
+                app.set_orphan_report(report);
+            }
+            app.set_removal_impacts(removals);
+            app.set_group_summaries(groups);
+            app.set_scope_summaries(summarize_scopes(&graph));
+            app.set_condensation(Condensation::of(&graph));
+            if let Some(lock) = &lock {
+                app.set_hoisting(explain_hoisting(lock));
+            }
+            let pins = Pins::load(&project.dir).unwrap_or_else(|e| {
+                warnings.push(STATE_DIR, format!("{}; starting without pinned packages", e));
+                Pins::default()
+            });
+            app.set_pinned(pins.packages.clone());
+            let notes = Notes::load(&project.dir).unwrap_or_else(|e| {
+                warnings.push(STATE_DIR, format!("{}; starting without notes", e));
+                Notes::default()
+            });
+            app.set_notes(notes.packages.clone());
+            let mut report = ExportReport::from_graph(
+                pkg.name.as_deref().unwrap_or("project"),
+                pkg.version.as_deref().unwrap_or(""),
+                &graph,
+            )
+            .with_groups(summarize_budgeted_groups(&config, &graph));
+            report.apply_ownership(&config);
+            app.set_export_report(
+                report
+                    .with_warnings(&warnings)
+                    .with_metadata(project.report_metadata(stats.as_deref())),
+                &project.dir,
+            );
+            app.set_warnings(warnings);
+            app.set_size_bars(config.tui.size_bars);
+            app.set_open_browser(config.tui.open_browser);
+            app.set_package_descriptions(descriptions);
+            app.set_package_links(links);
+            if let Some(report) = footprint {
+                app.set_footprint_report(report);
+            }
+            if let Some(chunk_graph) = bundle.as_ref().map(|b| b.chunk_graph.clone()).filter(|g| !g.is_empty()) {
+                app.set_chunk_graph(chunk_graph);
+            }
+            if *blame {
+                app.set_dependency_origins(project.blame());
+            }
+            if let Some(sites) = import_sites {
+                app.set_import_sites(&project.dir, sites);
+            }
+            #[cfg(feature = "watch-mode")]
+            let result = if *watch {
+                run_tui_watch(&mut terminal, &mut app, &cli, &project, stats.as_deref(), &graph)
+            } else {
+                run_app(&mut terminal, &mut app)
+            };
+            #[cfg(not(feature = "watch-mode"))]
+            let result = run_app(&mut terminal, &mut app);
+            restore_terminal(&mut terminal)?;
+
+            if app.pinned != pins.packages {
+                let pins = Pins { packages: app.pinned.clone() };
+                if let Err(e) = pins.save(&project.dir) {
+                    eprintln!("⚠️  Pinned packages not saved: {}", e);
+                }
+            }
+            if app.notes != notes.packages {
+                let notes = Notes { packages: app.notes.clone() };
+                if let Err(e) = notes.save(&project.dir) {
+                    eprintln!("⚠️  Notes not saved: {}", e);
+                }
+            }
+
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+
+            if let Some(package) = &app.uninstall_request {
+                if working_tree {
+                    uninstall_package(Path::new(path), package);
+                } else {
+                    eprintln!("⚠️  Not uninstalling {}: the analyzed revision is not the working tree.", package);
+                }
+            }
+        }
+        Some(Commands::Export {
+            path,
+            format,
+            output,
+            stats,
+            top,
+            columns,
+            requirements,
+            savings,
+            blame,
+            footprint,
+            native,
+            focus,
+            redact,
+            public_scopes,
+            sign,
+            schema,
+            select,
+            template,
+            force,
+        }) => {
+            if *schema {
+                let schema = export::json::schema();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&schema).expect("schema is valid JSON")
+                );
+                return Ok(());
+            }
+
+            let project = Project::open(path, cli.single_rev());
+            let (pkg, deps) = load_package(&project, cli.scope());
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            let lock = collect_lockfile(&project, &mut warnings);
+            let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            budget.check("the dependency graph", 0, &mut warnings);
+
+            let config = load_config(&project);
+            let stats = stats_file(stats.as_ref(), &project, &config);
+            let bundle = stats.as_ref().and_then(|stats_path| {
+                collect_stats_file(Path::new(path), stats_path, &deps, &mut graph, &mut budget, &mut warnings)
+            });
+            let owner = selected_owner(cli.owner.as_deref(), &config);
+            let mut report = ExportReport::from_graph(
+                pkg.name.as_deref().unwrap_or("project"),
+                pkg.version.as_deref().unwrap_or(""),
+                &graph,
+            )
+            .with_groups(summarize_budgeted_groups(&config, &graph));
+            report.apply_gzip_sizes(SizeDataset::bundled());
+            // Utilization needs the export counts of the stats
+            let imports = bundle
+                .as_ref()
+                .and_then(|_| scan_imports(&project, &config, &mut warnings, "utilization is not calculated"));
+            if let (Some(bundle), Some(imports)) = (&bundle, &imports) {
+                report.apply_utilization(&imports.utilization(&bundle.export_counts()));
+            }
+            if project.revision.is_none() {
+                match PackageStore::detect(&project.dir) {
+                    Ok(store) => report.apply_licenses(&LicenseReport::from_graph(&graph, &store).by_name()),
+                    Err(e) => warnings.push("node_modules", format!("{}; licenses are left out", e)),
+                }
+            }
+            if *savings {
+                report = report.with_savings(generate_savings_report(
+                    &project,
+                    &deps,
+                    bundle.as_ref(),
+                    imports.as_ref(),
+                    &config,
+                ));
+            }
+            let owners = summarize_owners(&config, &graph, report.savings.as_ref());
+            report = report.with_owners(owners);
+            report.apply_ownership(&config);
+            match Notes::load(&project.dir) {
+                Ok(notes) => report.apply_notes(&notes.packages),
+                Err(e) => warnings.push(STATE_DIR, format!("{}; notes are left out", e)),
+            }
+            if *blame {
+                report.apply_blame(&project.blame());
+            }
+            if *footprint {
+                if let Some(footprints) = measure_footprint(&project, &graph) {
+                    report.apply_footprints(&footprints.by_name());
+                }
+            }
+            if *native {
+                report.apply_platform_support(&detect_native(&project, &graph, lock.as_ref()).by_name());
+            }
+            if let Some(owner) = owner {
+                report.retain_owner(owner);
+            }
+            if let Some(package) = focus {
+                if !report.retain_subtree(package) {
+                    eprintln!("❌ {} is not in the dependency graph", package);
+                    std::process::exit(1);
+                }
+            }
+            let findings = template.as_ref().map(|_| {
+                let checks = PolicyOptions {
+                    cycles: true,
+                    production_only: false,
+                    conflicts: true,
+                    limits: DependencyLimits::default(),
+                    bundle: BundleLimits::default(),
+                    budgets: true,
+                    pinning: false,
+                    prerelease: true,
+                    licenses: false,
+                    custom: false,
+                };
+                let project_name = pkg.name.as_deref().unwrap_or("project");
+                let mut findings = checks.run(&deps, &graph, bundle.as_ref(), &config, Path::new(path), project_name);
+                if let Some(owner) = owner {
+                    findings.retain_owner(owner);
+                }
+                findings.findings().to_vec()
+            });
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+            report = report.with_warnings(&warnings).with_metadata(
+                project
+                    .report_metadata(stats.as_deref())
+                    .with_focus(focus.as_ref().map(|package| format!("{} and its dependencies", package))),
+            );
+            if *redact {
+                Redactor::new().with_public_scopes(public_scopes).redact(&mut report);
+            }
+
+            if select.is_some() && !matches!(format, ExportFormat::Json | ExportFormat::D3) {
+                eprintln!("❌ --select works on the json and d3 formats; add --format json.");
+                std::process::exit(1);
+            }
+            if format.is_binary() && output.is_none() {
+                eprintln!("❌ The {} format is binary; use --output to write it to a file.", format);
+                std::process::exit(1);
+            }
+
+            let output = file::output_file(output.as_deref());
+            if *sign && output.is_none() {
+                eprintln!("❌ --sign needs a file to sign; --output - writes to stdout.");
+                std::process::exit(1);
+            }
+            // Check both files first so a refused signature leaves no unsigned report
+            if let (Some(file), false) = (output, *force) {
+                let targets = [Some(file.to_string()), sign.then(|| signature_path(file))];
+                for target in targets.iter().flatten() {
+                    if let Err(e) = file::check_overwrite(Path::new(target)) {
+                        eprintln!("❌ Failed to write {}: {}", target, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            // Load the key before writing so a bad key leaves no unsigned report
+            let signing_key = if *sign {
+                let Some(key_file) = config.signing.key_file.as_ref() else {
+                    eprintln!("❌ --sign needs a private key: set signing.keyFile in {}", CONFIG_FILE);
+                    std::process::exit(1);
+                };
+                match SigningKey::load(&Path::new(path).join(key_file)) {
+                    Ok(key) => Some(key),
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                None
+            };
+
+            let content: Vec<u8> = match format {
+                _ if template.is_some() => render_template(
+                    template.as_deref().unwrap_or_default(),
+                    &report,
+                    findings.unwrap_or_default(),
+                ),
+                ExportFormat::Markdown => MarkdownExporter::new()
+                    .with_top_n(*top)
+                    .export(&report)
+                    .into_bytes(),
+                ExportFormat::Csv if *requirements => CsvExporter::new().export_requirements(&report).into_bytes(),
+                ExportFormat::Csv => {
+                    let mut exporter = CsvExporter::new();
+                    if let Some(list) = columns {
+                        match CsvColumn::parse_list(list) {
+                            Ok(cols) => exporter = exporter.with_columns(cols),
+                            Err(e) => {
+                                eprintln!("❌ Invalid --columns: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    exporter.export(&report).into_bytes()
+                }
+                ExportFormat::Json => JsonExporter::new().export(&report).into_bytes(),
+                ExportFormat::D3 => D3Exporter::new().export(&report).into_bytes(),
+                ExportFormat::Prometheus => PrometheusExporter::new().export(&report).into_bytes(),
+                #[cfg(feature = "xlsx")]
+                ExportFormat::Xlsx => match XlsxExporter::new().export(&report) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        eprintln!("❌ Failed to build xlsx workbook: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                #[cfg(not(feature = "xlsx"))]
+                ExportFormat::Xlsx => {
+                    eprintln!("❌ xlsx export is not available in this build.");
+                    eprintln!("   Rebuild with: cargo install codescope --features xlsx");
+                    std::process::exit(1);
+                }
+            };
+            let content = match select {
+                Some(path) => {
+                    let document: serde_json::Value =
+                        serde_json::from_slice(&content).expect("json and d3 exports are valid JSON");
+                    match select::select(&document, path) {
+                        Ok(value) => select::format_selected(&value).into_bytes(),
+                        Err(e) => {
+                            eprintln!("❌ Invalid --select: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                None => content,
+            };
+
+            match output {
+                Some(file) => {
+                    if let Err(e) = file::write_atomic(Path::new(file), &content, true) {
+                        eprintln!("❌ Failed to write {}: {}", file, e);
+                        std::process::exit(1);
+                    }
+                    match template {
+                        Some(template) => println!("✅ Exported report rendered with {} to {}", template, file),
+                        None => println!("✅ Exported {} report to {}", format, file),
+                    }
+                    if let Some(key) = signing_key {
+                        let sig_file = signature_path(file);
+                        if let Err(e) = file::write_atomic(Path::new(&sig_file), key.sign(&content).as_bytes(), true) {
+                            eprintln!("❌ Failed to write {}: {}", sig_file, e);
+                            std::process::exit(1);
+                        }
+                        println!("🔏 Signed with {} to {}", key.public_key().to_base64(), sig_file);
+                    }
+                }
+                None => {
+                    use std::io::Write;
+                    io::stdout().write_all(&content)?;
+                }
+            }
+        }
+        Some(Commands::Query { expr, path, stats, json }) => {
+            let query = match Query::parse(expr) {
+                Ok(query) => query,
+                Err(e) => {
+                    eprintln!("❌ Invalid query: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let columns = query.columns();
+
+            let project = Project::open(path, cli.single_rev());
+            let (pkg, deps) = load_package(&project, cli.scope());
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            let lock = collect_lockfile(&project, &mut warnings);
+            let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            budget.check("the dependency graph", 0, &mut warnings);
+
+            let config = load_config(&project);
+            if let Some(stats_path) = stats_file(stats.as_ref(), &project, &config) {
+                collect_stats_file(Path::new(path), &stats_path, &deps, &mut graph, &mut budget, &mut warnings);
+            }
+            let mut report = ExportReport::from_graph(
+                pkg.name.as_deref().unwrap_or("project"),
+                pkg.version.as_deref().unwrap_or(""),
+                &graph,
+            );
+            report.apply_ownership(&config);
+            // Measure only what the query asks about
+            let uses = |wanted: &[CsvColumn]| columns.iter().any(|column| wanted.contains(column));
+            if uses(&[CsvColumn::InstallSize, CsvColumn::InstallFiles, CsvColumn::InstallScripts]) {
+                if let Some(footprints) = measure_footprint(&project, &graph) {
+                    report.apply_footprints(&footprints.by_name());
+                }
+            }
+            if uses(&[CsvColumn::Native, CsvColumn::Platforms]) {
+                report.apply_platform_support(&detect_native(&project, &graph, lock.as_ref()).by_name());
+            }
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+
+            let packages = query.run(&report);
+            if *json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&query::to_json(&packages, &columns))
+                        .expect("query results are valid JSON")
+                );
+            } else {
+                print!("{}", query::format_table(&packages, &columns, report.packages.len()));
+            }
+        }
+        Some(Commands::VerifyReport {
+            report,
+            signature,
+            path,
+            public_key,
+        }) => {
+            let key_text = match public_key {
+                Some(key) => key.clone(),
+                None => match load_config(&Project::open(path, None)).signing.public_key {
+                    Some(key) => key,
+                    None => {
+                        eprintln!(
+                            "❌ No public key: pass --public-key or set signing.publicKey in {}",
+                            CONFIG_FILE
+                        );
+                        std::process::exit(1);
+                    }
+                },
+            };
+            let signature = signature.clone().unwrap_or_else(|| signature_path(report));
+            let result = VerifyingKey::parse(&key_text).and_then(|key| {
+                let content = std::fs::read(report).map_err(|e| SigningError::Io(report.clone(), e))?;
+                let signature_text =
+                    std::fs::read_to_string(&signature).map_err(|e| SigningError::Io(signature.clone(), e))?;
+                key.verify(&content, &signature_text).map(|()| key)
+            });
+            match result {
+                Ok(key) => println!("✅ {} is signed by {}", report, key.to_base64()),
+                Err(e) => {
+                    eprintln!("❌ {}: {}", report, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Aggregate { reports, policy, json }) => {
+            let licenses = match policy {
+                Some(file) => {
+                    let config = std::fs::read_to_string(file)
+                        .map_err(|e| e.to_string())
+                        .and_then(|content| {
+                            let format = if file.ends_with(".toml") { ConfigFormat::Toml } else { ConfigFormat::Json };
+                            ProjectConfig::parse_as(&content, format).map_err(|e| e.to_string())
+                        });
+                    match config {
+                        Ok(config) => config.licenses,
+                        Err(e) => {
+                            eprintln!("❌ Failed to read the policy {}: {}", file, e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                None => LicensePolicy::default(),
+            };
+
+            // An unreadable report leaves the others to aggregate
+            let mut warnings = AnalysisWarnings::new();
+            let projects: Vec<ExportedProject> = reports
+                .iter()
+                .filter_map(|file| {
+                    ExportedProject::from_file(file)
+                        .map_err(|e| warnings.push(file.as_str(), format!("{}; report skipped", e)))
+                        .ok()
+                })
+                .collect();
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+            if projects.is_empty() {
+                eprintln!("❌ None of the reports could be read.");
+                std::process::exit(1);
+            }
+
+            let report = AggregateReport::from_projects(&projects, &licenses);
+            if *json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("aggregate report is always serializable")
+                );
+            } else {
+                print!("{}", report.format_report(AGGREGATE_TOP));
+            }
+        }
+        Some(Commands::Compare { paths, stats, no_tui }) => {
+            // One --rev compares it with the working tree, as diff does
+            let revs: [Option<&str>; 2] = match cli.rev.as_slice() {
+                [] => [None, None],
+                [rev] => [Some(rev), None],
+                [left, right, ..] => [Some(left), Some(right)],
+            };
+            let path_of = |side: usize| paths[side.min(paths.len() - 1)].as_str();
+            if path_of(0) == path_of(1) && revs[0] == revs[1] {
+                eprintln!("❌ compare needs two paths, or --rev to compare a project with another snapshot of itself.");
+                std::process::exit(1);
+            }
+
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            let mut sides = Vec::new();
+            for (side, rev) in revs.into_iter().enumerate() {
+                let path = path_of(side);
+                let project = Project::open(path, rev);
+                let (_, deps) = load_package(&project, cli.scope());
+                let lock = load_lockfile(&project);
+                let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+                if let Some(stats_path) = stats.get(side) {
+                    collect_stats_file(Path::new(path), stats_path, &deps, &mut graph, &mut budget, &mut warnings);
+                }
+                let label = if path_of(0) == path_of(1) {
+                    project.label()
+                } else {
+                    project.describe(Path::new(path))
+                };
+                sides.push((label, graph));
+            }
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+
+            let rows = compare_graphs(&sides[0].1, &sides[1].1);
+            let (left, right) = (&sides[0].0, &sides[1].0);
+            if *no_tui {
+                print!("{}", format_comparison(&rows, left, right));
+                return Ok(());
+            }
+
+            enable_raw_mode()?;
+            let mut stdout = io::stdout();
+            execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+            let backend = CrosstermBackend::new(stdout);
+            let mut terminal = Terminal::new(backend)?;
+
+            let mut app = CompareApp::new(rows, left.as_str(), right.as_str());
+            let result = run_compare(&mut terminal, &mut app);
+
+            disable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )?;
+            terminal.show_cursor()?;
+
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Diff { path, json }) => {
+            let (before, after) = match cli.rev.as_slice() {
+                [rev] => (Project::open(path, Some(rev)), Project::open(path, None)),
+                [old, new] => (Project::open(path, Some(old)), Project::open(path, Some(new))),
+                _ => {
+                    eprintln!("❌ diff needs one --rev to compare with the working tree, or two to compare with each other.");
+                    std::process::exit(1);
+                }
+            };
+
+            let graph_of = |project: &Project| {
+                let (_, deps) = load_package(project, cli.scope());
+                let lock = load_lockfile(project);
+                build_dependency_graph(&deps, lock.as_ref(), cli.scope())
+            };
+            let diff = diff_graphs(&graph_of(&before), &graph_of(&after));
+
+            if *json {
+                let mut value = serde_json::to_value(&diff).expect("diff is always serializable");
+                value["before"] = before.to_json();
+                value["after"] = after.to_json();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&value).expect("diff is always serializable")
+                );
+            } else {
+                print!("{}", diff.format_report(&before.label(), &after.label()));
+            }
+        }
+        Some(Commands::Affected { path, base, head, json }) => {
+            let graph = WorkspaceGraph::from_workspace(&load_workspace(path));
+            let files = Repository::discover(Path::new(path)).and_then(|repo| repo.changed_files(base, head.as_deref()));
+            let files = match files {
+                Ok(files) => files,
+                Err(e) => {
+                    eprintln!("❌ Could not list changed files: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let affected = graph.affected_by(&files);
+
+            if *json {
+                let value = serde_json::json!({
+                    "base": base,
+                    "head": head,
+                    "files": files,
+                    "changed": affected.changed,
+                    "dependents": affected.dependents,
+                    "root_files": affected.root_files,
+                    "affected": affected.all(),
+                });
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&value).expect("affected packages are always serializable")
+                );
+            } else {
+                for name in affected.all() {
+                    println!("{}", name);
+                }
+            }
+        }
+        Some(Commands::BuildStats { path, bundler, output, force }) => {
+            // Refuse before the build rather than after it
+            if let (Some(file), false) = (output, *force) {
+                if let Err(e) = file::check_overwrite(Path::new(file)) {
+                    eprintln!("❌ Failed to write {}: {}", file, e);
+                    std::process::exit(1);
+                }
+            }
+            let project_dir = Path::new(path);
+            let raw_stats = std::env::temp_dir().join(format!("codescope-bundler-stats-{}.json", std::process::id()));
+            let (bundler, stats) = build_stats(project_dir, *bundler, &raw_stats).unwrap_or_else(|e| {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            });
+            let _ = std::fs::remove_file(&raw_stats);
+
+            let output_given = output.is_some();
+            let output = output.as_ref().map(PathBuf::from).unwrap_or_else(|| {
+                std::env::temp_dir().join(format!("codescope-stats-{}.json", std::process::id()))
+            });
+            let json = serde_json::to_string(&stats).expect("webpack stats are always serializable");
+            if let Err(e) = file::write_atomic(&output, json.as_bytes(), *force || !output_given) {
+                eprintln!("❌ Failed to write {}: {}", output.display(), e);
+                std::process::exit(1);
+            }
+
+            print!("{}", stats.analyze().format_summary(15));
+            println!();
+            println!("✅ Saved the {} stats to {}", bundler, output.display());
+            println!("   Explore them with: codescope analyze --path {} --stats {}", path, output.display());
+        }
+        Some(Commands::Licenses { path, notices, force }) => {
+            let project = Project::open(path, None);
+            let (pkg, deps) = load_package(&project, cli.scope());
+            let lock = load_lockfile(&project);
+            let graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            let store = PackageStore::detect(&project.dir).unwrap_or_else(|e| {
+                eprintln!("❌ Failed to locate installed packages: {}", e);
+                std::process::exit(1);
+            });
+            let report = LicenseReport::from_graph(&graph, &store);
+
+            let Some(notices) = notices else {
+                print!("{}", report.format_table());
+                return Ok(());
+            };
+            let project_name = pkg.name.as_deref().unwrap_or("This project");
+            let html = [".html", ".htm"].iter().any(|ext| notices.to_lowercase().ends_with(ext));
+            let content = if html {
+                report.notices_html(project_name)
+            } else {
+                report.notices_text(project_name)
+            };
+            if let Err(e) = file::write_atomic(Path::new(notices), content.as_bytes(), *force) {
+                eprintln!("❌ Failed to write {}: {}", notices, e);
+                std::process::exit(1);
+            }
+            println!("✅ Wrote third-party notices for {} packages to {}", report.packages.len(), notices);
+            let without_text = report.without_text();
+            if !without_text.is_empty() {
+                let names: Vec<&str> = without_text.iter().map(|p| p.name.as_str()).collect();
+                eprintln!("⚠️  No license file found for: {}", names.join(", "));
+            }
+            if !report.missing.is_empty() {
+                eprintln!("⚠️  {} package(s) are not installed and were left out; run npm install first.", report.missing.len());
+            }
+        }
+        Some(Commands::Ratchet { path, stats, down_only, dry_run }) => {
+            if !cli.rev.is_empty() {
+                eprintln!("❌ ratchet rewrites the working tree's configuration and cannot be combined with --rev.");
+                std::process::exit(1);
+            }
+            let project = Project::open(path, None);
+            let Ok(Some((content, format))) = read_config(&project) else {
+                eprintln!("❌ No {} or {} to ratchet in {}.", CONFIG_FILE, TOML_CONFIG_FILE, project.dir.display());
+                std::process::exit(1);
+            };
+            let config = load_config(&project);
+            let (_, deps) = load_package(&project, cli.scope());
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            let lock = collect_lockfile(&project, &mut warnings);
+            let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            let stats = stats_file(stats.as_ref(), &project, &config);
+            if let Some(stats_path) = &stats {
+                collect_stats_file(&project.dir, stats_path, &deps, &mut graph, &mut budget, &mut warnings);
+            }
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+
+            let tooling = if config.tooling.is_empty() {
+                None
+            } else {
+                measure_footprint(&project, &graph).map(|footprint| ToolingReport::from_footprint(&footprint, &graph))
+            };
+            let measured = ratchet_measurements(&summarize_budgeted_groups(&config, &graph), tooling.as_ref());
+            let ratchet = if *down_only { Ratchet::new().down_only() } else { Ratchet::new() };
+            let (rewritten, changes) = ratchet.apply_as(&content, format, &measured).unwrap_or_else(|e| {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            });
+
+            if changes.is_empty() {
+                println!("✅ Every threshold already matches the measured values.");
+                return Ok(());
+            }
+            for change in &changes {
+                println!("  {}", change);
+            }
+            if *dry_run {
+                println!("Would update {} threshold(s) in {}.", changes.len(), format.file_name());
+                return Ok(());
+            }
+            let config_path = project.dir.join(format.file_name());
+            if let Err(e) = std::fs::write(&config_path, rewritten) {
+                eprintln!("❌ Failed to write {}: {}", config_path.display(), e);
+                std::process::exit(1);
+            }
+            println!("✅ Updated {} threshold(s) in {}", changes.len(), config_path.display());
+        }
+        Some(Commands::Monitor { path, interval, stats, once }) => {
+            if !cli.rev.is_empty() {
+                eprintln!("❌ monitor analyzes the working tree and cannot be combined with --rev.");
+                std::process::exit(1);
+            }
+            run_monitor(&cli, path, stats.as_deref(), interval, *once);
+        }
+        Some(Commands::Graph { path, ascii, focus, depth, max_nodes, export, output, force, stats, condense }) => {
+            let project = Project::open(path, cli.single_rev());
+            let (pkg, deps) = load_package(&project, cli.scope());
+            let lock = load_lockfile(&project);
+            let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+            if let Some(stats_path) = stats {
+                let mut warnings = AnalysisWarnings::new();
+                let mut budget = cli.memory_budget();
+                budget.check("the dependency graph", 0, &mut warnings);
+                collect_stats_file(Path::new(path), stats_path, &deps, &mut graph, &mut budget, &mut warnings);
+                eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+            }
+
+            let condensation = condense.then(|| Condensation::of(&graph));
+            let (graph, focus) = match &condensation {
+                // A focused package inside a cycle is drawn as its cycle
+                Some(condensation) => (
+                    condensation.to_graph(&graph),
+                    focus.as_ref().map(|package| {
+                        condensation
+                            .component_of(package)
+                            .map_or(package.clone(), |component| component.label.clone())
+                    }),
+                ),
+                None => (graph, focus.clone()),
+            };
+
+            let layout = match &focus {
+                Some(package) => LayeredLayout::focused(&graph, package, *depth).unwrap_or_else(|| {
+                    eprintln!("❌ Package '{}' is not in the dependency graph.", package);
+                    std::process::exit(1);
+                }),
+                None => LayeredLayout::project(&graph, pkg.name.as_deref().unwrap_or("project"), *depth),
+            };
+            if let Some(format) = export {
+                let svg = SvgExporter::new().export(&layout);
+                let content = match format {
+                    ImageFormat::Svg => svg.into_bytes(),
+                    #[cfg(feature = "png")]
+                    ImageFormat::Png => render_png(&svg, 2.0).unwrap_or_else(|e| {
+                        eprintln!("❌ Failed to render PNG: {}", e);
+                        std::process::exit(1);
+                    }),
+                    #[cfg(not(feature = "png"))]
+                    ImageFormat::Png => {
+                        eprintln!("❌ PNG export is not available in this build.");
+                        eprintln!("   Rebuild with: cargo install codescope --features png");
+                        std::process::exit(1);
+                    }
+                };
+                match file::output_file(output.as_deref()) {
+                    Some(file) => {
+                        if let Err(e) = file::write_atomic(Path::new(file), &content, *force) {
+                            eprintln!("❌ Failed to write {}: {}", file, e);
+                            std::process::exit(1);
+                        }
+                        println!("✅ Exported {} graph to {}", format, file);
+                    }
+                    None => {
+                        use std::io::Write;
+                        io::stdout().write_all(&content)?;
+                    }
+                }
+                return Ok(());
+            }
+
+            let packages = layout.nodes.len() - 1;
+            if packages > *max_nodes {
+                eprintln!("❌ The graph has {} packages, too many to draw legibly (limit {}).", packages, max_nodes);
+                eprintln!("   Narrow it with --focus <PACKAGE>, --depth <LEVELS> or --prod, or raise --max-nodes.");
+                std::process::exit(1);
+            }
+            let charset = if *ascii { Charset::Ascii } else { Charset::Unicode };
+            print!("{}", draw_graph(&layout, charset));
+            if let Some(condensation) = &condensation {
+                for component in condensation.cycles() {
+                    println!("{} = {}", component.label, component.members.join(", "));
+                }
+            }
+        }
+        Some(Commands::Workspace { command }) => match command {
+            WorkspaceCommands::Duplicates { path, json } => {
+                let workspace = load_workspace(path);
+                let lock = load_lockfile(&Project::open(path, None));
+                let duplicates = find_workspace_duplicates(&workspace, lock.as_ref());
+
+                if *json {
+                    let entries: Vec<serde_json::Value> = duplicates
+                        .iter()
+                        .map(|dup| {
+                            let mut value = serde_json::to_value(dup).expect("duplicates are always serializable");
+                            value["resolution"] = dup.resolution().into();
+                            value
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&entries).expect("duplicates are always serializable")
+                    );
+                } else {
+                    print!("{}", format_duplicates(&duplicates));
+                }
+            }
+            WorkspaceCommands::Graph { path, json, order } => {
+                let graph = WorkspaceGraph::from_workspace(&load_workspace(path));
+                let cycles = graph.cycles();
+
+                if *json {
+                    let value = serde_json::json!({
+                        "packages": graph.packages(),
+                        "cycles": cycles.iter().map(|c| c.cycle_path()).collect::<Vec<_>>(),
+                        "build_order": graph.build_order(),
+                    });
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&value).expect("workspace graph is always serializable")
+                    );
+                } else if *order {
+                    let build_order = graph.build_order();
+                    for stage in &build_order.stages {
+                        println!("{}", stage.join(" "));
+                    }
+                    if !build_order.is_complete() {
+                        eprintln!("❌ Cannot order {}: cycle between workspace packages", build_order.blocked.join(", "));
+                    }
+                } else {
+                    print!("{}", format_workspace_graph(&graph));
+                }
+
+                if !cycles.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+        },
+        Some(Commands::Version) => {
+            println!("codescope v{}", env!("CARGO_PKG_VERSION"));
+        }
+        None => {
+            println!("CodeScope - Terminal UI Dependency Analyzer");
+            println!();
+            println!("Usage:");
+            println!("  codescope analyze [OPTIONS]     Analyze dependencies");
+            println!("  codescope export [OPTIONS]      Export a dependency report");
+            println!("  codescope diff --rev <REV>      Compare dependencies between revisions");
+            println!("  codescope affected --base <REV> List monorepo packages affected by changes");
+            println!("  codescope build-stats           Build the project and analyze its bundle");
+            println!("  codescope graph                 Draw the dependency graph as box art");
+            println!("  codescope licenses              List licenses and write third-party notices");
+            println!("  codescope workspace duplicates  Find version duplicates across a monorepo");
+            println!("  codescope workspace graph       Show the build order of a monorepo");
+            println!("  codescope version               Show version");
+            println!();
+            println!("Run 'codescope --help' for more options");
+        }
+    }
+
+    Ok(())
+}
+
+/// Load and parse the package.json in `path`, exiting with an error message on failure
+///
+/// Only dependencies in `scope` are returned.
+fn load_package(project: &Project, scope: Scope) -> (parser::PackageJson, Vec<parser::Dependency>) {
+    match read_package(project, scope) {
+        Ok(package) => package,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            if project.revision.is_none() && !project.dir.join("package.json").exists() {
+                eprintln!("   Run this command in a directory with a package.json file.");
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Load and parse the package.json of `project`
+///
+/// Only dependencies in `scope` are returned.
+#[tracing::instrument(name = "parse", skip_all, err)]
+fn read_package(
+    project: &Project,
+    scope: Scope,
+) -> Result<(parser::PackageJson, Vec<parser::Dependency>), String> {
+    let content = project.read("package.json")?.ok_or_else(|| {
+        format!(
+            "No package.json found at: {}",
+            project.describe(&project.dir.join("package.json"))
+        )
+    })?;
+
+    // Parse package.json
+    let pkg = parse_str(&content).map_err(|e| format!("Failed to parse package.json: {}", e))?;
+
+    // Extract dependencies
+    let mut deps = extract_dependencies(&pkg);
+    scope.filter(&mut deps);
+    Ok((pkg, deps))
+}
+
+/// Applies the bundle sizes of a webpack stats file to the graph and
+/// returns the bundle analysis. Entries the parser skipped are added to
+/// `warnings`; close to the memory limit, module-level detail is dropped.
+#[tracing::instrument(name = "stats", skip_all, fields(file = stats_path), err)]
+fn apply_stats_file(
+    project_dir: &Path,
+    stats_path: &str,
+    deps: &[parser::Dependency],
+    graph: &mut DependencyGraph,
+    budget: &mut MemoryBudget,
+    warnings: &mut AnalysisWarnings,
+) -> Result<BundleAnalysis, String> {
+    let file_size = std::fs::metadata(stats_path).map(|m| m.len()).unwrap_or(0);
+    budget.check("the webpack module list", file_size.saturating_mul(STATS_EXPANSION), warnings);
+    let mut stats = WebpackStats::from_file(stats_path)
+        .map_err(|e| format!("Failed to read webpack stats {}: {}", stats_path, e))?;
+    warnings.extend_from_parse(stats_path, &stats.parse_warnings);
+    if budget.check("the webpack module list", 0, warnings) == Fidelity::Reduced {
+        stats.shed_module_detail();
+    }
+    let mut analysis = stats.analyze();
+    drop(stats);
+    attribute_vendored_packages(project_dir, &mut analysis, deps);
+    apply_bundle_sizes_to_graph(graph, &analysis);
+    if budget.check("the bundle analysis", 0, warnings) == Fidelity::Reduced {
+        analysis.shed_module_detail();
+    }
+    Ok(analysis)
+}
+
+/// Like [`apply_stats_file`], but an unreadable stats file is a warning:
+/// the analysis continues without bundle sizes.
+fn collect_stats_file(
+    project_dir: &Path,
+    stats_path: &str,
+    deps: &[parser::Dependency],
+    graph: &mut DependencyGraph,
+    budget: &mut MemoryBudget,
+    warnings: &mut AnalysisWarnings,
+) -> Option<BundleAnalysis> {
+    apply_stats_file(project_dir, stats_path, deps, graph, budget, warnings)
+        .map_err(|e| warnings.push(stats_path, format!("{}; bundle sizes are unavailable", e)))
+        .ok()
+}
+
+/// Parses `--savings-threshold`, where a bare number is in KB as it was
+/// before units were accepted.
+fn parse_savings_threshold(value: &str) -> Result<u64, String> {
+    match value.trim().parse::<u64>() {
+        Ok(kb) => Ok(kb * 1024),
+        Err(_) => parse_size(value),
+    }
+}
+
+/// POSTs the findings of failed checks to the configured webhook.
+///
+/// A notification that cannot be sent is reported as a warning; the exit
+/// code stays that of the checks.
+fn send_notification(
+    report: &PolicyReport,
+    project_name: &str,
+    format: NotifyFormat,
+    config: &ProjectConfig,
+    project_dir: &Path,
+    offline: bool,
+) {
+    let Some(url) = std::env::var(WEBHOOK_URL_VAR)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .or_else(|| config.notify.webhook_url.clone())
+    else {
+        eprintln!(
+            "⚠️  Not notifying: set {} or notify.webhookUrl in {}.",
+            WEBHOOK_URL_VAR, CONFIG_FILE
+        );
+        return;
+    };
+    if offline {
+        eprintln!("⚠️  Not notifying the webhook in offline mode.");
+        return;
+    }
+    let npmrc = NpmrcConfig::load(project_dir).unwrap_or_default();
+    let body = notify::payload(report, project_name, format).to_string();
+    match HttpTransport::from_npmrc(&npmrc).and_then(|transport| transport.post_json(&url, &body)) {
+        Ok(_) => eprintln!("📣 Sent {} finding(s) to the webhook.", report.findings().len()),
+        // The URL is a credential, so the errors leave it out
+        Err(NetError::Http { status, .. }) => eprintln!("⚠️  Failed to notify: the webhook answered HTTP {}.", status),
+        Err(NetError::Transport { message, .. }) => eprintln!("⚠️  Failed to notify the webhook: {}", message),
+        Err(e) => eprintln!("⚠️  Failed to notify the webhook: {}", e),
+    }
+}
+
+/// Starts exporting spans if a collector is configured, unless `offline`.
+/// Failing to set up the exporter is a warning: the analysis runs all the
+/// same.
+#[cfg(feature = "otel")]
+fn start_telemetry(endpoint: Option<&str>, offline: bool) -> Option<codescope::telemetry::Telemetry> {
+    use codescope::telemetry::Telemetry;
+
+    if offline && Telemetry::is_configured(endpoint) {
+        eprintln!("⚠️  Not exporting spans in offline mode.");
+    }
+    Telemetry::init(endpoint, offline).unwrap_or_else(|e| {
+        eprintln!("⚠️  {}; spans are not exported", e);
+        None
+    })
+}
+
+#[cfg(not(feature = "otel"))]
+fn start_telemetry(endpoint: Option<&str>, _offline: bool) -> Option<()> {
+    if endpoint.is_some() {
+        eprintln!("❌ OpenTelemetry export is not available in this build.");
+        eprintln!("   Rebuild with: cargo install codescope --features otel");
+        std::process::exit(1);
+    }
+    None
+}
+
+/// Renders `report` through the Handlebars template in `file`, exiting on
+/// errors.
+#[cfg(feature = "templates")]
+fn render_template(file: &str, report: &ExportReport, findings: Vec<codescope::policy::Finding>) -> Vec<u8> {
+    let source = std::fs::read_to_string(file).unwrap_or_else(|e| {
+        eprintln!("❌ Failed to read {}: {}", file, e);
+        std::process::exit(1);
+    });
+    let html = Path::new(file)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+    TemplateExporter::new(&source)
+        .and_then(|exporter| exporter.with_html_escape(html).with_findings(findings).export(report))
+        .unwrap_or_else(|e| {
+            eprintln!("❌ {}: {}", file, e);
+            std::process::exit(1);
+        })
+        .into_bytes()
+}
+
+#[cfg(not(feature = "templates"))]
+fn render_template(_file: &str, _report: &ExportReport, _findings: Vec<codescope::policy::Finding>) -> Vec<u8> {
+    eprintln!("❌ Template export is not available in this build.");
+    eprintln!("   Rebuild with: cargo install codescope --features templates");
+    std::process::exit(1);
+}
+
+/// Turns the findings of the `--warn-only` checks, or every finding with
+/// `--no-fail`, into warnings.
+fn downgrade_findings(report: &mut PolicyReport, warn_only: &[String], no_fail: bool) {
+    for check in warn_only {
+        if !report.ran(check) {
+            eprintln!("⚠️  --warn-only {}: no such check ran.", check);
+        }
+        report.downgrade(check);
+    }
+    if no_fail {
+        report.downgrade_all();
+    }
+}
+
+/// The policy checks selected on the command line.
+#[derive(Clone, Copy)]
+struct PolicyOptions {
+    cycles: bool,
+    production_only: bool,
+    conflicts: bool,
+    limits: DependencyLimits,
+    bundle: BundleLimits,
+    budgets: bool,
+    pinning: bool,
+    prerelease: bool,
+    licenses: bool,
+    custom: bool,
+}
+
+impl PolicyOptions {
+    /// Returns true if any check is selected.
+    fn any(&self) -> bool {
+        self.cycles
+            || self.conflicts
+            || self.budgets
+            || self.pinning
+            || self.prerelease
+            || self.licenses
+            || self.custom
+            || !self.limits.is_empty()
+            || !self.bundle.is_empty()
+    }
+
+    /// Runs the selected checks and assigns the findings to their owners.
+    fn run(
+        &self,
+        deps: &[parser::Dependency],
+        graph: &DependencyGraph,
+        bundle: Option<&BundleAnalysis>,
+        config: &ProjectConfig,
+        project_dir: &Path,
+        project_name: &str,
+    ) -> PolicyReport {
+        let mut report = PolicyReport::new();
+        if self.cycles {
+            policy::check_cycles(graph, self.production_only, &mut report);
+        }
+        if self.conflicts {
+            policy::check_conflicts(graph, &mut report);
+        }
+        self.limits.evaluate(graph, &mut report);
+        if let Some(bundle) = bundle {
+            self.bundle.evaluate(bundle.shipped_size(), &mut report);
+        }
+        if self.budgets {
+            policy::check_group_budgets(&summarize_budgeted_groups(config, graph), &mut report);
+        }
+        if self.pinning {
+            policy::check_pinning(deps, &config.pinning, &mut report);
+        }
+        if self.prerelease {
+            policy::check_prereleases(graph, &mut report);
+        }
+        if self.licenses {
+            match PackageStore::detect(project_dir) {
+                Ok(store) => {
+                    let licenses = LicenseReport::from_graph(graph, &store);
+                    policy::check_licenses(&licenses, &config.licenses, &mut report);
+                }
+                Err(e) => eprintln!("⚠️  Skipping the license check, failed to locate installed packages: {}", e),
+            }
+        }
+        if self.custom {
+            let ctx = CheckContext::new(graph).with_project_name(project_name);
+            CheckRegistry::from_config(config, project_dir).run(&ctx, &mut report);
+        }
+        report.assign_owners(config);
+        report
+    }
+}
+
+/// Watches the project's input files and re-runs the analysis on every
+/// change, printing events until interrupted.
+///
+/// Cycles and conflicts are always checked; the other checks follow the
+/// command-line flags.
+#[cfg(feature = "watch-mode")]
+fn run_watch(cli: &Cli, path: &str, stats: Option<&str>, checks: PolicyOptions, json: bool) -> ! {
+    use codescope::watch::{diff, FileWatcher, Snapshot, WatchEvent};
+    use std::io::Write;
+
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+    let checks = PolicyOptions {
+        cycles: true,
+        conflicts: true,
+        ..checks
+    };
+    let project = Project::open(path, None);
+    let project_dir = project.dir.as_path();
+    selected_owner(cli.owner.as_deref(), &load_config(&project));
+    let mut watcher = FileWatcher::new(watched_files(&project, stats));
+
+    let emit = |event: WatchEvent| {
+        if json {
+            println!("{}", event.to_json_line());
+        } else {
+            println!("{}", event);
+        }
+        let _ = io::stdout().flush();
+    };
+
+    let mut previous: Option<Snapshot> = None;
+    let mut changed: Vec<String> = Vec::new();
+    for run in 1.. {
+        emit(WatchEvent::AnalysisStarted { run, changed });
+        match snapshot_project(cli, &project, stats, checks) {
+            Ok(snapshot) => {
+                for event in diff(run, previous.as_ref(), &snapshot) {
+                    emit(event);
+                }
+                emit(WatchEvent::AnalysisFinished {
+                    run,
+                    packages: snapshot.package_count(),
+                    total_bundle_size: snapshot.total_bundle_size(),
+                    findings: snapshot.findings().len(),
+                });
+                previous = Some(snapshot);
+            }
+            Err(message) => emit(WatchEvent::Error { run, message }),
+        }
+
+        changed = loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let files = watcher.changed();
+            if !files.is_empty() {
+                break files
+                    .iter()
+                    .map(|f| f.strip_prefix(project_dir).unwrap_or(f).display().to_string())
+                    .collect();
+            }
+        };
+    }
+    unreachable!("watch loop only ends when the process is interrupted")
+}
+
+/// Returns the input files of the analysis that watch mode polls.
+#[cfg(feature = "watch-mode")]
+fn watched_files(project: &Project, stats: Option<&str>) -> Vec<PathBuf> {
+    let mut files = vec![
+        project.dir.join("package.json"),
+        project.dir.join(PACKAGE_LOCK),
+        project.dir.join(CONFIG_FILE),
+        project.dir.join(TOML_CONFIG_FILE),
+    ];
+    files.extend(stats.map(PathBuf::from));
+    files
+}
+
+/// Runs the TUI while watching the project's input files: after every
+/// change the tree is rebuilt from a new analysis on a background thread,
+/// so keys keep working meanwhile, and the packages whose bundle size
+/// changed are badged.
+#[cfg(feature = "watch-mode")]
+fn run_tui_watch<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    cli: &Cli,
+    project: &Project,
+    stats: Option<&str>,
+    graph: &DependencyGraph,
+) -> io::Result<()> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+
+    use codescope::policy::PolicyReport;
+    use codescope::ui::changes::SizeChange;
+    use codescope::ui::events::{AnalysisUpdate, AppEvent};
+    use codescope::ui::run_app_watching;
+    use codescope::watch::{diff, FileWatcher, Snapshot, WatchEvent};
+
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+    let mut watcher = FileWatcher::new(watched_files(project, stats));
+    let mut previous = Snapshot::new(graph, &PolicyReport::new());
+    let (updates, received) = mpsc::channel();
+    let stop = AtomicBool::new(false);
+    app.record_size_changes(1, Vec::new());
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut run = 1;
+            while !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+                if watcher.changed().is_empty() {
+                    continue;
+                }
+                run += 1;
+                if updates.send(AppEvent::AnalysisUpdate(AnalysisUpdate::Started { run })).is_err() {
+                    break;
+                }
+                let event = match reanalyze_tree(cli, project, stats) {
+                    Ok((tree, graph)) => {
+                        let snapshot = Snapshot::new(&graph, &PolicyReport::new());
+                        let now = std::time::Instant::now();
+                        let changes = diff(run, Some(&previous), &snapshot)
+                            .into_iter()
+                            .filter_map(|event| match event {
+                                WatchEvent::SizeChanged { package, before, after, .. } => Some(SizeChange {
+                                    package,
+                                    before,
+                                    after,
+                                    run,
+                                    at: now,
+                                }),
+                                _ => None,
+                            })
+                            .collect();
+                        previous = snapshot;
+                        AppEvent::AnalysisUpdate(AnalysisUpdate::Finished { run, tree, changes })
+                    }
+                    Err(message) => AppEvent::Error(format!("Run {} failed: {}", run, message)),
+                };
+                if updates.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        let result = run_app_watching(terminal, app, &received);
+        // The watcher sees this before its next poll and ends the scope
+        stop.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+/// Analyzes the working tree again for the watching TUI, returning the new
+/// tree and graph. Warnings are left out, as they would draw over the TUI.
+#[cfg(feature = "watch-mode")]
+fn reanalyze_tree(cli: &Cli, project: &Project, stats: Option<&str>) -> Result<(TreeNode, DependencyGraph), String> {
+    let (pkg, mut deps) = read_package(project, cli.scope())?;
+    let mut warnings = AnalysisWarnings::new();
+    let lock = collect_lockfile(project, &mut warnings);
+    let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+    if let Some(stats_path) = stats {
+        let mut budget = cli.memory_budget();
+        apply_stats_file(&project.dir, stats_path, &deps, &mut graph, &mut budget, &mut warnings)?;
+    }
+    if let Some(owner) = cli.owner.as_deref() {
+        let config = ProjectConfig::load(&project.dir).map_err(|e| e.to_string())?;
+        deps.retain(|dep| config.is_owned_by(&dep.name, owner));
+    }
+    Ok((analyzed_tree(&pkg, &deps, &graph), graph))
+}
 
-            if !package_json_path.exists() {
-                eprintln!("❌ No package.json found at: {}", package_json_path.display());
-                eprintln!("   Run this command in a directory with a package.json file.");
-                std::process::exit(1);
-            }
+/// Analyzes the working tree for watch and monitor mode, returning the
+/// package sizes and the findings of `checks`.
+#[cfg(feature = "watch-mode")]
+fn snapshot_project(
+    cli: &Cli,
+    project: &Project,
+    stats: Option<&str>,
+    checks: PolicyOptions,
+) -> Result<codescope::watch::Snapshot, String> {
+    let project_dir = project.dir.as_path();
+    let (pkg, deps) = read_package(project, cli.scope())?;
+    let lock = load_lockfile(project);
+    let mut graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+    let bundle = stats
+        .map(|stats_path| {
+            let mut warnings = AnalysisWarnings::new();
+            let mut budget = cli.memory_budget();
+            budget.check("the dependency graph", 0, &mut warnings);
+            let bundle = apply_stats_file(project_dir, stats_path, &deps, &mut graph, &mut budget, &mut warnings);
+            eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+            bundle
+        })
+        .transpose()?;
+    let config = ProjectConfig::load(project_dir).map_err(|e| e.to_string())?;
+    let project_name = pkg.name.as_deref().unwrap_or("project");
+    let mut report = checks.run(&deps, &graph, bundle.as_ref(), &config, project_dir, project_name);
+    if let Some(owner) = cli.owner.as_deref() {
+        report.retain_owner(owner);
+    }
+    Ok(codescope::watch::Snapshot::new(&graph, &report))
+}
 
-            // Parse package.json
-            let pkg = match parse_file(&package_json_path) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("❌ Failed to parse package.json: {}", e);
-                    std::process::exit(1);
-                }
-            };
+/// Re-runs the analysis every `interval`, comparing each run with the
+/// previous one (across restarts, through the saved monitor state) and
+/// summarizing the changes in a desktop notification.
+#[cfg(feature = "watch-mode")]
+fn run_monitor(cli: &Cli, path: &str, stats: Option<&str>, interval: &str, once: bool) {
+    use codescope::watch::monitor::{notify, parse_interval, MonitorState, MonitorSummary};
+    use codescope::watch::{diff, WatchEvent};
 
-            // Extract dependencies
-            let deps = extract_dependencies(&pkg);
+    let interval = parse_interval(interval).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+    let checks = PolicyOptions {
+        cycles: true,
+        production_only: false,
+        conflicts: true,
+        limits: DependencyLimits::default(),
+        bundle: BundleLimits::default(),
+        budgets: true,
+        pinning: true,
+        prerelease: false,
+        licenses: false,
+        custom: false,
+    };
+    let project = Project::open(path, None);
+    selected_owner(cli.owner.as_deref(), &load_config(&project));
+    let state_path = MonitorState::default_path(&project.dir);
+    if state_path.is_none() {
+        eprintln!("⚠️  No cache directory found; runs are only compared while the monitor keeps running.");
+    }
+    let mut previous = state_path.as_deref().and_then(MonitorState::load);
+    let mut notifications = true;
 
-            // Build dependency graph for cycle detection
-            let graph = build_dependency_graph(&deps);
-
-            // Handle --check-cycles flag (for CI usage)
-            if *check_cycles {
-                let cycles = graph.get_cycle_details();
-                if cycles.is_empty() {
-                    println!("✅ No circular dependencies detected.");
-                    return Ok(());
-                } else {
-                    eprintln!("❌ Circular dependencies detected!");
-                    eprintln!();
-                    for (i, cycle) in cycles.iter().enumerate() {
-                        eprintln!("  Cycle {}: {}", i + 1, cycle.cycle_path());
+    for run in 1.. {
+        match snapshot_project(cli, &project, stats, checks) {
+            Ok(snapshot) => {
+                let events = diff(run, previous.as_ref().map(|state| &state.snapshot), &snapshot);
+                match &previous {
+                    None => println!(
+                        "📋 First run: {} packages, {} finding(s). Changes are reported from the next run.",
+                        snapshot.package_count(),
+                        snapshot.findings().len()
+                    ),
+                    Some(state) => {
+                        let summary = MonitorSummary::new(&state.snapshot, &snapshot, &events);
+                        if summary.is_empty() {
+                            println!("✅ No changes since the last run.");
+                        } else {
+                            let name = read_package(&project, Scope::All)
+                                .ok()
+                                .and_then(|(pkg, _)| pkg.name)
+                                .unwrap_or_else(|| project.dir.display().to_string());
+                            println!("{}", summary.title(&name));
+                            println!("{}", summary.body());
+                            if notifications {
+                                if let Err(e) = notify(&summary.title(&name), &summary.body()) {
+                                    eprintln!("⚠️  {}", e);
+                                    notifications = false;
+                                }
+                            }
+                        }
                     }
-                    eprintln!();
-                    eprintln!("Found {} circular dependency cycle(s).", cycles.len());
-                    std::process::exit(1);
                 }
-            }
-
-            // Handle --check-conflicts flag (for CI usage)
-            if *check_conflicts {
-                let conflicts = graph.detect_version_conflicts();
-                if conflicts.is_empty() {
-                    println!("✅ No version conflicts detected.");
-                    return Ok(());
-                } else {
-                    eprintln!("❌ Version conflicts detected!");
-                    eprintln!();
-                    for conflict in &conflicts {
-                        eprintln!("  {}", conflict.description());
+                let state = MonitorState::new(snapshot);
+                if let Some(state_path) = &state_path {
+                    if let Err(e) = state.save(state_path) {
+                        eprintln!("⚠️  Failed to save {}: {}", state_path.display(), e);
                     }
-                    eprintln!();
-                    eprintln!("Found {} version conflict(s).", conflicts.len());
-                    std::process::exit(1);
                 }
+                previous = Some(state);
             }
+            Err(message) => eprintln!("{}", WatchEvent::Error { run, message }),
+        }
+        if once {
+            return;
+        }
+        std::thread::sleep(interval);
+    }
+}
 
-            // Handle --savings-report flag (for CI usage)
-            if *savings_report {
-                let report = generate_savings_report(&deps);
-                print!("{}", report.format_report());
+#[cfg(not(feature = "watch-mode"))]
+fn run_monitor(_cli: &Cli, _path: &str, _stats: Option<&str>, _interval: &str, _once: bool) {
+    eprintln!("❌ Monitor mode is not available in this build.");
+    eprintln!("   Rebuild with: cargo install codescope --features watch-mode,notifications");
+    std::process::exit(1);
+}
 
-                // Check threshold if specified
-                if let Some(threshold_kb) = savings_threshold {
-                    let threshold_bytes = threshold_kb * 1024;
-                    if report.summary.total_potential_savings > threshold_bytes {
-                        eprintln!();
-                        eprintln!(
-                            "❌ Potential savings ({}) exceed threshold ({} KB)!",
-                            report.summary.format_total_savings(),
-                            threshold_kb
-                        );
-                        std::process::exit(1);
-                    } else {
-                        println!();
-                        println!(
-                            "✅ Potential savings ({}) are within threshold ({} KB).",
-                            report.summary.format_total_savings(),
-                            threshold_kb
-                        );
-                    }
-                }
-                return Ok(());
-            }
+#[cfg(not(feature = "watch-mode"))]
+fn run_watch(_cli: &Cli, _path: &str, _stats: Option<&str>, _checks: PolicyOptions, _json: bool) -> ! {
+    eprintln!("❌ Watch mode is not available in this build.");
+    eprintln!("   Rebuild with: cargo install codescope --features watch-mode");
+    std::process::exit(1);
+}
 
-            // Build tree structure
-            let mut tree = build_dependency_tree(&pkg.name.clone().unwrap_or_else(|| "project".to_string()),
-                                             &pkg.version.clone().unwrap_or_else(|| "0.0.0".to_string()),
-                                             &deps);
+/// Summarizes every project under `root` for `analyze --recursive`.
+///
+/// Projects whose package.json cannot be read are left out with a
+/// warning; the root's .codescoperc.json `ignore` globs apply to the
+/// search.
+fn analyze_recursive(cli: &Cli, root: &str) {
+    let config = load_config(&Project::open(root, None));
+    let dirs = discover_projects(Path::new(root), &config.ignore).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+    if dirs.is_empty() {
+        eprintln!("❌ No package.json found under {}.", root);
+        std::process::exit(1);
+    }
 
-            // Mark nodes that are part of cycles
-            let cycle_nodes = graph.get_nodes_in_cycles();
-            tree.mark_cycles(&cycle_nodes);
+    let mut warnings = AnalysisWarnings::new();
+    let mut projects = Vec::new();
+    for dir in dirs {
+        let relative = match dir.strip_prefix(root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => dir.display().to_string(),
+        };
+        let project = Project::open(&dir.to_string_lossy(), None);
+        let (pkg, deps) = match read_package(&project, cli.scope()) {
+            Ok(package) => package,
+            Err(e) => {
+                warnings.push(format!("{}/package.json", relative), format!("{}; project skipped", e));
+                continue;
+            }
+        };
+        let mut project_warnings = AnalysisWarnings::new();
+        let lock = collect_lockfile(&project, &mut project_warnings);
+        let graph = build_dependency_graph(&deps, lock.as_ref(), cli.scope());
+        warnings.append_in(&relative, project_warnings);
+        projects.push(ProjectSummary::new(
+            relative,
+            pkg.name.as_deref().unwrap_or("project"),
+            pkg.version.as_deref().unwrap_or("0.0.0"),
+            &deps,
+            &graph,
+        ));
+    }
 
-            // Mark nodes with version conflicts
-            let conflict_packages = graph.get_packages_with_conflicts();
-            tree.mark_conflicts(&conflict_packages);
+    eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+    print!("{}", BatchReport::new(projects).format_report(SHARED_PACKAGES_TOP));
+}
 
-            if *no_tui {
-                // Print tree to stdout
-                let total_bundle_size = calculate_tree_total_bundle_size(&tree);
-                print_tree(&tree, 0, total_bundle_size);
-                return Ok(());
-            }
+/// Build a TreeNode from parsed dependencies
+/// Builds the dependency tree of the analysis, marking the cycles,
+/// conflicts, pre-releases, graph depths and bundle sizes of the graph.
+fn analyzed_tree(pkg: &parser::PackageJson, deps: &[parser::Dependency], graph: &DependencyGraph) -> TreeNode {
+    let mut tree = build_dependency_tree(
+        pkg.name.as_deref().unwrap_or("project"),
+        pkg.version.as_deref().unwrap_or("0.0.0"),
+        deps,
+    );
 
-            // Setup terminal for TUI
-            enable_raw_mode()?;
-            let mut stdout = io::stdout();
-            execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-            let backend = CrosstermBackend::new(stdout);
-            let mut terminal = Terminal::new(backend)?;
+    // Mark nodes that are part of cycles
+    tree.mark_cycles(&graph.get_nodes_in_cycles());
 
-            // Create app and run with appropriate sort mode
-            let initial_sort_mode = if *sort_by_size {
-                SortMode::SizeDescending
-            } else {
-                SortMode::Alphabetical
-            };
-            let mut app = App::with_sort_mode(tree, initial_sort_mode);
-            let result = run_app(&mut terminal, &mut app);
+    // Mark nodes with version conflicts
+    tree.mark_conflicts(&graph.get_packages_with_conflicts());
 
-            // Restore terminal
-            disable_raw_mode()?;
-            execute!(
-                terminal.backend_mut(),
-                LeaveAlternateScreen,
-                DisableMouseCapture
-            )?;
-            terminal.show_cursor()?;
+    // Mark nodes installed at pre-release or 0.x versions
+    tree.mark_prereleases(&PrereleaseReport::from_graph(graph).names());
 
-            if let Err(e) = result {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
-        }
-        Some(Commands::Version) => {
-            println!("codescope v{}", env!("CARGO_PKG_VERSION"));
-        }
-        None => {
-            println!("CodeScope - Terminal UI Dependency Analyzer");
-            println!();
-            println!("Usage:");
-            println!("  codescope analyze [OPTIONS]     Analyze dependencies");
-            println!("  codescope version               Show version");
-            println!();
-            println!("Run 'codescope --help' for more options");
-        }
-    }
+    // Show each package's distance from the project, not its
+    // position in the tree
+    let depths: HashMap<String, usize> =
+        graph.get_all_nodes().into_iter().map(|node| (node.name.clone(), node.depth)).collect();
+    tree.mark_graph_depths(&depths);
 
-    Ok(())
+    let sizes: HashMap<String, (u64, usize)> = graph
+        .get_all_nodes()
+        .into_iter()
+        .filter_map(|node| Some((node.name.clone(), (node.bundle_size?, node.module_count.unwrap_or(0)))))
+        .collect();
+    tree.apply_bundle_sizes(&sizes);
+    tree
 }
 
-/// Build a TreeNode from parsed dependencies
 fn build_dependency_tree(
     project_name: &str,
     project_version: &str,
@@ -310,7 +2730,16 @@ fn build_dependency_tree(
 }
 
 /// Build a DependencyGraph from parsed dependencies for cycle detection
-fn build_dependency_graph(deps: &[parser::Dependency]) -> DependencyGraph {
+///
+/// With a lockfile, transitive packages and the edges between them are
+/// added and each package is classified by the direct dependencies that
+/// pull it in. Packages outside `scope` are then dropped.
+#[tracing::instrument(name = "resolve", skip_all, fields(dependencies = deps.len(), lockfile = lock.is_some()))]
+fn build_dependency_graph(
+    deps: &[parser::Dependency],
+    lock: Option<&PackageLock>,
+    scope: Scope,
+) -> DependencyGraph {
     let mut graph = DependencyGraph::with_capacity(deps.len(), deps.len() * 2);
 
     for dep in deps {
@@ -323,10 +2752,24 @@ fn build_dependency_graph(deps: &[parser::Dependency]) -> DependencyGraph {
         graph.add_dependency(&dep.name, &dep.version, dep_type);
     }
 
-    // Note: In a real implementation, we would add edges based on resolved
-    // dependency relationships from lock files or npm/yarn resolution.
-    // For now, the graph only contains nodes without edges, so cycle detection
-    // will only work if edges are added elsewhere.
+    let mut root = GraphRoot::new(deps.iter().map(|dep| dep.name.as_str()));
+    if let Some(name) = lock.and_then(|lock| lock.packages.get("")).and_then(|entry| entry.name.as_deref()) {
+        root = root.with_name(name);
+    }
+    graph.set_root(root);
+
+    // Without a lockfile the graph only contains the direct dependencies,
+    // so cycle detection has no edges to work with.
+    if let Some(lock) = lock {
+        graph.extend_from_lockfile(lock);
+        graph.propagate_dependency_types();
+        // Dropping packages outside the scope can lengthen the shortest
+        // path to the ones kept
+        if graph.retain_scope(scope) > 0 {
+            let direct: Vec<&str> = deps.iter().map(|dep| dep.name.as_str()).collect();
+            graph.compute_depths(&direct);
+        }
+    }
 
     graph
 }
@@ -357,6 +2800,9 @@ fn print_tree(node: &TreeNode, depth: usize, total_bundle_size: u64) {
     // Get conflict indicator
     let conflict_indicator = if node.has_conflict { "[~] " } else { "" };
 
+    // Get pre-release indicator
+    let prerelease_indicator = if node.is_prerelease { "[pre] " } else { "" };
+
     // Get bundle size indicator
     let size_indicator = if let Some(size) = node.bundle_size {
         if total_bundle_size > 0 {
@@ -372,7 +2818,7 @@ fn print_tree(node: &TreeNode, depth: usize, total_bundle_size: u64) {
     if node.version.is_empty() {
         println!("{}{}{}", indent, indicator, node.name);
     } else {
-        println!("{}{}{}{}{}{} @ {}{}", indent, indicator, cycle_indicator, conflict_indicator, type_indicator, node.name, node.version, size_indicator);
+        println!("{}{}{}{}{}{}{} @ {}{}", indent, indicator, cycle_indicator, conflict_indicator, prerelease_indicator, type_indicator, node.name, node.version, size_indicator);
     }
 
     if node.expanded || depth == 0 {
@@ -391,38 +2837,545 @@ fn calculate_tree_total_bundle_size(node: &TreeNode) -> u64 {
     total
 }
 
+/// Fetch registry metadata for all dependencies, reporting failures on stderr
+///
+/// In offline mode nothing is requested; cached metadata is used where present
+/// and marked "(cached)", everything else is reported as unavailable offline.
+#[tracing::instrument(name = "registry", skip_all, fields(packages = deps.len(), offline = offline))]
+fn fetch_registry_metadata(
+    project_dir: &Path,
+    deps: &[parser::Dependency],
+    offline: bool,
+) -> HashMap<String, Fetched> {
+    // Aliases are looked up under the real package name; git, file and
+    // workspace dependencies are not in any registry.
+    let names: Vec<String> = deps
+        .iter()
+        .filter_map(|d| d.registry_name())
+        .map(str::to_string)
+        .collect();
+
+    // Registries, scopes and credentials come from .npmrc, so private
+    // packages are never looked up on the public registry.
+    let npmrc = NpmrcConfig::load(project_dir).unwrap_or_else(|e| {
+        eprintln!("⚠️  Failed to read .npmrc: {}", e);
+        NpmrcConfig::default()
+    });
+    let client = match RegistryClient::from_npmrc(npmrc) {
+        Ok(client) => client.with_offline(offline),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    let results = client.prefetch(&names);
+
+    let mut metadata = HashMap::new();
+    let mut unavailable = 0;
+    let mut failed = 0;
+    for (name, result) in results {
+        match result {
+            Ok(fetched) => {
+                metadata.insert(name, fetched);
+            }
+            Err(NetError::Offline(_)) => unavailable += 1,
+            Err(e) => {
+                failed += 1;
+                eprintln!("⚠️  {}: {}", name, e);
+            }
+        }
+    }
+
+    let cached = metadata
+        .values()
+        .filter(|f| f.source == DataSource::Cache)
+        .count();
+    let mut summary = format!(
+        "🌐 Registry metadata: {} live, {} (cached)",
+        metadata.len() - cached,
+        cached
+    );
+    if unavailable > 0 {
+        summary.push_str(&format!(", {} (unavailable offline)", unavailable));
+    }
+    if failed > 0 {
+        summary.push_str(&format!(", {} failed", failed));
+    }
+    eprintln!("{}", summary);
+
+    metadata
+}
+
+/// Print a warning for every package whose latest version is deprecated
+fn report_deprecations(metadata: &HashMap<String, Fetched>) {
+    let mut deprecated: Vec<(&String, &str, DataSource)> = metadata
+        .iter()
+        .filter_map(|(name, fetched)| {
+            fetched
+                .metadata
+                .deprecation_message()
+                .map(|msg| (name, msg, fetched.source))
+        })
+        .collect();
+    deprecated.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (name, message, source) in deprecated {
+        eprintln!("⚠️  {} is deprecated{}: {}", name, source.annotation(), message);
+    }
+}
+
+/// Moves bundled dependencies into their parents' sizes and warns about
+/// vendored copies of packages the project also depends on directly.
+fn attribute_vendored_packages(
+    project_dir: &Path,
+    analysis: &mut BundleAnalysis,
+    deps: &[parser::Dependency],
+) {
+    match PackageStore::detect(project_dir) {
+        Ok(store) => {
+            let bundled = read_bundled_dependencies(analysis, &store);
+            analysis.apply_bundled_dependencies(&bundled);
+        }
+        Err(e) => eprintln!("⚠️  Failed to locate installed packages: {}", e),
+    }
+
+    let direct: Vec<&str> = deps.iter().map(|d| d.package_name()).collect();
+    for copy in analysis.duplicated_vendored(&direct) {
+        eprintln!(
+            "⚠️  {} ships a {} copy of {} ({}) that duplicates your direct dependency",
+            copy.parent,
+            copy.kind.label(),
+            copy.package,
+            format_size(copy.size)
+        );
+    }
+}
+
+/// Loads the monorepo rooted at `path`, exiting if it is not one.
+fn load_workspace(path: &str) -> Workspace {
+    match Workspace::load(Path::new(path)) {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => {
+            eprintln!("❌ {} is not a monorepo root: its package.json has no \"workspaces\" field.", path);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to load workspaces: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Loads the project's configuration and installs its locale, exiting
+/// on invalid configuration.
+fn load_config(project: &Project) -> ProjectConfig {
+    let config = match read_config(project) {
+        Ok(Some((content, format))) => ProjectConfig::parse_as(&content, format).map_err(|e| e.to_string()),
+        Ok(None) => Ok(ProjectConfig::default()),
+        Err(e) => Err(e),
+    };
+    match config {
+        Ok(config) => {
+            if let Some(locale) = &config.locale {
+                locale.install();
+            }
+            config
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads the project's `.codescoperc.json`, or its `.codescope.toml` if
+/// there is no JSON file.
+fn read_config(project: &Project) -> Result<Option<(String, ConfigFormat)>, String> {
+    for format in ConfigFormat::ALL {
+        if let Some(content) = project.read(format.file_name())? {
+            return Ok(Some((content, format)));
+        }
+    }
+    Ok(None)
+}
+
+/// Validates the --owner filter against the configured owners, exiting if
+/// the team owns nothing.
+fn selected_owner<'a>(owner: Option<&'a str>, config: &ProjectConfig) -> Option<&'a str> {
+    let owner = owner?;
+    let known = config.all_owners();
+    if !known.contains(&owner) {
+        if known.is_empty() {
+            eprintln!("❌ --owner needs \"owners\" lines in .codescoperc.json");
+        } else {
+            eprintln!("❌ Unknown owner '{}'. Known owners: {}", owner, known.join(", "));
+        }
+        std::process::exit(1);
+    }
+    Some(owner)
+}
+
+/// Loads the project's package-lock.json, if there is one, and prints
+/// any problems with it.
+fn load_lockfile(project: &Project) -> Option<PackageLock> {
+    let mut warnings = AnalysisWarnings::new();
+    let lock = collect_lockfile(project, &mut warnings);
+    eprint!("{}", warnings.format_report(MAX_LISTED_WARNINGS));
+    lock
+}
+
+/// Loads the project's package-lock.json, if there is one. An unreadable
+/// lockfile or skipped entries are added to `warnings`; without the
+/// lockfile only the direct dependencies are analyzed.
+#[tracing::instrument(name = "lockfile", skip_all)]
+fn collect_lockfile(project: &Project, warnings: &mut AnalysisWarnings) -> Option<PackageLock> {
+    let lock = project
+        .read(PACKAGE_LOCK)
+        .and_then(|content| content.map(|c| PackageLock::parse(&c).map_err(|e| e.to_string())).transpose());
+    match lock {
+        Ok(lock) => {
+            if let Some(lock) = &lock {
+                warnings.extend_from_parse(PACKAGE_LOCK, &lock.warnings);
+            }
+            lock
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "failed to read {}", PACKAGE_LOCK);
+            warnings.push(PACKAGE_LOCK, format!("Failed to read: {}; only direct dependencies are analyzed", e));
+            None
+        }
+    }
+}
+
+/// Number of warnings listed before the rest are summarized.
+const MAX_LISTED_WARNINGS: usize = 5;
+
+/// A project directory, read from the working tree or from a git revision.
+struct Project {
+    dir: PathBuf,
+    revision: Option<RevisionFiles>,
+}
+
+impl Project {
+    /// Opens the project in `path`, at `rev` if given, exiting if the
+    /// revision cannot be read.
+    fn open(path: &str, rev: Option<&str>) -> Self {
+        let dir = PathBuf::from(path);
+        let revision = rev.map(|rev| match RevisionFiles::open(&dir, rev) {
+            Ok(revision) => revision,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        });
+        Self { dir, revision }
+    }
+
+    /// Reads a file of the project directory, or `None` if it does not exist.
+    fn read(&self, name: &str) -> Result<Option<String>, String> {
+        match &self.revision {
+            Some(revision) => revision.read(name).map_err(|e| e.to_string()),
+            None => {
+                let path = self.dir.join(name);
+                if !path.is_file() {
+                    return Ok(None);
+                }
+                std::fs::read_to_string(&path)
+                    .map(Some)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            }
+        }
+    }
+
+    /// Describes a path of the project, with the revision if there is one.
+    fn describe(&self, path: &Path) -> String {
+        match &self.revision {
+            Some(revision) => format!("{} at {}", path.display(), revision.rev()),
+            None => path.display().to_string(),
+        }
+    }
+
+    /// Returns a label such as `main (1a2b3c4)` or `working tree`.
+    fn label(&self) -> String {
+        match &self.revision {
+            Some(revision) => format!("{} ({})", revision.rev(), revision.short_commit()),
+            None => "working tree".to_string(),
+        }
+    }
+
+    /// Finds the commit that added each direct dependency, in the history
+    /// of the analyzed revision or of HEAD; warns and returns nothing if
+    /// the history cannot be read.
+    fn blame(&self) -> BTreeMap<String, Commit> {
+        let origins = match &self.revision {
+            Some(revision) => blame_dependencies(revision.repository(), revision.commit()),
+            None => Repository::discover(&self.dir).and_then(|repository| blame_dependencies(&repository, "HEAD")),
+        };
+        origins.unwrap_or_else(|e| {
+            eprintln!("⚠️  Could not read git history: {}", e);
+            BTreeMap::new()
+        })
+    }
+
+    /// Returns the commit the project is read from: the revision's, or
+    /// HEAD's for a working tree inside a git repository.
+    fn commit(&self) -> Option<String> {
+        match &self.revision {
+            Some(revision) => Some(revision.commit().to_string()),
+            None => Repository::discover(&self.dir).and_then(|repository| repository.resolve("HEAD")).ok(),
+        }
+    }
+
+    /// Returns the file name of the project's lockfile, if it has one.
+    fn lockfile(&self) -> Option<&'static str> {
+        detect_lockfile(|name| matches!(self.read(name), Ok(Some(_))))
+    }
+
+    /// Returns the provenance of a report on this project, read with the
+    /// stats file `stats`.
+    fn report_metadata(&self, stats: Option<&str>) -> ReportMetadata {
+        ReportMetadata::new(self.dir.display().to_string())
+            .with_commit(self.commit())
+            .with_lockfile(self.lockfile().map(str::to_string))
+            .with_stats(stats.map(str::to_string))
+    }
+
+    /// Returns the revision as JSON, `null` for the working tree.
+    fn to_json(&self) -> serde_json::Value {
+        match &self.revision {
+            Some(revision) => serde_json::json!({ "rev": revision.rev(), "commit": revision.commit() }),
+            None => serde_json::Value::Null,
+        }
+    }
+}
+
+/// Returns the stats file to read: the one given on the command line, else
+/// the first stats file of the configured preset found in the working tree.
+fn stats_file(given: Option<&String>, project: &Project, config: &ProjectConfig) -> Option<String> {
+    if given.is_some() || project.revision.is_some() {
+        return given.cloned();
+    }
+    let preset = config.preset?;
+    let found = preset
+        .stats_paths()
+        .iter()
+        .map(|relative| project.dir.join(relative))
+        .find(|path| path.is_file())?;
+    eprintln!("📊 Using {} (preset {})", found.display(), preset);
+    Some(found.to_string_lossy().into_owned())
+}
+
+/// Returns the measured value of every threshold `ratchet` can set. Sizes
+/// are rounded up so the measured project stays within its budget; group
+/// sizes are only measured when bundle sizes are known.
+fn ratchet_measurements(groups: &[GroupSummary], tooling: Option<&ToolingReport>) -> Vec<Measurement> {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * 1024;
+    let mut measured = Vec::new();
+    for group in groups {
+        if group.has_sizes() {
+            measured.push(Measurement::new(&["groups", &group.name, "maxSizeKb"], group.bundle_size.div_ceil(KB)));
+        }
+        measured.push(Measurement::new(&["groups", &group.name, "maxCount"], group.count() as u64));
+    }
+    if let Some(tooling) = tooling {
+        let counts = [
+            ("maxInstallMb", tooling.footprint.total_bytes().div_ceil(MB)),
+            ("maxFiles", tooling.footprint.total_files() as u64),
+            ("maxNative", tooling.native_count() as u64),
+            ("maxInstallScripts", tooling.script_count() as u64),
+        ];
+        for (key, value) in counts {
+            measured.push(Measurement::new(&["tooling", key], value));
+        }
+    }
+    measured
+}
+
+/// Measures the install footprint of the graph's packages in the working
+/// tree, returning `None` after reporting why it cannot.
+#[tracing::instrument(name = "footprint", skip_all)]
+fn measure_footprint(project: &Project, graph: &DependencyGraph) -> Option<FootprintReport> {
+    if project.revision.is_some() {
+        eprintln!("⚠️  The install footprint is measured in node_modules, which says nothing about another revision.");
+        return None;
+    }
+    match PackageStore::detect(&project.dir) {
+        Ok(store) => Some(FootprintReport::from_graph(graph, &store)),
+        Err(e) => {
+            eprintln!("⚠️  Failed to locate installed packages: {}", e);
+            None
+        }
+    }
+}
+
+/// Finds native and platform-specific packages. Only the working tree has
+/// node_modules to inspect for bindings; other revisions rely on the
+/// lockfile's os/cpu fields.
+fn detect_native(project: &Project, graph: &DependencyGraph, lock: Option<&PackageLock>) -> NativeReport {
+    let store = match project.revision {
+        Some(_) => None,
+        None => PackageStore::detect(&project.dir)
+            .map_err(|e| eprintln!("⚠️  Failed to locate installed packages: {}", e))
+            .ok(),
+    };
+    NativeReport::from_graph(graph, store.as_ref(), lock)
+}
+
+/// Checks the installed dependencies against the project's browserslist
+/// targets. Exits if there is nothing to check.
+fn check_browser_support(project: &Project, graph: &DependencyGraph) -> BrowserSupportReport {
+    if project.revision.is_some() {
+        eprintln!("❌ --check-browsers inspects node_modules and only works on the working tree");
+        std::process::exit(1);
+    }
+    let targets = match BrowserTargets::load(&project.dir) {
+        Ok(Some(targets)) => targets,
+        Ok(None) => {
+            eprintln!("❌ No browserslist configuration in package.json or .browserslistrc");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to read the browserslist configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    match PackageStore::detect(&project.dir) {
+        Ok(store) => BrowserSupportReport::from_graph(graph, &targets, &store),
+        Err(e) => {
+            eprintln!("❌ Failed to locate installed packages: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Audits the @types dependencies. Bundled declarations are only found in
+/// the working tree's node_modules.
+fn audit_types(project: &Project, deps: &[parser::Dependency]) -> TypesAudit {
+    let store = match project.revision {
+        Some(_) => None,
+        None => PackageStore::detect(&project.dir)
+            .map_err(|e| eprintln!("⚠️  Failed to locate installed packages: {}", e))
+            .ok(),
+    };
+    TypesAudit::audit(deps, store.as_ref())
+}
+
+/// Finds installed packages that the project's package-lock.json does not
+/// require.
+fn find_orphans(project_dir: &Path, lock: &PackageLock) -> Option<OrphanReport> {
+    match find_orphaned_packages(project_dir, lock) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            eprintln!("⚠️  Failed to scan node_modules: {}", e);
+            None
+        }
+    }
+}
+
+/// Runs `npm uninstall` for a package confirmed in the removal preview.
+fn uninstall_package(project_dir: &Path, package: &str) {
+    println!("Running npm uninstall {}...", package);
+    match std::process::Command::new("npm")
+        .arg("uninstall")
+        .arg(package)
+        .current_dir(project_dir)
+        .status()
+    {
+        Ok(status) if status.success() => println!("✅ Uninstalled {}.", package),
+        Ok(status) => {
+            eprintln!("❌ npm uninstall {} failed ({}).", package, status);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to run npm: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Leaves the TUI, giving the terminal back to the shell.
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    terminal.show_cursor()
+}
+
+/// Writes the configuration chosen in the setup wizard and, if asked,
+/// builds the project to save its stats where the preset finds them.
+fn finish_setup(project: &Project, wizard: &SetupWizard) {
+    let plan = wizard.plan();
+    let config_path = project.dir.join(TOML_CONFIG_FILE);
+    if let Err(e) = std::fs::write(&config_path, plan.to_config()) {
+        eprintln!("❌ Failed to write {}: {}", config_path.display(), e);
+        std::process::exit(1);
+    }
+    println!("✅ Wrote {}", config_path.display());
+
+    let Some(stats_path) = plan.stats_path().filter(|_| wizard.generate_stats()) else {
+        return;
+    };
+    let output = project.dir.join(stats_path);
+    if output.exists() {
+        println!("✅ Keeping the existing stats at {}", output.display());
+        println!(
+            "   Rebuild them with: codescope build-stats --path {} --output {} --force",
+            project.dir.display(),
+            output.display()
+        );
+        return;
+    }
+    println!("Building with {} for bundle stats...", wizard.bundler.map_or("the bundler".to_string(), |b| b.to_string()));
+    let raw_stats = std::env::temp_dir().join(format!("codescope-bundler-stats-{}.json", std::process::id()));
+    let built = build_stats(&project.dir, wizard.bundler, &raw_stats);
+    let _ = std::fs::remove_file(&raw_stats);
+    let saved = built.map_err(|e| e.to_string()).and_then(|(_, stats)| {
+        let json = serde_json::to_string(&stats).expect("webpack stats are always serializable");
+        output
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| file::write_atomic(&output, json.as_bytes(), false))
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+    });
+    match saved {
+        Ok(()) => println!("✅ Saved the stats to {}", output.display()),
+        Err(e) => eprintln!("⚠️  No stats generated; continuing without bundle sizes.\n{}", e),
+    }
+}
+
+/// Runs this invocation again so the analysis picks up the configuration
+/// written during it, and exits with its status.
+fn rerun() -> ! {
+    let status = std::env::current_exe()
+        .and_then(|exe| std::process::Command::new(exe).args(std::env::args_os().skip(1)).status());
+    match status {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("❌ Failed to restart codescope: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Generate a savings report from parsed dependencies
 ///
 /// This creates a mock bundle analysis from the dependency list since we don't
 /// have actual webpack stats. For real bundle size data, use --with-bundle-size
 /// with a stats.json file.
-fn generate_savings_report(deps: &[parser::Dependency]) -> SavingsReport {
-    use std::collections::HashMap;
+#[tracing::instrument(name = "savings", skip_all)]
+fn generate_savings_report(
+    project: &Project,
+    deps: &[parser::Dependency],
+    bundle: Option<&BundleAnalysis>,
+    imports: Option<&ProjectImports>,
+    config: &ProjectConfig,
+) -> SavingsReport {
     use codescope::bundle::webpack::{BundleAnalysis, PackageBundleSize};
-    use codescope::analysis::exports::ProjectImports;
 
-    // Create a mock bundle analysis from dependencies
-    // In a real implementation, this would come from webpack stats
+    // Size each runtime dependency from the stats when they measured it,
+    // else from the bundled size dataset
     let mut analysis = BundleAnalysis::default();
-
-    // Use estimated sizes based on common package sizes
-    // This is a heuristic - real sizes would come from webpack stats
-    let estimated_sizes: HashMap<&str, u64> = [
-        ("react", 45 * 1024),
-        ("react-dom", 120 * 1024),
-        ("lodash", 70 * 1024),
-        ("moment", 290 * 1024),
-        ("axios", 15 * 1024),
-        ("express", 200 * 1024),
-        ("webpack", 100 * 1024),
-        ("typescript", 10 * 1024), // TypeScript is dev-only, minimal bundle impact
-        ("@types/", 0), // Type definitions have no runtime cost
-        ("eslint", 0), // Dev dependency
-        ("jest", 0), // Dev dependency
-        ("prettier", 0), // Dev dependency
-    ].into_iter().collect();
-
-    let default_size = 25 * 1024; // 25KB default estimate
+    let dataset = SizeDataset::bundled();
 
     for dep in deps {
         // Skip dev dependencies for bundle size (they don't affect runtime bundle)
@@ -430,12 +3383,10 @@ fn generate_savings_report(deps: &[parser::Dependency]) -> SavingsReport {
             continue;
         }
 
-        // Estimate size based on known packages or use default
-        let size = estimated_sizes
-            .iter()
-            .find(|(name, _)| dep.name.starts_with(*name))
-            .map(|(_, size)| *size)
-            .unwrap_or(default_size);
+        let measured = bundle
+            .and_then(|bundle| bundle.package_sizes.get(dep.package_name()))
+            .map(|pkg| pkg.total_size);
+        let size = measured.unwrap_or_else(|| dataset.estimate(dep.package_name()).0);
 
         if size > 0 {
             let mut pkg = PackageBundleSize::new(&dep.name);
@@ -452,5 +3403,71 @@ fn generate_savings_report(deps: &[parser::Dependency]) -> SavingsReport {
 
     // Calculate savings
     let calculator = SavingsCalculator::new();
-    calculator.calculate(&analysis, &project_imports, &export_counts)
+    let mut report = calculator.calculate(&analysis, &project_imports, &export_counts);
+
+    // The framework loads its runtime packages without the project
+    // importing them
+    let always_used: Vec<String> = report
+        .savings_by_category(SavingsCategory::Unused)
+        .into_iter()
+        .map(|saving| saving.package_name.clone())
+        .filter(|name| config.is_always_used(name))
+        .collect();
+    for name in always_used {
+        report.remove(&name);
+    }
+
+    // Type packages cost nothing at runtime but are still worth removing
+    for saving in audit_types(project, deps).to_savings() {
+        report.add(saving);
+    }
+
+    // Tree-shaking scores need export usage from the stats; the project's
+    // imports fill in modules webpack could not narrow down
+    if let Some(bundle) = bundle {
+        report.tree_shaking = TreeShakingReport::from_bundle(bundle, imports);
+        for saving in report.tree_shaking.to_savings() {
+            report.remove(&saving.package_name);
+            report.add(saving);
+        }
+    }
+
+    // Polyfill sizes come from real stats and replace the estimates
+    if let Some(bundle) = bundle {
+        for saving in find_polyfills(project, bundle).to_savings() {
+            report.remove(&saving.package_name);
+            report.add(saving);
+        }
+    }
+    report
+}
+
+/// Reads the imports of the project's sources, which only the working tree
+/// has. A failure is a warning ending in `consequence`.
+fn scan_imports(
+    project: &Project,
+    config: &ProjectConfig,
+    warnings: &mut AnalysisWarnings,
+    consequence: &str,
+) -> Option<ProjectImports> {
+    if project.revision.is_some() {
+        return None;
+    }
+    let imports = analyze_project_imports_with(&project.dir, &config.ignore)
+        .map_err(|e| warnings.push(CONFIG_FILE, format!("{}; {}", e, consequence)))
+        .ok()?;
+    for (file, error) in &imports.unreadable_files {
+        warnings.push(file.as_str(), format!("skipped, {}", error));
+    }
+    Some(imports)
+}
+
+/// Finds the polyfills of a bundle, compared with the project's
+/// browserslist targets when it has any.
+fn find_polyfills(project: &Project, bundle: &BundleAnalysis) -> PolyfillReport {
+    let targets = BrowserTargets::load(&project.dir)
+        .map_err(|e| eprintln!("⚠️  Failed to read the browserslist configuration: {}", e))
+        .ok()
+        .flatten();
+    PolyfillReport::from_bundle(bundle, targets.as_ref())
 }
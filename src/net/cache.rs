@@ -0,0 +1,252 @@
+//! On-disk cache for registry responses.
+//!
+//! Each package document is stored as a small JSON file together with the
+//! `ETag` it was served with, so later runs can revalidate it with
+//! `If-None-Match` instead of downloading it again.
+//!
+//! Several CI jobs or watch instances may share one cache directory. Every
+//! entry is replaced atomically through a temporary file, so a reader never
+//! sees a half-written entry, and access is coordinated through an advisory
+//! lock on a `.lock` file in the directory: writers hold it exclusively,
+//! readers share it if they can open it. A read-only cache is read
+//! unlocked.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::file::write_atomic;
+
+/// Name of the lock file in the cache directory
+const LOCK_FILE: &str = ".lock";
+
+/// A cached registry response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// `ETag` the response was served with
+    pub etag: Option<String>,
+    /// Unix timestamp (seconds) of the last successful fetch or revalidation
+    pub fetched_at: u64,
+    /// Raw response body
+    pub body: String,
+}
+
+impl CacheEntry {
+    /// Creates an entry stamped with the current time.
+    pub fn new(etag: Option<String>, body: String) -> Self {
+        Self {
+            etag,
+            fetched_at: now_secs(),
+            body,
+        }
+    }
+
+    /// Marks the entry as freshly revalidated.
+    pub fn touch(&mut self) {
+        self.fetched_at = now_secs();
+    }
+}
+
+/// Directory-backed cache of registry documents keyed by package name.
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    dir: PathBuf,
+}
+
+impl MetadataCache {
+    /// Creates a cache stored in `dir`. The directory is created lazily.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates a cache in the platform's default cache location.
+    ///
+    /// Returns `None` if no cache directory can be determined.
+    pub fn default_location() -> Option<Self> {
+        default_cache_dir().map(|dir| Self::new(dir.join("registry")))
+    }
+
+    /// Returns the directory the cache is stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Loads the entry for a package, if one exists and is readable.
+    pub fn get(&self, package_name: &str) -> Option<CacheEntry> {
+        let path = self.entry_path(package_name);
+        if !path.exists() {
+            return None;
+        }
+        // Entries are replaced atomically, so a cache that cannot be locked,
+        // such as a read-only one, is still safe to read
+        let _lock = self.lock_shared();
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Stores the entry for a package, replacing the previous one at once.
+    pub fn put(&self, package_name: &str, entry: &CacheEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string(entry)?;
+        let _lock = self.lock_exclusive()?;
+        write_atomic(&self.entry_path(package_name), content.as_bytes(), true)
+    }
+
+    /// Blocks until the cache lock is held exclusively, creating the lock
+    /// file. The lock is released when the returned file is dropped.
+    fn lock_exclusive(&self) -> io::Result<File> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.dir.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Blocks until the cache lock is shared, or returns `None` if the
+    /// lock file is missing or cannot be opened. Never writes to the
+    /// directory.
+    fn lock_shared(&self) -> Option<File> {
+        let file = File::open(self.dir.join(LOCK_FILE)).ok()?;
+        file.lock_shared().ok()?;
+        Some(file)
+    }
+
+    /// Returns the file path used for a package's entry.
+    fn entry_path(&self, package_name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", file_key(package_name)))
+    }
+}
+
+/// Converts a package name into a safe file name.
+///
+/// For example: `@babel/core` becomes `@babel%2Fcore`.
+fn file_key(package_name: &str) -> String {
+    package_name.replace('%', "%25").replace('/', "%2F").replace('\\', "%5C")
+}
+
+/// Returns the per-user cache directory for codescope.
+///
+/// Uses `$XDG_CACHE_HOME`, then `%LOCALAPPDATA%`, then `$HOME/.cache`.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let non_empty = |key: &str| std::env::var_os(key).filter(|v| !v.is_empty());
+
+    if let Some(dir) = non_empty("XDG_CACHE_HOME") {
+        return Some(PathBuf::from(dir).join("codescope"));
+    }
+    if let Some(dir) = non_empty("LOCALAPPDATA") {
+        return Some(PathBuf::from(dir).join("codescope"));
+    }
+    non_empty("HOME").map(|home| PathBuf::from(home).join(".cache").join("codescope"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> MetadataCache {
+        let dir = std::env::temp_dir().join(format!("codescope-cache-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        MetadataCache::new(dir)
+    }
+
+    #[test]
+    fn test_file_key() {
+        assert_eq!(file_key("react"), "react");
+        assert_eq!(file_key("@babel/core"), "@babel%2Fcore");
+        assert_eq!(file_key("a%2Fb"), "a%252Fb");
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let cache = temp_cache("roundtrip");
+        assert!(cache.get("@types/node").is_none());
+
+        let entry = CacheEntry::new(Some("\"abc\"".to_string()), "{}".to_string());
+        cache.put("@types/node", &entry).unwrap();
+
+        let loaded = cache.get("@types/node").unwrap();
+        assert_eq!(loaded, entry);
+        assert!(cache.dir().join("@types%2Fnode.json").exists());
+
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_ignored() {
+        let cache = temp_cache("corrupt");
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(cache.dir().join("react.json"), "not json").unwrap();
+
+        assert!(cache.get("react").is_none());
+
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_get_without_lock_file() {
+        let cache = temp_cache("unlocked");
+        fs::create_dir_all(cache.dir()).unwrap();
+        let entry = CacheEntry::new(None, "{}".to_string());
+        fs::write(cache.dir().join("react.json"), serde_json::to_string(&entry).unwrap()).unwrap();
+
+        // A cache populated elsewhere and mounted read-only has no lock file
+        assert_eq!(cache.get("react"), Some(entry));
+        assert!(!cache.dir().join(LOCK_FILE).exists(), "reads never create the lock file");
+
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let cache = temp_cache("concurrent");
+        // Large bodies, so an unsynchronized write would be seen half done
+        let bodies: Vec<String> = (0..8)
+            .map(|i| format!("{}{}", i, "x".repeat(64 * 1024)))
+            .collect();
+        let bodies = &bodies;
+        std::thread::scope(|scope| {
+            for body in bodies {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        cache.put("react", &CacheEntry::new(None, body.clone())).unwrap();
+                        let entry = cache.get("react").expect("entries are never half-written");
+                        assert!(bodies.contains(&entry.body));
+                    }
+                });
+            }
+        });
+
+        let mut files: Vec<String> = fs::read_dir(cache.dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec![LOCK_FILE, "react.json"], "no temporary files are left");
+
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_touch_updates_timestamp() {
+        let mut entry = CacheEntry {
+            etag: None,
+            fetched_at: 0,
+            body: String::new(),
+        };
+        entry.touch();
+        assert!(entry.fetched_at > 0);
+    }
+}
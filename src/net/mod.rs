@@ -0,0 +1,339 @@
+//! Network access for online features
+//!
+//! All outbound HTTP goes through this module so that retries, caching,
+//! `.npmrc` registry routing and credentials, proxies and extra CA
+//! certificates live in one place instead of being reimplemented by every
+//! check that needs registry data.
+//!
+//! Offline mode is enforced here as well: an offline `RegistryClient`
+//! never calls its transport and answers only from the local cache.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use codescope::net::RegistryClient;
+//!
+//! let client = RegistryClient::new();
+//! let results = client.prefetch(&["react".to_string(), "lodash".to_string()]);
+//! for (name, metadata) in results {
+//!     if let Ok(fetched) = metadata {
+//!         println!("{} latest: {:?}", name, fetched.metadata.latest_version());
+//!     }
+//! }
+//! ```
+
+pub mod cache;
+pub mod npmrc;
+pub mod proxy;
+pub mod registry;
+pub mod tls;
+
+pub use cache::MetadataCache;
+pub use npmrc::{NpmrcConfig, RegistryAuth};
+pub use proxy::ProxyConfig;
+pub use registry::{
+    DataSource, Fetched, PackageMetadata, RegistryClient, RetryPolicy, VersionMetadata,
+};
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Errors that can occur while talking to a remote service.
+#[derive(Error, Debug)]
+pub enum NetError {
+    #[error("HTTP {status} from {url}")]
+    Http { status: u16, url: String },
+
+    #[error("Request to {url} failed: {message}")]
+    Transport { url: String, message: String },
+
+    #[error("Package not found in registry: {0}")]
+    NotFound(String),
+
+    #[error("{0}: unavailable offline")]
+    Offline(String),
+
+    #[error("Invalid registry response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+
+    #[error("Cache error: {0}")]
+    Cache(#[from] std::io::Error),
+
+    #[error("Invalid proxy {url}: {message}")]
+    InvalidProxy { url: String, message: String },
+
+    #[error("Invalid CA bundle {}: {message}", path.display())]
+    InvalidCaBundle { path: PathBuf, message: String },
+}
+
+impl NetError {
+    /// Returns true if the request may succeed when retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            NetError::Http { status, .. } => *status == 429 || *status >= 500,
+            NetError::Transport { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Result type for network operations.
+pub type NetResult<T> = Result<T, NetError>;
+
+/// A minimal HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Value of the `ETag` header, if present
+    pub etag: Option<String>,
+    /// Response body
+    pub body: String,
+}
+
+/// Performs HTTP GET requests.
+///
+/// Abstracted so the registry client can be exercised without a network.
+/// Implementations return non-2xx responses as `Ok` so callers can inspect
+/// the status (e.g. `304 Not Modified`).
+pub trait Transport: Send + Sync {
+    /// Performs a GET request with the given extra headers.
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> NetResult<HttpResponse>;
+}
+
+/// Default request timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Blocking HTTP transport backed by `ureq`.
+///
+/// Holds one agent per route (direct, via the HTTP proxy, via the HTTPS
+/// proxy) and picks one per request, so `NO_PROXY` hosts are contacted
+/// directly while everything else goes through the configured proxy.
+#[derive(Debug)]
+pub struct HttpTransport {
+    direct: ureq::Agent,
+    http: Option<ureq::Agent>,
+    https: Option<ureq::Agent>,
+    proxy: ProxyConfig,
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpTransport {
+    /// Creates a direct transport with default timeouts and TLS roots.
+    pub fn new() -> Self {
+        let agent = Self::builder().build();
+        Self {
+            direct: agent,
+            http: None,
+            https: None,
+            proxy: ProxyConfig::default(),
+        }
+    }
+
+    /// Creates a transport using the proxy settings from the environment
+    /// and `.npmrc`, and the CA bundle from `cafile` or
+    /// `NODE_EXTRA_CA_CERTS`.
+    pub fn from_npmrc(npmrc: &NpmrcConfig) -> NetResult<Self> {
+        Self::configured(
+            ProxyConfig::resolve(npmrc),
+            tls::ca_bundle_path(npmrc).as_deref(),
+        )
+    }
+
+    /// Creates a transport with explicit proxy settings and an optional
+    /// PEM bundle of extra certificate authorities.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - Proxies to route requests through
+    /// * `ca_file` - CA bundle trusted in addition to the built-in roots
+    pub fn configured(proxy: ProxyConfig, ca_file: Option<&Path>) -> NetResult<Self> {
+        let tls = ca_file.map(tls::client_config).transpose()?;
+        let agent = |proxy_url: Option<&str>| -> NetResult<ureq::Agent> {
+            let mut builder = Self::builder();
+            if let Some(tls) = &tls {
+                builder = builder.tls_config(Arc::clone(tls));
+            }
+            if let Some(url) = proxy_url {
+                let proxy = ureq::Proxy::new(url).map_err(|e| NetError::InvalidProxy {
+                    url: url.to_string(),
+                    message: e.to_string(),
+                })?;
+                builder = builder.proxy(proxy);
+            }
+            Ok(builder.build())
+        };
+
+        let http = proxy.proxy_for_scheme(false).map(|url| agent(Some(url))).transpose()?;
+        let https = proxy.proxy_for_scheme(true).map(|url| agent(Some(url))).transpose()?;
+        Ok(Self {
+            direct: agent(None)?,
+            http,
+            https,
+            proxy,
+        })
+    }
+
+    /// Returns the agent a request to `url` is sent with.
+    fn agent_for(&self, url: &str) -> &ureq::Agent {
+        if self.proxy.bypasses(url) {
+            return &self.direct;
+        }
+        let proxied = if url.starts_with("https:") {
+            self.https.as_ref()
+        } else {
+            self.http.as_ref()
+        };
+        proxied.unwrap_or(&self.direct)
+    }
+
+    fn builder() -> ureq::AgentBuilder {
+        ureq::AgentBuilder::new()
+            .timeout(DEFAULT_TIMEOUT)
+            .user_agent(concat!("codescope/", env!("CARGO_PKG_VERSION")))
+    }
+}
+
+impl HttpTransport {
+    /// POSTs a JSON body, failing on a non-2xx response.
+    ///
+    /// Used for webhooks, which unlike registry requests are neither
+    /// retried nor cached.
+    pub fn post_json(&self, url: &str, body: &str) -> NetResult<HttpResponse> {
+        let request = self.agent_for(url).post(url).set("Content-Type", "application/json");
+        match request.send_string(body) {
+            Ok(response) => {
+                let status = response.status();
+                Ok(HttpResponse {
+                    status,
+                    etag: None,
+                    body: response.into_string().unwrap_or_default(),
+                })
+            }
+            Err(ureq::Error::Status(status, _)) => Err(NetError::Http {
+                status,
+                url: url.to_string(),
+            }),
+            Err(ureq::Error::Transport(e)) => Err(NetError::Transport {
+                url: url.to_string(),
+                message: transport_message(&e),
+            }),
+        }
+    }
+}
+
+fn transport_message(e: &ureq::Transport) -> String {
+    match e.message() {
+        Some(detail) => format!("{}: {}", e.kind(), detail),
+        None => e.kind().to_string(),
+    }
+}
+
+impl Transport for HttpTransport {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> NetResult<HttpResponse> {
+        let mut request = self.agent_for(url).get(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(e)) => {
+                return Err(NetError::Transport {
+                    url: url.to_string(),
+                    message: transport_message(&e),
+                });
+            }
+        };
+
+        let status = response.status();
+        let etag = response.header("etag").map(str::to_string);
+        // Read without ureq's 10 MB `into_string` cap: full registry
+        // documents for long-lived packages are routinely larger.
+        let mut body = String::new();
+        response
+            .into_reader()
+            .read_to_string(&mut body)
+            .map_err(|e| NetError::Transport {
+                url: url.to_string(),
+                message: e.to_string(),
+            })?;
+
+        Ok(HttpResponse { status, etag, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_errors() {
+        let http = |status| NetError::Http {
+            status,
+            url: "u".to_string(),
+        };
+        assert!(http(429).is_retryable());
+        assert!(http(503).is_retryable());
+        assert!(!http(404).is_retryable());
+        assert!(!http(401).is_retryable());
+        assert!(NetError::Transport {
+            url: "u".to_string(),
+            message: "reset".to_string()
+        }
+        .is_retryable());
+        assert!(!NetError::NotFound("x".to_string()).is_retryable());
+        assert!(!NetError::Offline("x".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_transport_routes_by_no_proxy() {
+        let proxy = ProxyConfig {
+            http_proxy: None,
+            https_proxy: Some("http://proxy.corp:3128".to_string()),
+            no_proxy: vec![".corp".to_string()],
+        };
+        let transport = HttpTransport::configured(proxy, None).unwrap();
+
+        assert!(transport.http.is_none());
+        assert!(transport.https.is_some());
+        assert!(std::ptr::eq(
+            transport.agent_for("https://npm.corp/react"),
+            &transport.direct
+        ));
+        assert!(std::ptr::eq(
+            transport.agent_for("https://registry.npmjs.org/react"),
+            transport.https.as_ref().unwrap()
+        ));
+        assert!(std::ptr::eq(
+            transport.agent_for("http://registry.npmjs.org/react"),
+            &transport.direct
+        ));
+    }
+
+    #[test]
+    fn test_invalid_proxy_and_ca_bundle() {
+        let proxy = ProxyConfig {
+            http_proxy: Some("ftp://proxy:21".to_string()),
+            ..ProxyConfig::default()
+        };
+        assert!(matches!(
+            HttpTransport::configured(proxy, None),
+            Err(NetError::InvalidProxy { .. })
+        ));
+        assert!(matches!(
+            HttpTransport::configured(ProxyConfig::default(), Some(Path::new("/nonexistent.pem"))),
+            Err(NetError::InvalidCaBundle { .. })
+        ));
+    }
+}
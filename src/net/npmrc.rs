@@ -0,0 +1,434 @@
+//! `.npmrc` configuration.
+//!
+//! Reads the subset of npm's configuration that decides where registry
+//! requests go and how they authenticate: the default `registry`,
+//! per-scope `@scope:registry` overrides, per-registry credentials keyed by
+//! "nerf-darted" URLs (`//host/path/:_authToken=...`), proxy settings and
+//! the `cafile` CA bundle.
+//!
+//! Files are read from the user config (`~/.npmrc` or
+//! `$NPM_CONFIG_USERCONFIG`) and then the project's `.npmrc`, with project
+//! values taking precedence, matching npm's own lookup order.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use super::registry::DEFAULT_REGISTRY;
+
+/// Credentials for one registry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryAuth {
+    /// Bearer token (`_authToken`)
+    pub token: Option<String>,
+    /// Pre-encoded `user:password` (`_auth`)
+    pub basic: Option<String>,
+    /// User name (`username`)
+    pub username: Option<String>,
+    /// Base64-encoded password (`_password`)
+    pub password: Option<String>,
+}
+
+impl RegistryAuth {
+    /// Returns the `Authorization` header value for these credentials.
+    pub fn header(&self) -> Option<String> {
+        if let Some(token) = &self.token {
+            return Some(format!("Bearer {}", token));
+        }
+        if let Some(basic) = &self.basic {
+            return Some(format!("Basic {}", basic));
+        }
+        match (&self.username, &self.password) {
+            (Some(user), Some(password)) => {
+                let password = BASE64
+                    .decode(password)
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())?;
+                Some(format!(
+                    "Basic {}",
+                    BASE64.encode(format!("{}:{}", user, password))
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Registry-related settings from one or more `.npmrc` files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NpmrcConfig {
+    /// Default registry URL
+    pub registry: Option<String>,
+    /// Registry URL per scope, keyed by scope including `@`
+    pub scoped_registries: HashMap<String, String>,
+    /// Credentials keyed by nerf-darted registry URL (`//host/path/`)
+    pub auth: HashMap<String, RegistryAuth>,
+    /// Proxy for plain HTTP requests (`proxy`)
+    pub proxy: Option<String>,
+    /// Proxy for HTTPS requests (`https-proxy`)
+    pub https_proxy: Option<String>,
+    /// Hosts that bypass the proxy (`noproxy`)
+    pub no_proxy: Option<String>,
+    /// Path to a PEM bundle of extra certificate authorities (`cafile`)
+    pub cafile: Option<String>,
+}
+
+impl NpmrcConfig {
+    /// Loads the user and project `.npmrc` files for a project directory.
+    ///
+    /// Missing files are skipped; unreadable files are reported as errors.
+    pub fn load(project_dir: &Path) -> io::Result<Self> {
+        let mut config = Self::default();
+        let mut paths: Vec<PathBuf> = Vec::new();
+
+        if let Some(user) = user_config_path() {
+            paths.push(user);
+        }
+        paths.push(project_dir.join(".npmrc"));
+
+        for path in paths {
+            match fs::read_to_string(&path) {
+                Ok(content) => config.merge(Self::parse(&content)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Parses the contents of a single `.npmrc` file.
+    ///
+    /// `${VAR}` references are expanded from the environment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::net::NpmrcConfig;
+    ///
+    /// let config = NpmrcConfig::parse("@acme:registry=https://npm.acme.dev/\n");
+    /// assert_eq!(config.registry_for("@acme/ui"), "https://npm.acme.dev");
+    /// assert_eq!(config.registry_for("react"), "https://registry.npmjs.org");
+    /// ```
+    pub fn parse(content: &str) -> Self {
+        let mut config = Self::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = expand_env(key.trim());
+            let value = expand_env(unquote(value.trim()));
+
+            if let Some(rest) = key.strip_prefix("//") {
+                // Per-registry setting: //host/path/:field
+                if let Some((registry, field)) = rest.rsplit_once(':') {
+                    let entry = config.auth.entry(nerf_dart_key(registry)).or_default();
+                    match field {
+                        "_authToken" => entry.token = Some(value),
+                        "_auth" => entry.basic = Some(value),
+                        "username" => entry.username = Some(value),
+                        "_password" => entry.password = Some(value),
+                        _ => {}
+                    }
+                }
+            } else if let Some(scope) = key.strip_suffix(":registry") {
+                if scope.starts_with('@') {
+                    config.scoped_registries.insert(scope.to_string(), value);
+                }
+            } else {
+                match key.as_str() {
+                    "registry" => config.registry = Some(value),
+                    "proxy" => config.proxy = Some(value),
+                    "https-proxy" => config.https_proxy = Some(value),
+                    "noproxy" | "no-proxy" => config.no_proxy = Some(value),
+                    "cafile" => config.cafile = Some(value),
+                    _ => {}
+                }
+            }
+        }
+
+        config
+    }
+
+    /// Overlays `other` on top of this configuration.
+    pub fn merge(&mut self, other: NpmrcConfig) {
+        if other.registry.is_some() {
+            self.registry = other.registry;
+        }
+        self.scoped_registries.extend(other.scoped_registries);
+        for (key, auth) in other.auth {
+            let entry = self.auth.entry(key).or_default();
+            entry.token = auth.token.or(entry.token.take());
+            entry.basic = auth.basic.or(entry.basic.take());
+            entry.username = auth.username.or(entry.username.take());
+            entry.password = auth.password.or(entry.password.take());
+        }
+        if other.proxy.is_some() {
+            self.proxy = other.proxy;
+        }
+        if other.https_proxy.is_some() {
+            self.https_proxy = other.https_proxy;
+        }
+        if other.no_proxy.is_some() {
+            self.no_proxy = other.no_proxy;
+        }
+        if other.cafile.is_some() {
+            self.cafile = other.cafile;
+        }
+    }
+
+    /// Returns the default registry URL, without a trailing slash.
+    pub fn default_registry(&self) -> &str {
+        self.registry
+            .as_deref()
+            .unwrap_or(DEFAULT_REGISTRY)
+            .trim_end_matches('/')
+    }
+
+    /// Returns the registry URL a package is fetched from.
+    pub fn registry_for(&self, package_name: &str) -> &str {
+        package_name
+            .split_once('/')
+            .filter(|(scope, _)| scope.starts_with('@'))
+            .and_then(|(scope, _)| self.scoped_registries.get(scope))
+            .map(|url| url.trim_end_matches('/'))
+            .unwrap_or_else(|| self.default_registry())
+    }
+
+    /// Returns the `Authorization` header for a request URL, if any
+    /// configured credentials apply to it.
+    ///
+    /// Credentials are matched by the longest nerf-darted registry prefix,
+    /// so a token is only ever sent to the registry it was configured for.
+    pub fn auth_header_for(&self, url: &str) -> Option<String> {
+        let target = nerf_dart_key(strip_scheme(url));
+        self.auth
+            .iter()
+            .filter(|(key, _)| target.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .and_then(|(_, auth)| auth.header())
+    }
+
+    /// Returns the proxy npm would use for a URL, if any.
+    pub fn proxy_for(&self, url: &str) -> Option<&str> {
+        if url.starts_with("https:") {
+            self.https_proxy.as_deref().or(self.proxy.as_deref())
+        } else {
+            self.proxy.as_deref()
+        }
+    }
+}
+
+/// Returns the user-level `.npmrc` path.
+fn user_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("NPM_CONFIG_USERCONFIG").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".npmrc"))
+}
+
+/// Normalizes `host/path` into the `//host/path/` form used as an auth key.
+fn nerf_dart_key(host_and_path: &str) -> String {
+    let trimmed = host_and_path.trim_start_matches('/').trim_end_matches('/');
+    format!("//{}/", trimmed)
+}
+
+fn strip_scheme(url: &str) -> &str {
+    url.split_once("://").map(|(_, rest)| rest).unwrap_or(url)
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Expands `${VAR}` references from the environment (missing variables
+/// expand to an empty string).
+fn expand_env(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        match rest[start + 2..].find('}') {
+            Some(end) => {
+                let name = &rest[start + 2..start + 2 + end];
+                out.push_str(&std::env::var(name.trim_end_matches('?')).unwrap_or_default());
+                rest = &rest[start + 3 + end..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Company registry
+registry=https://artifactory.acme.dev/api/npm/npm-virtual/
+@acme:registry=https://npm.pkg.github.com
+@internal:registry = "https://verdaccio.acme.dev/"
+//artifactory.acme.dev/api/npm/npm-virtual/:_authToken=art-token
+//npm.pkg.github.com/:_authToken=gh-token
+//verdaccio.acme.dev/:username=ci
+//verdaccio.acme.dev/:_password=c2VjcmV0
+https-proxy=http://proxy.acme.dev:8080
+noproxy=localhost,.acme.dev
+cafile=/etc/ssl/acme-root.pem
+; unknown keys are ignored
+save-exact=true
+"#;
+
+    #[test]
+    fn test_parse_registries() {
+        let config = NpmrcConfig::parse(SAMPLE);
+        assert_eq!(
+            config.default_registry(),
+            "https://artifactory.acme.dev/api/npm/npm-virtual"
+        );
+        assert_eq!(config.registry_for("@acme/design"), "https://npm.pkg.github.com");
+        assert_eq!(config.registry_for("@internal/tools"), "https://verdaccio.acme.dev");
+        assert_eq!(
+            config.registry_for("react"),
+            "https://artifactory.acme.dev/api/npm/npm-virtual"
+        );
+        assert_eq!(
+            config.registry_for("@other/pkg"),
+            "https://artifactory.acme.dev/api/npm/npm-virtual"
+        );
+    }
+
+    #[test]
+    fn test_default_registry_without_config() {
+        let config = NpmrcConfig::default();
+        assert_eq!(config.registry_for("react"), "https://registry.npmjs.org");
+        assert_eq!(config.auth_header_for("https://registry.npmjs.org/react"), None);
+    }
+
+    #[test]
+    fn test_auth_token_matches_registry() {
+        let config = NpmrcConfig::parse(SAMPLE);
+        assert_eq!(
+            config.auth_header_for("https://npm.pkg.github.com/@acme%2Fdesign"),
+            Some("Bearer gh-token".to_string())
+        );
+        assert_eq!(
+            config.auth_header_for("https://artifactory.acme.dev/api/npm/npm-virtual/react"),
+            Some("Bearer art-token".to_string())
+        );
+    }
+
+    #[test]
+    fn test_auth_not_sent_to_other_hosts() {
+        let config = NpmrcConfig::parse(SAMPLE);
+        assert_eq!(config.auth_header_for("https://registry.npmjs.org/react"), None);
+        assert_eq!(config.auth_header_for("https://npm.pkg.github.com.evil.io/x"), None);
+        assert_eq!(
+            config.auth_header_for("https://artifactory.acme.dev/api/npm/other-repo/react"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_basic_auth_from_username_password() {
+        let config = NpmrcConfig::parse(SAMPLE);
+        // "ci:secret"
+        assert_eq!(
+            config.auth_header_for("https://verdaccio.acme.dev/@internal%2Ftools"),
+            Some("Basic Y2k6c2VjcmV0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_legacy_auth() {
+        let config = NpmrcConfig::parse("//registry.acme.dev/:_auth=dXNlcjpwYXNz\n");
+        assert_eq!(
+            config.auth_header_for("https://registry.acme.dev/pkg"),
+            Some("Basic dXNlcjpwYXNz".to_string())
+        );
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let config = NpmrcConfig::parse(
+            "//host.dev/:_authToken=outer\n//host.dev/team/:_authToken=inner\n",
+        );
+        assert_eq!(
+            config.auth_header_for("https://host.dev/team/pkg"),
+            Some("Bearer inner".to_string())
+        );
+        assert_eq!(
+            config.auth_header_for("https://host.dev/pkg"),
+            Some("Bearer outer".to_string())
+        );
+    }
+
+    #[test]
+    fn test_proxy_settings() {
+        let config = NpmrcConfig::parse(SAMPLE);
+        assert_eq!(config.proxy_for("https://registry.npmjs.org/x"), Some("http://proxy.acme.dev:8080"));
+        assert_eq!(config.proxy_for("http://registry.npmjs.org/x"), None);
+        assert_eq!(config.no_proxy.as_deref(), Some("localhost,.acme.dev"));
+        assert_eq!(config.cafile.as_deref(), Some("/etc/ssl/acme-root.pem"));
+    }
+
+    #[test]
+    fn test_env_expansion() {
+        std::env::set_var("CODESCOPE_NPMRC_TEST_TOKEN", "from-env");
+        let config = NpmrcConfig::parse("//r.dev/:_authToken=${CODESCOPE_NPMRC_TEST_TOKEN}\n");
+        assert_eq!(
+            config.auth_header_for("https://r.dev/pkg"),
+            Some("Bearer from-env".to_string())
+        );
+        assert_eq!(expand_env("a${CODESCOPE_NPMRC_TEST_MISSING}b"), "ab");
+        assert_eq!(expand_env("${unterminated"), "${unterminated");
+    }
+
+    #[test]
+    fn test_merge_project_overrides_user() {
+        let mut user = NpmrcConfig::parse(
+            "registry=https://user.dev/\n//r.dev/:_authToken=user-token\n@a:registry=https://a.dev\n",
+        );
+        let project = NpmrcConfig::parse("registry=https://project.dev/\n//r.dev/:_authToken=project-token\n");
+        user.merge(project);
+
+        assert_eq!(user.default_registry(), "https://project.dev");
+        assert_eq!(user.registry_for("@a/x"), "https://a.dev");
+        assert_eq!(
+            user.auth_header_for("https://r.dev/x"),
+            Some("Bearer project-token".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_reads_project_file() {
+        let dir = std::env::temp_dir().join(format!("codescope-npmrc-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".npmrc"), "@acme:registry=https://npm.acme.dev\n").unwrap();
+
+        let config = NpmrcConfig::load(&dir).unwrap();
+        assert_eq!(config.registry_for("@acme/x"), "https://npm.acme.dev");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
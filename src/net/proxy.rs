@@ -0,0 +1,276 @@
+//! Proxy selection for outbound requests.
+//!
+//! Proxies come from the conventional `HTTP_PROXY`, `HTTPS_PROXY` and
+//! `NO_PROXY` environment variables (upper- or lowercase), with `.npmrc`
+//! `proxy`, `https-proxy` and `noproxy` taking precedence when set. HTTPS
+//! requests fall back to the plain HTTP proxy, as npm does.
+
+use super::npmrc::NpmrcConfig;
+
+/// Proxy settings resolved from the environment and `.npmrc`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy for plain HTTP requests
+    pub http_proxy: Option<String>,
+    /// Proxy for HTTPS requests
+    pub https_proxy: Option<String>,
+    /// Hosts that are contacted directly (`NO_PROXY` entries)
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Reads proxy settings from the process environment.
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Reads proxy settings through a variable lookup function.
+    ///
+    /// Lowercase variables take precedence over uppercase ones, matching
+    /// curl; empty values are treated as unset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::net::ProxyConfig;
+    ///
+    /// let proxy = ProxyConfig::from_vars(|key| match key {
+    ///     "HTTPS_PROXY" => Some("http://proxy.corp:3128".to_string()),
+    ///     "NO_PROXY" => Some("localhost,.corp".to_string()),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(proxy.proxy_for("https://registry.npmjs.org/react"), Some("http://proxy.corp:3128"));
+    /// assert_eq!(proxy.proxy_for("https://npm.corp/react"), None);
+    /// ```
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            lookup(&name.to_lowercase())
+                .filter(|v| !v.trim().is_empty())
+                .or_else(|| lookup(name).filter(|v| !v.trim().is_empty()))
+        };
+
+        Self {
+            http_proxy: var("HTTP_PROXY"),
+            https_proxy: var("HTTPS_PROXY"),
+            no_proxy: var("NO_PROXY")
+                .map(|list| parse_no_proxy(&list))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Resolves the effective settings: `.npmrc` values override the
+    /// environment.
+    pub fn resolve(npmrc: &NpmrcConfig) -> Self {
+        let mut config = Self::from_env();
+        config.overlay(npmrc);
+        config
+    }
+
+    /// Applies any proxy settings present in an `.npmrc` configuration.
+    pub fn overlay(&mut self, npmrc: &NpmrcConfig) {
+        if let Some(proxy) = &npmrc.proxy {
+            self.http_proxy = Some(proxy.clone());
+        }
+        if let Some(proxy) = &npmrc.https_proxy {
+            self.https_proxy = Some(proxy.clone());
+        }
+        if let Some(list) = &npmrc.no_proxy {
+            self.no_proxy = parse_no_proxy(list);
+        }
+    }
+
+    /// Returns true if no proxy is configured at all.
+    pub fn is_empty(&self) -> bool {
+        self.http_proxy.is_none() && self.https_proxy.is_none()
+    }
+
+    /// Returns the proxy a request to `url` should go through, if any.
+    pub fn proxy_for(&self, url: &str) -> Option<&str> {
+        if self.bypasses(url) {
+            return None;
+        }
+        self.proxy_for_scheme(is_https(url))
+    }
+
+    /// Returns the proxy used for HTTP or HTTPS requests, ignoring `NO_PROXY`.
+    pub fn proxy_for_scheme(&self, https: bool) -> Option<&str> {
+        if https {
+            self.https_proxy.as_deref().or(self.http_proxy.as_deref())
+        } else {
+            self.http_proxy.as_deref()
+        }
+    }
+
+    /// Returns true if `url` matches a `NO_PROXY` entry.
+    ///
+    /// Entries match the host exactly or any subdomain of it (`example.com`
+    /// and `.example.com` both cover `registry.example.com`); an entry with
+    /// a port only matches that port, and `*` matches every host.
+    pub fn bypasses(&self, url: &str) -> bool {
+        let Some((host, port)) = host_and_port(url) else {
+            return false;
+        };
+
+        self.no_proxy.iter().any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let (pattern, entry_port) = split_port(entry);
+            if let Some(entry_port) = entry_port {
+                if Some(entry_port) != port {
+                    return false;
+                }
+            }
+            let pattern = pattern.trim_start_matches("*.").trim_start_matches('.');
+            !pattern.is_empty()
+                && (host == pattern
+                    || host
+                        .strip_suffix(pattern)
+                        .is_some_and(|prefix| prefix.ends_with('.')))
+        })
+    }
+}
+
+/// Splits a comma- or whitespace-separated `NO_PROXY` list into lowercase
+/// entries.
+fn parse_no_proxy(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn is_https(url: &str) -> bool {
+    url.get(..6).is_some_and(|scheme| scheme.eq_ignore_ascii_case("https:"))
+}
+
+/// Extracts the lowercase host and the effective port from a URL.
+fn host_and_port(url: &str) -> Option<(String, Option<u16>)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let (host, port) = split_port(authority);
+    let port = port.or(match scheme.to_ascii_lowercase().as_str() {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    });
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    (!host.is_empty()).then(|| (host.to_lowercase(), port))
+}
+
+/// Splits `host:port`, leaving bracketed IPv6 addresses intact.
+fn split_port(authority: &str) -> (&str, Option<u16>) {
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && (!host.contains(':') || host.ends_with(']')) => {
+            match port.parse() {
+                Ok(port) => (host, Some(port)),
+                Err(_) => (authority, None),
+            }
+        }
+        _ => (authority, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn from_map(vars: &[(&str, &str)]) -> ProxyConfig {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ProxyConfig::from_vars(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_env_variables() {
+        let proxy = from_map(&[
+            ("HTTP_PROXY", "http://plain.proxy:80"),
+            ("HTTPS_PROXY", "http://secure.proxy:8443"),
+        ]);
+        assert_eq!(proxy.proxy_for("http://a.dev/x"), Some("http://plain.proxy:80"));
+        assert_eq!(proxy.proxy_for("https://a.dev/x"), Some("http://secure.proxy:8443"));
+    }
+
+    #[test]
+    fn test_lowercase_takes_precedence() {
+        let proxy = from_map(&[
+            ("https_proxy", "http://lower:1"),
+            ("HTTPS_PROXY", "http://upper:2"),
+            ("http_proxy", ""),
+            ("HTTP_PROXY", "http://upper:3"),
+        ]);
+        assert_eq!(proxy.https_proxy.as_deref(), Some("http://lower:1"));
+        // Empty lowercase values fall through to uppercase
+        assert_eq!(proxy.http_proxy.as_deref(), Some("http://upper:3"));
+    }
+
+    #[test]
+    fn test_https_falls_back_to_http_proxy() {
+        let proxy = from_map(&[("HTTP_PROXY", "http://only:3128")]);
+        assert_eq!(proxy.proxy_for("https://registry.npmjs.org/x"), Some("http://only:3128"));
+        assert!(!proxy.is_empty());
+        assert!(from_map(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_no_proxy_matching() {
+        let proxy = from_map(&[
+            ("HTTPS_PROXY", "http://proxy:3128"),
+            ("NO_PROXY", "localhost, .corp.dev,npm.acme.io:8443 *.internal 10.0.0.5"),
+        ]);
+        assert!(proxy.bypasses("https://localhost/x"));
+        assert!(proxy.bypasses("https://npm.corp.dev/x"));
+        assert!(proxy.bypasses("https://corp.dev/x"));
+        assert!(proxy.bypasses("https://a.b.internal/x"));
+        assert!(proxy.bypasses("http://10.0.0.5:4873/x"));
+        assert!(proxy.bypasses("https://npm.acme.io:8443/x"));
+        assert!(!proxy.bypasses("https://npm.acme.io/x"));
+        assert!(!proxy.bypasses("https://notcorp.dev/x"));
+        assert!(!proxy.bypasses("https://registry.npmjs.org/x"));
+        assert_eq!(proxy.proxy_for("https://npm.corp.dev/x"), None);
+        assert_eq!(proxy.proxy_for("https://registry.npmjs.org/x"), Some("http://proxy:3128"));
+    }
+
+    #[test]
+    fn test_no_proxy_wildcard_and_case() {
+        let proxy = from_map(&[("HTTPS_PROXY", "http://p:1"), ("no_proxy", "*")]);
+        assert!(proxy.bypasses("https://anything.example/x"));
+
+        let proxy = from_map(&[("NO_PROXY", "Registry.Example.COM")]);
+        assert!(proxy.bypasses("https://user:pw@REGISTRY.example.com/x"));
+    }
+
+    #[test]
+    fn test_npmrc_overrides_environment() {
+        let mut proxy = from_map(&[
+            ("HTTPS_PROXY", "http://env:1"),
+            ("HTTP_PROXY", "http://env:2"),
+            ("NO_PROXY", "env.dev"),
+        ]);
+        proxy.overlay(&NpmrcConfig::parse(
+            "https-proxy=http://npmrc:1\nnoproxy=npmrc.dev\n",
+        ));
+
+        assert_eq!(proxy.https_proxy.as_deref(), Some("http://npmrc:1"));
+        assert_eq!(proxy.http_proxy.as_deref(), Some("http://env:2"));
+        assert_eq!(proxy.no_proxy, vec!["npmrc.dev".to_string()]);
+    }
+
+    #[test]
+    fn test_host_and_port() {
+        assert_eq!(
+            host_and_port("https://registry.npmjs.org/react"),
+            Some(("registry.npmjs.org".to_string(), Some(443)))
+        );
+        assert_eq!(
+            host_and_port("http://[::1]:4873/x"),
+            Some(("::1".to_string(), Some(4873)))
+        );
+        assert_eq!(host_and_port("not a url"), None);
+    }
+}
@@ -0,0 +1,808 @@
+//! npm registry client.
+//!
+//! `RegistryClient` is the shared entry point for registry metadata. It
+//! fetches package documents with bounded parallelism, retries transient
+//! failures with exponential backoff, and revalidates cached documents
+//! using their `ETag` so unchanged packages cost a `304` instead of a
+//! full download.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::cache::{CacheEntry, MetadataCache};
+use super::npmrc::NpmrcConfig;
+use super::{HttpResponse, HttpTransport, NetError, NetResult, Transport};
+
+/// Default public npm registry
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// Default number of requests in flight at once
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Metadata for a single published version.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VersionMetadata {
+    /// Version string
+    #[serde(default)]
+    pub version: String,
+    /// Deprecation message (a string), or `false`/absent if not deprecated
+    #[serde(default)]
+    pub deprecated: Option<Value>,
+    /// License as published (SPDX string or legacy `{ "type": ... }` object)
+    #[serde(default)]
+    pub license: Option<Value>,
+}
+
+impl VersionMetadata {
+    /// Returns the deprecation message, if this version is deprecated.
+    pub fn deprecation_message(&self) -> Option<&str> {
+        self.deprecated
+            .as_ref()
+            .and_then(Value::as_str)
+            .filter(|msg| !msg.is_empty())
+    }
+
+    /// Returns the license identifier, if known.
+    pub fn license_id(&self) -> Option<&str> {
+        license_id(self.license.as_ref())
+    }
+}
+
+/// A package document from the registry.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PackageMetadata {
+    /// Package name
+    pub name: String,
+    /// Package description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Search keywords, normally a list of strings
+    #[serde(default)]
+    pub keywords: Option<Value>,
+    /// Distribution tags such as `latest` and `next`
+    #[serde(rename = "dist-tags", default)]
+    pub dist_tags: HashMap<String, String>,
+    /// All published versions
+    #[serde(default)]
+    pub versions: HashMap<String, VersionMetadata>,
+    /// Publish timestamps by version, plus `created` and `modified`
+    #[serde(default)]
+    pub time: HashMap<String, Value>,
+    /// License of the latest version
+    #[serde(default)]
+    pub license: Option<Value>,
+}
+
+impl PackageMetadata {
+    /// Parses a registry package document.
+    pub fn parse(body: &str) -> NetResult<Self> {
+        Ok(serde_json::from_str(body)?)
+    }
+
+    /// Returns the version tagged `latest`, if any.
+    pub fn latest_version(&self) -> Option<&str> {
+        self.dist_tags.get("latest").map(String::as_str)
+    }
+
+    /// Returns the metadata for a specific version.
+    pub fn version(&self, version: &str) -> Option<&VersionMetadata> {
+        self.versions.get(version)
+    }
+
+    /// Returns the deprecation message of the latest version, if deprecated.
+    pub fn deprecation_message(&self) -> Option<&str> {
+        self.latest_version()
+            .and_then(|v| self.version(v))
+            .and_then(VersionMetadata::deprecation_message)
+    }
+
+    /// Returns the package license identifier, if known.
+    pub fn license_id(&self) -> Option<&str> {
+        license_id(self.license.as_ref()).or_else(|| {
+            self.latest_version()
+                .and_then(|v| self.version(v))
+                .and_then(VersionMetadata::license_id)
+        })
+    }
+
+    /// Returns the publish timestamp (RFC 3339) of a version.
+    pub fn published_at(&self, version: &str) -> Option<&str> {
+        self.time.get(version).and_then(Value::as_str)
+    }
+}
+
+/// Extracts a license identifier from a string or `{ "type": ... }` value.
+fn license_id(value: Option<&Value>) -> Option<&str> {
+    match value? {
+        Value::String(s) => Some(s.as_str()),
+        Value::Object(obj) => obj.get("type").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Returns the cache key for a document URL.
+///
+/// Keys include the registry host so documents from a private registry are
+/// never confused with public packages of the same name.
+fn cache_key(url: &str) -> String {
+    url.split_once("://").map(|(_, rest)| rest).unwrap_or(url).to_string()
+}
+
+/// Where a piece of remote data came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    /// Fetched or revalidated against the registry during this run
+    Network,
+    /// Served from the local cache without contacting the registry
+    Cache,
+}
+
+impl DataSource {
+    /// Returns the annotation shown next to data from this source.
+    pub fn annotation(&self) -> &'static str {
+        match self {
+            DataSource::Network => "",
+            DataSource::Cache => " (cached)",
+        }
+    }
+}
+
+/// Package metadata together with where it came from.
+#[derive(Debug, Clone)]
+pub struct Fetched {
+    /// The package document
+    pub metadata: PackageMetadata,
+    /// Whether the document is live or cached
+    pub source: DataSource,
+}
+
+impl Fetched {
+    fn from_cache(entry: &CacheEntry) -> NetResult<Self> {
+        Ok(Self {
+            metadata: PackageMetadata::parse(&entry.body)?,
+            source: DataSource::Cache,
+        })
+    }
+}
+
+/// How failed requests are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay: Duration,
+    /// Upper bound for a single delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the delay before retry number `attempt` (0-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Shared client for npm registry metadata.
+#[derive(Clone)]
+pub struct RegistryClient {
+    config: NpmrcConfig,
+    transport: Arc<dyn Transport>,
+    cache: Option<MetadataCache>,
+    retry: RetryPolicy,
+    max_concurrency: usize,
+    offline: bool,
+}
+
+impl std::fmt::Debug for RegistryClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryClient")
+            .field("config", &self.config)
+            .field("cache", &self.cache)
+            .field("retry", &self.retry)
+            .field("max_concurrency", &self.max_concurrency)
+            .field("offline", &self.offline)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegistryClient {
+    /// Creates a client for the public registry using the default cache.
+    pub fn new() -> Self {
+        Self::with_transport(Arc::new(HttpTransport::new()))
+            .with_cache(MetadataCache::default_location())
+    }
+
+    /// Creates a client configured from `.npmrc` settings: registries per
+    /// scope and credentials, plus proxies and CA certificates from `.npmrc`
+    /// or the environment.
+    pub fn from_npmrc(config: NpmrcConfig) -> NetResult<Self> {
+        let transport = HttpTransport::from_npmrc(&config)?;
+        Ok(Self::with_transport(Arc::new(transport))
+            .with_cache(MetadataCache::default_location())
+            .with_npmrc(config))
+    }
+
+    /// Creates an uncached client using a custom transport.
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            config: NpmrcConfig::default(),
+            transport,
+            cache: None,
+            retry: RetryPolicy::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            offline: false,
+        }
+    }
+
+    /// Sets the registry base URL.
+    pub fn with_registry(mut self, url: impl Into<String>) -> Self {
+        self.config.registry = Some(url.into());
+        self
+    }
+
+    /// Sets the `.npmrc` configuration used to route and authenticate requests.
+    pub fn with_npmrc(mut self, config: NpmrcConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the response cache (`None` disables caching).
+    pub fn with_cache(mut self, cache: Option<MetadataCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the maximum number of concurrent requests (at least 1).
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Sets offline mode. An offline client never touches the network and
+    /// answers only from the cache.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Returns true if the client is in offline mode.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Returns the registry base URL.
+    pub fn registry_url(&self) -> &str {
+        self.config.default_registry()
+    }
+
+    /// Returns the document URL for a package.
+    ///
+    /// The registry is chosen per scope as configured in `.npmrc`. Scoped
+    /// names keep their `@` and encode the slash, as npm does.
+    pub fn package_url(&self, package_name: &str) -> String {
+        format!(
+            "{}/{}",
+            self.config.registry_for(package_name),
+            package_name.replace('/', "%2F")
+        )
+    }
+
+    /// Fetches the metadata for a single package.
+    ///
+    /// A cached document is revalidated with `If-None-Match` and reused
+    /// when the registry answers `304 Not Modified`. If the registry cannot
+    /// be reached, or the client is offline, the cached document is returned
+    /// as-is and marked as [`DataSource::Cache`].
+    pub fn fetch(&self, package_name: &str) -> NetResult<Fetched> {
+        let url = self.package_url(package_name);
+        let cache_key = cache_key(&url);
+        let cached = self.cache.as_ref().and_then(|c| c.get(&cache_key));
+
+        if self.offline {
+            return match cached {
+                Some(entry) => Fetched::from_cache(&entry),
+                None => Err(NetError::Offline(package_name.to_string())),
+            };
+        }
+
+        let auth = self.config.auth_header_for(&url);
+        let mut headers = vec![("Accept", "application/json")];
+        if let Some(auth) = auth.as_deref() {
+            headers.push(("Authorization", auth));
+        }
+        if let Some(etag) = cached.as_ref().and_then(|e| e.etag.as_deref()) {
+            headers.push(("If-None-Match", etag));
+        }
+
+        let response = match self.get_with_retry(&url, &headers) {
+            Ok(response) => response,
+            Err(e) if e.is_retryable() => {
+                return match cached {
+                    Some(entry) => Fetched::from_cache(&entry),
+                    None => Err(e),
+                };
+            }
+            Err(e) => return Err(e),
+        };
+
+        match response.status {
+            304 => match cached {
+                Some(mut entry) => {
+                    entry.touch();
+                    self.store(&cache_key, &entry);
+                    Ok(Fetched {
+                        metadata: PackageMetadata::parse(&entry.body)?,
+                        source: DataSource::Network,
+                    })
+                }
+                None => Err(NetError::Http { status: 304, url }),
+            },
+            200..=299 => {
+                let metadata = PackageMetadata::parse(&response.body)?;
+                self.store(&cache_key, &CacheEntry::new(response.etag, response.body));
+                Ok(Fetched {
+                    metadata,
+                    source: DataSource::Network,
+                })
+            }
+            404 => Err(NetError::NotFound(package_name.to_string())),
+            status => Err(NetError::Http { status, url }),
+        }
+    }
+
+    /// Fetches metadata for many packages concurrently.
+    ///
+    /// At most `max_concurrency` requests are in flight at once. Duplicate
+    /// names are fetched once. Every requested name has an entry in the
+    /// returned map, holding either its metadata or the error it hit.
+    pub fn prefetch(&self, package_names: &[String]) -> HashMap<String, NetResult<Fetched>> {
+        let mut unique: Vec<&str> = package_names.iter().map(String::as_str).collect();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(HashMap::with_capacity(unique.len()));
+        let workers = self.max_concurrency.min(unique.len());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(name) = unique.get(i) else {
+                        break;
+                    };
+                    let result = self.fetch(name);
+                    results
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(name.to_string(), result);
+                });
+            }
+        });
+
+        results.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Performs a GET, retrying transient failures with backoff.
+    fn get_with_retry(&self, url: &str, headers: &[(&str, &str)]) -> NetResult<HttpResponse> {
+        let mut attempt = 0;
+        loop {
+            let result = self.transport.get(url, headers).and_then(|response| {
+                if response.status == 429 || response.status >= 500 {
+                    Err(NetError::Http {
+                        status: response.status,
+                        url: url.to_string(),
+                    })
+                } else {
+                    Ok(response)
+                }
+            });
+
+            match result {
+                Err(e) if e.is_retryable() && attempt < self.retry.max_retries => {
+                    thread::sleep(self.retry.delay(attempt));
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Writes a cache entry; cache failures never fail a fetch.
+    fn store(&self, key: &str, entry: &CacheEntry) {
+        if let Some(cache) = &self.cache {
+            let _ = cache.put(key, entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// A recorded request: URL and headers.
+    type Request = (String, Vec<(String, String)>);
+
+    /// Transport that replays scripted responses per URL and records requests.
+    #[derive(Default)]
+    struct MockTransport {
+        responses: Mutex<HashMap<String, VecDeque<NetResult<HttpResponse>>>>,
+        requests: Mutex<Vec<Request>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl MockTransport {
+        fn respond(&self, url: &str, response: NetResult<HttpResponse>) {
+            self.responses
+                .lock()
+                .unwrap()
+                .entry(url.to_string())
+                .or_default()
+                .push_back(response);
+        }
+
+        fn request_count(&self, url: &str) -> usize {
+            self.requests.lock().unwrap().iter().filter(|(u, _)| u == url).count()
+        }
+
+        fn last_headers(&self, url: &str) -> Vec<(String, String)> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .find(|(u, _)| u == url)
+                .map(|(_, h)| h.clone())
+                .unwrap_or_default()
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> NetResult<HttpResponse> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+
+            self.requests.lock().unwrap().push((
+                url.to_string(),
+                headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            ));
+            let response = self
+                .responses
+                .lock()
+                .unwrap()
+                .get_mut(url)
+                .and_then(VecDeque::pop_front)
+                .unwrap_or_else(|| ok(200, None, &doc("fallback", "1.0.0")));
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            response
+        }
+    }
+
+    fn ok(status: u16, etag: Option<&str>, body: &str) -> NetResult<HttpResponse> {
+        Ok(HttpResponse {
+            status,
+            etag: etag.map(str::to_string),
+            body: body.to_string(),
+        })
+    }
+
+    fn doc(name: &str, latest: &str) -> String {
+        format!(
+            r#"{{"name":"{name}","dist-tags":{{"latest":"{latest}"}},"versions":{{"{latest}":{{"version":"{latest}","license":"MIT"}}}}}}"#
+        )
+    }
+
+    fn client(transport: &Arc<MockTransport>) -> RegistryClient {
+        RegistryClient::with_transport(transport.clone()).with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        })
+    }
+
+    fn temp_cache(name: &str) -> MetadataCache {
+        let dir = std::env::temp_dir().join(format!("codescope-registry-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        MetadataCache::new(dir)
+    }
+
+    #[test]
+    fn test_package_url() {
+        let client = RegistryClient::with_transport(Arc::new(MockTransport::default()))
+            .with_registry("https://npm.example.com/");
+        assert_eq!(client.package_url("react"), "https://npm.example.com/react");
+        assert_eq!(client.package_url("@babel/core"), "https://npm.example.com/@babel%2Fcore");
+    }
+
+    #[test]
+    fn test_package_url_uses_scoped_registry() {
+        let config = NpmrcConfig::parse("@acme:registry=https://npm.acme.dev/\n");
+        let client = RegistryClient::with_transport(Arc::new(MockTransport::default())).with_npmrc(config);
+        assert_eq!(client.package_url("@acme/ui"), "https://npm.acme.dev/@acme%2Fui");
+        assert_eq!(client.package_url("react"), "https://registry.npmjs.org/react");
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(cache_key("https://registry.npmjs.org/react"), "registry.npmjs.org/react");
+        assert_eq!(cache_key("https://npm.acme.dev/@acme%2Fui"), "npm.acme.dev/@acme%2Fui");
+    }
+
+    #[test]
+    fn test_fetch_sends_auth_only_to_configured_registry() {
+        let config = NpmrcConfig::parse(
+            "@acme:registry=https://npm.acme.dev/\n//npm.acme.dev/:_authToken=secret\n",
+        );
+        let transport = Arc::new(MockTransport::default());
+        let client = client(&transport).with_npmrc(config);
+
+        client.fetch("@acme/ui").unwrap();
+        client.fetch("react").unwrap();
+
+        assert!(transport
+            .last_headers("https://npm.acme.dev/@acme%2Fui")
+            .contains(&("Authorization".to_string(), "Bearer secret".to_string())));
+        assert!(!transport
+            .last_headers("https://registry.npmjs.org/react")
+            .iter()
+            .any(|(k, _)| k == "Authorization"));
+        assert_eq!(transport.request_count("https://registry.npmjs.org/@acme%2Fui"), 0);
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let body = r#"{
+            "name": "request",
+            "dist-tags": { "latest": "2.88.2" },
+            "versions": {
+                "2.88.2": { "version": "2.88.2", "deprecated": "request has been deprecated", "license": "Apache-2.0" },
+                "2.88.0": { "version": "2.88.0", "deprecated": false }
+            },
+            "time": { "2.88.2": "2020-02-11T16:35:18.000Z", "unpublished": { "time": "x" } },
+            "license": { "type": "Apache-2.0" }
+        }"#;
+        let meta = PackageMetadata::parse(body).unwrap();
+
+        assert_eq!(meta.latest_version(), Some("2.88.2"));
+        assert_eq!(meta.deprecation_message(), Some("request has been deprecated"));
+        assert_eq!(meta.version("2.88.0").unwrap().deprecation_message(), None);
+        assert_eq!(meta.license_id(), Some("Apache-2.0"));
+        assert_eq!(meta.published_at("2.88.2"), Some("2020-02-11T16:35:18.000Z"));
+        assert_eq!(meta.published_at("unpublished"), None);
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+        assert_eq!(policy.delay(3), Duration::from_millis(500));
+        assert_eq!(policy.delay(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_fetch_success() {
+        let transport = Arc::new(MockTransport::default());
+        let url = "https://registry.npmjs.org/react";
+        transport.respond(url, ok(200, None, &doc("react", "18.2.0")));
+
+        let fetched = client(&transport).fetch("react").unwrap();
+        assert_eq!(fetched.source, DataSource::Network);
+        let meta = fetched.metadata;
+        assert_eq!(meta.name, "react");
+        assert_eq!(meta.latest_version(), Some("18.2.0"));
+        assert_eq!(transport.request_count(url), 1);
+    }
+
+    #[test]
+    fn test_fetch_retries_transient_errors() {
+        let transport = Arc::new(MockTransport::default());
+        let url = "https://registry.npmjs.org/react";
+        transport.respond(url, ok(503, None, ""));
+        transport.respond(
+            url,
+            Err(NetError::Transport {
+                url: url.to_string(),
+                message: "connection reset".to_string(),
+            }),
+        );
+        transport.respond(url, ok(200, None, &doc("react", "18.2.0")));
+
+        assert!(client(&transport).fetch("react").is_ok());
+        assert_eq!(transport.request_count(url), 3);
+    }
+
+    #[test]
+    fn test_fetch_gives_up_after_max_retries() {
+        let transport = Arc::new(MockTransport::default());
+        let url = "https://registry.npmjs.org/react";
+        for _ in 0..5 {
+            transport.respond(url, ok(429, None, ""));
+        }
+
+        let err = client(&transport).fetch("react").unwrap_err();
+        assert!(matches!(err, NetError::Http { status: 429, .. }));
+        assert_eq!(transport.request_count(url), 3);
+    }
+
+    #[test]
+    fn test_fetch_does_not_retry_not_found() {
+        let transport = Arc::new(MockTransport::default());
+        let url = "https://registry.npmjs.org/nope";
+        transport.respond(url, ok(404, None, ""));
+
+        let err = client(&transport).fetch("nope").unwrap_err();
+        assert!(matches!(err, NetError::NotFound(name) if name == "nope"));
+        assert_eq!(transport.request_count(url), 1);
+    }
+
+    #[test]
+    fn test_fetch_revalidates_with_etag() {
+        let cache = temp_cache("etag");
+        let transport = Arc::new(MockTransport::default());
+        let url = "https://registry.npmjs.org/react";
+        transport.respond(url, ok(200, Some("\"v1\""), &doc("react", "18.2.0")));
+        transport.respond(url, ok(304, None, ""));
+
+        let client = client(&transport).with_cache(Some(cache.clone()));
+
+        client.fetch("react").unwrap();
+        assert!(!transport
+            .last_headers(url)
+            .iter()
+            .any(|(k, _)| k == "If-None-Match"));
+
+        let fetched = client.fetch("react").unwrap();
+        assert_eq!(fetched.source, DataSource::Network);
+        assert_eq!(fetched.metadata.latest_version(), Some("18.2.0"));
+        assert!(transport
+            .last_headers(url)
+            .contains(&("If-None-Match".to_string(), "\"v1\"".to_string())));
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_offline_uses_cache_without_network() {
+        let cache = temp_cache("offline");
+        cache
+            .put("registry.npmjs.org/react", &CacheEntry::new(None, doc("react", "18.2.0")))
+            .unwrap();
+        let transport = Arc::new(MockTransport::default());
+
+        let client = client(&transport)
+            .with_cache(Some(cache.clone()))
+            .with_offline(true);
+        assert!(client.is_offline());
+
+        let fetched = client.fetch("react").unwrap();
+        assert_eq!(fetched.source, DataSource::Cache);
+        assert_eq!(fetched.metadata.latest_version(), Some("18.2.0"));
+
+        let err = client.fetch("lodash").unwrap_err();
+        assert!(matches!(err, NetError::Offline(ref name) if name == "lodash"));
+        assert_eq!(err.to_string(), "lodash: unavailable offline");
+
+        assert!(transport.requests.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_network_failure_falls_back_to_cache() {
+        let cache = temp_cache("fallback");
+        cache
+            .put("registry.npmjs.org/react", &CacheEntry::new(None, doc("react", "18.2.0")))
+            .unwrap();
+        let transport = Arc::new(MockTransport::default());
+        for _ in 0..3 {
+            transport.respond("https://registry.npmjs.org/react", ok(503, None, ""));
+        }
+
+        let fetched = client(&transport)
+            .with_cache(Some(cache.clone()))
+            .fetch("react")
+            .unwrap();
+        assert_eq!(fetched.source, DataSource::Cache);
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_data_source_annotation() {
+        assert_eq!(DataSource::Network.annotation(), "");
+        assert_eq!(DataSource::Cache.annotation(), " (cached)");
+    }
+
+    #[test]
+    fn test_not_modified_without_cache_is_error() {
+        let transport = Arc::new(MockTransport::default());
+        transport.respond("https://registry.npmjs.org/react", ok(304, None, ""));
+
+        let err = client(&transport).fetch("react").unwrap_err();
+        assert!(matches!(err, NetError::Http { status: 304, .. }));
+    }
+
+    #[test]
+    fn test_prefetch_collects_all_results() {
+        let transport = Arc::new(MockTransport::default());
+        transport.respond("https://registry.npmjs.org/missing", ok(404, None, ""));
+
+        let names: Vec<String> = ["react", "lodash", "react", "missing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let results = client(&transport).prefetch(&names);
+
+        assert_eq!(results.len(), 3);
+        assert!(results["react"].is_ok());
+        assert!(results["lodash"].is_ok());
+        assert!(results["missing"].is_err());
+        assert_eq!(transport.request_count("https://registry.npmjs.org/react"), 1);
+    }
+
+    #[test]
+    fn test_prefetch_bounds_concurrency() {
+        let transport = Arc::new(MockTransport::default());
+        let names: Vec<String> = (0..20).map(|i| format!("pkg-{}", i)).collect();
+
+        let results = client(&transport).with_max_concurrency(3).prefetch(&names);
+
+        assert_eq!(results.len(), 20);
+        let max = transport.max_in_flight.load(Ordering::SeqCst);
+        assert!(max <= 3, "max in flight was {}", max);
+        assert!(max >= 1);
+    }
+
+    #[test]
+    fn test_prefetch_empty() {
+        let transport = Arc::new(MockTransport::default());
+        assert!(client(&transport).prefetch(&[]).is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+//! TLS configuration for outbound requests.
+//!
+//! Corporate networks often intercept TLS with a private root certificate.
+//! A PEM bundle of extra certificate authorities can be supplied through
+//! `.npmrc` (`cafile`) or `NODE_EXTRA_CA_CERTS`; its certificates are
+//! trusted in addition to the built-in Mozilla root set.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::CertificateDer;
+use rustls::{ClientConfig, RootCertStore};
+
+use super::npmrc::NpmrcConfig;
+use super::{NetError, NetResult};
+
+/// Returns the CA bundle configured for a project, if any.
+///
+/// `.npmrc` `cafile` takes precedence over `NODE_EXTRA_CA_CERTS`.
+pub fn ca_bundle_path(npmrc: &NpmrcConfig) -> Option<PathBuf> {
+    npmrc.cafile.as_ref().map(PathBuf::from).or_else(|| {
+        std::env::var_os("NODE_EXTRA_CA_CERTS")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Reads every certificate from a PEM bundle.
+///
+/// Non-certificate sections (such as private keys) are skipped; a bundle
+/// without any certificate is an error.
+pub fn load_ca_bundle(path: &Path) -> NetResult<Vec<CertificateDer<'static>>> {
+    let invalid = |message: String| NetError::InvalidCaBundle {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let pem = fs::read(path).map_err(|e| invalid(e.to_string()))?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid(e.to_string()))?;
+
+    if certs.is_empty() {
+        return Err(invalid("no PEM certificates found".to_string()));
+    }
+    Ok(certs)
+}
+
+/// Builds a client configuration trusting the built-in roots plus the
+/// certificates in the bundle at `path`.
+pub fn client_config(path: &Path) -> NetResult<Arc<ClientConfig>> {
+    let mut roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    for cert in load_ca_bundle(path)? {
+        roots.add(cert).map_err(|e| NetError::InvalidCaBundle {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+    }
+
+    // Pin the provider ureq itself uses rather than relying on a process
+    // default being installed.
+    let config = ClientConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+        .with_protocol_versions(&[&rustls::version::TLS12, &rustls::version::TLS13])
+        .map_err(|e| NetError::InvalidCaBundle {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(Arc::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBjzCCATWgAwIBAgIUeZu1BlcA7snM3Ed8ZAWfAFgwXsswCgYIKoZIzj0EAwIw
+HDEaMBgGA1UEAwwRY29kZXNjb3BlIHRlc3QgQ0EwIBcNMjYxMDE2MTQzMjU3WhgP
+MjEyNjA5MjIxNDMyNTdaMBwxGjAYBgNVBAMMEWNvZGVzY29wZSB0ZXN0IENBMFkw
+EwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEtuY1iWOCuXk1laBdHvmjRQTMZuLLn2wF
+Q9Lga++dRJ8rQQxndkK2MEP5cZGfGvS77N4bbdZ6SZND5MmxEsg//aNTMFEwHQYD
+VR0OBBYEFN/Q4IybMwT8wmooaoteWc6uR7uYMB8GA1UdIwQYMBaAFN/Q4IybMwT8
+wmooaoteWc6uR7uYMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSAAwRQIg
+b7DsIC3YBvWLXxHb+ENz9InR/2d2uW78TFaPg+0UnAICIQC9OCClBj1RZhzYHBSK
+DH5mjyi0YXSnkoRhX+uzDSHWKg==
+-----END CERTIFICATE-----
+";
+
+    fn write_temp(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("codescope-tls-test-{}-{}.pem", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_bundle() {
+        let path = write_temp("bundle", &format!("# corporate roots\n{}{}", TEST_CA, TEST_CA));
+        assert_eq!(load_ca_bundle(&path).unwrap().len(), 2);
+        assert!(client_config(&path).is_ok());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_empty_bundle_is_rejected() {
+        let path = write_temp("empty", "not a certificate\n");
+        let err = load_ca_bundle(&path).unwrap_err();
+        assert!(matches!(err, NetError::InvalidCaBundle { .. }));
+        assert!(err.to_string().contains("no PEM certificates found"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_bundle() {
+        let err = load_ca_bundle(Path::new("/nonexistent/codescope-ca.pem")).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid CA bundle /nonexistent/codescope-ca.pem"));
+    }
+
+    #[test]
+    fn test_npmrc_cafile_takes_precedence() {
+        let npmrc = NpmrcConfig::parse("cafile=/etc/ssl/corp.pem\n");
+        assert_eq!(ca_bundle_path(&npmrc), Some(PathBuf::from("/etc/ssl/corp.pem")));
+    }
+}
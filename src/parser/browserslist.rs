@@ -0,0 +1,347 @@
+//! Browserslist configuration.
+//!
+//! Reads the browsers a project declares support for, from the
+//! `browserslist` field of package.json or a `.browserslistrc` file, and
+//! reduces the queries to the oldest version of each browser. Only queries
+//! that name a browser and version can be resolved offline; usage and
+//! release based queries such as `> 0.5%` or `last 2 versions` need the
+//! caniuse database and are kept aside as unresolved.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::package_json::ParseResult;
+
+/// Config files read when package.json has no `browserslist` field.
+const CONFIG_FILES: [&str; 2] = [".browserslistrc", "browserslist"];
+
+/// The environment whose queries are used; builds run in production.
+const ENVIRONMENT: &str = "production";
+
+/// A browser release, e.g. `13.1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct BrowserVersion {
+    /// Major version
+    pub major: u32,
+    /// Minor version, 0 if absent
+    pub minor: u32,
+}
+
+impl BrowserVersion {
+    /// Creates a version.
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns the smallest version above this one, for `> 60` queries.
+    fn successor(self) -> Self {
+        Self::new(self.major, self.minor + 1)
+    }
+}
+
+impl fmt::Display for BrowserVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.minor == 0 {
+            write!(f, "{}", self.major)
+        } else {
+            write!(f, "{}.{}", self.major, self.minor)
+        }
+    }
+}
+
+impl FromStr for BrowserVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid browser version '{}'", s);
+        let mut parts = s.trim().split('.');
+        let major = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let minor = match parts.next() {
+            Some(part) => part.parse().map_err(|_| invalid())?,
+            None => 0,
+        };
+        Ok(Self::new(major, minor))
+    }
+}
+
+/// Returns the canonical browserslist name of a browser or alias.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::browserslist::canonical_browser;
+///
+/// assert_eq!(canonical_browser("Explorer"), Some("ie"));
+/// assert_eq!(canonical_browser("and_chr"), Some("chrome"));
+/// assert_eq!(canonical_browser("netscape"), None);
+/// ```
+pub fn canonical_browser(name: &str) -> Option<&'static str> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "chrome" | "and_chr" | "chromeandroid" => "chrome",
+        "edge" => "edge",
+        "firefox" | "ff" | "and_ff" | "firefoxandroid" => "firefox",
+        "safari" => "safari",
+        "ios_saf" | "ios" => "ios_saf",
+        "opera" => "opera",
+        "samsung" => "samsung",
+        "ie" | "explorer" => "ie",
+        "node" => "node",
+        _ => return None,
+    })
+}
+
+/// The oldest supported version of each browser a project targets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BrowserTargets {
+    /// Oldest supported version per canonical browser name
+    pub minimums: BTreeMap<String, BrowserVersion>,
+    /// Queries that cannot be resolved without usage data
+    pub unresolved: Vec<String>,
+}
+
+/// A query naming one browser, e.g. `safari >= 13.1`.
+enum Clause {
+    /// The versions from this one up
+    AtLeast(&'static str, BrowserVersion),
+    /// The versions up to this one, back to the first release
+    AtMost(&'static str, BrowserVersion),
+    /// Exactly this version
+    Exactly(&'static str, BrowserVersion),
+}
+
+impl Clause {
+    fn browser(&self) -> &'static str {
+        match self {
+            Clause::AtLeast(browser, _) | Clause::AtMost(browser, _) | Clause::Exactly(browser, _) => browser,
+        }
+    }
+
+    /// Returns true if the clause includes a version of its browser.
+    fn includes(&self, version: BrowserVersion) -> bool {
+        match self {
+            Clause::AtLeast(_, from) => version >= *from,
+            Clause::AtMost(_, to) => version <= *to,
+            Clause::Exactly(_, exact) => version == *exact,
+        }
+    }
+
+    fn parse(query: &str) -> Option<Self> {
+        let mut words = query.split_whitespace();
+        let browser = canonical_browser(words.next()?)?;
+        let rest: Vec<&str> = words.collect();
+        match rest.as_slice() {
+            [">=", version] => Some(Clause::AtLeast(browser, version.parse().ok()?)),
+            [">", version] => Some(Clause::AtLeast(browser, version.parse::<BrowserVersion>().ok()?.successor())),
+            ["<=", version] => Some(Clause::AtMost(browser, version.parse().ok()?)),
+            [version] => match version.split_once('-') {
+                Some((from, _)) => Some(Clause::AtLeast(browser, from.parse().ok()?)),
+                None => Some(Clause::Exactly(browser, version.parse().ok()?)),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl BrowserTargets {
+    /// Resolves a list of browserslist queries.
+    ///
+    /// Each entry may hold several queries separated by commas or `or`.
+    /// A browser's oldest version is the lowest any query includes. A
+    /// `not` query that excludes a browser's oldest version drops the
+    /// browser altogether.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::browserslist::{BrowserTargets, BrowserVersion};
+    ///
+    /// let targets = BrowserTargets::parse(&["chrome >= 80, safari 13.1", "> 0.5%"]);
+    /// assert_eq!(targets.minimum("chrome"), Some(BrowserVersion::new(80, 0)));
+    /// assert_eq!(targets.minimum("safari"), Some(BrowserVersion::new(13, 1)));
+    /// assert_eq!(targets.unresolved, vec!["> 0.5%"]);
+    /// ```
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Self {
+        let mut targets = Self::default();
+        let mut exclusions = Vec::new();
+        let queries = entries
+            .iter()
+            .flat_map(|entry| entry.as_ref().split(','))
+            .flat_map(|part| part.split(" or "))
+            .map(str::trim)
+            .filter(|query| !query.is_empty());
+
+        for query in queries {
+            let (negated, clause) = match query.strip_prefix("not ") {
+                Some(rest) => (true, Clause::parse(rest)),
+                None => (false, Clause::parse(query)),
+            };
+            match (negated, clause) {
+                (true, Some(clause)) => exclusions.push(clause),
+                (false, Some(Clause::AtLeast(browser, version) | Clause::Exactly(browser, version))) => {
+                    let minimum = targets.minimums.entry(browser.to_string()).or_insert(version);
+                    *minimum = (*minimum).min(version);
+                }
+                // The first release of a browser is not known offline
+                (false, Some(Clause::AtMost(..))) | (_, None) => targets.unresolved.push(query.to_string()),
+            }
+        }
+
+        for clause in exclusions {
+            let browser = clause.browser();
+            if targets.minimums.get(browser).is_some_and(|min| clause.includes(*min)) {
+                targets.minimums.remove(browser);
+            }
+        }
+        targets
+    }
+
+    /// Resolves the contents of a `.browserslistrc` file.
+    ///
+    /// Lines outside any `[section]` apply everywhere; of the
+    /// environment sections only `production` is read.
+    pub fn from_config(content: &str) -> Self {
+        let mut in_section = true;
+        let queries: Vec<&str> = content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim())
+            .filter(|line| {
+                if let Some(names) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                    in_section = names.split_whitespace().any(|name| name == ENVIRONMENT);
+                    return false;
+                }
+                in_section && !line.is_empty()
+            })
+            .collect();
+        Self::parse(&queries)
+    }
+
+    /// Resolves the `browserslist` field of a package.json, if it has one.
+    ///
+    /// The field is a query string, a list of queries, or an object of
+    /// per-environment lists, of which `production` is read, falling back
+    /// to `defaults`.
+    pub fn from_manifest(manifest: &serde_json::Value) -> Option<Self> {
+        let mut field = manifest.get("browserslist")?;
+        if field.is_object() {
+            field = field.get(ENVIRONMENT).or_else(|| field.get("defaults"))?;
+        }
+        match field {
+            serde_json::Value::String(query) => Some(Self::parse(&[query])),
+            serde_json::Value::Array(queries) => {
+                let queries: Vec<&str> = queries.iter().filter_map(|q| q.as_str()).collect();
+                Some(Self::parse(&queries))
+            }
+            _ => None,
+        }
+    }
+
+    /// Loads the browser targets of a project.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the project declares no browserslist configuration.
+    pub fn load(project_dir: &Path) -> ParseResult<Option<Self>> {
+        let manifest_path = project_dir.join("package.json");
+        if manifest_path.is_file() {
+            let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(manifest_path)?)?;
+            if let Some(targets) = Self::from_manifest(&manifest) {
+                return Ok(Some(targets));
+            }
+        }
+        for file in CONFIG_FILES {
+            let path = project_dir.join(file);
+            if path.is_file() {
+                return Ok(Some(Self::from_config(&fs::read_to_string(path)?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the oldest supported version of a browser.
+    pub fn minimum(&self, browser: &str) -> Option<BrowserVersion> {
+        self.minimums.get(browser).copied()
+    }
+
+    /// Returns true if no browser version could be resolved.
+    pub fn is_empty(&self) -> bool {
+        self.minimums.is_empty()
+    }
+
+    /// Describes the targets, e.g. `chrome >= 80, ie >= 11`.
+    pub fn describe(&self) -> String {
+        self.minimums
+            .iter()
+            .map(|(browser, version)| format!("{} >= {}", browser, version))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_queries() {
+        let targets = BrowserTargets::parse(&[
+            "Chrome >= 60",
+            "chrome > 70",
+            "ff 60-62, explorer 11",
+            "ios >= 12 or safari > 13",
+            "last 2 versions",
+            "opera <= 60",
+            "not dead",
+        ]);
+        let minimums: Vec<(&str, String)> = targets
+            .minimums
+            .iter()
+            .map(|(browser, version)| (browser.as_str(), version.to_string()))
+            .collect();
+        assert_eq!(
+            minimums,
+            vec![
+                ("chrome", "60".to_string()),
+                ("firefox", "60".to_string()),
+                ("ie", "11".to_string()),
+                ("ios_saf", "12".to_string()),
+                ("safari", "13.1".to_string()),
+            ]
+        );
+        assert_eq!(targets.unresolved, vec!["last 2 versions", "opera <= 60", "not dead"]);
+        assert_eq!(targets.describe(), "chrome >= 60, firefox >= 60, ie >= 11, ios_saf >= 12, safari >= 13.1");
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let targets = BrowserTargets::parse(&["ie 11", "not ie <= 11", "edge >= 18", "not edge >= 79"]);
+        assert_eq!(targets.minimum("ie"), None);
+        assert_eq!(targets.minimum("edge"), Some(BrowserVersion::new(18, 0)), "edge 18 is still supported");
+    }
+
+    #[test]
+    fn test_config_file() {
+        let targets = BrowserTargets::from_config(
+            "# Browsers we support\nchrome >= 80\n\n[development]\nchrome >= 120\n\n[production staging]\nsafari >= 14 # iPads\n",
+        );
+        assert_eq!(targets.describe(), "chrome >= 80, safari >= 14");
+    }
+
+    #[test]
+    fn test_manifest_field() {
+        let manifest = serde_json::json!({
+            "browserslist": {"production": ["firefox >= 78"], "development": ["last 1 firefox version"]}
+        });
+        let targets = BrowserTargets::from_manifest(&manifest).unwrap();
+        assert_eq!(targets.describe(), "firefox >= 78");
+        assert_eq!(
+            BrowserTargets::from_manifest(&serde_json::json!({"browserslist": "node 14"})).unwrap().describe(),
+            "node >= 14"
+        );
+        assert!(BrowserTargets::from_manifest(&serde_json::json!({"name": "x"})).is_none());
+    }
+}
@@ -0,0 +1,393 @@
+//! Locating installed packages on disk.
+//!
+//! Analyses that read package contents (sizes on disk, `package.json`
+//! fields, entry files) go through [`PackageStore`] so they work both for
+//! classic `node_modules` installs and for Yarn Plug'n'Play projects, where
+//! packages stay zipped in the Yarn cache.
+//!
+//! pnpm and workspace installs link packages into `node_modules`; links
+//! are resolved with [`resolve_links`], which gives up on link cycles
+//! instead of following them forever. Walks inside a package never follow
+//! links, so each file is counted once.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::package_json::ParseResult;
+use super::paths::long_path;
+use super::pnp::PnpManifest;
+
+/// Where a package's files live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageLocation {
+    /// An unpacked package directory
+    Directory(PathBuf),
+    /// A directory inside a zip archive (Yarn cache)
+    Zip {
+        /// Path to the archive
+        archive: PathBuf,
+        /// Path of the package directory inside the archive, ending in `/`
+        prefix: String,
+    },
+}
+
+/// Aggregate size of a package's files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Total uncompressed size in bytes
+    pub bytes: u64,
+    /// Number of files
+    pub files: usize,
+}
+
+impl PackageLocation {
+    /// Reads a file relative to the package root.
+    pub fn read_to_string(&self, relative: &str) -> io::Result<String> {
+        match self {
+            PackageLocation::Directory(dir) => fs::read_to_string(dir.join(relative)),
+            PackageLocation::Zip { archive, prefix } => {
+                let mut zip = open_zip(archive)?;
+                let mut file = zip
+                    .by_name(&format!("{}{}", prefix, relative))
+                    .map_err(zip_error)?;
+                let mut content = String::new();
+                file.read_to_string(&mut content)?;
+                Ok(content)
+            }
+        }
+    }
+
+    /// Lists the package's files as `/`-separated paths relative to its
+    /// root, sorted.
+    pub fn files(&self) -> io::Result<Vec<String>> {
+        let mut files = Vec::new();
+        match self {
+            PackageLocation::Directory(dir) => {
+                // Deep trees exceed MAX_PATH on Windows
+                let dir = long_path(dir);
+                for entry in WalkDir::new(&dir).into_iter().filter_map(|e| e.ok()) {
+                    if entry.file_type().is_file() {
+                        if let Ok(relative) = entry.path().strip_prefix(&dir) {
+                            files.push(relative.to_string_lossy().replace('\\', "/"));
+                        }
+                    }
+                }
+            }
+            PackageLocation::Zip { archive, prefix } => {
+                let mut zip = open_zip(archive)?;
+                for i in 0..zip.len() {
+                    let file = zip.by_index_raw(i).map_err(zip_error)?;
+                    if let Some(relative) = file.name().strip_prefix(prefix.as_str()) {
+                        if file.is_file() {
+                            files.push(relative.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Lists the files directly in the package root, sorted.
+    ///
+    /// Unlike [`PackageLocation::files`] this does not walk subdirectories.
+    pub fn root_files(&self) -> io::Result<Vec<String>> {
+        let mut files = Vec::new();
+        match self {
+            PackageLocation::Directory(dir) => {
+                for entry in fs::read_dir(dir)? {
+                    let entry = entry?;
+                    if entry.file_type()?.is_file() {
+                        files.push(entry.file_name().to_string_lossy().into_owned());
+                    }
+                }
+            }
+            PackageLocation::Zip { archive, prefix } => {
+                let mut zip = open_zip(archive)?;
+                for i in 0..zip.len() {
+                    let file = zip.by_index_raw(i).map_err(zip_error)?;
+                    if let Some(relative) = file.name().strip_prefix(prefix.as_str()) {
+                        if file.is_file() && !relative.contains('/') {
+                            files.push(relative.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Computes the unpacked size and file count of the package.
+    ///
+    /// Sizes inside archives are the uncompressed sizes recorded in the zip
+    /// directory, so both install styles report comparable numbers.
+    pub fn disk_usage(&self) -> io::Result<DiskUsage> {
+        let mut usage = DiskUsage::default();
+        match self {
+            PackageLocation::Directory(dir) => {
+                for entry in WalkDir::new(long_path(dir)).into_iter().filter_map(|e| e.ok()) {
+                    if entry.file_type().is_file() {
+                        usage.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                        usage.files += 1;
+                    }
+                }
+            }
+            PackageLocation::Zip { archive, prefix } => {
+                let mut zip = open_zip(archive)?;
+                for i in 0..zip.len() {
+                    let file = zip.by_index_raw(i).map_err(zip_error)?;
+                    if file.is_file() && file.name().starts_with(prefix.as_str()) {
+                        usage.bytes += file.size();
+                        usage.files += 1;
+                    }
+                }
+            }
+        }
+        Ok(usage)
+    }
+}
+
+/// Most links followed before giving up, as Linux does for path lookups.
+pub const MAX_LINK_DEPTH: usize = 40;
+
+/// Follows `path` through symbolic links to the file or directory they
+/// point to.
+///
+/// Relative link targets are resolved against the link's own folder, so
+/// `node_modules/react -> .pnpm/react@18.2.0/node_modules/react` resolves
+/// into the virtual store. Paths that are not links are returned as they
+/// are.
+///
+/// # Errors
+///
+/// Returns an error if a link cannot be read, if the links form a cycle
+/// or if more than [`MAX_LINK_DEPTH`] links are chained. A dangling link
+/// resolves to its missing target; check that it exists.
+pub fn resolve_links(path: &Path) -> io::Result<PathBuf> {
+    let mut current = path.to_path_buf();
+    let mut seen = HashSet::new();
+    for _ in 0..MAX_LINK_DEPTH {
+        match fs::symlink_metadata(&current) {
+            Ok(meta) if meta.file_type().is_symlink() => {}
+            _ => return Ok(current),
+        }
+        if !seen.insert(current.clone()) {
+            return Err(link_loop(path));
+        }
+        let target = fs::read_link(&current)?;
+        current = match current.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+    }
+    Err(link_loop(path))
+}
+
+fn link_loop(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("symbolic link cycle at {}", path.display()),
+    )
+}
+
+fn open_zip(archive: &Path) -> io::Result<zip::ZipArchive<File>> {
+    zip::ZipArchive::new(File::open(archive)?).map_err(zip_error)
+}
+
+fn zip_error(e: zip::result::ZipError) -> io::Error {
+    match e {
+        zip::result::ZipError::Io(e) => e,
+        zip::result::ZipError::FileNotFound => io::Error::new(io::ErrorKind::NotFound, e),
+        other => io::Error::new(io::ErrorKind::InvalidData, other),
+    }
+}
+
+/// How a project's dependencies are installed.
+#[derive(Debug, Clone)]
+pub enum PackageStore {
+    /// Classic install under `<root>/node_modules`
+    NodeModules(PathBuf),
+    /// Yarn Plug'n'Play install
+    Pnp(PnpManifest),
+}
+
+impl PackageStore {
+    /// Detects the install style of a project.
+    ///
+    /// A PnP manifest takes precedence: Yarn may leave a stale
+    /// `node_modules` folder behind after switching linkers.
+    pub fn detect(project_dir: &Path) -> ParseResult<Self> {
+        Ok(match PnpManifest::load(project_dir)? {
+            Some(manifest) => PackageStore::Pnp(manifest),
+            None => PackageStore::NodeModules(project_dir.join("node_modules")),
+        })
+    }
+
+    /// Returns true for Plug'n'Play installs.
+    pub fn is_pnp(&self) -> bool {
+        matches!(self, PackageStore::Pnp(_))
+    }
+
+    /// Locates an installed package by name.
+    ///
+    /// For PnP installs with several versions of a package, `version`
+    /// selects the instance; without it the first registry instance wins.
+    /// Returns `None` if the package is not installed.
+    pub fn locate(&self, name: &str, version: Option<&str>) -> Option<PackageLocation> {
+        match self {
+            PackageStore::NodeModules(root) => {
+                // pnpm and linked workspaces install packages as links
+                let dir = resolve_links(&root.join(name)).ok()?;
+                dir.join("package.json")
+                    .is_file()
+                    .then_some(PackageLocation::Directory(dir))
+            }
+            PackageStore::Pnp(manifest) => {
+                let mut instances = manifest.instances(name);
+                let package = match version {
+                    Some(version) => instances.find(|p| p.version() == Some(version)),
+                    None => instances.next(),
+                }?;
+                Some(manifest.location(package))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codescope-installed-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_node_modules_store() {
+        let dir = temp_dir("nm");
+        let pkg = dir.join("node_modules/@scope/pkg");
+        fs::create_dir_all(pkg.join("lib")).unwrap();
+        fs::write(pkg.join("package.json"), r#"{"name":"@scope/pkg"}"#).unwrap();
+        fs::write(pkg.join("lib/index.js"), "module.exports = 1;\n").unwrap();
+
+        let store = PackageStore::detect(&dir).unwrap();
+        assert!(!store.is_pnp());
+        assert!(store.locate("missing", None).is_none());
+
+        let location = store.locate("@scope/pkg", None).unwrap();
+        assert!(location.read_to_string("package.json").unwrap().contains("@scope/pkg"));
+        assert_eq!(
+            location.disk_usage().unwrap(),
+            DiskUsage { bytes: 21 + 20, files: 2 }
+        );
+        assert_eq!(location.files().unwrap(), vec!["lib/index.js", "package.json"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_node_modules_store_follows_pnpm_links() {
+        use std::os::unix::fs::symlink;
+
+        let dir = temp_dir("pnpm");
+        let real = dir.join("node_modules/.pnpm/react@18.2.0/node_modules/react");
+        fs::create_dir_all(&real).unwrap();
+        fs::write(real.join("package.json"), r#"{"name":"react","version":"18.2.0"}"#).unwrap();
+        // Dependencies are linked next to the package, back into the store
+        symlink("../../loose-envify@1.4.0/node_modules/loose-envify", real.parent().unwrap().join("loose-envify")).unwrap();
+        symlink(".pnpm/react@18.2.0/node_modules/react", dir.join("node_modules/react")).unwrap();
+        symlink("b", dir.join("node_modules/a")).unwrap();
+        symlink("a", dir.join("node_modules/b")).unwrap();
+
+        let store = PackageStore::detect(&dir).unwrap();
+        let location = store.locate("react", None).unwrap();
+        assert_eq!(
+            location,
+            PackageLocation::Directory(dir.join("node_modules/.pnpm/react@18.2.0/node_modules/react"))
+        );
+        assert_eq!(location.disk_usage().unwrap().files, 1);
+        assert!(store.locate("a", None).is_none());
+        assert_eq!(
+            resolve_links(&dir.join("node_modules/a")).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_disk_usage_beyond_max_path() {
+        let dir = temp_dir("long");
+        let root = dir.join("node_modules/deep");
+        let mut nested = root.clone();
+        while nested.as_os_str().len() < crate::parser::paths::MAX_PATH + 40 {
+            nested = nested.join("node_modules").join("package-with-a-long-name");
+        }
+        fs::create_dir_all(long_path(&nested)).unwrap();
+        fs::write(long_path(&nested.join("index.js")), "module.exports = 1;\n").unwrap();
+
+        let location = PackageLocation::Directory(root);
+        assert_eq!(location.disk_usage().unwrap(), DiskUsage { bytes: 20, files: 1 });
+        assert_eq!(location.files().unwrap().len(), 1);
+        assert!(location.files().unwrap()[0].ends_with("package-with-a-long-name/index.js"));
+
+        let _ = fs::remove_dir_all(long_path(&dir));
+    }
+
+    #[test]
+    fn test_pnp_store_reads_from_zip() {
+        let dir = temp_dir("pnp");
+        fs::create_dir_all(dir.join(".yarn/cache")).unwrap();
+        write_zip(
+            &dir.join(".yarn/cache/left-pad-npm-1.3.0-abc.zip"),
+            &[
+                ("node_modules/left-pad/package.json", r#"{"name":"left-pad","version":"1.3.0"}"#),
+                ("node_modules/left-pad/index.js", "module.exports = leftPad;\n"),
+            ],
+        );
+        fs::write(
+            dir.join(".pnp.data.json"),
+            r#"{"packageRegistryData": [
+                ["left-pad", [["npm:1.3.0", {
+                    "packageLocation": "./.yarn/cache/left-pad-npm-1.3.0-abc.zip/node_modules/left-pad/",
+                    "packageDependencies": [], "linkType": "HARD"}]]]
+            ]}"#,
+        )
+        .unwrap();
+        // A stale node_modules folder must not win over the PnP manifest
+        fs::create_dir_all(dir.join("node_modules")).unwrap();
+
+        let store = PackageStore::detect(&dir).unwrap();
+        assert!(store.is_pnp());
+        assert!(store.locate("left-pad", Some("2.0.0")).is_none());
+
+        let location = store.locate("left-pad", Some("1.3.0")).unwrap();
+        assert!(location.read_to_string("package.json").unwrap().contains("1.3.0"));
+        assert_eq!(location.read_to_string("missing.js").unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(location.disk_usage().unwrap(), DiskUsage { bytes: 37 + 26, files: 2 });
+        assert_eq!(location.files().unwrap(), vec!["index.js", "package.json"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
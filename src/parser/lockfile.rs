@@ -0,0 +1,364 @@
+//! Parser for npm `package-lock.json` files.
+//!
+//! Lockfile versions 2 and 3 record the resolved install tree as a flat
+//! `packages` map keyed by install path (`""` for the project root,
+//! `node_modules/a`, `node_modules/a/node_modules/b`, and workspace folders
+//! such as `packages/ui`). Version 1 lockfiles, written by npm 6, only have
+//! the legacy nested `dependencies` tree and are not supported.
+//!
+//! Entries that cannot be read, typically after a hand edit, are skipped
+//! and listed in [`PackageLock::warnings`] instead of failing the parse.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::package_json::{ParseError, ParseResult};
+use super::recovery::{recover_map, ParseWarning, SyntaxError};
+
+/// File name of the npm lockfile.
+pub const PACKAGE_LOCK: &str = "package-lock.json";
+
+/// A single entry of the lockfile `packages` map.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedPackage {
+    /// Package name, present when it differs from the install path (aliases)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Resolved version
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Tarball URL, or the link target for `link` entries
+    #[serde(default)]
+    pub resolved: Option<String>,
+
+    /// Whether this entry is a symlink to `resolved` (workspaces, `file:`)
+    #[serde(default)]
+    pub link: bool,
+
+    /// Only required by development dependencies
+    #[serde(default)]
+    pub dev: bool,
+
+    /// Only required by optional dependencies
+    #[serde(default)]
+    pub optional: bool,
+
+    /// Present on disk but not required by anything
+    #[serde(default)]
+    pub extraneous: bool,
+
+    /// Production dependencies
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+
+    /// Development dependencies (only installed for the root and workspaces)
+    #[serde(default)]
+    pub dev_dependencies: HashMap<String, String>,
+
+    /// Optional dependencies
+    #[serde(default)]
+    pub optional_dependencies: HashMap<String, String>,
+
+    /// Peer dependencies (installed automatically since npm 7)
+    #[serde(default)]
+    pub peer_dependencies: HashMap<String, String>,
+
+    /// Operating systems the package is restricted to (`!` excludes one)
+    #[serde(default)]
+    pub os: Vec<String>,
+
+    /// CPU architectures the package is restricted to (`!` excludes one)
+    #[serde(default)]
+    pub cpu: Vec<String>,
+}
+
+/// A parsed `package-lock.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLock {
+    /// Lockfile format version
+    #[serde(default)]
+    pub lockfile_version: u32,
+
+    /// Install tree keyed by path relative to the project root
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockedPackage>,
+
+    /// Entries of `packages` that were skipped because they could not be read
+    #[serde(skip)]
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl PackageLock {
+    /// Loads the lockfile of a project, or `None` if there is none.
+    pub fn load(project_dir: &Path) -> ParseResult<Option<Self>> {
+        let path = project_dir.join(PACKAGE_LOCK);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Self::parse(&fs::read_to_string(path)?).map(Some)
+    }
+
+    /// Parses the contents of a `package-lock.json`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::lockfile::PackageLock;
+    ///
+    /// let lock = PackageLock::parse(r#"{
+    ///   "lockfileVersion": 3,
+    ///   "packages": {
+    ///     "": { "dependencies": { "react": "^18.2.0" } },
+    ///     "node_modules/react": { "version": "18.2.0" }
+    ///   }
+    /// }"#).unwrap();
+    /// assert_eq!(lock.resolve("", "react"), Some("node_modules/react"));
+    /// ```
+    pub fn parse(content: &str) -> ParseResult<Self> {
+        let value: Value = serde_json::from_str(content).map_err(|e| SyntaxError::from_json(content, &e))?;
+        let Value::Object(mut fields) = value else {
+            return Err(ParseError::InvalidLockfile("expected a JSON object".to_string()));
+        };
+        let lockfile_version = fields
+            .get("lockfileVersion")
+            .and_then(Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(0);
+
+        let mut warnings = Vec::new();
+        let packages: BTreeMap<String, LockedPackage> =
+            recover_map(fields.remove("packages"), "packages", &mut warnings).into_iter().collect();
+        if lockfile_version < 2 || packages.is_empty() {
+            return Err(ParseError::InvalidLockfile(format!(
+                "lockfileVersion {} has no packages map; regenerate it with npm 7 or later",
+                lockfile_version
+            )));
+        }
+        Ok(Self {
+            lockfile_version,
+            packages,
+            warnings,
+        })
+    }
+
+    /// Resolves `name` as required from the package at install path `from`,
+    /// following Node's lookup through enclosing `node_modules` folders.
+    ///
+    /// Returns the install path of the package that would be loaded.
+    pub fn resolve(&self, from: &str, name: &str) -> Option<&str> {
+        let mut dir = from;
+        loop {
+            let candidate = if dir.is_empty() {
+                format!("node_modules/{}", name)
+            } else {
+                format!("{}/node_modules/{}", dir, name)
+            };
+            if let Some((path, _)) = self.packages.get_key_value(&candidate) {
+                return Some(path);
+            }
+            if dir.is_empty() {
+                return None;
+            }
+            dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+        }
+    }
+
+    /// Returns the install paths reachable from the project root and its
+    /// workspaces.
+    ///
+    /// Development dependencies are followed for the root and workspace
+    /// folders only, since npm never installs those of other packages.
+    /// Link entries count as required together with their target.
+    pub fn required_paths(&self) -> HashSet<&str> {
+        let mut required = HashSet::new();
+        let mut stack: Vec<&str> = self
+            .packages
+            .keys()
+            .filter(|path| !is_installed_path(path))
+            .map(String::as_str)
+            .collect();
+
+        while let Some(path) = stack.pop() {
+            if !required.insert(path) {
+                continue;
+            }
+            let Some(entry) = self.packages.get(path) else {
+                continue;
+            };
+
+            if entry.link {
+                if let Some((target, _)) = entry
+                    .resolved
+                    .as_ref()
+                    .and_then(|target| self.packages.get_key_value(target))
+                {
+                    stack.push(target);
+                }
+                continue;
+            }
+
+            let dev = (!is_installed_path(path)).then_some(&entry.dev_dependencies);
+            let names = entry
+                .dependencies
+                .keys()
+                .chain(entry.optional_dependencies.keys())
+                .chain(entry.peer_dependencies.keys())
+                .chain(dev.into_iter().flat_map(|deps| deps.keys()));
+            for name in names {
+                if let Some(dep) = self.resolve(path, name) {
+                    stack.push(dep);
+                }
+            }
+        }
+
+        required
+    }
+}
+
+/// Returns the name a package is installed under, derived from its install
+/// path (`node_modules/a/node_modules/@s/b` is `@s/b`).
+///
+/// For `npm:` aliases this is the alias, not the real package name, which
+/// is recorded in [`LockedPackage::name`].
+pub fn install_name(path: &str) -> &str {
+    let start = path.rfind("node_modules/").map_or(0, |i| i + "node_modules/".len());
+    &path[start..]
+}
+
+/// Returns true for paths inside a `node_modules` folder, as opposed to the
+/// project root and workspace folders.
+pub fn is_installed_path(path: &str) -> bool {
+    path.starts_with("node_modules/") || path.contains("/node_modules/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK: &str = r#"{
+  "name": "my-app",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": {
+      "name": "my-app",
+      "workspaces": ["packages/ui"],
+      "dependencies": { "react": "^18.2.0", "ui": "*" },
+      "devDependencies": { "typescript": "^5.0.0" }
+    },
+    "node_modules/react": {
+      "version": "18.2.0",
+      "dependencies": { "loose-envify": "^1.1.0" }
+    },
+    "node_modules/loose-envify": {
+      "version": "1.4.0",
+      "dependencies": { "js-tokens": "^4.0.0" },
+      "devDependencies": { "tap": "^16.0.0" }
+    },
+    "node_modules/loose-envify/node_modules/js-tokens": { "version": "4.0.0" },
+    "node_modules/js-tokens": { "version": "3.0.2", "extraneous": true },
+    "node_modules/typescript": { "version": "5.3.3", "dev": true },
+    "node_modules/ui": { "resolved": "packages/ui", "link": true },
+    "packages/ui": {
+      "name": "ui",
+      "version": "1.0.0",
+      "devDependencies": { "left-pad": "^1.3.0" }
+    },
+    "node_modules/left-pad": { "version": "1.3.0", "dev": true },
+    "node_modules/tap": { "version": "16.3.0", "dev": true }
+  }
+}"#;
+
+    #[test]
+    fn test_parse() {
+        let lock = PackageLock::parse(LOCK).unwrap();
+        assert_eq!(lock.lockfile_version, 3);
+        assert_eq!(lock.packages.len(), 10);
+
+        let ui = &lock.packages["node_modules/ui"];
+        assert!(ui.link);
+        assert_eq!(ui.resolved.as_deref(), Some("packages/ui"));
+        assert!(lock.packages["node_modules/js-tokens"].extraneous);
+    }
+
+    #[test]
+    fn test_resolve_prefers_nested_copy() {
+        let lock = PackageLock::parse(LOCK).unwrap();
+        assert_eq!(
+            lock.resolve("node_modules/loose-envify", "js-tokens"),
+            Some("node_modules/loose-envify/node_modules/js-tokens")
+        );
+        assert_eq!(lock.resolve("node_modules/react", "js-tokens"), Some("node_modules/js-tokens"));
+        assert_eq!(lock.resolve("packages/ui", "left-pad"), Some("node_modules/left-pad"));
+        assert_eq!(lock.resolve("", "missing"), None);
+    }
+
+    #[test]
+    fn test_required_paths() {
+        let lock = PackageLock::parse(LOCK).unwrap();
+        let required = lock.required_paths();
+
+        for path in [
+            "",
+            "node_modules/react",
+            "node_modules/loose-envify",
+            "node_modules/loose-envify/node_modules/js-tokens",
+            "node_modules/typescript",
+            "node_modules/ui",
+            "packages/ui",
+            "node_modules/left-pad",
+        ] {
+            assert!(required.contains(path), "{} should be required", path);
+        }
+        assert!(!required.contains("node_modules/js-tokens"));
+        // devDependencies of installed packages are never installed
+        assert!(!required.contains("node_modules/tap"));
+    }
+
+    #[test]
+    fn test_install_name() {
+        assert_eq!(install_name("node_modules/react"), "react");
+        assert_eq!(install_name("node_modules/a/node_modules/@scope/b"), "@scope/b");
+        assert!(!is_installed_path("packages/ui"));
+        assert!(is_installed_path("packages/ui/node_modules/x"));
+    }
+
+    #[test]
+    fn test_v1_lockfile_is_rejected() {
+        let err = PackageLock::parse(r#"{"lockfileVersion": 1, "dependencies": {}}"#).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidLockfile(_)));
+    }
+
+    #[test]
+    fn test_hand_edited_entries_are_skipped() {
+        let lock = PackageLock::parse(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "react": "^18.2.0", "left-pad": "^1.3.0" } },
+    "node_modules/react": { "version": "18.2.0" },
+    "node_modules/left-pad": { "version": "1.3.0", "dependencies": ["oops"] }
+  }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(lock.packages.len(), 2);
+        assert_eq!(lock.warnings.len(), 1);
+        assert_eq!(lock.warnings[0].location, "packages[\"node_modules/left-pad\"]");
+        assert_eq!(lock.resolve("", "left-pad"), None);
+
+        let err = PackageLock::parse("{\n  \"lockfileVersion\": 3,\n  \"packages\": {\n}").unwrap_err();
+        match err {
+            ParseError::Syntax(err) => assert_eq!((err.line, err.offset), (4, 42)),
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
+    }
+}
@@ -6,6 +6,12 @@
 //! # Supported Formats
 //!
 //! - **package.json** (npm/Node.js) - Fully supported
+//! - **package-lock.json** (npm 7+) - Resolved install tree
+//! - **.pnp.cjs / .pnp.data.json** (Yarn Plug'n'Play) - Package locations
+//! - **workspaces** (npm/Yarn/pnpm monorepos) - Workspace packages
+//! - **pnpm virtual store** (`node_modules/.pnpm`) - Package paths
+//! - **browserslist** (package.json field or `.browserslistrc`) - Browser targets
+//! - **SPDX license expressions** (package.json `license` field)
 //! - **Cargo.toml** (Rust) - Planned
 //! - **go.mod** (Go) - Planned
 //! - **pyproject.toml** (Python) - Planned
@@ -30,8 +36,19 @@
 //! println!("Found {} production dependencies", prod_deps.len());
 //! ```
 
+pub mod browserslist;
+pub mod installed;
+pub mod lockfile;
 pub mod package_json;
+pub mod paths;
+pub mod pnp;
+pub mod pnpm;
+pub mod range;
+pub mod recovery;
+pub mod source;
+pub mod spdx;
 pub mod types;
+pub mod workspace;
 
 // Re-export commonly used types for convenience
 pub use package_json::{
@@ -39,4 +56,13 @@ pub use package_json::{
     validate, ParseError, ParseResult,
 };
 
-pub use types::{Dependency, DependencyType, PackageJson};
+pub use browserslist::{BrowserTargets, BrowserVersion};
+pub use installed::{DiskUsage, PackageLocation, PackageStore};
+pub use lockfile::PackageLock;
+pub use pnp::PnpManifest;
+pub use range::VersionRange;
+pub use recovery::{ParseWarning, SyntaxError};
+pub use source::DependencySource;
+pub use spdx::{LicenseExpression, LicenseTerm};
+pub use types::{BundledDependencies, Dependency, DependencyType, PackageJson, Scope, Workspaces};
+pub use workspace::{Workspace, WorkspaceMember};
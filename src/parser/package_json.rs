@@ -6,6 +6,7 @@
 use std::fs;
 use std::path::Path;
 
+use super::recovery::SyntaxError;
 use super::types::{Dependency, DependencyType, PackageJson};
 
 /// Errors that can occur during package.json parsing.
@@ -19,9 +20,21 @@ pub enum ParseError {
     #[error("Failed to parse JSON: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    /// The JSON is malformed or has the wrong shape at a known position.
+    #[error("Failed to parse JSON: {0}")]
+    Syntax(#[from] SyntaxError),
+
     /// The package.json structure is invalid or missing required fields.
     #[error("Invalid package.json: {0}")]
     InvalidPackage(String),
+
+    /// The Yarn Plug'n'Play manifest could not be interpreted.
+    #[error("Invalid Yarn PnP manifest: {0}")]
+    InvalidPnp(String),
+
+    /// The npm lockfile could not be interpreted.
+    #[error("Invalid package-lock.json: {0}")]
+    InvalidLockfile(String),
 }
 
 /// Result type alias for parser operations.
@@ -71,7 +84,7 @@ pub fn parse_file(path: &Path) -> ParseResult<PackageJson> {
 /// assert_eq!(pkg.name, Some("my-app".to_string()));
 /// ```
 pub fn parse_str(content: &str) -> ParseResult<PackageJson> {
-    let pkg: PackageJson = serde_json::from_str(content)?;
+    let pkg: PackageJson = serde_json::from_str(content).map_err(|e| SyntaxError::from_json(content, &e))?;
     Ok(pkg)
 }
 
@@ -274,7 +287,10 @@ mod tests {
         let result = parse_str(json);
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), ParseError::JsonError(_)));
+        match result.unwrap_err() {
+            ParseError::Syntax(err) => assert_eq!((err.line, err.offset), (1, 2)),
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
     }
 
     #[test]
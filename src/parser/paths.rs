@@ -0,0 +1,150 @@
+//! Path handling that works the same for files written on Windows.
+//!
+//! Stats files and metafiles produced on Windows record module paths with
+//! backslashes (`.\node_modules\react\index.js`), while everything that
+//! reads package names from paths expects `/`. Parsers pass the paths
+//! they read through [`to_slash`].
+//!
+//! Deep `node_modules` trees also exceed the 260 character `MAX_PATH`
+//! limit of the classic Windows file APIs. The disk scanner turns the
+//! folders it walks into their `\\?\` form with [`long_path`], so the
+//! paths below them may be of any length; elsewhere it returns the path
+//! unchanged.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Longest path the classic Windows file APIs accept, including the
+/// terminating NUL.
+pub const MAX_PATH: usize = 260;
+
+/// Replaces backslash separators with `/`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::paths::to_slash;
+///
+/// assert_eq!(to_slash(r".\node_modules\@babel\core\lib\index.js"), "./node_modules/@babel/core/lib/index.js");
+/// assert_eq!(to_slash("./src/index.js"), "./src/index.js");
+/// ```
+pub fn to_slash(path: &str) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Replaces backslash separators with `/` in place, for paths stored in
+/// parsed structures.
+pub fn normalize_separators(path: &mut String) {
+    if path.contains('\\') {
+        *path = path.replace('\\', "/");
+    }
+}
+
+/// Returns a form of `path` that Windows file APIs accept, along with
+/// every path below it, beyond [`MAX_PATH`].
+///
+/// On Windows the path is made absolute and given the `\\?\` prefix; on
+/// other platforms, or if the path cannot be converted, it is returned as
+/// it is.
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        let verbatim = std::path::absolute(path)
+            .ok()
+            .and_then(|absolute| absolute.to_str().and_then(verbatim_path));
+        if let Some(verbatim) = verbatim {
+            return PathBuf::from(verbatim);
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Converts an absolute Windows path into its verbatim `\\?\` form, or
+/// returns `None` for relative and already verbatim paths.
+///
+/// Verbatim paths skip the normalization Windows otherwise applies, so
+/// separators are turned into `\` and `.` and `..` segments are resolved
+/// here.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::paths::verbatim_path;
+///
+/// assert_eq!(verbatim_path(r"C:\app\.\node_modules\a").as_deref(), Some(r"\\?\C:\app\node_modules\a"));
+/// assert_eq!(verbatim_path(r"node_modules\a"), None);
+/// ```
+pub fn verbatim_path(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") {
+        return None;
+    }
+
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(share) = path.strip_prefix(r"\\") {
+        // \\server\share\... becomes \\?\UNC\server\share\...
+        (r"\\?\UNC\".to_string(), share.to_string())
+    } else {
+        let bytes = path.as_bytes();
+        let is_drive = bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+        if !is_drive {
+            return None;
+        }
+        (format!(r"\\?\{}\", &path[..2]), path[3..].to_string())
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split('\\') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(format!("{}{}", prefix, segments.join("\\")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deep(root: &str) -> String {
+        format!(r"{}\{}index.js", root, r"node_modules\package-with-a-long-name\".repeat(8))
+    }
+
+    #[test]
+    fn test_verbatim_drive_path() {
+        let path = deep(r"C:\Users\dev\app\.\src\..");
+        assert!(path.len() >= MAX_PATH);
+        let verbatim = verbatim_path(&path).unwrap();
+        assert!(verbatim.starts_with(r"\\?\C:\Users\dev\app\node_modules\package-with-a-long-name\"));
+        assert!(!verbatim.contains(r"\.\") && !verbatim.contains(r"\..\"));
+        assert_eq!(verbatim_path(&verbatim), None, "already verbatim");
+        assert_eq!(verbatim_path(&path.replace('\\', "/")), Some(verbatim));
+    }
+
+    #[test]
+    fn test_verbatim_unc_and_relative_paths() {
+        let verbatim = verbatim_path(&deep(r"\\build-server\share\app")).unwrap();
+        assert!(verbatim.starts_with(r"\\?\UNC\build-server\share\app\node_modules\"));
+        assert_eq!(verbatim_path(&deep(r"app")), None, "relative paths cannot be verbatim");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_long_path_is_unchanged_elsewhere() {
+        assert_eq!(long_path(Path::new("node_modules/react")), PathBuf::from("node_modules/react"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_path_on_windows() {
+        let path = PathBuf::from(deep(r"C:\app"));
+        assert!(long_path(&path).to_string_lossy().starts_with(r"\\?\C:\app\"));
+        assert!(long_path(Path::new("node_modules")).to_string_lossy().starts_with(r"\\?\"));
+    }
+}
@@ -0,0 +1,343 @@
+//! Parser for Yarn Plug'n'Play manifests.
+//!
+//! Projects installed with Yarn PnP have no `node_modules`; instead Yarn
+//! writes a resolution map to `.pnp.cjs` (embedded as a JSON string) or to
+//! `.pnp.data.json` when inlining is disabled. Each entry records where a
+//! package lives, usually inside a zip archive in the Yarn cache:
+//!
+//! ```text
+//! ./.yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip/node_modules/lodash/
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::installed::PackageLocation;
+use super::package_json::{ParseError, ParseResult};
+use super::recovery::SyntaxError;
+
+/// File name of the PnP runtime with the inlined resolution map.
+pub const PNP_CJS: &str = ".pnp.cjs";
+
+/// File name of the standalone resolution map.
+pub const PNP_DATA_JSON: &str = ".pnp.data.json";
+
+/// How a package is linked into the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// Package files are owned by Yarn (cache archives, unplugged folders)
+    Hard,
+    /// Package points at a folder Yarn does not own (workspaces, `portal:`)
+    Soft,
+}
+
+/// A single package instance from the resolution map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PnpPackage {
+    /// Package name
+    pub name: String,
+    /// Yarn locator reference (e.g. `npm:4.17.21`, `workspace:packages/ui`)
+    pub reference: String,
+    /// Location relative to the manifest directory, as written by Yarn
+    pub location: String,
+    /// Link type
+    pub link_type: LinkType,
+}
+
+impl PnpPackage {
+    /// Returns the resolved version for registry packages.
+    ///
+    /// Virtual references (`virtual:<hash>#npm:1.2.3`) resolve to the
+    /// version of the package they wrap.
+    pub fn version(&self) -> Option<&str> {
+        let reference = self
+            .reference
+            .rsplit_once('#')
+            .map_or(self.reference.as_str(), |(_, inner)| inner);
+        reference.strip_prefix("npm:")
+    }
+
+    /// Returns true for virtual instances created for peer dependencies.
+    pub fn is_virtual(&self) -> bool {
+        self.reference.starts_with("virtual:")
+    }
+
+    /// Returns true for workspace packages.
+    pub fn is_workspace(&self) -> bool {
+        self.reference.starts_with("workspace:")
+    }
+}
+
+/// A parsed Yarn PnP resolution map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PnpManifest {
+    /// Directory the manifest was found in; locations are relative to it
+    pub root: PathBuf,
+    /// Every package instance, excluding the anonymous top-level locator
+    pub packages: Vec<PnpPackage>,
+}
+
+impl PnpManifest {
+    /// Returns the PnP manifest file in a project directory, if any.
+    ///
+    /// `.pnp.data.json` is preferred because it avoids unescaping the
+    /// JavaScript string literal in `.pnp.cjs`.
+    pub fn find(project_dir: &Path) -> Option<PathBuf> {
+        [PNP_DATA_JSON, PNP_CJS]
+            .iter()
+            .map(|name| project_dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Loads the PnP manifest of a project, or `None` if the project does
+    /// not use Plug'n'Play.
+    pub fn load(project_dir: &Path) -> ParseResult<Option<Self>> {
+        let Some(path) = Self::find(project_dir) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)?;
+        let manifest = if path.ends_with(PNP_CJS) {
+            Self::parse_cjs(&content, project_dir)?
+        } else {
+            Self::parse_data(&content, project_dir)?
+        };
+        Ok(Some(manifest))
+    }
+
+    /// Parses the contents of `.pnp.data.json`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use codescope::parser::pnp::PnpManifest;
+    ///
+    /// let data = r#"{"packageRegistryData": [
+    ///   ["lodash", [["npm:4.17.21", {
+    ///     "packageLocation": "./.yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip/node_modules/lodash/",
+    ///     "packageDependencies": [],
+    ///     "linkType": "HARD"
+    ///   }]]]
+    /// ]}"#;
+    /// let manifest = PnpManifest::parse_data(data, Path::new(".")).unwrap();
+    /// assert_eq!(manifest.packages[0].version(), Some("4.17.21"));
+    /// ```
+    pub fn parse_data(content: &str, root: &Path) -> ParseResult<Self> {
+        let data: Value = serde_json::from_str(content).map_err(|e| SyntaxError::from_json(content, &e))?;
+        let registry = data
+            .get("packageRegistryData")
+            .and_then(Value::as_array)
+            .ok_or_else(|| ParseError::InvalidPnp("missing packageRegistryData".to_string()))?;
+
+        let mut packages = Vec::new();
+        for entry in registry {
+            // [name | null, [[reference | null, info], ...]]
+            let Some(name) = entry.get(0).and_then(Value::as_str) else {
+                continue;
+            };
+            let Some(instances) = entry.get(1).and_then(Value::as_array) else {
+                continue;
+            };
+            for instance in instances {
+                let reference = instance.get(0).and_then(Value::as_str);
+                let info = instance.get(1);
+                let location = info
+                    .and_then(|i| i.get("packageLocation"))
+                    .and_then(Value::as_str);
+                let (Some(reference), Some(location)) = (reference, location) else {
+                    continue;
+                };
+                let link_type = match info.and_then(|i| i.get("linkType")).and_then(Value::as_str) {
+                    Some("SOFT") => LinkType::Soft,
+                    _ => LinkType::Hard,
+                };
+                packages.push(PnpPackage {
+                    name: name.to_string(),
+                    reference: reference.to_string(),
+                    location: location.to_string(),
+                    link_type,
+                });
+            }
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            packages,
+        })
+    }
+
+    /// Parses a `.pnp.cjs` runtime by extracting its `RAW_RUNTIME_STATE`.
+    pub fn parse_cjs(content: &str, root: &Path) -> ParseResult<Self> {
+        let state = extract_runtime_state(content)
+            .ok_or_else(|| ParseError::InvalidPnp("RAW_RUNTIME_STATE not found".to_string()))?;
+        Self::parse_data(&state, root)
+    }
+
+    /// Returns every instance of a package, virtual ones excluded.
+    pub fn instances<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a PnpPackage> + 'a {
+        self.packages
+            .iter()
+            .filter(move |p| p.name == name && !p.is_virtual())
+    }
+
+    /// Returns where an instance's files live on disk.
+    pub fn location(&self, package: &PnpPackage) -> PackageLocation {
+        let location = package
+            .location
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        match location.split_once(".zip/") {
+            Some((archive, inner)) => PackageLocation::Zip {
+                archive: self.root.join(format!("{}.zip", archive)),
+                prefix: format!("{}/", inner),
+            },
+            None => PackageLocation::Directory(self.root.join(location)),
+        }
+    }
+}
+
+/// Extracts the JSON resolution map from the `RAW_RUNTIME_STATE` string
+/// literal in `.pnp.cjs`, undoing JavaScript string escapes.
+fn extract_runtime_state(content: &str) -> Option<String> {
+    let start = content.find("RAW_RUNTIME_STATE")?;
+    let rest = &content[start..];
+    let open = rest.find(['\'', '"'])?;
+    let quote = rest[open..].chars().next()?;
+
+    let mut out = String::new();
+    let mut chars = rest[open + 1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                // Line continuation
+                '\n' => {}
+                '\r' => {
+                    let mut lookahead = chars.clone();
+                    if lookahead.next() == Some('\n') {
+                        chars = lookahead;
+                    }
+                }
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            },
+            c if c == quote => return Some(out),
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = r#"{
+  "__info": ["This file is automatically generated. Do not touch it, or risk", "your modifications being lost."],
+  "dependencyTreeRoots": [{"name": "my-app", "reference": "workspace:."}],
+  "packageRegistryData": [
+    [null, [[null, {"packageLocation": "./", "packageDependencies": [["react", "npm:18.2.0"]], "linkType": "SOFT"}]]],
+    ["my-app", [["workspace:.", {"packageLocation": "./", "packageDependencies": [], "linkType": "SOFT"}]]],
+    ["react", [
+      ["npm:18.2.0", {"packageLocation": "./.yarn/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip/node_modules/react/", "packageDependencies": [], "linkType": "HARD"}],
+      ["virtual:abc123#npm:18.2.0", {"packageLocation": "./.yarn/__virtual__/react-virtual-abc123/0/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip/node_modules/react/", "packageDependencies": [], "linkType": "HARD"}]
+    ]],
+    ["esbuild", [["npm:0.19.0", {"packageLocation": "./.yarn/unplugged/esbuild-npm-0.19.0-ab12/node_modules/esbuild/", "packageDependencies": [], "linkType": "HARD"}]]]
+  ]
+}"#;
+
+    #[test]
+    fn test_parse_data() {
+        let manifest = PnpManifest::parse_data(DATA, Path::new("/app")).unwrap();
+        assert_eq!(manifest.packages.len(), 4);
+
+        let react: Vec<_> = manifest.instances("react").collect();
+        assert_eq!(react.len(), 1);
+        assert_eq!(react[0].version(), Some("18.2.0"));
+        assert_eq!(react[0].link_type, LinkType::Hard);
+
+        let app = manifest.instances("my-app").next().unwrap();
+        assert!(app.is_workspace());
+        assert_eq!(app.link_type, LinkType::Soft);
+        assert_eq!(app.version(), None);
+    }
+
+    #[test]
+    fn test_virtual_version() {
+        let pkg = PnpPackage {
+            name: "react".to_string(),
+            reference: "virtual:abc123#npm:18.2.0".to_string(),
+            location: String::new(),
+            link_type: LinkType::Hard,
+        };
+        assert!(pkg.is_virtual());
+        assert_eq!(pkg.version(), Some("18.2.0"));
+    }
+
+    #[test]
+    fn test_locations() {
+        let manifest = PnpManifest::parse_data(DATA, Path::new("/app")).unwrap();
+
+        let react = manifest.instances("react").next().unwrap();
+        assert_eq!(
+            manifest.location(react),
+            PackageLocation::Zip {
+                archive: PathBuf::from("/app/.yarn/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip"),
+                prefix: "node_modules/react/".to_string(),
+            }
+        );
+
+        let esbuild = manifest.instances("esbuild").next().unwrap();
+        assert_eq!(
+            manifest.location(esbuild),
+            PackageLocation::Directory(PathBuf::from(
+                "/app/.yarn/unplugged/esbuild-npm-0.19.0-ab12/node_modules/esbuild"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_cjs() {
+        let escaped: String = DATA
+            .lines()
+            .map(|line| line.replace('\\', "\\\\").replace('\'', "\\'"))
+            .collect::<Vec<_>>()
+            .join("\\\n");
+        let cjs = format!(
+            "#!/usr/bin/env node\n/* eslint-disable */\n\"use strict\";\n\nconst RAW_RUNTIME_STATE =\n'{}';\n\nfunction $$SETUP_STATE(hydrateRuntimeState, basePath) {{}}\n",
+            escaped
+        );
+
+        let manifest = PnpManifest::parse_cjs(&cjs, Path::new("/app")).unwrap();
+        assert_eq!(manifest, PnpManifest::parse_data(DATA, Path::new("/app")).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_manifests() {
+        assert!(matches!(
+            PnpManifest::parse_cjs("module.exports = {};", Path::new(".")),
+            Err(ParseError::InvalidPnp(_))
+        ));
+        assert!(matches!(
+            PnpManifest::parse_data("{}", Path::new(".")),
+            Err(ParseError::InvalidPnp(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_prefers_data_json() {
+        let dir = std::env::temp_dir().join(format!("codescope-pnp-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(PnpManifest::load(&dir).unwrap(), None);
+
+        fs::write(dir.join(PNP_CJS), "not a runtime").unwrap();
+        fs::write(dir.join(PNP_DATA_JSON), DATA).unwrap();
+        let manifest = PnpManifest::load(&dir).unwrap().unwrap();
+        assert_eq!(manifest.packages.len(), 4);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
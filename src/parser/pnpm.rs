@@ -0,0 +1,156 @@
+//! pnpm virtual store paths.
+//!
+//! pnpm installs every package once under `node_modules/.pnpm`, in a
+//! folder named after the package and its version, and links it into the
+//! `node_modules` folders that depend on it:
+//!
+//! ```text
+//! node_modules/react -> .pnpm/react@18.2.0/node_modules/react
+//! node_modules/.pnpm/@babel+core@7.24.0/node_modules/@babel/core
+//! ```
+//!
+//! Bundlers report the real paths, so code that reads the package name
+//! from the path after `node_modules/` has to see through the store
+//! folders first; [`normalize_store_path`] removes them.
+
+use std::borrow::Cow;
+
+/// Name of the virtual store folder inside `node_modules`.
+pub const VIRTUAL_STORE: &str = ".pnpm";
+
+/// A package folder of the virtual store, decoded from its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreEntry {
+    /// Package name, with the scope's `/` restored
+    pub name: String,
+    /// Installed version
+    pub version: String,
+}
+
+impl StoreEntry {
+    /// Decodes a store folder name such as `@babel+core@7.24.0`.
+    ///
+    /// Peer dependency suffixes, written `_react@18.2.0` by pnpm 7 and 8
+    /// and `(react@18.2.0)` by pnpm 9, are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::pnpm::StoreEntry;
+    ///
+    /// let entry = StoreEntry::parse("@mui+material@5.15.0_react@18.2.0").unwrap();
+    /// assert_eq!(entry.name, "@mui/material");
+    /// assert_eq!(entry.version, "5.15.0");
+    /// assert!(StoreEntry::parse("lock.yaml").is_none());
+    /// ```
+    pub fn parse(folder: &str) -> Option<Self> {
+        // Only a scope can start with `@`, so the next one ends the name
+        let at = folder.get(1..)?.find('@')? + 1;
+        let name = &folder[..at];
+        let version = folder[at + 1..]
+            .split(['_', '('])
+            .next()
+            .filter(|v| !v.is_empty())?;
+
+        let name = match name.strip_prefix('@') {
+            Some(scoped) => {
+                let (scope, package) = scoped.split_once('+')?;
+                format!("@{}/{}", scope, package)
+            }
+            None => name.to_string(),
+        };
+        if name.is_empty() || name.contains('+') {
+            return None;
+        }
+
+        Some(Self {
+            name,
+            version: version.to_string(),
+        })
+    }
+}
+
+/// Removes the virtual store folders from a module path, leaving the path
+/// a flat `node_modules` install would have.
+///
+/// Paths without a store folder are returned as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::pnpm::normalize_store_path;
+///
+/// assert_eq!(
+///     normalize_store_path("./node_modules/.pnpm/react-dom@18.2.0_react@18.2.0/node_modules/react-dom/index.js"),
+///     "./node_modules/react-dom/index.js"
+/// );
+/// assert_eq!(normalize_store_path("./src/index.js"), "./src/index.js");
+/// ```
+pub fn normalize_store_path(path: &str) -> Cow<'_, str> {
+    const MARKER: &str = "node_modules/.pnpm/";
+
+    if !path.contains(MARKER) {
+        return Cow::Borrowed(path);
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find(MARKER) {
+        let entry = &rest[start + MARKER.len()..];
+        // `.pnpm/<entry>/node_modules/` is replaced by `node_modules/`;
+        // anything else in the store (lock files, the hoisted
+        // `.pnpm/node_modules`) is kept
+        match entry.split_once('/') {
+            Some((folder, inner)) if folder != "node_modules" && inner.starts_with("node_modules/") => {
+                normalized.push_str(&rest[..start]);
+                rest = inner;
+            }
+            _ => {
+                normalized.push_str(&rest[..start + MARKER.len()]);
+                rest = entry;
+            }
+        }
+    }
+    normalized.push_str(rest);
+    Cow::Owned(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_entry_parse() {
+        let entry = |folder: &str| StoreEntry::parse(folder).map(|e| (e.name, e.version));
+        assert_eq!(entry("react@18.2.0"), Some(("react".to_string(), "18.2.0".to_string())));
+        assert_eq!(entry("string_decoder@1.3.0"), Some(("string_decoder".to_string(), "1.3.0".to_string())));
+        assert_eq!(
+            entry("@babel+core@7.24.0(@babel+types@7.24.0)"),
+            Some(("@babel/core".to_string(), "7.24.0".to_string()))
+        );
+        assert_eq!(
+            entry("next@14.1.0_react-dom@18.2.0_react@18.2.0"),
+            Some(("next".to_string(), "14.1.0".to_string()))
+        );
+        assert_eq!(entry("node_modules"), None);
+        assert_eq!(entry("@babel"), None);
+        assert_eq!(entry("react@"), None);
+    }
+
+    #[test]
+    fn test_normalize_nested_and_hoisted_paths() {
+        // A store package depending on an unhoisted copy of another
+        assert_eq!(
+            normalize_store_path(
+                "/app/node_modules/.pnpm/a@1.0.0/node_modules/a/node_modules/.pnpm/b@2.0.0/node_modules/b/x.js"
+            ),
+            "/app/node_modules/a/node_modules/b/x.js"
+        );
+        // The hoisted folder already reads like a flat install
+        assert_eq!(
+            normalize_store_path("node_modules/.pnpm/node_modules/debug/src/index.js"),
+            "node_modules/.pnpm/node_modules/debug/src/index.js"
+        );
+        assert_eq!(normalize_store_path("node_modules/.pnpm/lock.yaml"), "node_modules/.pnpm/lock.yaml");
+    }
+}
@@ -0,0 +1,234 @@
+//! npm version ranges.
+//!
+//! Translates npm range syntax (`^1.2.3`, `1.x`, `>=1.0.0 <2`, `1.0 - 2.0`,
+//! `^1 || ^2`) into [`semver`] requirements so resolved versions can be
+//! checked against what a package.json asks for. Specifiers that are not
+//! ranges, such as git URLs, paths or dist-tags, do not parse.
+
+use semver::{Op, Version, VersionReq};
+
+/// A parsed npm version range: a union of requirements joined by `||`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    alternatives: Vec<VersionReq>,
+}
+
+impl VersionRange {
+    /// Parses an npm range, including the range of an `npm:` alias.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `spec` is not a version range.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::VersionRange;
+    ///
+    /// let range = VersionRange::parse("^4.17.0 || 5.x").unwrap();
+    /// assert!(range.matches("4.17.21"));
+    /// assert!(range.matches("5.2.0"));
+    /// assert!(!range.matches("6.0.0"));
+    /// assert!(VersionRange::parse("github:lodash/lodash").is_none());
+    /// ```
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = match spec.strip_prefix("npm:") {
+            Some(alias) => alias.rsplit_once('@').map_or("", |(_, range)| range),
+            None => spec,
+        };
+        let alternatives = spec
+            .split("||")
+            .map(parse_comparator_set)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { alternatives })
+    }
+
+    /// Returns true if `version` satisfies the range.
+    pub fn matches(&self, version: &str) -> bool {
+        parse_version(version).is_some_and(|v| self.matches_version(&v))
+    }
+
+    /// Returns true if `version` satisfies the range.
+    pub fn matches_version(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|req| req.matches(version))
+    }
+
+    /// Returns true if the range allows exactly one version, such as
+    /// `1.2.3` or `=1.2.3`.
+    pub fn is_exact(&self) -> bool {
+        match &self.alternatives[..] {
+            [req] => match &req.comparators[..] {
+                [c] => c.op == Op::Exact && c.minor.is_some() && c.patch.is_some(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns true if every alternative has an upper bound, so a new
+    /// major version can never satisfy the range; `*`, `>=1.0.0` and
+    /// `>1` are unbounded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::VersionRange;
+    ///
+    /// assert!(VersionRange::parse("^1.2.3 || ~2.0.0").unwrap().is_bounded());
+    /// assert!(!VersionRange::parse(">=1.0.0").unwrap().is_bounded());
+    /// assert!(!VersionRange::parse("*").unwrap().is_bounded());
+    /// ```
+    pub fn is_bounded(&self) -> bool {
+        self.alternatives.iter().all(|req| {
+            req.comparators.iter().any(|c| {
+                matches!(c.op, Op::Exact | Op::Less | Op::LessEq | Op::Tilde | Op::Caret | Op::Wildcard)
+            })
+        })
+    }
+
+    /// Returns the full versions the range names as lower bounds, such as
+    /// `4.17.21` for `^4.17.21`; useful as candidates when looking for a
+    /// version several ranges agree on.
+    pub fn lower_bounds(&self) -> Vec<Version> {
+        self.alternatives
+            .iter()
+            .flat_map(|req| &req.comparators)
+            .filter(|c| matches!(c.op, Op::Exact | Op::GreaterEq | Op::Caret | Op::Tilde))
+            .filter_map(|c| {
+                Some(Version {
+                    major: c.major,
+                    minor: c.minor?,
+                    patch: c.patch?,
+                    pre: c.pre.clone(),
+                    build: Default::default(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses a version as found in lockfiles, allowing a leading `v` or `=`.
+pub fn parse_version(version: &str) -> Option<Version> {
+    let version = version.trim().trim_start_matches(['=', 'v']);
+    Version::parse(version).ok()
+}
+
+/// Parses one `||` alternative: whitespace-separated comparators or a
+/// hyphen range.
+fn parse_comparator_set(set: &str) -> Option<VersionReq> {
+    let tokens: Vec<&str> = set.split_whitespace().collect();
+
+    if let [low, "-", high] = tokens[..] {
+        let req = format!(">={}, <={}", strip_v(low), strip_v(high));
+        return VersionReq::parse(&req).ok();
+    }
+
+    // Join operators written apart from their version (`>= 1.2.3`)
+    let mut comparators: Vec<String> = Vec::new();
+    let mut pending = String::new();
+    for token in tokens {
+        if token.chars().all(|c| "<>=~^".contains(c)) {
+            pending.push_str(token);
+            continue;
+        }
+        comparators.push(comparator(&format!("{}{}", pending, token))?);
+        pending.clear();
+    }
+    if !pending.is_empty() {
+        return None;
+    }
+    if comparators.is_empty() {
+        return Some(VersionReq::STAR);
+    }
+
+    VersionReq::parse(&comparators.join(", ")).ok()
+}
+
+/// Converts one npm comparator to semver syntax.
+fn comparator(token: &str) -> Option<String> {
+    let split = token.find(|c: char| !"<>=~^".contains(c))?;
+    let (op, version) = token.split_at(split);
+    let version = strip_v(version);
+    if !version.starts_with(|c: char| c.is_ascii_digit() || "xX*".contains(c)) {
+        return None;
+    }
+    if matches!(version, "x" | "X" | "*") {
+        return Some("*".to_string());
+    }
+    let op = match op {
+        // A bare version is exact in npm, but a caret requirement in semver
+        "" => "=",
+        "~>" => "~",
+        op => op,
+    };
+    Some(format!("{}{}", op, version))
+}
+
+fn strip_v(version: &str) -> &str {
+    version.strip_prefix('v').unwrap_or(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .unwrap_or_else(|| panic!("{} should parse", range))
+            .matches(version)
+    }
+
+    #[test]
+    fn test_npm_range_syntax() {
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("1.2.3", "1.2.3"));
+        assert!(!matches("1.2.3", "1.2.4"), "bare versions are exact");
+        assert!(matches("v1.2.3", "1.2.3"));
+        assert!(matches("1.x", "1.5.0"));
+        assert!(matches("1.2", "1.2.7"));
+        assert!(matches("*", "9.9.9"));
+        assert!(matches("", "0.0.1"));
+        assert!(matches(">=1.0.0 <2", "1.9.9"));
+        assert!(!matches(">=1.0.0 <2", "2.0.0"));
+        assert!(matches(">= 1.0.0", "1.0.0"));
+        assert!(matches("1.0.0 - 2.0.0", "2.0.0"));
+        assert!(matches("^1 || ^3", "3.1.0"));
+        assert!(!matches("^1 || ^3", "2.1.0"));
+        assert!(matches("npm:lodash@^4.0.0", "4.17.21"));
+    }
+
+    #[test]
+    fn test_non_ranges() {
+        for spec in ["latest", "github:user/repo", "file:../lib", "https://x.test/a.tgz", ">="] {
+            assert!(VersionRange::parse(spec).is_none(), "{}", spec);
+        }
+        assert!(!VersionRange::parse("^1.0.0").unwrap().matches("not-a-version"));
+    }
+
+    #[test]
+    fn test_exact_and_bounded_ranges() {
+        let range = |spec: &str| VersionRange::parse(spec).unwrap();
+        for spec in ["1.2.3", "=1.2.3", "v1.2.3"] {
+            assert!(range(spec).is_exact(), "{}", spec);
+        }
+        for spec in ["1.2", "^1.2.3", "1.2.3 || 1.2.4", ">=1.2.3 <=1.2.3"] {
+            assert!(!range(spec).is_exact(), "{}", spec);
+        }
+        for spec in ["1.x", "1", "~1.2", ">=1 <2", "1.0.0 - 2.0.0", "<3"] {
+            assert!(range(spec).is_bounded(), "{}", spec);
+        }
+        for spec in ["", "x", ">1", ">=1.0.0", "^1 || >=3"] {
+            assert!(!range(spec).is_bounded(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_lower_bounds() {
+        let range = VersionRange::parse("^4.17.21 || >=5.0.0 <6 || 7.x").unwrap();
+        let bounds: Vec<String> = range.lower_bounds().iter().map(Version::to_string).collect();
+        assert_eq!(bounds, vec!["4.17.21", "5.0.0"]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Located parse errors and recovery from malformed entries.
+//!
+//! Hand-edited lockfiles and stats files from unusual bundler setups often
+//! contain a few entries that cannot be read. Rather than rejecting the
+//! whole file, parsers skip such entries and report each one as a
+//! [`ParseWarning`]. Errors that leave nothing to analyze carry the
+//! position of the problem as a [`SyntaxError`].
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A JSON error with its position in the input.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at line {line}, column {column} (byte {offset})")]
+pub struct SyntaxError {
+    /// What went wrong, without the position
+    pub message: String,
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the error in bytes, starting at 1; 0 when the input ends
+    /// right after a line break
+    pub column: usize,
+    /// Byte offset of the error from the start of the input; for an
+    /// unexpected end of input, that of the last byte read
+    pub offset: usize,
+}
+
+impl SyntaxError {
+    /// Locates a `serde_json` error in the input it was raised for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::recovery::SyntaxError;
+    ///
+    /// let input = "{\n  \"name\": 1,\n}";
+    /// let err = serde_json::from_str::<serde_json::Value>(input).unwrap_err();
+    /// let err = SyntaxError::from_json(input, &err);
+    /// assert_eq!((err.line, err.column, err.offset), (3, 1, 15));
+    /// assert_eq!(err.message, "trailing comma");
+    /// ```
+    pub fn from_json(content: &str, error: &serde_json::Error) -> Self {
+        let mut message = error.to_string();
+        if let Some(at) = message.rfind(" at line ") {
+            message.truncate(at);
+        }
+        let (line, column) = (error.line(), error.column());
+        let line_start: usize = content
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum();
+        Self {
+            message,
+            line,
+            column,
+            offset: (line_start + column.saturating_sub(1)).min(content.len()),
+        }
+    }
+}
+
+/// An entry a parser skipped because it could not be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseWarning {
+    /// Where the entry is, e.g. `modules[3]` or `packages["node_modules/a"]`
+    pub location: String,
+    /// Why it was skipped
+    pub message: String,
+}
+
+impl ParseWarning {
+    /// Creates a warning.
+    pub fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Reads every element of a JSON array that deserializes, skipping the
+/// others with a warning.
+///
+/// A missing or `null` field is empty; any other non-array value is
+/// skipped as a whole. Skipped elements are located by index, followed by
+/// their `name` when they have one.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::recovery::recover_list;
+/// use serde_json::json;
+///
+/// let mut warnings = Vec::new();
+/// let sizes: Vec<u64> = recover_list(Some(json!([1, "two", 3])), "sizes", &mut warnings);
+/// assert_eq!(sizes, vec![1, 3]);
+/// assert_eq!(warnings[0].location, "sizes[1]");
+/// ```
+pub fn recover_list<T: DeserializeOwned>(
+    value: Option<Value>,
+    field: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Vec<T> {
+    let items = match value {
+        None | Some(Value::Null) => return Vec::new(),
+        Some(Value::Array(items)) => items,
+        Some(other) => {
+            warnings.push(ParseWarning::new(field, format!("skipped, expected an array but found {}", kind(&other))));
+            return Vec::new();
+        }
+    };
+
+    let mut recovered = Vec::with_capacity(items.len());
+    for (i, item) in items.into_iter().enumerate() {
+        let name = item.get("name").and_then(Value::as_str).map(str::to_string);
+        match serde_json::from_value(item) {
+            Ok(item) => recovered.push(item),
+            Err(e) => {
+                let location = match name {
+                    Some(name) => format!("{}[{}] ({})", field, i, name),
+                    None => format!("{}[{}]", field, i),
+                };
+                warnings.push(ParseWarning::new(location, format!("skipped, {}", e)));
+            }
+        }
+    }
+    recovered
+}
+
+/// Reads every value of a JSON object that deserializes, skipping the
+/// others with a warning.
+///
+/// A missing or `null` field is empty; any other non-object value is
+/// skipped as a whole.
+pub fn recover_map<T: DeserializeOwned>(
+    value: Option<Value>,
+    field: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Vec<(String, T)> {
+    let entries = match value {
+        None | Some(Value::Null) => return Vec::new(),
+        Some(Value::Object(entries)) => entries,
+        Some(other) => {
+            warnings.push(ParseWarning::new(field, format!("skipped, expected an object but found {}", kind(&other))));
+            return Vec::new();
+        }
+    };
+
+    let mut recovered = Vec::with_capacity(entries.len());
+    for (key, value) in entries {
+        match serde_json::from_value(value) {
+            Ok(value) => recovered.push((key, value)),
+            Err(e) => warnings.push(ParseWarning::new(format!("{}[{:?}]", field, key), format!("skipped, {}", e))),
+        }
+    }
+    recovered
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_syntax_error_at_end_of_input() {
+        let input = "{\"a\": [1, 2";
+        let err = serde_json::from_str::<Value>(input).unwrap_err();
+        let err = SyntaxError::from_json(input, &err);
+        // The position is that of the last byte read
+        assert_eq!(err.offset, input.len() - 1);
+        assert_eq!(err.to_string(), "EOF while parsing a list at line 1, column 11 (byte 10)");
+    }
+
+    #[test]
+    fn test_recover_map() {
+        let mut warnings = Vec::new();
+        let versions: BTreeMap<String, String> = recover_map(
+            Some(json!({"react": "18.2.0", "lodash": 4})),
+            "packages",
+            &mut warnings,
+        )
+        .into_iter()
+        .collect();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location, "packages[\"lodash\"]");
+        assert!(warnings[0].message.starts_with("skipped, invalid type: integer `4`"));
+
+        let none: Vec<(String, String)> = recover_map(Some(json!([1])), "packages", &mut warnings);
+        assert!(none.is_empty());
+        assert_eq!(warnings[1].to_string(), "packages: skipped, expected an object but found an array");
+    }
+}
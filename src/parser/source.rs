@@ -0,0 +1,315 @@
+//! Dependency specifier parsing.
+//!
+//! The value side of a `package.json` dependency entry is not always a
+//! semver range. npm also accepts aliases (`npm:other@^2`), git
+//! repositories, local paths, tarball URLs and, in Yarn/pnpm workspaces,
+//! the `workspace:` protocol. This module classifies a specifier so the
+//! rest of CodeScope can look up the real package and compare like with
+//! like.
+
+use std::fmt;
+
+/// Where a dependency is installed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DependencySource {
+    /// A registry package: a semver range, exact version or dist-tag
+    Registry,
+    /// An `npm:` alias for another registry package
+    Alias {
+        /// The real package name
+        package: String,
+        /// The requested range (`*` if none was given)
+        range: String,
+    },
+    /// A git repository
+    Git {
+        /// Repository URL or hosted shorthand (`github:user/repo`, `user/repo`)
+        url: String,
+        /// Branch, tag, commit or semver range after `#`
+        committish: Option<String>,
+    },
+    /// A local directory or tarball (`file:`, `link:` or a relative path)
+    File {
+        /// Path as written, without the protocol
+        path: String,
+    },
+    /// A remote tarball URL
+    Tarball {
+        /// Tarball URL
+        url: String,
+    },
+    /// A sibling package in the same workspace (`workspace:`)
+    Workspace {
+        /// The requested range (`*`, `^`, `~` or a semver range)
+        range: String,
+    },
+}
+
+/// Hosted git shorthand prefixes understood by npm.
+const GIT_HOST_PREFIXES: &[&str] = &["github:", "gitlab:", "bitbucket:", "gist:"];
+
+impl DependencySource {
+    /// Classifies a dependency specifier.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::DependencySource;
+    ///
+    /// assert_eq!(DependencySource::parse("^18.2.0"), DependencySource::Registry);
+    /// assert_eq!(
+    ///     DependencySource::parse("npm:string-width@^4.2.0"),
+    ///     DependencySource::Alias { package: "string-width".into(), range: "^4.2.0".into() }
+    /// );
+    /// assert_eq!(DependencySource::parse("file:../shared").label(), "file");
+    /// assert_eq!(DependencySource::parse("expressjs/express#4.x").label(), "git");
+    /// ```
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+
+        if let Some(rest) = spec.strip_prefix("npm:") {
+            let (package, range) = split_name_and_range(rest);
+            return DependencySource::Alias {
+                package: package.to_string(),
+                range: if range.is_empty() { "*".to_string() } else { range.to_string() },
+            };
+        }
+        if let Some(range) = spec.strip_prefix("workspace:") {
+            return DependencySource::Workspace {
+                range: range.to_string(),
+            };
+        }
+        if let Some(path) = spec.strip_prefix("file:").or_else(|| spec.strip_prefix("link:")) {
+            return DependencySource::File {
+                path: path.to_string(),
+            };
+        }
+        if ["./", "../", "/", "~/"].iter().any(|p| spec.starts_with(p)) {
+            return DependencySource::File {
+                path: spec.to_string(),
+            };
+        }
+        if is_git(spec) {
+            let (url, committish) = match spec.split_once('#') {
+                Some((url, committish)) => (url, Some(committish.to_string())),
+                None => (spec, None),
+            };
+            return DependencySource::Git {
+                url: url.to_string(),
+                committish,
+            };
+        }
+        if spec.starts_with("http://") || spec.starts_with("https://") {
+            return DependencySource::Tarball {
+                url: spec.to_string(),
+            };
+        }
+
+        DependencySource::Registry
+    }
+
+    /// Returns a short label for the source kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            DependencySource::Registry => "registry",
+            DependencySource::Alias { .. } => "alias",
+            DependencySource::Git { .. } => "git",
+            DependencySource::File { .. } => "file",
+            DependencySource::Tarball { .. } => "tarball",
+            DependencySource::Workspace { .. } => "workspace",
+        }
+    }
+
+    /// Returns true if the package is fetched from the npm registry.
+    pub fn is_registry(&self) -> bool {
+        matches!(self, DependencySource::Registry | DependencySource::Alias { .. })
+    }
+
+    /// Returns the real package name and version range for registry
+    /// sources, resolving aliases.
+    ///
+    /// Returns `None` for git, file, tarball and workspace sources, whose
+    /// specifiers are not semver ranges.
+    pub fn registry_target<'a>(&'a self, name: &'a str, spec: &'a str) -> Option<(&'a str, &'a str)> {
+        match self {
+            DependencySource::Registry => Some((name, spec.trim())),
+            DependencySource::Alias { package, range } => Some((package, range)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DependencySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Splits `name@range` (with optional `@scope/`) into its parts.
+fn split_name_and_range(spec: &str) -> (&str, &str) {
+    // The first character is part of the name, even if it is a scope's `@`
+    match spec.char_indices().skip(1).find(|&(_, c)| c == '@') {
+        Some((at, _)) => (&spec[..at], &spec[at + 1..]),
+        None => (spec, ""),
+    }
+}
+
+/// Detects git URLs, hosted shorthands and bare `user/repo` GitHub
+/// shorthands.
+fn is_git(spec: &str) -> bool {
+    if spec.starts_with("git+") || spec.starts_with("git://") || spec.starts_with("git@") {
+        return true;
+    }
+    if GIT_HOST_PREFIXES.iter().any(|p| spec.starts_with(p)) {
+        return true;
+    }
+    if spec.starts_with("http://") || spec.starts_with("https://") {
+        let url = spec.split('#').next().unwrap_or(spec);
+        return url.ends_with(".git");
+    }
+
+    // `user/repo` or `user/repo#ref`
+    let repo = spec.split('#').next().unwrap_or(spec);
+    match repo.split_once('/') {
+        Some((user, name)) => {
+            let valid = |s: &str| {
+                !s.is_empty()
+                    && s
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            };
+            !user.starts_with(['@', '.']) && valid(user) && valid(name)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(url: &str, committish: Option<&str>) -> DependencySource {
+        DependencySource::Git {
+            url: url.to_string(),
+            committish: committish.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_registry_specs() {
+        for spec in ["^18.2.0", "~1.2.3", "1.0.0", ">=2 <3", "latest", "*", "", "1.x || 2.x"] {
+            assert_eq!(DependencySource::parse(spec), DependencySource::Registry, "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_alias_specs() {
+        assert_eq!(
+            DependencySource::parse("npm:@babel/core@^7.0.0"),
+            DependencySource::Alias {
+                package: "@babel/core".to_string(),
+                range: "^7.0.0".to_string()
+            }
+        );
+        assert_eq!(
+            DependencySource::parse("npm:lodash"),
+            DependencySource::Alias {
+                package: "lodash".to_string(),
+                range: "*".to_string()
+            }
+        );
+        assert_eq!(
+            DependencySource::parse("npm:@scope/pkg"),
+            DependencySource::Alias {
+                package: "@scope/pkg".to_string(),
+                range: "*".to_string()
+            }
+        );
+        // Non-ASCII names must not split inside a character
+        assert_eq!(
+            DependencySource::parse("npm:é@1.0.0"),
+            DependencySource::Alias {
+                package: "é".to_string(),
+                range: "1.0.0".to_string()
+            }
+        );
+        assert_eq!(
+            DependencySource::parse("npm:ü"),
+            DependencySource::Alias {
+                package: "ü".to_string(),
+                range: "*".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_git_specs() {
+        assert_eq!(
+            DependencySource::parse("git+https://github.com/user/repo.git#v1.0.0"),
+            git("git+https://github.com/user/repo.git", Some("v1.0.0"))
+        );
+        assert_eq!(
+            DependencySource::parse("git://github.com/user/repo.git"),
+            git("git://github.com/user/repo.git", None)
+        );
+        assert_eq!(
+            DependencySource::parse("git@github.com:user/repo.git"),
+            git("git@github.com:user/repo.git", None)
+        );
+        assert_eq!(DependencySource::parse("github:user/repo#main"), git("github:user/repo", Some("main")));
+        assert_eq!(
+            DependencySource::parse("https://github.com/user/repo.git#semver:^1.0"),
+            git("https://github.com/user/repo.git", Some("semver:^1.0"))
+        );
+        assert_eq!(DependencySource::parse("expressjs/express"), git("expressjs/express", None));
+    }
+
+    #[test]
+    fn test_file_and_workspace_specs() {
+        assert_eq!(
+            DependencySource::parse("file:../shared"),
+            DependencySource::File {
+                path: "../shared".to_string()
+            }
+        );
+        assert_eq!(
+            DependencySource::parse("link:./packages/ui"),
+            DependencySource::File {
+                path: "./packages/ui".to_string()
+            }
+        );
+        assert_eq!(DependencySource::parse("./vendor/pkg.tgz").label(), "file");
+        assert_eq!(
+            DependencySource::parse("workspace:^"),
+            DependencySource::Workspace {
+                range: "^".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_tarball_specs() {
+        assert_eq!(
+            DependencySource::parse("https://registry.example.com/pkg/-/pkg-1.0.0.tgz"),
+            DependencySource::Tarball {
+                url: "https://registry.example.com/pkg/-/pkg-1.0.0.tgz".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_registry_target() {
+        let alias = DependencySource::parse("npm:strip-ansi@^6.0.1");
+        assert_eq!(
+            alias.registry_target("strip-ansi-cjs", "npm:strip-ansi@^6.0.1"),
+            Some(("strip-ansi", "^6.0.1"))
+        );
+        assert_eq!(
+            DependencySource::Registry.registry_target("react", "^18.0.0"),
+            Some(("react", "^18.0.0"))
+        );
+        assert!(DependencySource::parse("file:../x").registry_target("x", "file:../x").is_none());
+        assert!(!DependencySource::parse("github:a/b").is_registry());
+        assert!(alias.is_registry());
+    }
+}
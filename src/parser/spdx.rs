@@ -0,0 +1,322 @@
+//! SPDX license expressions.
+//!
+//! The `license` field of package.json holds an SPDX expression:
+//! `MIT`, `(MIT OR Apache-2.0)` for a choice of licenses, `MIT AND CC0-1.0`
+//! when both apply, or `GPL-2.0-only WITH Classpath-exception-2.0` for a
+//! license with an exception. Policies evaluate the parsed expression, so a
+//! package offered under `GPL-3.0-only OR MIT` is acceptable wherever MIT
+//! is.
+
+use std::fmt;
+
+/// A single license of an expression, with its exception if any.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LicenseTerm {
+    /// License identifier, e.g. `Apache-2.0` or `LicenseRef-Proprietary`
+    pub id: String,
+    /// Whether the identifier was followed by `+` ("or any later version")
+    pub or_later: bool,
+    /// Exception identifier after `WITH`
+    pub exception: Option<String>,
+}
+
+impl LicenseTerm {
+    /// Parses a single license, optionally with an exception, such as
+    /// `MIT`, `GPL-2.0+` or `GPL-2.0-only WITH Classpath-exception-2.0`.
+    pub fn parse(term: &str) -> Option<Self> {
+        match LicenseExpression::parse(term)? {
+            LicenseExpression::License(term) => Some(term),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this term is covered by `other`, ignoring case.
+    ///
+    /// A term without an exception covers the same license with any
+    /// exception; a term with an exception only covers that exception.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::spdx::LicenseTerm;
+    ///
+    /// let gpl = LicenseTerm::parse("GPL-2.0-only").unwrap();
+    /// let classpath = LicenseTerm::parse("GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+    /// assert!(classpath.is_covered_by(&gpl));
+    /// assert!(!gpl.is_covered_by(&classpath));
+    /// assert!(LicenseTerm::parse("mit").unwrap().is_covered_by(&LicenseTerm::parse("MIT").unwrap()));
+    /// ```
+    pub fn is_covered_by(&self, other: &LicenseTerm) -> bool {
+        self.id.eq_ignore_ascii_case(&other.id)
+            && self.or_later == other.or_later
+            && match (&self.exception, &other.exception) {
+                (_, None) => true,
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                (None, Some(_)) => false,
+            }
+    }
+}
+
+impl fmt::Display for LicenseTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        if self.or_later {
+            write!(f, "+")?;
+        }
+        if let Some(exception) = &self.exception {
+            write!(f, " WITH {}", exception)?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed SPDX license expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpression {
+    /// A single license
+    License(LicenseTerm),
+    /// All of the expressions apply
+    And(Vec<LicenseExpression>),
+    /// Any one of the expressions may be chosen
+    Or(Vec<LicenseExpression>),
+}
+
+impl LicenseExpression {
+    /// Parses an expression.
+    ///
+    /// `WITH` binds tighter than `AND`, which binds tighter than `OR`.
+    /// Operators are accepted in any case, since older packages do not
+    /// always write them in upper case.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the text is not a valid expression, e.g.
+    /// `SEE LICENSE IN LICENSE.txt`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::spdx::LicenseExpression;
+    ///
+    /// let expr = LicenseExpression::parse("(MIT OR Apache-2.0) AND BSD-3-Clause").unwrap();
+    /// assert_eq!(expr.to_string(), "(MIT OR Apache-2.0) AND BSD-3-Clause");
+    /// assert_eq!(expr.terms().len(), 3);
+    /// assert!(LicenseExpression::parse("SEE LICENSE IN LICENSE.txt").is_none());
+    /// ```
+    pub fn parse(expression: &str) -> Option<Self> {
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut parser = Parser { tokens: &tokens, pos: 0 };
+        let parsed = parser.or()?;
+        (parser.pos == tokens.len()).then_some(parsed)
+    }
+
+    /// Returns true if the expression can be satisfied using only licenses
+    /// that `accept` accepts: one alternative of every `OR`, and every part
+    /// of an `AND`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::spdx::LicenseExpression;
+    ///
+    /// let expr = LicenseExpression::parse("GPL-3.0-only OR MIT").unwrap();
+    /// assert!(expr.is_satisfied_by(|term| term.id != "GPL-3.0-only"));
+    /// assert!(!expr.is_satisfied_by(|term| term.id == "ISC"));
+    /// ```
+    pub fn is_satisfied_by<F: Fn(&LicenseTerm) -> bool>(&self, accept: F) -> bool {
+        self.satisfied(&accept)
+    }
+
+    fn satisfied(&self, accept: &dyn Fn(&LicenseTerm) -> bool) -> bool {
+        match self {
+            LicenseExpression::License(term) => accept(term),
+            LicenseExpression::And(parts) => parts.iter().all(|part| part.satisfied(accept)),
+            LicenseExpression::Or(parts) => parts.iter().any(|part| part.satisfied(accept)),
+        }
+    }
+
+    /// Returns every license mentioned in the expression, in order.
+    pub fn terms(&self) -> Vec<&LicenseTerm> {
+        match self {
+            LicenseExpression::License(term) => vec![term],
+            LicenseExpression::And(parts) | LicenseExpression::Or(parts) => {
+                parts.iter().flat_map(|part| part.terms()).collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LicenseExpression::License(term) => write!(f, "{}", term),
+            LicenseExpression::And(parts) | LicenseExpression::Or(parts) => {
+                let operator = if matches!(self, LicenseExpression::And(_)) { " AND " } else { " OR " };
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", operator)?;
+                    }
+                    match part {
+                        LicenseExpression::License(_) => write!(f, "{}", part)?,
+                        _ => write!(f, "({})", part)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Recursive descent over the tokens of an expression.
+struct Parser<'a> {
+    tokens: &'a [&'a str],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn eat_operator(&mut self, operator: &str) -> bool {
+        let found = self.peek().is_some_and(|token| token.eq_ignore_ascii_case(operator));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Option<LicenseExpression> {
+        let mut parts = vec![self.and()?];
+        while self.eat_operator("OR") {
+            parts.push(self.and()?);
+        }
+        Some(combine(parts, false))
+    }
+
+    fn and(&mut self) -> Option<LicenseExpression> {
+        let mut parts = vec![self.with()?];
+        while self.eat_operator("AND") {
+            parts.push(self.with()?);
+        }
+        Some(combine(parts, true))
+    }
+
+    fn with(&mut self) -> Option<LicenseExpression> {
+        if self.peek() == Some("(") {
+            self.pos += 1;
+            let inner = self.or()?;
+            return (self.peek() == Some(")")).then(|| {
+                self.pos += 1;
+                inner
+            });
+        }
+
+        let token = self.peek()?;
+        let (id, or_later) = match token.strip_suffix('+') {
+            Some(id) => (id, true),
+            None => (token, false),
+        };
+        if !is_identifier(id) || is_operator(id) {
+            return None;
+        }
+        self.pos += 1;
+        let exception = if self.eat_operator("WITH") {
+            let exception = self.peek().filter(|e| is_identifier(e) && !is_operator(e))?;
+            self.pos += 1;
+            Some(exception.to_string())
+        } else {
+            None
+        };
+        Some(LicenseExpression::License(LicenseTerm {
+            id: id.to_string(),
+            or_later,
+            exception,
+        }))
+    }
+}
+
+/// Builds an `AND` or `OR` node, flattening nested nodes of the same kind
+/// and unwrapping single parts.
+fn combine(parts: Vec<LicenseExpression>, is_and: bool) -> LicenseExpression {
+    if parts.len() == 1 {
+        return parts.into_iter().next().expect("one part");
+    }
+    let mut flat = Vec::new();
+    for part in parts {
+        match part {
+            LicenseExpression::And(inner) if is_and => flat.extend(inner),
+            LicenseExpression::Or(inner) if !is_and => flat.extend(inner),
+            other => flat.push(other),
+        }
+    }
+    if is_and {
+        LicenseExpression::And(flat)
+    } else {
+        LicenseExpression::Or(flat)
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == ':')
+}
+
+fn is_operator(token: &str) -> bool {
+    ["AND", "OR", "WITH"].iter().any(|op| token.eq_ignore_ascii_case(op))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(id: &str) -> LicenseExpression {
+        LicenseExpression::License(LicenseTerm {
+            id: id.to_string(),
+            or_later: false,
+            exception: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        assert_eq!(
+            LicenseExpression::parse("MIT OR Apache-2.0 AND BSD-3-Clause"),
+            Some(LicenseExpression::Or(vec![
+                term("MIT"),
+                LicenseExpression::And(vec![term("Apache-2.0"), term("BSD-3-Clause")]),
+            ]))
+        );
+        assert_eq!(
+            LicenseExpression::parse("(MIT or ISC) OR (Apache-2.0)"),
+            Some(LicenseExpression::Or(vec![term("MIT"), term("ISC"), term("Apache-2.0")])),
+            "nested alternatives flatten"
+        );
+
+        let with = LicenseExpression::parse("GPL-2.0+ WITH Classpath-exception-2.0 AND MIT").unwrap();
+        let terms = with.terms();
+        assert!(terms[0].or_later);
+        assert_eq!(terms[0].exception.as_deref(), Some("Classpath-exception-2.0"));
+        assert_eq!(with.to_string(), "GPL-2.0+ WITH Classpath-exception-2.0 AND MIT");
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for invalid in ["", "MIT OR", "(MIT", "MIT)", "MIT Apache-2.0", "MIT WITH", "AND", "MIT/Apache"] {
+            assert_eq!(LicenseExpression::parse(invalid), None, "{:?}", invalid);
+        }
+        assert_eq!(LicenseTerm::parse("MIT OR ISC"), None);
+    }
+
+    #[test]
+    fn test_satisfied_by() {
+        let denied = |term: &LicenseTerm| !term.id.starts_with("GPL");
+        let parse = |e: &str| LicenseExpression::parse(e).unwrap();
+        assert!(parse("(MIT OR GPL-3.0-only)").is_satisfied_by(denied));
+        assert!(!parse("MIT AND GPL-3.0-only").is_satisfied_by(denied));
+        assert!(parse("(GPL-2.0-only AND MIT) OR (ISC AND MIT)").is_satisfied_by(denied));
+    }
+}
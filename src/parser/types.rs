@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 
+use super::source::DependencySource;
+
 /// Represents the structure of a package.json file.
 ///
 /// This struct mirrors the npm package.json specification,
@@ -47,6 +49,39 @@ pub struct PackageJson {
     /// Optional dependencies that enhance functionality if available.
     #[serde(rename = "optionalDependencies")]
     pub optional_dependencies: Option<HashMap<String, String>>,
+
+    /// Dependencies packed into the package's own tarball.
+    #[serde(rename = "bundledDependencies", alias = "bundleDependencies")]
+    pub bundled_dependencies: Option<BundledDependencies>,
+
+    /// Folder patterns of the workspace packages, in a monorepo root.
+    pub workspaces: Option<Workspaces>,
+}
+
+/// The `workspaces` field: a list of folder patterns, or Yarn's object form
+/// with the patterns under `packages`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Workspaces {
+    /// `["packages/*"]`
+    List(Vec<String>),
+    /// `{ "packages": ["packages/*"], "nohoist": [...] }`
+    Config {
+        /// Folder patterns
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+/// The `bundledDependencies` field: a list of names, or `true` to bundle
+/// every production dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BundledDependencies {
+    /// `true` bundles all dependencies, `false` none
+    All(bool),
+    /// Explicit list of bundled package names
+    List(Vec<String>),
 }
 
 impl PackageJson {
@@ -67,6 +102,48 @@ impl PackageJson {
                 .is_some_and(|d| !d.is_empty())
     }
 
+    /// Returns the names of bundled dependencies, sorted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::parse_str;
+    ///
+    /// let pkg = parse_str(r#"{"dependencies": {"a": "1", "b": "2"}, "bundleDependencies": true}"#).unwrap();
+    /// assert_eq!(pkg.bundled_dependency_names(), vec!["a", "b"]);
+    /// ```
+    pub fn bundled_dependency_names(&self) -> Vec<String> {
+        let mut names = match &self.bundled_dependencies {
+            Some(BundledDependencies::List(names)) => names.clone(),
+            Some(BundledDependencies::All(true)) => self
+                .dependencies
+                .as_ref()
+                .map(|d| d.keys().cloned().collect())
+                .unwrap_or_default(),
+            Some(BundledDependencies::All(false)) | None => Vec::new(),
+        };
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns the workspace folder patterns, empty outside a monorepo root.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::parse_str;
+    ///
+    /// let pkg = parse_str(r#"{"workspaces": {"packages": ["apps/*", "libs/*"]}}"#).unwrap();
+    /// assert_eq!(pkg.workspace_patterns(), ["apps/*", "libs/*"]);
+    /// ```
+    pub fn workspace_patterns(&self) -> &[String] {
+        match &self.workspaces {
+            Some(Workspaces::List(patterns)) | Some(Workspaces::Config { packages: patterns }) => patterns,
+            None => &[],
+        }
+    }
+
     /// Returns the total count of all dependencies.
     pub fn dependency_count(&self) -> usize {
         self.dependencies.as_ref().map_or(0, |d| d.len())
@@ -128,6 +205,47 @@ impl fmt::Display for DependencyType {
     }
 }
 
+/// Which dependencies an analysis covers.
+///
+/// The production scope keeps everything npm installs with
+/// `--omit=dev`: production, peer and optional dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Scope {
+    /// Every dependency
+    #[default]
+    All,
+    /// Everything except development dependencies
+    Production,
+    /// Development dependencies only
+    Development,
+}
+
+impl Scope {
+    /// Returns true if dependencies of the given type are in scope.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::{DependencyType, Scope};
+    ///
+    /// assert!(Scope::Production.includes(DependencyType::Peer));
+    /// assert!(!Scope::Production.includes(DependencyType::Development));
+    /// assert!(Scope::Development.includes(DependencyType::Development));
+    /// ```
+    pub fn includes(&self, dep_type: DependencyType) -> bool {
+        match self {
+            Scope::All => true,
+            Scope::Production => dep_type != DependencyType::Development,
+            Scope::Development => dep_type == DependencyType::Development,
+        }
+    }
+
+    /// Keeps only the dependencies in scope.
+    pub fn filter(&self, deps: &mut Vec<Dependency>) {
+        deps.retain(|d| self.includes(d.dep_type));
+    }
+}
+
 /// Represents a single dependency with its metadata.
 ///
 /// This is the normalized form used throughout CodeScope,
@@ -142,6 +260,9 @@ pub struct Dependency {
 
     /// The category of this dependency.
     pub dep_type: DependencyType,
+
+    /// Where the dependency is installed from, parsed from `version`.
+    pub source: DependencySource,
 }
 
 impl Dependency {
@@ -151,13 +272,41 @@ impl Dependency {
         version: impl Into<String>,
         dep_type: DependencyType,
     ) -> Self {
+        let version = version.into();
         Self {
             name: name.into(),
-            version: version.into(),
+            source: DependencySource::parse(&version),
+            version,
             dep_type,
         }
     }
 
+    /// Returns the name of the package that is actually installed.
+    ///
+    /// For `npm:` aliases this is the aliased package, otherwise the
+    /// dependency's own name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::parser::{Dependency, DependencyType};
+    ///
+    /// let dep = Dependency::new("react-17", "npm:react@^17.0.2", DependencyType::Production);
+    /// assert_eq!(dep.package_name(), "react");
+    /// ```
+    pub fn package_name(&self) -> &str {
+        match &self.source {
+            DependencySource::Alias { package, .. } => package,
+            _ => &self.name,
+        }
+    }
+
+    /// Returns the package name to look up in the npm registry, or `None`
+    /// for dependencies that are not installed from a registry.
+    pub fn registry_name(&self) -> Option<&str> {
+        self.source.is_registry().then(|| self.package_name())
+    }
+
     /// Returns true if this is a production dependency.
     pub fn is_production(&self) -> bool {
         self.dep_type == DependencyType::Production
@@ -195,6 +344,40 @@ mod tests {
         assert!(DependencyType::Optional.affects_bundle_size());
     }
 
+    #[test]
+    fn test_scope_filter() {
+        let mut deps = vec![
+            Dependency::new("react", "^18.0.0", DependencyType::Production),
+            Dependency::new("jest", "^29.0.0", DependencyType::Development),
+            Dependency::new("fsevents", "^2.0.0", DependencyType::Optional),
+        ];
+
+        let mut dev = deps.clone();
+        Scope::Development.filter(&mut dev);
+        assert_eq!(dev.len(), 1);
+        assert_eq!(dev[0].name, "jest");
+
+        Scope::Production.filter(&mut deps);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["react", "fsevents"]);
+    }
+
+    #[test]
+    fn test_dependency_source() {
+        let alias = Dependency::new("old-react", "npm:react@^16", DependencyType::Production);
+        assert_eq!(alias.source.label(), "alias");
+        assert_eq!(alias.package_name(), "react");
+        assert_eq!(alias.registry_name(), Some("react"));
+
+        let local = Dependency::new("shared", "file:../shared", DependencyType::Production);
+        assert_eq!(local.package_name(), "shared");
+        assert_eq!(local.registry_name(), None);
+
+        let plain = Dependency::new("lodash", "^4.17.21", DependencyType::Production);
+        assert_eq!(plain.source, DependencySource::Registry);
+        assert_eq!(plain.registry_name(), Some("lodash"));
+    }
+
     #[test]
     fn test_dependency_new() {
         let dep = Dependency::new("react", "^18.0.0", DependencyType::Production);
@@ -229,4 +412,21 @@ mod tests {
         assert!(pkg.has_dependencies());
         assert_eq!(pkg.dependency_count(), 1);
     }
+
+    #[test]
+    fn test_bundled_dependencies() {
+        let pkg: PackageJson =
+            serde_json::from_str(r#"{"bundledDependencies": ["zeta", "alpha"]}"#).unwrap();
+        assert_eq!(pkg.bundled_dependency_names(), vec!["alpha", "zeta"]);
+
+        let pkg: PackageJson = serde_json::from_str(
+            r#"{"dependencies": {"chalk": "^5.0.0"}, "bundleDependencies": true}"#,
+        )
+        .unwrap();
+        assert_eq!(pkg.bundled_dependency_names(), vec!["chalk"]);
+
+        let pkg: PackageJson = serde_json::from_str(r#"{"bundleDependencies": false}"#).unwrap();
+        assert!(pkg.bundled_dependency_names().is_empty());
+        assert!(PackageJson::default().bundled_dependency_names().is_empty());
+    }
 }
@@ -0,0 +1,328 @@
+//! Monorepo workspaces.
+//!
+//! A project whose package.json has a `workspaces` field is a monorepo
+//! root; each folder matched by the patterns that contains a package.json
+//! is a workspace member. Patterns may use `*` within a folder name, `**`
+//! for any number of folders, and a leading `!` to exclude folders.
+//!
+//! pnpm ignores the `workspaces` field and reads the patterns from the
+//! `packages` list of `pnpm-workspace.yaml` instead.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::config::glob_match;
+
+use super::package_json::{extract_dependencies, parse_file, ParseResult};
+use super::types::{Dependency, PackageJson};
+
+/// File name of the pnpm workspace manifest.
+pub const PNPM_WORKSPACE: &str = "pnpm-workspace.yaml";
+
+/// A monorepo root and its workspace packages.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// Root directory of the monorepo
+    pub root: PathBuf,
+    /// The root package.json
+    pub package: PackageJson,
+    /// Workspace packages, sorted by path
+    pub members: Vec<WorkspaceMember>,
+}
+
+/// A package of a workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    /// Package name, or the folder path if the package has no name
+    pub name: String,
+    /// Folder relative to the root, `/`-separated
+    pub path: String,
+    /// The member's package.json
+    pub package: PackageJson,
+}
+
+impl WorkspaceMember {
+    /// Returns every dependency declared in the member's package.json.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        extract_dependencies(&self.package)
+    }
+}
+
+impl Workspace {
+    /// Loads the workspace rooted at `root`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither the root package.json nor a `pnpm-workspace.yaml`
+    /// declares workspaces. Folders without a package.json are skipped;
+    /// unreadable manifests are errors.
+    pub fn load(root: &Path) -> ParseResult<Option<Self>> {
+        let package = parse_file(&root.join("package.json"))?;
+        let patterns = match package.workspace_patterns() {
+            [] => match fs::read_to_string(root.join(PNPM_WORKSPACE)) {
+                Ok(content) => pnpm_workspace_patterns(&content),
+                Err(_) => Vec::new(),
+            },
+            patterns => patterns.to_vec(),
+        };
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut members = Vec::new();
+        for path in expand_patterns(root, &patterns) {
+            let manifest = root.join(&path).join("package.json");
+            if !manifest.is_file() {
+                continue;
+            }
+            let member = parse_file(&manifest)?;
+            members.push(WorkspaceMember {
+                name: member.name.clone().unwrap_or_else(|| path.clone()),
+                path,
+                package: member,
+            });
+        }
+
+        Ok(Some(Self {
+            root: root.to_path_buf(),
+            package,
+            members,
+        }))
+    }
+
+    /// Returns the member with the given package name.
+    pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    /// Returns true if `name` is a workspace package rather than an
+    /// external dependency.
+    pub fn is_member(&self, name: &str) -> bool {
+        self.member(name).is_some()
+    }
+}
+
+/// Reads the `packages` list of a `pnpm-workspace.yaml`.
+///
+/// Only the block list form pnpm writes is understood; other keys such as
+/// `catalog` are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::parser::workspace::pnpm_workspace_patterns;
+///
+/// let yaml = "packages:\n  - 'apps/*'\n  - \"!**/test/**\"  # fixtures\n";
+/// assert_eq!(pnpm_workspace_patterns(yaml), vec!["apps/*", "!**/test/**"]);
+/// ```
+pub fn pnpm_workspace_patterns(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed.trim_end() == "packages:";
+            continue;
+        }
+        let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) else {
+            continue;
+        };
+        let item = item.trim();
+        let pattern = match item.chars().next() {
+            Some(quote @ ('\'' | '"')) => item[1..].split(quote).next().unwrap_or_default(),
+            _ => item.split(" #").next().unwrap_or_default().trim(),
+        };
+        if !pattern.is_empty() {
+            patterns.push(pattern.to_string());
+        }
+    }
+    patterns
+}
+
+/// Expands workspace patterns into folder paths relative to `root`,
+/// sorted and without duplicates.
+///
+/// `node_modules` folders are never matched.
+pub fn expand_patterns(root: &Path, patterns: &[String]) -> Vec<String> {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(pattern) => excluded.extend(expand_pattern(root, pattern)),
+            None => included.extend(expand_pattern(root, pattern)),
+        }
+    }
+
+    included.retain(|path| !excluded.contains(path));
+    included.sort();
+    included.dedup();
+    included
+}
+
+/// Expands one pattern, folder by folder.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<String> {
+    let mut paths = vec![String::new()];
+    let segments = pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".");
+
+    for segment in segments {
+        let mut next = Vec::new();
+        for path in &paths {
+            let dir = root.join(path);
+            if segment == "**" {
+                next.extend(
+                    // Walkdir reports link cycles as errors, which are skipped
+                    WalkDir::new(&dir)
+                        .follow_links(true)
+                        .into_iter()
+                        .filter_entry(|e| e.file_name() != "node_modules")
+                        .filter_map(Result::ok)
+                        .filter(|e| e.file_type().is_dir())
+                        .filter_map(|e| {
+                            let relative = e.path().strip_prefix(&dir).ok()?;
+                            let relative = relative.to_string_lossy().replace('\\', "/");
+                            Some(join(path, &relative))
+                        }),
+                );
+            } else if segment.contains('*') {
+                let Ok(entries) = fs::read_dir(&dir) else {
+                    continue;
+                };
+                next.extend(
+                    entries
+                        .filter_map(Result::ok)
+                        // Linked workspace folders count as folders
+                        .filter(|e| e.path().is_dir())
+                        .filter_map(|e| e.file_name().into_string().ok())
+                        .filter(|name| name != "node_modules" && glob_match(segment, name))
+                        .map(|name| join(path, &name)),
+                );
+            } else if dir.join(segment).is_dir() {
+                next.push(join(path, segment));
+            }
+        }
+        paths = next;
+    }
+
+    paths.retain(|path| !path.is_empty());
+    paths
+}
+
+fn join(parent: &str, child: &str) -> String {
+    match (parent.is_empty(), child.is_empty()) {
+        (true, _) => child.to_string(),
+        (_, true) => parent.to_string(),
+        _ => format!("{}/{}", parent, child),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Creates a monorepo with the given root manifest and member manifests.
+    pub(crate) fn create_workspace(name: &str, root: &str, members: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codescope-ws-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("package.json"), root).unwrap();
+        for (path, manifest) in members {
+            fs::create_dir_all(dir.join(path)).unwrap();
+            fs::write(dir.join(path).join("package.json"), manifest).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_load_workspace() {
+        let dir = create_workspace(
+            "load",
+            r#"{"name": "root", "workspaces": ["packages/*", "apps/**", "!packages/legacy"]}"#,
+            &[
+                ("packages/ui", r#"{"name": "@acme/ui"}"#),
+                ("packages/legacy", r#"{"name": "@acme/legacy"}"#),
+                ("apps/web/site", r#"{"name": "site"}"#),
+                ("apps/web", r#"{}"#),
+            ],
+        );
+        fs::create_dir_all(dir.join("packages/empty")).unwrap();
+        fs::create_dir_all(dir.join("apps/node_modules/dep")).unwrap();
+        fs::write(dir.join("apps/node_modules/dep/package.json"), "{}").unwrap();
+
+        let workspace = Workspace::load(&dir).unwrap().unwrap();
+        let members: Vec<(&str, &str)> = workspace
+            .members
+            .iter()
+            .map(|m| (m.path.as_str(), m.name.as_str()))
+            .collect();
+        assert_eq!(
+            members,
+            vec![("apps/web", "apps/web"), ("apps/web/site", "site"), ("packages/ui", "@acme/ui")]
+        );
+        assert!(workspace.is_member("@acme/ui"));
+        assert!(!workspace.is_member("@acme/legacy"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_pnpm_workspace() {
+        let dir = create_workspace(
+            "pnpm",
+            r#"{"name": "root"}"#,
+            &[("packages/ui", r#"{"name": "@acme/ui"}"#), ("tools/cli", r#"{"name": "cli"}"#)],
+        );
+        fs::write(
+            dir.join(PNPM_WORKSPACE),
+            "packages:\n- packages/*\n\ncatalog:\n  react: ^18.2.0\n  - tools/*\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(&dir).unwrap().unwrap();
+        let names: Vec<&str> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["@acme/ui"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_linked_members_and_link_cycles() {
+        use std::os::unix::fs::symlink;
+
+        let dir = create_workspace(
+            "links",
+            r#"{"name": "root", "workspaces": ["packages/*", "apps/**"]}"#,
+            &[("shared/ui", r#"{"name": "@acme/ui"}"#), ("apps/web", r#"{"name": "web"}"#)],
+        );
+        fs::create_dir_all(dir.join("packages")).unwrap();
+        symlink("../shared/ui", dir.join("packages/ui")).unwrap();
+        // A link back up the tree must not make `**` walk forever
+        symlink("..", dir.join("apps/web/parent")).unwrap();
+
+        let workspace = Workspace::load(&dir).unwrap().unwrap();
+        let members: Vec<(&str, &str)> = workspace
+            .members
+            .iter()
+            .map(|m| (m.path.as_str(), m.name.as_str()))
+            .collect();
+        assert_eq!(members, vec![("apps/web", "web"), ("packages/ui", "@acme/ui")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_not_a_workspace() {
+        let dir = create_workspace("none", r#"{"name": "app"}"#, &[]);
+        assert!(Workspace::load(&dir).unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,260 @@
+//! Extension point for custom policy checks.
+//!
+//! Organization-specific rules implement [`Check`] and are added to a
+//! [`CheckRegistry`], which runs them next to the built-in checks and
+//! records their findings in the same [`PolicyReport`]. Checks defined in
+//! `.codescoperc.json` run as external commands through
+//! [`CommandCheck`](super::CommandCheck).
+//!
+//! # Example
+//!
+//! ```rust
+//! use codescope::graph::{DependencyGraph, DependencyType};
+//! use codescope::policy::{Check, CheckContext, CheckRegistry, CheckResult, Finding, PolicyReport};
+//!
+//! /// Forbids packages from an internal blocklist.
+//! struct Blocklist(Vec<&'static str>);
+//!
+//! impl Check for Blocklist {
+//!     fn name(&self) -> &str {
+//!         "blocklist"
+//!     }
+//!
+//!     fn run(&self, ctx: &CheckContext<'_>) -> CheckResult<Vec<Finding>> {
+//!         Ok(self
+//!             .0
+//!             .iter()
+//!             .filter(|name| ctx.graph.get_node(name).is_some())
+//!             .map(|name| Finding::new("blocklist", format!("{} is not allowed", name), 7).with_packages([*name]))
+//!             .collect())
+//!     }
+//! }
+//!
+//! let mut graph = DependencyGraph::new();
+//! graph.add_dependency("left-pad", "1.3.0", DependencyType::Production);
+//!
+//! let registry = CheckRegistry::new().with_check(Blocklist(vec!["left-pad"]));
+//! let mut report = PolicyReport::new();
+//! registry.run(&CheckContext::new(&graph), &mut report);
+//!
+//! assert_eq!(report.findings()[0].message, "left-pad is not allowed");
+//! assert_eq!(report.exit_code(), 7);
+//! ```
+
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::analysis::exports::ProjectImports;
+use crate::bundle::webpack::BundleAnalysis;
+use crate::config::ProjectConfig;
+use crate::graph::DependencyGraph;
+
+use super::{CommandCheck, Finding, PolicyReport};
+
+/// Default exit code for findings of custom checks.
+pub const EXIT_CUSTOM: i32 = 7;
+
+/// Exit code when a custom check fails to run.
+pub const EXIT_CHECK_ERROR: i32 = 8;
+
+/// Errors that can occur while running a custom check.
+#[derive(Error, Debug)]
+pub enum CheckError {
+    /// The check's command could not be started.
+    #[error("Failed to run {program}: {source}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The check's command exited unsuccessfully.
+    #[error("Command exited with {status}: {stderr}")]
+    Failed { status: String, stderr: String },
+
+    /// The check's output is not a valid findings document.
+    #[error("Invalid check output: {0}")]
+    InvalidOutput(#[from] serde_json::Error),
+
+    /// Any other failure reported by a check.
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Result type alias for custom checks.
+pub type CheckResult<T> = Result<T, CheckError>;
+
+/// The analysis results a check can inspect.
+#[derive(Debug, Clone, Copy)]
+pub struct CheckContext<'a> {
+    /// The resolved dependency graph, with bundle sizes applied if known
+    pub graph: &'a DependencyGraph,
+    /// Bundle analysis from webpack stats, if available
+    pub bundle: Option<&'a BundleAnalysis>,
+    /// Import analysis of the project sources, if available
+    pub imports: Option<&'a ProjectImports>,
+    /// Name of the analyzed project
+    pub project_name: &'a str,
+}
+
+impl<'a> CheckContext<'a> {
+    /// Creates a context with only the dependency graph.
+    pub fn new(graph: &'a DependencyGraph) -> Self {
+        Self {
+            graph,
+            bundle: None,
+            imports: None,
+            project_name: "",
+        }
+    }
+
+    /// Adds the bundle analysis.
+    pub fn with_bundle(mut self, bundle: &'a BundleAnalysis) -> Self {
+        self.bundle = Some(bundle);
+        self
+    }
+
+    /// Adds the import analysis.
+    pub fn with_imports(mut self, imports: &'a ProjectImports) -> Self {
+        self.imports = Some(imports);
+        self
+    }
+
+    /// Sets the project name.
+    pub fn with_project_name(mut self, name: &'a str) -> Self {
+        self.project_name = name;
+        self
+    }
+}
+
+/// A policy check that can be added to a [`CheckRegistry`].
+pub trait Check {
+    /// Identifier shown next to the check's findings (e.g. `no-copyleft`).
+    fn name(&self) -> &str;
+
+    /// Message shown when the check finds nothing.
+    fn passed_message(&self) -> String {
+        format!("Check {} passed.", self.name())
+    }
+
+    /// Inspects the analysis results and returns the violations found.
+    fn run(&self, ctx: &CheckContext<'_>) -> CheckResult<Vec<Finding>>;
+}
+
+/// An ordered collection of custom checks.
+#[derive(Default)]
+pub struct CheckRegistry {
+    checks: Vec<Box<dyn Check>>,
+}
+
+impl CheckRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the command checks of the project
+    /// configuration, running from `project_dir`.
+    pub fn from_config(config: &ProjectConfig, project_dir: &Path) -> Self {
+        let mut registry = Self::new();
+        for check in &config.checks {
+            registry.register(CommandCheck::from_config(check, project_dir));
+        }
+        registry
+    }
+
+    /// Adds a check; checks run in the order they were added.
+    pub fn register(&mut self, check: impl Check + 'static) {
+        self.checks.push(Box::new(check));
+    }
+
+    /// Adds a check, builder style.
+    pub fn with_check(mut self, check: impl Check + 'static) -> Self {
+        self.register(check);
+        self
+    }
+
+    /// Returns the number of registered checks.
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    /// Returns true if no check is registered.
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Returns the names of the registered checks, in order.
+    pub fn names(&self) -> Vec<&str> {
+        self.checks.iter().map(|check| check.name()).collect()
+    }
+
+    /// Runs every check and records its outcome.
+    ///
+    /// A check that fails to run is recorded as a finding with
+    /// [`EXIT_CHECK_ERROR`], so a broken rule cannot pass silently.
+    pub fn run(&self, ctx: &CheckContext<'_>, report: &mut PolicyReport) {
+        for check in &self.checks {
+            let findings = match check.run(ctx) {
+                Ok(findings) => findings,
+                Err(e) => vec![Finding::new(
+                    check.name(),
+                    format!("Check could not run: {}", e),
+                    EXIT_CHECK_ERROR,
+                )],
+            };
+            report.record(check.name(), check.passed_message(), findings);
+        }
+    }
+}
+
+impl std::fmt::Debug for CheckRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckRegistry")
+            .field("checks", &self.names())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    struct Fixed(&'static str, CheckResult<Vec<Finding>>);
+
+    impl Check for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn run(&self, _ctx: &CheckContext<'_>) -> CheckResult<Vec<Finding>> {
+            match &self.1 {
+                Ok(findings) => Ok(findings.clone()),
+                Err(e) => Err(CheckError::Other(e.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_runs_checks_in_order() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+
+        let registry = CheckRegistry::new()
+            .with_check(Fixed("clean", Ok(Vec::new())))
+            .with_check(Fixed("broken", Err(CheckError::Other("boom".to_string()))))
+            .with_check(Fixed("strict", Ok(vec![Finding::new("strict", "nope", EXIT_CUSTOM)])));
+        assert_eq!(registry.names(), vec!["clean", "broken", "strict"]);
+
+        let mut report = PolicyReport::new();
+        registry.run(&CheckContext::new(&graph), &mut report);
+
+        assert_eq!(report.format_passed(), "✅ Check clean passed.\n");
+        assert_eq!(report.findings().len(), 2);
+        assert_eq!(report.findings()[0].message, "Check could not run: boom");
+        assert_eq!(report.exit_code(), EXIT_CHECK_ERROR);
+        assert_eq!(report.findings()[1].exit_code, EXIT_CUSTOM);
+    }
+}
@@ -0,0 +1,251 @@
+//! Custom checks implemented by external commands.
+//!
+//! The command receives the JSON export of the analysis (the document
+//! produced by `codescope export --format json`) on stdin and prints its
+//! findings to stdout:
+//!
+//! ```json
+//! {
+//!   "findings": [
+//!     {
+//!       "message": "left-pad is GPL-3.0",
+//!       "details": ["Copyleft licenses need legal review"],
+//!       "packages": ["left-pad"],
+//!       "exitCode": 7
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Only `message` is required; `exitCode` defaults to the check's exit
+//! code, [`EXIT_CUSTOM`] unless configured. Empty output means no
+//! findings. A non-zero exit status means the check itself failed, not
+//! that it found something.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+use crate::config::CheckConfig;
+use crate::export::{ExportReport, JsonExporter};
+
+use super::check::{Check, CheckContext, CheckError, CheckResult, EXIT_CUSTOM};
+use super::Finding;
+
+/// A check that runs an external command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandCheck {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    current_dir: Option<PathBuf>,
+    exit_code: i32,
+}
+
+/// Output document of a check command.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CommandOutput {
+    #[serde(default)]
+    findings: Vec<CommandFinding>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CommandFinding {
+    message: String,
+    #[serde(default)]
+    details: Vec<String>,
+    #[serde(default)]
+    packages: Vec<String>,
+    #[serde(default)]
+    exit_code: Option<i32>,
+}
+
+impl CommandCheck {
+    /// Creates a check that runs `program` without arguments.
+    pub fn new(name: impl Into<String>, program: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            program: program.into(),
+            args: Vec::new(),
+            current_dir: None,
+            exit_code: EXIT_CUSTOM,
+        }
+    }
+
+    /// Creates the check described by a `checks` entry of the project
+    /// configuration, running from `project_dir`.
+    pub fn from_config(config: &CheckConfig, project_dir: &Path) -> Self {
+        let (program, args) = config
+            .command
+            .split_first()
+            .map_or(("", &[][..]), |(program, args)| (program.as_str(), args));
+        let check = Self::new(&config.name, program)
+            .with_args(args.iter().cloned())
+            .with_current_dir(project_dir);
+        match config.exit_code {
+            Some(code) => check.with_exit_code(code),
+            None => check,
+        }
+    }
+
+    /// Sets the command-line arguments.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the working directory of the command.
+    pub fn with_current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the exit code for findings that do not set their own.
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+
+    /// Converts the command's stdout into findings.
+    fn parse_findings(&self, stdout: &str) -> CheckResult<Vec<Finding>> {
+        if stdout.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output: CommandOutput = serde_json::from_str(stdout)?;
+        Ok(output
+            .findings
+            .into_iter()
+            .map(|f| {
+                let mut finding = Finding::new(&self.name, f.message, f.exit_code.unwrap_or(self.exit_code))
+                    .with_packages(f.packages);
+                finding.details = f.details;
+                finding
+            })
+            .collect())
+    }
+}
+
+impl Check for CommandCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, ctx: &CheckContext<'_>) -> CheckResult<Vec<Finding>> {
+        let report = ExportReport::from_graph(ctx.project_name, "", ctx.graph);
+        let input = JsonExporter::new().with_pretty(false).export(&report);
+
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.spawn().map_err(|source| CheckError::Spawn {
+            program: self.program.clone(),
+            source,
+        })?;
+
+        // Feed stdin from a thread so a command that writes before it has
+        // read everything cannot deadlock on a full pipe. Commands that do
+        // not read the report at all close the pipe early, which is fine.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+        let output = child.wait_with_output().map_err(|source| CheckError::Spawn {
+            program: self.program.clone(),
+            source,
+        })?;
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(CheckError::Failed {
+                status: output.status.to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        self.parse_findings(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    #[test]
+    fn test_parse_findings() {
+        let check = CommandCheck::new("licenses", "true").with_exit_code(9);
+        assert!(check.parse_findings("  \n").unwrap().is_empty());
+
+        let findings = check
+            .parse_findings(
+                r#"{"findings": [
+                    {"message": "left-pad is GPL-3.0", "details": ["review"], "packages": ["left-pad"]},
+                    {"message": "warn only", "exitCode": 0}
+                ]}"#,
+            )
+            .unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].check, "licenses");
+        assert_eq!(findings[0].details, vec!["review"]);
+        assert_eq!(findings[0].packages, vec!["left-pad"]);
+        assert_eq!(findings[0].exit_code, 9);
+        assert_eq!(findings[1].exit_code, 0);
+
+        assert!(matches!(
+            check.parse_findings(r#"{"findings": [{"msg": "typo"}]}"#),
+            Err(CheckError::InvalidOutput(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = CheckConfig {
+            name: "lint".to_string(),
+            command: vec!["node".to_string(), "lint.js".to_string()],
+            exit_code: None,
+        };
+        let check = CommandCheck::from_config(&config, Path::new("/project"));
+        assert_eq!(
+            check,
+            CommandCheck::new("lint", "node")
+                .with_args(["lint.js"])
+                .with_current_dir("/project")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("left-pad", "1.3.0", DependencyType::Production);
+        let ctx = CheckContext::new(&graph);
+
+        // The report arrives on stdin and the findings are read from stdout
+        let check = CommandCheck::new("stdin", "sh").with_args([
+            "-c",
+            r#"grep -q '"left-pad"' && echo '{"findings": [{"message": "found left-pad"}]}'"#,
+        ]);
+        let findings = check.run(&ctx).unwrap();
+        assert_eq!(findings[0].message, "found left-pad");
+        assert_eq!(findings[0].exit_code, EXIT_CUSTOM);
+
+        let failing = CommandCheck::new("failing", "sh").with_args(["-c", "echo oops >&2; exit 3"]);
+        match failing.run(&ctx) {
+            Err(CheckError::Failed { stderr, .. }) => assert_eq!(stderr, "oops"),
+            other => panic!("expected failure, got {:?}", other),
+        }
+
+        let missing = CommandCheck::new("missing", "codescope-no-such-command");
+        assert!(matches!(missing.run(&ctx), Err(CheckError::Spawn { .. })));
+    }
+}
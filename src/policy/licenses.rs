@@ -0,0 +1,139 @@
+//! License policy of installed packages.
+//!
+//! Declared licenses are evaluated as SPDX expressions against the
+//! [`LicensePolicy`] in `.codescoperc.json`, so a package offered under
+//! `(GPL-3.0-only OR MIT)` passes a policy that denies GPL, while one under
+//! `GPL-3.0-only AND MIT` does not.
+
+use crate::analysis::licenses::LicenseReport;
+use crate::config::LicensePolicy;
+use crate::parser::spdx::LicenseExpression;
+
+use super::{Finding, PolicyReport};
+
+/// Exit code for packages whose license the policy does not allow.
+pub const EXIT_LICENSES: i32 = 13;
+
+/// Reports every installed package whose declared license the policy does
+/// not allow.
+///
+/// Packages without a declared license, or whose license is not a valid
+/// SPDX expression (e.g. `SEE LICENSE IN LICENSE.txt`), are only reported
+/// when the policy has an allow list, since a deny list cannot rule them
+/// out.
+///
+/// # Arguments
+///
+/// * `licenses` - Licenses of the installed packages
+/// * `policy` - Allowed and denied licenses
+/// * `report` - Report to record the outcome in
+pub fn check_licenses(licenses: &LicenseReport, policy: &LicensePolicy, report: &mut PolicyReport) {
+    let mut findings = Vec::new();
+    for package in &licenses.packages {
+        let declared = package.license.as_deref();
+        let Some(expression) = declared.and_then(LicenseExpression::parse) else {
+            if policy.allow.is_empty() {
+                continue;
+            }
+            let message = match declared {
+                Some(license) => format!(
+                    "{}@{} declares license '{}', which is not an SPDX expression",
+                    package.name, package.version, license
+                ),
+                None => format!("{}@{} declares no license", package.name, package.version),
+            };
+            findings.push(
+                Finding::new("licenses", message, EXIT_LICENSES)
+                    .with_detail("Check its license files by hand, e.g. with codescope licenses")
+                    .with_packages([package.name.as_str()]),
+            );
+            continue;
+        };
+        if policy.permits(&expression) {
+            continue;
+        }
+
+        let rejected: Vec<String> = expression
+            .terms()
+            .into_iter()
+            .filter(|term| !policy.accepts(term))
+            .map(|term| term.to_string())
+            .collect();
+        findings.push(
+            Finding::new(
+                "licenses",
+                format!(
+                    "{}@{} is licensed under '{}', which the license policy does not allow",
+                    package.name, package.version, expression
+                ),
+                EXIT_LICENSES,
+            )
+            .with_detail(format!("Not allowed: {}", rejected.join(", ")))
+            .with_packages([package.name.as_str()]),
+        );
+    }
+    report.record(
+        "licenses",
+        format!(
+            "{} installed packages are licensed as the policy allows.",
+            licenses.packages.len()
+        ),
+        findings,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::licenses::PackageLicense;
+    use crate::config::ProjectConfig;
+
+    fn licenses() -> LicenseReport {
+        let package = |name: &str, license: Option<&str>| PackageLicense {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            license: license.map(str::to_string),
+            texts: Vec::new(),
+        };
+        LicenseReport {
+            packages: vec![
+                package("dual", Some("(MIT OR GPL-3.0-only)")),
+                package("both", Some("MIT AND GPL-3.0-only")),
+                package("java-ish", Some("GPL-2.0-only WITH Classpath-exception-2.0")),
+                package("custom", Some("SEE LICENSE IN LICENSE.md")),
+                package("unknown", None),
+            ],
+            missing: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_dual_licenses_pass_deny_lists() {
+        let config = ProjectConfig::parse(r#"{"licenses": {"deny": ["GPL-3.0-only", "GPL-2.0-only"]}}"#).unwrap();
+        let mut report = PolicyReport::new();
+        check_licenses(&licenses(), &config.licenses, &mut report);
+
+        let flagged: Vec<&str> = report.findings().iter().flat_map(|f| &f.packages).map(String::as_str).collect();
+        assert_eq!(flagged, vec!["both", "java-ish"]);
+        assert_eq!(
+            report.findings()[0].message,
+            "both@1.0.0 is licensed under 'MIT AND GPL-3.0-only', which the license policy does not allow"
+        );
+        assert_eq!(report.findings()[0].details, vec!["Not allowed: GPL-3.0-only"]);
+        assert_eq!(report.exit_code(), EXIT_LICENSES);
+    }
+
+    #[test]
+    fn test_allow_list_flags_unknown_licenses() {
+        let config = ProjectConfig::parse(
+            r#"{"licenses": {"allow": ["MIT", "GPL-2.0-only WITH Classpath-exception-2.0"]}}"#,
+        )
+        .unwrap();
+        let mut report = PolicyReport::new();
+        check_licenses(&licenses(), &config.licenses, &mut report);
+
+        let flagged: Vec<&str> = report.findings().iter().flat_map(|f| &f.packages).map(String::as_str).collect();
+        assert_eq!(flagged, vec!["both", "custom", "unknown"]);
+        assert_eq!(report.findings()[2].message, "unknown@1.0.0 declares no license");
+    }
+}
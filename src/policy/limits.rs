@@ -0,0 +1,361 @@
+//! Dependency count and depth limits.
+//!
+//! Size thresholds catch heavy packages; these limits catch dependency
+//! trees that grow wide or deep one small package at a time. All counts
+//! are taken from the resolved graph, so transitive packages from the
+//! lockfile are included.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::analysis::depth::chain_to;
+use crate::bundle::webpack::{format_size, parse_size};
+use crate::graph::DependencyGraph;
+
+use super::{Finding, PolicyReport};
+
+/// Exit code when the total dependency count exceeds `--max-deps`.
+pub const EXIT_MAX_DEPS: i32 = 3;
+
+/// Exit code when the direct dependency count exceeds `--max-direct-deps`.
+pub const EXIT_MAX_DIRECT_DEPS: i32 = 4;
+
+/// Exit code when the dependency tree is deeper than `--max-depth`.
+pub const EXIT_MAX_DEPTH: i32 = 5;
+
+/// Exit code when the bundle is larger than `--max-size`.
+pub const EXIT_MAX_SIZE: i32 = 9;
+
+/// Exit code when the bundle grew more than `--max-increase`.
+pub const EXIT_MAX_INCREASE: i32 = 10;
+
+/// Upper bounds on the size and shape of the dependency graph.
+///
+/// Unset limits are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DependencyLimits {
+    /// Maximum number of packages in the graph, direct and transitive
+    pub max_deps: Option<usize>,
+    /// Maximum number of direct dependencies
+    pub max_direct_deps: Option<usize>,
+    /// Maximum depth, counting direct dependencies as depth 1
+    pub max_depth: Option<usize>,
+}
+
+impl DependencyLimits {
+    /// Creates limits with nothing checked.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum total number of packages.
+    pub fn with_max_deps(mut self, max: usize) -> Self {
+        self.max_deps = Some(max);
+        self
+    }
+
+    /// Sets the maximum number of direct dependencies.
+    pub fn with_max_direct_deps(mut self, max: usize) -> Self {
+        self.max_direct_deps = Some(max);
+        self
+    }
+
+    /// Sets the maximum dependency depth.
+    pub fn with_max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Returns true if no limit is set.
+    pub fn is_empty(&self) -> bool {
+        self.max_deps.is_none() && self.max_direct_deps.is_none() && self.max_depth.is_none()
+    }
+
+    /// Checks every set limit against the graph and records the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The resolved dependency graph
+    /// * `report` - Report to record one outcome per set limit in
+    pub fn evaluate(&self, graph: &DependencyGraph, report: &mut PolicyReport) {
+        if let Some(max) = self.max_deps {
+            let count = graph.node_count();
+            let findings = if count > max {
+                vec![Finding::new(
+                    "max-deps",
+                    format!("{} dependencies exceed the limit of {}", count, max),
+                    EXIT_MAX_DEPS,
+                )]
+            } else {
+                Vec::new()
+            };
+            report.record(
+                "max-deps",
+                format!("{} dependencies are within the limit of {}.", count, max),
+                findings,
+            );
+        }
+
+        if let Some(max) = self.max_direct_deps {
+            let count = graph.get_nodes_at_depth(0).len();
+            let findings = if count > max {
+                vec![Finding::new(
+                    "max-direct-deps",
+                    format!("{} direct dependencies exceed the limit of {}", count, max),
+                    EXIT_MAX_DIRECT_DEPS,
+                )]
+            } else {
+                Vec::new()
+            };
+            report.record(
+                "max-direct-deps",
+                format!("{} direct dependencies are within the limit of {}.", count, max),
+                findings,
+            );
+        }
+
+        if let Some(max) = self.max_depth {
+            let deepest = graph
+                .get_all_nodes()
+                .into_iter()
+                .max_by(|a, b| a.depth.cmp(&b.depth).then_with(|| b.name.cmp(&a.name)));
+            let depth = deepest.map_or(0, |node| node.depth + 1);
+            let findings = match deepest {
+                Some(node) if depth > max => {
+                    let chain = chain_to(graph, node);
+                    vec![Finding::new(
+                        "max-depth",
+                        format!("Dependency depth {} exceeds the limit of {}", depth, max),
+                        EXIT_MAX_DEPTH,
+                    )
+                    .with_detail(format!("Deepest chain: {}", chain.join(" -> ")))
+                    .with_packages(chain)]
+                }
+                _ => Vec::new(),
+            };
+            report.record(
+                "max-depth",
+                format!("Dependency depth {} is within the limit of {}.", depth, max),
+                findings,
+            );
+        }
+    }
+}
+
+/// A size limit given either in bytes or relative to a baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeThreshold {
+    /// An absolute size, e.g. `50KB`
+    Bytes(u64),
+    /// A percentage of the baseline, e.g. `5%`
+    Percent(f64),
+}
+
+impl SizeThreshold {
+    /// Returns the limit in bytes for a baseline size.
+    pub fn bytes_of(&self, baseline: u64) -> u64 {
+        match self {
+            SizeThreshold::Bytes(bytes) => *bytes,
+            SizeThreshold::Percent(percent) => (baseline as f64 * percent / 100.0).round() as u64,
+        }
+    }
+}
+
+impl fmt::Display for SizeThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeThreshold::Bytes(bytes) => write!(f, "{}", format_size(*bytes)),
+            SizeThreshold::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+impl FromStr for SizeThreshold {
+    type Err = String;
+
+    /// Parses `5%` as a percentage and anything else with [`parse_size`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().strip_suffix('%') {
+            Some(number) => number
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|percent| percent.is_finite() && *percent >= 0.0)
+                .map(SizeThreshold::Percent)
+                .ok_or_else(|| format!("invalid percentage '{}': expected e.g. 5%", s)),
+            None => parse_size(s).map(SizeThreshold::Bytes),
+        }
+    }
+}
+
+/// Upper bounds on the bundle size, absolute and compared with a
+/// baseline build.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BundleLimits {
+    /// Maximum bundle size in bytes
+    pub max_size: Option<u64>,
+    /// Maximum growth over the baseline
+    pub max_increase: Option<SizeThreshold>,
+    /// Bundle size of the baseline build, needed for `max_increase`
+    pub baseline_size: Option<u64>,
+}
+
+impl BundleLimits {
+    /// Returns true if no limit is set.
+    pub fn is_empty(&self) -> bool {
+        self.max_size.is_none() && self.max_increase.is_none()
+    }
+
+    /// Checks every set limit against the bundle size and records the
+    /// results.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The bundle size in bytes
+    /// * `report` - Report to record one outcome per set limit in
+    pub fn evaluate(&self, size: u64, report: &mut PolicyReport) {
+        if let Some(max) = self.max_size {
+            let findings = if size > max {
+                vec![Finding::new(
+                    "max-size",
+                    format!("Bundle size {} exceeds the limit of {}", format_size(size), format_size(max)),
+                    EXIT_MAX_SIZE,
+                )]
+            } else {
+                Vec::new()
+            };
+            report.record(
+                "max-size",
+                format!("Bundle size {} is within the limit of {}.", format_size(size), format_size(max)),
+                findings,
+            );
+        }
+
+        if let (Some(threshold), Some(baseline)) = (self.max_increase, self.baseline_size) {
+            let increase = size.saturating_sub(baseline);
+            let limit = threshold.bytes_of(baseline);
+            let growth = format!("Bundle grew by {} (from {})", format_size(increase), format_size(baseline));
+            let findings = if increase > limit {
+                vec![Finding::new(
+                    "max-increase",
+                    format!("{}, over the limit of {}", growth, threshold),
+                    EXIT_MAX_INCREASE,
+                )]
+            } else {
+                Vec::new()
+            };
+            report.record("max-increase", format!("{}, within the limit of {}.", growth, threshold), findings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    /// app deps: react -> loose-envify -> js-tokens, lodash
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency_with_depth("react", "18.2.0", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("lodash", "4.17.21", DependencyType::Production, 0);
+        graph.add_dependency_with_depth("loose-envify", "1.4.0", DependencyType::Production, 1);
+        graph.add_dependency_with_depth("js-tokens", "4.0.0", DependencyType::Production, 2);
+        graph.add_edge("react", "loose-envify");
+        graph.add_edge("loose-envify", "js-tokens");
+        graph
+    }
+
+    #[test]
+    fn test_size_threshold_parsing() {
+        assert_eq!("5%".parse(), Ok(SizeThreshold::Percent(5.0)));
+        assert_eq!("1.5MB".parse(), Ok(SizeThreshold::Bytes(1572864)));
+        assert_eq!(
+            "-2%".parse::<SizeThreshold>(),
+            Err("invalid percentage '-2%': expected e.g. 5%".to_string())
+        );
+        assert_eq!(SizeThreshold::Percent(5.0).bytes_of(200_000), 10_000);
+        assert_eq!(SizeThreshold::Bytes(2048).to_string(), "2.00 KB");
+    }
+
+    #[test]
+    fn test_bundle_limits() {
+        let limits = BundleLimits {
+            max_size: Some(1024 * 1024),
+            max_increase: Some(SizeThreshold::Percent(5.0)),
+            baseline_size: Some(900 * 1024),
+        };
+
+        let mut report = PolicyReport::new();
+        limits.evaluate(920 * 1024, &mut report);
+        assert!(report.passed());
+        assert_eq!(
+            report.format_passed(),
+            "✅ Bundle size 920.00 KB is within the limit of 1.00 MB.\n\
+             ✅ Bundle grew by 20.00 KB (from 900.00 KB), within the limit of 5%.\n"
+        );
+
+        let mut report = PolicyReport::new();
+        limits.evaluate(1100 * 1024, &mut report);
+        assert_eq!(report.findings().len(), 2);
+        assert_eq!(report.findings()[1].message, "Bundle grew by 200.00 KB (from 900.00 KB), over the limit of 5%");
+        assert_eq!(report.exit_code(), EXIT_MAX_SIZE);
+    }
+
+    #[test]
+    fn test_no_limits_records_nothing() {
+        let limits = DependencyLimits::new();
+        assert!(limits.is_empty());
+
+        let mut report = PolicyReport::new();
+        limits.evaluate(&graph(), &mut report);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_limits_within_bounds() {
+        let limits = DependencyLimits::new()
+            .with_max_deps(4)
+            .with_max_direct_deps(2)
+            .with_max_depth(3);
+
+        let mut report = PolicyReport::new();
+        limits.evaluate(&graph(), &mut report);
+        assert!(report.passed());
+        assert_eq!(
+            report.format_passed(),
+            "✅ 4 dependencies are within the limit of 4.\n\
+             ✅ 2 direct dependencies are within the limit of 2.\n\
+             ✅ Dependency depth 3 is within the limit of 3.\n"
+        );
+    }
+
+    #[test]
+    fn test_each_limit_has_its_own_exit_code() {
+        let graph = graph();
+        let cases = [
+            (DependencyLimits::new().with_max_deps(3), "max-deps", EXIT_MAX_DEPS),
+            (DependencyLimits::new().with_max_direct_deps(1), "max-direct-deps", EXIT_MAX_DIRECT_DEPS),
+            (DependencyLimits::new().with_max_depth(2), "max-depth", EXIT_MAX_DEPTH),
+        ];
+        for (limits, check, code) in cases {
+            let mut report = PolicyReport::new();
+            limits.evaluate(&graph, &mut report);
+            assert_eq!(report.findings().len(), 1, "{}", check);
+            assert_eq!(report.findings()[0].check, check);
+            assert_eq!(report.exit_code(), code);
+        }
+    }
+
+    #[test]
+    fn test_max_depth_shows_deepest_chain() {
+        let mut report = PolicyReport::new();
+        DependencyLimits::new()
+            .with_max_depth(1)
+            .evaluate(&graph(), &mut report);
+
+        let finding = &report.findings()[0];
+        assert_eq!(finding.message, "Dependency depth 3 exceeds the limit of 1");
+        assert_eq!(finding.details, vec!["Deepest chain: react -> loose-envify -> js-tokens"]);
+    }
+}
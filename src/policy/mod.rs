@@ -0,0 +1,618 @@
+//! Policy checks for CI usage
+//!
+//! Each check inspects the resolved dependency graph and reports
+//! [`Finding`]s. A [`PolicyReport`] collects the findings of every check
+//! that ran, prints them as one list and decides the process exit code, so
+//! several checks can be combined in a single invocation.
+//!
+//! # Exit codes
+//!
+//! | Check              | Code |
+//! |--------------------|------|
+//! | `cycles`           | 1    |
+//! | `conflicts`        | 1    |
+//! | `max-deps`         | 3    |
+//! | `max-direct-deps`  | 4    |
+//! | `max-depth`        | 5    |
+//! | `group-budget`     | 6    |
+//! | custom checks      | 7, unless a finding sets its own |
+//! | custom check error | 8    |
+//! | `max-size`         | 9    |
+//! | `max-increase`     | 10   |
+//! | `pinning`          | 11   |
+//! | `prerelease`       | 12   |
+//! | `zero-major`       | 12   |
+//! | `licenses`         | 13   |
+//! | `savings`          | 14   |
+//!
+//! When several checks fail, the code of the first failing check wins.
+//! Custom checks are added through the [`Check`] trait; see [`check`].
+//!
+//! Findings of a check can be [downgraded](PolicyReport::downgrade) to
+//! warnings, which are reported but never fail the run, so a check can be
+//! introduced in CI before it is enforced.
+//!
+//! A failed run can be announced to a chat channel through a webhook; see
+//! [`notify`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use codescope::graph::{DependencyGraph, DependencyType};
+//! use codescope::policy::{DependencyLimits, PolicyReport};
+//!
+//! let mut graph = DependencyGraph::new();
+//! graph.add_dependency("react", "18.2.0", DependencyType::Production);
+//! graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+//!
+//! let limits = DependencyLimits::new().with_max_direct_deps(1);
+//! let mut report = PolicyReport::new();
+//! limits.evaluate(&graph, &mut report);
+//!
+//! assert_eq!(report.findings().len(), 1);
+//! assert_eq!(report.exit_code(), 4);
+//! ```
+
+pub mod check;
+pub mod command;
+pub mod licenses;
+pub mod limits;
+pub mod notify;
+pub mod pinning;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::groups::GroupSummary;
+use crate::analysis::prerelease::{Instability, PrereleaseReport};
+use crate::bundle::savings::SavingsReport;
+use crate::bundle::webpack::format_size;
+use crate::config::ProjectConfig;
+use crate::graph::DependencyGraph;
+
+pub use check::{
+    Check, CheckContext, CheckError, CheckRegistry, CheckResult, EXIT_CHECK_ERROR, EXIT_CUSTOM,
+};
+pub use command::CommandCheck;
+pub use licenses::{check_licenses, EXIT_LICENSES};
+pub use limits::{BundleLimits, DependencyLimits, SizeThreshold};
+pub use pinning::{check_pinning, pinning_of, EXIT_PINNING};
+
+/// A single policy violation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    /// Identifier of the check that produced the finding (e.g. `max-deps`)
+    pub check: String,
+    /// One-line description of the violation
+    pub message: String,
+    /// Additional lines shown under the message
+    pub details: Vec<String>,
+    /// Process exit code used when this finding fails the run
+    pub exit_code: i32,
+    /// Packages the finding is about, used to route it to their owners
+    pub packages: Vec<String>,
+    /// Owners of `packages`, once assigned with [`PolicyReport::assign_owners`]
+    pub owners: Vec<String>,
+}
+
+impl Finding {
+    /// Creates a finding without details.
+    pub fn new(check: impl Into<String>, message: impl Into<String>, exit_code: i32) -> Self {
+        Self {
+            check: check.into(),
+            message: message.into(),
+            details: Vec::new(),
+            exit_code,
+            packages: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    /// Sets the packages the finding is about.
+    pub fn with_packages(mut self, packages: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.packages = packages.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a detail line.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.details.push(detail.into());
+        self
+    }
+}
+
+/// The outcome of one check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CheckOutcome {
+    check: String,
+    /// Message printed when the check passes
+    passed: String,
+}
+
+/// Collected results of the policy checks that ran.
+#[derive(Debug, Clone, Default)]
+pub struct PolicyReport {
+    outcomes: Vec<CheckOutcome>,
+    findings: Vec<Finding>,
+    warnings: Vec<Finding>,
+}
+
+impl PolicyReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `check` ran and produced `findings`.
+    ///
+    /// `passed` is shown instead when there are no findings.
+    pub fn record(&mut self, check: impl Into<String>, passed: impl Into<String>, findings: Vec<Finding>) {
+        self.outcomes.push(CheckOutcome {
+            check: check.into(),
+            passed: passed.into(),
+        });
+        self.findings.extend(findings);
+    }
+
+    /// Returns true if no check has run.
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    /// Returns true if `check` ran.
+    pub fn ran(&self, check: &str) -> bool {
+        self.outcomes.iter().any(|outcome| outcome.check == check)
+    }
+
+    /// Turns the findings of `check` into warnings, which are reported but
+    /// do not fail the run.
+    pub fn downgrade(&mut self, check: &str) {
+        let (warnings, findings): (Vec<Finding>, Vec<Finding>) = std::mem::take(&mut self.findings)
+            .into_iter()
+            .partition(|finding| finding.check == check);
+        self.findings = findings;
+        self.warnings.extend(warnings);
+    }
+
+    /// Turns every finding into a warning, so the run always passes.
+    pub fn downgrade_all(&mut self) {
+        self.warnings.append(&mut self.findings);
+    }
+
+    /// Resolves the owners of every finding from its packages.
+    pub fn assign_owners(&mut self, config: &ProjectConfig) {
+        for finding in self.findings.iter_mut().chain(&mut self.warnings) {
+            let mut owners: Vec<String> = finding
+                .packages
+                .iter()
+                .flat_map(|pkg| config.owners_of(pkg).iter().cloned())
+                .collect();
+            owners.sort();
+            owners.dedup();
+            finding.owners = owners;
+        }
+    }
+
+    /// Keeps only the findings routed to `owner`.
+    ///
+    /// Findings that are not about specific packages have no owner and are
+    /// dropped. Checks left without findings count as passed for the owner.
+    /// Call [`assign_owners`](Self::assign_owners) first.
+    pub fn retain_owner(&mut self, owner: &str) {
+        self.findings
+            .retain(|finding| finding.owners.iter().any(|o| o == owner));
+        self.warnings
+            .retain(|finding| finding.owners.iter().any(|o| o == owner));
+    }
+
+    /// Returns every finding, in the order the checks ran.
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Returns the downgraded findings.
+    pub fn warnings(&self) -> &[Finding] {
+        &self.warnings
+    }
+
+    /// Returns true if every check passed.
+    pub fn passed(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns the process exit code: 0 if every check passed, otherwise
+    /// the code of the first finding.
+    pub fn exit_code(&self) -> i32 {
+        self.findings.first().map_or(0, |f| f.exit_code)
+    }
+
+    /// Formats the pass messages of clean checks, one per line.
+    pub fn format_passed(&self) -> String {
+        let mut out = String::new();
+        for outcome in &self.outcomes {
+            if !self.findings.iter().chain(&self.warnings).any(|f| f.check == outcome.check) {
+                out.push_str(&format!("✅ {}\n", outcome.passed));
+            }
+        }
+        out
+    }
+
+    /// Formats the findings list, or an empty string if there are none.
+    pub fn format_findings(&self) -> String {
+        if self.findings.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("❌ Policy checks failed!\n\n");
+        format_list(&self.findings, &mut out);
+        out.push_str(&format!("\nFound {} policy violation(s).\n", self.findings.len()));
+        out
+    }
+
+    /// Formats the warnings list, or an empty string if there are none.
+    pub fn format_warnings(&self) -> String {
+        if self.warnings.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("⚠️  Policy warnings (not enforced):\n\n");
+        format_list(&self.warnings, &mut out);
+        out.push_str(&format!("\nFound {} policy warning(s).\n", self.warnings.len()));
+        out
+    }
+}
+
+/// Appends findings with their details and owners to `out`.
+fn format_list(findings: &[Finding], out: &mut String) {
+    for finding in findings {
+        out.push_str(&format!("  [{}] {}\n", finding.check, finding.message));
+        for detail in &finding.details {
+            out.push_str(&format!("      {}\n", detail));
+        }
+        if !finding.owners.is_empty() {
+            out.push_str(&format!("      Owners: {}\n", finding.owners.join(", ")));
+        }
+    }
+}
+
+/// Exit code for circular dependency findings.
+pub const EXIT_CYCLES: i32 = 1;
+
+/// Exit code for version conflict findings.
+pub const EXIT_CONFLICTS: i32 = 1;
+
+/// Reports every cycle in the graph, with its suggested break.
+///
+/// With `production_only`, only cycles between production dependencies count.
+pub fn check_cycles(graph: &DependencyGraph, production_only: bool, report: &mut PolicyReport) {
+    let (cycles, scope) = if production_only {
+        (graph.get_production_cycle_details(), " production")
+    } else {
+        (graph.get_cycle_details(), "")
+    };
+
+    let findings = cycles
+        .iter()
+        .map(|cycle| {
+            let finding = Finding::new("cycles", format!("Cycle: {}", cycle.cycle_path()), EXIT_CYCLES)
+                .with_packages(&cycle.nodes);
+            match &cycle.suggested_break {
+                Some(fix) => finding.with_detail(format!("Suggested fix: {}", fix)),
+                None => finding,
+            }
+        })
+        .collect();
+    report.record("cycles", format!("No circular{} dependencies detected.", scope), findings);
+}
+
+/// Exit code for package groups over their budget.
+pub const EXIT_GROUP_BUDGET: i32 = 6;
+
+/// Reports every package group that exceeds its size or count budget.
+///
+/// Size budgets only count packages with a known bundle size.
+pub fn check_group_budgets(groups: &[GroupSummary], report: &mut PolicyReport) {
+    let mut findings = Vec::new();
+    for group in groups {
+        if group.over_size_budget() {
+            findings.push(
+                Finding::new(
+                    "group-budget",
+                    format!(
+                        "Group {} is {}, over its budget of {} KB",
+                        group.name,
+                        format_size(group.bundle_size),
+                        group.max_size_kb.unwrap_or_default()
+                    ),
+                    EXIT_GROUP_BUDGET,
+                )
+                .with_packages(&group.packages),
+            );
+        }
+        if group.over_count_budget() {
+            findings.push(
+                Finding::new(
+                    "group-budget",
+                    format!(
+                        "Group {} has {} packages, over its budget of {}",
+                        group.name,
+                        group.count(),
+                        group.max_count.unwrap_or_default()
+                    ),
+                    EXIT_GROUP_BUDGET,
+                )
+                .with_detail(group.packages.join(", "))
+                .with_packages(&group.packages),
+            );
+        }
+    }
+
+    let budgeted = groups.iter().filter(|g| g.has_budget()).count();
+    report.record(
+        "group-budget",
+        format!("{} package group(s) are within budget.", budgeted),
+        findings,
+    );
+}
+
+/// Exit code for production packages at pre-release or 0.x versions.
+pub const EXIT_PRERELEASE: i32 = 12;
+
+/// Reports every production package installed at an unstable version.
+///
+/// Pre-release tags are reported by the `prerelease` check and 0.x
+/// versions by `zero-major`, so the latter can be downgraded to warnings
+/// on its own.
+pub fn check_prereleases(graph: &DependencyGraph, report: &mut PolicyReport) {
+    let unstable = PrereleaseReport::from_graph(graph);
+    for (check, instability, passed) in [
+        ("prerelease", Instability::PreRelease, "No production dependencies at pre-release versions."),
+        ("zero-major", Instability::ZeroMajor, "No production dependencies at 0.x versions."),
+    ] {
+        let findings = unstable
+            .production()
+            .filter(|package| package.instability == instability)
+            .map(|package| {
+                let via = if package.direct { "" } else { " (transitive)" };
+                Finding::new(
+                    check,
+                    format!("{}@{} is a {} version{}", package.name, package.version, instability.label(), via),
+                    EXIT_PRERELEASE,
+                )
+                .with_packages([&package.name])
+            })
+            .collect();
+        report.record(check, passed, findings);
+    }
+}
+
+/// Exit code for potential bundle savings over the threshold.
+pub const EXIT_SAVINGS: i32 = 14;
+
+/// Reports potential bundle savings above `threshold` bytes, naming the
+/// packages they come from.
+pub fn check_savings(savings: &SavingsReport, threshold: u64, report: &mut PolicyReport) {
+    let total = savings.summary.total_potential_savings;
+    let findings = if total > threshold {
+        let packages: Vec<&str> = savings.savings_by_size().iter().map(|s| s.package_name.as_str()).collect();
+        let finding = Finding::new(
+            "savings",
+            format!(
+                "Potential savings ({}) exceed threshold ({})",
+                savings.summary.format_total_savings(),
+                format_size(threshold)
+            ),
+            EXIT_SAVINGS,
+        );
+        vec![finding.with_detail(packages.join(", ")).with_packages(packages)]
+    } else {
+        Vec::new()
+    };
+    report.record(
+        "savings",
+        format!(
+            "Potential savings ({}) are within threshold ({}).",
+            savings.summary.format_total_savings(),
+            format_size(threshold)
+        ),
+        findings,
+    );
+}
+
+/// Reports every package required at conflicting versions.
+pub fn check_conflicts(graph: &DependencyGraph, report: &mut PolicyReport) {
+    let findings = graph
+        .detect_version_conflicts()
+        .iter()
+        .map(|conflict| {
+            Finding::new("conflicts", conflict.description(), EXIT_CONFLICTS)
+                .with_packages([&conflict.package_name])
+        })
+        .collect();
+    report.record("conflicts", "No version conflicts detected.", findings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+
+    #[test]
+    fn test_empty_report_passes() {
+        let report = PolicyReport::new();
+        assert!(report.is_empty());
+        assert!(report.passed());
+        assert_eq!(report.exit_code(), 0);
+        assert_eq!(report.format_findings(), "");
+    }
+
+    #[test]
+    fn test_first_failing_check_sets_exit_code() {
+        let mut report = PolicyReport::new();
+        report.record("a", "a passed", vec![]);
+        report.record("b", "b passed", vec![Finding::new("b", "too big", 3).with_detail("limit 1")]);
+        report.record("c", "c passed", vec![Finding::new("c", "too deep", 5)]);
+
+        assert!(!report.passed());
+        assert_eq!(report.exit_code(), 3);
+        assert_eq!(report.format_passed(), "✅ a passed\n");
+        assert_eq!(
+            report.format_findings(),
+            "❌ Policy checks failed!\n\n  [b] too big\n      limit 1\n  [c] too deep\n\nFound 2 policy violation(s).\n"
+        );
+    }
+
+    #[test]
+    fn test_downgraded_findings_do_not_fail() {
+        let mut report = PolicyReport::new();
+        report.record("max-deps", "ok", vec![Finding::new("max-deps", "Too many", 3)]);
+        report.record("max-depth", "ok", vec![Finding::new("max-depth", "Too deep", 5)]);
+        assert!(report.ran("max-deps"));
+        assert!(!report.ran("cycles"));
+
+        report.downgrade("max-deps");
+        assert_eq!(report.exit_code(), 5);
+        assert_eq!(report.warnings()[0].message, "Too many");
+        assert_eq!(report.format_passed(), "", "a downgraded check did not pass");
+        assert_eq!(
+            report.format_warnings(),
+            "⚠️  Policy warnings (not enforced):\n\n  [max-deps] Too many\n\nFound 1 policy warning(s).\n"
+        );
+
+        report.downgrade_all();
+        assert!(report.passed());
+        assert_eq!(report.exit_code(), 0);
+        assert_eq!(report.warnings().len(), 2);
+        assert_eq!(report.format_findings(), "");
+    }
+
+    #[test]
+    fn test_cycle_and_conflict_checks() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency("b", "1.0.0", DependencyType::Development);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+
+        let mut report = PolicyReport::new();
+        check_cycles(&graph, true, &mut report);
+        check_conflicts(&graph, &mut report);
+        assert!(report.passed());
+        assert!(report.format_passed().contains("No circular production dependencies detected."));
+
+        check_cycles(&graph, false, &mut report);
+        assert_eq!(report.findings().len(), 1);
+        assert_eq!(report.findings()[0].message, "Cycle: a -> b -> a");
+        assert_eq!(report.exit_code(), EXIT_CYCLES);
+        assert_eq!(report.findings()[0].packages, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_prerelease_checks() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "19.0.0-rc.1", DependencyType::Production);
+        graph.add_dependency("vitest", "2.0.0-beta.3", DependencyType::Development);
+        graph.add_dependency_with_depth("nanoid", "0.4.1", DependencyType::Production, 1);
+
+        let mut report = PolicyReport::new();
+        check_prereleases(&graph, &mut report);
+        let messages: Vec<(&str, &str)> = report
+            .findings()
+            .iter()
+            .map(|f| (f.check.as_str(), f.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("prerelease", "react@19.0.0-rc.1 is a pre-release version"),
+                ("zero-major", "nanoid@0.4.1 is a 0.x version (transitive)"),
+            ]
+        );
+        assert_eq!(report.exit_code(), EXIT_PRERELEASE);
+
+        report.downgrade("zero-major");
+        assert_eq!(report.findings().len(), 1);
+    }
+
+    #[test]
+    fn test_assign_owners() {
+        let config = ProjectConfig::parse(r#"{"owners": ["a @web", "b @platform @web"]}"#).unwrap();
+        let mut report = PolicyReport::new();
+        report.record(
+            "cycles",
+            "ok",
+            vec![Finding::new("cycles", "Cycle: a -> b -> a", EXIT_CYCLES).with_packages(["a", "b"])],
+        );
+        report.assign_owners(&config);
+
+        assert_eq!(report.findings()[0].owners, vec!["@platform", "@web"]);
+        assert!(report.format_findings().contains("      Owners: @platform, @web\n"));
+
+        report.record("max-deps", "ok", vec![Finding::new("max-deps", "Too many", 3)]);
+        report.record("conflicts", "No conflicts", Vec::new());
+        report.retain_owner("@web");
+        assert_eq!(report.findings().len(), 1);
+        assert_eq!(report.format_passed(), "✅ ok\n✅ No conflicts\n");
+
+        report.retain_owner("@data");
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn test_group_budgets() {
+        let group = GroupSummary {
+            name: "polyfills".to_string(),
+            packages: vec!["core-js".to_string(), "whatwg-fetch".to_string()],
+            bundle_size: 200 * 1024,
+            sized_packages: 2,
+            max_size_kb: Some(100),
+            max_count: Some(1),
+        };
+
+        let mut report = PolicyReport::new();
+        check_group_budgets(std::slice::from_ref(&group), &mut report);
+        assert_eq!(report.findings().len(), 2);
+        assert_eq!(report.findings()[0].message, "Group polyfills is 200.00 KB, over its budget of 100 KB");
+        assert_eq!(report.findings()[1].details, vec!["core-js, whatwg-fetch"]);
+        assert_eq!(report.exit_code(), EXIT_GROUP_BUDGET);
+
+        let within = GroupSummary {
+            max_size_kb: Some(300),
+            max_count: None,
+            ..group
+        };
+        let mut report = PolicyReport::new();
+        check_group_budgets(&[within], &mut report);
+        assert_eq!(report.format_passed(), "✅ 1 package group(s) are within budget.\n");
+    }
+
+    #[test]
+    fn test_savings_threshold() {
+        use crate::bundle::savings::{PackageSavings, SavingsCategory};
+
+        let mut savings = SavingsReport::default();
+        for (name, size) in [("moment", 60 * 1024), ("lodash", 20 * 1024)] {
+            savings.add(PackageSavings {
+                package_name: name.to_string(),
+                current_size: size,
+                potential_savings: size,
+                category: SavingsCategory::Unused,
+                utilization_percentage: None,
+                exports_used: 0,
+                total_exports: None,
+                suggestion: String::new(),
+                alternative: None,
+            });
+        }
+
+        let mut report = PolicyReport::new();
+        check_savings(&savings, 50 * 1024, &mut report);
+        assert_eq!(report.findings()[0].message, "Potential savings (80.00 KB) exceed threshold (50.00 KB)");
+        assert_eq!(report.findings()[0].packages, vec!["moment", "lodash"]);
+        assert_eq!(report.exit_code(), EXIT_SAVINGS);
+
+        let mut report = PolicyReport::new();
+        check_savings(&savings, 100 * 1024, &mut report);
+        assert!(report.passed());
+        assert_eq!(report.format_passed(), "✅ Potential savings (80.00 KB) are within threshold (100.00 KB).\n");
+    }
+}
@@ -0,0 +1,182 @@
+//! Webhook notifications of failed policy checks
+//!
+//! With `--notify`, a CI run whose checks fail POSTs a summary of the
+//! findings to a webhook, so the channel watching the project hears about
+//! it without anyone reading the job log. The URL comes from the
+//! `CODESCOPE_WEBHOOK_URL` environment variable or `notify.webhookUrl` in
+//! `.codescoperc.json`; since a webhook URL is a credential, CI jobs should
+//! prefer the variable, set from a secret.
+//!
+//! Two payloads are supported:
+//!
+//! - `json`: the project, exit code and findings, for a custom receiver
+//! - `slack`: a Slack incoming-webhook message listing the first findings
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+
+use super::PolicyReport;
+
+/// Environment variable holding the webhook URL, preferred over the
+/// configuration file
+pub const WEBHOOK_URL_VAR: &str = "CODESCOPE_WEBHOOK_URL";
+
+/// Most findings listed in a Slack message; Slack truncates long sections
+const SLACK_FINDINGS: usize = 10;
+
+/// Payload format of a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyFormat {
+    /// Plain JSON document of the findings
+    Json,
+    /// Slack incoming-webhook message
+    Slack,
+}
+
+impl fmt::Display for NotifyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyFormat::Json => write!(f, "json"),
+            NotifyFormat::Slack => write!(f, "slack"),
+        }
+    }
+}
+
+impl FromStr for NotifyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(NotifyFormat::Json),
+            "slack" => Ok(NotifyFormat::Slack),
+            other => Err(format!("unknown notification format '{}' (expected: json, slack)", other)),
+        }
+    }
+}
+
+/// Returns the body to POST for the findings of `report` about `project`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::policy::notify::{payload, NotifyFormat};
+/// use codescope::policy::{Finding, PolicyReport};
+///
+/// let mut report = PolicyReport::new();
+/// report.record("max-deps", "Within limits.", vec![Finding::new("max-deps", "120 packages, over 100", 3)]);
+///
+/// let body = payload(&report, "my-app", NotifyFormat::Json);
+/// assert_eq!(body["exit_code"], 3);
+/// assert_eq!(body["findings"][0]["message"], "120 packages, over 100");
+/// ```
+pub fn payload(report: &PolicyReport, project: &str, format: NotifyFormat) -> Value {
+    match format {
+        NotifyFormat::Json => json!({
+            "project": project,
+            "passed": report.passed(),
+            "exit_code": report.exit_code(),
+            "findings": report.findings(),
+            "warning_count": report.warnings().len(),
+        }),
+        NotifyFormat::Slack => slack_message(report, project),
+    }
+}
+
+fn slack_message(report: &PolicyReport, project: &str) -> Value {
+    let findings = report.findings();
+    let title = format!(
+        "codescope: {} policy violation(s) in {}",
+        findings.len(),
+        project
+    );
+    let mut list: Vec<String> = findings
+        .iter()
+        .take(SLACK_FINDINGS)
+        .map(|finding| {
+            let mut line = format!("• *[{}]* {}", finding.check, escape(&finding.message));
+            if !finding.owners.is_empty() {
+                line.push_str(&format!(" ({})", escape(&finding.owners.join(", "))));
+            }
+            line
+        })
+        .collect();
+    if findings.len() > SLACK_FINDINGS {
+        list.push(format!("…and {} more", findings.len() - SLACK_FINDINGS));
+    }
+
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": format!("❌ {}", title) },
+    })];
+    if !list.is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": list.join("\n") },
+        }));
+    }
+    if !report.warnings().is_empty() {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{
+                "type": "mrkdwn",
+                "text": format!("{} warning(s) not enforced", report.warnings().len()),
+            }],
+        }));
+    }
+    // `text` is the fallback shown in notifications
+    json!({ "text": title, "blocks": blocks })
+}
+
+/// Escapes the characters Slack's mrkdwn treats as control characters.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::Finding;
+
+    fn failed_report(count: usize) -> PolicyReport {
+        let mut report = PolicyReport::new();
+        let findings = (0..count)
+            .map(|i| Finding::new("cycles", format!("Cycle: a{} -> b -> a{}", i, i), 1))
+            .collect();
+        report.record("cycles", "No circular dependencies detected.", findings);
+        report
+    }
+
+    #[test]
+    fn test_json_payload() {
+        let mut report = failed_report(2);
+        report.record("max-deps", "Within limits.", vec![Finding::new("max-deps", "Too many", 3)]);
+        report.downgrade("max-deps");
+        let body = payload(&report, "my-app", NotifyFormat::Json);
+        assert_eq!(body["project"], "my-app");
+        assert_eq!(body["passed"], false);
+        assert_eq!(body["exit_code"], 1);
+        assert_eq!(body["findings"].as_array().unwrap().len(), 2);
+        assert_eq!(body["findings"][1]["check"], "cycles");
+        assert_eq!(body["warning_count"], 1);
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let body = payload(&failed_report(12), "my-app", NotifyFormat::Slack);
+        assert_eq!(body["text"], "codescope: 12 policy violation(s) in my-app");
+        let list = body["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(list.starts_with("• *[cycles]* Cycle: a0 -&gt; b -&gt; a0\n"), "{}", list);
+        assert_eq!(list.lines().count(), SLACK_FINDINGS + 1);
+        assert!(list.ends_with("…and 2 more"));
+        assert_eq!(body["blocks"].as_array().unwrap().len(), 2, "no warnings block");
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("Slack".parse::<NotifyFormat>(), Ok(NotifyFormat::Slack));
+        assert_eq!("json".parse::<NotifyFormat>(), Ok(NotifyFormat::Json));
+        assert!("email".parse::<NotifyFormat>().is_err());
+    }
+}
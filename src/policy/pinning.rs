@@ -0,0 +1,142 @@
+//! Version pinning of direct dependencies.
+//!
+//! A `*` or `latest` specifier lets the next install pick up any release,
+//! including breaking ones; teams that want reproducible builds go further
+//! and require exact versions. The required [`Pinning`] is configured per
+//! dependency type in `.codescoperc.json`.
+
+use crate::config::{Pinning, PinningConfig};
+use crate::parser::{Dependency, VersionRange};
+
+use super::{Finding, PolicyReport};
+
+/// Exit code for dependencies pinned less tightly than required.
+pub const EXIT_PINNING: i32 = 11;
+
+/// Returns how tightly a dependency's specifier pins its version.
+///
+/// Dist-tags such as `latest` count as loose. Git, file, tarball and
+/// workspace dependencies are not versioned through the registry and
+/// return `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::config::Pinning;
+/// use codescope::parser::{Dependency, DependencyType};
+/// use codescope::policy::pinning::pinning_of;
+///
+/// let dep = |spec: &str| Dependency::new("react", spec, DependencyType::Production);
+/// assert_eq!(pinning_of(&dep("18.2.0")), Some(Pinning::Exact));
+/// assert_eq!(pinning_of(&dep("^18.2.0")), Some(Pinning::Bounded));
+/// assert_eq!(pinning_of(&dep("latest")), Some(Pinning::Loose));
+/// assert_eq!(pinning_of(&dep("github:facebook/react")), None);
+/// ```
+pub fn pinning_of(dep: &Dependency) -> Option<Pinning> {
+    let (_, spec) = dep.source.registry_target(&dep.name, &dep.version)?;
+    Some(match VersionRange::parse(spec) {
+        Some(range) if range.is_exact() => Pinning::Exact,
+        Some(range) if range.is_bounded() => Pinning::Bounded,
+        _ => Pinning::Loose,
+    })
+}
+
+/// Reports every direct dependency pinned less tightly than its type
+/// requires.
+///
+/// # Arguments
+///
+/// * `deps` - The project's direct dependencies
+/// * `config` - Required pinning per dependency type
+/// * `report` - Report to record the outcome in
+pub fn check_pinning(deps: &[Dependency], config: &PinningConfig, report: &mut PolicyReport) {
+    let mut findings = Vec::new();
+    let mut checked = 0;
+    for dep in deps {
+        let Some(pinning) = pinning_of(dep) else {
+            continue;
+        };
+        checked += 1;
+        let required = config.required(dep.dep_type);
+        if pinning >= required {
+            continue;
+        }
+        let advice = match required {
+            Pinning::Exact => "Pin an exact version, e.g. 1.2.3",
+            _ => "Use a range with an upper bound, e.g. ^1.2.3",
+        };
+        findings.push(
+            Finding::new(
+                "pinning",
+                format!(
+                    "{} uses {} range '{}', but {} dependencies must be {}",
+                    dep.name,
+                    pinning.label(),
+                    dep.version,
+                    dep.dep_type,
+                    required.label()
+                ),
+                EXIT_PINNING,
+            )
+            .with_detail(advice)
+            .with_packages([dep.package_name()]),
+        );
+    }
+    report.record(
+        "pinning",
+        format!("{} direct dependencies are pinned as required.", checked),
+        findings,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProjectConfig;
+    use crate::parser::DependencyType;
+
+    fn deps() -> Vec<Dependency> {
+        vec![
+            Dependency::new("react", "18.2.0", DependencyType::Production),
+            Dependency::new("lodash", "^4.17.21", DependencyType::Production),
+            Dependency::new("left-pad", "*", DependencyType::Production),
+            Dependency::new("typescript", ">=5", DependencyType::Development),
+            Dependency::new("react-dom", ">=16.8", DependencyType::Peer),
+            Dependency::new("utils", "workspace:*", DependencyType::Production),
+            Dependency::new("old-react", "npm:react@latest", DependencyType::Production),
+        ]
+    }
+
+    #[test]
+    fn test_default_pinning_flags_loose_ranges() {
+        let mut report = PolicyReport::new();
+        check_pinning(&deps(), &PinningConfig::default(), &mut report);
+
+        let messages: Vec<&str> = report.findings().iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "left-pad uses loose range '*', but production dependencies must be bounded",
+                "typescript uses loose range '>=5', but development dependencies must be bounded",
+                "old-react uses loose range 'npm:react@latest', but production dependencies must be bounded",
+            ]
+        );
+        assert_eq!(report.findings()[2].packages, vec!["react"]);
+        assert_eq!(report.exit_code(), EXIT_PINNING);
+    }
+
+    #[test]
+    fn test_exact_pins_required() {
+        let config = ProjectConfig::parse(r#"{"pinning": {"production": "exact", "development": "loose"}}"#).unwrap();
+        let mut report = PolicyReport::new();
+        check_pinning(&deps()[..4], &config.pinning, &mut report);
+
+        let flagged: Vec<&str> = report.findings().iter().flat_map(|f| &f.packages).map(String::as_str).collect();
+        assert_eq!(flagged, vec!["lodash", "left-pad"]);
+        assert_eq!(report.findings()[0].details, vec!["Pin an exact version, e.g. 1.2.3"]);
+
+        let mut report = PolicyReport::new();
+        check_pinning(&deps()[..2], &PinningConfig::default(), &mut report);
+        assert_eq!(report.format_passed(), "✅ 2 direct dependencies are pinned as required.\n");
+    }
+}
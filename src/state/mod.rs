@@ -0,0 +1,77 @@
+//! Per-project state kept between sessions
+//!
+//! What a user builds up while exploring a project, such as pinned
+//! packages and notes on packages, is kept in a `.codescope/` directory in the project root, next
+//! to `.codescoperc.json`. Unlike the configuration, these files are
+//! written by codescope; whether to commit them is up to the team.
+
+pub mod notes;
+pub mod pins;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+pub use notes::Notes;
+pub use pins::Pins;
+
+/// Directory of the state files, relative to the project root.
+pub const STATE_DIR: &str = ".codescope";
+
+/// Errors that can occur while reading or writing project state.
+#[derive(Error, Debug)]
+pub enum StateError {
+    /// A state file could not be read or written.
+    #[error("Failed to access {}: {source}", .path.display())]
+    Io {
+        /// The state file
+        path: PathBuf,
+        /// The underlying error
+        source: io::Error,
+    },
+
+    /// A state file is not valid JSON of the expected shape.
+    #[error("Failed to parse {}: {source}", .path.display())]
+    Json {
+        /// The state file
+        path: PathBuf,
+        /// The underlying error
+        source: serde_json::Error,
+    },
+}
+
+/// Result type alias for project state operations.
+pub type StateResult<T> = Result<T, StateError>;
+
+/// Returns the path of a state file of the project in `project_dir`.
+pub fn state_file(project_dir: &Path, name: &str) -> PathBuf {
+    project_dir.join(STATE_DIR).join(name)
+}
+
+/// Reads a state file, or the default value if it does not exist.
+pub(crate) fn read_state<T: DeserializeOwned + Default>(path: &Path) -> StateResult<T> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(source) => return Err(StateError::Io { path: path.to_path_buf(), source }),
+    };
+    serde_json::from_str(&content).map_err(|source| StateError::Json { path: path.to_path_buf(), source })
+}
+
+/// Writes a state file as pretty-printed JSON, creating its directory.
+pub(crate) fn write_state<T: Serialize>(path: &Path, value: &T) -> StateResult<()> {
+    let io_error = |source| StateError::Io { path: path.to_path_buf(), source };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let mut content = serde_json::to_string_pretty(value).map_err(|source| StateError::Json {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    content.push('\n');
+    fs::write(path, content).map_err(io_error)
+}
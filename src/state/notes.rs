@@ -0,0 +1,86 @@
+//! Free-text notes on packages.
+//!
+//! Notes record what the team knows about a dependency that the lockfile
+//! does not, such as "scheduled for removal in Q3, owner: infra". They are
+//! written in the TUI (`n`), shown in its notes panel and carried into the
+//! Markdown and JSON exports, and kept in `.codescope/notes.json`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{read_state, state_file, write_state, StateResult};
+
+/// Name of the notes file in the state directory.
+pub const NOTES_FILE: &str = "notes.json";
+
+/// The notes of a project, keyed by package name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notes {
+    /// Note per package name
+    #[serde(default)]
+    pub packages: BTreeMap<String, String>,
+}
+
+impl Notes {
+    /// Loads the notes of the project in `project_dir`; a project without a
+    /// notes file has none.
+    pub fn load(project_dir: &Path) -> StateResult<Self> {
+        read_state(&state_file(project_dir, NOTES_FILE))
+    }
+
+    /// Saves the notes of the project in `project_dir`.
+    pub fn save(&self, project_dir: &Path) -> StateResult<()> {
+        write_state(&state_file(project_dir, NOTES_FILE), self)
+    }
+
+    /// Returns the note on `package`, if any.
+    pub fn get(&self, package: &str) -> Option<&str> {
+        self.packages.get(package).map(String::as_str)
+    }
+
+    /// Sets the note on `package`; a blank note removes it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::state::Notes;
+    ///
+    /// let mut notes = Notes::default();
+    /// notes.set("moment", "  scheduled for removal in Q3, owner: infra ");
+    /// assert_eq!(notes.get("moment"), Some("scheduled for removal in Q3, owner: infra"));
+    /// notes.set("moment", " ");
+    /// assert_eq!(notes.get("moment"), None);
+    /// ```
+    pub fn set(&mut self, package: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.packages.remove(package);
+        } else {
+            self.packages.insert(package.to_string(), note.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("codescope-notes-test-{}", std::process::id()));
+        assert_eq!(Notes::load(&dir).unwrap(), Notes::default());
+
+        let mut notes = Notes::default();
+        notes.set("moment", "replace with date-fns");
+        notes.set("@acme/ui", "owner: design systems");
+        notes.save(&dir).unwrap();
+        assert_eq!(Notes::load(&dir).unwrap(), notes);
+        assert_eq!(
+            std::fs::read_to_string(state_file(&dir, NOTES_FILE)).unwrap(),
+            "{\n  \"packages\": {\n    \"@acme/ui\": \"owner: design systems\",\n    \"moment\": \"replace with date-fns\"\n  }\n}\n"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
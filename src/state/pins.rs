@@ -0,0 +1,91 @@
+//! Packages pinned for quick access.
+//!
+//! Users tracking a handful of problem dependencies pin them in the TUI
+//! (`p`), which lists them in a strip above the tree. The pins are kept in
+//! `.codescope/pins.json` so the next session starts with them.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{read_state, state_file, write_state, StateResult};
+
+/// Name of the pins file in the state directory.
+pub const PINS_FILE: &str = "pins.json";
+
+/// The pinned packages of a project, in the order they were pinned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pins {
+    /// Names of the pinned packages
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+impl Pins {
+    /// Loads the pins of the project in `project_dir`; a project without a
+    /// pins file has none.
+    pub fn load(project_dir: &Path) -> StateResult<Self> {
+        read_state(&state_file(project_dir, PINS_FILE))
+    }
+
+    /// Saves the pins of the project in `project_dir`.
+    pub fn save(&self, project_dir: &Path) -> StateResult<()> {
+        write_state(&state_file(project_dir, PINS_FILE), self)
+    }
+
+    /// Returns true if `package` is pinned.
+    pub fn contains(&self, package: &str) -> bool {
+        self.packages.iter().any(|pinned| pinned == package)
+    }
+
+    /// Pins `package`, or unpins it if it is pinned.
+    ///
+    /// Returns true if the package is pinned now.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::state::Pins;
+    ///
+    /// let mut pins = Pins::default();
+    /// assert!(pins.toggle("moment"));
+    /// assert!(pins.toggle("lodash"));
+    /// assert!(!pins.toggle("moment"));
+    /// assert_eq!(pins.packages, vec!["lodash"]);
+    /// ```
+    pub fn toggle(&mut self, package: &str) -> bool {
+        match self.packages.iter().position(|pinned| pinned == package) {
+            Some(index) => {
+                self.packages.remove(index);
+                false
+            }
+            None => {
+                self.packages.push(package.to_string());
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("codescope-pins-test-{}", std::process::id()));
+        assert_eq!(Pins::load(&dir).unwrap(), Pins::default());
+
+        let mut pins = Pins::default();
+        pins.toggle("moment");
+        pins.toggle("@babel/core");
+        pins.save(&dir).unwrap();
+        let loaded = Pins::load(&dir).unwrap();
+        assert_eq!(loaded, pins);
+        assert!(loaded.contains("@babel/core"));
+
+        std::fs::write(state_file(&dir, PINS_FILE), "[").unwrap();
+        assert!(Pins::load(&dir).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
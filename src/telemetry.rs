@@ -0,0 +1,121 @@
+//! OpenTelemetry export of the analysis phases
+//!
+//! Every run is a `codescope` span with one child span per phase: `parse`
+//! (package.json), `lockfile`, `resolve` (the dependency graph), `stats`
+//! (webpack stats), `scan` (source imports), `savings`, `registry` and
+//! `footprint`. A phase that fails has the error status, so a platform team
+//! running codescope across many repositories can see both where the time
+//! goes and which projects fail to analyze.
+//!
+//! The spans are sent over OTLP/HTTP to the collector at the
+//! `--otlp-endpoint` URL, or else the one set by the standard
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` variables; `OTEL_EXPORTER_OTLP_HEADERS`
+//! adds headers such as credentials. Each span is sent when it ends, so a
+//! run that exits early still reports the phases it completed.
+//!
+//! Without a collector the spans cost next to nothing: nothing records
+//! them. With `--offline` nothing is exported either, since offline runs
+//! make no network requests.
+//!
+//! Only available with the `otel` cargo feature.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Environment variable of the standard OTLP collector URL
+pub const ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Environment variable of the OTLP collector URL for traces only
+pub const TRACES_ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+
+/// Exports the spans of this process until dropped.
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// Returns whether a collector is configured, by `endpoint` or by the
+    /// environment variables.
+    pub fn is_configured(endpoint: Option<&str>) -> bool {
+        configured_with(endpoint, |var| std::env::var(var).ok())
+    }
+
+    /// Starts exporting spans to `endpoint`, the base URL of an OTLP/HTTP
+    /// collector, or to the collector of the environment variables.
+    ///
+    /// Returns `Ok(None)` when no collector is configured or the run is
+    /// `offline`.
+    pub fn init(endpoint: Option<&str>, offline: bool) -> Result<Option<Self>, String> {
+        Self::init_with(endpoint, offline, |var| std::env::var(var).ok())
+    }
+
+    /// Like [`Telemetry::init`], reading the environment through `env`.
+    fn init_with(
+        endpoint: Option<&str>,
+        offline: bool,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<Self>, String> {
+        if offline || !configured_with(endpoint, env) {
+            return Ok(None);
+        }
+
+        let mut exporter = SpanExporter::builder().with_http();
+        if let Some(endpoint) = endpoint {
+            // Unlike the variable, an explicit endpoint is the full URL
+            exporter = exporter.with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')));
+        }
+        let exporter = exporter
+            .build()
+            .map_err(|e| format!("Failed to set up the OTLP exporter: {}", e))?;
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name("codescope")
+                    .with_attribute(opentelemetry::KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+                    .build(),
+            )
+            .build();
+
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("codescope"));
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))
+            .map_err(|e| format!("Failed to install the span exporter: {}", e))?;
+        Ok(Some(Self { provider }))
+    }
+}
+
+/// Returns whether `endpoint` or a variable of `env` names a collector.
+fn configured_with(endpoint: Option<&str>, env: impl Fn(&str) -> Option<String>) -> bool {
+    let configured = |var: &str| env(var).is_some_and(|value| !value.is_empty());
+    endpoint.is_some() || configured(ENDPOINT_VAR) || configured(TRACES_ENDPOINT_VAR)
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        // Sends whatever the exporter still holds
+        let _ = self.provider.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_exports_nothing() {
+        let no_env = |_: &str| None;
+        let collector_env = |var: &str| (var == ENDPOINT_VAR).then(|| "http://127.0.0.1:4318".to_string());
+
+        let telemetry = Telemetry::init_with(Some("http://127.0.0.1:4318"), true, no_env).unwrap();
+        assert!(telemetry.is_none());
+
+        assert!(configured_with(None, collector_env));
+        assert!(!configured_with(None, no_env));
+        assert!(!configured_with(None, |_: &str| Some(String::new())), "empty variables are unset");
+        let telemetry = Telemetry::init_with(None, true, collector_env).unwrap();
+        assert!(telemetry.is_none());
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the unit tests.
+
+use std::fs;
+use std::path::Path;
+
+/// Installs a package into `root/node_modules/<name>` with the given
+/// `package.json` and files, creating parent folders as needed. `name` can
+/// be a nested install path like `react/node_modules/scheduler`.
+pub fn install(root: &Path, name: &str, manifest: &str, files: &[(&str, &str)]) {
+    let dir = root.join("node_modules").join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("package.json"), manifest).unwrap();
+    for (file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
@@ -3,20 +3,47 @@
 //! Manages the application state and handles user input for the
 //! dependency tree visualization.
 
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
 use ratatui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 
-use crate::bundle::savings::{SavingsReport, SavingsCategory};
+use crate::analysis::descriptions::PackageDescription;
+use crate::analysis::exports::ImportSite;
+use crate::analysis::footprint::{install_label, FootprintReport, FootprintSort};
+use crate::analysis::groups::GroupSummary;
+use crate::analysis::hoisting::HoistingExplanation;
+use crate::analysis::links::{npm_page, PackageLinks};
+use crate::analysis::orphans::{OrphanReport, OrphanedPackage};
+use crate::analysis::removal::RemovalImpact;
+use crate::analysis::scopes::ScopeSummary;
+use crate::analysis::warnings::AnalysisWarnings;
+use crate::bundle::chunks::{ChunkGraph, ChunkInfo, ChunkRow};
+use crate::bundle::savings::{PackageSavings, SavingsReport, SavingsCategory};
+use crate::export::file::write_atomic;
+use crate::export::{ExportReport, MarkdownExporter};
+use crate::git::Commit;
+use crate::graph::condensation::{Component, Condensation};
 use crate::parser::types::DependencyType;
+use crate::state::STATE_DIR;
+use super::changes::{format_age, ChangeLog, SizeChange};
+use super::events::{spinner_frame, AnalysisUpdate, AppEvent, ANIMATION_TICK, CURSOR_BLINK_TICKS, IDLE_TICK};
+use super::launch::{editor_command, open_in_browser, open_in_editor};
+use super::preview::{Highlight, SourcePreview, CONTEXT_LINES};
+use super::screen;
+use super::table::{Column, SortableTable};
 use super::tree::{FlattenedNode, TreeNode, format_size};
 
 /// Sort mode for the dependency tree
@@ -113,6 +140,41 @@ impl VirtualScrollState {
             self.offset = selected.saturating_sub(self.viewport_height - 1);
         }
     }
+
+    /// Pull the offset back so the viewport ends at the last of `total`
+    /// rows rather than past it, e.g. after the terminal grew taller
+    pub fn clamp(&mut self, total: usize) {
+        self.offset = self.offset.min(total.saturating_sub(self.viewport_height));
+    }
+}
+
+/// A web page of a package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackagePage {
+    /// The package on npmjs.com
+    Npm,
+    /// The source repository, or the homepage without one
+    Repository,
+    /// The changelog, or the repository's releases
+    Changelog,
+}
+
+/// Something to open outside the TUI, run by the event loop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenRequest {
+    /// Open a package directory in the editor
+    Editor(PathBuf),
+    /// Open a web page in the default browser
+    Browser(String),
+}
+
+/// What clicking a part of the screen selects
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClickTarget {
+    /// The node at a position in the tree order
+    TreeIndex(usize),
+    /// The first node of a package, expanding its ancestors
+    Package(String),
 }
 
 /// Application state
@@ -135,6 +197,9 @@ pub struct App {
     pub search_active: bool,
     /// Current search query
     pub search_query: String,
+    /// Package descriptions and keywords the search also matches, keyed
+    /// by name
+    pub package_descriptions: BTreeMap<String, PackageDescription>,
     /// Virtual scroll state for performance with large trees
     pub scroll_state: VirtualScrollState,
     /// Current sort mode for the dependency list
@@ -143,6 +208,107 @@ pub struct App {
     pub savings_report: Option<SavingsReport>,
     /// Whether to show the savings panel
     pub show_savings_panel: bool,
+    /// Columns and sort of the savings panel
+    pub savings_table: SortableTable<PackageSavings>,
+    /// Orphaned node_modules packages (optional, set when a lockfile is present)
+    pub orphan_report: Option<OrphanReport>,
+    /// Whether to show the orphaned packages panel
+    pub show_orphan_panel: bool,
+    /// Columns and sort of the orphaned packages panel
+    pub orphan_table: SortableTable<OrphanedPackage>,
+    /// Simulated removal of each direct dependency, keyed by name
+    pub removal_impacts: BTreeMap<String, RemovalImpact>,
+    /// Whether to show the removal preview panel for the selected node
+    pub show_removal_panel: bool,
+    /// Per-group aggregates from the project configuration
+    pub group_summaries: Vec<GroupSummary>,
+    /// Whether to show the package groups panel
+    pub show_group_panel: bool,
+    /// Per-scope aggregates, largest first
+    pub scope_summaries: Vec<ScopeSummary>,
+    /// Whether to show the npm scopes panel
+    pub show_scope_panel: bool,
+    /// Columns and sort of the npm scopes panel
+    pub scope_table: SortableTable<ScopeSummary>,
+    /// Commit that introduced each direct dependency, keyed by name
+    pub dependency_origins: BTreeMap<String, Commit>,
+    /// Whether to show the blame panel for the selected node
+    pub show_blame_panel: bool,
+    /// Install cost of each package in node_modules
+    pub footprint_report: Option<FootprintReport>,
+    /// Whether to show the install footprint panel
+    pub show_footprint_panel: bool,
+    /// Order of the install footprint panel
+    pub footprint_sort: FootprintSort,
+    /// Chunks of the webpack build (optional, set when stats are provided)
+    pub chunk_graph: Option<ChunkGraph>,
+    /// Lines of the chunk tree
+    pub chunk_rows: Vec<ChunkRow>,
+    /// Selected line of the chunk tree
+    pub chunk_selected: usize,
+    /// Whether to show the chunk graph panel
+    pub show_chunk_panel: bool,
+    /// Strongly connected components of the dependency graph
+    pub condensation: Option<Condensation>,
+    /// Whether to show the condensed graph panel
+    pub show_condensed_panel: bool,
+    /// Why packages installed more than once were hoisted or nested, keyed
+    /// by package name
+    pub hoisting: BTreeMap<String, HoistingExplanation>,
+    /// Whether to show the hoisting panel for the selected node
+    pub show_hoisting_panel: bool,
+    /// Files importing each package, keyed by name, with paths relative
+    /// to `source_root`
+    pub import_sites: BTreeMap<String, Vec<ImportSite>>,
+    /// Directory the importing files are read from
+    pub source_root: PathBuf,
+    /// Selected importing file of each package, keyed by name
+    import_selected: BTreeMap<String, usize>,
+    /// Preview of the selected importing file, keyed by package and file
+    import_preview: Option<((String, String), Result<SourcePreview, String>)>,
+    /// Whether to show the imports panel for the selected node
+    pub show_imports_panel: bool,
+    /// Directory and web pages of each installed package, keyed by name
+    pub package_links: BTreeMap<String, PackageLinks>,
+    /// Whether web pages open in the browser, or their URLs are shown
+    pub open_browser: bool,
+    /// Directory or page to open, taken by the event loop
+    pub open_request: Option<OpenRequest>,
+    /// One-line message replacing the key help until the next key press
+    pub status: Option<String>,
+    /// Report of the whole project, narrowed to the selection on export
+    pub export_report: Option<ExportReport>,
+    /// Project directory whose state directory exports are written to
+    pub export_dir: PathBuf,
+    /// Latest analysis run while the project is watched
+    pub watch_run: Option<u64>,
+    /// Size changes seen by re-analyses in watch mode
+    pub size_changes: ChangeLog,
+    /// Whether to show the panel of recent size changes
+    pub show_changes_panel: bool,
+    /// Inputs the analysis went without
+    pub warnings: AnalysisWarnings,
+    /// Whether to show the warnings in the notification area
+    pub show_warnings: bool,
+    /// Package the user confirmed to uninstall after the TUI exits
+    pub uninstall_request: Option<String>,
+    /// Whether rows show a bar of the package's share of the bundle size
+    pub show_size_bars: bool,
+    /// Packages pinned for quick access, in the order they were pinned
+    pub pinned: Vec<String>,
+    /// The team's notes on packages, keyed by name
+    pub notes: BTreeMap<String, String>,
+    /// Whether to show the notes panel for the selected node
+    pub show_notes_panel: bool,
+    /// Text of the note being written, while editing it
+    pub note_input: Option<String>,
+    /// Screen areas of the breadcrumb segments and pinned packages and
+    /// what each selects, updated on each render
+    click_targets: Vec<(Rect, ClickTarget)>,
+    /// Ticks of the event loop's clock so far, driving the animations
+    pub ticks: u64,
+    /// Watch-mode run being analyzed in the background
+    pub analyzing: Option<u64>,
 }
 
 impl App {
@@ -163,10 +329,60 @@ impl App {
             list_state: ListState::default(),
             search_active: false,
             search_query: String::new(),
+            package_descriptions: BTreeMap::new(),
             scroll_state: VirtualScrollState::new(),
             sort_mode,
             savings_report: None,
             show_savings_panel: false,
+            savings_table: savings_table(),
+            orphan_report: None,
+            show_orphan_panel: false,
+            orphan_table: orphan_table(),
+            removal_impacts: BTreeMap::new(),
+            show_removal_panel: false,
+            group_summaries: Vec::new(),
+            show_group_panel: false,
+            scope_summaries: Vec::new(),
+            show_scope_panel: false,
+            scope_table: scope_table(),
+            dependency_origins: BTreeMap::new(),
+            show_blame_panel: false,
+            footprint_report: None,
+            show_footprint_panel: false,
+            footprint_sort: FootprintSort::default(),
+            chunk_graph: None,
+            chunk_rows: Vec::new(),
+            chunk_selected: 0,
+            show_chunk_panel: false,
+            condensation: None,
+            show_condensed_panel: false,
+            hoisting: BTreeMap::new(),
+            show_hoisting_panel: false,
+            import_sites: BTreeMap::new(),
+            source_root: PathBuf::new(),
+            import_selected: BTreeMap::new(),
+            import_preview: None,
+            show_imports_panel: false,
+            package_links: BTreeMap::new(),
+            open_browser: true,
+            open_request: None,
+            status: None,
+            export_report: None,
+            export_dir: PathBuf::new(),
+            watch_run: None,
+            size_changes: ChangeLog::default(),
+            show_changes_panel: false,
+            warnings: AnalysisWarnings::new(),
+            show_warnings: false,
+            uninstall_request: None,
+            show_size_bars: true,
+            pinned: Vec::new(),
+            notes: BTreeMap::new(),
+            show_notes_panel: false,
+            note_input: None,
+            click_targets: Vec::new(),
+            ticks: 0,
+            analyzing: None,
         };
         app.refresh_flattened();
         app.list_state.select(Some(0));
@@ -181,7 +397,9 @@ impl App {
     /// Toggle the savings panel visibility
     pub fn toggle_savings_panel(&mut self) {
         if self.savings_report.is_some() {
-            self.show_savings_panel = !self.show_savings_panel;
+            let show = !self.show_savings_panel;
+            self.close_side_panels();
+            self.show_savings_panel = show;
         }
     }
 
@@ -190,887 +408,3147 @@ impl App {
         self.savings_report.is_some()
     }
 
-    /// Refresh the flattened view from the tree
-    pub fn refresh_flattened(&mut self) {
-        self.flattened = self.tree.flatten();
-        self.apply_sort();
-        self.rebuild_ancestors_last();
+    /// Set the orphaned packages report for display
+    pub fn set_orphan_report(&mut self, report: OrphanReport) {
+        self.orphan_report = Some(report);
+    }
 
-        // Ensure selected index is valid
-        if !self.flattened.is_empty() && self.selected_index >= self.flattened.len() {
-            self.selected_index = self.flattened.len() - 1;
+    /// Toggle the orphaned packages panel visibility
+    pub fn toggle_orphan_panel(&mut self) {
+        if self.orphan_report.is_some() {
+            let show = !self.show_orphan_panel;
+            self.close_side_panels();
+            self.show_orphan_panel = show;
         }
     }
 
-    /// Apply the current sort mode to the flattened view
+    /// Check if orphaned package data is available
+    pub fn has_orphan_data(&self) -> bool {
+        self.orphan_report.is_some()
+    }
+
+    /// Set the simulated removals of the direct dependencies
+    pub fn set_removal_impacts(&mut self, impacts: BTreeMap<String, RemovalImpact>) {
+        self.removal_impacts = impacts;
+    }
+
+    /// Toggle the removal preview panel visibility
     ///
-    /// For size-based sorting, the view is flattened (tree structure is lost)
-    /// to allow proper comparison by bundle size. Nodes without bundle size
-    /// are placed at the end.
-    fn apply_sort(&mut self) {
-        match self.sort_mode {
-            SortMode::Alphabetical => {
-                // Alphabetical mode preserves tree structure (already sorted by tree traversal)
-                // No additional sorting needed
-            }
-            SortMode::SizeDescending => {
-                // Sort by size descending, nodes without size go last
-                self.flattened.sort_by(|a, b| {
-                    match (a.bundle_size, b.bundle_size) {
-                        (Some(size_a), Some(size_b)) => size_b.cmp(&size_a),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => a.name.cmp(&b.name),
-                    }
-                });
-            }
-            SortMode::SizeAscending => {
-                // Sort by size ascending, nodes without size go last
-                self.flattened.sort_by(|a, b| {
-                    match (a.bundle_size, b.bundle_size) {
-                        (Some(size_a), Some(size_b)) => size_a.cmp(&size_b),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => a.name.cmp(&b.name),
-                    }
-                });
-            }
+    /// The panel follows the selection, so it stays open while navigating.
+    pub fn toggle_removal_panel(&mut self) {
+        if self.has_removal_data() {
+            let show = !self.show_removal_panel;
+            self.close_side_panels();
+            self.show_removal_panel = show;
         }
     }
 
-    /// Cycle to the next sort mode and refresh the view
-    pub fn cycle_sort_mode(&mut self) {
-        self.sort_mode = self.sort_mode.cycle();
-        self.refresh_flattened();
-        // Reset selection to top when changing sort mode
-        self.selected_index = 0;
-        self.list_state.select(Some(0));
-        self.scroll_state.offset = 0;
+    /// Check if removal simulations are available
+    pub fn has_removal_data(&self) -> bool {
+        !self.removal_impacts.is_empty()
     }
 
-    /// Set the sort mode and refresh the view
-    pub fn set_sort_mode(&mut self, mode: SortMode) {
-        if self.sort_mode != mode {
-            self.sort_mode = mode;
-            self.refresh_flattened();
-            self.selected_index = 0;
-            self.list_state.select(Some(0));
-            self.scroll_state.offset = 0;
-        }
+    /// Set the per-group aggregates for display
+    pub fn set_group_summaries(&mut self, groups: Vec<GroupSummary>) {
+        self.group_summaries = groups;
     }
 
-    /// Rebuild the ancestors_last tracking for tree drawing
-    fn rebuild_ancestors_last(&mut self) {
-        self.ancestors_last.clear();
-        for node in &self.flattened {
-            // Track the is_last_child status for each depth level
-            while self.ancestors_last.len() < node.depth {
-                self.ancestors_last.push(false);
-            }
-            if node.depth > 0 && self.ancestors_last.len() >= node.depth {
-                self.ancestors_last[node.depth - 1] = node.is_last_child;
-            }
+    /// Toggle the package groups panel visibility
+    pub fn toggle_group_panel(&mut self) {
+        if self.has_group_data() {
+            let show = !self.show_group_panel;
+            self.close_side_panels();
+            self.show_group_panel = show;
         }
     }
 
-    /// Move selection to the next item
-    pub fn select_next(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 {
-            self.selected_index = (self.selected_index + 1).min(total - 1);
-            self.list_state.select(Some(self.selected_index));
-            self.scroll_state.ensure_visible(self.selected_index, total);
-        }
+    /// Check if package groups are configured
+    pub fn has_group_data(&self) -> bool {
+        !self.group_summaries.is_empty()
     }
 
-    /// Move selection to the previous item
-    pub fn select_previous(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 && self.selected_index > 0 {
-            self.selected_index -= 1;
-            self.list_state.select(Some(self.selected_index));
-            self.scroll_state.ensure_visible(self.selected_index, total);
-        }
+    /// Set the per-scope aggregates for display
+    pub fn set_scope_summaries(&mut self, scopes: Vec<ScopeSummary>) {
+        self.scope_summaries = scopes;
     }
 
-    /// Move selection down by a page
-    pub fn page_down(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 {
-            let page_size = self.scroll_state.viewport_height.max(1);
-            self.selected_index = (self.selected_index + page_size).min(total - 1);
-            self.list_state.select(Some(self.selected_index));
-            self.scroll_state.ensure_visible(self.selected_index, total);
+    /// Toggle the npm scopes panel visibility
+    pub fn toggle_scope_panel(&mut self) {
+        if self.has_scope_data() {
+            let show = !self.show_scope_panel;
+            self.close_side_panels();
+            self.show_scope_panel = show;
         }
     }
 
-    /// Move selection up by a page
-    pub fn page_up(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 {
-            let page_size = self.scroll_state.viewport_height.max(1);
-            self.selected_index = self.selected_index.saturating_sub(page_size);
-            self.list_state.select(Some(self.selected_index));
-            self.scroll_state.ensure_visible(self.selected_index, total);
-        }
+    /// Check if the project has scoped packages
+    pub fn has_scope_data(&self) -> bool {
+        !self.scope_summaries.is_empty()
     }
 
-    /// Jump to the first item
-    pub fn select_first(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 {
-            self.selected_index = 0;
-            self.list_state.select(Some(0));
-            self.scroll_state.offset = 0;
-        }
+    /// Set the commit that introduced each direct dependency
+    pub fn set_dependency_origins(&mut self, origins: BTreeMap<String, Commit>) {
+        self.dependency_origins = origins;
     }
 
-    /// Jump to the last item
-    pub fn select_last(&mut self) {
-        let total = self.current_list_len();
-        if total > 0 {
-            self.selected_index = total - 1;
-            self.list_state.select(Some(self.selected_index));
-            self.scroll_state.ensure_visible(self.selected_index, total);
+    /// Toggle the blame panel visibility
+    ///
+    /// The panel follows the selection, so it stays open while navigating.
+    pub fn toggle_blame_panel(&mut self) {
+        if self.has_blame_data() {
+            let show = !self.show_blame_panel;
+            self.close_side_panels();
+            self.show_blame_panel = show;
         }
     }
 
-    /// Get the current list length (filtered or full)
-    fn current_list_len(&self) -> usize {
-        if !self.search_query.is_empty() {
-            self.filtered.len()
-        } else {
-            self.flattened.len()
-        }
+    /// Check if git history was read
+    pub fn has_blame_data(&self) -> bool {
+        !self.dependency_origins.is_empty()
     }
 
-    /// Toggle expansion of the selected item
-    pub fn toggle_selected(&mut self) {
-        if self.tree.toggle_at_index(self.selected_index) {
-            self.refresh_flattened();
-        }
+    /// Get the commit that introduced the selected node, if it is a direct dependency
+    pub fn selected_origin(&self) -> Option<&Commit> {
+        self.selected_node()
+            .and_then(|node| self.dependency_origins.get(&node.name))
     }
 
-    /// Signal that the application should quit
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Set the install footprint report for display
+    pub fn set_footprint_report(&mut self, mut report: FootprintReport) {
+        report.sort(self.footprint_sort);
+        self.footprint_report = Some(report);
     }
 
-    /// Get the tree prefix for a node at the given index
-    fn get_tree_prefix(&self, index: usize) -> String {
-        if index >= self.flattened.len() {
-            return String::new();
+    /// Toggle the install footprint panel visibility
+    pub fn toggle_footprint_panel(&mut self) {
+        if self.has_footprint_data() {
+            let show = !self.show_footprint_panel;
+            self.close_side_panels();
+            self.show_footprint_panel = show;
         }
+    }
 
-        let node = &self.flattened[index];
-        let mut prefix = String::new();
-
-        // Build ancestors_last for this specific path
-        let mut ancestors_last_for_node = Vec::new();
-        let mut current_depth = 0;
+    /// Check if node_modules was measured
+    pub fn has_footprint_data(&self) -> bool {
+        self.footprint_report.is_some()
+    }
 
-        for (i, n) in self.flattened.iter().enumerate().take(index + 1) {
-            if n.depth <= current_depth || i == index {
-                while ancestors_last_for_node.len() > n.depth {
-                    ancestors_last_for_node.pop();
-                }
-            }
-            if n.depth > 0 {
-                while ancestors_last_for_node.len() < n.depth {
-                    ancestors_last_for_node.push(false);
-                }
-                if ancestors_last_for_node.len() >= n.depth {
-                    ancestors_last_for_node[n.depth - 1] = n.is_last_child;
-                }
-            }
-            current_depth = n.depth;
+    /// Cycle the order of the install footprint panel
+    pub fn cycle_footprint_sort(&mut self) {
+        self.footprint_sort = self.footprint_sort.cycle();
+        if let Some(report) = &mut self.footprint_report {
+            report.sort(self.footprint_sort);
         }
+    }
 
-        // Build the prefix string
-        for i in 0..node.depth {
-            if i < ancestors_last_for_node.len() {
-                if ancestors_last_for_node[i] {
-                    prefix.push_str("    ");
-                } else {
-                    prefix.push_str("│   ");
-                }
-            } else {
-                prefix.push_str("    ");
-            }
-        }
+    /// Set the chunk graph of the webpack build for display
+    pub fn set_chunk_graph(&mut self, graph: ChunkGraph) {
+        self.chunk_rows = graph.rows();
+        self.chunk_selected = 0;
+        self.chunk_graph = Some(graph);
+    }
 
-        // Add the branch connector
-        if node.depth > 0 {
-            if node.is_last_child {
-                prefix.push_str("└── ");
-            } else {
-                prefix.push_str("├── ");
-            }
+    /// Toggle the chunk graph panel visibility
+    ///
+    /// While the panel is open, navigation moves through the chunks.
+    pub fn toggle_chunk_panel(&mut self) {
+        if self.has_chunk_data() {
+            let show = !self.show_chunk_panel;
+            self.close_side_panels();
+            self.show_chunk_panel = show;
         }
-
-        prefix
     }
 
-    /// Start search mode
-    pub fn start_search(&mut self) {
-        self.search_active = true;
-        self.search_query.clear();
+    /// Check if the build has chunks
+    pub fn has_chunk_data(&self) -> bool {
+        !self.chunk_rows.is_empty()
     }
 
-    /// Clear search and return to normal mode
-    pub fn clear_search(&mut self) {
-        self.search_active = false;
-        self.search_query.clear();
-        self.filtered.clear();
-        // Reset selection to first item
-        self.selected_index = 0;
-        self.list_state.select(Some(0));
+    /// Move the chunk selection down
+    pub fn select_next_chunk(&mut self) {
+        if self.chunk_selected + 1 < self.chunk_rows.len() {
+            self.chunk_selected += 1;
+        }
     }
 
-    /// Add a character to the search query
-    pub fn search_push(&mut self, c: char) {
-        self.search_query.push(c);
-        self.update_filter();
+    /// Move the chunk selection up
+    pub fn select_previous_chunk(&mut self) {
+        self.chunk_selected = self.chunk_selected.saturating_sub(1);
     }
 
-    /// Remove the last character from the search query
-    pub fn search_pop(&mut self) {
-        self.search_query.pop();
-        self.update_filter();
+    /// Get the selected chunk
+    pub fn selected_chunk(&self) -> Option<&ChunkInfo> {
+        let row = self.chunk_rows.get(self.chunk_selected)?;
+        self.chunk_graph.as_ref()?.chunks.get(row.index)
     }
 
-    /// Update the filtered view based on the current search query
-    fn update_filter(&mut self) {
-        if self.search_query.is_empty() {
-            self.filtered.clear();
-            self.selected_index = 0;
-        } else {
-            self.filtered = self
-                .flattened
-                .iter()
-                .filter(|node| fuzzy_match(&node.name, &self.search_query))
-                .cloned()
-                .collect();
+    /// Set the strongly connected components of the dependency graph
+    pub fn set_condensation(&mut self, condensation: Condensation) {
+        self.condensation = Some(condensation);
+    }
 
-            // Reset selection if current selection is out of bounds
-            if !self.filtered.is_empty() {
-                self.selected_index = 0;
-            }
+    /// Toggle the condensed graph panel visibility
+    ///
+    /// The panel follows the selection, listing the cycle of the selected
+    /// package.
+    pub fn toggle_condensed_panel(&mut self) {
+        if self.has_condensed_data() {
+            let show = !self.show_condensed_panel;
+            self.close_side_panels();
+            self.show_condensed_panel = show;
         }
-        self.list_state.select(Some(self.selected_index));
     }
 
-}
+    /// Check if the dependency graph has cycles to condense
+    pub fn has_condensed_data(&self) -> bool {
+        self.condensation.as_ref().is_some_and(|c| c.cycles().next().is_some())
+    }
 
-/// Perform fuzzy matching of query against text (case-insensitive)
-/// A match requires all characters of the query to appear in order in the text
-fn fuzzy_match(text: &str, query: &str) -> bool {
-    if query.is_empty() {
-        return true;
+    /// Get the component of the selected node
+    pub fn selected_component(&self) -> Option<&Component> {
+        let node = self.selected_node()?;
+        self.condensation.as_ref()?.component_of(&node.name)
     }
 
-    let text_lower = text.to_lowercase();
-    let query_lower = query.to_lowercase();
+    /// Set the hoisting explanations of the packages installed more than once
+    pub fn set_hoisting(&mut self, hoisting: BTreeMap<String, HoistingExplanation>) {
+        self.hoisting = hoisting;
+    }
 
-    let mut query_chars = query_lower.chars().peekable();
-    for c in text_lower.chars() {
-        if let Some(&q) = query_chars.peek() {
-            if c == q {
-                query_chars.next();
-            }
-        }
-        if query_chars.peek().is_none() {
-            return true;
+    /// Toggle the hoisting panel visibility
+    ///
+    /// The panel follows the selection, explaining the copies of the
+    /// selected package.
+    pub fn toggle_hoisting_panel(&mut self) {
+        if self.has_hoisting_data() {
+            let show = !self.show_hoisting_panel;
+            self.close_side_panels();
+            self.show_hoisting_panel = show;
         }
     }
-    query_chars.peek().is_none()
-}
 
-/// Get the color for a dependency type, with cycle and conflict overrides
-///
-/// Returns the appropriate color based on the dependency category:
-/// - Cycle nodes: Red (circular dependency warning - highest priority)
-/// - Conflict nodes: Rgb(255, 165, 0) orange (version conflict warning)
-/// - Production: Green (bundled with the application)
-/// - Development: Yellow (only needed during development)
-/// - Peer: Cyan (expected to be provided by the consumer)
-/// - Optional: Gray (enhance functionality if available)
-fn get_dep_type_color(dep_type: Option<DependencyType>, is_in_cycle: bool, has_conflict: bool) -> Color {
-    // Cycle nodes are always shown in red regardless of dependency type (highest priority)
-    if is_in_cycle {
-        return Color::Red;
+    /// Check if any package is installed more than once
+    pub fn has_hoisting_data(&self) -> bool {
+        !self.hoisting.is_empty()
     }
-    // Conflict nodes shown in orange
-    if has_conflict {
-        return Color::Rgb(255, 165, 0); // Orange color
+
+    /// Get the hoisting explanation of the selected node, if it is installed
+    /// more than once
+    pub fn selected_hoisting(&self) -> Option<&HoistingExplanation> {
+        self.selected_node().and_then(|node| self.hoisting.get(&node.name))
     }
-    match dep_type {
-        Some(DependencyType::Production) => Color::Green,
-        Some(DependencyType::Development) => Color::Yellow,
-        Some(DependencyType::Peer) => Color::Cyan,
-        Some(DependencyType::Optional) => Color::Gray,
-        None => Color::White, // Root node or unknown type
+
+    /// Set the files importing each package
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Directory the files are read from; paths under it are
+    ///   shown relative to it
+    /// * `sites` - Importing files per package name, as returned by
+    ///   [`ProjectImports::import_sites`](crate::analysis::ProjectImports::import_sites)
+    pub fn set_import_sites(&mut self, root: &Path, mut sites: BTreeMap<String, Vec<ImportSite>>) {
+        for site in sites.values_mut().flatten() {
+            if let Ok(relative) = Path::new(&site.file).strip_prefix(root) {
+                site.file = relative.display().to_string();
+            }
+        }
+        self.source_root = root.to_path_buf();
+        self.import_sites = sites;
+        self.import_selected.clear();
+        self.import_preview = None;
     }
-}
 
-/// Maximum depth for color gradient calculations
-const MAX_DEPTH_FOR_COLOR: usize = 10;
+    /// Toggle the imports panel visibility
+    ///
+    /// The panel follows the selection, listing the files importing the
+    /// selected package with a preview of the selected file's imports.
+    pub fn toggle_imports_panel(&mut self) {
+        if self.has_import_data() {
+            let show = !self.show_imports_panel;
+            self.close_side_panels();
+            self.show_imports_panel = show;
+        }
+    }
 
-/// Get color intensity based on depth (brighter = closer to root)
-///
-/// Returns a brightness factor from 0.0 to 1.0 where:
-/// - Depth 0 (root): 1.0 (brightest)
-/// - Max depth: 0.4 (dimmer but still visible)
-fn get_depth_brightness(depth: usize) -> f32 {
-    let clamped_depth = depth.min(MAX_DEPTH_FOR_COLOR);
-    let ratio = clamped_depth as f32 / MAX_DEPTH_FOR_COLOR as f32;
-    // Linear interpolation from 1.0 (bright) to 0.4 (dim)
-    1.0 - (ratio * 0.6)
-}
+    /// Check if the project's imports were analyzed
+    pub fn has_import_data(&self) -> bool {
+        !self.import_sites.is_empty()
+    }
 
-/// Apply brightness modifier to a color based on depth
-///
-/// Adjusts the color brightness so deeper nodes appear dimmer,
-/// making the dependency chain depth immediately visible.
-fn apply_depth_color(base_color: Color, depth: usize) -> Color {
-    let brightness = get_depth_brightness(depth);
+    /// Get the files importing the selected node
+    pub fn selected_import_sites(&self) -> &[ImportSite] {
+        self.selected_node()
+            .and_then(|node| self.import_sites.get(&node.name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
 
-    match base_color {
-        Color::Rgb(r, g, b) => {
-            Color::Rgb(
-                (r as f32 * brightness) as u8,
-                (g as f32 * brightness) as u8,
-                (b as f32 * brightness) as u8,
-            )
-        }
-        Color::Green => {
-            let base = 255_f32;
-            Color::Rgb(0, (base * brightness) as u8, 0)
-        }
-        Color::Yellow => {
-            let base = 255_f32;
-            Color::Rgb((base * brightness) as u8, (base * brightness) as u8, 0)
-        }
-        Color::Cyan => {
-            let base = 255_f32;
-            Color::Rgb(0, (base * brightness) as u8, (base * brightness) as u8)
-        }
-        Color::Red => {
-            let base = 255_f32;
-            Color::Rgb((base * brightness) as u8, 0, 0)
+    /// Get the position of the selected importing file among the files
+    /// importing the selected node
+    pub fn selected_import_index(&self) -> usize {
+        let selected = self
+            .selected_node()
+            .and_then(|node| self.import_selected.get(&node.name))
+            .copied()
+            .unwrap_or(0);
+        selected.min(self.selected_import_sites().len().saturating_sub(1))
+    }
+
+    /// Move to the next file importing the selected node, wrapping around
+    pub fn select_next_import(&mut self) {
+        let count = self.selected_import_sites().len();
+        if let (Some(name), true) = (self.selected_node().map(|node| node.name.clone()), count > 0) {
+            let next = (self.selected_import_index() + 1) % count;
+            self.import_selected.insert(name, next);
         }
-        Color::Gray => {
-            let base = 128_f32;
-            let val = (base * brightness) as u8;
-            Color::Rgb(val, val, val)
+    }
+
+    /// Move to the previous file importing the selected node, wrapping
+    /// around
+    pub fn select_previous_import(&mut self) {
+        let count = self.selected_import_sites().len();
+        if let (Some(name), true) = (self.selected_node().map(|node| node.name.clone()), count > 0) {
+            let previous = (self.selected_import_index() + count - 1) % count;
+            self.import_selected.insert(name, previous);
         }
-        Color::White => {
-            let base = 255_f32;
-            let val = (base * brightness) as u8;
-            Color::Rgb(val, val, val)
+    }
+
+    /// Get the preview of the selected importing file, reading the file
+    /// when the selection moved to another one
+    pub fn selected_import_preview(&mut self) -> Option<&Result<SourcePreview, String>> {
+        let name = self.selected_node()?.name.clone();
+        let site = self.selected_import_sites().get(self.selected_import_index())?.clone();
+        let key = (name, site.file.clone());
+        if self.import_preview.as_ref().map(|(cached, _)| cached) != Some(&key) {
+            let preview = SourcePreview::load(&self.source_root.join(&site.file), &site.lines, CONTEXT_LINES)
+                .map_err(|e| e.to_string());
+            self.import_preview = Some((key, preview));
         }
-        // For other color types, return as-is
-        other => other,
+        self.import_preview.as_ref().map(|(_, preview)| preview)
     }
-}
 
-/// Get the depth indicator string for a node
-///
-/// Returns a depth level indicator that shows how deep in the
-/// dependency tree this node is located.
-fn get_depth_indicator(depth: usize) -> String {
-    if depth == 0 {
-        String::new() // Root node doesn't need depth indicator
-    } else {
-        format!("L{} ", depth)
+    /// Set the directory and web pages of the installed packages
+    pub fn set_package_links(&mut self, links: BTreeMap<String, PackageLinks>) {
+        self.package_links = links;
     }
-}
 
-/// Get the short type indicator for a dependency type
-///
-/// Returns a short label for display next to the dependency name:
-/// - P: Production
-/// - D: Development
-/// - Pe: Peer
-/// - O: Optional
-fn get_dep_type_indicator(dep_type: Option<DependencyType>) -> &'static str {
-    match dep_type {
-        Some(DependencyType::Production) => "[P] ",
-        Some(DependencyType::Development) => "[D] ",
-        Some(DependencyType::Peer) => "[Pe] ",
-        Some(DependencyType::Optional) => "[O] ",
-        None => "", // Root node or unknown type
+    /// Open web pages in the browser, or only show their URLs
+    pub fn set_open_browser(&mut self, open: bool) {
+        self.open_browser = open;
     }
-}
 
-/// Get the cycle indicator if the node is part of a circular dependency
-///
-/// Returns a warning symbol for nodes in cycles
-fn get_cycle_indicator(is_in_cycle: bool) -> &'static str {
-    if is_in_cycle {
-        "[!] "
-    } else {
-        ""
+    /// Ask to open the selected package's directory in the editor
+    pub fn open_selected_directory(&mut self) {
+        let Some(name) = self.selected_node().map(|node| node.name.clone()) else {
+            return;
+        };
+        match self.package_links.get(&name).and_then(|links| links.directory.clone()) {
+            Some(dir) => self.open_request = Some(OpenRequest::Editor(dir)),
+            None => self.status = Some(format!("{} is not installed in a directory", name)),
+        }
     }
-}
 
-/// Get the conflict indicator if the node has version conflicts
-///
-/// Returns a warning symbol for nodes with conflicts
-fn get_conflict_indicator(has_conflict: bool) -> &'static str {
-    if has_conflict {
-        "[~] "
-    } else {
-        ""
+    /// Ask to open a web page of the selected package, or show its URL if
+    /// the browser is off
+    pub fn open_selected_page(&mut self, page: PackagePage) {
+        let Some(name) = self.selected_node().map(|node| node.name.clone()) else {
+            return;
+        };
+        let links = self.package_links.get(&name);
+        let url = match page {
+            PackagePage::Npm => Some(npm_page(&name)),
+            PackagePage::Repository => links.and_then(|links| links.repository.clone().or(links.homepage.clone())),
+            PackagePage::Changelog => links.and_then(|links| links.changelog.clone()),
+        };
+        match url {
+            Some(url) if self.open_browser => self.open_request = Some(OpenRequest::Browser(url)),
+            Some(url) => self.status = Some(url),
+            None if links.is_none() => self.status = Some(format!("{} is not installed; its package.json is unknown", name)),
+            None => self.status = Some(format!("{}'s package.json does not name a repository", name)),
+        }
     }
-}
 
-/// Size thresholds for color coding (in bytes)
-const SIZE_LARGE_THRESHOLD: u64 = 500 * 1024; // 500KB
-const SIZE_MEDIUM_THRESHOLD: u64 = 100 * 1024; // 100KB
+    /// Set the report that selections are exported from, and the project
+    /// directory the exports are written under
+    pub fn set_export_report(&mut self, report: ExportReport, project_dir: &Path) {
+        self.export_report = Some(report);
+        self.export_dir = project_dir.to_path_buf();
+    }
 
-/// Get the color for a bundle size based on thresholds
-///
-/// Returns the appropriate color based on size:
-/// - Red: Large (> 500KB)
-/// - Yellow: Medium (100KB - 500KB)
-/// - Green: Small (< 100KB)
-fn get_size_color(bytes: u64) -> Color {
-    if bytes >= SIZE_LARGE_THRESHOLD {
-        Color::Red
-    } else if bytes >= SIZE_MEDIUM_THRESHOLD {
-        Color::Yellow
-    } else {
-        Color::Green
+    /// Export the selection as a Markdown report in `.codescope/exports/`
+    ///
+    /// While searching, the report covers the matching packages; otherwise
+    /// the selected package and everything it depends on, or the whole
+    /// project when the project itself is selected.
+    pub fn export_selection(&mut self) {
+        let Some(mut report) = self.export_report.clone() else {
+            return;
+        };
+        let (file, focus) = if !self.search_query.is_empty() {
+            let matches: HashSet<String> = self.filtered.iter().map(|node| node.name.clone()).collect();
+            report.retain_packages(&matches);
+            (
+                format!("search-{}", self.search_query),
+                Some(format!("packages matching \"{}\"", self.search_query)),
+            )
+        } else {
+            let path = self.tree.path_to_index(self.selected_index);
+            match path.as_slice() {
+                [] => return,
+                [_] => (report.project_name.clone(), None),
+                [.., (_, node)] => {
+                    // A category row stands for the packages under it
+                    let is_package = report.packages.iter().any(|pkg| pkg.name == node.name);
+                    let mut names = Vec::new();
+                    collect_names(node, &mut names);
+                    let subtree = report.subtree(names.iter().map(String::as_str));
+                    report.retain_packages(&subtree);
+                    let focus = if is_package {
+                        format!("{} and its dependencies", node.name)
+                    } else {
+                        format!("{} and their dependencies", node.name)
+                    };
+                    (node.name.clone(), Some(focus))
+                }
+            }
+        };
+        report.apply_notes(&self.notes);
+        if let Some(metadata) = report.metadata.take() {
+            report.metadata = Some(metadata.with_focus(focus));
+        }
+
+        let file: String = file
+            .trim_start_matches('@')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '-' })
+            .collect();
+        let file = file.trim_matches('-');
+        let path = Path::new(STATE_DIR).join("exports").join(format!("{}.md", file));
+        let content = MarkdownExporter::new().export(&report);
+        let written = fs::create_dir_all(self.export_dir.join(STATE_DIR).join("exports"))
+            .and_then(|()| write_atomic(&self.export_dir.join(&path), content.as_bytes(), true));
+        self.status = Some(match written {
+            Ok(()) => format!(
+                "Exported {} package{} to {}",
+                report.packages.len(),
+                if report.packages.len() == 1 { "" } else { "s" },
+                path.display()
+            ),
+            Err(e) => format!("Failed to write {}: {}", path.display(), e),
+        });
     }
-}
 
-/// Calculate the total bundle size from all nodes in a flattened tree
-fn calculate_total_bundle_size(nodes: &[FlattenedNode]) -> u64 {
-    nodes.iter()
-        .filter_map(|n| n.bundle_size)
-        .sum()
-}
+    /// Replace the tree with one from a re-analysis, keeping the expanded
+    /// nodes and the selected package
+    pub fn replace_tree(&mut self, mut tree: TreeNode) {
+        tree.copy_expansion_from(&self.tree);
+        let selected = self.selected_node().map(|node| node.name.clone());
+        self.tree = tree;
+        self.refresh_flattened();
+        if !self.search_query.is_empty() {
+            self.update_filter();
+        }
+        if let Some(name) = selected {
+            self.select_package(&name);
+        }
+    }
 
-/// Format the size with percentage of total bundle
-fn format_size_with_percentage(bytes: u64, total: u64) -> String {
-    let size_str = format_size(bytes);
-    if total > 0 {
-        let percentage = (bytes as f64 / total as f64) * 100.0;
-        format!("{} ({:.1}%)", size_str, percentage)
-    } else {
-        size_str
+    /// Record the size changes of watch-mode run `run`
+    pub fn record_size_changes(&mut self, run: u64, changes: Vec<SizeChange>) {
+        self.watch_run = Some(run);
+        self.size_changes.record(changes);
     }
-}
 
-/// Run the TUI application
-pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
-    loop {
-        terminal.draw(|frame| render(frame, app))?;
+    /// Check if a re-analysis changed any package size
+    pub fn has_change_data(&self) -> bool {
+        !self.size_changes.is_empty()
+    }
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                if app.search_active {
-                    // Search mode key handling
-                    match key.code {
-                        KeyCode::Esc => app.clear_search(),
-                        KeyCode::Enter => {
-                            // Exit search mode but keep the filter active
-                            app.search_active = false;
-                        }
-                        KeyCode::Backspace => app.search_pop(),
-                        KeyCode::Char(c) => app.search_push(c),
-                        KeyCode::Down | KeyCode::Tab => app.select_next(),
-                        KeyCode::Up | KeyCode::BackTab => app.select_previous(),
-                        _ => {}
-                    }
-                } else {
-                    // Normal mode key handling
-                    match key.code {
-                        KeyCode::Char('q') => app.quit(),
-                        KeyCode::Esc => {
-                            if app.show_savings_panel {
-                                // Close savings panel first
-                                app.show_savings_panel = false;
-                            } else if !app.search_query.is_empty() {
-                                // Clear the filter but stay in normal mode
-                                app.clear_search();
-                            } else {
-                                app.quit();
-                            }
-                        }
-                        KeyCode::Char('/') => app.start_search(),
-                        KeyCode::Char('j') | KeyCode::Down => app.select_next(),
-                        KeyCode::Char('k') | KeyCode::Up => app.select_previous(),
-                        KeyCode::Enter | KeyCode::Char(' ') => app.toggle_selected(),
-                        // Page navigation for large trees
-                        KeyCode::PageDown | KeyCode::Char('d') => app.page_down(),
-                        KeyCode::PageUp | KeyCode::Char('u') => app.page_up(),
-                        KeyCode::Home | KeyCode::Char('g') => app.select_first(),
-                        KeyCode::End | KeyCode::Char('G') => app.select_last(),
-                        // Sort mode toggle
-                        KeyCode::Char('s') => app.cycle_sort_mode(),
-                        // Toggle savings panel
-                        KeyCode::Char('i') => app.toggle_savings_panel(),
-                        _ => {}
-                    }
-                }
-            }
+    /// Toggle the panel of recent size changes
+    pub fn toggle_changes_panel(&mut self) {
+        if self.has_change_data() {
+            let show = !self.show_changes_panel;
+            self.close_side_panels();
+            self.show_changes_panel = show;
         }
+    }
 
-        if app.should_quit {
-            return Ok(());
-        }
+    /// Set the notes on packages
+    pub fn set_notes(&mut self, notes: BTreeMap<String, String>) {
+        self.notes = notes;
     }
-}
 
-/// Render the application UI
-fn render(frame: &mut Frame, app: &mut App) {
-    // Determine if search bar is visible
-    let show_search = app.search_active || !app.search_query.is_empty();
-    let show_savings = app.show_savings_panel && app.savings_report.is_some();
+    /// Get the note on the selected node, if any
+    pub fn selected_note(&self) -> Option<&str> {
+        self.selected_node()
+            .and_then(|node| self.notes.get(&node.name))
+            .map(String::as_str)
+    }
 
-    // Calculate main layout
-    let main_chunks = if show_savings {
-        // Split horizontally: tree on left, savings panel on right
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(65), // Main content
-                Constraint::Percentage(35), // Savings panel
-            ])
-            .split(frame.area())
-    } else {
-        // Full width for main content
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(100)])
-            .split(frame.area())
-    };
+    /// Open the notes panel and start editing the note on the selected node
+    pub fn start_note(&mut self) {
+        if self.selected_node().is_none() {
+            return;
+        }
+        let note = self.selected_note().unwrap_or_default().to_string();
+        self.close_side_panels();
+        self.show_notes_panel = true;
+        self.note_input = Some(note);
+    }
 
-    // Render savings panel if visible
-    if show_savings {
-        if let Some(ref report) = app.savings_report {
-            render_savings_panel(frame, report, main_chunks[1]);
+    /// Add a character to the note being written
+    pub fn note_push(&mut self, c: char) {
+        if let Some(input) = &mut self.note_input {
+            input.push(c);
         }
     }
 
-    // Calculate vertical layout for main content area
-    let content_area = main_chunks[0];
-    let chunks = if show_search {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Length(3), // Search bar
-                Constraint::Min(0),    // Tree
+    /// Remove the last character of the note being written
+    pub fn note_pop(&mut self) {
+        if let Some(input) = &mut self.note_input {
+            input.pop();
+        }
+    }
+
+    /// Save the note being written on the selected node; a blank note
+    /// removes it
+    pub fn save_note(&mut self) {
+        let Some(input) = self.note_input.take() else {
+            return;
+        };
+        let Some(name) = self.selected_node().map(|node| node.name.clone()) else {
+            return;
+        };
+        let note = input.trim();
+        if note.is_empty() {
+            self.notes.remove(&name);
+        } else {
+            self.notes.insert(name, note.to_string());
+        }
+    }
+
+    /// Stop editing the note, keeping the saved one
+    pub fn cancel_note(&mut self) {
+        self.note_input = None;
+    }
+
+    /// Set the warnings collected during the analysis
+    ///
+    /// The notification area shows them until dismissed.
+    pub fn set_warnings(&mut self, warnings: AnalysisWarnings) {
+        self.show_warnings = !warnings.is_empty();
+        self.warnings = warnings;
+    }
+
+    /// Show or dismiss the notification area
+    pub fn toggle_warnings(&mut self) {
+        if self.has_warnings() {
+            self.show_warnings = !self.show_warnings;
+        }
+    }
+
+    /// Check if the analysis collected any warnings
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// Check if any side panel is open
+    pub fn has_open_panel(&self) -> bool {
+        self.show_savings_panel
+            || self.show_orphan_panel
+            || self.show_removal_panel
+            || self.show_group_panel
+            || self.show_scope_panel
+            || self.show_blame_panel
+            || self.show_footprint_panel
+            || self.show_chunk_panel
+            || self.show_condensed_panel
+            || self.show_hoisting_panel
+            || self.show_notes_panel
+            || self.show_imports_panel
+            || self.show_changes_panel
+    }
+
+    /// Returns true if the open side panel is a sortable table
+    pub fn has_open_table(&self) -> bool {
+        self.show_savings_panel || self.show_orphan_panel || self.show_scope_panel
+    }
+
+    /// Sort the open table by its next column and order
+    pub fn sort_table_next(&mut self) {
+        if self.show_savings_panel {
+            self.savings_table.sort_next();
+        } else if self.show_orphan_panel {
+            self.orphan_table.sort_next();
+        } else if self.show_scope_panel {
+            self.scope_table.sort_next();
+        }
+    }
+
+    /// Sort the open table by its previous column and order
+    pub fn sort_table_previous(&mut self) {
+        if self.show_savings_panel {
+            self.savings_table.sort_previous();
+        } else if self.show_orphan_panel {
+            self.orphan_table.sort_previous();
+        } else if self.show_scope_panel {
+            self.scope_table.sort_previous();
+        }
+    }
+
+    /// Select the next row of the open table
+    pub fn select_next_table_row(&mut self) {
+        if self.show_savings_panel {
+            let len = self.savings_report.as_ref().map_or(0, |report| report.package_savings.len());
+            self.savings_table.select_next(len);
+        } else if self.show_orphan_panel {
+            let len = self.orphan_report.as_ref().map_or(0, |report| report.packages.len());
+            self.orphan_table.select_next(len);
+        } else if self.show_scope_panel {
+            self.scope_table.select_next(self.scope_summaries.len());
+        }
+    }
+
+    /// Select the previous row of the open table
+    pub fn select_previous_table_row(&mut self) {
+        if self.show_savings_panel {
+            self.savings_table.select_previous();
+        } else if self.show_orphan_panel {
+            self.orphan_table.select_previous();
+        } else if self.show_scope_panel {
+            self.scope_table.select_previous();
+        }
+    }
+
+    /// Scroll the open table a column to the right
+    pub fn scroll_table_right(&mut self) {
+        if self.show_savings_panel {
+            self.savings_table.scroll_right();
+        } else if self.show_orphan_panel {
+            self.orphan_table.scroll_right();
+        } else if self.show_scope_panel {
+            self.scope_table.scroll_right();
+        }
+    }
+
+    /// Scroll the open table a column to the left
+    pub fn scroll_table_left(&mut self) {
+        if self.show_savings_panel {
+            self.savings_table.scroll_left();
+        } else if self.show_orphan_panel {
+            self.orphan_table.scroll_left();
+        } else if self.show_scope_panel {
+            self.scope_table.scroll_left();
+        }
+    }
+
+    /// Fit the scroll window to a terminal resized to `height` rows, until
+    /// the next render measures the tree again
+    pub fn resize(&mut self, height: u16) {
+        let viewport_height = self.scroll_state.viewport_height.min(height as usize);
+        self.scroll_state.set_viewport_height(viewport_height);
+        self.scroll_state.clamp(self.current_list_len());
+        self.scroll_state.ensure_visible(self.selected_index, self.current_list_len());
+    }
+
+    /// Apply an event to the app, returning whether the screen changed
+    pub fn update(&mut self, event: AppEvent) -> bool {
+        match event {
+            AppEvent::Input(event) => {
+                // Pointer moves change nothing on screen
+                if matches!(event, Event::Mouse(mouse) if mouse.kind == MouseEventKind::Moved) {
+                    return false;
+                }
+                self.handle_input(event);
+                true
+            }
+            AppEvent::Tick => {
+                self.ticks += 1;
+                // Badges expire and ages grow while the screen is still
+                self.is_animating() || self.has_change_data()
+            }
+            AppEvent::AnalysisUpdate(AnalysisUpdate::Started { run }) => {
+                self.analyzing = Some(run);
+                true
+            }
+            AppEvent::AnalysisUpdate(AnalysisUpdate::Finished { run, tree, changes }) => {
+                self.analyzing = None;
+                self.replace_tree(tree);
+                self.record_size_changes(run, changes);
+                true
+            }
+            AppEvent::Error(message) => {
+                self.analyzing = None;
+                self.status = Some(message);
+                true
+            }
+        }
+    }
+
+    /// Check if something on screen moves between ticks: the spinner of a
+    /// running analysis, or a blinking cursor
+    pub fn is_animating(&self) -> bool {
+        self.analyzing.is_some() || self.search_active || self.note_input.is_some()
+    }
+
+    /// Time until the next tick
+    pub fn tick_rate(&self) -> Duration {
+        if self.is_animating() {
+            ANIMATION_TICK
+        } else {
+            IDLE_TICK
+        }
+    }
+
+    /// Check if the blinking cursor is shown at the current tick
+    pub fn cursor_visible(&self) -> bool {
+        (self.ticks / CURSOR_BLINK_TICKS).is_multiple_of(2)
+    }
+
+    /// Handle a key press, mouse click or resize
+    fn handle_input(&mut self, event: Event) {
+        if let Event::Resize(_, height) = event {
+            self.resize(height);
+        }
+        if let Event::Mouse(mouse) = event {
+            // Clicking a breadcrumb segment jumps up to that ancestor
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                self.click(mouse.column, mouse.row);
+            }
+        }
+        if let Event::Key(key) = event {
+            if key.kind == KeyEventKind::Press {
+                self.status = None;
+                if self.note_input.is_some() {
+                    // Note editing key handling
+                    match key.code {
+                        KeyCode::Esc => self.cancel_note(),
+                        KeyCode::Enter => self.save_note(),
+                        KeyCode::Backspace => self.note_pop(),
+                        KeyCode::Char(c) => self.note_push(c),
+                        _ => {}
+                    }
+                } else if self.search_active {
+                    // Search mode key handling
+                    match key.code {
+                        KeyCode::Esc => self.clear_search(),
+                        KeyCode::Enter => {
+                            // Exit search mode but keep the filter active
+                            self.search_active = false;
+                        }
+                        KeyCode::Backspace => self.search_pop(),
+                        KeyCode::Char(c) => self.search_push(c),
+                        KeyCode::Down | KeyCode::Tab => self.select_next(),
+                        KeyCode::Up | KeyCode::BackTab => self.select_previous(),
+                        _ => {}
+                    }
+                } else {
+                    // Normal mode key handling
+                    match key.code {
+                        KeyCode::Char('q') => self.quit(),
+                        KeyCode::Esc => {
+                            if self.has_open_panel() {
+                                // Close side panels first
+                                self.close_side_panels();
+                            } else if !self.search_query.is_empty() {
+                                // Clear the filter but stay in normal mode
+                                self.clear_search();
+                            } else {
+                                self.quit();
+                            }
+                        }
+                        KeyCode::Char('/') => self.start_search(),
+                        // Navigate the open chunk graph panel, or the tree
+                        KeyCode::Char('j') | KeyCode::Down if self.show_chunk_panel => self.select_next_chunk(),
+                        KeyCode::Char('k') | KeyCode::Up if self.show_chunk_panel => self.select_previous_chunk(),
+                        // Navigate and scroll the open table
+                        KeyCode::Char('j') | KeyCode::Down if self.has_open_table() => self.select_next_table_row(),
+                        KeyCode::Char('k') | KeyCode::Up if self.has_open_table() => self.select_previous_table_row(),
+                        KeyCode::Right if self.has_open_table() => self.scroll_table_right(),
+                        KeyCode::Left if self.has_open_table() => self.scroll_table_left(),
+                        KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+                        KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+                        KeyCode::Enter | KeyCode::Char(' ') => self.toggle_selected(),
+                        // Step through the files importing the selected dependency
+                        KeyCode::Tab if self.show_imports_panel => self.select_next_import(),
+                        KeyCode::BackTab if self.show_imports_panel => self.select_previous_import(),
+                        // Jump up to the parent, as in the breadcrumb bar
+                        KeyCode::Left | KeyCode::Backspace => self.select_parent(),
+                        // Pin the selected package, or jump to a pinned one
+                        KeyCode::Char('p') => self.toggle_pin_selected(),
+                        KeyCode::Char(c @ '1'..='9') => self.select_pinned(c as usize - '1' as usize),
+                        // Page navigation for large trees
+                        KeyCode::PageDown | KeyCode::Char('d') => self.page_down(),
+                        KeyCode::PageUp | KeyCode::Char('u') => self.page_up(),
+                        KeyCode::Home | KeyCode::Char('g') => self.select_first(),
+                        KeyCode::End | KeyCode::Char('G') => self.select_last(),
+                        // Sort the open footprint panel, or the tree
+                        KeyCode::Char('s') if self.show_footprint_panel => self.cycle_footprint_sort(),
+                        KeyCode::Char('s') => self.cycle_sort_mode(),
+                        // Sort the open table by another column or order
+                        KeyCode::Char('>') => self.sort_table_next(),
+                        KeyCode::Char('<') => self.sort_table_previous(),
+                        // Toggle savings panel
+                        KeyCode::Char('i') => self.toggle_savings_panel(),
+                        // Toggle orphaned packages panel
+                        KeyCode::Char('o') => self.toggle_orphan_panel(),
+                        // Preview removing the selected dependency, then confirm
+                        KeyCode::Char('x') => self.toggle_removal_panel(),
+                        KeyCode::Char('y') => self.confirm_uninstall(),
+                        // Toggle package groups panel
+                        KeyCode::Char('t') => self.toggle_group_panel(),
+                        // Toggle npm scopes panel
+                        KeyCode::Char('@') => self.toggle_scope_panel(),
+                        // Toggle blame panel for the selected dependency
+                        KeyCode::Char('b') => self.toggle_blame_panel(),
+                        // Toggle install footprint panel
+                        KeyCode::Char('f') => self.toggle_footprint_panel(),
+                        // Toggle chunk graph panel
+                        KeyCode::Char('c') => self.toggle_chunk_panel(),
+                        // Toggle condensed graph panel
+                        KeyCode::Char('z') => self.toggle_condensed_panel(),
+                        // Toggle the log of size changes in watch mode
+                        KeyCode::Char('l') => self.toggle_changes_panel(),
+                        // Toggle hoisting panel for the selected dependency
+                        KeyCode::Char('h') => self.toggle_hoisting_panel(),
+                        // Show or dismiss the warnings notification area
+                        KeyCode::Char('w') => self.toggle_warnings(),
+                        // Write a note on the selected dependency
+                        KeyCode::Char('n') => self.start_note(),
+                        // Toggle the files importing the selected dependency
+                        KeyCode::Char('v') => self.toggle_imports_panel(),
+                        // Open the selected dependency in the editor or browser
+                        KeyCode::Char('e') => self.open_selected_directory(),
+                        KeyCode::Char('N') => self.open_selected_page(PackagePage::Npm),
+                        KeyCode::Char('R') => self.open_selected_page(PackagePage::Repository),
+                        KeyCode::Char('C') => self.open_selected_page(PackagePage::Changelog),
+                        // Export the selected subtree, or the search matches
+                        KeyCode::Char('E') => self.export_selection(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Close every side panel; only one is shown at a time
+    pub fn close_side_panels(&mut self) {
+        self.show_savings_panel = false;
+        self.show_orphan_panel = false;
+        self.show_removal_panel = false;
+        self.show_group_panel = false;
+        self.show_scope_panel = false;
+        self.show_blame_panel = false;
+        self.show_footprint_panel = false;
+        self.show_chunk_panel = false;
+        self.show_condensed_panel = false;
+        self.show_hoisting_panel = false;
+        self.show_notes_panel = false;
+        self.show_imports_panel = false;
+        self.show_changes_panel = false;
+    }
+
+    /// Get the currently selected node
+    pub fn selected_node(&self) -> Option<&FlattenedNode> {
+        if !self.search_query.is_empty() {
+            self.filtered.get(self.selected_index)
+        } else {
+            self.flattened.get(self.selected_index)
+        }
+    }
+
+    /// Show or hide the size bars next to the packages
+    pub fn set_size_bars(&mut self, show: bool) {
+        self.show_size_bars = show;
+    }
+
+    /// Get the path from the root to the selected node, each node with its
+    /// position in the tree order
+    pub fn breadcrumb(&self) -> Vec<(usize, &TreeNode)> {
+        self.selected_node()
+            .map(|node| self.tree.path_to_index(node.tree_index))
+            .unwrap_or_default()
+    }
+
+    /// Select the node at a position in the tree order, clearing the
+    /// search if the filter hides it
+    pub fn select_tree_index(&mut self, tree_index: usize) {
+        let position = |nodes: &[FlattenedNode]| nodes.iter().position(|node| node.tree_index == tree_index);
+        let mut index = if self.search_query.is_empty() {
+            position(&self.flattened)
+        } else {
+            position(&self.filtered)
+        };
+        if index.is_none() && !self.search_query.is_empty() {
+            self.clear_search();
+            index = position(&self.flattened);
+        }
+        if let Some(index) = index {
+            self.selected_index = index;
+            self.list_state.select(Some(index));
+            self.scroll_state.ensure_visible(index, self.current_list_len());
+        }
+    }
+
+    /// Move selection to the parent of the selected node
+    pub fn select_parent(&mut self) {
+        let path = self.breadcrumb();
+        if let Some(&(parent, _)) = path.len().checked_sub(2).and_then(|i| path.get(i)) {
+            self.select_tree_index(parent);
+        }
+    }
+
+    /// Jump to the breadcrumb segment or pinned package at a screen
+    /// position, if any
+    pub fn click(&mut self, column: u16, row: u16) {
+        let target = self
+            .click_targets
+            .iter()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, target)| target.clone());
+        match target {
+            Some(ClickTarget::TreeIndex(tree_index)) => self.select_tree_index(tree_index),
+            Some(ClickTarget::Package(name)) => self.select_package(&name),
+            None => {}
+        }
+    }
+
+    /// Set the pinned packages
+    pub fn set_pinned(&mut self, pinned: Vec<String>) {
+        self.pinned = pinned;
+    }
+
+    /// Pin the selected package, or unpin it if it is pinned
+    pub fn toggle_pin_selected(&mut self) {
+        let Some(name) = self.selected_node().map(|node| node.name.clone()) else {
+            return;
+        };
+        match self.pinned.iter().position(|pinned| *pinned == name) {
+            Some(index) => {
+                self.pinned.remove(index);
+            }
+            None => self.pinned.push(name),
+        }
+    }
+
+    /// Jump to the pinned package at a position of the pins strip
+    pub fn select_pinned(&mut self, index: usize) {
+        if let Some(name) = self.pinned.get(index).cloned() {
+            self.select_package(&name);
+        }
+    }
+
+    /// Select the first node of a package, clearing the search if the
+    /// filter hides it and expanding its ancestors if they are collapsed
+    pub fn select_package(&mut self, name: &str) {
+        if !self.search_query.is_empty() && !self.filtered.iter().any(|node| node.name == name) {
+            self.clear_search();
+        }
+        if self.search_query.is_empty()
+            && !self.flattened.iter().any(|node| node.name == name)
+            && self.tree.expand_path_to(name)
+        {
+            self.refresh_flattened();
+        }
+        let nodes = if self.search_query.is_empty() { &self.flattened } else { &self.filtered };
+        if let Some(tree_index) = nodes.iter().find(|node| node.name == name).map(|node| node.tree_index) {
+            self.select_tree_index(tree_index);
+        }
+    }
+
+    /// Get the simulated removal of the selected node, if it is a direct dependency
+    pub fn selected_removal_impact(&self) -> Option<&RemovalImpact> {
+        self.selected_node()
+            .and_then(|node| self.removal_impacts.get(&node.name))
+    }
+
+    /// Confirm uninstalling the package previewed in the removal panel
+    ///
+    /// The TUI quits so the package manager can run in the normal terminal.
+    pub fn confirm_uninstall(&mut self) {
+        if !self.show_removal_panel {
+            return;
+        }
+        if let Some(impact) = self.selected_removal_impact() {
+            self.uninstall_request = Some(impact.package.clone());
+            self.quit();
+        }
+    }
+
+    /// Refresh the flattened view from the tree
+    pub fn refresh_flattened(&mut self) {
+        self.flattened = self.tree.flatten();
+        self.apply_sort();
+        self.rebuild_ancestors_last();
+
+        // Ensure selected index is valid
+        if !self.flattened.is_empty() && self.selected_index >= self.flattened.len() {
+            self.selected_index = self.flattened.len() - 1;
+        }
+    }
+
+    /// Apply the current sort mode to the flattened view
+    ///
+    /// For size-based sorting, the view is flattened (tree structure is lost)
+    /// to allow proper comparison by bundle size. Nodes without bundle size
+    /// are placed at the end.
+    fn apply_sort(&mut self) {
+        match self.sort_mode {
+            SortMode::Alphabetical => {
+                // Alphabetical mode preserves tree structure (already sorted by tree traversal)
+                // No additional sorting needed
+            }
+            SortMode::SizeDescending => {
+                // Sort by size descending, nodes without size go last
+                self.flattened.sort_by(|a, b| {
+                    match (a.bundle_size, b.bundle_size) {
+                        (Some(size_a), Some(size_b)) => size_b.cmp(&size_a),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => a.name.cmp(&b.name),
+                    }
+                });
+            }
+            SortMode::SizeAscending => {
+                // Sort by size ascending, nodes without size go last
+                self.flattened.sort_by(|a, b| {
+                    match (a.bundle_size, b.bundle_size) {
+                        (Some(size_a), Some(size_b)) => size_a.cmp(&size_b),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => a.name.cmp(&b.name),
+                    }
+                });
+            }
+        }
+    }
+
+    /// Cycle to the next sort mode and refresh the view
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.cycle();
+        self.refresh_flattened();
+        // Reset selection to top when changing sort mode
+        self.selected_index = 0;
+        self.list_state.select(Some(0));
+        self.scroll_state.offset = 0;
+    }
+
+    /// Set the sort mode and refresh the view
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        if self.sort_mode != mode {
+            self.sort_mode = mode;
+            self.refresh_flattened();
+            self.selected_index = 0;
+            self.list_state.select(Some(0));
+            self.scroll_state.offset = 0;
+        }
+    }
+
+    /// Rebuild the ancestors_last tracking for tree drawing
+    fn rebuild_ancestors_last(&mut self) {
+        self.ancestors_last.clear();
+        for node in &self.flattened {
+            // Track the is_last_child status for each depth level
+            while self.ancestors_last.len() < node.depth {
+                self.ancestors_last.push(false);
+            }
+            if node.depth > 0 && self.ancestors_last.len() >= node.depth {
+                self.ancestors_last[node.depth - 1] = node.is_last_child;
+            }
+        }
+    }
+
+    /// Move selection to the next item
+    pub fn select_next(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 {
+            self.selected_index = (self.selected_index + 1).min(total - 1);
+            self.list_state.select(Some(self.selected_index));
+            self.scroll_state.ensure_visible(self.selected_index, total);
+        }
+    }
+
+    /// Move selection to the previous item
+    pub fn select_previous(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 && self.selected_index > 0 {
+            self.selected_index -= 1;
+            self.list_state.select(Some(self.selected_index));
+            self.scroll_state.ensure_visible(self.selected_index, total);
+        }
+    }
+
+    /// Move selection down by a page
+    pub fn page_down(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 {
+            let page_size = self.scroll_state.viewport_height.max(1);
+            self.selected_index = (self.selected_index + page_size).min(total - 1);
+            self.list_state.select(Some(self.selected_index));
+            self.scroll_state.ensure_visible(self.selected_index, total);
+        }
+    }
+
+    /// Move selection up by a page
+    pub fn page_up(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 {
+            let page_size = self.scroll_state.viewport_height.max(1);
+            self.selected_index = self.selected_index.saturating_sub(page_size);
+            self.list_state.select(Some(self.selected_index));
+            self.scroll_state.ensure_visible(self.selected_index, total);
+        }
+    }
+
+    /// Jump to the first item
+    pub fn select_first(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 {
+            self.selected_index = 0;
+            self.list_state.select(Some(0));
+            self.scroll_state.offset = 0;
+        }
+    }
+
+    /// Jump to the last item
+    pub fn select_last(&mut self) {
+        let total = self.current_list_len();
+        if total > 0 {
+            self.selected_index = total - 1;
+            self.list_state.select(Some(self.selected_index));
+            self.scroll_state.ensure_visible(self.selected_index, total);
+        }
+    }
+
+    /// Get the current list length (filtered or full)
+    fn current_list_len(&self) -> usize {
+        if !self.search_query.is_empty() {
+            self.filtered.len()
+        } else {
+            self.flattened.len()
+        }
+    }
+
+    /// Toggle expansion of the selected item
+    pub fn toggle_selected(&mut self) {
+        if self.tree.toggle_at_index(self.selected_index) {
+            self.refresh_flattened();
+        }
+    }
+
+    /// Signal that the application should quit
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    /// Get the tree prefix for a node at the given index
+    fn get_tree_prefix(&self, index: usize) -> String {
+        if index >= self.flattened.len() {
+            return String::new();
+        }
+
+        let node = &self.flattened[index];
+        let mut prefix = String::new();
+
+        // Build ancestors_last for this specific path
+        let mut ancestors_last_for_node = Vec::new();
+        let mut current_depth = 0;
+
+        for (i, n) in self.flattened.iter().enumerate().take(index + 1) {
+            if n.depth <= current_depth || i == index {
+                while ancestors_last_for_node.len() > n.depth {
+                    ancestors_last_for_node.pop();
+                }
+            }
+            if n.depth > 0 {
+                while ancestors_last_for_node.len() < n.depth {
+                    ancestors_last_for_node.push(false);
+                }
+                if ancestors_last_for_node.len() >= n.depth {
+                    ancestors_last_for_node[n.depth - 1] = n.is_last_child;
+                }
+            }
+            current_depth = n.depth;
+        }
+
+        // Build the prefix string
+        for i in 0..node.depth {
+            if i < ancestors_last_for_node.len() {
+                if ancestors_last_for_node[i] {
+                    prefix.push_str("    ");
+                } else {
+                    prefix.push_str("│   ");
+                }
+            } else {
+                prefix.push_str("    ");
+            }
+        }
+
+        // Add the branch connector
+        if node.depth > 0 {
+            if node.is_last_child {
+                prefix.push_str("└── ");
+            } else {
+                prefix.push_str("├── ");
+            }
+        }
+
+        prefix
+    }
+
+    /// Start search mode
+    pub fn start_search(&mut self) {
+        self.search_active = true;
+        self.search_query.clear();
+    }
+
+    /// Clear search and return to normal mode
+    pub fn clear_search(&mut self) {
+        self.search_active = false;
+        self.search_query.clear();
+        self.filtered.clear();
+        // Reset selection to first item
+        self.selected_index = 0;
+        self.list_state.select(Some(0));
+    }
+
+    /// Set the package descriptions and keywords searched alongside names
+    pub fn set_package_descriptions(&mut self, descriptions: BTreeMap<String, PackageDescription>) {
+        self.package_descriptions = descriptions;
+        if !self.search_query.is_empty() {
+            self.update_filter();
+        }
+    }
+
+    /// Add a character to the search query
+    pub fn search_push(&mut self, c: char) {
+        self.search_query.push(c);
+        self.update_filter();
+    }
+
+    /// Remove the last character from the search query
+    pub fn search_pop(&mut self) {
+        self.search_query.pop();
+        self.update_filter();
+    }
+
+    /// Update the filtered view based on the current search query
+    ///
+    /// Names match fuzzily; descriptions and keywords match when they
+    /// contain every word of the query.
+    fn update_filter(&mut self) {
+        if self.search_query.is_empty() {
+            self.filtered.clear();
+            self.selected_index = 0;
+        } else {
+            self.filtered = self
+                .flattened
+                .iter()
+                .filter(|node| {
+                    fuzzy_match(&node.name, &self.search_query)
+                        || self
+                            .package_descriptions
+                            .get(&node.name)
+                            .is_some_and(|description| description.matches(&self.search_query))
+                })
+                .cloned()
+                .collect();
+
+            // Reset selection if current selection is out of bounds
+            if !self.filtered.is_empty() {
+                self.selected_index = 0;
+            }
+        }
+        self.list_state.select(Some(self.selected_index));
+    }
+
+}
+
+/// Perform fuzzy matching of query against text (case-insensitive)
+/// A match requires all characters of the query to appear in order in the text
+pub(super) fn fuzzy_match(text: &str, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+
+    let text_lower = text.to_lowercase();
+    let query_lower = query.to_lowercase();
+
+    let mut query_chars = query_lower.chars().peekable();
+    for c in text_lower.chars() {
+        if let Some(&q) = query_chars.peek() {
+            if c == q {
+                query_chars.next();
+            }
+        }
+        if query_chars.peek().is_none() {
+            return true;
+        }
+    }
+    query_chars.peek().is_none()
+}
+
+/// Get the color for a dependency type, with cycle and conflict overrides
+///
+/// Returns the appropriate color based on the dependency category:
+/// - Cycle nodes: Red (circular dependency warning - highest priority)
+/// - Conflict nodes: Rgb(255, 165, 0) orange (version conflict warning)
+/// - Production: Green (bundled with the application)
+/// - Development: Yellow (only needed during development)
+/// - Peer: Cyan (expected to be provided by the consumer)
+/// - Optional: Gray (enhance functionality if available)
+fn get_dep_type_color(dep_type: Option<DependencyType>, is_in_cycle: bool, has_conflict: bool) -> Color {
+    // Cycle nodes are always shown in red regardless of dependency type (highest priority)
+    if is_in_cycle {
+        return Color::Red;
+    }
+    // Conflict nodes shown in orange
+    if has_conflict {
+        return Color::Rgb(255, 165, 0); // Orange color
+    }
+    match dep_type {
+        Some(DependencyType::Production) => Color::Green,
+        Some(DependencyType::Development) => Color::Yellow,
+        Some(DependencyType::Peer) => Color::Cyan,
+        Some(DependencyType::Optional) => Color::Gray,
+        None => Color::White, // Root node or unknown type
+    }
+}
+
+/// Maximum depth for color gradient calculations
+const MAX_DEPTH_FOR_COLOR: usize = 10;
+
+/// Get color intensity based on depth (brighter = closer to root)
+///
+/// Returns a brightness factor from 0.0 to 1.0 where:
+/// - Depth 0 (root): 1.0 (brightest)
+/// - Max depth: 0.4 (dimmer but still visible)
+fn get_depth_brightness(depth: usize) -> f32 {
+    let clamped_depth = depth.min(MAX_DEPTH_FOR_COLOR);
+    let ratio = clamped_depth as f32 / MAX_DEPTH_FOR_COLOR as f32;
+    // Linear interpolation from 1.0 (bright) to 0.4 (dim)
+    1.0 - (ratio * 0.6)
+}
+
+/// Apply brightness modifier to a color based on depth
+///
+/// Adjusts the color brightness so deeper nodes appear dimmer,
+/// making the dependency chain depth immediately visible.
+fn apply_depth_color(base_color: Color, depth: usize) -> Color {
+    let brightness = get_depth_brightness(depth);
+
+    match base_color {
+        Color::Rgb(r, g, b) => {
+            Color::Rgb(
+                (r as f32 * brightness) as u8,
+                (g as f32 * brightness) as u8,
+                (b as f32 * brightness) as u8,
+            )
+        }
+        Color::Green => {
+            let base = 255_f32;
+            Color::Rgb(0, (base * brightness) as u8, 0)
+        }
+        Color::Yellow => {
+            let base = 255_f32;
+            Color::Rgb((base * brightness) as u8, (base * brightness) as u8, 0)
+        }
+        Color::Cyan => {
+            let base = 255_f32;
+            Color::Rgb(0, (base * brightness) as u8, (base * brightness) as u8)
+        }
+        Color::Red => {
+            let base = 255_f32;
+            Color::Rgb((base * brightness) as u8, 0, 0)
+        }
+        Color::Gray => {
+            let base = 128_f32;
+            let val = (base * brightness) as u8;
+            Color::Rgb(val, val, val)
+        }
+        Color::White => {
+            let base = 255_f32;
+            let val = (base * brightness) as u8;
+            Color::Rgb(val, val, val)
+        }
+        // For other color types, return as-is
+        other => other,
+    }
+}
+
+/// Get the depth indicator string for a node
+///
+/// Returns a depth level indicator that shows how deep in the
+/// dependency tree this node is located.
+fn get_depth_indicator(depth: usize) -> String {
+    if depth == 0 {
+        String::new() // Root node doesn't need depth indicator
+    } else {
+        format!("L{} ", depth)
+    }
+}
+
+/// Get the short type indicator for a dependency type
+///
+/// Returns a short label for display next to the dependency name:
+/// - P: Production
+/// - D: Development
+/// - Pe: Peer
+/// - O: Optional
+fn get_dep_type_indicator(dep_type: Option<DependencyType>) -> &'static str {
+    match dep_type {
+        Some(DependencyType::Production) => "[P] ",
+        Some(DependencyType::Development) => "[D] ",
+        Some(DependencyType::Peer) => "[Pe] ",
+        Some(DependencyType::Optional) => "[O] ",
+        None => "", // Root node or unknown type
+    }
+}
+
+/// Get the cycle indicator if the node is part of a circular dependency
+///
+/// Returns a warning symbol for nodes in cycles
+fn get_cycle_indicator(is_in_cycle: bool) -> &'static str {
+    if is_in_cycle {
+        "[!] "
+    } else {
+        ""
+    }
+}
+
+/// Get the conflict indicator if the node has version conflicts
+///
+/// Returns a warning symbol for nodes with conflicts
+fn get_conflict_indicator(has_conflict: bool) -> &'static str {
+    if has_conflict {
+        "[~] "
+    } else {
+        ""
+    }
+}
+
+/// Get the pre-release indicator if the node is at an unstable version
+fn get_prerelease_indicator(is_prerelease: bool) -> &'static str {
+    if is_prerelease {
+        "[pre] "
+    } else {
+        ""
+    }
+}
+
+/// Size thresholds for color coding (in bytes)
+const SIZE_LARGE_THRESHOLD: u64 = 500 * 1024; // 500KB
+const SIZE_MEDIUM_THRESHOLD: u64 = 100 * 1024; // 100KB
+
+/// Get the color for a bundle size based on thresholds
+///
+/// Returns the appropriate color based on size:
+/// - Red: Large (> 500KB)
+/// - Yellow: Medium (100KB - 500KB)
+/// - Green: Small (< 100KB)
+fn get_size_color(bytes: u64) -> Color {
+    if bytes >= SIZE_LARGE_THRESHOLD {
+        Color::Red
+    } else if bytes >= SIZE_MEDIUM_THRESHOLD {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+/// Calculate the total bundle size from all nodes in a flattened tree
+fn calculate_total_bundle_size(nodes: &[FlattenedNode]) -> u64 {
+    nodes.iter()
+        .filter_map(|n| n.bundle_size)
+        .sum()
+}
+
+/// Format the size with percentage of total bundle
+fn format_size_with_percentage(bytes: u64, total: u64) -> String {
+    let size_str = format_size(bytes);
+    if total > 0 {
+        let percentage = (bytes as f64 / total as f64) * 100.0;
+        format!("{} ({:.1}%)", size_str, percentage)
+    } else {
+        size_str
+    }
+}
+
+/// Width of the size bars, in cells
+const SIZE_BAR_WIDTH: usize = 12;
+
+/// Partial blocks by eighths of a cell, from one eighth to a full block
+const BAR_EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Draw a bar of `width` cells filled to the share `bytes` has of `total`
+///
+/// Any non-zero share shows at least an eighth of a cell.
+fn size_bar(bytes: u64, total: u64, width: usize) -> String {
+    let eighths = if total == 0 {
+        0
+    } else {
+        let exact = (bytes as f64 / total as f64).min(1.0) * (width * 8) as f64;
+        (exact.round() as usize).max(usize::from(bytes > 0))
+    };
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(BAR_EIGHTHS[eighths % 8 - 1]);
+    }
+    let filled = bar.chars().count();
+    bar.push_str(&" ".repeat(width - filled));
+    bar
+}
+
+/// Run the TUI application
+pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
+    run_loop(terminal, app, None)
+}
+
+/// Run the TUI application, applying the events another thread sends on
+/// `updates` as they arrive
+///
+/// Watch mode re-analyzes the project on its own thread when its inputs
+/// change, and reports each run as an [`AnalysisUpdate`].
+pub fn run_app_watching<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    updates: &Receiver<AppEvent>,
+) -> io::Result<()> {
+    run_loop(terminal, app, Some(updates))
+}
+
+/// Shortest time between two frames; events arriving faster, such as a
+/// held key, are handled together before the next frame
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// The event loop: waits for input without spinning between ticks, hands
+/// every event to [`App::update`], and redraws only when one changed the
+/// screen, at most once per frame interval
+fn run_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    updates: Option<&Receiver<AppEvent>>,
+) -> io::Result<()> {
+    let mut last_tick = Instant::now();
+    let mut last_frame: Option<Instant> = None;
+    let mut dirty = true;
+    loop {
+        if let Some(updates) = updates {
+            while let Ok(event) = updates.try_recv() {
+                dirty |= app.update(event);
+            }
+        }
+        let tick_rate = app.tick_rate();
+        if last_tick.elapsed() >= tick_rate {
+            dirty |= app.update(AppEvent::Tick);
+            last_tick = Instant::now();
+        }
+        let frame_due = last_frame.is_none_or(|at| at.elapsed() >= FRAME_INTERVAL);
+        if dirty && frame_due {
+            terminal.draw(|frame| render(frame, app))?;
+            last_frame = Some(Instant::now());
+            dirty = false;
+        }
+
+        // Sleep until input arrives or the next tick or frame is due
+        let mut timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if let (true, Some(at)) = (dirty, last_frame) {
+            timeout = timeout.min(FRAME_INTERVAL.saturating_sub(at.elapsed()));
+        }
+        if event::poll(timeout)? {
+            dirty |= app.update(AppEvent::Input(event::read()?));
+        }
+
+        match app.open_request.take() {
+            Some(OpenRequest::Editor(dir)) => match editor_command() {
+                Some(editor) => {
+                    if let Err(e) = open_in_editor(&editor, &dir) {
+                        app.status = Some(format!("Failed to open {}: {}", dir.display(), e));
+                    }
+                    // The editor drew over the screen
+                    terminal.clear()?;
+                }
+                None => app.status = Some("Set $EDITOR to open packages in an editor".to_string()),
+            },
+            Some(OpenRequest::Browser(url)) => {
+                if let Err(e) = open_in_browser(&url) {
+                    app.status = Some(format!("Failed to open a browser ({}): {}", e, url));
+                }
+            }
+            None => {}
+        }
+
+        if app.should_quit {
+            return Ok(());
+        }
+    }
+}
+
+/// Collect the names of a tree node and its descendants
+fn collect_names(node: &TreeNode, names: &mut Vec<String>) {
+    names.push(node.name.clone());
+    for child in &node.children {
+        collect_names(child, names);
+    }
+}
+
+/// Terminal width below which the side panel goes under the tree rather
+/// than beside it
+const STACKED_PANEL_WIDTH: u16 = 100;
+
+/// Render the application UI
+fn render(frame: &mut Frame, app: &mut App) {
+    if !screen::fits(frame.area()) {
+        screen::render_too_small(frame);
+        return;
+    }
+
+    // Determine if search bar is visible
+    let show_search = app.search_active || !app.search_query.is_empty();
+    let show_savings = app.show_savings_panel && app.savings_report.is_some();
+    let show_orphans = app.show_orphan_panel && app.orphan_report.is_some();
+    let show_removal = app.show_removal_panel && app.has_removal_data();
+    let show_groups = app.show_group_panel && app.has_group_data();
+    let show_scopes = app.show_scope_panel && app.has_scope_data();
+    let show_blame = app.show_blame_panel && app.has_blame_data();
+    let show_footprint = app.show_footprint_panel && app.has_footprint_data();
+    let show_chunks = app.show_chunk_panel && app.has_chunk_data();
+    let show_condensed = app.show_condensed_panel && app.has_condensed_data();
+    let show_hoisting = app.show_hoisting_panel && app.has_hoisting_data();
+    let show_notes = app.show_notes_panel;
+    let show_imports = app.show_imports_panel && app.has_import_data();
+    let show_changes = app.show_changes_panel && app.has_change_data();
+
+    // Calculate main layout
+    let main_chunks = if show_savings
+        || show_orphans
+        || show_removal
+        || show_groups
+        || show_scopes
+        || show_blame
+        || show_footprint
+        || show_chunks
+        || show_condensed
+        || show_hoisting
+        || show_notes
+        || show_imports
+        || show_changes
+    {
+        if frame.area().width < STACKED_PANEL_WIDTH {
+            // Split vertically on narrow terminals: tree on top, panel below
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(60), // Main content
+                    Constraint::Percentage(40), // Side panel
+                ])
+                .split(frame.area())
+        } else {
+            // Split horizontally: tree on left, savings panel on right
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(65), // Main content
+                    Constraint::Percentage(35), // Side panel
+                ])
+                .split(frame.area())
+        }
+    } else {
+        // Full width for main content
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(100)])
+            .split(frame.area())
+    };
+
+    // Render savings panel if visible
+    if show_savings {
+        if let Some(ref report) = app.savings_report {
+            render_savings_panel(frame, report, &mut app.savings_table, main_chunks[1]);
+        }
+    } else if show_orphans {
+        if let Some(ref report) = app.orphan_report {
+            render_orphan_panel(frame, report, &mut app.orphan_table, main_chunks[1]);
+        }
+    } else if show_removal {
+        let selected = app.selected_node().map(|node| node.name.clone()).unwrap_or_default();
+        render_removal_panel(frame, &selected, app.selected_removal_impact(), main_chunks[1]);
+    } else if show_groups {
+        render_group_panel(frame, &app.group_summaries, main_chunks[1]);
+    } else if show_scopes {
+        render_scope_panel(frame, &app.scope_summaries, &mut app.scope_table, main_chunks[1]);
+    } else if show_blame {
+        let selected = app.selected_node().map(|node| node.name.clone()).unwrap_or_default();
+        render_blame_panel(frame, &selected, app.selected_origin(), main_chunks[1]);
+    } else if show_footprint {
+        if let Some(ref report) = app.footprint_report {
+            render_footprint_panel(frame, report, app.footprint_sort, main_chunks[1]);
+        }
+    } else if show_chunks {
+        if let Some(ref graph) = app.chunk_graph {
+            render_chunk_panel(frame, graph, &app.chunk_rows, app.chunk_selected, main_chunks[1]);
+        }
+    } else if show_condensed {
+        if let Some(ref condensation) = app.condensation {
+            let selected = app.selected_node().map(|node| node.name.as_str()).unwrap_or_default();
+            render_condensed_panel(frame, condensation, selected, main_chunks[1]);
+        }
+    } else if show_hoisting {
+        let selected = app.selected_node().map(|node| node.name.clone()).unwrap_or_default();
+        render_hoisting_panel(frame, &selected, app.selected_hoisting(), app.hoisting.len(), main_chunks[1]);
+    } else if show_notes {
+        let selected = app.selected_node().map(|node| node.name.clone()).unwrap_or_default();
+        let input = app.note_input.as_deref().map(|input| (input, app.cursor_visible()));
+        render_notes_panel(frame, &selected, app.selected_note(), input, main_chunks[1]);
+    } else if show_imports {
+        render_imports_panel(frame, app, main_chunks[1]);
+    } else if show_changes {
+        render_changes_panel(frame, &app.size_changes, Instant::now(), main_chunks[1]);
+    }
+
+    // Calculate vertical layout for main content area, keeping room for
+    // the notification area below the tree
+    let mut content_area = main_chunks[0];
+    if app.show_warnings && app.has_warnings() {
+        let height = (app.warnings.len().min(MAX_NOTIFICATION_LINES) as u16 + 2)
+            .min(content_area.height / 3);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(height)])
+            .split(content_area);
+        content_area = areas[0];
+        render_notifications(frame, &app.warnings, areas[1]);
+    }
+    // The pinned packages get a line above the breadcrumb once there are any
+    let navigation_height = if app.pinned.is_empty() { 1 } else { 2 };
+    let chunks = if show_search {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Length(3), // Search bar
+                Constraint::Length(navigation_height), // Pins and breadcrumb
+                Constraint::Min(0),    // Tree
+                Constraint::Length(3), // Footer
+            ])
+            .split(content_area)
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Length(navigation_height), // Pins and breadcrumb
+                Constraint::Min(0),    // Tree
                 Constraint::Length(3), // Footer
             ])
             .split(content_area)
+    };
+
+    if show_search {
+        render_header(frame, chunks[0]);
+        render_search_bar(frame, app, chunks[1]);
+        render_navigation(frame, app, chunks[2]);
+        render_tree(frame, app, chunks[3]);
+        render_footer(frame, app, chunks[4]);
+    } else {
+        render_header(frame, chunks[0]);
+        render_navigation(frame, app, chunks[1]);
+        render_tree(frame, app, chunks[2]);
+        render_footer(frame, app, chunks[3]);
+    }
+}
+
+/// Warnings listed in the notification area before the rest are counted
+const MAX_NOTIFICATION_LINES: usize = 3;
+
+/// Render the notification area listing the analysis warnings
+fn render_notifications(frame: &mut Frame, warnings: &AnalysisWarnings, area: Rect) {
+    let visible = if warnings.len() > MAX_NOTIFICATION_LINES {
+        MAX_NOTIFICATION_LINES - 1
+    } else {
+        warnings.len()
+    };
+    let mut lines: Vec<Line> = warnings
+        .iter()
+        .take(visible)
+        .map(|warning| Line::from(Span::raw(warning.to_string())))
+        .collect();
+    if warnings.len() > visible {
+        lines.push(Line::from(Span::styled(
+            format!("... and {} more (see the export's Warnings section)", warnings.len() - visible),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let title = format!(
+        "⚠ {} {}, results may be incomplete (w to dismiss)",
+        warnings.len(),
+        if warnings.len() == 1 { "warning" } else { "warnings" }
+    );
+    let notifications = Paragraph::new(lines)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(title),
+        );
+    frame.render_widget(notifications, area);
+}
+
+/// Render the header
+fn render_header(frame: &mut Frame, area: Rect) {
+    let header = Paragraph::new("CodeScope - Dependency Analyzer")
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, area);
+}
+
+/// Render the search bar
+fn render_search_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let (border_color, title) = if app.search_active {
+        (Color::Yellow, "Search (Enter to confirm, Esc to cancel)")
+    } else {
+        (Color::Gray, "Filter (/ to edit, Esc to clear)")
+    };
+
+    let search_display = format!("/{}", app.search_query);
+    let cursor = if app.search_active && app.cursor_visible() { "_" } else { " " };
+
+    let result_count = if !app.search_query.is_empty() {
+        format!(" ({} matches)", app.filtered.len())
+    } else {
+        String::new()
+    };
+
+    let content = Line::from(vec![
+        Span::styled(&search_display, Style::default().fg(Color::White)),
+        Span::styled(cursor, Style::default().fg(Color::Yellow)),
+        Span::styled(&result_count, Style::default().fg(Color::DarkGray)),
+    ]);
+
+    let search_bar = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color)),
+        );
+    frame.render_widget(search_bar, area);
+}
+
+/// Separator between the segments of the breadcrumb
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
+/// Marker in place of the segments that do not fit
+const BREADCRUMB_ELLIPSIS: &str = "…";
+
+/// Fit the names along a path into `width` columns, leaving out the
+/// segments nearest the root first; the last segment is always kept
+///
+/// Returns whether segments were left out, and the starting column of
+/// each kept segment with its position in `path`.
+fn layout_breadcrumb(path: &[&str], width: usize) -> (bool, Vec<(usize, usize)>) {
+    let separator = BREADCRUMB_SEPARATOR.chars().count();
+    let prefix = BREADCRUMB_ELLIPSIS.chars().count() + separator;
+    let mut first = 0;
+    let fits = |first: usize| {
+        let names: usize = path[first..].iter().map(|name| name.chars().count()).sum();
+        let separators = separator * (path.len() - first).saturating_sub(1);
+        names + separators + if first > 0 { prefix } else { 0 } <= width
+    };
+    while first + 1 < path.len() && !fits(first) {
+        first += 1;
+    }
+
+    let mut column = if first > 0 { prefix } else { 0 };
+    let mut segments = Vec::new();
+    for (i, name) in path.iter().enumerate().skip(first) {
+        segments.push((column, i));
+        column += name.chars().count() + separator;
+    }
+    (first > 0, segments)
+}
+
+/// Render the pinned packages, if any, and the breadcrumb below them
+fn render_navigation(frame: &mut Frame, app: &mut App, area: Rect) {
+    app.click_targets.clear();
+    if app.pinned.is_empty() {
+        render_breadcrumb(frame, app, area);
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(area);
+    render_pins(frame, app, rows[0]);
+    render_breadcrumb(frame, app, rows[1]);
+}
+
+/// Render the strip of pinned packages, numbered for their `1`-`9` keys
+///
+/// Each package can be clicked to jump to it.
+fn render_pins(frame: &mut Frame, app: &mut App, area: Rect) {
+    let selected = app.selected_node().map(|node| node.name.clone());
+    let mut spans = vec![Span::styled("★ ", Style::default().fg(Color::Yellow))];
+    let mut column = 2;
+    for (n, name) in app.pinned.iter().enumerate() {
+        if n > 0 {
+            spans.push(Span::raw("  "));
+            column += 2;
+        }
+        if n < 9 {
+            let key = format!("{} ", n + 1);
+            column += key.len();
+            spans.push(Span::styled(key, Style::default().fg(Color::Yellow)));
+        }
+        let style = if selected.as_ref() == Some(name) {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        spans.push(Span::styled(name.clone(), style));
+
+        let x = area.x.saturating_add(column as u16);
+        let width = (name.chars().count() as u16).min(area.right().saturating_sub(x));
+        if width > 0 {
+            app.click_targets.push((Rect::new(x, area.y, width, 1), ClickTarget::Package(name.clone())));
+        }
+        column += name.chars().count();
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Render the breadcrumb line with the path from the root to the selection
+///
+/// Each ancestor can be clicked to jump up to it.
+fn render_breadcrumb(frame: &mut Frame, app: &mut App, area: Rect) {
+    let path: Vec<(usize, String)> = app
+        .breadcrumb()
+        .into_iter()
+        .map(|(tree_index, node)| (tree_index, node.name.clone()))
+        .collect();
+    let names: Vec<&str> = path.iter().map(|(_, name)| name.as_str()).collect();
+    let (truncated, segments) = layout_breadcrumb(&names, area.width as usize);
+
+    let mut spans = Vec::new();
+    if truncated {
+        spans.push(Span::styled(BREADCRUMB_ELLIPSIS, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(BREADCRUMB_SEPARATOR, Style::default().fg(Color::DarkGray)));
+    }
+    for (n, &(column, i)) in segments.iter().enumerate() {
+        if n > 0 {
+            spans.push(Span::styled(BREADCRUMB_SEPARATOR, Style::default().fg(Color::DarkGray)));
+        }
+        let (tree_index, name) = &path[i];
+        if i + 1 == path.len() {
+            spans.push(Span::styled(name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+        } else {
+            spans.push(Span::styled(name.clone(), Style::default().fg(Color::Cyan)));
+            let x = area.x.saturating_add(column as u16);
+            let width = (name.chars().count() as u16).min(area.right().saturating_sub(x));
+            app.click_targets.push((Rect::new(x, area.y, width, 1), ClickTarget::TreeIndex(*tree_index)));
+        }
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Most ancestor rows pinned above the tree while scrolling
+const MAX_STICKY_ROWS: usize = 3;
+
+/// Get the ancestors of the node at `first_row` that are scrolled out of
+/// view, outermost first and at most `max` of them (the innermost ones)
+///
+/// Ancestors only exist in the tree order: not while the view is filtered
+/// or sorted by size.
+fn sticky_ancestors(app: &App, first_row: usize, max: usize) -> Vec<usize> {
+    if !app.search_query.is_empty() || app.sort_mode != SortMode::Alphabetical {
+        return Vec::new();
+    }
+    let Some(node) = app.flattened.get(first_row) else {
+        return Vec::new();
+    };
+    // In the tree order a node's position is its tree index
+    let mut ancestors: Vec<usize> = app
+        .tree
+        .path_to_index(node.tree_index)
+        .into_iter()
+        .map(|(tree_index, _)| tree_index)
+        .filter(|&tree_index| tree_index < first_row)
+        .collect();
+    let excess = ancestors.len().saturating_sub(max);
+    ancestors.drain(..excess);
+    ancestors
+}
+
+/// Build the line of one node of the tree
+///
+/// # Arguments
+///
+/// * `index` - Position of the node in the shown list
+/// * `search_query` - Query whose matches are highlighted; the tree
+///   prefix is left out while filtering
+/// * `total_bundle_size` - Bundle size the node's share is computed from
+fn tree_row(app: &App, index: usize, node: &FlattenedNode, search_query: &str, total_bundle_size: u64) -> Line<'static> {
+    let has_search = !search_query.is_empty();
+
+    // Only show tree prefix for non-filtered views
+    let prefix = if has_search {
+        String::new()
+    } else {
+        app.get_tree_prefix(index)
+    };
+    let indicator = node.expansion_indicator();
+    let base_dep_color = get_dep_type_color(node.dep_type, node.is_in_cycle, node.has_conflict);
+    // Apply depth-based color gradient (brighter = closer to root)
+    let dep_color = apply_depth_color(base_dep_color, node.display_depth());
+    let type_indicator = get_dep_type_indicator(node.dep_type);
+    let cycle_indicator = get_cycle_indicator(node.is_in_cycle);
+    let conflict_indicator = get_conflict_indicator(node.has_conflict);
+    let prerelease_indicator = get_prerelease_indicator(node.is_prerelease);
+    let depth_indicator = get_depth_indicator(node.display_depth());
+
+    // Build the name with highlighting if there's a search query
+    let name_spans = if has_search {
+        highlight_matches(&node.name, search_query, dep_color)
+    } else {
+        vec![Span::styled(node.name.clone(), Style::default().fg(dep_color))]
+    };
+
+    // Depth indicator color - blue gradient based on depth
+    let depth_color = apply_depth_color(Color::Rgb(100, 149, 237), node.display_depth()); // Cornflower blue
+
+    let mut content_spans = vec![
+        Span::styled(prefix, Style::default().fg(Color::DarkGray)),
+        Span::styled(indicator, Style::default().fg(Color::Yellow)),
+        Span::styled(depth_indicator, Style::default().fg(depth_color)),
+        Span::styled(cycle_indicator, Style::default().fg(Color::Red)),
+        Span::styled(conflict_indicator, Style::default().fg(Color::Rgb(255, 165, 0))),
+        Span::styled(prerelease_indicator, Style::default().fg(Color::Magenta)),
+        Span::styled(type_indicator, Style::default().fg(dep_color)),
+    ];
+    content_spans.extend(name_spans);
+    content_spans.push(Span::styled(
+        format!(" @{}", node.version),
+        Style::default().fg(Color::DarkGray),
+    ));
+    if app.notes.contains_key(&node.name) {
+        content_spans.push(Span::styled(" ✎", Style::default().fg(Color::Yellow)));
+    }
+
+    // Badge the size change of a recent re-analysis
+    if let Some(change) = app.size_changes.recent(&node.name, Instant::now()) {
+        let color = if change.delta() > 0 { Color::Red } else { Color::Green };
+        content_spans.push(Span::styled(
+            format!(" {}", change.badge()),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Add bundle size column if available
+    if let Some(size) = node.bundle_size {
+        let size_color = get_size_color(size);
+        if app.show_size_bars && total_bundle_size > 0 {
+            content_spans.push(Span::styled(
+                format!("  {}", size_bar(size, total_bundle_size, SIZE_BAR_WIDTH)),
+                Style::default().fg(size_color),
+            ));
+        }
+        let size_str = format_size_with_percentage(size, total_bundle_size);
+        content_spans.push(Span::styled(
+            format!("  [{}]", size_str),
+            Style::default().fg(size_color),
+        ));
+    }
+
+    Line::from(content_spans)
+}
+
+/// Render the dependency tree with virtual scrolling
+///
+/// Only renders visible nodes for performance with large trees (1000+ nodes).
+/// Updates the scroll state viewport height based on available area.
+///
+/// Deep inside an expanded subtree, the ancestors of the first visible row
+/// stay pinned above the rows (like sticky scroll in editors), so the
+/// context of the rows is never scrolled away.
+pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
+    // Clone what we need to avoid borrowing issues
+    let has_search = !app.search_query.is_empty();
+    let search_query = app.search_query.clone();
+
+    let total_nodes = app.current_list_len();
+
+    // Calculate total bundle size for percentage display
+    let total_bundle_size = if has_search {
+        calculate_total_bundle_size(&app.filtered)
+    } else {
+        calculate_total_bundle_size(&app.flattened)
+    };
+
+    // Calculate viewport height (area height minus borders)
+    // Border takes 2 rows (top + bottom)
+    let viewport_height = (area.height as usize).saturating_sub(2);
+    let max_sticky = MAX_STICKY_ROWS.min(viewport_height / 2);
+
+    // Pinned rows take from the scrolled rows, which can change which rows
+    // are pinned; settle on a window whose pinned rows fit
+    let mut sticky: Vec<usize> = Vec::new();
+    let (mut start_idx, mut end_idx) = (0, 0);
+    for _ in 0..=max_sticky {
+        app.scroll_state.set_viewport_height(viewport_height - sticky.len());
+        app.scroll_state.clamp(total_nodes);
+        // Ensure selection is visible and get visible range
+        app.scroll_state.ensure_visible(app.selected_index, total_nodes);
+        (start_idx, end_idx) = app.scroll_state.visible_range(app.selected_index, total_nodes);
+        let pinned = sticky_ancestors(app, start_idx, max_sticky);
+        if pinned.len() <= sticky.len() {
+            sticky = pinned;
+            break;
+        }
+        sticky = pinned;
+    }
+
+    // Only render visible nodes (virtual scrolling optimization)
+    let display_nodes: &[FlattenedNode] = if has_search {
+        &app.filtered
+    } else {
+        &app.flattened
+    };
+    let items: Vec<ListItem> = display_nodes[start_idx..end_idx]
+        .iter()
+        .enumerate()
+        .map(|(visible_idx, node)| {
+            // Calculate actual index in the full list
+            let actual_index = start_idx + visible_idx;
+            ListItem::new(tree_row(app, actual_index, node, &search_query, total_bundle_size))
+        })
+        .collect();
+    let sticky_lines: Vec<Line> = sticky
+        .iter()
+        .enumerate()
+        .map(|(i, &index)| {
+            let mut line = tree_row(app, index, &app.flattened[index], "", total_bundle_size);
+            // Indent like the rows below their highlight symbol, and
+            // underline the last pinned row to set them apart
+            line.spans.insert(0, Span::raw("  "));
+            let mut style = Style::default().add_modifier(Modifier::BOLD);
+            if i + 1 == sticky.len() {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            line.style(style)
+        })
+        .collect();
+
+    // Adjust list_state selection to be relative to visible window
+    let relative_selection = app.selected_index.saturating_sub(start_idx);
+    app.list_state.select(Some(relative_selection));
+
+    // Build title with scroll position indicator for large trees
+    let title = if has_search {
+        format!("Dependencies (filtered: {} matches)", total_nodes)
+    } else if total_nodes > viewport_height {
+        // Show scroll position for large trees
+        format!(
+            "Dependencies ({}-{} of {})",
+            start_idx + 1,
+            end_idx,
+            total_nodes
+        )
+    } else {
+        format!("Dependencies ({})", total_nodes)
+    };
+
+    let tree_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Gray));
+    let inner = tree_block.inner(area);
+    frame.render_widget(tree_block, area);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(sticky_lines.len() as u16), Constraint::Min(0)])
+        .split(inner);
+    frame.render_widget(Paragraph::new(sticky_lines), parts[0]);
+
+    let tree_list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ");
+
+    frame.render_stateful_widget(tree_list, parts[1], &mut app.list_state);
+}
+
+/// Highlight matching characters in a string based on fuzzy search
+pub(super) fn highlight_matches(text: &str, query: &str, base_color: Color) -> Vec<Span<'static>> {
+    if query.is_empty() {
+        return vec![Span::styled(text.to_string(), Style::default().fg(base_color))];
+    }
+
+    let query_lower = query.to_lowercase();
+    let mut result = Vec::new();
+    let mut current_segment = String::new();
+    let mut current_is_match = false;
+    let mut query_chars = query_lower.chars().peekable();
+
+    for c in text.chars() {
+        let c_lower = c.to_lowercase().next().unwrap_or(c);
+        let is_match = query_chars.peek().is_some_and(|&q| c_lower == q);
+
+        if is_match {
+            query_chars.next();
+        }
+
+        if is_match != current_is_match && !current_segment.is_empty() {
+            // Push the current segment
+            let style = if current_is_match {
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(base_color)
+            };
+            result.push(Span::styled(current_segment.clone(), style));
+            current_segment.clear();
+        }
+
+        current_segment.push(c);
+        current_is_match = is_match;
+    }
+
+    // Push the final segment
+    if !current_segment.is_empty() {
+        let style = if current_is_match {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(base_color)
+        };
+        result.push(Span::styled(current_segment, style));
+    }
+
+    result
+}
+
+/// Marker and color of a savings category in the savings panel
+fn savings_marker(category: SavingsCategory) -> (&'static str, Color) {
+    match category {
+        SavingsCategory::Unused => ("[U]", Color::Red),
+        SavingsCategory::Underutilized => ("[<]", Color::Yellow),
+        SavingsCategory::TreeShaking => ("[T]", Color::Blue),
+        SavingsCategory::HasAlternative => ("[A]", Color::Magenta),
+        SavingsCategory::RemovableTypes => ("[D]", Color::Cyan),
+        SavingsCategory::UnneededPolyfills => ("[P]", Color::Green),
+    }
+}
+
+/// Columns of the savings panel, largest savings first
+fn savings_table() -> SortableTable<PackageSavings> {
+    SortableTable::new(vec![
+        Column::new(
+            "Kind",
+            6,
+            |a: &PackageSavings, b| savings_marker(a.category).0.cmp(savings_marker(b.category).0),
+            |saving| {
+                let (marker, color) = savings_marker(saving.category);
+                Span::styled(marker, Style::default().fg(color))
+            },
+        ),
+        Column::new(
+            "Package",
+            12,
+            |a: &PackageSavings, b| a.package_name.cmp(&b.package_name),
+            |saving| Span::styled(saving.package_name.clone(), Style::default().fg(Color::White)),
+        )
+        .flexible(),
+        Column::new(
+            "Size",
+            10,
+            |a: &PackageSavings, b| a.current_size.cmp(&b.current_size),
+            |saving| Span::raw(saving.format_current_size()),
+        )
+        .descending(),
+        Column::new(
+            "Savings",
+            11,
+            |a: &PackageSavings, b| a.potential_savings.cmp(&b.potential_savings),
+            |saving| {
+                Span::styled(
+                    format!("-{}", saving.format_potential_savings()),
+                    Style::default().fg(Color::Green),
+                )
+            },
+        )
+        .descending(),
+    ])
+    .sorted_by(3)
+}
+
+/// Columns of the orphaned packages panel, largest first
+fn orphan_table() -> SortableTable<OrphanedPackage> {
+    SortableTable::new(vec![
+        Column::new(
+            "Path",
+            16,
+            |a: &OrphanedPackage, b| a.path.cmp(&b.path),
+            |pkg| Span::styled(pkg.path.clone(), Style::default().fg(Color::White)),
+        )
+        .flexible(),
+        Column::new(
+            "Version",
+            10,
+            |a: &OrphanedPackage, b| a.version.cmp(&b.version),
+            |pkg| {
+                Span::styled(
+                    pkg.version.clone().unwrap_or_default(),
+                    Style::default().fg(Color::DarkGray),
+                )
+            },
+        ),
+        Column::new(
+            "Size",
+            10,
+            |a: &OrphanedPackage, b| a.size.bytes.cmp(&b.size.bytes),
+            |pkg| {
+                Span::styled(
+                    format_size(pkg.size.bytes),
+                    Style::default().fg(get_size_color(pkg.size.bytes)),
+                )
+            },
+        )
+        .descending(),
+    ])
+    .sorted_by(2)
+}
+
+/// Columns of the npm scopes panel, largest first
+fn scope_table() -> SortableTable<ScopeSummary> {
+    SortableTable::new(vec![
+        Column::new(
+            "Scope",
+            12,
+            |a: &ScopeSummary, b| a.scope.cmp(&b.scope),
+            |scope| {
+                Span::styled(
+                    scope.scope.clone(),
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                )
+            },
+        )
+        .flexible(),
+        Column::new(
+            "Pkgs",
+            6,
+            |a: &ScopeSummary, b| a.count().cmp(&b.count()),
+            |scope| Span::raw(scope.count().to_string()),
+        )
+        .descending(),
+        Column::new(
+            "Size",
+            10,
+            |a: &ScopeSummary, b| a.bundle_size.cmp(&b.bundle_size),
+            |scope| {
+                if scope.has_sizes() {
+                    Span::styled(
+                        format_size(scope.bundle_size),
+                        Style::default().fg(get_size_color(scope.bundle_size)),
+                    )
+                } else {
+                    Span::styled("unknown", Style::default().fg(Color::DarkGray))
+                }
+            },
+        )
+        .descending(),
+    ])
+    .sorted_by(2)
+}
+
+/// Render the savings panel
+fn render_savings_panel(frame: &mut Frame, report: &SavingsReport, table: &mut SortableTable<PackageSavings>, area: Rect) {
+    let summary = &report.summary;
+
+    // Create the panel layout
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(6), // Summary section
+            Constraint::Min(0),    // Package list
+        ])
+        .split(area);
+
+    // Render summary section
+    let savings_pct = summary.savings_percentage();
+    let savings_color = if savings_pct > 30.0 {
+        Color::Red
+    } else if savings_pct > 15.0 {
+        Color::Yellow
     } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Tree
-                Constraint::Length(3), // Footer
-            ])
-            .split(content_area)
+        Color::Green
     };
 
-    if show_search {
-        render_header(frame, chunks[0]);
-        render_search_bar(frame, app, chunks[1]);
-        render_tree(frame, app, chunks[2]);
-        render_footer(frame, app, chunks[3]);
+    let summary_lines = vec![
+        Line::from(vec![
+            Span::raw("Total: "),
+            Span::styled(
+                summary.format_total_bundle_size(),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Savings: "),
+            Span::styled(
+                format!("{} ({:.1}%)", summary.format_total_savings(), savings_pct),
+                Style::default().fg(savings_color).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{}", summary.unused_count),
+                Style::default().fg(Color::Red),
+            ),
+            Span::raw(" unused  "),
+            Span::styled(
+                format!("{}", summary.underutilized_count),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(" underutil  "),
+            Span::styled(
+                format!("{}", summary.tree_shaking_count),
+                Style::default().fg(Color::Blue),
+            ),
+            Span::raw(" tree-shake"),
+        ]),
+    ];
+
+    let summary_widget = Paragraph::new(summary_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Potential Savings ")
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::White));
+    frame.render_widget(summary_widget, chunks[0]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Top Savings ")
+        .title_style(Style::default().fg(Color::White))
+        .style(Style::default().fg(Color::Gray));
+    table.render(frame, chunks[1], &report.package_savings, block);
+}
+
+/// Render the orphaned packages panel
+fn render_orphan_panel(frame: &mut Frame, report: &OrphanReport, table: &mut SortableTable<OrphanedPackage>, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Summary section
+            Constraint::Min(0),    // Package list
+            Constraint::Length(5), // Cleanup command
+        ])
+        .split(area);
+
+    let count_color = if report.is_empty() { Color::Green } else { Color::Yellow };
+    let summary_lines = vec![
+        Line::from(vec![
+            Span::raw("Orphaned: "),
+            Span::styled(
+                format!("{}", report.packages.len()),
+                Style::default().fg(count_color).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Reclaimable: "),
+            Span::styled(
+                format_size(report.total_bytes()),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+    ];
+
+    let summary_widget = Paragraph::new(summary_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Orphaned Packages ")
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::White));
+    frame.render_widget(summary_widget, chunks[0]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Not Required by the Lockfile ")
+        .title_style(Style::default().fg(Color::White));
+    if report.is_empty() {
+        let message = Paragraph::new(Span::styled(
+            "node_modules matches the lockfile",
+            Style::default().fg(Color::Green),
+        ))
+        .block(block);
+        frame.render_widget(message, chunks[1]);
     } else {
-        render_header(frame, chunks[0]);
-        render_tree(frame, app, chunks[1]);
-        render_footer(frame, app, chunks[2]);
+        table.render(frame, chunks[1], &report.packages, block.style(Style::default().fg(Color::Gray)));
     }
+
+    let command = report
+        .cleanup_command()
+        .unwrap_or_else(|| "Nothing to clean up".to_string());
+    let command_widget = Paragraph::new(command)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Cleanup ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(command_widget, chunks[2]);
 }
 
-/// Render the header
-fn render_header(frame: &mut Frame, area: Rect) {
-    let header = Paragraph::new("CodeScope - Dependency Analyzer")
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+/// Render the package groups with their totals and budgets
+fn render_group_panel(frame: &mut Frame, groups: &[GroupSummary], area: Rect) {
+    let over_budget = groups.iter().filter(|g| g.over_budget()).count();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Summary section
+            Constraint::Min(0),    // Group list
+        ])
+        .split(area);
+
+    let status_color = if over_budget == 0 { Color::Green } else { Color::Red };
+    let summary_lines = vec![
+        Line::from(vec![
+            Span::raw("Groups: "),
+            Span::styled(
+                format!("{}", groups.len()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Over budget: "),
+            Span::styled(
+                format!("{}", over_budget),
+                Style::default().fg(status_color).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
+
+    let summary_widget = Paragraph::new(summary_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Package Groups ")
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         )
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(header, area);
+        .style(Style::default().fg(Color::White));
+    frame.render_widget(summary_widget, chunks[0]);
+
+    let items: Vec<ListItem> = groups
+        .iter()
+        .map(|group| {
+            let (marker, color) = if group.over_budget() {
+                ("✗ ", Color::Red)
+            } else if group.has_budget() {
+                ("✓ ", Color::Green)
+            } else {
+                ("  ", Color::White)
+            };
+            let size = if group.has_sizes() {
+                format_size(group.bundle_size)
+            } else {
+                "size unknown".to_string()
+            };
+            let mut budget = Vec::new();
+            if let Some(max) = group.max_size_kb {
+                budget.push(format!("{} KB", max));
+            }
+            if let Some(max) = group.max_count {
+                budget.push(format!("{} pkgs", max));
+            }
+
+            let mut lines = vec![Line::from(vec![
+                Span::styled(marker, Style::default().fg(color)),
+                Span::styled(&group.name, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            ])];
+            let mut detail = vec![Span::styled(
+                format!("  {} packages, {}", group.count(), size),
+                Style::default().fg(Color::Gray),
+            )];
+            if !budget.is_empty() {
+                detail.push(Span::styled(
+                    format!(" / budget {}", budget.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(detail));
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let groups_widget = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" By Group ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(groups_widget, chunks[1]);
 }
 
-/// Render the search bar
-fn render_search_bar(frame: &mut Frame, app: &App, area: Rect) {
-    let (border_color, title) = if app.search_active {
-        (Color::Yellow, "Search (Enter to confirm, Esc to cancel)")
+/// Render the per-scope aggregates
+fn render_scope_panel(frame: &mut Frame, scopes: &[ScopeSummary], table: &mut SortableTable<ScopeSummary>, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Summary section
+            Constraint::Min(0),    // Scope list
+        ])
+        .split(area);
+
+    let scoped_packages: usize = scopes.iter().map(|s| s.count()).sum();
+    let summary_lines = vec![
+        Line::from(vec![
+            Span::raw("Scopes: "),
+            Span::styled(
+                format!("{}", scopes.len()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Scoped packages: "),
+            Span::styled(format!("{}", scoped_packages), Style::default().fg(Color::Cyan)),
+        ]),
+    ];
+
+    let summary_widget = Paragraph::new(summary_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" npm Scopes ")
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::White));
+    frame.render_widget(summary_widget, chunks[0]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" By Scope ")
+        .title_style(Style::default().fg(Color::White))
+        .style(Style::default().fg(Color::Gray));
+    table.render(frame, chunks[1], scopes, block);
+}
+
+/// Render the removal preview for the selected dependency
+fn render_removal_panel(frame: &mut Frame, selected: &str, impact: Option<&RemovalImpact>, area: Rect) {
+    let Some(impact) = impact else {
+        let message = Paragraph::new(format!(
+            "{} is not a direct dependency.\n\nSelect a package under dependencies or devDependencies to preview its removal.",
+            selected
+        ))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Remove Dependency ")
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::Gray));
+        frame.render_widget(message, area);
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(5), // Summary section
+            Constraint::Min(0),    // Removed packages
+            Constraint::Percentage(30), // Retained packages
+            Constraint::Length(4), // Uninstall command
+        ])
+        .split(area);
+
+    let bundle = if impact.is_bundle_size_unknown() {
+        "unknown".to_string()
     } else {
-        (Color::Gray, "Filter (/ to edit, Esc to clear)")
+        format_size(impact.reclaimed_bundle_bytes())
+    };
+    let summary_lines = vec![
+        Line::from(vec![
+            Span::raw("Packages removed: "),
+            Span::styled(
+                format!("{}", impact.removed.len()),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Bundle reclaimed: "),
+            Span::styled(bundle, Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(vec![
+            Span::raw("Disk reclaimed: "),
+            Span::styled(
+                format_size(impact.reclaimed_disk_bytes()),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+    ];
+
+    let summary_widget = Paragraph::new(summary_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Remove {} ", impact.package))
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::White));
+    frame.render_widget(summary_widget, chunks[0]);
+
+    let removed_items: Vec<ListItem> = if impact.removed.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "Nothing is uninstalled; other packages still need it",
+            Style::default().fg(Color::DarkGray),
+        )))]
+    } else {
+        impact
+            .removed
+            .iter()
+            .map(|pkg| {
+                let mut spans = vec![
+                    Span::styled(&pkg.name, Style::default().fg(Color::White)),
+                    Span::styled(format!(" @ {}", pkg.version), Style::default().fg(Color::DarkGray)),
+                ];
+                if let Some(size) = pkg.bundle_size.or(pkg.disk_usage.map(|u| u.bytes)) {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(format_size(size), Style::default().fg(get_size_color(size))));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect()
     };
 
-    let search_display = format!("/{}", app.search_query);
-    let cursor = if app.search_active { "_" } else { "" };
+    let removed_widget = List::new(removed_items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Would Be Uninstalled ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(removed_widget, chunks[1]);
 
-    let result_count = if !app.search_query.is_empty() {
-        format!(" ({} matches)", app.filtered.len())
-    } else {
-        String::new()
-    };
+    let retained_items: Vec<ListItem> = impact
+        .retained
+        .iter()
+        .map(|pkg| {
+            ListItem::new(Line::from(vec![
+                Span::styled(&pkg.name, Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(" ← {}", pkg.needed_by.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
 
-    let content = Line::from(vec![
-        Span::styled(&search_display, Style::default().fg(Color::White)),
-        Span::styled(cursor, Style::default().fg(Color::Yellow).add_modifier(Modifier::SLOW_BLINK)),
-        Span::styled(&result_count, Style::default().fg(Color::DarkGray)),
-    ]);
+    let retained_widget = List::new(retained_items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Still Needed By ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(retained_widget, chunks[2]);
 
-    let search_bar = Paragraph::new(content)
+    let command_widget = Paragraph::new(format!("{}\nPress y to run it", impact.uninstall_command()))
+        .wrap(Wrap { trim: false })
         .block(
             Block::default()
-                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(border_color)),
-        );
-    frame.render_widget(search_bar, area);
+                .title(" Uninstall ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(command_widget, chunks[3]);
 }
 
-/// Render the dependency tree with virtual scrolling
-///
-/// Only renders visible nodes for performance with large trees (1000+ nodes).
-/// Updates the scroll state viewport height based on available area.
-pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
-    // Clone what we need to avoid borrowing issues
-    let has_search = !app.search_query.is_empty();
-    let search_query = app.search_query.clone();
-
-    // Get the nodes to display
-    let display_nodes: &[FlattenedNode] = if has_search {
-        &app.filtered
-    } else {
-        &app.flattened
+/// Render the commit that introduced the selected dependency
+fn render_blame_panel(frame: &mut Frame, selected: &str, origin: Option<&Commit>, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Blame {} ", selected))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let Some(origin) = origin else {
+        let message = Paragraph::new(format!(
+            "No commit added {} to package.json.\n\nTransitive dependencies and uncommitted changes have no history.",
+            selected
+        ))
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+        frame.render_widget(message, area);
+        return;
     };
 
-    let total_nodes = display_nodes.len();
+    let field = |label: &'static str, value: &str, color: Color| {
+        Line::from(vec![
+            Span::raw(label),
+            Span::styled(value.to_string(), Style::default().fg(color)),
+        ])
+    };
+    let lines = vec![
+        field("Added in: ", origin.short_commit(), Color::Yellow),
+        field("Author:   ", &origin.author, Color::White),
+        field("Date:     ", &origin.date, Color::Cyan),
+        Line::from(""),
+        Line::from(Span::styled(&origin.summary, Style::default().fg(Color::White))),
+    ];
 
-    // Calculate total bundle size for percentage display
-    let total_bundle_size = calculate_total_bundle_size(display_nodes);
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(widget, area);
+}
 
-    // Calculate viewport height (area height minus borders)
-    // Border takes 2 rows (top + bottom)
-    let viewport_height = (area.height as usize).saturating_sub(2);
-    app.scroll_state.set_viewport_height(viewport_height);
+/// Render the note on the selected package, or the note being written
+/// with whether its blinking cursor is shown
+fn render_notes_panel(frame: &mut Frame, selected: &str, note: Option<&str>, input: Option<(&str, bool)>, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Note on {} ", selected))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let lines = match (input, note) {
+        (Some((input, cursor_visible)), _) => vec![
+            Line::from(vec![
+                Span::styled(input.to_string(), Style::default().fg(Color::White)),
+                Span::styled(if cursor_visible { "_" } else { " " }, Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Enter to save, Esc to cancel. An empty note is removed.",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ],
+        (None, Some(note)) => vec![
+            Line::from(Span::styled(note.to_string(), Style::default().fg(Color::White))),
+            Line::from(""),
+            Line::from(Span::styled("n to edit", Style::default().fg(Color::DarkGray))),
+        ],
+        (None, None) => vec![Line::from(format!("No note on {}. Press n to write one.", selected))],
+    };
 
-    // Ensure selection is visible and get visible range
-    app.scroll_state.ensure_visible(app.selected_index, total_nodes);
-    let (start_idx, end_idx) = app.scroll_state.visible_range(app.selected_index, total_nodes);
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(widget, area);
+}
 
-    // Only render visible nodes (virtual scrolling optimization)
-    let visible_nodes = &display_nodes[start_idx..end_idx];
+/// Render the size changes of the last watch-mode runs, newest first
+fn render_changes_panel(frame: &mut Frame, changes: &ChangeLog, now: Instant, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Size Changes ")
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
 
-    let items: Vec<ListItem> = visible_nodes
+    let size = |size: Option<u64>| size.map_or_else(|| "-".to_string(), format_size);
+    let lines: Vec<Line> = changes
         .iter()
-        .enumerate()
-        .map(|(visible_idx, node)| {
-            // Calculate actual index in the full list
-            let actual_index = start_idx + visible_idx;
+        .map(|change| {
+            let color = if change.delta() > 0 { Color::Red } else { Color::Green };
+            Line::from(vec![
+                Span::styled(
+                    format!("run {} · {:>7}  ", change.run, format_age(change.at, now)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(change.package.clone(), Style::default().fg(Color::White)),
+                Span::raw(format!("  {} → {}  ", size(change.before), size(change.after))),
+                Span::styled(change.badge(), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            ])
+        })
+        .collect();
 
-            // Only show tree prefix for non-filtered views
-            let prefix = if has_search {
-                String::new()
-            } else {
-                app.get_tree_prefix(actual_index)
-            };
-            let indicator = node.expansion_indicator();
-            let base_dep_color = get_dep_type_color(node.dep_type, node.is_in_cycle, node.has_conflict);
-            // Apply depth-based color gradient (brighter = closer to root)
-            let dep_color = apply_depth_color(base_dep_color, node.depth);
-            let type_indicator = get_dep_type_indicator(node.dep_type);
-            let cycle_indicator = get_cycle_indicator(node.is_in_cycle);
-            let conflict_indicator = get_conflict_indicator(node.has_conflict);
-            let depth_indicator = get_depth_indicator(node.depth);
-
-            // Build the name with highlighting if there's a search query
-            let name_spans = if has_search {
-                highlight_matches(&node.name, &search_query, dep_color)
-            } else {
-                vec![Span::styled(node.name.clone(), Style::default().fg(dep_color))]
-            };
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(widget, area);
+}
 
-            // Depth indicator color - blue gradient based on depth
-            let depth_color = apply_depth_color(Color::Rgb(100, 149, 237), node.depth); // Cornflower blue
+/// Render the files importing the selected package above a preview of the
+/// selected file's import lines
+fn render_imports_panel(frame: &mut Frame, app: &mut App, area: Rect) {
+    let selected = app.selected_node().map(|node| node.name.clone()).unwrap_or_default();
+    let preview = app.selected_import_preview().cloned();
+    let sites = app.selected_import_sites();
+    let selected_index = app.selected_import_index();
 
-            let mut content_spans = vec![
-                Span::styled(prefix, Style::default().fg(Color::DarkGray)),
-                Span::styled(indicator, Style::default().fg(Color::Yellow)),
-                Span::styled(depth_indicator, Style::default().fg(depth_color)),
-                Span::styled(cycle_indicator, Style::default().fg(Color::Red)),
-                Span::styled(conflict_indicator, Style::default().fg(Color::Rgb(255, 165, 0))),
-                Span::styled(type_indicator, Style::default().fg(dep_color)),
-            ];
-            content_spans.extend(name_spans);
-            content_spans.push(Span::styled(
-                format!(" @{}", node.version),
-                Style::default().fg(Color::DarkGray),
-            ));
+    let list_height = (sites.len() as u16 + 2).clamp(3, area.height / 3);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(list_height), Constraint::Min(0)])
+        .split(area);
 
-            // Add bundle size column if available
-            if let Some(size) = node.bundle_size {
-                let size_color = get_size_color(size);
-                let size_str = format_size_with_percentage(size, total_bundle_size);
-                content_spans.push(Span::styled(
-                    format!("  [{}]", size_str),
-                    Style::default().fg(size_color),
-                ));
-            }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " Imported by {} {} ",
+            sites.len(),
+            if sites.len() == 1 { "file" } else { "files" }
+        ))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    if sites.is_empty() {
+        let message = Paragraph::new(format!("No source file imports {}.", selected))
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .style(Style::default().fg(Color::Gray));
+        frame.render_widget(message, area);
+        return;
+    }
 
-            ListItem::new(Line::from(content_spans))
+    let items: Vec<ListItem> = sites
+        .iter()
+        .map(|site| {
+            let lines: Vec<String> = site.lines.iter().map(|line| line.to_string()).collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(site.file.clone(), Style::default().fg(Color::White)),
+                Span::styled(format!(":{}", lines.join(",")), Style::default().fg(Color::DarkGray)),
+            ]))
         })
         .collect();
-
-    // Adjust list_state selection to be relative to visible window
-    let relative_selection = app.selected_index.saturating_sub(start_idx);
-    app.list_state.select(Some(relative_selection));
-
-    // Build title with scroll position indicator for large trees
-    let title = if has_search {
-        format!("Dependencies (filtered: {} matches)", total_nodes)
-    } else if total_nodes > viewport_height {
-        // Show scroll position for large trees
-        format!(
-            "Dependencies ({}-{} of {})",
-            start_idx + 1,
-            end_idx,
-            total_nodes
-        )
-    } else {
-        format!("Dependencies ({})", total_nodes)
-    };
-
-    let tree_block = Block::default()
-        .title(title)
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default().with_selected(Some(selected_index));
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let file = sites[selected_index].file.clone();
+    let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Gray));
-
-    let tree_list = List::new(items)
-        .block(tree_block)
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("► ");
+        .title(format!(" {} ", file))
+        .title_bottom(Line::from(" Tab next file ").right_aligned())
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    let preview = match preview {
+        Some(Ok(preview)) => preview,
+        Some(Err(e)) => {
+            let message = Paragraph::new(format!("Failed to read {}: {}", file, e))
+                .wrap(Wrap { trim: false })
+                .block(block)
+                .style(Style::default().fg(Color::Red));
+            frame.render_widget(message, chunks[1]);
+            return;
+        }
+        None => SourcePreview::default(),
+    };
 
-    frame.render_stateful_widget(tree_list, area, &mut app.list_state);
+    let number_width = preview
+        .hunks
+        .iter()
+        .flatten()
+        .map(|line| line.number.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut lines = Vec::new();
+    for (n, hunk) in preview.hunks.iter().enumerate() {
+        if n > 0 {
+            lines.push(Line::from(Span::styled(
+                format!("{:>width$} ⋯", "", width = number_width),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for line in hunk {
+            let number_style = if line.is_import {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let mut spans = vec![Span::styled(
+                format!("{:>width$} {} ", line.number, if line.is_import { "▶" } else { "│" }, width = number_width),
+                number_style,
+            )];
+            spans.extend(
+                line.segments
+                    .iter()
+                    .map(|(highlight, text)| Span::styled(text.clone(), highlight_style(*highlight))),
+            );
+            lines.push(Line::from(spans));
+        }
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), chunks[1]);
 }
 
-/// Highlight matching characters in a string based on fuzzy search
-fn highlight_matches(text: &str, query: &str, base_color: Color) -> Vec<Span<'static>> {
-    if query.is_empty() {
-        return vec![Span::styled(text.to_string(), Style::default().fg(base_color))];
+/// Get the style of a syntax class in the source preview
+fn highlight_style(highlight: Highlight) -> Style {
+    match highlight {
+        Highlight::Plain => Style::default().fg(Color::White),
+        Highlight::Keyword => Style::default().fg(Color::Magenta),
+        Highlight::String => Style::default().fg(Color::Green),
+        Highlight::Literal => Style::default().fg(Color::Yellow),
+        Highlight::Type => Style::default().fg(Color::Cyan),
+        Highlight::Comment => Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
     }
+}
 
-    let query_lower = query.to_lowercase();
-    let mut result = Vec::new();
-    let mut current_segment = String::new();
-    let mut current_is_match = false;
-    let mut query_chars = query_lower.chars().peekable();
-
-    for c in text.chars() {
-        let c_lower = c.to_lowercase().next().unwrap_or(c);
-        let is_match = query_chars.peek().is_some_and(|&q| c_lower == q);
-
-        if is_match {
-            query_chars.next();
+/// Render the condensed graph: the cycle of the selected package with its
+/// members and neighbouring components, or every cycle when the selected
+/// package is in none
+fn render_condensed_panel(frame: &mut Frame, condensation: &Condensation, selected: &str, area: Rect) {
+    let cycles: Vec<&Component> = condensation.cycles().collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " Condensed: {} components, {} cycles ",
+            condensation.components.len(),
+            cycles.len()
+        ))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let heading = |text: String| Line::from(Span::styled(text, Style::default().fg(Color::Yellow)));
+    let mut lines = Vec::new();
+    match condensation.component_of(selected).filter(|component| component.is_cycle()) {
+        Some(component) => {
+            lines.push(Line::from(Span::styled(
+                component.label.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(heading(format!("{} packages in this cycle:", component.members.len())));
+            for member in &component.members {
+                let style = if member == selected {
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                lines.push(Line::from(Span::styled(format!("  {}", member), style)));
+            }
+            for (title, neighbours) in [
+                ("Depends on", condensation.dependencies(component)),
+                ("Used by", condensation.dependents(component)),
+            ] {
+                if !neighbours.is_empty() {
+                    lines.push(Line::from(""));
+                    lines.push(heading(format!("{}:", title)));
+                    lines.extend(neighbours.iter().map(|c| Line::from(format!("  {}", c.label))));
+                }
+            }
         }
-
-        if is_match != current_is_match && !current_segment.is_empty() {
-            // Push the current segment
-            let style = if current_is_match {
-                Style::default()
-                    .fg(Color::Magenta)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            } else {
-                Style::default().fg(base_color)
-            };
-            result.push(Span::styled(current_segment.clone(), style));
-            current_segment.clear();
+        None => {
+            lines.push(Line::from(Span::styled(
+                "Select a package in a cycle to list its members.",
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+            for component in cycles {
+                lines.push(Line::from(vec![
+                    Span::styled(component.label.clone(), Style::default().fg(Color::Red)),
+                    Span::raw(format!(" ({} packages)", component.members.len())),
+                ]));
+            }
         }
-
-        current_segment.push(c);
-        current_is_match = is_match;
     }
 
-    // Push the final segment
-    if !current_segment.is_empty() {
-        let style = if current_is_match {
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(widget, area);
+}
+
+/// Render why the copies of the selected package were hoisted or nested
+fn render_hoisting_panel(
+    frame: &mut Frame,
+    selected: &str,
+    explanation: Option<&HoistingExplanation>,
+    duplicated: usize,
+    area: Rect,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Hoisting {} ", selected))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let Some(explanation) = explanation else {
+        let message = Paragraph::new(format!(
+            "{} is installed once.\n\n{} package(s) are installed more than once; select one to see which copy was hoisted and why the others are nested.",
+            selected, duplicated
+        ))
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+        frame.render_widget(message, area);
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} copies installed", explanation.copy_count()),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+    ];
+    for line in explanation.format_lines() {
+        let style = if line.trim_start().starts_with("hoist blocked") {
+            Style::default().fg(Color::Red)
+        } else if line.starts_with("    ") {
+            Style::default().fg(Color::Gray)
         } else {
-            Style::default().fg(base_color)
+            Style::default().fg(Color::White)
         };
-        result.push(Span::styled(current_segment, style));
+        lines.push(Line::from(Span::styled(line.trim_start_matches("  ").to_string(), style)));
     }
 
-    result
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(widget, area);
 }
 
-/// Render the savings panel
-fn render_savings_panel(frame: &mut Frame, report: &SavingsReport, area: Rect) {
-    let summary = &report.summary;
-
-    // Create the panel layout
+/// Render the install footprint panel, in the selected order
+fn render_footprint_panel(frame: &mut Frame, report: &FootprintReport, sort: FootprintSort, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(6), // Summary section
+            Constraint::Length(5), // Summary section
             Constraint::Min(0),    // Package list
         ])
         .split(area);
 
-    // Render summary section
-    let savings_pct = summary.savings_percentage();
-    let savings_color = if savings_pct > 30.0 {
-        Color::Red
-    } else if savings_pct > 15.0 {
-        Color::Yellow
-    } else {
-        Color::Green
-    };
-
+    let running = report.packages_running_code().len();
+    let running_color = if running == 0 { Color::Green } else { Color::Yellow };
     let summary_lines = vec![
         Line::from(vec![
-            Span::raw("Total: "),
+            Span::raw("On disk: "),
             Span::styled(
-                summary.format_total_bundle_size(),
-                Style::default().fg(Color::Cyan),
+                format_size(report.total_bytes()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
             ),
-        ]),
-        Line::from(vec![
-            Span::raw("Savings: "),
             Span::styled(
-                format!("{} ({:.1}%)", summary.format_total_savings(), savings_pct),
-                Style::default().fg(savings_color).add_modifier(Modifier::BOLD),
+                format!(" in {} files", report.total_files()),
+                Style::default().fg(Color::DarkGray),
             ),
         ]),
-        Line::from(""),
         Line::from(vec![
-            Span::styled(
-                format!("{}", summary.unused_count),
-                Style::default().fg(Color::Red),
-            ),
-            Span::raw(" unused  "),
-            Span::styled(
-                format!("{}", summary.underutilized_count),
-                Style::default().fg(Color::Yellow),
-            ),
-            Span::raw(" underutil  "),
-            Span::styled(
-                format!("{}", summary.tree_shaking_count),
-                Style::default().fg(Color::Blue),
-            ),
-            Span::raw(" tree-shake"),
+            Span::raw("Run install code: "),
+            Span::styled(format!("{}", running), Style::default().fg(running_color)),
+        ]),
+        Line::from(vec![
+            Span::raw("Sort (s): "),
+            Span::styled(sort.display_name(), Style::default().fg(Color::Cyan)),
         ]),
     ];
 
@@ -1078,67 +3556,148 @@ fn render_savings_panel(frame: &mut Frame, report: &SavingsReport, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Potential Savings ")
+                .title(" Install Footprint ")
                 .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         )
         .style(Style::default().fg(Color::White));
     frame.render_widget(summary_widget, chunks[0]);
 
-    // Render package list
-    let sorted_savings = report.savings_by_size();
-    let items: Vec<ListItem> = sorted_savings
+    let items: Vec<ListItem> = report
+        .packages
         .iter()
-        .take(20) // Limit to top 20 packages
-        .map(|saving| {
-            let category_color = match saving.category {
-                SavingsCategory::Unused => Color::Red,
-                SavingsCategory::Underutilized => Color::Yellow,
-                SavingsCategory::TreeShaking => Color::Blue,
-                SavingsCategory::HasAlternative => Color::Magenta,
-            };
-
-            let category_indicator = match saving.category {
-                SavingsCategory::Unused => "[U]",
-                SavingsCategory::Underutilized => "[<]",
-                SavingsCategory::TreeShaking => "[T]",
-                SavingsCategory::HasAlternative => "[A]",
-            };
-
-            let line = Line::from(vec![
-                Span::styled(
-                    category_indicator,
-                    Style::default().fg(category_color),
-                ),
-                Span::raw(" "),
-                Span::styled(
-                    &saving.package_name,
-                    Style::default().fg(Color::White),
-                ),
+        .map(|pkg| {
+            let footprint = &pkg.footprint;
+            let mut spans = vec![
+                Span::styled(&pkg.name, Style::default().fg(Color::White)),
                 Span::raw(" "),
-                Span::styled(
-                    format!("-{}", saving.format_potential_savings()),
-                    Style::default().fg(Color::Green),
-                ),
-            ]);
+                Span::styled(format_size(footprint.bytes), Style::default().fg(get_size_color(footprint.bytes))),
+                Span::styled(format!(" {} files", footprint.files), Style::default().fg(Color::DarkGray)),
+            ];
+            if footprint.runs_code() {
+                spans.push(Span::styled(
+                    format!(" [{}]", install_label(footprint)),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let packages_widget = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" node_modules ")
+                .title_style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(packages_widget, chunks[1]);
+}
 
-            ListItem::new(line)
+/// Render the footer with help text and legend
+/// Render the chunk tree of the webpack build and the selected chunk's details
+fn render_chunk_panel(frame: &mut Frame, graph: &ChunkGraph, rows: &[ChunkRow], selected: usize, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(50), // Chunk tree
+            Constraint::Min(0),         // Selected chunk
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let chunk = &graph.chunks[row.index];
+            let kind_color = match chunk.kind() {
+                "entry" => Color::Green,
+                "initial" => Color::Cyan,
+                _ => Color::Magenta,
+            };
+            let mut spans = vec![
+                Span::raw("  ".repeat(row.depth)),
+                Span::styled(chunk.label(), Style::default().fg(Color::White)),
+                Span::raw(" "),
+                Span::styled(format_size(chunk.size), Style::default().fg(get_size_color(chunk.size))),
+                Span::styled(format!(" [{}]", chunk.kind()), Style::default().fg(kind_color)),
+            ];
+            if row.repeated {
+                spans.push(Span::styled(" (shown above)", Style::default().fg(Color::DarkGray)));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let packages_widget = List::new(items)
+    let total: u64 = graph.chunks.iter().map(|c| c.size).sum();
+    let tree_widget = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Chunks ({}, {}) ", graph.chunks.len(), format_size(total)))
+                .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        )
+        .style(Style::default().fg(Color::Gray))
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    frame.render_stateful_widget(tree_widget, chunks[0], &mut state);
+
+    let Some(chunk) = rows.get(selected).and_then(|row| graph.chunks.get(row.index)) else {
+        return;
+    };
+    let labels = |ids: &[String]| {
+        if ids.is_empty() {
+            "none".to_string()
+        } else {
+            ids.iter()
+                .map(|id| graph.get(id).map(|c| c.label()).unwrap_or_else(|| format!("#{}", id)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("Size: "),
+            Span::styled(
+                format_size(chunk.size),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!(" ({})", chunk.kind()), Style::default().fg(Color::DarkGray)),
+        ]),
+        Line::from(vec![Span::raw("Parents: "), Span::styled(labels(&chunk.parents), Style::default().fg(Color::White))]),
+        Line::from(vec![Span::raw("Children: "), Span::styled(labels(&chunk.children), Style::default().fg(Color::White))]),
+        Line::from(vec![Span::raw("Siblings: "), Span::styled(labels(&chunk.siblings), Style::default().fg(Color::White))]),
+        Line::from(""),
+    ];
+    if chunk.packages.is_empty() {
+        lines.push(Line::from(Span::styled("No modules in the stats", Style::default().fg(Color::DarkGray))));
+    }
+    for package in &chunk.packages {
+        lines.push(Line::from(vec![
+            Span::styled(&package.name, Style::default().fg(Color::White)),
+            Span::raw(" "),
+            Span::styled(format_size(package.size), Style::default().fg(get_size_color(package.size))),
+            Span::styled(format!(" {} modules", package.module_count), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let details_widget = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Top Savings ")
+                .title(format!(" {} ", chunk.label()))
                 .title_style(Style::default().fg(Color::White)),
         )
-        .style(Style::default().fg(Color::Gray));
-    frame.render_widget(packages_widget, chunks[1]);
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(details_widget, chunks[1]);
 }
 
-/// Render the footer with help text and legend
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let help_text = if app.search_active {
+    let help_text = if let Some(status) = &app.status {
+        // Result of the last key, such as a URL or why nothing opened
+        Line::from(Span::styled(status.clone(), Style::default().fg(Color::Cyan)))
+    } else if app.search_active {
         // Search mode help
         Line::from(vec![
             Span::styled("Type", Style::default().fg(Color::Yellow)),
@@ -1165,9 +3724,106 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
             spans.push(Span::raw(" Savings  "));
         }
 
+        // Add orphan panel shortcut if a lockfile was analyzed
+        if app.has_orphan_data() {
+            spans.push(Span::styled("o", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Orphans  "));
+        }
+
+        // Add groups panel shortcut if groups are configured
+        if app.has_group_data() {
+            spans.push(Span::styled("t", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Groups  "));
+        }
+
+        // Add scopes panel shortcut if the project has scoped packages
+        if app.has_scope_data() {
+            spans.push(Span::styled("@", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Scopes  "));
+        }
+
+        // Add blame panel shortcut if git history was read
+        if app.has_blame_data() {
+            spans.push(Span::styled("b", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Blame  "));
+        }
+
+        // Add install footprint panel shortcut if node_modules was measured
+        if app.has_footprint_data() {
+            spans.push(Span::styled("f", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Install  "));
+        }
+
+        // Add chunk graph panel shortcut if webpack stats list chunks
+        if app.has_chunk_data() {
+            spans.push(Span::styled("c", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Chunks  "));
+        }
+
+        // Add condensed graph panel shortcut if the graph has cycles
+        if app.has_condensed_data() {
+            spans.push(Span::styled("z", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Condensed  "));
+        }
+
+        // Add hoisting panel shortcut if a package is installed more than once
+        if app.has_hoisting_data() {
+            spans.push(Span::styled("h", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Hoisting  "));
+        }
+
+        // Add warnings shortcut once the notification area was dismissed
+        if app.has_warnings() && !app.show_warnings {
+            spans.push(Span::styled("w", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(format!(" Warnings ({})  ", app.warnings.len())));
+        }
+
+        // Add imports shortcut once the sources were analyzed
+        if app.has_import_data() {
+            spans.push(Span::styled("v", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Imports  "));
+        }
+
+        // Add size changes shortcut once a re-analysis changed sizes
+        if app.has_change_data() {
+            spans.push(Span::styled("l", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Changes  "));
+        }
+
+        // Add export shortcut if the analysis can be exported
+        if app.export_report.is_some() {
+            spans.push(Span::styled("E", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Export  "));
+        }
+
+        // Add column sort and scroll shortcuts while a table panel is open
+        if app.has_open_table() {
+            spans.push(Span::styled("</>", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Sort column  "));
+            spans.push(Span::styled("←/→", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Scroll  "));
+        }
+
+        // Add removal preview shortcut, and its confirmation while open
+        if app.show_removal_panel && app.selected_removal_impact().is_some() {
+            spans.push(Span::styled("y", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Uninstall  "));
+        } else if app.has_removal_data() {
+            spans.push(Span::styled("x", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Remove  "));
+        }
+
         spans.extend(vec![
             Span::styled("j/k", Style::default().fg(Color::Yellow)),
             Span::raw(" Nav  "),
+            Span::styled("←", Style::default().fg(Color::Yellow)),
+            Span::raw(" Parent  "),
+            Span::styled("p", Style::default().fg(Color::Yellow)),
+            Span::raw(" Pin  "),
+            Span::styled("n", Style::default().fg(Color::Yellow)),
+            Span::raw(" Note  "),
+            Span::styled("e/N/R/C", Style::default().fg(Color::Yellow)),
+            Span::raw(" Open  "),
             Span::styled("q", Style::default().fg(Color::Yellow)),
             Span::raw(" Quit  │  "),
             Span::styled("[P]", Style::default().fg(Color::Green)),
@@ -1178,6 +3834,16 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
             Span::raw(" Cycle  │  Sort: "),
             Span::styled(app.sort_mode.display_name(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         ]);
+        if let Some(run) = app.analyzing {
+            spans.push(Span::raw("  │  "));
+            spans.push(Span::styled(
+                format!("{} Analyzing, run {}", spinner_frame(app.ticks), run),
+                Style::default().fg(Color::Yellow),
+            ));
+        } else if let Some(run) = app.watch_run {
+            spans.push(Span::raw("  │  "));
+            spans.push(Span::styled(format!("● Watching, run {}", run), Style::default().fg(Color::Green)));
+        }
 
         Line::from(spans)
     };
@@ -1275,6 +3941,315 @@ mod tests {
         assert!(app.should_quit);
     }
 
+    #[test]
+    fn test_toggle_orphan_panel() {
+        let mut app = create_test_app();
+        app.toggle_orphan_panel();
+        assert!(!app.show_orphan_panel, "panel needs a report");
+
+        app.set_orphan_report(OrphanReport::default());
+        app.set_savings_report(SavingsReport::default());
+        assert!(app.has_orphan_data());
+
+        app.toggle_savings_panel();
+        app.toggle_orphan_panel();
+        assert!(app.show_orphan_panel);
+        assert!(!app.show_savings_panel, "side panels are exclusive");
+
+        app.toggle_orphan_panel();
+        assert!(!app.show_orphan_panel);
+    }
+
+    #[test]
+    fn test_toggle_group_panel() {
+        let mut app = create_test_app();
+        app.toggle_group_panel();
+        assert!(!app.show_group_panel, "panel needs configured groups");
+
+        app.set_group_summaries(vec![GroupSummary {
+            name: "ui".to_string(),
+            packages: vec!["react".to_string()],
+            bundle_size: 0,
+            sized_packages: 0,
+            max_size_kb: None,
+            max_count: Some(1),
+        }]);
+        app.set_orphan_report(OrphanReport::default());
+        app.toggle_orphan_panel();
+        app.toggle_group_panel();
+        assert!(app.show_group_panel);
+        assert!(!app.show_orphan_panel, "side panels are exclusive");
+        assert!(app.has_open_panel());
+
+        app.close_side_panels();
+        assert!(!app.has_open_panel());
+    }
+
+    #[test]
+    fn test_removal_panel_and_confirm() {
+        use crate::analysis::removal::simulate_removals;
+        use crate::graph::{self, DependencyGraph};
+
+        let mut app = create_test_app();
+        app.toggle_removal_panel();
+        assert!(!app.show_removal_panel, "panel needs simulations");
+
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", graph::DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", graph::DependencyType::Production);
+        app.set_removal_impacts(simulate_removals(&graph, None));
+
+        // Root is selected: not a direct dependency
+        app.toggle_removal_panel();
+        assert!(app.show_removal_panel);
+        assert!(app.selected_removal_impact().is_none());
+        app.confirm_uninstall();
+        assert!(!app.should_quit);
+
+        app.select_next();
+        assert_eq!(app.selected_removal_impact().unwrap().package, "react");
+        app.confirm_uninstall();
+        assert_eq!(app.uninstall_request.as_deref(), Some("react"));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_blame_panel_follows_selection() {
+        let mut app = create_test_app();
+        app.toggle_blame_panel();
+        assert!(!app.show_blame_panel, "panel needs git history");
+
+        let mut origins = BTreeMap::new();
+        origins.insert(
+            "react".to_string(),
+            Commit {
+                commit: "1a2b3c4d5e".to_string(),
+                author: "Ada".to_string(),
+                date: "2024-01-02".to_string(),
+                summary: "Add react".to_string(),
+            },
+        );
+        app.set_dependency_origins(origins);
+        app.toggle_blame_panel();
+        assert!(app.show_blame_panel);
+        assert!(app.selected_origin().is_none());
+
+        app.select_next();
+        assert_eq!(app.selected_origin().unwrap().short_commit(), "1a2b3c4");
+
+        app.toggle_group_panel();
+        app.close_side_panels();
+        assert!(!app.has_open_panel());
+    }
+
+    #[test]
+    fn test_hoisting_panel_follows_selection() {
+        use crate::analysis::hoisting::explain_hoisting;
+        use crate::parser::lockfile::PackageLock;
+
+        let mut app = create_test_app();
+        app.toggle_hoisting_panel();
+        assert!(!app.show_hoisting_panel, "panel needs a duplicated package");
+
+        let lock = PackageLock::parse(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "react": "^18.0.0", "lodash": "^4.17.0" } },
+    "node_modules/react": { "version": "18.2.0", "dependencies": { "lodash": "^3.0.0" } },
+    "node_modules/react/node_modules/lodash": { "version": "3.10.1" },
+    "node_modules/lodash": { "version": "4.17.21" }
+  }
+}"#,
+        )
+        .unwrap();
+        app.set_hoisting(explain_hoisting(&lock));
+        app.toggle_hoisting_panel();
+        assert!(app.show_hoisting_panel);
+        assert!(app.selected_hoisting().is_none());
+
+        app.select_next();
+        assert!(app.selected_hoisting().is_none(), "react is installed once");
+        app.select_next();
+        assert_eq!(app.selected_hoisting().unwrap().copy_count(), 2);
+    }
+
+    #[test]
+    fn test_condensed_panel_follows_selection() {
+        use crate::graph::{DependencyGraph, DependencyType as GraphType};
+
+        let mut app = create_test_app();
+        let mut graph = DependencyGraph::new();
+        for name in ["react", "react-dom", "lodash"] {
+            graph.add_dependency(name, "1.0.0", GraphType::Production);
+        }
+        app.set_condensation(Condensation::of(&graph));
+        app.toggle_condensed_panel();
+        assert!(!app.show_condensed_panel, "panel needs a cycle");
+
+        graph.add_edge("react", "react-dom");
+        graph.add_edge("react-dom", "react");
+        app.set_condensation(Condensation::of(&graph));
+        app.toggle_condensed_panel();
+        assert!(app.show_condensed_panel);
+
+        app.select_next();
+        assert_eq!(app.selected_component().unwrap().members, vec!["react", "react-dom"]);
+        app.select_next();
+        assert!(!app.selected_component().unwrap().is_cycle());
+    }
+
+    #[test]
+    fn test_scope_panel_toggle() {
+        let mut app = create_test_app();
+        app.toggle_scope_panel();
+        assert!(!app.show_scope_panel, "panel needs scoped packages");
+
+        app.set_scope_summaries(vec![ScopeSummary {
+            scope: "@babel".to_string(),
+            packages: vec!["@babel/core".to_string()],
+            bundle_size: 0,
+            sized_packages: 0,
+        }]);
+        app.toggle_scope_panel();
+        assert!(app.show_scope_panel);
+        assert!(app.has_open_panel());
+
+        app.toggle_footprint_panel();
+        assert!(app.show_scope_panel, "footprint needs node_modules");
+        app.toggle_scope_panel();
+        assert!(!app.has_open_panel());
+    }
+
+    #[test]
+    fn test_table_sort_follows_open_panel() {
+        let mut app = create_test_app();
+        app.set_scope_summaries(vec![
+            ScopeSummary {
+                scope: "@babel".to_string(),
+                packages: vec!["@babel/core".to_string()],
+                bundle_size: 30_000,
+                sized_packages: 1,
+            },
+            ScopeSummary {
+                scope: "@types".to_string(),
+                packages: vec!["@types/node".to_string(), "@types/react".to_string()],
+                bundle_size: 0,
+                sized_packages: 0,
+            },
+        ]);
+        app.sort_table_next();
+        assert_eq!(app.scope_table.sort_label(), "Size ↓", "no table is open");
+
+        app.toggle_scope_panel();
+        assert!(app.has_open_table());
+        app.sort_table_next();
+        app.sort_table_next();
+        assert_eq!(app.scope_table.sort_label(), "Scope ↑");
+        app.sort_table_previous();
+        assert_eq!(app.scope_table.sort_label(), "Size ↑");
+        let scopes: Vec<_> = app.scope_table.sorted(&app.scope_summaries).iter().map(|s| s.scope.as_str()).collect();
+        assert_eq!(scopes, vec!["@types", "@babel"]);
+        assert_eq!(app.savings_table.sort_label(), "Savings ↓");
+
+        app.select_next_table_row();
+        app.select_next_table_row();
+        assert_eq!(app.scope_table.selected_row(&app.scope_summaries).map(|s| s.scope.as_str()), Some("@babel"));
+        app.select_previous_table_row();
+        assert_eq!(app.scope_table.selected(), 0);
+        assert_eq!(app.savings_table.selected(), 0, "only the open table moves");
+    }
+
+    #[test]
+    fn test_warnings_notification_toggle() {
+        let mut app = create_test_app();
+        app.toggle_warnings();
+        assert!(!app.show_warnings, "nothing to show");
+
+        let mut warnings = AnalysisWarnings::new();
+        warnings.push("stats.json", "Failed to read webpack stats");
+        app.set_warnings(warnings);
+        assert!(app.show_warnings, "shown until dismissed");
+        app.toggle_warnings();
+        assert!(!app.show_warnings);
+        assert!(app.has_warnings());
+    }
+
+    #[test]
+    fn test_chunk_panel_navigation() {
+        let mut app = create_test_app();
+        app.toggle_chunk_panel();
+        assert!(!app.show_chunk_panel, "panel needs webpack chunks");
+
+        let stats = crate::bundle::WebpackStats::parse(
+            r#"{"chunks": [
+                {"id": 0, "names": ["main"], "size": 500, "entry": true, "children": [1]},
+                {"id": 1, "size": 100, "parents": [0]}
+            ]}"#,
+        )
+        .unwrap();
+        app.set_chunk_graph(crate::bundle::ChunkGraph::from_stats(&stats));
+        app.toggle_chunk_panel();
+        assert!(app.show_chunk_panel);
+        assert_eq!(app.selected_chunk().map(|c| c.label()), Some("main".to_string()));
+
+        app.select_next_chunk();
+        app.select_next_chunk();
+        assert_eq!(app.selected_chunk().map(|c| c.label()), Some("#1".to_string()));
+        app.select_previous_chunk();
+        assert_eq!(app.chunk_selected, 0);
+        assert_eq!(app.selected_index, 0, "tree selection is unchanged");
+
+        app.toggle_scope_panel();
+        assert!(app.show_chunk_panel, "scopes need scoped packages");
+        app.toggle_chunk_panel();
+        assert!(!app.has_open_panel());
+    }
+
+    #[test]
+    fn test_footprint_panel_sorting() {
+        use crate::analysis::footprint::{InstallFootprint, PackageFootprint};
+
+        let mut app = create_test_app();
+        app.toggle_footprint_panel();
+        assert!(!app.show_footprint_panel, "panel needs node_modules");
+
+        let package = |name: &str, bytes: u64, files: usize| PackageFootprint {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            footprint: InstallFootprint {
+                bytes,
+                files,
+                ..Default::default()
+            },
+        };
+        app.set_footprint_report(FootprintReport {
+            packages: vec![package("small", 10, 50), package("large", 900, 5)],
+            missing: Vec::new(),
+        });
+        app.toggle_footprint_panel();
+        assert!(app.show_footprint_panel);
+
+        let names = |app: &App| {
+            app.footprint_report
+                .as_ref()
+                .unwrap()
+                .packages
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&app), vec!["large", "small"]);
+        app.cycle_footprint_sort();
+        assert_eq!(app.footprint_sort, FootprintSort::Files);
+        assert_eq!(names(&app), vec!["small", "large"]);
+
+        app.toggle_blame_panel();
+        assert!(app.show_footprint_panel, "blame needs git history");
+        app.close_side_panels();
+        assert!(!app.has_open_panel());
+    }
+
     #[test]
     fn test_fuzzy_match() {
         // Exact match
@@ -1349,6 +4324,336 @@ mod tests {
         assert!(app.filtered.iter().any(|n| n.name == "react-dom"));
     }
 
+    #[test]
+    fn test_breadcrumb_and_parent() {
+        let mut app = create_test_app();
+        app.selected_index = 1;
+        app.toggle_selected();
+        app.select_next();
+        let names = |app: &App| app.breadcrumb().iter().map(|(_, node)| node.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&app), vec!["my-project", "react", "react-dom"]);
+
+        app.select_parent();
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react"));
+        app.select_parent();
+        app.select_parent();
+        assert_eq!(app.selected_index, 0);
+
+        // A parent hidden by the filter clears the search
+        app.start_search();
+        app.search_push('d');
+        app.search_push('o');
+        app.search_push('m');
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react-dom"));
+        app.search_active = false;
+        app.select_parent();
+        assert!(app.search_query.is_empty());
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react"));
+    }
+
+    #[test]
+    fn test_breadcrumb_click_follows_selection_order() {
+        let mut app = create_test_app();
+        app.selected_index = 1;
+        app.toggle_selected();
+        app.set_sort_mode(SortMode::SizeDescending);
+        let dom = app.flattened.iter().position(|node| node.name == "react-dom").unwrap();
+        app.selected_index = dom;
+        assert_eq!(app.breadcrumb().len(), 3);
+
+        app.click_targets = vec![
+            (Rect::new(0, 4, 10, 1), ClickTarget::TreeIndex(0)),
+            (Rect::new(13, 4, 5, 1), ClickTarget::TreeIndex(1)),
+        ];
+        app.click(15, 5);
+        assert_eq!(app.selected_index, dom);
+        app.click(15, 4);
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react"));
+    }
+
+    #[test]
+    fn test_pins() {
+        let mut app = create_test_app();
+        app.selected_index = 2;
+        app.toggle_pin_selected();
+        app.set_pinned(vec!["react-dom".to_string(), "lodash".to_string()]);
+        app.selected_index = 2;
+        app.toggle_pin_selected();
+        assert_eq!(app.pinned, vec!["react-dom"]);
+
+        // Jumping to a package under a collapsed node expands the way to it
+        app.select_pinned(0);
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react-dom"));
+        assert_eq!(app.flattened.len(), 4);
+
+        // ...and clears a search that hides it
+        app.selected_index = 0;
+        for c in "lod".chars() {
+            app.search_push(c);
+        }
+        app.select_pinned(0);
+        assert!(app.search_query.is_empty());
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react-dom"));
+
+        app.select_pinned(5);
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("react-dom"));
+        app.click_targets = vec![(Rect::new(2, 3, 6, 1), ClickTarget::Package("lodash".to_string()))];
+        app.click(4, 3);
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("lodash"));
+    }
+
+    #[test]
+    fn test_open_package() {
+        let mut app = create_test_app();
+        app.set_package_links(BTreeMap::from([(
+            "react".to_string(),
+            PackageLinks {
+                directory: Some(PathBuf::from("node_modules/react")),
+                repository: Some("https://github.com/facebook/react".to_string()),
+                ..PackageLinks::default()
+            },
+        )]));
+
+        app.selected_index += 1;
+        app.open_selected_directory();
+        assert_eq!(app.open_request.take(), Some(OpenRequest::Editor(PathBuf::from("node_modules/react"))));
+        app.open_selected_page(PackagePage::Npm);
+        assert_eq!(
+            app.open_request.take(),
+            Some(OpenRequest::Browser("https://www.npmjs.com/package/react".to_string()))
+        );
+        app.open_selected_page(PackagePage::Changelog);
+        assert_eq!(app.open_request, None);
+        assert_eq!(app.status.as_deref(), Some("react's package.json does not name a repository"));
+
+        // Without the browser the URL is only shown
+        app.set_open_browser(false);
+        app.open_selected_page(PackagePage::Repository);
+        assert_eq!(app.open_request, None);
+        assert_eq!(app.status.as_deref(), Some("https://github.com/facebook/react"));
+
+        app.selected_index += 1;
+        app.open_selected_directory();
+        assert_eq!(app.open_request, None);
+        assert_eq!(app.status.as_deref(), Some("lodash is not installed in a directory"));
+    }
+
+    #[test]
+    fn test_export_selection() {
+        let mut graph = crate::graph::DependencyGraph::new();
+        for name in ["react", "react-dom", "lodash"] {
+            graph.add_dependency(name, "1.0.0", crate::graph::DependencyType::Production);
+        }
+        graph.add_edge("react", "react-dom");
+        let report = ExportReport::from_graph("my-project", "1.0.0", &graph)
+            .with_metadata(crate::export::metadata::ReportMetadata::new("."));
+        let dir = std::env::temp_dir().join(format!("codescope-export-selection-test-{}", std::process::id()));
+
+        let mut app = create_test_app();
+        app.set_export_report(report, &dir);
+        app.selected_index = 1;
+        app.export_selection();
+        assert_eq!(app.status.as_deref(), Some("Exported 2 packages to .codescope/exports/react.md"));
+        let markdown = fs::read_to_string(dir.join(".codescope/exports/react.md")).unwrap();
+        assert!(markdown.contains("react and its dependencies"));
+        assert!(markdown.contains("react-dom") && !markdown.contains("lodash"));
+
+        app.search_query = "lod".to_string();
+        app.update_filter();
+        app.export_selection();
+        assert_eq!(app.status.as_deref(), Some("Exported 1 package to .codescope/exports/search-lod.md"));
+
+        app.search_query.clear();
+        app.update_filter();
+        app.export_selection();
+        assert_eq!(app.status.as_deref(), Some("Exported 3 packages to .codescope/exports/my-project.md"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_watch_changes() {
+        let mut app = create_test_app();
+        app.toggle_changes_panel();
+        assert!(!app.show_changes_panel, "panel needs a size change");
+
+        app.select_package("lodash");
+        let mut tree = TreeNode::new("my-project".to_string(), "1.0.0".to_string());
+        tree.add_child(TreeNode::new("dayjs".to_string(), "1.11.10".to_string()));
+        tree.add_child(TreeNode::new("lodash".to_string(), "4.17.21".to_string()));
+        app.replace_tree(tree);
+        assert!(app.tree.expanded, "expansion is kept");
+        assert_eq!(app.selected_node().map(|node| node.name.as_str()), Some("lodash"));
+
+        let now = Instant::now();
+        app.record_size_changes(2, vec![SizeChange {
+            package: "dayjs".to_string(),
+            before: None,
+            after: Some(7_000),
+            run: 2,
+            at: now,
+        }]);
+        assert_eq!(app.watch_run, Some(2));
+        assert_eq!(app.size_changes.recent("dayjs", now).map(SizeChange::badge).as_deref(), Some("new"));
+        app.toggle_changes_panel();
+        assert!(app.show_changes_panel);
+    }
+
+    #[test]
+    fn test_update_events() {
+        let mut app = create_test_app();
+        assert!(!app.update(AppEvent::Tick), "nothing moves on an idle screen");
+        assert_eq!(app.tick_rate(), IDLE_TICK);
+        assert!(app.update(AppEvent::Input(Event::Key(KeyCode::Char('/').into()))));
+        assert!(app.search_active);
+        assert_eq!(app.tick_rate(), ANIMATION_TICK, "the search cursor blinks");
+        app.update(AppEvent::Input(Event::Key(KeyCode::Esc.into())));
+
+        assert!(app.update(AppEvent::AnalysisUpdate(AnalysisUpdate::Started { run: 2 })));
+        assert_eq!(app.analyzing, Some(2));
+        assert!(app.update(AppEvent::Tick), "the spinner turns");
+
+        let mut tree = TreeNode::new("my-project".to_string(), "1.0.0".to_string());
+        tree.add_child(TreeNode::new("dayjs".to_string(), "1.11.10".to_string()));
+        app.update(AppEvent::AnalysisUpdate(AnalysisUpdate::Finished { run: 2, tree, changes: Vec::new() }));
+        assert_eq!(app.analyzing, None);
+        assert_eq!(app.watch_run, Some(2));
+        assert_eq!(app.tree.children.len(), 1);
+
+        app.update(AppEvent::AnalysisUpdate(AnalysisUpdate::Started { run: 3 }));
+        app.update(AppEvent::Error("Run 3 failed: bad package.json".to_string()));
+        assert_eq!(app.analyzing, None);
+        assert_eq!(app.status.as_deref(), Some("Run 3 failed: bad package.json"));
+    }
+
+    #[test]
+    fn test_notes() {
+        let mut app = create_test_app();
+        app.selected_index = 2;
+        app.start_note();
+        assert!(app.show_notes_panel);
+        for c in "replace with es-toolkit ".chars() {
+            app.note_push(c);
+        }
+        app.note_pop();
+        app.save_note();
+        assert_eq!(app.note_input, None);
+        assert_eq!(app.selected_note(), Some("replace with es-toolkit"));
+
+        // Editing starts from the saved note, and cancelling keeps it
+        app.start_note();
+        assert_eq!(app.note_input.as_deref(), Some("replace with es-toolkit"));
+        app.note_push('!');
+        app.cancel_note();
+        assert_eq!(app.selected_note(), Some("replace with es-toolkit"));
+
+        // A blank note removes it
+        app.start_note();
+        app.note_input = Some("  ".to_string());
+        app.save_note();
+        assert!(app.notes.is_empty());
+
+        app.selected_index = 1;
+        assert_eq!(app.selected_note(), None);
+    }
+
+    #[test]
+    fn test_imports_panel() {
+        let root = std::env::temp_dir().join(format!("codescope-imports-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.js"), "import _ from 'lodash';\n_.chunk([]);\n").unwrap();
+        let site = |file: &Path, line: usize| ImportSite {
+            file: file.display().to_string(),
+            lines: vec![line],
+        };
+        let mut sites = BTreeMap::new();
+        sites.insert("lodash".to_string(), vec![site(&root.join("src/a.js"), 1), site(&root.join("src/b.js"), 3)]);
+
+        let mut app = create_test_app();
+        app.toggle_imports_panel();
+        assert!(!app.show_imports_panel, "panel needs analyzed imports");
+        app.set_import_sites(&root, sites);
+        app.toggle_imports_panel();
+        assert!(app.show_imports_panel);
+
+        app.selected_index = 2;
+        assert_eq!(app.selected_import_sites()[1].file, Path::new("src").join("b.js").display().to_string());
+        let preview = app.selected_import_preview().unwrap().as_ref().unwrap();
+        assert_eq!(preview.hunks[0].len(), 2);
+        assert!(preview.hunks[0][0].is_import);
+
+        // The selected file is kept per package and wraps around
+        app.select_previous_import();
+        assert_eq!(app.selected_import_index(), 1);
+        assert!(app.selected_import_preview().unwrap().is_err());
+        app.selected_index = 1;
+        assert!(app.selected_import_sites().is_empty());
+        app.select_next_import();
+        app.selected_index = 2;
+        app.select_next_import();
+        assert_eq!(app.selected_import_index(), 0);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_sticky_ancestors() {
+        let mut root = TreeNode::new("my-project".to_string(), "1.0.0".to_string());
+        let mut deps = TreeNode::new("dependencies".to_string(), String::new());
+        let mut react = TreeNode::new("react".to_string(), "18.2.0".to_string());
+        for name in ["loose-envify", "scheduler", "js-tokens"] {
+            react.add_child(TreeNode::new(name.to_string(), "1.0.0".to_string()));
+        }
+        react.expanded = true;
+        deps.add_child(react);
+        deps.expanded = true;
+        root.add_child(deps);
+        root.expanded = true;
+        let mut app = App::new(root);
+
+        // my-project, dependencies, react, loose-envify, scheduler, js-tokens
+        assert!(sticky_ancestors(&app, 0, 3).is_empty());
+        assert_eq!(sticky_ancestors(&app, 4, 3), vec![0, 1, 2]);
+        assert_eq!(sticky_ancestors(&app, 4, 2), vec![1, 2]);
+        assert_eq!(sticky_ancestors(&app, 2, 3), vec![0, 1]);
+
+        // Sorted and filtered views have no ancestry to pin
+        app.set_sort_mode(SortMode::SizeDescending);
+        assert!(sticky_ancestors(&app, 4, 3).is_empty());
+    }
+
+    #[test]
+    fn test_layout_breadcrumb() {
+        let path = ["my-project", "dependencies", "react", "scheduler"];
+        // my-project › dependencies › react › scheduler
+        assert_eq!(layout_breadcrumb(&path, 80), (false, vec![(0, 0), (13, 1), (28, 2), (36, 3)]));
+        // … › react › scheduler
+        assert_eq!(layout_breadcrumb(&path, 24), (true, vec![(4, 2), (12, 3)]));
+        assert_eq!(layout_breadcrumb(&path, 3), (true, vec![(4, 3)]));
+        assert_eq!(layout_breadcrumb(&[], 10), (false, vec![]));
+    }
+
+    #[test]
+    fn test_search_matches_descriptions() {
+        let mut app = create_test_app();
+        app.start_search();
+        for c in "utility".chars() {
+            app.search_push(c);
+        }
+        assert!(app.filtered.is_empty());
+
+        let mut descriptions = BTreeMap::new();
+        descriptions.insert(
+            "lodash".to_string(),
+            PackageDescription::new(Some("Lodash modular utilities."), None),
+        );
+        app.set_package_descriptions(descriptions);
+        assert!(app.filtered.is_empty(), "'utility' is not in 'utilities'");
+
+        app.search_pop();
+        app.search_pop();
+        assert_eq!(app.filtered.len(), 1);
+        assert_eq!(app.filtered[0].name, "lodash");
+    }
+
     #[test]
     fn test_search_pop() {
         let mut app = create_test_app();
@@ -1467,6 +4772,31 @@ mod tests {
         assert!(state.offset + state.viewport_height > 15);
     }
 
+    #[test]
+    fn test_virtual_scroll_clamp() {
+        let mut state = VirtualScrollState::new();
+        state.set_viewport_height(10);
+        state.offset = 95;
+        state.clamp(100);
+        assert_eq!(state.offset, 90);
+
+        // A viewport taller than the rows shows them from the top
+        state.set_viewport_height(120);
+        state.clamp(100);
+        assert_eq!(state.offset, 0);
+    }
+
+    #[test]
+    fn test_resize_keeps_selection_visible() {
+        let mut app = create_test_app();
+        app.scroll_state.set_viewport_height(40);
+        app.select_last();
+        app.resize(2);
+        assert_eq!(app.scroll_state.viewport_height, 2);
+        let (start, end) = app.scroll_state.visible_range(app.selected_index, app.current_list_len());
+        assert!((start..end).contains(&app.selected_index));
+    }
+
     #[test]
     fn test_page_navigation() {
         let mut app = create_test_app();
@@ -1560,40 +4890,49 @@ mod tests {
                 name: "react".to_string(),
                 version: "18.0.0".to_string(),
                 depth: 0,
+                graph_depth: None,
                 is_expanded: false,
                 has_children: false,
                 is_last_child: false,
                 dep_type: None,
                 is_in_cycle: false,
                 has_conflict: false,
+                is_prerelease: false,
                 bundle_size: Some(10000),
                 module_count: Some(5),
+                tree_index: 0,
             },
             FlattenedNode {
                 name: "lodash".to_string(),
                 version: "4.17.0".to_string(),
                 depth: 0,
+                graph_depth: None,
                 is_expanded: false,
                 has_children: false,
                 is_last_child: false,
                 dep_type: None,
                 is_in_cycle: false,
                 has_conflict: false,
+                is_prerelease: false,
                 bundle_size: Some(25000),
                 module_count: Some(10),
+                tree_index: 0,
             },
             FlattenedNode {
                 name: "no-size".to_string(),
                 version: "1.0.0".to_string(),
                 depth: 0,
+                graph_depth: None,
                 is_expanded: false,
                 has_children: false,
                 is_last_child: false,
                 dep_type: None,
                 is_in_cycle: false,
                 has_conflict: false,
+                is_prerelease: false,
                 bundle_size: None,
                 module_count: None,
+                tree_index: 0,
             },
         ];
 
@@ -1619,6 +4958,17 @@ mod tests {
         assert_eq!(result, "10.00 KB");
     }
 
+    #[test]
+    fn test_size_bar() {
+        assert_eq!(size_bar(50, 100, 4), "██  ");
+        assert_eq!(size_bar(100, 100, 4), "████");
+        // 3/32 of the width is three eighths of a cell
+        assert_eq!(size_bar(3, 32, 4), "▍   ");
+        assert_eq!(size_bar(1, 1_000_000, 4), "▏   ");
+        assert_eq!(size_bar(0, 100, 4), "    ");
+        assert_eq!(size_bar(10, 0, 4), "    ");
+    }
+
     #[test]
     fn test_format_size_with_percentage_large() {
         // 1MB out of 2MB = 50%
@@ -0,0 +1,155 @@
+//! Size changes seen while the TUI watches a project.
+//!
+//! When the analysis re-runs after package.json, the lockfile or the stats
+//! file changed, every package whose bundle size moved gets a `+12.3 KB` or
+//! `-4.0 KB` badge in the tree for [`BADGE_DURATION`], so the cost of the
+//! dependency just added shows where it lands. The changes of the last runs
+//! stay listed in the changes panel.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::tree::format_size;
+
+/// How long a package shows the badge of its last size change.
+pub const BADGE_DURATION: Duration = Duration::from_secs(60);
+
+/// Number of size changes kept for the changes panel.
+pub const CHANGE_LOG_LIMIT: usize = 100;
+
+/// A package's bundle size before and after a re-analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeChange {
+    /// Package name
+    pub package: String,
+    /// Size before, `None` if unknown or not installed
+    pub before: Option<u64>,
+    /// Size after, `None` if unknown or no longer installed
+    pub after: Option<u64>,
+    /// Analysis run that saw the change
+    pub run: u64,
+    /// When the change was seen
+    pub at: Instant,
+}
+
+impl SizeChange {
+    /// Returns the growth in bytes, negative if the package shrank.
+    pub fn delta(&self) -> i64 {
+        self.after.unwrap_or(0) as i64 - self.before.unwrap_or(0) as i64
+    }
+
+    /// Returns the badge shown next to the package, e.g. `+12.3 KB`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Instant;
+    /// use codescope::ui::changes::SizeChange;
+    ///
+    /// let change = |before, after| SizeChange { package: "dayjs".to_string(), before, after, run: 2, at: Instant::now() };
+    /// assert_eq!(change(Some(1024), Some(3072)).badge(), "+2.00 KB");
+    /// assert_eq!(change(Some(3072), Some(1024)).badge(), "-2.00 KB");
+    /// assert_eq!(change(None, Some(1024)).badge(), "new");
+    /// assert_eq!(change(Some(1024), None).badge(), "removed");
+    /// ```
+    pub fn badge(&self) -> String {
+        match (self.before, self.after) {
+            (None, Some(_)) => "new".to_string(),
+            (Some(_), None) => "removed".to_string(),
+            _ if self.delta() < 0 => format!("-{}", format_size(self.delta().unsigned_abs())),
+            _ => format!("+{}", format_size(self.delta().unsigned_abs())),
+        }
+    }
+}
+
+/// The size changes of the last runs, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeLog {
+    changes: VecDeque<SizeChange>,
+}
+
+impl ChangeLog {
+    /// Records the changes of one run, dropping the oldest beyond
+    /// [`CHANGE_LOG_LIMIT`].
+    pub fn record(&mut self, changes: impl IntoIterator<Item = SizeChange>) {
+        for change in changes {
+            self.changes.push_front(change);
+        }
+        self.changes.truncate(CHANGE_LOG_LIMIT);
+    }
+
+    /// Returns the changes, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &SizeChange> {
+        self.changes.iter()
+    }
+
+    /// Returns true if no change was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the last change of `package` if it was seen less than
+    /// [`BADGE_DURATION`] before `now`.
+    pub fn recent(&self, package: &str, now: Instant) -> Option<&SizeChange> {
+        self.changes
+            .iter()
+            .find(|change| change.package == package)
+            .filter(|change| now.saturating_duration_since(change.at) < BADGE_DURATION)
+    }
+}
+
+/// Formats how long ago `at` was, e.g. `12s ago` or `3m ago`.
+pub fn format_age(at: Instant, now: Instant) -> String {
+    let secs = now.saturating_duration_since(at).as_secs();
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(package: &str, run: u64, at: Instant) -> SizeChange {
+        SizeChange {
+            package: package.to_string(),
+            before: Some(1000),
+            after: Some(1500),
+            run,
+            at,
+        }
+    }
+
+    #[test]
+    fn test_change_log() {
+        let start = Instant::now();
+        let mut log = ChangeLog::default();
+        assert!(log.is_empty());
+        log.record([change("react", 2, start), change("lodash", 2, start)]);
+        log.record([change("react", 3, start + Duration::from_secs(30))]);
+
+        assert_eq!(log.iter().map(|c| (c.package.as_str(), c.run)).collect::<Vec<_>>(), vec![
+            ("react", 3),
+            ("lodash", 2),
+            ("react", 2),
+        ]);
+        let later = start + Duration::from_secs(70);
+        assert_eq!(log.recent("react", later).map(|c| c.run), Some(3));
+        assert!(log.recent("lodash", later).is_none());
+        assert_eq!(log.recent("react", later).unwrap().delta(), 500);
+
+        log.record((0..CHANGE_LOG_LIMIT as u64).map(|run| change("dayjs", run, start)));
+        assert_eq!(log.iter().count(), CHANGE_LOG_LIMIT);
+        assert!(log.iter().all(|c| c.package == "dayjs"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        let start = Instant::now();
+        assert_eq!(format_age(start, start + Duration::from_secs(12)), "12s ago");
+        assert_eq!(format_age(start, start + Duration::from_secs(190)), "3m ago");
+        assert_eq!(format_age(start, start + Duration::from_secs(7300)), "2h ago");
+    }
+}
@@ -0,0 +1,493 @@
+//! Side-by-side comparison of two projects
+//!
+//! Shows the packages of two projects (or two snapshots of one) in a split
+//! layout, one pane per side, with the rows lined up by package name. A
+//! single search filters both panes, and each row is colored by how the
+//! two sides differ.
+
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::analysis::diff::{format_size_delta, ComparedPackage, ComparedSide, Difference};
+use super::app::{fuzzy_match, highlight_matches};
+use super::screen;
+use super::tree::format_size;
+
+/// Which pane a row is rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// State of the comparison view
+pub struct CompareApp {
+    /// Label of the left project
+    pub left_label: String,
+    /// Label of the right project
+    pub right_label: String,
+    /// Every compared package, sorted by name
+    rows: Vec<ComparedPackage>,
+    /// Indices into `rows` of the packages shown
+    visible: Vec<usize>,
+    /// Index into `visible` of the selected row
+    pub selected_index: usize,
+    /// List state shared by both panes, so they scroll together
+    list_state: ListState,
+    /// Rows per page, updated on each render
+    page_size: usize,
+    /// Whether search mode is active
+    pub search_active: bool,
+    /// Current search query, applied to both panes
+    pub search_query: String,
+    /// Whether packages that are the same on both sides are hidden
+    pub differences_only: bool,
+    /// Whether the application should quit
+    pub should_quit: bool,
+}
+
+impl CompareApp {
+    /// Create a comparison view of the given packages
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The compared packages, from [`compare_graphs`](crate::analysis::compare_graphs)
+    /// * `left_label` - Label of the left project
+    /// * `right_label` - Label of the right project
+    pub fn new(rows: Vec<ComparedPackage>, left_label: impl Into<String>, right_label: impl Into<String>) -> Self {
+        let mut app = Self {
+            left_label: left_label.into(),
+            right_label: right_label.into(),
+            rows,
+            visible: Vec::new(),
+            selected_index: 0,
+            list_state: ListState::default(),
+            page_size: 1,
+            search_active: false,
+            search_query: String::new(),
+            differences_only: false,
+            should_quit: false,
+        };
+        app.update_filter();
+        app
+    }
+
+    /// Get the packages currently shown
+    pub fn visible_rows(&self) -> impl Iterator<Item = &ComparedPackage> {
+        self.visible.iter().map(|&i| &self.rows[i])
+    }
+
+    /// Get the selected package
+    pub fn selected(&self) -> Option<&ComparedPackage> {
+        self.visible.get(self.selected_index).map(|&i| &self.rows[i])
+    }
+
+    /// Count the packages with the given difference, ignoring the filter
+    pub fn count(&self, difference: Difference) -> usize {
+        self.rows.iter().filter(|row| row.difference() == difference).count()
+    }
+
+    /// Show only the packages that differ, or all of them again
+    pub fn toggle_differences_only(&mut self) {
+        self.differences_only = !self.differences_only;
+        self.update_filter();
+    }
+
+    /// Enter search mode
+    pub fn start_search(&mut self) {
+        self.search_active = true;
+        self.search_query.clear();
+        self.update_filter();
+    }
+
+    /// Clear search and return to normal mode
+    pub fn clear_search(&mut self) {
+        self.search_active = false;
+        self.search_query.clear();
+        self.update_filter();
+    }
+
+    /// Add a character to the search query
+    pub fn search_push(&mut self, c: char) {
+        self.search_query.push(c);
+        self.update_filter();
+    }
+
+    /// Remove the last character from the search query
+    pub fn search_pop(&mut self) {
+        self.search_query.pop();
+        self.update_filter();
+    }
+
+    /// Recompute the shown packages from the search and the filter
+    fn update_filter(&mut self) {
+        self.visible = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !self.differences_only || row.difference() != Difference::Same)
+            .filter(|(_, row)| fuzzy_match(&row.name, &self.search_query))
+            .map(|(i, _)| i)
+            .collect();
+        self.select(0);
+    }
+
+    /// Select a row, clamped to the shown packages
+    fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.visible.len().saturating_sub(1));
+        self.list_state
+            .select((!self.visible.is_empty()).then_some(self.selected_index));
+    }
+
+    /// Move selection to the next row
+    pub fn select_next(&mut self) {
+        self.select(self.selected_index + 1);
+    }
+
+    /// Move selection to the previous row
+    pub fn select_previous(&mut self) {
+        self.select(self.selected_index.saturating_sub(1));
+    }
+
+    /// Move selection down by a page
+    pub fn page_down(&mut self) {
+        self.select(self.selected_index + self.page_size);
+    }
+
+    /// Move selection up by a page
+    pub fn page_up(&mut self) {
+        self.select(self.selected_index.saturating_sub(self.page_size));
+    }
+
+    /// Jump to the first row
+    pub fn select_first(&mut self) {
+        self.select(0);
+    }
+
+    /// Jump to the last row
+    pub fn select_last(&mut self) {
+        self.select(self.visible.len().saturating_sub(1));
+    }
+
+    /// Signal that the application should quit
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+}
+
+/// Run the comparison view's event loop
+pub fn run_compare<B: Backend>(terminal: &mut Terminal<B>, app: &mut CompareApp) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| render(frame, app))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                if app.search_active {
+                    match key.code {
+                        KeyCode::Esc => app.clear_search(),
+                        KeyCode::Enter => app.search_active = false,
+                        KeyCode::Backspace => app.search_pop(),
+                        KeyCode::Char(c) => app.search_push(c),
+                        KeyCode::Down | KeyCode::Tab => app.select_next(),
+                        KeyCode::Up | KeyCode::BackTab => app.select_previous(),
+                        _ => {}
+                    }
+                } else {
+                    match key.code {
+                        KeyCode::Char('q') => app.quit(),
+                        KeyCode::Esc => {
+                            if !app.search_query.is_empty() {
+                                app.clear_search();
+                            } else {
+                                app.quit();
+                            }
+                        }
+                        KeyCode::Char('/') => app.start_search(),
+                        KeyCode::Char('f') => app.toggle_differences_only(),
+                        KeyCode::Char('j') | KeyCode::Down => app.select_next(),
+                        KeyCode::Char('k') | KeyCode::Up => app.select_previous(),
+                        KeyCode::PageDown | KeyCode::Char('d') => app.page_down(),
+                        KeyCode::PageUp | KeyCode::Char('u') => app.page_up(),
+                        KeyCode::Home | KeyCode::Char('g') => app.select_first(),
+                        KeyCode::End | KeyCode::Char('G') => app.select_last(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if app.should_quit {
+            return Ok(());
+        }
+    }
+}
+
+/// Render the comparison view
+fn render(frame: &mut Frame, app: &mut CompareApp) {
+    if !screen::fits(frame.area()) {
+        screen::render_too_small(frame);
+        return;
+    }
+
+    let show_search = app.search_active || !app.search_query.is_empty();
+    let mut constraints = vec![Constraint::Length(3)];
+    if show_search {
+        constraints.push(Constraint::Length(3));
+    }
+    constraints.extend([Constraint::Min(0), Constraint::Length(3)]);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(frame.area());
+
+    render_header(frame, app, chunks[0]);
+    if show_search {
+        render_search_bar(frame, app, chunks[1]);
+    }
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[chunks.len() - 2]);
+    // Borders take two lines of each pane
+    app.page_size = (panes[0].height as usize).saturating_sub(2).max(1);
+    render_pane(frame, app, Side::Left, panes[0]);
+    render_pane(frame, app, Side::Right, panes[1]);
+    render_footer(frame, app, chunks[chunks.len() - 1]);
+}
+
+/// Render the header with both labels
+fn render_header(frame: &mut Frame, app: &CompareApp, area: Rect) {
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled("CodeScope - Comparing ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(&app.left_label, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+        Span::styled(" with ", Style::default().fg(Color::Cyan)),
+        Span::styled(&app.right_label, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, area);
+}
+
+/// Render the search bar shared by both panes
+fn render_search_bar(frame: &mut Frame, app: &CompareApp, area: Rect) {
+    let (border_color, title) = if app.search_active {
+        (Color::Yellow, "Search both sides (Enter to confirm, Esc to cancel)")
+    } else {
+        (Color::Gray, "Filter (/ to edit, Esc to clear)")
+    };
+    let cursor = if app.search_active { "_" } else { "" };
+
+    let content = Line::from(vec![
+        Span::styled(format!("/{}", app.search_query), Style::default().fg(Color::White)),
+        Span::styled(cursor, Style::default().fg(Color::Yellow).add_modifier(Modifier::SLOW_BLINK)),
+        Span::styled(format!(" ({} matches)", app.visible.len()), Style::default().fg(Color::DarkGray)),
+    ]);
+    let search_bar = Paragraph::new(content).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color)),
+    );
+    frame.render_widget(search_bar, area);
+}
+
+/// Get the color of a row in one pane
+///
+/// - Present only on this side: Green on the left, Cyan on the right
+/// - Missing on this side: DarkGray
+/// - Different versions: Yellow
+/// - Different sizes: Magenta
+fn row_color(difference: Difference, side: Side) -> Color {
+    match (difference, side) {
+        (Difference::OnlyLeft, Side::Left) => Color::Green,
+        (Difference::OnlyRight, Side::Right) => Color::Cyan,
+        (Difference::OnlyLeft, Side::Right) | (Difference::OnlyRight, Side::Left) => Color::DarkGray,
+        (Difference::Version, _) => Color::Yellow,
+        (Difference::Size, _) => Color::Magenta,
+        (Difference::Same, _) => Color::White,
+    }
+}
+
+/// Build the line of one package in one pane
+fn row_line(row: &ComparedPackage, side: Side, query: &str) -> Line<'static> {
+    let difference = row.difference();
+    let color = row_color(difference, side);
+    let package = match side {
+        Side::Left => row.left.as_ref(),
+        Side::Right => row.right.as_ref(),
+    };
+
+    let mut spans = highlight_matches(&row.name, query, color);
+    match package {
+        Some(ComparedSide { version, bundle_size }) => {
+            spans.push(Span::styled(format!(" {}", version), Style::default().fg(color)));
+            if let Some(size) = bundle_size {
+                spans.push(Span::styled(
+                    format!(" ({})", format_size(*size)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if let (Side::Right, Some(delta)) = (side, row.size_delta().filter(|&delta| delta != 0)) {
+                spans.push(Span::styled(format!(" {}", format_size_delta(delta)), Style::default().fg(Color::Magenta)));
+            }
+        }
+        None => spans.push(Span::styled(" —", Style::default().fg(Color::DarkGray))),
+    }
+    Line::from(spans)
+}
+
+/// Render one side of the comparison
+fn render_pane(frame: &mut Frame, app: &mut CompareApp, side: Side, area: Rect) {
+    let (label, count, color) = match side {
+        Side::Left => (&app.left_label, app.rows.iter().filter(|r| r.left.is_some()).count(), Color::Green),
+        Side::Right => (&app.right_label, app.rows.iter().filter(|r| r.right.is_some()).count(), Color::Cyan),
+    };
+    let title = format!("{} ({} packages)", label, count);
+
+    let items: Vec<ListItem> = app
+        .visible_rows()
+        .map(|row| ListItem::new(row_line(row, side, &app.search_query)))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(color)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("► ");
+
+    frame.render_stateful_widget(list, area, &mut app.list_state);
+}
+
+/// Render the footer with the difference counts and key help
+fn render_footer(frame: &mut Frame, app: &CompareApp, area: Rect) {
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(Color::Yellow));
+    let help = Line::from(vec![
+        Span::styled(format!("{} only left", app.count(Difference::OnlyLeft)), Style::default().fg(Color::Green)),
+        Span::raw("  "),
+        Span::styled(format!("{} only right", app.count(Difference::OnlyRight)), Style::default().fg(Color::Cyan)),
+        Span::raw("  "),
+        Span::styled(format!("{} versions", app.count(Difference::Version)), Style::default().fg(Color::Yellow)),
+        Span::raw("  "),
+        Span::styled(format!("{} sizes", app.count(Difference::Size)), Style::default().fg(Color::Magenta)),
+        Span::raw("  │  "),
+        key("/"),
+        Span::raw(" Search  "),
+        key("f"),
+        Span::raw(if app.differences_only { " Show all  " } else { " Differences only  " }),
+        key("j/k"),
+        Span::raw(" Navigate  "),
+        key("q"),
+        Span::raw(" Quit"),
+    ]);
+    let footer = Paragraph::new(help).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(version: &str, bundle_size: Option<u64>) -> Option<ComparedSide> {
+        Some(ComparedSide {
+            version: version.to_string(),
+            bundle_size,
+        })
+    }
+
+    fn sample_app() -> CompareApp {
+        let row = |name: &str, left, right| ComparedPackage {
+            name: name.to_string(),
+            left,
+            right,
+        };
+        CompareApp::new(
+            vec![
+                row("dayjs", None, side("1.11.10", None)),
+                row("lodash", side("4.17.21", Some(70_000)), side("4.17.21", Some(71_536))),
+                row("moment", side("2.29.4", None), None),
+                row("react", side("18.2.0", None), side("18.3.1", None)),
+                row("react-dom", side("18.2.0", None), side("18.2.0", None)),
+            ],
+            "app-a",
+            "app-b",
+        )
+    }
+
+    fn visible_names(app: &CompareApp) -> Vec<&str> {
+        app.visible_rows().map(|row| row.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_filters_both_sides() {
+        let mut app = sample_app();
+        assert_eq!(visible_names(&app).len(), 5);
+
+        app.start_search();
+        for c in "rct".chars() {
+            app.search_push(c);
+        }
+        assert_eq!(visible_names(&app), vec!["react", "react-dom"]);
+
+        app.clear_search();
+        assert_eq!(visible_names(&app).len(), 5);
+    }
+
+    #[test]
+    fn test_differences_only() {
+        let mut app = sample_app();
+        app.toggle_differences_only();
+        assert_eq!(visible_names(&app), vec!["dayjs", "lodash", "moment", "react"]);
+        assert_eq!(app.count(Difference::Same), 1);
+
+        app.toggle_differences_only();
+        assert_eq!(visible_names(&app).len(), 5);
+    }
+
+    #[test]
+    fn test_navigation_stays_in_bounds() {
+        let mut app = sample_app();
+        app.select_previous();
+        assert_eq!(app.selected().map(|row| row.name.as_str()), Some("dayjs"));
+
+        app.select_last();
+        app.select_next();
+        assert_eq!(app.selected().map(|row| row.name.as_str()), Some("react-dom"));
+
+        app.toggle_differences_only();
+        app.page_down();
+        assert_eq!(app.selected_index, 1);
+
+        app.start_search();
+        app.search_push('z');
+        assert!(app.selected().is_none());
+        app.select_next();
+        assert!(app.selected().is_none());
+    }
+
+    #[test]
+    fn test_row_line_shows_missing_side_and_delta() {
+        let app = sample_app();
+        let text = |name: &str, side| {
+            let row = app.rows.iter().find(|row| row.name == name).unwrap();
+            row_line(row, side, "")
+                .spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect::<String>()
+        };
+        assert_eq!(text("moment", Side::Right), "moment —");
+        assert_eq!(text("lodash", Side::Right), "lodash 4.17.21 (69.86 KB) +1.50 KB");
+        assert_eq!(text("lodash", Side::Left), "lodash 4.17.21 (68.36 KB)");
+    }
+}
@@ -0,0 +1,75 @@
+//! Events driving the TUI.
+//!
+//! Key presses, the passing of time and the results of analyses running
+//! on other threads all reach the app as an [`AppEvent`], handled by
+//! [`App::update`](super::App::update). The event loop polls the terminal
+//! for input between ticks, so a slow analysis never holds up the keys;
+//! its thread sends its progress through an [`mpsc`](std::sync::mpsc)
+//! channel the loop drains on every pass.
+
+use std::time::Duration;
+
+use crossterm::event::Event;
+
+use super::changes::SizeChange;
+use super::tree::TreeNode;
+
+/// Time between ticks while something on screen moves, such as a spinner
+/// or a blinking cursor
+pub const ANIMATION_TICK: Duration = Duration::from_millis(100);
+
+/// Time between ticks while the screen is still; ticks then only age what
+/// shows how long ago it happened
+pub const IDLE_TICK: Duration = Duration::from_secs(1);
+
+/// Frames of the spinner shown while an analysis runs
+pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Ticks a blinking cursor stays shown, then hidden
+pub const CURSOR_BLINK_TICKS: u64 = 5;
+
+/// Something the app reacts to.
+#[derive(Debug)]
+pub enum AppEvent {
+    /// A key press, mouse click or resize of the terminal
+    Input(Event),
+    /// A beat of the event loop's clock
+    Tick,
+    /// Progress of an analysis running in the background
+    AnalysisUpdate(AnalysisUpdate),
+    /// A background task failed, with the message to show
+    Error(String),
+}
+
+/// Progress of a background analysis.
+#[derive(Debug)]
+pub enum AnalysisUpdate {
+    /// Analysis run `run` started
+    Started {
+        /// Number of the run
+        run: u64,
+    },
+    /// Analysis run `run` finished with a new tree
+    Finished {
+        /// Number of the run
+        run: u64,
+        /// The tree of the new analysis
+        tree: TreeNode,
+        /// Packages whose bundle size changed since the previous run
+        changes: Vec<SizeChange>,
+    },
+}
+
+/// Returns the spinner frame of tick `tick`.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::ui::events::spinner_frame;
+///
+/// assert_eq!(spinner_frame(0), "⠋");
+/// assert_eq!(spinner_frame(11), "⠙");
+/// ```
+pub fn spinner_frame(tick: u64) -> &'static str {
+    SPINNER[(tick % SPINNER.len() as u64) as usize]
+}
@@ -0,0 +1,324 @@
+//! Box-art drawing of a layered dependency graph.
+//!
+//! Draws a [`LayeredLayout`] as boxes connected by lines, dependents above
+//! their dependencies, for pasting into docs or a quick look without the
+//! TUI. Lines are merged cell by cell, so junctions and crossings get the
+//! matching box-drawing character.
+//!
+//! ```text
+//!      ┌────────┐
+//!      │ my-app │
+//!      └────┬───┘
+//!           │
+//!      ┌────┴─────┐
+//!      │          │
+//! ┌────┴───┐  ┌───┴───┐
+//! │ lodash │  │ react │
+//! └────────┘  └───────┘
+//! ```
+
+use crate::graph::layout::{LayeredLayout, Vertex};
+
+/// Columns between neighbouring vertices of a layer.
+const GAP: usize = 2;
+
+/// Characters used for boxes and lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Charset {
+    /// Unicode box-drawing characters
+    #[default]
+    Unicode,
+    /// Plain ASCII (`+`, `-`, `|`), for places that mangle Unicode
+    Ascii,
+}
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Blank,
+    Text(char),
+    /// Line directions leaving the cell
+    Line(u8),
+}
+
+/// A grid of cells that lines are merged into.
+struct Canvas {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            rows: vec![vec![Cell::Blank; width]; height],
+        }
+    }
+
+    fn connect(&mut self, x: usize, y: usize, directions: u8) {
+        let cell = &mut self.rows[y][x];
+        *cell = match *cell {
+            Cell::Line(existing) => Cell::Line(existing | directions),
+            _ => Cell::Line(directions),
+        };
+    }
+
+    /// Draws a vertical line between two rows, inclusive.
+    fn vline(&mut self, x: usize, top: usize, bottom: usize) {
+        for y in top..=bottom {
+            let mut directions = 0;
+            if y > top {
+                directions |= UP;
+            }
+            if y < bottom {
+                directions |= DOWN;
+            }
+            self.connect(x, y, directions);
+        }
+    }
+
+    /// Draws a horizontal line between two columns, inclusive.
+    fn hline(&mut self, left: usize, right: usize, y: usize) {
+        for x in left..=right {
+            let mut directions = 0;
+            if x > left {
+                directions |= LEFT;
+            }
+            if x < right {
+                directions |= RIGHT;
+            }
+            self.connect(x, y, directions);
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            self.rows[y][x + i] = Cell::Text(c);
+        }
+    }
+
+    fn draw_box(&mut self, x: usize, y: usize, label: &str) {
+        let right = x + box_width(label) - 1;
+        self.hline(x, right, y);
+        self.hline(x, right, y + 2);
+        self.vline(x, y, y + 2);
+        self.vline(right, y, y + 2);
+        self.text(x + 2, y + 1, label);
+    }
+
+    fn render(&self, charset: Charset) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let line: String = row
+                .iter()
+                .map(|cell| match *cell {
+                    Cell::Blank => ' ',
+                    Cell::Text(c) => c,
+                    Cell::Line(directions) => line_char(directions, charset),
+                })
+                .collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn line_char(directions: u8, charset: Charset) -> char {
+    let vertical = directions & (UP | DOWN) != 0;
+    let horizontal = directions & (LEFT | RIGHT) != 0;
+    if charset == Charset::Ascii {
+        return match (vertical, horizontal) {
+            (true, true) => '+',
+            (true, false) => '|',
+            _ => '-',
+        };
+    }
+    match directions {
+        d if d == UP | DOWN || d == UP || d == DOWN => '│',
+        d if d == LEFT | RIGHT || d == LEFT || d == RIGHT => '─',
+        d if d == DOWN | RIGHT => '┌',
+        d if d == DOWN | LEFT => '┐',
+        d if d == UP | RIGHT => '└',
+        d if d == UP | LEFT => '┘',
+        d if d == UP | DOWN | RIGHT => '├',
+        d if d == UP | DOWN | LEFT => '┤',
+        d if d == LEFT | RIGHT | DOWN => '┬',
+        d if d == LEFT | RIGHT | UP => '┴',
+        _ => '┼',
+    }
+}
+
+fn box_width(label: &str) -> usize {
+    label.chars().count() + 4
+}
+
+/// Draws a layout as box art.
+///
+/// Edges that close a cycle are listed below the drawing, since they
+/// cannot point downwards.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::graph::{DependencyGraph, DependencyType};
+/// use codescope::graph::layout::LayeredLayout;
+/// use codescope::ui::graph_art::{draw_graph, Charset};
+///
+/// let mut graph = DependencyGraph::new();
+/// graph.add_dependency("react", "18.2.0", DependencyType::Production);
+///
+/// let art = draw_graph(&LayeredLayout::project(&graph, "app", None), Charset::Ascii);
+/// assert!(art.contains("| react |"));
+/// ```
+pub fn draw_graph(layout: &LayeredLayout, charset: Charset) -> String {
+    let label = |vertex: Vertex| layout.node(vertex).map(|node| node.name.as_str());
+    let width_of = |vertex: Vertex| label(vertex).map_or(1, box_width);
+
+    // Columns: each layer is centered on the widest one.
+    let layer_widths: Vec<usize> = layout
+        .layers
+        .iter()
+        .map(|layer| layer.iter().map(|&v| width_of(v)).sum::<usize>() + GAP * layer.len().saturating_sub(1))
+        .collect();
+    let width = layer_widths.iter().copied().max().unwrap_or(0);
+    let columns: Vec<Vec<usize>> = layout
+        .layers
+        .iter()
+        .zip(&layer_widths)
+        .map(|(layer, layer_width)| {
+            let mut x = (width - layer_width) / 2;
+            layer
+                .iter()
+                .map(|&v| {
+                    let left = x;
+                    x += width_of(v) + GAP;
+                    left
+                })
+                .collect()
+        })
+        .collect();
+    let center = |layer: usize, pos: usize| columns[layer][pos] + width_of(layout.layers[layer][pos]) / 2;
+
+    // Rows: three per layer, then the routing area to the next layer with
+    // one horizontal track per vertex whose links do not go straight down.
+    let mut tracks: Vec<Vec<Option<usize>>> = Vec::new();
+    let mut tops = Vec::new();
+    let mut y = 0;
+    for (l, layer) in layout.layers.iter().enumerate() {
+        tops.push(y);
+        y += 3;
+        if l + 1 == layout.layers.len() {
+            break;
+        }
+        let mut count = 0;
+        let layer_tracks = (0..layer.len())
+            .map(|pos| {
+                let targets: Vec<usize> = layout
+                    .links
+                    .iter()
+                    .filter(|link| link.layer == l && link.from == pos)
+                    .map(|link| link.to)
+                    .collect();
+                let straight = targets.len() == 1 && center(l + 1, targets[0]) == center(l, pos);
+                (!targets.is_empty() && !straight).then(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+            .collect();
+        tracks.push(layer_tracks);
+        y += count + 2;
+    }
+
+    let mut canvas = Canvas::new(width, y);
+    for (l, layer) in layout.layers.iter().enumerate() {
+        for (pos, &vertex) in layer.iter().enumerate() {
+            match label(vertex) {
+                Some(name) => canvas.draw_box(columns[l][pos], tops[l], name),
+                None => canvas.vline(center(l, pos), tops[l], tops[l] + 2),
+            }
+        }
+    }
+    for link in &layout.links {
+        let (l, from, to) = (link.layer, link.from, link.to);
+        let (x_from, x_to) = (center(l, from), center(l + 1, to));
+        let (bottom, next_top) = (tops[l] + 2, tops[l + 1]);
+        match tracks[l][from] {
+            None => canvas.vline(x_from, bottom, next_top),
+            Some(track) => {
+                let row = bottom + 2 + track;
+                canvas.vline(x_from, bottom, row);
+                canvas.hline(x_from.min(x_to), x_from.max(x_to), row);
+                canvas.vline(x_to, row, next_top);
+            }
+        }
+    }
+
+    let mut out = canvas.render(charset);
+    if !layout.back_edges.is_empty() {
+        let arrow = if charset == Charset::Ascii { "->" } else { "→" };
+        out.push_str("\nNot drawn, closing a cycle:\n");
+        for &(from, to) in &layout.back_edges {
+            out.push_str(&format!("  {} {} {}\n", layout.nodes[from].name, arrow, layout.nodes[to].name));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+
+    #[test]
+    fn test_draw_graph() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        let art = draw_graph(&LayeredLayout::project(&graph, "my-app", None), Charset::Unicode);
+        assert_eq!(
+            art,
+            concat!(
+                "     ┌────────┐\n",
+                "     │ my-app │\n",
+                "     └────┬───┘\n",
+                "          │\n",
+                "     ┌────┴─────┐\n",
+                "     │          │\n",
+                "┌────┴───┐  ┌───┴───┐\n",
+                "│ lodash │  │ react │\n",
+                "└────────┘  └───────┘\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_straight_links_and_cycles() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("a", "1.0.0", DependencyType::Production);
+        graph.add_dependency_with_depth("b", "1.0.0", DependencyType::Production, 1);
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "a");
+
+        let art = draw_graph(&LayeredLayout::focused(&graph, "a", None).unwrap(), Charset::Ascii);
+        assert_eq!(
+            art,
+            concat!(
+                "+---+\n",
+                "| a |\n",
+                "+-+-+\n",
+                "  |\n",
+                "  |\n",
+                "+-+-+\n",
+                "| b |\n",
+                "+---+\n",
+                "\n",
+                "Not drawn, closing a cycle:\n",
+                "  b -> a\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Opening packages outside the TUI.
+//!
+//! A package directory opens in the user's `$VISUAL` or `$EDITOR`, which
+//! takes over the terminal until it exits. Web pages open in the default
+//! browser, which runs alongside the TUI.
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+
+/// Returns the user's editor, split into the program and its arguments.
+pub fn editor_command() -> Option<Vec<String>> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .map(|command| command.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .find(|command| !command.is_empty())
+}
+
+/// Opens `dir` in `editor`, handing it the terminal until it exits.
+pub fn open_in_editor(editor: &[String], dir: &Path) -> io::Result<()> {
+    let Some((program, args)) = editor.split_first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no editor command"));
+    };
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    let status = Command::new(program).args(args).arg(dir).status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    match status? {
+        status if status.success() => Ok(()),
+        status => Err(io::Error::other(format!("{} exited with {}", program, status))),
+    }
+}
+
+/// Opens `url` in the default browser without waiting for it.
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reap the opener once it hands the URL over
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
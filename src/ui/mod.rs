@@ -4,7 +4,18 @@
 //! dependency trees and interacting with the analysis results.
 
 mod app;
+pub mod changes;
+mod compare;
+pub mod events;
+pub mod graph_art;
+pub mod launch;
+pub mod preview;
+pub mod screen;
+mod setup;
+pub mod table;
 pub mod tree;
 
-pub use app::{run_app, App, SortMode};
+pub use app::{run_app, run_app_watching, App, OpenRequest, PackagePage, SortMode};
+pub use compare::{run_compare, CompareApp};
+pub use setup::{run_setup, SetupOutcome, SetupStep, SetupWizard};
 pub use tree::{TreeNode, format_size};
@@ -0,0 +1,241 @@
+//! Read-only preview of the lines importing a package.
+//!
+//! The imports panel of the TUI shows how a file uses a package without
+//! switching to an editor: each of the file's import lines with a few lines
+//! around it, highlighted with the tree-sitter grammars the import analysis
+//! parses with. Import lines close enough to share context are shown as one
+//! hunk.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use crate::analysis::exports::SourceLanguage;
+
+/// Lines shown above and below each import line.
+pub const CONTEXT_LINES: usize = 2;
+
+/// Columns a tab is expanded to.
+const TAB_WIDTH: usize = 4;
+
+/// Syntax class of a piece of source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Highlight {
+    /// Identifiers, operators and punctuation
+    #[default]
+    Plain,
+    /// Keywords such as `import`, `from` and `const`
+    Keyword,
+    /// String, template and regex literals
+    String,
+    /// Numbers, booleans, `null` and `undefined`
+    Literal,
+    /// TypeScript type names
+    Type,
+    /// Comments
+    Comment,
+}
+
+/// One line of a preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewLine {
+    /// Line number in the file (1-indexed)
+    pub number: usize,
+    /// Whether the line imports the package
+    pub is_import: bool,
+    /// Text of the line, split where its syntax class changes
+    pub segments: Vec<(Highlight, String)>,
+}
+
+/// The import lines of one file, with their context.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourcePreview {
+    /// Runs of consecutive lines, in file order
+    pub hunks: Vec<Vec<PreviewLine>>,
+}
+
+impl SourcePreview {
+    /// Reads the file at `path` and previews its `import_lines`.
+    ///
+    /// Files that are not JavaScript or TypeScript are shown without
+    /// highlighting.
+    pub fn load(path: &Path, import_lines: &[usize], context: usize) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+        let language = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(SourceLanguage::from_extension);
+        Ok(Self::new(&source, language, import_lines, context))
+    }
+
+    /// Previews the `import_lines` (1-indexed) of a source, each with
+    /// `context` lines above and below.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::analysis::exports::SourceLanguage;
+    /// use codescope::ui::preview::{Highlight, SourcePreview};
+    ///
+    /// let source = "// app\nimport React from 'react';\n\nrender();\n";
+    /// let preview = SourcePreview::new(source, Some(SourceLanguage::JavaScript), &[2], 1);
+    /// let lines = &preview.hunks[0];
+    /// assert_eq!(lines.iter().map(|line| line.number).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert!(lines[1].is_import);
+    /// assert_eq!(lines[1].segments[0], (Highlight::Keyword, "import".to_string()));
+    /// ```
+    pub fn new(source: &str, language: Option<SourceLanguage>, import_lines: &[usize], context: usize) -> Self {
+        let classes = language.map(|language| classify(source, language)).unwrap_or_default();
+
+        // Byte range of every line, without its line ending
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let text = line.trim_end_matches('\n').trim_end_matches('\r');
+            lines.push(start..start + text.len());
+            start += line.len();
+        }
+
+        let mut import_lines: Vec<usize> = import_lines
+            .iter()
+            .copied()
+            .filter(|&line| line >= 1 && line <= lines.len())
+            .collect();
+        import_lines.sort_unstable();
+        import_lines.dedup();
+
+        // Merge the context windows that touch or overlap
+        let mut windows: Vec<(usize, usize)> = Vec::new();
+        for &line in &import_lines {
+            let first = line.saturating_sub(context).max(1);
+            let last = (line + context).min(lines.len());
+            match windows.last_mut() {
+                Some((_, end)) if first <= *end + 1 => *end = last,
+                _ => windows.push((first, last)),
+            }
+        }
+
+        let hunks = windows
+            .into_iter()
+            .map(|(first, last)| {
+                (first..=last)
+                    .map(|number| {
+                        let range = lines[number - 1].clone();
+                        PreviewLine {
+                            number,
+                            is_import: import_lines.binary_search(&number).is_ok(),
+                            segments: segments(source, &classes, range),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { hunks }
+    }
+
+    /// Returns true if there is nothing to show.
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+/// Returns the syntax class of every byte of a source, or nothing if it
+/// cannot be parsed.
+fn classify(source: &str, language: SourceLanguage) -> Vec<Highlight> {
+    let mut parser = Parser::new();
+    if parser.set_language(&language.tree_sitter_language()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+    let mut classes = vec![Highlight::Plain; source.len()];
+    mark(tree.root_node(), &mut classes);
+    classes
+}
+
+/// Sets the syntax class of the bytes of a node and its descendants.
+fn mark(node: Node, classes: &mut [Highlight]) {
+    let highlight = match node.kind() {
+        "comment" => Some(Highlight::Comment),
+        "string" | "template_string" | "regex" => Some(Highlight::String),
+        "number" | "true" | "false" | "null" | "undefined" => Some(Highlight::Literal),
+        "type_identifier" | "predefined_type" => Some(Highlight::Type),
+        kind if !node.is_named() && kind.chars().all(|c| c.is_ascii_alphabetic()) => Some(Highlight::Keyword),
+        _ => None,
+    };
+    if let Some(highlight) = highlight {
+        classes[node.start_byte()..node.end_byte()].fill(highlight);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        mark(child, classes);
+    }
+}
+
+/// Splits a line of a source where its syntax class changes, expanding tabs.
+fn segments(source: &str, classes: &[Highlight], range: std::ops::Range<usize>) -> Vec<(Highlight, String)> {
+    let class = |index: usize| classes.get(index).copied().unwrap_or_default();
+    let mut segments: Vec<(Highlight, String)> = Vec::new();
+    let mut start = range.start;
+    for (offset, _) in source[range.clone()].char_indices().skip(1) {
+        let index = range.start + offset;
+        if class(index) != class(start) {
+            segments.push((class(start), source[start..index].replace('\t', &" ".repeat(TAB_WIDTH))));
+            start = index;
+        }
+    }
+    if start < range.end {
+        segments.push((class(start), source[start..range.end].replace('\t', &" ".repeat(TAB_WIDTH))));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &PreviewLine) -> String {
+        line.segments.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_hunks() {
+        let source: String = (1..=20).map(|n| format!("line{}\n", n)).collect();
+        let preview = SourcePreview::new(&source, None, &[1, 4, 12, 40], 2);
+        let numbers: Vec<Vec<usize>> = preview
+            .hunks
+            .iter()
+            .map(|hunk| hunk.iter().map(|line| line.number).collect())
+            .collect();
+        assert_eq!(numbers, vec![vec![1, 2, 3, 4, 5, 6], vec![10, 11, 12, 13, 14]]);
+        assert_eq!(text(&preview.hunks[1][2]), "line12");
+        assert!(preview.hunks[1][2].is_import && !preview.hunks[1][1].is_import);
+        assert!(SourcePreview::new("", None, &[1], 2).is_empty());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let source = "import { useState } from 'react'; // hooks\r\nconst\tn: number = 1;\n";
+        let preview = SourcePreview::new(source, Some(SourceLanguage::TypeScript), &[1, 2], 0);
+        let lines = &preview.hunks[0];
+        assert_eq!(
+            lines[0].segments,
+            vec![
+                (Highlight::Keyword, "import".to_string()),
+                (Highlight::Plain, " { useState } ".to_string()),
+                (Highlight::Keyword, "from".to_string()),
+                (Highlight::Plain, " ".to_string()),
+                (Highlight::String, "'react'".to_string()),
+                (Highlight::Plain, "; ".to_string()),
+                (Highlight::Comment, "// hooks".to_string()),
+            ]
+        );
+        assert_eq!(text(&lines[1]), "const    n: number = 1;");
+        assert!(lines[1].segments.contains(&(Highlight::Type, "number".to_string())));
+        assert!(lines[1].segments.contains(&(Highlight::Literal, "1".to_string())));
+    }
+}
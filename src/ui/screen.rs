@@ -0,0 +1,62 @@
+//! Screen size checks shared by the TUI views.
+//!
+//! Below a minimum size the layouts of the views leave no room for their
+//! rows, so instead of drawing them squeezed the views show a screen asking
+//! for a larger terminal. Keys keep working underneath, so `q` still quits.
+
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Fewest columns the views are drawn in
+pub const MIN_WIDTH: u16 = 40;
+
+/// Fewest rows the views are drawn in
+pub const MIN_HEIGHT: u16 = 12;
+
+/// Returns true if `area` is large enough to draw the views in.
+///
+/// # Example
+///
+/// ```rust
+/// use ratatui::layout::Rect;
+/// use codescope::ui::screen::fits;
+///
+/// assert!(fits(Rect::new(0, 0, 80, 24)));
+/// assert!(!fits(Rect::new(0, 0, 80, 8)));
+/// ```
+pub fn fits(area: Rect) -> bool {
+    area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT
+}
+
+/// Draws the screen asking for a larger terminal over the whole frame.
+pub fn render_too_small(frame: &mut Frame) {
+    let area = frame.area();
+    let dimension = |label: &str, size: u16, min: u16| {
+        let color = if size < min { Color::Red } else { Color::Green };
+        Line::from(vec![
+            Span::raw(format!("{} ", label)),
+            Span::styled(size.to_string(), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" / {}", min), Style::default().fg(Color::DarkGray)),
+        ])
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        dimension("Width", area.width, MIN_WIDTH),
+        dimension("Height", area.height, MIN_HEIGHT),
+        Line::from(""),
+        Line::from(Span::styled("Enlarge the window, or press q to quit", Style::default().fg(Color::Gray))),
+    ];
+    // Center the message vertically when there is room for it
+    let padding = area.height.saturating_sub(lines.len() as u16) / 2;
+    for _ in 0..padding {
+        lines.insert(0, Line::from(""));
+    }
+    let message = Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true });
+    frame.render_widget(message, area);
+}
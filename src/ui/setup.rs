@@ -0,0 +1,514 @@
+//! First-run setup wizard
+//!
+//! Opening a project without a configuration first walks through a few
+//! screens: the detected stack and its preset, stats generation, and the
+//! thresholds to hold the project to, ending with a preview of the
+//! `.codescope.toml`. The wizard only collects choices; the caller writes
+//! the file and builds the stats.
+
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::bundle::build::Bundler;
+use crate::config::{Measurement, PackageManager, Preset, SetupPlan, TOML_CONFIG_FILE};
+use super::screen;
+
+/// A screen of the wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+    /// Detected package manager and bundler, and the preset
+    Stack,
+    /// Whether to build the project to generate stats
+    Stats,
+    /// Which measured thresholds to set
+    Thresholds,
+    /// Preview of the file to write
+    Review,
+}
+
+impl SetupStep {
+    /// Every step, in order
+    const ALL: [SetupStep; 4] = [SetupStep::Stack, SetupStep::Stats, SetupStep::Thresholds, SetupStep::Review];
+
+    /// Get the step's title
+    fn title(&self) -> &'static str {
+        match self {
+            SetupStep::Stack => "Stack",
+            SetupStep::Stats => "Bundle stats",
+            SetupStep::Thresholds => "Thresholds",
+            SetupStep::Review => "Review",
+        }
+    }
+}
+
+/// How the wizard ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupOutcome {
+    /// The wizard is still open
+    Pending,
+    /// Skipped for this run; it opens again next time
+    Skipped,
+    /// Declined for good: write an empty configuration
+    Dismissed,
+    /// Confirmed: write the configuration of [`SetupWizard::plan`]
+    Confirmed,
+}
+
+/// State of the first-run setup wizard
+pub struct SetupWizard {
+    /// Name of the project being set up
+    pub project_label: String,
+    /// Package manager found from the lockfile
+    pub package_manager: Option<PackageManager>,
+    /// Bundler found in the project
+    pub bundler: Option<Bundler>,
+    /// Stats file the analysis already read, if any
+    pub existing_stats: Option<String>,
+    /// The current screen
+    pub step: SetupStep,
+    /// Index into the preset choices: no preset, then [`Preset::ALL`]
+    preset_index: usize,
+    /// Whether to build the project for stats after the wizard
+    generate_stats: bool,
+    /// Measured thresholds and whether each is set
+    thresholds: Vec<(Measurement, bool)>,
+    /// Index of the selected row on the current screen
+    pub selected_index: usize,
+    /// List state of the current screen
+    list_state: ListState,
+    /// How the wizard ended
+    pub outcome: SetupOutcome,
+}
+
+impl SetupWizard {
+    /// Create a wizard for a project
+    ///
+    /// # Arguments
+    ///
+    /// * `project_label` - Name of the project
+    /// * `suggested` - Preset to preselect
+    /// * `thresholds` - Measured values of the thresholds to offer, all
+    ///   selected at first
+    pub fn new(project_label: impl Into<String>, suggested: Option<Preset>, thresholds: Vec<Measurement>) -> Self {
+        let preset_index = suggested
+            .and_then(|preset| Preset::ALL.iter().position(|p| *p == preset))
+            .map_or(0, |i| i + 1);
+        let mut wizard = Self {
+            project_label: project_label.into(),
+            package_manager: None,
+            bundler: None,
+            existing_stats: None,
+            step: SetupStep::Stack,
+            preset_index,
+            generate_stats: false,
+            thresholds: thresholds.into_iter().map(|t| (t, true)).collect(),
+            selected_index: 0,
+            list_state: ListState::default(),
+            outcome: SetupOutcome::Pending,
+        };
+        wizard.select(preset_index);
+        wizard
+    }
+
+    /// Set what was detected about the project
+    pub fn with_detected(
+        mut self,
+        package_manager: Option<PackageManager>,
+        bundler: Option<Bundler>,
+        existing_stats: Option<String>,
+    ) -> Self {
+        self.package_manager = package_manager;
+        self.bundler = bundler;
+        self.existing_stats = existing_stats;
+        self
+    }
+
+    /// Get the chosen preset
+    pub fn preset(&self) -> Option<Preset> {
+        self.preset_index.checked_sub(1).map(|i| Preset::ALL[i])
+    }
+
+    /// Check whether stats can be generated: the project has a bundler,
+    /// no stats yet, and a preset that knows where to find them
+    pub fn can_generate_stats(&self) -> bool {
+        self.bundler.is_some() && self.existing_stats.is_none() && self.plan().stats_path().is_some()
+    }
+
+    /// Check whether the project is built for stats after the wizard
+    pub fn generate_stats(&self) -> bool {
+        self.generate_stats && self.can_generate_stats()
+    }
+
+    /// Get the settings chosen so far
+    pub fn plan(&self) -> SetupPlan {
+        SetupPlan {
+            preset: self.preset(),
+            thresholds: self
+                .thresholds
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(threshold, _)| threshold.clone())
+                .collect(),
+        }
+    }
+
+    /// Get the number of selectable rows on the current screen
+    fn row_count(&self) -> usize {
+        match self.step {
+            SetupStep::Stack => Preset::ALL.len() + 1,
+            SetupStep::Stats => usize::from(self.can_generate_stats()),
+            SetupStep::Thresholds => self.thresholds.len(),
+            SetupStep::Review => 0,
+        }
+    }
+
+    /// Select a row, clamped to the current screen
+    fn select(&mut self, index: usize) {
+        let count = self.row_count();
+        self.selected_index = index.min(count.saturating_sub(1));
+        self.list_state.select((count > 0).then_some(self.selected_index));
+        if self.step == SetupStep::Stack {
+            self.preset_index = self.selected_index;
+        }
+    }
+
+    /// Move selection to the next row
+    pub fn select_next(&mut self) {
+        self.select(self.selected_index + 1);
+    }
+
+    /// Move selection to the previous row
+    pub fn select_previous(&mut self) {
+        self.select(self.selected_index.saturating_sub(1));
+    }
+
+    /// Toggle the selected row
+    pub fn toggle_selected(&mut self) {
+        match self.step {
+            SetupStep::Stats if self.can_generate_stats() => self.generate_stats = !self.generate_stats,
+            SetupStep::Thresholds => {
+                if let Some((_, enabled)) = self.thresholds.get_mut(self.selected_index) {
+                    *enabled = !*enabled;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Go to a screen, selecting its first row (the chosen preset on the
+    /// stack screen)
+    fn go_to(&mut self, step: SetupStep) {
+        self.step = step;
+        let index = if step == SetupStep::Stack { self.preset_index } else { 0 };
+        self.select(index);
+    }
+
+    /// Continue to the next screen, or confirm on the last one
+    pub fn next_step(&mut self) {
+        match SetupStep::ALL.iter().position(|s| *s == self.step) {
+            Some(i) if i + 1 < SetupStep::ALL.len() => self.go_to(SetupStep::ALL[i + 1]),
+            _ => self.outcome = SetupOutcome::Confirmed,
+        }
+    }
+
+    /// Go back to the previous screen
+    pub fn previous_step(&mut self) {
+        if let Some(i) = SetupStep::ALL.iter().position(|s| *s == self.step).filter(|&i| i > 0) {
+            self.go_to(SetupStep::ALL[i - 1]);
+        }
+    }
+
+    /// Skip the setup for this run
+    pub fn skip(&mut self) {
+        self.outcome = SetupOutcome::Skipped;
+    }
+
+    /// Decline the setup for good
+    pub fn dismiss(&mut self) {
+        self.outcome = SetupOutcome::Dismissed;
+    }
+}
+
+/// Run the wizard's event loop until it ends
+pub fn run_setup<B: Backend>(terminal: &mut Terminal<B>, wizard: &mut SetupWizard) -> io::Result<SetupOutcome> {
+    loop {
+        terminal.draw(|frame| render(frame, wizard))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => wizard.skip(),
+                    KeyCode::Char('n') => wizard.dismiss(),
+                    KeyCode::Char('j') | KeyCode::Down => wizard.select_next(),
+                    KeyCode::Char('k') | KeyCode::Up => wizard.select_previous(),
+                    KeyCode::Char(' ') => wizard.toggle_selected(),
+                    KeyCode::Enter | KeyCode::Right | KeyCode::Tab => wizard.next_step(),
+                    KeyCode::Backspace | KeyCode::Left | KeyCode::BackTab => wizard.previous_step(),
+                    _ => {}
+                }
+            }
+        }
+
+        if wizard.outcome != SetupOutcome::Pending {
+            return Ok(wizard.outcome);
+        }
+    }
+}
+
+/// Render the wizard
+fn render(frame: &mut Frame, wizard: &mut SetupWizard) {
+    if !screen::fits(frame.area()) {
+        screen::render_too_small(frame);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)])
+        .split(frame.area());
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled("CodeScope - Set up ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(&wizard.project_label, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+        Span::styled(" (no configuration yet)", Style::default().fg(Color::DarkGray)),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    render_steps(frame, wizard, chunks[1]);
+    match wizard.step {
+        SetupStep::Review => render_review(frame, wizard, chunks[2]),
+        _ => render_choices(frame, wizard, chunks[2]),
+    }
+    render_footer(frame, wizard, chunks[3]);
+}
+
+/// Render the step indicator, highlighting the current step
+fn render_steps(frame: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let mut spans = Vec::new();
+    for (i, step) in SetupStep::ALL.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if *step == wizard.step {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(format!("{} {}", i + 1, step.title()), style));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL)), area);
+}
+
+/// Get the explanation shown above the choices of the current screen
+fn step_intro(wizard: &SetupWizard) -> Vec<Line<'static>> {
+    let detected = |label: &str, value: Option<String>| {
+        Line::from(vec![
+            Span::raw(format!("{:<17}", label)),
+            match value {
+                Some(value) => Span::styled(value, Style::default().fg(Color::Green)),
+                None => Span::styled("not found", Style::default().fg(Color::DarkGray)),
+            },
+        ])
+    };
+    match wizard.step {
+        SetupStep::Stack => vec![
+            detected("Package manager", wizard.package_manager.map(|pm| pm.to_string())),
+            detected("Bundler", wizard.bundler.map(|b| b.to_string())),
+            Line::raw(""),
+            Line::raw("A preset fills in where the stack keeps its build output and stats."),
+        ],
+        SetupStep::Stats => {
+            let text = match (&wizard.existing_stats, wizard.bundler, wizard.plan().stats_path()) {
+                (Some(stats), _, _) => format!("Bundle sizes are read from {}.", stats),
+                (None, None, _) => "No bundler found; pass --stats to analyze to see bundle sizes.".to_string(),
+                (None, Some(_), None) => {
+                    "Without a nextjs or vite-react preset, run build-stats and pass --stats to analyze.".to_string()
+                }
+                (None, Some(bundler), Some(path)) => {
+                    format!("Building with {} writes bundle stats to {}, where the preset finds them.", bundler, path)
+                }
+            };
+            vec![Line::raw(text)]
+        }
+        SetupStep::Thresholds if wizard.thresholds.is_empty() => {
+            vec![Line::raw("Nothing was measured; install the dependencies to get thresholds.")]
+        }
+        SetupStep::Thresholds => vec![Line::raw("Checks fail once the project gets worse than it is today.")],
+        SetupStep::Review => Vec::new(),
+    }
+}
+
+/// Get the rows to choose from on the current screen
+fn step_rows(wizard: &SetupWizard) -> Vec<Line<'static>> {
+    let check = |on: bool| if on { "[x] " } else { "[ ] " };
+    match wizard.step {
+        SetupStep::Stack => std::iter::once(None)
+            .chain(Preset::ALL.iter().copied().map(Some))
+            .map(|preset| {
+                let label = preset.map_or("No preset", |p| p.name());
+                let mark = if preset == wizard.preset() { "(•) " } else { "( ) " };
+                Line::raw(format!("{}{}", mark, label))
+            })
+            .collect(),
+        SetupStep::Stats if wizard.can_generate_stats() => {
+            vec![Line::raw(format!("{}Generate stats after setup", check(wizard.generate_stats)))]
+        }
+        SetupStep::Thresholds => wizard
+            .thresholds
+            .iter()
+            .map(|(threshold, enabled)| {
+                Line::raw(format!("{}{} = {}", check(*enabled), threshold.path.join("."), threshold.value))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Render the explanation and choices of the current screen
+fn render_choices(frame: &mut Frame, wizard: &mut SetupWizard, area: Rect) {
+    let block = Block::default()
+        .title(wizard.step.title())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let intro = step_intro(wizard);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(intro.len() as u16 + 1), Constraint::Min(0)])
+        .split(inner);
+    frame.render_widget(Paragraph::new(intro).wrap(Wrap { trim: false }), parts[0]);
+
+    let items: Vec<ListItem> = step_rows(wizard).into_iter().map(ListItem::new).collect();
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, parts[1], &mut wizard.list_state);
+}
+
+/// Render the preview of the file to write
+fn render_review(frame: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let mut lines: Vec<Line> = wizard.plan().to_config().lines().map(|line| Line::raw(line.to_string())).collect();
+    if wizard.generate_stats() {
+        if let (Some(bundler), Some(path)) = (wizard.bundler, wizard.plan().stats_path()) {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!("Then builds with {} and saves the stats to {}.", bundler, path),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+    }
+    let preview = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!("{} (Enter to write)", TOML_CONFIG_FILE))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green)),
+    );
+    frame.render_widget(preview, area);
+}
+
+/// Render the footer with key help
+fn render_footer(frame: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(Color::Yellow));
+    let mut help = vec![key("j/k"), Span::raw(" Choose  ")];
+    if matches!(wizard.step, SetupStep::Stats | SetupStep::Thresholds) {
+        help.extend([key("Space"), Span::raw(" Toggle  ")]);
+    }
+    let next = if wizard.step == SetupStep::Review { " Write  " } else { " Next  " };
+    help.extend([
+        key("Enter"),
+        Span::raw(next),
+        key("Backspace"),
+        Span::raw(" Back  "),
+        key("Esc"),
+        Span::raw(" Skip  "),
+        key("n"),
+        Span::raw(" Don't ask again"),
+    ]);
+    frame.render_widget(Paragraph::new(Line::from(help)).block(Block::default().borders(Borders::ALL)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_wizard() -> SetupWizard {
+        SetupWizard::new(
+            "web",
+            Some(Preset::ViteReact),
+            vec![
+                Measurement::new(&["tooling", "maxInstallMb"], 412),
+                Measurement::new(&["tooling", "maxNative"], 2),
+            ],
+        )
+        .with_detected(Some(PackageManager::Pnpm), Some(Bundler::Vite), None)
+    }
+
+    #[test]
+    fn test_preset_follows_selection() {
+        let mut wizard = sample_wizard();
+        assert_eq!(wizard.preset(), Some(Preset::ViteReact));
+        assert_eq!(wizard.selected_index, 2);
+
+        wizard.select_previous();
+        wizard.select_previous();
+        assert_eq!(wizard.preset(), None);
+        wizard.select_previous();
+        assert_eq!(wizard.preset(), None);
+
+        wizard.next_step();
+        wizard.previous_step();
+        assert_eq!(wizard.step, SetupStep::Stack);
+        assert_eq!(wizard.selected_index, 0);
+    }
+
+    #[test]
+    fn test_walk_through_and_confirm() {
+        let mut wizard = sample_wizard();
+        wizard.next_step();
+        assert_eq!(wizard.step, SetupStep::Stats);
+        assert!(wizard.can_generate_stats());
+        wizard.toggle_selected();
+        assert!(wizard.generate_stats());
+
+        wizard.next_step();
+        wizard.select_next();
+        wizard.toggle_selected();
+        let plan = wizard.plan();
+        assert_eq!(plan.thresholds, vec![Measurement::new(&["tooling", "maxInstallMb"], 412)]);
+
+        wizard.next_step();
+        assert_eq!(wizard.step, SetupStep::Review);
+        assert_eq!(wizard.outcome, SetupOutcome::Pending);
+        wizard.next_step();
+        assert_eq!(wizard.outcome, SetupOutcome::Confirmed);
+    }
+
+    #[test]
+    fn test_stats_need_a_preset_that_finds_them() {
+        let mut wizard = sample_wizard();
+        wizard.generate_stats = true;
+        wizard.select_next();
+        assert_eq!(wizard.preset(), Some(Preset::NodeService));
+        assert!(!wizard.can_generate_stats());
+        assert!(!wizard.generate_stats());
+
+        let found = SetupWizard::new("web", Some(Preset::ViteReact), Vec::new()).with_detected(
+            None,
+            Some(Bundler::Vite),
+            Some("stats.json".to_string()),
+        );
+        assert!(!found.can_generate_stats());
+    }
+}
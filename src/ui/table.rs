@@ -0,0 +1,461 @@
+//! Sortable tables for the flat views of the TUI.
+//!
+//! The side panels that list one row per package or scope share this
+//! component instead of each ordering and drawing its rows its own way. A
+//! table is described by its [`Column`]s; `>` steps the sort through the
+//! columns and both orders of each, `<` steps back, and the sort column's
+//! header shows an arrow for the order.
+//!
+//! Each column has a width, and the flexible one grows to fill the rest of
+//! the panel. When the columns are wider than the panel the table scrolls
+//! sideways a column at a time, and cells longer than their column end in
+//! an ellipsis.
+
+use std::cmp::Ordering;
+
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use ratatui::Frame;
+
+/// Marker ending a cell cut short to fit its column
+const ELLIPSIS: char = '…';
+
+/// Blank cells between two columns
+const COLUMN_SPACING: u16 = 1;
+
+/// Direction of a sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest, or alphabetically first, at the top
+    Ascending,
+    /// Largest, or alphabetically last, at the top
+    Descending,
+}
+
+impl SortOrder {
+    /// Returns the other order.
+    pub fn reversed(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+
+    /// Returns the arrow shown next to the sort column's title.
+    pub fn arrow(self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "↑",
+            SortOrder::Descending => "↓",
+        }
+    }
+}
+
+/// A column of a [`SortableTable`] of `T` rows.
+pub struct Column<T> {
+    /// Header of the column
+    pub title: &'static str,
+    /// Width of the column in cells
+    pub width: u16,
+    /// Whether the column grows past its width to fill the table
+    pub flexible: bool,
+    /// Order the column sorts in when the sort first reaches it; sizes and
+    /// counts put the largest first
+    pub first_order: SortOrder,
+    /// Orders two rows ascending by the column
+    pub compare: fn(&T, &T) -> Ordering,
+    /// Renders the column's cell of a row
+    pub cell: fn(&T) -> Span<'static>,
+}
+
+impl<T> Column<T> {
+    /// Creates a column of a fixed width sorting ascending first.
+    pub fn new(
+        title: &'static str,
+        width: u16,
+        compare: fn(&T, &T) -> Ordering,
+        cell: fn(&T) -> Span<'static>,
+    ) -> Self {
+        Self {
+            title,
+            width,
+            flexible: false,
+            first_order: SortOrder::Ascending,
+            compare,
+            cell,
+        }
+    }
+
+    /// Makes the column sort descending first.
+    pub fn descending(mut self) -> Self {
+        self.first_order = SortOrder::Descending;
+        self
+    }
+
+    /// Makes the column take the width the other columns leave.
+    pub fn flexible(mut self) -> Self {
+        self.flexible = true;
+        self
+    }
+}
+
+/// Columns of a flat view, the column and order its rows are sorted by, the
+/// selected row and the first column shown.
+pub struct SortableTable<T> {
+    columns: Vec<Column<T>>,
+    sort_column: usize,
+    order: SortOrder,
+    selected: usize,
+    first_column: usize,
+    width: u16,
+}
+
+impl<T> SortableTable<T> {
+    /// Creates a table sorted by its first column.
+    pub fn new(columns: Vec<Column<T>>) -> Self {
+        let order = columns.first().map_or(SortOrder::Ascending, |column| column.first_order);
+        Self {
+            columns,
+            sort_column: 0,
+            order,
+            selected: 0,
+            first_column: 0,
+            width: u16::MAX,
+        }
+    }
+
+    /// Sorts by the column at `index`, in its first order.
+    pub fn sorted_by(mut self, index: usize) -> Self {
+        if let Some(column) = self.columns.get(index) {
+            self.sort_column = index;
+            self.order = column.first_order;
+        }
+        self
+    }
+
+    /// Returns the index of the sort column and the order.
+    pub fn sort(&self) -> (usize, SortOrder) {
+        (self.sort_column, self.order)
+    }
+
+    /// Sorts by the other order of the sort column, or by the next column
+    /// once both orders were shown, wrapping around after the last.
+    pub fn sort_next(&mut self) {
+        if self.columns.is_empty() {
+            return;
+        }
+        if self.order == self.columns[self.sort_column].first_order {
+            self.order = self.order.reversed();
+        } else {
+            self.sort_column = (self.sort_column + 1) % self.columns.len();
+            self.order = self.columns[self.sort_column].first_order;
+        }
+    }
+
+    /// Undoes [`sort_next`](Self::sort_next).
+    pub fn sort_previous(&mut self) {
+        if self.columns.is_empty() {
+            return;
+        }
+        if self.order != self.columns[self.sort_column].first_order {
+            self.order = self.order.reversed();
+        } else {
+            self.sort_column = (self.sort_column + self.columns.len() - 1) % self.columns.len();
+            self.order = self.columns[self.sort_column].first_order.reversed();
+        }
+    }
+
+    /// Returns the sort column's title and the order's arrow, e.g. `Size ↓`.
+    pub fn sort_label(&self) -> String {
+        match self.columns.get(self.sort_column) {
+            Some(column) => format!("{} {}", column.title, self.order.arrow()),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the rows in the table's order; rows that compare equal keep
+    /// the order they are given in.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ratatui::text::Span;
+    /// use codescope::ui::table::{Column, SortableTable};
+    ///
+    /// let mut table = SortableTable::new(vec![
+    ///     Column::new("Name", 8, |a: &(&str, u64), b| a.0.cmp(b.0), |row| Span::raw(row.0)),
+    ///     Column::new("Size", 8, |a: &(&str, u64), b| a.1.cmp(&b.1), |row| Span::raw(row.1.to_string()))
+    ///         .descending(),
+    /// ]);
+    /// let rows = [("react", 45), ("lodash", 70)];
+    /// assert_eq!(table.sorted(&rows)[0].0, "lodash");
+    /// table.sort_next();
+    /// table.sort_next();
+    /// assert_eq!(table.sort_label(), "Size ↓");
+    /// assert_eq!(table.sorted(&rows)[0].0, "lodash");
+    /// ```
+    pub fn sorted<'a>(&self, rows: &'a [T]) -> Vec<&'a T> {
+        let mut sorted: Vec<&T> = rows.iter().collect();
+        if let Some(column) = self.columns.get(self.sort_column) {
+            sorted.sort_by(|a, b| match self.order {
+                SortOrder::Ascending => (column.compare)(a, b),
+                SortOrder::Descending => (column.compare)(b, a),
+            });
+        }
+        sorted
+    }
+
+    /// Returns the position of the selected row in the table's order.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns the selected row of `rows`, if there are any.
+    pub fn selected_row<'a>(&self, rows: &'a [T]) -> Option<&'a T> {
+        let sorted = self.sorted(rows);
+        sorted.get(self.selected.min(sorted.len().saturating_sub(1))).copied()
+    }
+
+    /// Selects the row below the selected one, stopping at the last of
+    /// `len` rows.
+    pub fn select_next(&mut self, len: usize) {
+        self.selected = (self.selected + 1).min(len.saturating_sub(1));
+    }
+
+    /// Selects the row above the selected one.
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Returns the index of the leftmost column shown.
+    pub fn first_column(&self) -> usize {
+        self.first_column
+    }
+
+    /// Scrolls a column to the right, unless the last column is already
+    /// shown at the width the table was last drawn at.
+    pub fn scroll_right(&mut self) {
+        if self.first_column < self.last_first_column(self.width) {
+            self.first_column += 1;
+        }
+    }
+
+    /// Scrolls a column to the left.
+    pub fn scroll_left(&mut self) {
+        self.first_column = self.first_column.saturating_sub(1);
+    }
+
+    /// Returns the leftmost column that still shows every column after it
+    /// within `width`, the furthest the table scrolls right.
+    fn last_first_column(&self, width: u16) -> usize {
+        let mut used = 0u16;
+        for (index, column) in self.columns.iter().enumerate().rev() {
+            let spacing = if used == 0 { 0 } else { COLUMN_SPACING };
+            used = used.saturating_add(column.width + spacing);
+            if used > width {
+                return (index + 1).min(self.columns.len() - 1);
+            }
+        }
+        0
+    }
+
+    /// Returns the index and width of each column shown in `width` cells
+    /// from the leftmost one; the flexible column, or else the last shown,
+    /// takes what the others leave, and a single column wider than the
+    /// table is cut to fit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ratatui::text::Span;
+    /// use codescope::ui::table::{Column, SortableTable};
+    ///
+    /// let table = SortableTable::new(vec![
+    ///     Column::new("Name", 10, |a: &(&str, u64), b| a.0.cmp(b.0), |row| Span::raw(row.0)).flexible(),
+    ///     Column::new("Size", 8, |a: &(&str, u64), b| a.1.cmp(&b.1), |row| Span::raw(row.1.to_string())),
+    /// ]);
+    /// assert_eq!(table.layout(30), vec![(0, 21), (1, 8)]);
+    /// assert_eq!(table.layout(15), vec![(0, 15)]);
+    /// ```
+    pub fn layout(&self, width: u16) -> Vec<(usize, u16)> {
+        let first = self.first_column.min(self.last_first_column(width));
+        let mut shown = Vec::new();
+        let mut used = 0u16;
+        for (index, column) in self.columns.iter().enumerate().skip(first) {
+            let spacing = if shown.is_empty() { 0 } else { COLUMN_SPACING };
+            if !shown.is_empty() && used + spacing + column.width > width {
+                break;
+            }
+            used += spacing + column.width.min(width);
+            shown.push((index, column.width.min(width)));
+        }
+        let grow = shown
+            .iter()
+            .position(|&(index, _)| self.columns[index].flexible)
+            .or(shown.len().checked_sub(1));
+        if let Some(grow) = grow {
+            shown[grow].1 += width.saturating_sub(used);
+        }
+        shown
+    }
+
+    /// Draws `rows` in `area` inside `block`: a header with the sort column
+    /// highlighted and arrows where columns are scrolled out of view, then
+    /// the rows in the table's order with the selected one highlighted.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, rows: &[T], block: Block) {
+        let inner = block.inner(area);
+        self.width = inner.width;
+        self.first_column = self.first_column.min(self.last_first_column(inner.width));
+        self.selected = self.selected.min(rows.len().saturating_sub(1));
+
+        let layout = self.layout(inner.width);
+        let hidden_left = self.first_column > 0;
+        let hidden_right = layout.last().is_some_and(|&(index, _)| index + 1 < self.columns.len());
+        let header = Row::new(layout.iter().enumerate().map(|(position, &(index, width))| {
+            let column = &self.columns[index];
+            let mut title = if index == self.sort_column {
+                format!("{} {}", column.title, self.order.arrow())
+            } else {
+                column.title.to_string()
+            };
+            if position == 0 && hidden_left {
+                title = format!("‹ {}", title);
+            }
+            if position + 1 == layout.len() && hidden_right {
+                title = format!("{} ›", title);
+            }
+            let style = if index == self.sort_column {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            Cell::from(Span::styled(truncate(&title, width), style))
+        }));
+        let body: Vec<Row> = self
+            .sorted(rows)
+            .into_iter()
+            .map(|row| {
+                Row::new(layout.iter().map(|&(index, width)| {
+                    let span = (self.columns[index].cell)(row);
+                    Cell::from(Span::styled(truncate(&span.content, width), span.style))
+                }))
+            })
+            .collect();
+
+        let table = Table::new(body, layout.iter().map(|&(_, width)| Constraint::Length(width)))
+            .header(header)
+            .column_spacing(COLUMN_SPACING)
+            .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+            .block(block);
+        let mut state = TableState::default().with_selected((!rows.is_empty()).then_some(self.selected));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+}
+
+/// Cuts `text` to `width` characters, ending it in an ellipsis when it
+/// does not fit.
+///
+/// # Example
+///
+/// ```rust
+/// use codescope::ui::table::truncate;
+///
+/// assert_eq!(truncate("@babel/core", 20), "@babel/core");
+/// assert_eq!(truncate("@babel/core", 8), "@babel/…");
+/// ```
+pub fn truncate(text: &str, width: u16) -> String {
+    let width = width as usize;
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push(ELLIPSIS);
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> SortableTable<(&'static str, u64)> {
+        SortableTable::new(vec![
+            Column::new("Name", 8, |a: &(&str, u64), b| a.0.cmp(b.0), |row| Span::raw(row.0)),
+            Column::new("Size", 8, |a: &(&str, u64), b| a.1.cmp(&b.1), |row| Span::raw(row.1.to_string()))
+                .descending(),
+        ])
+    }
+
+    #[test]
+    fn test_sort_steps() {
+        let mut table = table();
+        let mut labels = vec![table.sort_label()];
+        for _ in 0..4 {
+            table.sort_next();
+            labels.push(table.sort_label());
+        }
+        assert_eq!(labels, vec!["Name ↑", "Name ↓", "Size ↓", "Size ↑", "Name ↑"]);
+
+        for expected in ["Size ↑", "Size ↓", "Name ↓", "Name ↑"] {
+            table.sort_previous();
+            assert_eq!(table.sort_label(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sorted_rows() {
+        let rows = [("react", 45), ("lodash", 70), ("dayjs", 45)];
+        let names = |table: &SortableTable<(&str, u64)>| table.sorted(&rows).iter().map(|row| row.0).collect::<Vec<_>>();
+
+        let mut table = table().sorted_by(1);
+        assert_eq!(table.sort(), (1, SortOrder::Descending));
+        assert_eq!(names(&table), vec!["lodash", "react", "dayjs"], "ties keep their order");
+        table.sort_next();
+        assert_eq!(names(&table), vec!["react", "dayjs", "lodash"]);
+        assert_eq!(names(&table.sorted_by(0)), vec!["dayjs", "lodash", "react"]);
+    }
+
+    #[test]
+    fn test_row_selection() {
+        let rows = [("react", 45), ("lodash", 70)];
+        let mut table = table();
+        table.select_previous();
+        assert_eq!(table.selected_row(&rows), Some(&("lodash", 70)));
+        table.select_next(rows.len());
+        table.select_next(rows.len());
+        assert_eq!(table.selected(), 1);
+        assert_eq!(table.selected_row(&rows), Some(&("react", 45)));
+        assert_eq!(table.selected_row(&[]), None);
+    }
+
+    #[test]
+    fn test_horizontal_scrolling() {
+        let mut table = table();
+        table.width = 12;
+        assert_eq!(table.layout(12), vec![(0, 12)]);
+        table.scroll_right();
+        assert_eq!(table.first_column(), 1);
+        assert_eq!(table.layout(12), vec![(1, 12)]);
+        table.scroll_right();
+        assert_eq!(table.first_column(), 1, "the last column is shown");
+        table.scroll_left();
+        table.scroll_left();
+        assert_eq!(table.first_column(), 0);
+
+        table.width = 17;
+        table.scroll_right();
+        assert_eq!(table.first_column(), 0, "every column fits");
+        assert_eq!(table.layout(20), vec![(0, 8), (1, 11)]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("lodash", 6), "lodash");
+        assert_eq!(truncate("lodash", 5), "loda…");
+        assert_eq!(truncate("lodash", 1), "…");
+        assert_eq!(truncate("lodash", 0), "");
+    }
+}
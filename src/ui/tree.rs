@@ -3,8 +3,9 @@
 //! Provides `TreeNode` for hierarchical data and `FlattenedNode`
 //! for rendering the tree as a scrollable list in the TUI.
 
+use crate::config::Locale;
 use crate::parser::types::DependencyType;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// A node in the dependency tree
 #[derive(Debug, Clone)]
@@ -19,12 +20,17 @@ pub struct TreeNode {
     pub expanded: bool,
     /// Depth in the tree (0 = root)
     pub depth: usize,
+    /// Shortest distance from the project in the dependency graph
+    /// (1 = direct dependency), where known
+    pub graph_depth: Option<usize>,
     /// The type of dependency (Production, Development, Peer, Optional)
     pub dep_type: Option<DependencyType>,
     /// Whether this node is part of a circular dependency
     pub is_in_cycle: bool,
     /// Whether this node has a version conflict
     pub has_conflict: bool,
+    /// Whether this node is installed at a pre-release or 0.x version
+    pub is_prerelease: bool,
     /// Bundle size in bytes (from webpack/bundler stats)
     pub bundle_size: Option<u64>,
     /// Number of modules from this package included in the bundle
@@ -40,9 +46,11 @@ impl TreeNode {
             children: Vec::new(),
             expanded: false,
             depth: 0,
+            graph_depth: None,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: None,
             module_count: None,
         }
@@ -57,9 +65,11 @@ impl TreeNode {
             children: Vec::new(),
             expanded: false,
             depth,
+            graph_depth: None,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: None,
             module_count: None,
         }
@@ -73,9 +83,11 @@ impl TreeNode {
             children: Vec::new(),
             expanded: false,
             depth: 0,
+            graph_depth: None,
             dep_type: Some(dep_type),
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: None,
             module_count: None,
         }
@@ -94,9 +106,11 @@ impl TreeNode {
             children: Vec::new(),
             expanded: false,
             depth: 0,
+            graph_depth: None,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: Some(bundle_size),
             module_count: Some(module_count),
         }
@@ -146,6 +160,29 @@ impl TreeNode {
         }
     }
 
+    /// Mark nodes installed at pre-release or 0.x versions
+    ///
+    /// This method recursively marks all nodes in the tree that match
+    /// names in the provided set.
+    pub fn mark_prereleases(&mut self, prerelease_packages: &HashSet<String>) {
+        self.is_prerelease = prerelease_packages.contains(&self.name);
+        for child in &mut self.children {
+            child.mark_prereleases(prerelease_packages);
+        }
+    }
+
+    /// Set the distance of each package from the project, from the graph
+    /// depths of packages (0 = direct dependency).
+    ///
+    /// This method recursively marks all nodes in the tree that match
+    /// names in the provided map.
+    pub fn mark_graph_depths(&mut self, depths: &HashMap<String, usize>) {
+        self.graph_depth = depths.get(&self.name).map(|depth| depth + 1);
+        for child in &mut self.children {
+            child.mark_graph_depths(depths);
+        }
+    }
+
     /// Add a child node
     pub fn add_child(&mut self, mut child: TreeNode) {
         child.depth = self.depth + 1;
@@ -173,19 +210,91 @@ impl TreeNode {
         result
     }
 
+    /// Render the whole tree as indented plain text for screen readers
+    ///
+    /// Every line spells out the node's position ("level N, item X of Y")
+    /// and whether it has children, and uses words instead of symbols,
+    /// box drawing or color. Collapsed nodes are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_bundle_size` - Bundle size the per-package shares are
+    ///   computed from, 0 to leave shares out
+    pub fn format_linear(&self, total_bundle_size: u64) -> String {
+        let mut out = format!("{}{}", self.name, self.linear_details(total_bundle_size));
+        out.push_str(&format!(", {}.\n", Self::linear_children(self.children.len())));
+        self.format_linear_children(1, total_bundle_size, &mut out);
+        out
+    }
+
+    fn format_linear_children(&self, level: usize, total_bundle_size: u64, out: &mut String) {
+        let count = self.children.len();
+        for (i, child) in self.children.iter().enumerate() {
+            out.push_str(&format!(
+                "{}Level {}, item {} of {}: {}{}, {}.\n",
+                "  ".repeat(level),
+                level,
+                i + 1,
+                count,
+                child.name,
+                child.linear_details(total_bundle_size),
+                Self::linear_children(child.children.len())
+            ));
+            child.format_linear_children(level + 1, total_bundle_size, out);
+        }
+    }
+
+    /// Describes version, type, size and problems, each after a comma.
+    fn linear_details(&self, total_bundle_size: u64) -> String {
+        let mut out = String::new();
+        if !self.version.is_empty() {
+            out.push_str(&format!(" version {}", self.version));
+        }
+        if let Some(dep_type) = self.dep_type {
+            out.push_str(&format!(", {} dependency", dep_type));
+        }
+        if let Some(size) = self.bundle_size {
+            out.push_str(&format!(", bundle size {}", format_size(size)));
+            if total_bundle_size > 0 {
+                out.push_str(&format!(" ({:.1} percent)", size as f64 / total_bundle_size as f64 * 100.0));
+            }
+        }
+        if self.is_in_cycle {
+            out.push_str(", part of a circular dependency");
+        }
+        if self.has_conflict {
+            out.push_str(", has a version conflict");
+        }
+        if self.is_prerelease {
+            out.push_str(", pre-release version");
+        }
+        out
+    }
+
+    fn linear_children(count: usize) -> String {
+        match count {
+            0 => "no children".to_string(),
+            1 => "has 1 child".to_string(),
+            n => format!("has {} children", n),
+        }
+    }
+
     fn flatten_recursive(&self, result: &mut Vec<FlattenedNode>, is_last: bool) {
         result.push(FlattenedNode {
             name: self.name.clone(),
             version: self.version.clone(),
             depth: self.depth,
+            graph_depth: self.graph_depth,
             is_expanded: self.expanded,
             has_children: self.has_children(),
             is_last_child: is_last,
             dep_type: self.dep_type,
             is_in_cycle: self.is_in_cycle,
             has_conflict: self.has_conflict,
+            is_prerelease: self.is_prerelease,
             bundle_size: self.bundle_size,
             module_count: self.module_count,
+            tree_index: result.len(),
         });
 
         if self.expanded {
@@ -197,6 +306,70 @@ impl TreeNode {
         }
     }
 
+    /// Find the path from this node to the node at a given flattened index
+    ///
+    /// Returns each node on the path, starting with this one and ending
+    /// with the target, together with its flattened index; empty if no
+    /// visible node has that index.
+    pub fn path_to_index(&self, target_index: usize) -> Vec<(usize, &TreeNode)> {
+        let mut path = Vec::new();
+        let mut current_index = 0;
+        self.path_to_index_recursive(target_index, &mut current_index, &mut path);
+        path
+    }
+
+    fn path_to_index_recursive<'a>(
+        &'a self,
+        target_index: usize,
+        current_index: &mut usize,
+        path: &mut Vec<(usize, &'a TreeNode)>,
+    ) -> bool {
+        path.push((*current_index, self));
+        if *current_index == target_index {
+            return true;
+        }
+        *current_index += 1;
+
+        if self.expanded {
+            for child in &self.children {
+                if child.path_to_index_recursive(target_index, current_index, path) {
+                    return true;
+                }
+            }
+        }
+        path.pop();
+        false
+    }
+
+    /// Expand the ancestors of the first node named `name`, so it is shown
+    ///
+    /// Returns true if there is such a node.
+    pub fn expand_path_to(&mut self, name: &str) -> bool {
+        if self.name == name {
+            return true;
+        }
+        for child in &mut self.children {
+            if child.expand_path_to(name) {
+                self.expanded = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Expand or collapse the nodes that are in `other` as they are there
+    ///
+    /// Children are matched by name; nodes `other` does not have keep
+    /// their own state. Used to keep the view when the tree is rebuilt.
+    pub fn copy_expansion_from(&mut self, other: &TreeNode) {
+        self.expanded = other.expanded;
+        for child in &mut self.children {
+            if let Some(previous) = other.children.iter().find(|previous| previous.name == child.name) {
+                child.copy_expansion_from(previous);
+            }
+        }
+    }
+
     /// Find a node at a given flattened index and toggle its expansion
     ///
     /// Returns true if the toggle was successful
@@ -236,6 +409,8 @@ pub struct FlattenedNode {
     pub version: String,
     /// Depth in the tree
     pub depth: usize,
+    /// Shortest distance from the project in the dependency graph, where known
+    pub graph_depth: Option<usize>,
     /// Whether this node is currently expanded
     pub is_expanded: bool,
     /// Whether this node has children
@@ -248,13 +423,24 @@ pub struct FlattenedNode {
     pub is_in_cycle: bool,
     /// Whether this node has a version conflict
     pub has_conflict: bool,
+    /// Whether this node is installed at a pre-release or 0.x version
+    pub is_prerelease: bool,
     /// Bundle size in bytes (from webpack/bundler stats)
     pub bundle_size: Option<u64>,
     /// Number of modules from this package included in the bundle
     pub module_count: Option<usize>,
+    /// Position of this node in the tree order, before sorting or filtering
+    pub tree_index: usize,
 }
 
 impl FlattenedNode {
+    /// Get the depth shown by the depth indicator and color: the distance
+    /// from the project in the dependency graph, or the tree depth for
+    /// nodes that are not packages
+    pub fn display_depth(&self) -> usize {
+        self.graph_depth.unwrap_or(self.depth)
+    }
+
     /// Get the expansion indicator character
     pub fn expansion_indicator(&self) -> &'static str {
         if !self.has_children {
@@ -305,21 +491,9 @@ impl FlattenedNode {
     }
 }
 
-/// Format a byte size as a human-readable string.
+/// Format a byte size as a human-readable string, in the installed locale.
 pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
+    Locale::current().format_size(bytes)
 }
 
 #[cfg(test)]
@@ -408,20 +582,65 @@ mod tests {
         assert!(!root.children[0].expanded);
     }
 
+    #[test]
+    fn test_expand_path_to() {
+        let mut root = create_test_tree();
+        assert!(root.expand_path_to("sub-dep-2"));
+        assert!(root.expanded && root.children[0].expanded);
+        assert!(!root.children[1].expanded);
+        assert_eq!(root.flatten().len(), 5);
+        assert!(!root.expand_path_to("missing"));
+    }
+
+    #[test]
+    fn test_copy_expansion_from() {
+        let mut previous = create_test_tree();
+        previous.expanded = true;
+        previous.children[0].expanded = true;
+
+        let mut rebuilt = create_test_tree();
+        rebuilt.children[1].add_child(TreeNode::new("sub-dep-3".to_string(), "1.0.0".to_string()));
+        rebuilt.children[1].expanded = true;
+        rebuilt.copy_expansion_from(&previous);
+        assert!(rebuilt.expanded && rebuilt.children[0].expanded);
+        assert!(!rebuilt.children[1].expanded, "dep-b is collapsed in the previous tree");
+    }
+
+    #[test]
+    fn test_path_to_index() {
+        let mut root = create_test_tree();
+        root.expanded = true;
+        root.children[0].expanded = true;
+
+        // project, dep-a, sub-dep-1, sub-dep-2, dep-b
+        let path: Vec<(usize, &str)> = root
+            .path_to_index(3)
+            .into_iter()
+            .map(|(index, node)| (index, node.name.as_str()))
+            .collect();
+        assert_eq!(path, vec![(0, "project"), (1, "dep-a"), (3, "sub-dep-2")]);
+        assert_eq!(root.path_to_index(4).len(), 2);
+        assert_eq!(root.flatten()[4].tree_index, 4);
+        assert!(root.path_to_index(5).is_empty());
+    }
+
     #[test]
     fn test_expansion_indicator() {
         let node_with_children = FlattenedNode {
             name: "test".to_string(),
             version: "1.0.0".to_string(),
             depth: 0,
+            graph_depth: None,
             is_expanded: false,
             has_children: true,
             is_last_child: false,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: None,
             module_count: None,
+            tree_index: 0,
         };
         assert_eq!(node_with_children.expansion_indicator(), "▶ ");
 
@@ -514,6 +733,33 @@ mod tests {
         assert!(!root.children[2].has_conflict); // typescript
     }
 
+    #[test]
+    fn test_mark_prereleases() {
+        let mut root = create_test_tree();
+        root.expanded = true;
+        root.children[0].expanded = true;
+        root.mark_prereleases(&HashSet::from(["sub-dep-2".to_string()]));
+
+        let flagged: Vec<String> = root.flatten().into_iter().filter(|n| n.is_prerelease).map(|n| n.name).collect();
+        assert_eq!(flagged, vec!["sub-dep-2"]);
+        assert!(root.format_linear(0).contains("sub-dep-2 version 0.2.0, pre-release version, no children."));
+    }
+
+    #[test]
+    fn test_mark_graph_depths() {
+        let mut root = create_test_tree();
+        root.expanded = true;
+        root.children[0].expanded = true;
+        let depths = HashMap::from([("dep-a".to_string(), 0), ("sub-dep-1".to_string(), 3)]);
+        root.mark_graph_depths(&depths);
+
+        let shown: Vec<(String, usize)> = root.flatten().into_iter().map(|n| (n.name.clone(), n.display_depth())).collect();
+        assert_eq!(shown[0], ("project".to_string(), 0), "not a package, keeps its tree depth");
+        assert_eq!(shown[1], ("dep-a".to_string(), 1));
+        assert_eq!(shown[2], ("sub-dep-1".to_string(), 4));
+        assert_eq!(shown[3], ("sub-dep-2".to_string(), 2));
+    }
+
     #[test]
     fn test_flatten_includes_conflict_info() {
         let mut root = TreeNode::new("project".to_string(), "1.0.0".to_string());
@@ -614,6 +860,24 @@ mod tests {
         assert_eq!(flattened[1].module_count, Some(5));
     }
 
+    #[test]
+    fn test_format_linear() {
+        let mut tree = create_test_tree();
+        tree.children[1].dep_type = Some(DependencyType::Development);
+        tree.children[1].bundle_size = Some(2048);
+        tree.children[1].is_in_cycle = true;
+
+        assert_eq!(
+            tree.format_linear(8192),
+            "project version 1.0.0, has 2 children.\n\
+             \x20 Level 1, item 1 of 2: dep-a version 2.0.0, has 2 children.\n\
+             \x20   Level 2, item 1 of 2: sub-dep-1 version 0.1.0, no children.\n\
+             \x20   Level 2, item 2 of 2: sub-dep-2 version 0.2.0, no children.\n\
+             \x20 Level 1, item 2 of 2: dep-b version 3.0.0, development dependency, \
+             bundle size 2.00 KB (25.0 percent), part of a circular dependency, no children.\n"
+        );
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(0), "0 B");
@@ -630,14 +894,17 @@ mod tests {
             name: "react".to_string(),
             version: "18.0.0".to_string(),
             depth: 0,
+            graph_depth: None,
             is_expanded: false,
             has_children: false,
             is_last_child: false,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: Some(1048576),
             module_count: Some(5),
+            tree_index: 0,
         };
 
         assert!(node.has_bundle_size());
@@ -650,14 +917,17 @@ mod tests {
             name: "react".to_string(),
             version: "18.0.0".to_string(),
             depth: 0,
+            graph_depth: None,
             is_expanded: false,
             has_children: false,
             is_last_child: false,
             dep_type: None,
             is_in_cycle: false,
             has_conflict: false,
+            is_prerelease: false,
             bundle_size: None,
             module_count: None,
+            tree_index: 0,
         };
 
         assert!(!node.has_bundle_size());
@@ -0,0 +1,358 @@
+//! Watch mode
+//!
+//! Re-runs the analysis whenever one of the project's input files changes
+//! and reports what changed between two runs as [`WatchEvent`]s. With
+//! `--json` each event is written to stdout as one line of JSON (NDJSON),
+//! so dashboards and editor plugins can follow a project live:
+//!
+//! ```text
+//! {"event":"analysis-started","run":2,"changed":["package-lock.json"]}
+//! {"event":"finding-added","run":2,"finding":{"check":"cycles","message":"Cycle: a -> b -> a",...}}
+//! {"event":"size-changed","run":2,"package":"react-dom","before":133120,"after":136192}
+//! {"event":"analysis-finished","run":2,"packages":42,"total_bundle_size":512000,"findings":1}
+//! ```
+//!
+//! Without `--no-tui`, `--json` or checks, the TUI stays open instead and
+//! badges the packages whose size changed (see [`crate::ui::changes`]).
+//!
+//! Files are polled for modification times, which works the same on every
+//! platform and needs no extra dependencies.
+//!
+//! Only available with the `watch-mode` cargo feature, like the scheduled
+//! reports of [`monitor`].
+
+pub mod monitor;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::webpack::format_size;
+use crate::graph::DependencyGraph;
+use crate::policy::{Finding, PolicyReport};
+
+/// Detects changes to a fixed set of files by polling their modification
+/// times.
+#[derive(Debug, Clone)]
+pub struct FileWatcher {
+    files: Vec<PathBuf>,
+    modified: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl FileWatcher {
+    /// Starts watching `files`; their current state is the baseline.
+    ///
+    /// Files that do not exist yet are watched for creation.
+    pub fn new(files: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        let files: Vec<PathBuf> = files.into_iter().map(Into::into).collect();
+        let modified = files.iter().map(|f| (f.clone(), modified_time(f))).collect();
+        Self { files, modified }
+    }
+
+    /// Returns the watched files.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Returns the files created, modified or deleted since the last call.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for file in &self.files {
+            let now = modified_time(file);
+            if self.modified.insert(file.clone(), now) != Some(now) {
+                changed.push(file.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The state of one analysis run that later runs are compared against.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Bundle size per package, `None` if unknown
+    sizes: BTreeMap<String, Option<u64>>,
+    /// Policy findings in the order they were reported
+    findings: Vec<Finding>,
+}
+
+impl Snapshot {
+    /// Captures the package sizes of `graph` and the findings of `report`.
+    pub fn new(graph: &DependencyGraph, report: &PolicyReport) -> Self {
+        Self {
+            sizes: graph
+                .get_all_nodes()
+                .into_iter()
+                .map(|node| (node.name.clone(), node.bundle_size))
+                .collect(),
+            findings: report.findings().to_vec(),
+        }
+    }
+
+    /// Returns the number of packages.
+    pub fn package_count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns the sum of the known bundle sizes in bytes.
+    pub fn total_bundle_size(&self) -> u64 {
+        self.sizes.values().flatten().sum()
+    }
+
+    /// Returns the findings.
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    fn contains(&self, finding: &Finding) -> bool {
+        self.findings
+            .iter()
+            .any(|f| f.check == finding.check && f.message == finding.message)
+    }
+}
+
+/// Something that happened during watch mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum WatchEvent {
+    /// An analysis run began, triggered by changes to `changed`
+    AnalysisStarted { run: u64, changed: Vec<String> },
+    /// A finding appeared that the previous run did not report
+    FindingAdded { run: u64, finding: Finding },
+    /// A finding of the previous run is gone
+    FindingResolved { run: u64, finding: Finding },
+    /// A package's bundle size changed; `None` means unknown or absent
+    SizeChanged {
+        run: u64,
+        package: String,
+        before: Option<u64>,
+        after: Option<u64>,
+    },
+    /// An analysis run completed
+    AnalysisFinished {
+        run: u64,
+        packages: usize,
+        total_bundle_size: u64,
+        findings: usize,
+    },
+    /// An analysis run could not complete; watching continues
+    Error { run: u64, message: String },
+}
+
+impl WatchEvent {
+    /// Serializes the event as one line of NDJSON, without the newline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::watch::WatchEvent;
+    ///
+    /// let event = WatchEvent::Error { run: 3, message: "bad JSON".to_string() };
+    /// assert_eq!(event.to_json_line(), r#"{"event":"error","run":3,"message":"bad JSON"}"#);
+    /// ```
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).expect("watch events are always serializable")
+    }
+}
+
+impl fmt::Display for WatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchEvent::AnalysisStarted { changed, .. } if changed.is_empty() => {
+                write!(f, "🔍 Analyzing...")
+            }
+            WatchEvent::AnalysisStarted { changed, .. } => {
+                write!(f, "🔄 {} changed, re-analyzing...", changed.join(", "))
+            }
+            WatchEvent::FindingAdded { finding, .. } => {
+                write!(f, "❌ [{}] {}", finding.check, finding.message)
+            }
+            WatchEvent::FindingResolved { finding, .. } => {
+                write!(f, "✅ Resolved [{}] {}", finding.check, finding.message)
+            }
+            WatchEvent::SizeChanged {
+                package,
+                before,
+                after,
+                ..
+            } => {
+                let size = |s: &Option<u64>| s.map_or_else(|| "-".to_string(), format_size);
+                write!(f, "📦 {}: {} -> {}", package, size(before), size(after))
+            }
+            WatchEvent::AnalysisFinished {
+                packages,
+                total_bundle_size,
+                findings,
+                ..
+            } => write!(
+                f,
+                "Analyzed {} packages ({}), {} finding(s). Watching for changes...",
+                packages,
+                format_size(*total_bundle_size),
+                findings
+            ),
+            WatchEvent::Error { message, .. } => write!(f, "⚠️  {}", message),
+        }
+    }
+}
+
+/// Compares a run with the previous one.
+///
+/// The first run (`previous` is `None`) reports every finding as added but
+/// no size changes, since there is nothing to compare sizes with.
+///
+/// # Returns
+///
+/// Resolved findings, then added findings, then size changes sorted by
+/// package name.
+pub fn diff(run: u64, previous: Option<&Snapshot>, current: &Snapshot) -> Vec<WatchEvent> {
+    let empty = Snapshot::default();
+    let before = previous.unwrap_or(&empty);
+    let mut events = Vec::new();
+
+    for finding in &before.findings {
+        if !current.contains(finding) {
+            events.push(WatchEvent::FindingResolved {
+                run,
+                finding: finding.clone(),
+            });
+        }
+    }
+    for finding in &current.findings {
+        if !before.contains(finding) {
+            events.push(WatchEvent::FindingAdded {
+                run,
+                finding: finding.clone(),
+            });
+        }
+    }
+
+    if previous.is_some() {
+        let mut packages: Vec<&String> = before.sizes.keys().chain(current.sizes.keys()).collect();
+        packages.sort();
+        packages.dedup();
+        for package in packages {
+            let old = before.sizes.get(package).copied().flatten();
+            let new = current.sizes.get(package).copied().flatten();
+            if old != new {
+                events.push(WatchEvent::SizeChanged {
+                    run,
+                    package: package.clone(),
+                    before: old,
+                    after: new,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DependencyType;
+    use crate::policy;
+
+    fn snapshot(edges: &[(&str, &str)], react_size: Option<u64>) -> Snapshot {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency("react", "18.2.0", DependencyType::Production);
+        graph.add_dependency("lodash", "4.17.21", DependencyType::Production);
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        if let Some(size) = react_size {
+            graph.get_node_mut("react").unwrap().set_bundle_size(size, 1);
+        }
+        let mut report = PolicyReport::new();
+        policy::check_cycles(&graph, false, &mut report);
+        Snapshot::new(&graph, &report)
+    }
+
+    #[test]
+    fn test_first_run_reports_findings_only() {
+        let current = snapshot(&[("react", "lodash"), ("lodash", "react")], Some(1000));
+        let events = diff(1, None, &current);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], WatchEvent::FindingAdded { run: 1, finding } if finding.check == "cycles"));
+        assert_eq!(current.total_bundle_size(), 1000);
+    }
+
+    #[test]
+    fn test_diff_between_runs() {
+        let first = snapshot(&[("react", "lodash"), ("lodash", "react")], Some(1000));
+        let second = snapshot(&[], Some(2000));
+        let events = diff(2, Some(&first), &second);
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], WatchEvent::FindingResolved { .. }));
+        assert_eq!(
+            events[1],
+            WatchEvent::SizeChanged {
+                run: 2,
+                package: "react".to_string(),
+                before: Some(1000),
+                after: Some(2000),
+            }
+        );
+        assert!(diff(3, Some(&second), &second).is_empty());
+    }
+
+    #[test]
+    fn test_event_json_lines() {
+        let started = WatchEvent::AnalysisStarted {
+            run: 1,
+            changed: vec!["package.json".to_string()],
+        };
+        assert_eq!(
+            started.to_json_line(),
+            r#"{"event":"analysis-started","run":1,"changed":["package.json"]}"#
+        );
+
+        let added = WatchEvent::FindingAdded {
+            run: 2,
+            finding: Finding::new("max-deps", "Too many", 3),
+        };
+        let value: serde_json::Value = serde_json::from_str(&added.to_json_line()).unwrap();
+        assert_eq!(value["event"], "finding-added");
+        assert_eq!(value["finding"]["check"], "max-deps");
+        assert_eq!(value["finding"]["exit_code"], 3);
+        assert_eq!(
+            WatchEvent::SizeChanged {
+                run: 2,
+                package: "react".to_string(),
+                before: None,
+                after: Some(2048),
+            }
+            .to_string(),
+            "📦 react: - -> 2.00 KB"
+        );
+    }
+
+    #[test]
+    fn test_file_watcher() {
+        let dir = std::env::temp_dir().join(format!("codescope-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("package.json");
+        let _ = fs::remove_file(&file);
+
+        let mut watcher = FileWatcher::new([&file]);
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&file, "{}").unwrap();
+        assert_eq!(watcher.changed(), vec![file.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        fs::remove_file(&file).unwrap();
+        assert_eq!(watcher.changed(), vec![file]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,271 @@
+//! Scheduled reports for long-lived dev machines.
+//!
+//! `codescope monitor --interval 24h` re-runs the analysis on a schedule,
+//! compares it with the previous run and summarizes what changed in a
+//! desktop notification. The last run is kept in the user cache directory,
+//! so a restarted monitor still compares with the run before the restart.
+//!
+//! Notifications need the `notifications` cargo feature; without it the
+//! summary is only printed.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::diff::format_size_delta;
+use crate::export::file::write_atomic;
+use crate::net::cache::default_cache_dir;
+
+use super::{Snapshot, WatchEvent};
+
+/// Parses an interval such as `30m`, `24h` or `7d`; a bare number is in
+/// seconds.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use codescope::watch::monitor::parse_interval;
+///
+/// assert_eq!(parse_interval("24h"), Ok(Duration::from_secs(86_400)));
+/// assert_eq!(parse_interval("90"), Ok(Duration::from_secs(90)));
+/// assert!(parse_interval("0m").is_err());
+/// assert!(parse_interval("1w").is_err());
+/// ```
+pub fn parse_interval(input: &str) -> Result<Duration, String> {
+    let trimmed = input.trim();
+    let split = trimmed.find(|c: char| !c.is_ascii_digit()).unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid interval '{}': expected a number with a unit, e.g. 30m or 24h", input))?;
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        other => {
+            return Err(format!(
+                "invalid interval '{}': unknown unit '{}' (expected s, m, h or d)",
+                input, other
+            ))
+        }
+    };
+    if value == 0 {
+        return Err(format!("invalid interval '{}': must be longer than zero", input));
+    }
+    Ok(Duration::from_secs(value * seconds))
+}
+
+/// What the monitor remembers of its last run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonitorState {
+    /// Unix timestamp (seconds) the run finished at
+    pub finished_at: u64,
+    /// Analysis of the run
+    pub snapshot: Snapshot,
+}
+
+impl MonitorState {
+    /// Creates the state of a run finishing now.
+    pub fn new(snapshot: Snapshot) -> Self {
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self { finished_at, snapshot }
+    }
+
+    /// Loads the state saved at `path`, or `None` if there is no readable
+    /// state.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Saves the state to `path`, creating its directory. The file is
+    /// replaced at once, so a concurrent monitor never loads half a state.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(path, serde_json::to_string(self)?.as_bytes(), true)
+    }
+
+    /// Returns where the state of the project in `project_dir` is kept.
+    ///
+    /// Returns `None` if no cache directory can be determined.
+    pub fn default_path(project_dir: &Path) -> Option<PathBuf> {
+        let project = project_dir.canonicalize().unwrap_or_else(|_| project_dir.to_path_buf());
+        let key = project
+            .to_string_lossy()
+            .replace('%', "%25")
+            .replace('/', "%2F")
+            .replace('\\', "%5C")
+            .replace(':', "%3A");
+        default_cache_dir().map(|dir| dir.join("monitor").join(format!("{}.json", key)))
+    }
+}
+
+/// What changed between two monitor runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MonitorSummary {
+    /// Findings the previous run did not report
+    pub new_findings: Vec<String>,
+    /// Number of findings of the previous run that are gone
+    pub resolved_findings: usize,
+    /// Change in the number of packages
+    pub package_delta: i64,
+    /// Change in the total known bundle size, in bytes
+    pub size_delta: i64,
+    /// Package whose bundle size grew the most, with the growth in bytes
+    pub largest_growth: Option<(String, i64)>,
+}
+
+impl MonitorSummary {
+    /// Summarizes the events of [`super::diff`] between two snapshots.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codescope::watch::monitor::MonitorSummary;
+    /// use codescope::watch::{diff, Snapshot};
+    ///
+    /// let empty = Snapshot::default();
+    /// let summary = MonitorSummary::new(&empty, &empty, &diff(2, Some(&empty), &empty));
+    /// assert!(summary.is_empty());
+    /// ```
+    pub fn new(previous: &Snapshot, current: &Snapshot, events: &[WatchEvent]) -> Self {
+        let mut summary = Self {
+            package_delta: current.package_count() as i64 - previous.package_count() as i64,
+            size_delta: current.total_bundle_size() as i64 - previous.total_bundle_size() as i64,
+            ..Self::default()
+        };
+        for event in events {
+            match event {
+                WatchEvent::FindingAdded { finding, .. } => {
+                    summary.new_findings.push(format!("[{}] {}", finding.check, finding.message));
+                }
+                WatchEvent::FindingResolved { .. } => summary.resolved_findings += 1,
+                WatchEvent::SizeChanged {
+                    package,
+                    before: Some(before),
+                    after: Some(after),
+                    ..
+                } if after > before => {
+                    let growth = (after - before) as i64;
+                    if summary.largest_growth.as_ref().is_none_or(|(_, largest)| growth > *largest) {
+                        summary.largest_growth = Some((package.clone(), growth));
+                    }
+                }
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    /// Returns true if nothing worth a notification changed.
+    pub fn is_empty(&self) -> bool {
+        self.new_findings.is_empty() && self.resolved_findings == 0 && self.package_delta == 0 && self.size_delta == 0
+    }
+
+    /// Returns the notification title for `project`.
+    pub fn title(&self, project: &str) -> String {
+        if self.new_findings.is_empty() {
+            format!("codescope: {} changed", project)
+        } else {
+            format!("codescope: {} new finding(s) in {}", self.new_findings.len(), project)
+        }
+    }
+
+    /// Returns the notification body, one change per line.
+    pub fn body(&self) -> String {
+        let mut lines: Vec<String> = self.new_findings.clone();
+        if self.resolved_findings > 0 {
+            lines.push(format!("{} finding(s) resolved", self.resolved_findings));
+        }
+        if self.size_delta != 0 {
+            let mut line = format!("Bundle size {}", format_size_delta(self.size_delta));
+            if let Some((package, growth)) = &self.largest_growth {
+                line.push_str(&format!(" ({} {})", package, format_size_delta(*growth)));
+            }
+            lines.push(line);
+        }
+        if self.package_delta != 0 {
+            lines.push(format!("Packages {:+}", self.package_delta));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Shows a desktop notification.
+#[cfg(feature = "notifications")]
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("codescope")
+        .summary(title)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to show a desktop notification: {}", e))
+}
+
+/// Shows a desktop notification.
+#[cfg(not(feature = "notifications"))]
+pub fn notify(_title: &str, _body: &str) -> Result<(), String> {
+    Err("Desktop notifications are not available in this build; rebuild with --features notifications".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyGraph, DependencyType};
+    use crate::policy::{self, PolicyReport};
+    use crate::watch::diff;
+
+    fn snapshot(packages: &[(&str, u64)], cycle: bool) -> Snapshot {
+        let mut graph = DependencyGraph::new();
+        for (name, size) in packages {
+            graph.add_dependency(name, "1.0.0", DependencyType::Production);
+            graph.get_node_mut(name).unwrap().set_bundle_size(*size, 1);
+        }
+        if cycle {
+            graph.add_edge("react", "lodash");
+            graph.add_edge("lodash", "react");
+        }
+        let mut report = PolicyReport::new();
+        policy::check_cycles(&graph, false, &mut report);
+        Snapshot::new(&graph, &report)
+    }
+
+    #[test]
+    fn test_summary_between_runs() {
+        let yesterday = snapshot(&[("react", 1000), ("lodash", 4000)], false);
+        let today = snapshot(&[("react", 3048), ("lodash", 4000), ("moment", 1024)], true);
+        let events = diff(2, Some(&yesterday), &today);
+        let summary = MonitorSummary::new(&yesterday, &today, &events);
+
+        assert_eq!(summary.new_findings.len(), 1);
+        assert_eq!(summary.package_delta, 1);
+        assert_eq!(summary.size_delta, 3072);
+        assert_eq!(summary.largest_growth, Some(("react".to_string(), 2048)));
+        assert_eq!(summary.title("app"), "codescope: 1 new finding(s) in app");
+        assert!(summary.body().ends_with("Bundle size +3.00 KB (react +2.00 KB)\nPackages +1"), "{}", summary.body());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = std::env::temp_dir().join(format!("codescope-monitor-test-{}", std::process::id()));
+        let path = dir.join("state.json");
+        let state = MonitorState::new(snapshot(&[("react", 1000), ("lodash", 4000)], true));
+        state.save(&path).unwrap();
+
+        let loaded = MonitorState::load(&path).unwrap();
+        assert_eq!(loaded.finished_at, state.finished_at);
+        assert!(diff(2, Some(&state.snapshot), &loaded.snapshot).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,5 @@
+import * as React from 'react';
+import { addDays, format } from 'date-fns';
+
+const tomorrow = format(addDays(new Date(), 1), 'yyyy-MM-dd');
+console.log(React.version, tomorrow);
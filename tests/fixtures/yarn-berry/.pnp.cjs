@@ -0,0 +1,165 @@
+#!/usr/bin/env node
+/* eslint-disable */
+// @ts-nocheck
+"use strict";
+
+const RAW_RUNTIME_STATE =
+'{\
+  "__info": [\
+    "This file is automatically generated. Do not touch it, or risk",\
+    "your modifications being lost."\
+  ],\
+  "dependencyTreeRoots": [\
+    {\
+      "name": "yarn-berry",\
+      "reference": "workspace:."\
+    }\
+  ],\
+  "enableTopLevelFallback": true,\
+  "ignorePatternData": "(^(?:\\\\.yarn\\\\/sdks(?:\\\\/(?!\\\\.{1,2}(?:\\\\/|$))(?:(?:(?!(?:^|\\\\/)\\\\.{1,2}(?:\\\\/|$)).)*?)|$))$)",\
+  "fallbackExclusionList": [\
+    [\
+      "yarn-berry",\
+      [\
+        "workspace:."\
+      ]\
+    ]\
+  ],\
+  "fallbackPool": [],\
+  "packageRegistryData": [\
+    [\
+      null,\
+      [\
+        [\
+          null,\
+          {\
+            "packageLocation": "./",\
+            "packageDependencies": [\
+              [\
+                "lodash-es",\
+                "npm:4.17.21"\
+              ],\
+              [\
+                "nanoid",\
+                "npm:5.0.7"\
+              ],\
+              [\
+                "vite",\
+                "virtual:4a3e5b1c2d#npm:5.2.11"\
+              ]\
+            ],\
+            "linkType": "SOFT"\
+          }\
+        ]\
+      ]\
+    ],\
+    [\
+      "lodash-es",\
+      [\
+        [\
+          "npm:4.17.21",\
+          {\
+            "packageLocation": "./.yarn/cache/lodash-es-npm-4.17.21-b45e6a5bc4-05c6bd2e6e.zip/node_modules/lodash-es/",\
+            "packageDependencies": [\
+              [\
+                "lodash-es",\
+                "npm:4.17.21"\
+              ]\
+            ],\
+            "linkType": "HARD"\
+          }\
+        ]\
+      ]\
+    ],\
+    [\
+      "nanoid",\
+      [\
+        [\
+          "npm:5.0.7",\
+          {\
+            "packageLocation": "./.yarn/cache/nanoid-npm-5.0.7-9a3f6c2e1d-7a2b1c0f9e.zip/node_modules/nanoid/",\
+            "packageDependencies": [\
+              [\
+                "nanoid",\
+                "npm:5.0.7"\
+              ]\
+            ],\
+            "linkType": "HARD"\
+          }\
+        ]\
+      ]\
+    ],\
+    [\
+      "vite",\
+      [\
+        [\
+          "npm:5.2.11",\
+          {\
+            "packageLocation": "./.yarn/cache/vite-npm-5.2.11-6f1c0d2e3a-ee0ad038f0.zip/node_modules/vite/",\
+            "packageDependencies": [\
+              [\
+                "vite",\
+                "npm:5.2.11"\
+              ]\
+            ],\
+            "linkType": "HARD"\
+          }\
+        ],\
+        [\
+          "virtual:4a3e5b1c2d#npm:5.2.11",\
+          {\
+            "packageLocation": "./.yarn/__virtual__/vite-virtual-8d1e7f6a5b/0/cache/vite-npm-5.2.11-6f1c0d2e3a-ee0ad038f0.zip/node_modules/vite/",\
+            "packageDependencies": [\
+              [\
+                "vite",\
+                "virtual:4a3e5b1c2d#npm:5.2.11"\
+              ],\
+              [\
+                "@types/node",\
+                null\
+              ]\
+            ],\
+            "packagePeers": [\
+              "@types/node"\
+            ],\
+            "linkType": "HARD"\
+          }\
+        ]\
+      ]\
+    ],\
+    [\
+      "yarn-berry",\
+      [\
+        [\
+          "workspace:.",\
+          {\
+            "packageLocation": "./",\
+            "packageDependencies": [\
+              [\
+                "yarn-berry",\
+                "workspace:."\
+              ],\
+              [\
+                "lodash-es",\
+                "npm:4.17.21"\
+              ],\
+              [\
+                "nanoid",\
+                "npm:5.0.7"\
+              ],\
+              [\
+                "vite",\
+                "virtual:4a3e5b1c2d#npm:5.2.11"\
+              ]\
+            ],\
+            "linkType": "SOFT"\
+          }\
+        ]\
+      ]\
+    ]\
+  ]\
+}';
+
+function $$SETUP_STATE(hydrateRuntimeState, basePath) {
+  return hydrateRuntimeState(JSON.parse(RAW_RUNTIME_STATE), {basePath: basePath || __dirname});
+}
@@ -0,0 +1,4 @@
+import { chunk } from 'lodash-es';
+import { nanoid } from 'nanoid';
+
+document.querySelector('#app').textContent = chunk([nanoid(), nanoid()], 1).length;
@@ -0,0 +1,109 @@
+//! Resilience of the parsers against truncated and corrupted inputs.
+//!
+//! Runs every truncation and a set of single-byte corruptions of the
+//! vendored fixtures through the parsers and the analysis built on them.
+//! None may panic, and every located error must point into the input. The
+//! targets under `fuzz/` explore the same entry points at random.
+
+use std::fs;
+use std::path::Path;
+
+use codescope::bundle::WebpackStats;
+use codescope::graph::DependencyGraph;
+use codescope::parser::{parse_str, PackageLock, ParseError, PnpManifest, SyntaxError};
+
+/// Bytes that break JSON structure in interesting ways.
+const CORRUPTIONS: &[u8] = b"\"{}[],:0-n ";
+
+fn read_fixture(path: &str) -> String {
+    fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)).unwrap()
+}
+
+/// Calls `parse` with every prefix of `input` and with `input` corrupted at
+/// regular intervals.
+fn mutations(input: &str, mut parse: impl FnMut(&str)) {
+    for end in (0..input.len()).filter(|&end| input.is_char_boundary(end)) {
+        parse(&input[..end]);
+    }
+    let mut bytes = input.as_bytes().to_vec();
+    for i in (0..bytes.len()).step_by(7) {
+        let original = bytes[i];
+        for &corruption in CORRUPTIONS {
+            bytes[i] = corruption;
+            if let Ok(corrupted) = std::str::from_utf8(&bytes) {
+                parse(corrupted);
+            }
+        }
+        bytes[i] = original;
+    }
+}
+
+fn assert_located(input: &str, err: &SyntaxError) {
+    assert!(err.offset <= input.len(), "{} is outside the input", err);
+    assert!(err.line >= 1);
+}
+
+#[test]
+fn test_lockfiles() {
+    for fixture in ["cra-app/package-lock.json", "nextjs-app/package-lock.json"] {
+        mutations(&read_fixture(fixture), |input| match PackageLock::parse(input) {
+            Ok(lock) => {
+                let mut graph = DependencyGraph::new();
+                graph.extend_from_lockfile(&lock);
+                graph.propagate_dependency_types();
+                graph.detect_cycles();
+                lock.required_paths();
+            }
+            Err(ParseError::Syntax(err)) => assert_located(input, &err),
+            Err(_) => {}
+        });
+    }
+}
+
+#[test]
+fn test_stats() {
+    mutations(&read_fixture("cra-app/build/bundle-stats.json"), |input| {
+        match WebpackStats::parse(input) {
+            Ok(stats) => {
+                stats.analyze().format_summary(10);
+            }
+            Err(err) => {
+                if let Some(err) = err.get_ref().and_then(|e| e.downcast_ref::<SyntaxError>()) {
+                    assert_located(input, err);
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn test_stats_recover_from_bad_modules() {
+    let input = read_fixture("cra-app/build/bundle-stats.json").replace("web-vitals.js\", \"size\": 4569", "web-vitals.js\", \"size\": \"4.5 KB\"");
+    let stats = WebpackStats::parse(&input).unwrap();
+    assert_eq!(stats.modules.len(), 12);
+    assert_eq!(stats.parse_warnings.len(), 1);
+    assert_eq!(
+        stats.parse_warnings[0].location,
+        "modules[9] (./node_modules/web-vitals/dist/web-vitals.js)"
+    );
+    let analysis = stats.analyze();
+    assert_eq!(analysis.package_sizes["react-dom"].total_size, 133_864);
+    assert!(!analysis.package_sizes.contains_key("web-vitals"));
+}
+
+#[test]
+fn test_manifests() {
+    for fixture in ["cra-app/package.json", "pnpm-monorepo/apps/web/package.json"] {
+        mutations(&read_fixture(fixture), |input| {
+            if let Err(ParseError::Syntax(err)) = parse_str(input) {
+                assert_located(input, &err);
+            }
+        });
+    }
+    mutations(&read_fixture("yarn-berry/.pnp.cjs"), |input| {
+        if let Err(ParseError::Syntax(err)) = PnpManifest::parse_cjs(input, Path::new(".")) {
+            // Positions are relative to the extracted resolution map
+            assert!(err.line >= 1);
+        }
+    });
+}
@@ -0,0 +1,304 @@
+//! End-to-end analysis of vendored real-world projects.
+//!
+//! Each folder under `tests/fixtures` is a trimmed copy of a project as the
+//! ecosystem's tooling writes it: manifests, lockfiles, install metadata and
+//! build stats. The tests run the same steps as the CLI against them, so a
+//! change in those formats shows up here before it reaches users.
+
+use std::path::{Path, PathBuf};
+
+use codescope::analysis::{
+    find_workspace_duplicates, LicenseReport, NativeReport, WorkspaceGraph,
+};
+use codescope::bundle::build::from_visualizer_data;
+use codescope::bundle::{
+    apply_bundle_sizes_to_graph, match_bundle_to_dependencies, WebpackStats,
+};
+use codescope::config::LicensePolicy;
+use codescope::graph::{self, DependencyGraph};
+use codescope::parser::{
+    extract_dependencies, parse_file, PackageLock, PackageStore, Scope, Workspace,
+};
+use codescope::policy::{
+    check_cycles, check_licenses, DependencyLimits, PolicyReport, EXIT_CONFLICTS, EXIT_LICENSES,
+    EXIT_SAVINGS,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// Builds the dependency graph of a project the way the CLI does.
+fn analyze(dir: &Path, scope: Scope) -> DependencyGraph {
+    let package = parse_file(&dir.join("package.json")).unwrap();
+    let mut graph = DependencyGraph::new();
+    for dep in extract_dependencies(&package) {
+        graph.add_dependency(&dep.name, &dep.version, graph::DependencyType::from(dep.dep_type));
+    }
+    if let Some(lock) = PackageLock::load(dir).unwrap() {
+        graph.extend_from_lockfile(&lock);
+        graph.propagate_dependency_types();
+        graph.retain_scope(scope);
+    }
+    graph
+}
+
+fn names(graph: &DependencyGraph, dep_type: graph::DependencyType) -> Vec<&str> {
+    let mut names: Vec<&str> = graph
+        .get_nodes_by_type(dep_type)
+        .into_iter()
+        .map(|node| node.name.as_str())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_create_react_app() {
+    let dir = fixture("cra-app");
+    let mut graph = analyze(&dir, Scope::All);
+    assert_eq!(graph.node_count(), 11);
+    assert_eq!(
+        names(&graph, graph::DependencyType::Production),
+        vec!["js-tokens", "loose-envify", "react", "react-dom", "scheduler", "web-vitals"]
+    );
+    // The build toolchain, optional fsevents included, is only reachable
+    // through react-scripts
+    assert_eq!(
+        names(&graph, graph::DependencyType::Development),
+        vec!["@babel/runtime", "babel-preset-react-app", "fsevents", "react-scripts", "regenerator-runtime"]
+    );
+    assert_eq!(graph.get_node("scheduler").unwrap().depth, 1);
+    assert_eq!(graph.get_node("js-tokens").unwrap().depth, 2);
+    assert!(!graph.has_cycles());
+
+    let stats = WebpackStats::from_file(dir.join("build/bundle-stats.json")).unwrap();
+    let analysis = stats.analyze();
+    assert_eq!(analysis.total_asset_size, 150_187);
+    assert_eq!(analysis.unmapped_modules.len(), 3);
+    assert_eq!(analysis.chunk_graph.rows().len(), 2);
+    assert_eq!(apply_bundle_sizes_to_graph(&mut graph, &analysis), 4);
+    assert_eq!(graph.get_node("react-dom").unwrap().bundle_size, Some(133_864));
+    assert_eq!(graph.get_node("react").unwrap().bundle_size, Some(8193));
+    assert_eq!(graph.get_node("web-vitals").unwrap().bundle_size, Some(4569));
+    assert_eq!(graph.total_bundle_size(), 150_963);
+
+    let matched = match_bundle_to_dependencies(&graph, &analysis);
+    assert_eq!(matched.matched_count, 4);
+    assert!(matched.extra_packages.is_empty());
+
+    // Production scope drops the toolchain entirely
+    let production = analyze(&dir, Scope::Production);
+    assert_eq!(production.node_count(), 6);
+    let mut report = PolicyReport::new();
+    DependencyLimits::new().with_max_depth(2).evaluate(&production, &mut report);
+    assert!(!report.passed());
+    assert_eq!(report.findings()[0].check, "max-depth");
+}
+
+#[test]
+fn test_nextjs_app() {
+    let dir = fixture("nextjs-app");
+    let graph = analyze(&dir, Scope::All);
+    assert_eq!(graph.node_count(), 26);
+    assert_eq!(
+        names(&graph, graph::DependencyType::Optional),
+        vec!["@next/swc-darwin-arm64", "@next/swc-linux-x64-gnu"]
+    );
+    assert_eq!(
+        names(&graph, graph::DependencyType::Development),
+        vec!["@types/prop-types", "@types/react", "csstype", "typescript"]
+    );
+    // next pins its own postcss below node_modules/next
+    let postcss = graph.get_node("postcss").unwrap();
+    assert_eq!(postcss.version, "8.4.31");
+    assert_eq!(postcss.dep_type, graph::DependencyType::Production);
+    let dependencies: Vec<&str> = graph
+        .get_dependencies("postcss")
+        .into_iter()
+        .map(|node| node.name.as_str())
+        .collect();
+    assert_eq!(dependencies.len(), 3);
+    assert!(dependencies.contains(&"nanoid"));
+    // next's peer dependencies resolve to the project's react
+    assert!(graph.get_dependents("react").iter().any(|node| node.name == "next"));
+    assert_eq!(graph.get_node("react").unwrap().version, "^18");
+
+    let mut report = PolicyReport::new();
+    check_cycles(&graph, false, &mut report);
+    assert!(report.passed());
+
+    // The SWC binaries are built for one platform each
+    let lock = PackageLock::load(&dir).unwrap().unwrap();
+    let native = NativeReport::from_graph(&graph, None, Some(&lock));
+    let platforms: Vec<(&str, String)> = native
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.support.platforms()))
+        .collect();
+    assert_eq!(platforms.len(), 2);
+    assert_eq!(platforms[0].0, "@next/swc-darwin-arm64");
+    assert!(platforms[0].1.contains("darwin"));
+    assert!(platforms[1].1.contains("linux"));
+
+    let production = analyze(&dir, Scope::Production);
+    assert_eq!(production.node_count(), 22);
+    assert!(!production.contains("typescript"));
+}
+
+#[test]
+fn test_pnpm_monorepo() {
+    let dir = fixture("pnpm-monorepo");
+    let workspace = Workspace::load(&dir).unwrap().unwrap();
+    let members: Vec<(&str, &str)> = workspace
+        .members
+        .iter()
+        .map(|m| (m.path.as_str(), m.name.as_str()))
+        .collect();
+    assert_eq!(
+        members,
+        vec![
+            ("apps/docs", "docs"),
+            ("apps/web", "web"),
+            ("packages/ui", "@acme/ui"),
+            ("packages/utils", "@acme/utils"),
+        ]
+    );
+
+    let graph = WorkspaceGraph::from_workspace(&workspace);
+    assert_eq!(graph.edge_count(), 4);
+    assert!(graph.cycles().is_empty());
+    let order = graph.build_order();
+    assert!(order.is_complete());
+    assert_eq!(
+        order.stages,
+        vec![vec!["@acme/utils"], vec!["@acme/ui"], vec!["docs", "web"]]
+    );
+    let affected = graph.affected_by(&["packages/utils/src/index.ts".to_string()]);
+    assert_eq!(affected.all(), vec!["@acme/ui", "@acme/utils", "docs", "web"]);
+
+    // pnpm-lock.yaml is not read, so only the declared ranges are compared
+    let duplicates = find_workspace_duplicates(&workspace, None);
+    let packages: Vec<&str> = duplicates.iter().map(|d| d.package.as_str()).collect();
+    assert_eq!(packages, vec!["lodash", "react", "react-dom"]);
+    let react = &duplicates[1];
+    // Every range accepts the newest release, so the packages can align
+    assert_eq!(react.suggested.as_deref(), Some("18.3.1"));
+    assert!(react.satisfies_all);
+    assert!(react.conflicting_members().is_empty());
+}
+
+#[test]
+fn test_yarn_berry_pnp() {
+    let dir = fixture("yarn-berry");
+    let store = PackageStore::detect(&dir).unwrap();
+    let PackageStore::Pnp(manifest) = &store else {
+        panic!("expected a PnP install");
+    };
+    // The virtual instance of vite is not a separate package
+    assert_eq!(manifest.instances("vite").count(), 1);
+    assert!(manifest.instances("yarn-berry").next().unwrap().is_workspace());
+
+    // Without a package-lock.json the graph has the direct dependencies only
+    let mut graph = analyze(&dir, Scope::All);
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.get_node("vite").unwrap().dep_type, graph::DependencyType::Development);
+
+    let licenses = LicenseReport::from_graph(&graph, &store);
+    assert!(licenses.missing.is_empty());
+    let read: Vec<(&str, &str, Vec<&str>)> = licenses
+        .packages
+        .iter()
+        .map(|p| {
+            let files = p.texts.iter().map(|t| t.file.as_str()).collect();
+            (p.name.as_str(), p.license_label(), files)
+        })
+        .collect();
+    assert_eq!(
+        read,
+        vec![
+            ("lodash-es", "MIT", vec!["LICENSE"]),
+            ("nanoid", "MIT", vec!["LICENSE"]),
+            ("vite", "MIT", vec!["LICENSE.md"]),
+        ]
+    );
+
+    let mut report = PolicyReport::new();
+    let policy = LicensePolicy {
+        allow: vec!["Apache-2.0".to_string()],
+        deny: Vec::new(),
+    };
+    check_licenses(&licenses, &policy, &mut report);
+    assert_eq!(report.findings().len(), 3);
+    assert_eq!(report.exit_code(), EXIT_LICENSES);
+
+    // Vite builds bundle straight out of the zip archives
+    let stats = std::fs::read_to_string(dir.join("dist/stats.json")).unwrap();
+    let analysis = from_visualizer_data(&stats).unwrap().analyze();
+    assert_eq!(apply_bundle_sizes_to_graph(&mut graph, &analysis), 2);
+    assert_eq!(graph.get_node("lodash-es").unwrap().bundle_size, Some(1790));
+    assert_eq!(graph.get_node("nanoid").unwrap().bundle_size, Some(520));
+    assert_eq!(analysis.unmapped_modules.len(), 1);
+}
+
+#[test]
+fn test_webpack_app_export() {
+    // Runs the export command itself, which gathers the data the columns need
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_codescope"))
+        .current_dir(fixture("webpack-app"))
+        .args(["export", "--stats", "dist/stats.json", "--format", "csv"])
+        .args(["--columns", "name,bundle_size,gzip_size,utilization,license"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<&str> = csv.lines().filter(|line| !line.starts_with('#')).collect();
+    assert_eq!(
+        rows,
+        vec![
+            "name,bundle_size,gzip_size,utilization,license",
+            // 2 of the 10 exports webpack reports; a namespace import uses all
+            "date-fns,22400,18432,20.0,MIT",
+            "react,7000,2765,100.0,MIT",
+        ]
+    );
+}
+
+#[test]
+fn test_nested_version_conflict() {
+    // b gets its own c@2.0.0 nested under node_modules/b, while a shares the
+    // hoisted c@1.0.0; only the lockfile edges record what each asked for
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_codescope"))
+        .args(["analyze", "--check-conflicts", "-p"])
+        .arg(fixture("nested-conflict"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(EXIT_CONFLICTS));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[conflicts] c requires: ^1.0.0 (by a), ^2.0.0 (by b)"), "{}", stderr);
+}
+
+#[test]
+fn test_savings_threshold_no_fail() {
+    let savings = |extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_codescope"))
+            .current_dir(fixture("webpack-app"))
+            .args(["analyze", "-p", ".", "--stats", "dist/stats.json", "--savings-report"])
+            .args(["--savings-threshold", "0"])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let output = savings(&[]);
+    assert_eq!(output.status.code(), Some(EXIT_SAVINGS));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("❌ Policy checks failed!"));
+
+    // The threshold is a policy check like any other, so it can be downgraded
+    for extra in [&["--no-fail"][..], &["--warn-only", "savings"]] {
+        let output = savings(extra);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{}", stderr);
+        assert!(stderr.contains("[savings] Potential savings"), "{}", stderr);
+    }
+}